@@ -1,8 +1,9 @@
 #![cfg(feature = "derive")]
 
 use muds::{
+    collections::{IterableMapMut, MapJoin},
     cons,
-    ecs::{Archetypes, Component, Components, Entities, Entity, Registry},
+    ecs::{query::Query, Archetypes, Component, Components, Entities, Entity, Registry},
     Cons,
 };
 
@@ -27,23 +28,76 @@ macro_rules! create_entities {
 #[derive(Entity, Debug)]
 struct Data(f32);
 
+#[derive(Component, Debug)]
+struct Z(f32);
+
+fn create_registry() -> Registry {
+    let mut registry = Registry::default();
+    registry.register_entity::<Data>();
+
+    create_entities!(registry; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y);
+    registry.register_component::<Data, Z>();
+    {
+        let cons!(mut entities, mut z) = registry.storage::<&mut Data, Cons!(&mut Z)>();
+        for _i in 0..20 {
+            let id = entities.insert(Data(1.0));
+            z.insert(id, Z(0.0));
+        }
+    }
+
+    registry
+}
+
 pub struct Benchmark(Registry);
 
 impl Benchmark {
     pub fn new() -> Self {
-        let mut registry = Registry::default();
-        registry.register_entity::<Data>();
+        Self(create_registry())
+    }
+
+    pub fn run(&mut self) {
+        let mut entities = self.0.entities_mut::<Data>();
+
+        for (_, data) in entities.iter_mut() {
+            data.0 *= 2.0;
+        }
+    }
+}
 
-        create_entities!(registry; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z);
+/// Joins the entities with the last of the fragmented components.
+pub struct JoinBenchmark(Registry);
 
-        Self(registry)
+impl JoinBenchmark {
+    pub fn new() -> Self {
+        Self(create_registry())
     }
 
     pub fn run(&mut self) {
         let mut entities = self.0.entities_mut::<Data>();
+        let z = self.0.components::<Data, Z>();
 
-        for (_, data) in entities.iter_mut() {
+        for (_, (_, data)) in entities.iter_mut().map_join(&*z) {
             data.0 *= 2.0;
         }
     }
 }
+
+/// Same as [JoinBenchmark], using a cached [Query].
+pub struct QueryBenchmark(Registry, Query<Data>);
+
+impl QueryBenchmark {
+    pub fn new() -> Self {
+        Self(create_registry(), Query::new().with::<Z>())
+    }
+
+    pub fn run(&mut self) {
+        let ids = self.1.entities(&self.0);
+        let mut entities = self.0.entity_values_mut::<Data>();
+
+        for id in ids {
+            if let Some(data) = entities.get_mut(id) {
+                data.0 *= 2.0;
+            }
+        }
+    }
+}
@@ -14,6 +14,10 @@ fn bench_simple_insert(c: &mut Criterion) {
         let mut bench = simple::InsertBenchmark::new();
         b.iter(move || bench.run());
     });
+    group.bench_function("muds_batch", |b| {
+        let mut bench = simple::BatchInsertBenchmark::new();
+        b.iter(move || bench.run());
+    });
 }
 
 fn bench_simple_iter(c: &mut Criterion) {
@@ -32,6 +36,18 @@ fn bench_frag_iter(c: &mut Criterion) {
     });
 }
 
+fn bench_frag_join(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fragmented_join");
+    group.bench_function("muds", |b| {
+        let mut bench = frag_iter::JoinBenchmark::new();
+        b.iter(move || bench.run());
+    });
+    group.bench_function("muds_query", |b| {
+        let mut bench = frag_iter::QueryBenchmark::new();
+        b.iter(move || bench.run());
+    });
+}
+
 fn bench_add_remove(c: &mut Criterion) {
     let mut group = c.benchmark_group("add_remove");
     group.bench_function("muds", |b| {
@@ -45,6 +61,7 @@ criterion_group!(
     bench_simple_insert,
     bench_simple_iter,
     bench_frag_iter,
+    bench_frag_join,
     bench_add_remove
 );
 criterion_main!(benchmarks);
@@ -37,6 +37,31 @@ impl InsertBenchmark {
     }
 }
 
+pub struct BatchInsertBenchmark;
+
+impl BatchInsertBenchmark {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn run(&mut self) {
+        let mut registry = Registry::default();
+        registry.register_archetype::<Node, Cons!(Transform, Position, Rotation, Velocity)>();
+
+        registry.spawn_batch((0..ENTITIES).map(|_| {
+            (
+                Node,
+                cons!(
+                    Transform(transform::scaling(vec3(1.0, 1.0, 1.0))),
+                    Position(vec3(1.0, 0.0, 0.0)),
+                    Rotation(vec3(1.0, 0.0, 0.0)),
+                    Velocity(vec3(1.0, 0.0, 0.0))
+                ),
+            )
+        }));
+    }
+}
+
 pub struct IterBenchmark(Registry);
 
 impl IterBenchmark {
@@ -0,0 +1,57 @@
+//! SparseSet benchmarks for the growth strategy of the sparse vector.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use muds::collections::SparseSet;
+
+const KEYS: usize = 100_000;
+
+/// Inserts increasing keys, where every new key is beyond the current sparse vector.
+fn insert_increasing(set: &mut SparseSet<u32>) {
+    for i in 0..KEYS {
+        set.insert(i, i as u32);
+    }
+    black_box(set);
+}
+
+/// Inserts increasing keys with a stride, e.g. entity indices shared with other component types.
+fn insert_strided(set: &mut SparseSet<u32>) {
+    for i in 0..(KEYS / 16) {
+        set.insert(i * 16, i as u32);
+    }
+    black_box(set);
+}
+
+fn bench_sparse_growth(c: &mut Criterion) {
+    let cases: [(&str, fn(&mut SparseSet<u32>)); 2] = [
+        ("increasing", insert_increasing),
+        ("strided", insert_strided),
+    ];
+    for (name, insert) in cases {
+        let mut group = c.benchmark_group(format!("sparseset_insert_{}", name));
+        group.bench_function("exact_fit", |b| {
+            b.iter_batched_ref(
+                || {
+                    let mut set = SparseSet::new();
+                    set.set_max_sparse_waste(0.);
+                    set
+                },
+                insert,
+                BatchSize::SmallInput,
+            );
+        });
+        group.bench_function("doubling", |b| {
+            b.iter_batched_ref(SparseSet::new, insert, BatchSize::SmallInput);
+        });
+        group.bench_function("presized", |b| {
+            b.iter_batched_ref(
+                || SparseSet::with_sparse_capacity(0, KEYS),
+                insert,
+                BatchSize::SmallInput,
+            );
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benchmarks, bench_sparse_growth);
+criterion_main!(benchmarks);
@@ -1,4 +1,4 @@
-//! Implements the `#[derive(Entity)]`, `#[derive(Component)]` macro and `#[storage]` attribute.
+//! Implements the `#[derive(Entity)]`, `#[derive(Component)]`, `#[derive(FromRegistry)]` macro and `#[storage]` attribute.
 
 #![recursion_limit = "128"]
 
@@ -94,6 +94,46 @@ fn impl_component(ast: &DeriveInput) -> proc_macro2::TokenStream {
     }
 }
 
+/// Derive macro for the `FromRegistry` trait, constructing the `Default` value from any registry.
+///
+/// ## Examples
+/// ```rust,ignore
+/// #[derive(Component, FromRegistry, Default, Debug)]
+/// struct C(f32);
+/// ```
+#[proc_macro_derive(FromRegistry)]
+pub fn from_registry(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+    let gen = impl_from_registry(&ast);
+    gen.into()
+}
+
+fn impl_from_registry(ast: &DeriveInput) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    // Add the registry type parameter after the lifetimes, keeping all params of the type
+    let mut generics = ast.generics.clone();
+    let registry_index = generics.lifetimes().count();
+    generics
+        .params
+        .insert(registry_index, parse_quote!(__Registry: ?Sized));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(Self: ::core::default::Default));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics muds::ecs::FromRegistry<__Registry> for #name #ty_generics #where_clause {
+            #[inline]
+            fn from_registry(_registry: &__Registry) -> Self {
+                ::core::default::Default::default()
+            }
+        }
+    }
+}
+
 struct StorageAttribute {
     storage: Path,
 }
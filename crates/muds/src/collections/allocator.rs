@@ -1,5 +1,6 @@
 //! Generational index allocator.
 
+use alloc::collections::TryReserveError;
 use alloc::vec::Vec;
 use num::{NumCast, ToPrimitive, Zero};
 
@@ -110,6 +111,22 @@ impl<I: GenIndex> GenIndexAllocator<I> {
         self.indices.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the given allocator.
+    /// Unlike [GenIndexAllocator::reserve], this returns an error instead of panicking or aborting on allocation failure.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexAllocator;
+    /// let mut allocator = <GenIndexAllocator>::new();
+    /// allocator.try_reserve(10).expect("out of memory");
+    /// assert!(allocator.capacity() >= 10);
+    /// assert!(allocator.try_reserve(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.indices.try_reserve(additional)
+    }
+
     /// Clears the allocator, removing all values.
     /// Note that this method has no effect on the allocated capacity of the allocator.
     ///
@@ -166,6 +183,25 @@ impl<I: GenIndex> GenIndexAllocator<I> {
         *gen_index
     }
 
+    /// Tries to create and return the next index, allocating more capacity if necessary.
+    ///
+    /// # Errors
+    /// Returns an error if the allocator reports a failure. The allocator is left unchanged in that case.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexAllocator;
+    /// let mut allocator = <GenIndexAllocator>::new();
+    /// let i = &allocator.try_create().expect("out of memory");
+    /// assert!(allocator.contains(i));
+    /// ```
+    pub fn try_create(&mut self) -> Result<I, TryReserveError> {
+        if self.free_list_size == 0 {
+            self.indices.try_reserve(1)?;
+        }
+        Ok(self.create())
+    }
+
     /// Removes index `i` from the allocator if exists.
     /// Returns a bool indicating whether the allocator originally contains the index.
     ///
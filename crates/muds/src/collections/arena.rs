@@ -1,6 +1,7 @@
 //! Generational index arena.
 use super::{Arena, GenIndexAllocator, IterableMap, IterableMapMut, Map, VecMap};
 use crate::{GenIndex, IndexF64};
+use alloc::collections::TryReserveError;
 use core::ops;
 
 /// The `GenIndexArena` holds elements that are referenced to by [GenIndex].
@@ -108,6 +109,22 @@ impl<T, I: GenIndex> GenIndexArena<T, I> {
         self.items.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the given arena.
+    /// Unlike [GenIndexArena::reserve], this returns an error instead of panicking or aborting on allocation failure.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<()>::new();
+    /// arena.try_reserve(10).expect("out of memory");
+    /// assert!(arena.capacity() >= 10);
+    /// assert!(arena.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.indices.try_reserve(additional)?;
+        self.items.try_reserve(additional)
+    }
+
     /// Clears the arena, removing all values.
     /// Note that this method has no effect on the allocated capacity of the arena.
     ///
@@ -144,6 +161,28 @@ impl<T, I: GenIndex> GenIndexArena<T, I> {
         return i;
     }
 
+    /// Tries to insert `value` into the arena, allocating more capacity if necessary.
+    /// The `value`'s assigned index in the arena is returned.
+    ///
+    /// # Errors
+    /// Returns an error if the allocator reports a failure. The value is not inserted in that case.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<i32>::new();
+    /// let idx = arena.try_insert(123).expect("out of memory");
+    /// assert_eq!(arena[idx], 123);
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<I, TryReserveError> {
+        let i = self.indices.try_create()?;
+        if let Err(err) = self.items.try_insert(i.index(), value) {
+            self.indices.remove(&i);
+            return Err(err);
+        }
+        Ok(i)
+    }
+
     /// Removes and returns the element at `key` from the arena if exists.
     ///
     /// # Examples
@@ -284,6 +323,95 @@ impl<T, I: GenIndex> GenIndexArena<T, I> {
             indices: &self.indices,
         }
     }
+
+    /// Returns an iterator over the indices of the arena, by value.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<i32>::new();
+    /// let idx1 = arena.insert(1);
+    /// let idx2 = arena.insert(2);
+    /// assert_eq!(arena.keys().collect::<Vec<_>>(), [idx1, idx2]);
+    /// ```
+    #[inline]
+    pub fn keys(&self) -> iter::Keys<'_, T, I> {
+        iter::Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values of the arena.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<i32>::new();
+    /// arena.insert(1);
+    /// arena.insert(2);
+    /// assert_eq!(arena.values().sum::<i32>(), 3);
+    /// ```
+    #[inline]
+    pub fn values(&self) -> iter::Values<'_, T, I> {
+        iter::Values { inner: self.iter() }
+    }
+
+    /// Returns an iterator that allows modifying each value of the arena.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<i32>::new();
+    /// let idx = arena.insert(1);
+    /// for value in arena.values_mut() {
+    ///     *value += 1;
+    /// }
+    /// assert_eq!(arena[idx], 2);
+    /// ```
+    #[inline]
+    pub fn values_mut(&mut self) -> iter::ValuesMut<'_, T, I> {
+        iter::ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over the arena that yields indices by value, unlike [GenIndexArena::iter].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::GenIndexArena;
+    /// let mut arena = GenIndexArena::<i32>::new();
+    /// let idx = arena.insert(1);
+    /// arena.insert(2);
+    ///
+    /// let odd = arena
+    ///     .indexed_values()
+    ///     .filter(|(_, value)| **value % 2 == 1)
+    ///     .map(|(idx, _)| idx)
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(odd, [idx]);
+    /// ```
+    #[inline]
+    pub fn indexed_values(&self) -> iter::IndexedValues<'_, T, I> {
+        iter::IndexedValues { inner: self.iter() }
+    }
+
+    /// Returns an iterator that allows modifying each value over the arena, and yields indices by value.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{IndexF64, collections::GenIndexArena};
+    /// let mut arena = GenIndexArena::<Option<IndexF64>>::new();
+    /// let idx = arena.insert(None);
+    /// for (idx, value) in arena.indexed_values_mut() {
+    ///     *value = Some(idx);
+    /// }
+    /// assert_eq!(arena[idx], Some(idx));
+    /// ```
+    #[inline]
+    pub fn indexed_values_mut(&mut self) -> iter::IndexedValuesMut<'_, T, I> {
+        iter::IndexedValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
 }
 
 impl<T, I: GenIndex> Default for GenIndexArena<T, I> {
@@ -321,6 +449,11 @@ impl<T, I: GenIndex> Map for GenIndexArena<T, I> {
     fn len(&self) -> usize {
         self.len()
     }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
 }
 
 impl<T, I: GenIndex> Arena for GenIndexArena<T, I> {
@@ -348,6 +481,11 @@ impl<T, I: GenIndex> Arena for GenIndexArena<T, I> {
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
         self.retain(f)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
 }
 
 impl<'a, T, I: GenIndex> IterableMap<'a> for GenIndexArena<T, I>
@@ -592,6 +730,88 @@ pub mod iter {
     }
 
     impl<T, I: GenIndex> FusedIterator for IntoIter<T, I> {}
+
+    /// Implements all Iterator traits for an iterator mapping the items of an inner arena iterator.
+    macro_rules! impl_mapped_iter {
+        ( $name:ident <$lt:lifetime>, $item:ty, $map:expr ) => {
+            impl<$lt, T: $lt, I: GenIndex + $lt> Iterator for $name<$lt, T, I> {
+                type Item = $item;
+
+                #[inline]
+                fn next(&mut self) -> Option<Self::Item> {
+                    self.inner.next().map($map)
+                }
+
+                #[inline]
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.inner.size_hint()
+                }
+            }
+
+            impl<$lt, T: $lt, I: GenIndex + $lt> DoubleEndedIterator for $name<$lt, T, I> {
+                #[inline]
+                fn next_back(&mut self) -> Option<Self::Item> {
+                    self.inner.next_back().map($map)
+                }
+            }
+
+            impl<$lt, T: $lt, I: GenIndex + $lt> ExactSizeIterator for $name<$lt, T, I> {
+                #[inline]
+                fn len(&self) -> usize {
+                    self.inner.len()
+                }
+            }
+
+            impl<$lt, T: $lt, I: GenIndex + $lt> FusedIterator for $name<$lt, T, I> {}
+        };
+    }
+
+    /// An iterator over the indices of a `GenIndexArena`, by value.
+    /// This struct is created by the `keys` method on `GenIndexArena`.
+    #[derive(Clone, Debug)]
+    pub struct Keys<'a, T: 'a, I: GenIndex + 'a> {
+        pub(super) inner: Iter<'a, T, I>,
+    }
+
+    impl_mapped_iter!(Keys<'a>, I, |(idx, _)| *idx);
+
+    /// An immutable iterator over the values of a `GenIndexArena`.
+    /// This struct is created by the `values` method on `GenIndexArena`.
+    #[derive(Clone, Debug)]
+    pub struct Values<'a, T: 'a, I: GenIndex + 'a> {
+        pub(super) inner: Iter<'a, T, I>,
+    }
+
+    impl_mapped_iter!(Values<'a>, &'a T, |(_, value)| value);
+
+    /// A mutable iterator over the values of a `GenIndexArena`.
+    /// This struct is created by the `values_mut` method on `GenIndexArena`.
+    #[derive(Debug)]
+    pub struct ValuesMut<'a, T: 'a, I: GenIndex + 'a> {
+        pub(super) inner: IterMut<'a, T, I>,
+    }
+
+    impl_mapped_iter!(ValuesMut<'a>, &'a mut T, |(_, value)| value);
+
+    /// An immutable iterator over a `GenIndexArena`, which yields indices by value.
+    /// This struct is created by the `indexed_values` method on `GenIndexArena`.
+    #[derive(Clone, Debug)]
+    pub struct IndexedValues<'a, T: 'a, I: GenIndex + 'a> {
+        pub(super) inner: Iter<'a, T, I>,
+    }
+
+    impl_mapped_iter!(IndexedValues<'a>, (I, &'a T), |(idx, value)| (*idx, value));
+
+    /// A mutable iterator over a `GenIndexArena`, which yields indices by value.
+    /// This struct is created by the `indexed_values_mut` method on `GenIndexArena`.
+    #[derive(Debug)]
+    pub struct IndexedValuesMut<'a, T: 'a, I: GenIndex + 'a> {
+        pub(super) inner: IterMut<'a, T, I>,
+    }
+
+    impl_mapped_iter!(IndexedValuesMut<'a>, (I, &'a mut T), |(idx, value)| (
+        *idx, value
+    ));
 }
 
 #[cfg(feature = "serde")]
@@ -210,6 +210,11 @@ where
     fn len(&self) -> usize {
         self.len()
     }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
 }
 
 impl<T, I, M> MapMut for GenIndexMap<T, I, M>
@@ -259,6 +264,11 @@ where
     fn retain(&mut self, f: impl FnMut(&I, &mut T) -> bool) {
         self.retain(f)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional)
+    }
 }
 
 impl<T, I, M> Default for GenIndexMap<T, I, M>
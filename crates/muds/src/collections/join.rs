@@ -1,4 +1,32 @@
 //! Helper traits for joining with `Map`s.
+//!
+//! [MapJoin] adaptors combine the items of a map iterator with other maps by key:
+//! - [MapJoin::map_join] inner joins, yielding only keys in both maps with both values.
+//! - [MapJoin::map_join_left] left joins, yielding all keys with an optional RHS value.
+//!   [MapJoin::left_join] and [MapJoin::maybe] are the same, the latter for fetching optional components.
+//! - [MapJoin::map_join_with] filters by keys in the RHS map, without fetching its values.
+//! - [MapJoin::map_join_left_excl] filters by keys not in the RHS map, i.e. an anti-join.
+//!   [MapJoin::without] is the same.
+//!
+//! Chaining them expresses ECS queries, e.g. all entities with a position and an optional velocity,
+//! excluding frozen ones:
+//! ```rust
+//! # use muds::cons;
+//! # use muds::collections::MapJoin;
+//! # use std::collections::BTreeMap;
+//! let pos = BTreeMap::from([(0, 1), (1, 2), (2, 3)]);
+//! let vel = BTreeMap::from([(1, 10), (2, 20)]);
+//! let frozen = BTreeMap::from([(2, ())]);
+//!
+//! let moving: Vec<_> = pos
+//!     .iter()
+//!     .cons()
+//!     .map_join_left(&vel)
+//!     .map_join_left_excl(&frozen)
+//!     .map(|(key, cons!(vel, pos))| (*key, *pos, vel.copied()))
+//!     .collect();
+//! assert_eq!(moving, [(0, 1, None), (1, 2, Some(10))]);
+//! ```
 
 use core::iter::FusedIterator;
 
@@ -26,6 +54,28 @@ pub trait MapJoin<'a, K: 'a, V>: Iterator<Item = (&'a K, V)> + Sized {
     }
 
     /// Returns an iterator adaptor that left joins this iterator with a `Map`.
+    /// The RHS value is `None` for keys not in the RHS map, so optional components
+    /// can be fetched in the same pass, without a separate lookup per key.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::cons;
+    /// # use muds::collections::MapJoin;
+    /// # use std::collections::BTreeMap;
+    /// let mut pos = BTreeMap::new();
+    /// let mut vel = BTreeMap::new();
+    /// pos.insert(0, 1);
+    /// pos.insert(1, 2);
+    /// vel.insert(1, 10);
+    ///
+    /// let joined: Vec<_> = pos
+    ///     .iter()
+    ///     .cons()
+    ///     .map_join_left(&vel)
+    ///     .map(|(key, cons!(vel, pos))| (*key, *pos, vel.copied()))
+    ///     .collect();
+    /// assert_eq!(joined, [(0, 1, None), (1, 2, Some(10))]);
+    /// ```
     #[inline(always)]
     fn map_join_left<M>(self, rhs: &'a M) -> MapJoinLeftIter<Self, &'a M>
     where
@@ -37,8 +87,91 @@ pub trait MapJoin<'a, K: 'a, V>: Iterator<Item = (&'a K, V)> + Sized {
         }
     }
 
+    /// Returns an iterator adaptor that left joins this iterator with a `Map`. Same as [MapJoin::map_join_left].
+    #[inline(always)]
+    fn left_join<M>(self, rhs: &'a M) -> MapJoinLeftIter<Self, &'a M>
+    where
+        M: Map<Key = K>,
+    {
+        self.map_join_left(rhs)
+    }
+
+    /// Returns an iterator adaptor that joins this iterator with an optional `Map`, i.e. a left join.
+    /// Same as [MapJoin::map_join_left], which is useful for fetching optional components in a query.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::cons;
+    /// # use muds::collections::MapJoin;
+    /// # use std::collections::BTreeMap;
+    /// let pos = BTreeMap::from([(0, 1), (1, 2), (2, 3)]);
+    /// let vel = BTreeMap::from([(1, 10)]);
+    /// let tint = BTreeMap::from([(1, 'r'), (2, 'g')]);
+    ///
+    /// let joined: Vec<_> = pos
+    ///     .iter()
+    ///     .cons()
+    ///     .maybe(&vel)
+    ///     .maybe(&tint)
+    ///     .map(|(key, cons!(tint, vel, pos))| (*key, *pos, vel.copied(), tint.copied()))
+    ///     .collect();
+    /// assert_eq!(
+    ///     joined,
+    ///     [(0, 1, None, None), (1, 2, Some(10), Some('r')), (2, 3, None, Some('g'))]
+    /// );
+    /// ```
+    #[inline(always)]
+    fn maybe<M>(self, rhs: &'a M) -> MapJoinLeftIter<Self, &'a M>
+    where
+        M: Map<Key = K>,
+    {
+        self.map_join_left(rhs)
+    }
+
+    /// Returns an iterator adaptor that filters this iterator by the keys of a `Map`.
+    /// Like [MapJoin::map_join], it yields only the elements with keys in the RHS map, but without fetching
+    /// the RHS values. Useful for filtering by tag components that hold no data.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::MapJoin;
+    /// # use std::collections::BTreeMap;
+    /// let mut pos = BTreeMap::new();
+    /// let mut tags = BTreeMap::new();
+    /// pos.insert(0, 1);
+    /// pos.insert(1, 2);
+    /// tags.insert(1, ());
+    ///
+    /// let tagged: Vec<_> = pos.iter().map_join_with(&tags).collect();
+    /// assert_eq!(tagged, [(&1, &2)]);
+    /// ```
+    #[inline(always)]
+    fn map_join_with<M>(self, rhs: &'a M) -> MapJoinWithIter<Self, &'a M>
+    where
+        M: Map<Key = K>,
+    {
+        MapJoinWithIter {
+            iter: self,
+            map: rhs,
+        }
+    }
+
     /// Returns an iterator adaptor that left exclusive joins this iterator with a `Map`.
     /// The returned iterator will yield only the elements with keys not in the RHS map.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::MapJoin;
+    /// # use std::collections::BTreeMap;
+    /// let mut pos = BTreeMap::new();
+    /// let mut frozen = BTreeMap::new();
+    /// pos.insert(0, 1);
+    /// pos.insert(1, 2);
+    /// frozen.insert(1, ());
+    ///
+    /// let active: Vec<_> = pos.iter().map_join_left_excl(&frozen).collect();
+    /// assert_eq!(active, [(&0, &1)]);
+    /// ```
     #[inline(always)]
     fn map_join_left_excl<M>(self, rhs: &'a M) -> MapJoinLeftExclIter<Self, &'a M>
     where
@@ -50,6 +183,35 @@ pub trait MapJoin<'a, K: 'a, V>: Iterator<Item = (&'a K, V)> + Sized {
         }
     }
 
+    /// Returns an iterator adaptor that excludes the keys of a `Map` from this iterator, i.e. an anti-join.
+    /// Same as [MapJoin::map_join_left_excl], which is useful for skipping entities with a tag component.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::cons;
+    /// # use muds::collections::MapJoin;
+    /// # use std::collections::BTreeMap;
+    /// let pos = BTreeMap::from([(0, 1), (1, 2), (2, 3)]);
+    /// let vel = BTreeMap::from([(1, 10), (2, 20)]);
+    /// let frozen = BTreeMap::from([(2, ())]);
+    ///
+    /// let moving: Vec<_> = pos
+    ///     .iter()
+    ///     .cons()
+    ///     .left_join(&vel)
+    ///     .without(&frozen)
+    ///     .map(|(key, cons!(vel, pos))| (*key, *pos, vel.copied()))
+    ///     .collect();
+    /// assert_eq!(moving, [(0, 1, None), (1, 2, Some(10))]);
+    /// ```
+    #[inline(always)]
+    fn without<M>(self, rhs: &'a M) -> MapJoinLeftExclIter<Self, &'a M>
+    where
+        M: Map<Key = K>,
+    {
+        self.map_join_left_excl(rhs)
+    }
+
     /// Inner joins with a `MapMut`.
     ///
     /// # Safety
@@ -66,7 +228,7 @@ pub trait MapJoin<'a, K: 'a, V>: Iterator<Item = (&'a K, V)> + Sized {
         }
     }
 
-    /// Left joins with a `MapMut`.
+    /// Left joins with a `MapMut`, yielding `None` for keys not in the RHS map.
     ///
     /// # Safety
     /// Self must be a map iterator that never returns duplicate keys.
@@ -81,6 +243,19 @@ pub trait MapJoin<'a, K: 'a, V>: Iterator<Item = (&'a K, V)> + Sized {
             map: rhs,
         }
     }
+
+    /// Joins with an optional `MapMut`. Same as [MapJoin::map_join_left_mut].
+    ///
+    /// # Safety
+    /// Self must be a map iterator that never returns duplicate keys.
+    /// Otherwise, this method may potentially hand out multiple mutable references to the same RHS value!
+    #[inline(always)]
+    unsafe fn maybe_mut<M>(self, rhs: &'a mut M) -> MapJoinLeftIter<Self, &'a mut M>
+    where
+        M: MapMut<Key = K>,
+    {
+        self.map_join_left_mut(rhs)
+    }
 }
 
 impl<'a, T, K, V> MapJoin<'a, K, V> for T
@@ -221,6 +396,39 @@ where
 {
 }
 
+/// Iterator adaptor that filters a map by the keys of another map.
+#[derive(Debug)]
+pub struct MapJoinWithIter<LHS: Iterator, RHS> {
+    iter: LHS,
+    map: RHS,
+}
+
+impl<'a, K: 'a, V, LHS, RHS> Iterator for MapJoinWithIter<LHS, &'a RHS>
+where
+    LHS: Iterator<Item = (&'a K, V)>,
+    RHS: Map<Key = K>,
+{
+    type Item = LHS::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let map = self.map;
+        self.iter.find(|(key, _)| map.contains_key(key))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<LHS, RHS> FusedIterator for MapJoinWithIter<LHS, RHS>
+where
+    Self: Iterator,
+    LHS: FusedIterator,
+{
+}
+
 /// Iterator adaptor that left exclusive joins 2 maps.
 #[derive(Debug)]
 pub struct MapJoinLeftExclIter<LHS: Iterator, RHS> {
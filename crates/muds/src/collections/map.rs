@@ -25,6 +25,13 @@ pub trait Map {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the number of elements the map can hold without reallocating.
+    /// Defaults to its length for maps that do not preallocate.
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.len()
+    }
 }
 
 /// A mutable associative array.
@@ -44,6 +51,11 @@ pub trait MapMut: Map {
     /// Retains only the elements specified by the predicate, passing a mutable reference to it.
     /// In other words, removes all elements such that `f(&index, &mut value)` returns `false`.
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool);
+
+    /// Reserves capacity for at least `additional` more elements to be inserted.
+    /// Does nothing for maps that do not preallocate.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
 }
 
 /// An iterable map type.
@@ -81,6 +93,11 @@ pub trait Arena: Map {
     /// Retains only the elements specified by the predicate, passing a mutable reference to it.
     /// In other words, removes all elements such that `f(&index, &mut value)` returns `false`.
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool);
+
+    /// Reserves capacity for at least `additional` more elements to be inserted.
+    /// Does nothing for arenas that do not preallocate.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
 }
 
 /// Implement MapMut for all Arena for compatibility
@@ -110,4 +127,9 @@ impl<A: Arena> MapMut for A {
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
         self.retain(f)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        Arena::reserve(self, additional)
+    }
 }
@@ -2,6 +2,7 @@
 
 use super::{IterableMap, IterableMapMut, Map, MapMut};
 use crate::UnsignedNum;
+use alloc::collections::TryReserveError;
 use alloc::vec::Vec;
 use core::{cmp, mem, ops};
 use iter::{Drain, IntoIter, Iter, IterMut};
@@ -9,12 +10,22 @@ use iter::{Drain, IntoIter, Iter, IterMut};
 /// Represents a sparse index pointing to null
 const NULL_INDEX: usize = usize::MAX;
 
+/// Minimum length of the sparse vector once allocated
+const MIN_SPARSE_LEN: usize = 8;
+
+/// Default maximum ratio of unused to required sparse slots when growing the sparse vector
+const DEFAULT_MAX_SPARSE_WASTE: f32 = 1.;
+
 /// The `SparseSet` is a type of associative array that uses unsigned integer as key.
 /// It uses a dense and a sparse vector to map keys to elements.
+///
+/// The sparse vector grows by amortized doubling, so inserting increasing keys only reallocates
+/// logarithmically often. The growth is bounded by a maximum waste ratio, see [SparseSet::set_max_sparse_waste].
 #[derive(Clone, Debug)]
 pub struct SparseSet<T, I: UnsignedNum = usize> {
     items: Vec<Entry<T, I>>,
     sparse: Vec<usize>,
+    max_sparse_waste: f32,
 }
 
 /// `SparseSet` entry type
@@ -49,11 +60,30 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
     /// ```
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        let mut sparse = Vec::with_capacity(capacity);
-        unsafe { sparse.set_len(capacity) }
+        Self::with_sparse_capacity(capacity, capacity)
+    }
+
+    /// Constructs a new, empty `SparseSet` with the specified capacity of elements,
+    /// and a sparse vector able to hold keys less than `sparse_capacity` without reallocating.
+    /// This is useful when the range of keys is known in advance, e.g. entity indices.
+    ///
+    /// # Panic
+    /// Panics if the capacity overflows.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let set = SparseSet::<()>::with_sparse_capacity(10, 1000);
+    /// assert_eq!(set.capacity(), 10);
+    /// assert!(set.sparse_capacity() >= 1000);
+    /// ```
+    pub fn with_sparse_capacity(capacity: usize, sparse_capacity: usize) -> Self {
+        let mut sparse = Vec::with_capacity(sparse_capacity);
+        unsafe { sparse.set_len(sparse.capacity()) }
         Self {
             items: Vec::with_capacity(capacity),
             sparse,
+            max_sparse_waste: DEFAULT_MAX_SPARSE_WASTE,
         }
     }
 
@@ -70,6 +100,55 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
         self.items.capacity()
     }
 
+    /// Returns the number of keys the set can hold without reallocating its sparse vector,
+    /// i.e. all keys less than this value can be inserted without reallocating the sparse vector.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let mut set = SparseSet::<()>::new();
+    /// set.insert(100, ());
+    /// assert!(set.sparse_capacity() > 100);
+    /// ```
+    #[inline]
+    pub fn sparse_capacity(&self) -> usize {
+        self.sparse.len()
+    }
+
+    /// Returns the maximum ratio of unused to required sparse slots when growing the sparse vector.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let set = SparseSet::<()>::new();
+    /// assert_eq!(set.max_sparse_waste(), 1.);
+    /// ```
+    #[inline]
+    pub fn max_sparse_waste(&self) -> f32 {
+        self.max_sparse_waste
+    }
+
+    /// Sets the maximum ratio of unused to required sparse slots when growing the sparse vector.
+    ///
+    /// When a key does not fit into the sparse vector, the vector is doubled in length,
+    /// but never beyond `(1 + ratio)` times the length required by the key.
+    /// The default ratio of 1 allows full doubling, while 0 grows the vector to exactly fit the key,
+    /// trading more frequent reallocations for less memory when keys are very sparse.
+    /// Negative ratios are treated as 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let mut set = SparseSet::<()>::new();
+    /// set.set_max_sparse_waste(0.);
+    /// set.insert(99, ());
+    /// assert_eq!(set.sparse_capacity(), 100);
+    /// ```
+    #[inline]
+    pub fn set_max_sparse_waste(&mut self, ratio: f32) {
+        self.max_sparse_waste = if ratio > 0. { ratio } else { 0. };
+    }
+
     /// Reserves capacity for at least `additional` more elements to be inserted in the given set.
     /// The collection may reserve more space to avoid frequent reallocations. After calling reserve, capacity
     /// will be greater than or equal to self.len() + additional. Does nothing if capacity is already sufficient.
@@ -88,10 +167,30 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
         self.items.reserve(additional);
         let min_sparse = self.items.len() + additional;
         if min_sparse > self.sparse.len() {
-            self.reserve_sparse(min_sparse - self.sparse.len());
+            self.reserve_sparse(min_sparse);
         }
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the given set.
+    /// Unlike [SparseSet::reserve], this returns an error instead of panicking or aborting on allocation failure.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let mut set = SparseSet::<()>::new();
+    /// set.try_reserve(10).expect("out of memory");
+    /// assert!(set.capacity() >= 10);
+    /// assert!(set.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.items.try_reserve(additional)?;
+        let min_sparse = self.items.len().saturating_add(additional);
+        if min_sparse > self.sparse.len() {
+            self.try_reserve_sparse(min_sparse)?;
+        }
+        Ok(())
+    }
+
     /// Returns the number of elements in the set, also referred to as its ‘length’.
     ///
     /// # Examples
@@ -180,7 +279,7 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
     pub fn insert(&mut self, i: I, v: T) -> Option<T> {
         let sparse_index = i.to_usize()?;
         if sparse_index >= self.sparse.len() {
-            self.reserve_sparse(sparse_index - self.sparse.len() + 1);
+            self.reserve_sparse(sparse_index + 1);
         }
         let item_index = self.sparse[sparse_index];
 
@@ -195,6 +294,34 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
         }
     }
 
+    /// Tries to insert `value` into the set, allocating more capacity if necessary.
+    /// The existing key-value in the set is returned.
+    ///
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// The set is left unchanged in that case.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{SparseSet};
+    /// let mut set = SparseSet::<i32>::new();
+    /// assert_eq!(set.try_insert(1, 123), Ok(None));
+    /// assert_eq!(set.try_insert(1, 456), Ok(Some(123)));
+    /// assert!(set.try_insert(usize::MAX, 789).is_err());
+    /// assert_eq!(set[1], 456);
+    /// ```
+    pub fn try_insert(&mut self, i: I, v: T) -> Result<Option<T>, TryReserveError> {
+        if let Some(sparse_index) = i.to_usize() {
+            if sparse_index >= self.sparse.len() {
+                self.try_reserve_sparse(sparse_index.saturating_add(1))?;
+            }
+            if self.get_item_index(&i).is_none() {
+                self.items.try_reserve(1)?;
+            }
+        }
+        Ok(self.insert(i, v))
+    }
+
     /// Removes and returns the element at index `i` from the set if exists.
     ///
     /// # Examples
@@ -365,11 +492,27 @@ impl<T, I: UnsignedNum> SparseSet<T, I> {
         }
     }
 
+    /// Returns the new length of the sparse vector to hold at least `min_len` keys.
+    fn sparse_growth(&self, min_len: usize) -> usize {
+        let doubled = cmp::max(self.sparse.len().saturating_mul(2), MIN_SPARSE_LEN);
+        let max_len = min_len.saturating_add((min_len as f32 * self.max_sparse_waste) as usize);
+        cmp::max(min_len, cmp::min(doubled, max_len))
+    }
+
     #[inline]
-    fn reserve_sparse(&mut self, additional: usize) {
-        self.sparse.reserve(additional);
+    fn reserve_sparse(&mut self, min_len: usize) {
+        let additional = self.sparse_growth(min_len) - self.sparse.len();
+        self.sparse.reserve_exact(additional);
         unsafe { self.sparse.set_len(self.sparse.capacity()) }
     }
+
+    #[inline]
+    fn try_reserve_sparse(&mut self, min_len: usize) -> Result<(), TryReserveError> {
+        let additional = self.sparse_growth(min_len) - self.sparse.len();
+        self.sparse.try_reserve_exact(additional)?;
+        unsafe { self.sparse.set_len(self.sparse.capacity()) }
+        Ok(())
+    }
 }
 
 impl<V, I: UnsignedNum> Map for SparseSet<V, I> {
@@ -385,6 +528,11 @@ impl<V, I: UnsignedNum> Map for SparseSet<V, I> {
     fn get(&self, i: &Self::Key) -> Option<&Self::Value> {
         self.get(i)
     }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
 }
 
 impl<V, I: UnsignedNum> MapMut for SparseSet<V, I> {
@@ -412,6 +560,11 @@ impl<V, I: UnsignedNum> MapMut for SparseSet<V, I> {
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
         self.retain(f)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
 }
 
 impl<T, I: UnsignedNum> Default for SparseSet<T, I> {
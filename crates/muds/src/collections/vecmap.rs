@@ -2,6 +2,7 @@
 
 use super::{Map, MapMut};
 use crate::UnsignedNum;
+use alloc::collections::TryReserveError;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 use core::{mem, ops};
@@ -157,7 +158,6 @@ impl<V, I: UnsignedNum> VecMap<V, I> {
     /// ```
     pub fn insert(&mut self, i: I, v: V) -> Option<V> {
         let index = i.to_usize()?;
-        self.len += 1;
         match self.items.get_mut(index) {
             Some(Some(item)) => Some(mem::replace(item, v)),
             _ => {
@@ -165,11 +165,38 @@ impl<V, I: UnsignedNum> VecMap<V, I> {
                     self.items.resize_with(index + 1, || None);
                 }
                 self.items[index] = Some(v);
+                self.len += 1;
                 None
             }
         }
     }
 
+    /// Tries to insert `value` into the map, allocating more capacity if necessary.
+    /// The existing key-value in the map is returned.
+    ///
+    /// # Errors
+    /// Returns an error if the capacity overflows or the allocator reports a failure.
+    /// The map is left unchanged in that case.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{VecMap};
+    /// let mut map = VecMap::<i32>::new();
+    /// assert_eq!(map.try_insert(1, 123), Ok(None));
+    /// assert_eq!(map.try_insert(1, 456), Ok(Some(123)));
+    /// assert!(map.try_insert(usize::MAX, 789).is_err());
+    /// assert_eq!(map[1], 456);
+    /// ```
+    pub fn try_insert(&mut self, i: I, v: V) -> Result<Option<V>, TryReserveError> {
+        if let Some(index) = i.to_usize() {
+            if index >= self.items.len() {
+                self.items
+                    .try_reserve(index.saturating_add(1) - self.items.len())?;
+            }
+        }
+        Ok(self.insert(i, v))
+    }
+
     /// Removes and returns the element at index `i` from the map if exists.
     ///
     /// # Examples
@@ -234,6 +261,22 @@ impl<V, I: UnsignedNum> VecMap<V, I> {
         self.items.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in the given map.
+    /// Unlike [VecMap::reserve], this returns an error instead of panicking or aborting on allocation failure.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::{VecMap};
+    /// let mut map = VecMap::<()>::new();
+    /// map.try_reserve(10).expect("out of memory");
+    /// assert!(map.capacity() >= 10);
+    /// assert!(map.try_reserve(usize::MAX).is_err());
+    /// ```
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.items.try_reserve(additional)
+    }
+
     /// Returns an iterator over the map.
     ///
     /// # Examples
@@ -291,6 +334,11 @@ impl<V, I: UnsignedNum> Map for VecMap<V, I> {
     fn get(&self, i: &Self::Key) -> Option<&Self::Value> {
         self.get(i)
     }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
 }
 
 impl<V, I: UnsignedNum> MapMut for VecMap<V, I> {
@@ -318,6 +366,11 @@ impl<V, I: UnsignedNum> MapMut for VecMap<V, I> {
     fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
         self.retain(f)
     }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
 }
 
 impl<V, I: UnsignedNum> Default for VecMap<V, I> {
@@ -1,12 +1,14 @@
 //! Archetype helpers.
 
 use super::{
+    labels::Labels,
     registry::{Ref, RefMut},
-    Component, Components, Entities, Entity, EntityId, Resources,
+    table::ArchetypeTables,
+    Component, Components, Entities, Entity, EntityId, Resources, ResourcesExt,
 };
 use crate::collections::Cons;
 use alloc::vec::Vec;
-use core::any::Any;
+use core::any::{Any, TypeId};
 
 /// Registry for archetypes, which represent a bundle of an [Entity] and its [Component]s.
 ///
@@ -114,6 +116,85 @@ pub trait Archetypes: Entities + Components + Resources + Sized {
             .insert(self, entity, components)
     }
 
+    /// Creates an [EntityBuilder] of an [Entity] with given cons of [Component]s.
+    /// More components can be added with [EntityBuilder::with], and the entity is inserted on [EntityBuilder::build].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{cons, Cons};
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{Archetypes, Registry, Component, Entity, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # struct Pos(u32, u32);
+    /// # struct Vel(u32, u32);
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// # impl Component<E> for Vel { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos, Vel)>();
+    /// let eid = registry.spawn(E, cons!(Pos(1, 2))).with(Vel(3, 4)).build();
+    /// let cons!(_e, p, v) = registry.storage::<&E, Cons!(&Pos, &Vel)>();
+    /// assert_eq!(p.get(&eid).unwrap().0, 1);
+    /// assert_eq!(v.get(&eid).unwrap().0, 3);
+    /// ```
+    #[inline]
+    fn spawn<E: Entity + Any, C>(&self, entity: E, components: C) -> EntityBuilder<'_, Self, E, C>
+    where
+        Self: Any,
+        C: Cons + InsertComponents<Self, E>,
+    {
+        EntityBuilder {
+            registry: self,
+            entity,
+            components,
+        }
+    }
+
+    /// Inserts a batch of [Entity]s with their [Component]s, returning their [EntityId]s in order.
+    /// Capacity for the batch is reserved up front in the entity storage and every component storage of `C`,
+    /// based on the lower bound of the iterator size.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{cons, Cons};
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{Archetypes, Registry, Component, Entity, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # struct Pos(u32, u32);
+    /// # struct Vel(u32, u32);
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// # impl Component<E> for Vel { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos, Vel)>();
+    /// let eids = registry.spawn_batch((0..10).map(|i| (E, cons!(Pos(i, i), Vel(1, 1)))));
+    /// assert_eq!(eids.len(), 10);
+    /// let cons!(e, p, v) = registry.storage::<&E, Cons!(&Pos, &Vel)>();
+    /// assert_eq!(e.len(), 10);
+    /// assert_eq!(p.get(&eids[3]).unwrap().0, 3);
+    /// assert_eq!(v.len(), 10);
+    /// ```
+    fn spawn_batch<E: Entity + Any, C, I: IntoIterator<Item = (E, C)>>(
+        &self,
+        batch: I,
+    ) -> Vec<EntityId<E>>
+    where
+        Self: Any,
+        C: Cons + InsertComponents<Self, E>,
+    {
+        use crate::collections::Arena;
+
+        let batch = batch.into_iter();
+        let additional = batch.size_hint().0;
+        Arena::reserve(&mut *self.entities_mut::<E>(), additional);
+        C::reserve(self, additional);
+
+        let archetype = self.resource::<Archetype<Self, E>>();
+        batch
+            .map(|(entity, components)| archetype.insert(self, entity, components))
+            .collect()
+    }
+
     /// Removes an [Entity] and all of its [Component]s by its [EntityId].
     ///
     /// # Examples
@@ -145,21 +226,191 @@ pub trait Archetypes: Entities + Components + Resources + Sized {
     {
         self.resource::<Archetype<Self, E>>().remove(self, key)
     }
+
+    /// Registers [ArchetypeTables] of an [Entity] as a resource, to store its components grouped by component set.
+    /// Registers the [Entity] archetype if not yet registered. Removing an archetype also removes its table components.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Archetypes, Entity, Registry, Resources, storage::ArenaStorage, table::ArchetypeTables};
+    /// # struct E;
+    /// # struct Pos(u32, u32);
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_tables::<E>();
+    /// let eid = registry.insert_archetype(E, ());
+    /// registry.resource_mut::<ArchetypeTables<E>>().insert(eid, Pos(1, 2));
+    /// registry.remove_archetype(&eid);
+    /// assert!(registry.resource::<ArchetypeTables<E>>().is_empty());
+    /// ```
+    fn register_tables<E: Entity + Any>(&mut self)
+    where
+        Self: Any,
+    {
+        if !self.has_resource::<Archetype<Self, E>>() {
+            self.register_archetype::<E, ()>();
+        }
+        self.register_resource(ArchetypeTables::<E>::new());
+        self.resource_mut::<Archetype<Self, E>>().register_tables();
+    }
+
+    /// Registers [Labels] of an [Entity] as a resource, to look up entities by name.
+    /// Registers the [Entity] archetype if not yet registered. Removing an archetype also removes its label.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Archetypes, Entity, Registry, Resources, storage::ArenaStorage, labels::Labels};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_labels::<E>();
+    /// let eid = registry.insert_archetype(E, ());
+    /// registry.resource_mut::<Labels<E>>().insert(eid, "player");
+    /// registry.remove_archetype(&eid);
+    /// assert!(registry.resource::<Labels<E>>().is_empty());
+    /// ```
+    fn register_labels<E: Entity + Any>(&mut self)
+    where
+        Self: Any,
+    {
+        if !self.has_resource::<Archetype<Self, E>>() {
+            self.register_archetype::<E, ()>();
+        }
+        self.register_resource(Labels::<E>::new());
+        self.resource_mut::<Archetype<Self, E>>().register_labels();
+    }
+
+    /// Registers the [ComponentTeardown] of a [Component] in the archetype of an [Entity].
+    /// The teardown is invoked with the removed component when its entity is removed by [Archetypes::remove_archetype]
+    /// or [Archetypes::clear_archetype], and when the registry is dropped.
+    /// Components removed directly from their storage are not torn down.
+    ///
+    /// # Panics
+    /// Panics if the archetype is not registered.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{cons, Cons};
+    /// # use muds::ecs::{Archetypes, Registry, Resources, Component, Entity, EntityId, storage::{ArenaStorage, VecStorage}};
+    /// # use muds::ecs::archetype::ComponentTeardown;
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct GpuBuffers(Vec<u32>);
+    /// struct Mesh(u32);
+    /// impl Component<E> for Mesh { type Storage = VecStorage<E, Self>; }
+    /// impl ComponentTeardown<Registry, E> for Mesh {
+    ///     fn teardown(self, registry: &Registry, _entity: &EntityId<E>) {
+    ///         registry.resource_mut::<GpuBuffers>().0.retain(|buffer| *buffer != self.0);
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(GpuBuffers(vec![1, 2]));
+    /// registry.register_archetype::<E, Cons!(Mesh)>();
+    /// registry.register_teardown::<E, Mesh>();
+    /// let eid = registry.insert_archetype(E, cons!(Mesh(1)));
+    /// registry.insert_archetype(E, cons!(Mesh(2)));
+    ///
+    /// registry.remove_archetype(&eid);
+    /// assert_eq!(registry.resource::<GpuBuffers>().0, [2]);
+    /// registry.clear_archetype::<E>();
+    /// assert!(registry.resource::<GpuBuffers>().0.is_empty());
+    /// ```
+    fn register_teardown<E: Entity + Any, C: ComponentTeardown<Self, E> + Any>(&mut self)
+    where
+        Self: ResourcesExt + Any,
+    {
+        let first = !self.resource::<Archetype<Self, E>>().has_teardown();
+        self.resource_mut::<Archetype<Self, E>>()
+            .register_teardown::<C>();
+        if first {
+            self.register_drop_callback(|registry| registry.clear_archetype::<E>());
+        }
+    }
+
+    /// Removes all entities of an [Entity] archetype and their [Component]s.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{cons, Cons};
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{Archetypes, Registry, Component, Entity, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # struct Pos(u32, u32);
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// registry.insert_archetype(E, cons!(Pos(1, 2)));
+    /// registry.insert_archetype(E, cons!(Pos(3, 4)));
+    /// registry.clear_archetype::<E>();
+    /// let cons!(e, p) = registry.storage::<&E, Cons!(&Pos)>();
+    /// assert!(e.is_empty());
+    /// assert!(p.is_empty());
+    /// ```
+    #[inline]
+    fn clear_archetype<E: Entity + Any>(&self)
+    where
+        Self: Any,
+    {
+        self.resource::<Archetype<Self, E>>().clear(self)
+    }
 }
 
 impl<T: Entities + Components + Resources> Archetypes for T {}
 
+/// Builder of an [Entity] and its [Component]s, created by [Archetypes::spawn].
+/// Components are accumulated into a cons, and inserted together with the entity on [EntityBuilder::build].
+#[must_use = "the entity is not inserted until build is called"]
+pub struct EntityBuilder<'a, R, E, C> {
+    registry: &'a R,
+    entity: E,
+    components: C,
+}
+
+impl<'a, R: Archetypes + Any, E: Entity + Any, C: Cons + InsertComponents<R, E>>
+    EntityBuilder<'a, R, E, C>
+{
+    /// Adds a [Component] to the entity.
+    #[inline]
+    pub fn with<T: Component<E> + Any>(self, component: T) -> EntityBuilder<'a, R, E, (T, C)> {
+        EntityBuilder {
+            registry: self.registry,
+            entity: self.entity,
+            components: (component, self.components),
+        }
+    }
+
+    /// Inserts the entity and its components, returning the [EntityId].
+    #[inline]
+    pub fn build(self) -> EntityId<E> {
+        self.registry.insert_archetype(self.entity, self.components)
+    }
+}
+
+/// A [Component] with a teardown callback, e.g. to release an external handle held by the component.
+/// See [Archetypes::register_teardown].
+pub trait ComponentTeardown<R, E: Entity>: Component<E> {
+    /// Tears down this component removed from given entity, with access to the registry resources.
+    fn teardown(self, registry: &R, entity: &EntityId<E>);
+}
+
 /// An entity archetype.
 pub struct Archetype<R: Entities + Components, E: Entity> {
-    drop: Vec<fn(&R, &EntityId<E>)>,
+    drop: Vec<(TypeId, DropFn<R, E>)>,
+    teardowns: usize,
 }
 
+/// Removes a component of an entity from registry.
+type DropFn<R, E> = fn(&R, &EntityId<E>);
+
 impl<R: Entities + Components, E: Entity + Any> Archetype<R, E> {
     /// Creates a new [Archetype].
     #[inline]
     pub fn new() -> Self {
         Self {
             drop: Default::default(),
+            teardowns: 0,
         }
     }
 
@@ -167,9 +418,38 @@ impl<R: Entities + Components, E: Entity + Any> Archetype<R, E> {
     pub fn register_component<C: Component<E> + Any>(&mut self) {
         use crate::collections::MapMut;
 
-        self.drop.push(|r, e| {
+        self.drop.push((TypeId::of::<C>(), |r, e| {
             r.components_mut::<E, C>().remove(e);
-        });
+        }));
+    }
+
+    /// Registers the [ComponentTeardown] of a [Component] to this [Archetype],
+    /// to be invoked when the component is removed with its entity.
+    pub fn register_teardown<C: ComponentTeardown<R, E> + Any>(&mut self) {
+        use crate::collections::MapMut;
+
+        let drop: DropFn<R, E> = |r, e| {
+            // Release the storage before teardown, which may access the registry
+            let component = r.components_mut::<E, C>().remove(e);
+            if let Some(component) = component {
+                component.teardown(r, e);
+            }
+        };
+        match self
+            .drop
+            .iter_mut()
+            .find(|(ty, _)| *ty == TypeId::of::<C>())
+        {
+            Some(entry) => entry.1 = drop,
+            None => self.drop.push((TypeId::of::<C>(), drop)),
+        }
+        self.teardowns += 1;
+    }
+
+    /// Returns `true` if any [ComponentTeardown] is registered to this [Archetype].
+    #[inline]
+    pub fn has_teardown(&self) -> bool {
+        self.teardowns > 0
     }
 
     /// Inserts an entity and components to registry.
@@ -188,11 +468,41 @@ impl<R: Entities + Components, E: Entity + Any> Archetype<R, E> {
     pub fn remove(&self, registry: &R, key: &EntityId<E>) {
         use crate::collections::Arena;
 
-        for drop in &self.drop {
+        for (_, drop) in &self.drop {
             drop(registry, key);
         }
         registry.entities_mut::<E>().remove(key);
     }
+
+    /// Removes all entities and their components from registry.
+    pub fn clear(&self, registry: &R) {
+        use crate::collections::IterableMap;
+
+        let keys: Vec<EntityId<E>> = registry
+            .entities::<E>()
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        for key in &keys {
+            self.remove(registry, key);
+        }
+    }
+}
+
+impl<R: Entities + Components + Resources, E: Entity + Any> Archetype<R, E> {
+    /// Registers [ArchetypeTables] resource of the entity to this [Archetype].
+    pub fn register_tables(&mut self) {
+        self.drop.push((TypeId::of::<ArchetypeTables<E>>(), |r, e| {
+            r.resource_mut::<ArchetypeTables<E>>().remove_entity(e);
+        }));
+    }
+
+    /// Registers [Labels] resource of the entity to this [Archetype].
+    pub fn register_labels(&mut self) {
+        self.drop.push((TypeId::of::<Labels<E>>(), |r, e| {
+            r.resource_mut::<Labels<E>>().remove(e);
+        }));
+    }
 }
 
 /// Trait for registering an archetype.
@@ -329,6 +639,10 @@ pub trait InsertComponents<R: Components, E: Entity>: Sized {
     /// Inserts components held by self to given [EntityId].
     #[inline(always)]
     fn insert(self, _registry: &R, _key: EntityId<E>) {}
+
+    /// Reserves capacity for `additional` more components in the storage of each component type.
+    #[inline(always)]
+    fn reserve(_registry: &R, _additional: usize) {}
 }
 
 impl<'a, R: Components, E: Entity> InsertComponents<R, E> for () {}
@@ -345,4 +659,12 @@ where
         registry.components_mut::<E, C>().insert(key, self.0);
         self.1.insert(registry, key);
     }
+
+    #[inline(always)]
+    fn reserve(registry: &R, additional: usize) {
+        use crate::collections::MapMut;
+
+        registry.components_mut::<E, C>().reserve(additional);
+        Tail::reserve(registry, additional);
+    }
 }
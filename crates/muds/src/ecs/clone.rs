@@ -0,0 +1,316 @@
+//! Entity cloning.
+
+use super::{
+    prefab::{CloneComponent, EntityMap},
+    Component, Components, Entities, Entity, EntityId, GenIndexType, Registry, RegistryKey,
+};
+use crate::collections::{Arena, Map, MapMut};
+use core::any::Any;
+
+/// A type-erased cloner of a component, registered by [Registry::register_cloneable].
+#[derive(Clone, Copy, Debug)]
+pub(super) struct ComponentCloner {
+    /// Key of the component storage.
+    key: RegistryKey,
+    /// Registers the component storage in a destination registry.
+    register: fn(&mut Registry),
+    /// Clones the component of the source entity index in the source registry, if any,
+    /// to the destination entity index in the destination registry, mapping its entity references.
+    clone: fn(&Registry, &Registry, &EntityMap, GenIndexType, GenIndexType),
+}
+
+impl Registry {
+    /// Registers a cloneable component type for an entity type, so that it is copied by [Registry::clone_entity],
+    /// and by [PrefabTypes::instantiate](super::prefab::PrefabTypes::instantiate) if this registry is a prefab.
+    /// The component storage is registered as well if needed.
+    ///
+    /// The component is cloned as is, so it should not hold [EntityId]s;
+    /// register components with entity references by [Registry::register_clone_component] instead.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::ecs::storage::{ArenaStorage, VecStorage};
+    /// #[derive(Clone)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// #[derive(Clone)]
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_cloneable::<E, Pos>();
+    /// assert!(registry.has_component::<E, Pos>());
+    /// ```
+    pub fn register_cloneable<E: Entity + Any, C: Component<E> + Clone + Any>(&mut self) {
+        self.register_cloner::<E, C>(|src_registry, dst_registry, _, src, dst| {
+            clone_component::<E, C>(src_registry, dst_registry, src, dst, |component| {
+                Some(component.clone())
+            })
+        });
+    }
+
+    /// Registers a component type holding entity references for an entity type, so that it is copied by
+    /// [Registry::clone_entities] with [CloneComponent], which remaps the references to the cloned entities.
+    /// Components of prefab registries are copied by [PrefabTypes::instantiate](super::prefab::PrefabTypes::instantiate)
+    /// the same way.
+    /// The component storage is registered as well if needed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{prefab::{CloneComponent, EntityMap}, storage::{ArenaStorage, VecStorage}};
+    /// #[derive(Clone)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct Target(EntityId<E>);
+    /// impl Component<E> for Target { type Storage = VecStorage<E, Self>; }
+    /// impl CloneComponent for Target {
+    ///     fn clone_component(&self, entities: &EntityMap) -> Option<Self> {
+    ///         Some(Target(entities.map(self.0)))
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_clone_component::<E, Target>();
+    /// let (a, b) = {
+    ///     let mut entities = registry.entities_mut::<E>();
+    ///     (entities.insert(E), entities.insert(E))
+    /// };
+    /// registry.components_mut::<E, Target>().insert(a, Target(b));
+    /// registry.components_mut::<E, Target>().insert(b, Target(a));
+    ///
+    /// let clones = registry.clone_entities([a, b]);
+    /// let (a2, b2) = (clones.get(a).unwrap(), clones.get(b).unwrap());
+    /// assert_eq!(registry.components::<E, Target>().get(&a2).unwrap().0, b2);
+    /// assert_eq!(registry.components::<E, Target>().get(&b2).unwrap().0, a2);
+    /// ```
+    pub fn register_clone_component<E: Entity + Any, C: Component<E> + CloneComponent + Any>(
+        &mut self,
+    ) {
+        self.register_cloner::<E, C>(|src_registry, dst_registry, entities, src, dst| {
+            clone_component::<E, C>(src_registry, dst_registry, src, dst, |component| {
+                component.clone_component(entities)
+            })
+        });
+    }
+
+    /// Clones an entity together with all its components registered by [Registry::register_cloneable].
+    /// Components that are not registered as cloneable are not copied.
+    /// Returns the ID of the new entity, or `None` if the source entity does not exist.
+    ///
+    /// # Panics
+    /// Panics if the entity storage, or any cloneable component storage, is currently borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::storage::{ArenaStorage, VecStorage};
+    /// #[derive(Clone)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// struct Selected;
+    /// impl Component<E> for Selected { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_cloneable::<E, Pos>();
+    /// registry.register_component::<E, Selected>();
+    /// let a = registry.entities_mut::<E>().insert(E);
+    /// registry.components_mut::<E, Pos>().insert(a, Pos(1));
+    /// registry.components_mut::<E, Selected>().insert(a, Selected);
+    ///
+    /// let b = registry.clone_entity(a).unwrap();
+    /// assert_ne!(a, b);
+    /// assert_eq!(registry.components::<E, Pos>().get(&b), Some(&Pos(1)));
+    /// assert!(!registry.components::<E, Selected>().contains_key(&b));
+    /// ```
+    #[inline]
+    pub fn clone_entity<E: Entity + Clone + Any>(&self, src: EntityId<E>) -> Option<EntityId<E>> {
+        self.clone_entity_with(src, |_| true)
+    }
+
+    /// Clones a group of entities, e.g. a subtree of the hierarchy, together with all their cloneable components.
+    /// Entity references between the cloned entities are remapped to the clones by [CloneComponent].
+    /// Entities that do not exist are skipped.
+    /// Returns the map from the source entities to their clones.
+    ///
+    /// # Panics
+    /// Panics if the entity storage, or any cloneable component storage, is currently borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{hierarchy::*, storage::ArenaStorage};
+    /// #[derive(Clone)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// register_hierarchy::<E>(&mut registry);
+    /// let (root, a, b) = {
+    ///     let mut entities = registry.entities_mut::<E>();
+    ///     (entities.insert(E), entities.insert(E), entities.insert(E))
+    /// };
+    /// attach(&registry, a, root).unwrap();
+    /// attach(&registry, b, a).unwrap();
+    ///
+    /// // Clone the subtree of a
+    /// let clones = registry.clone_entities([a, b]);
+    /// let (a2, b2) = (clones.get(a).unwrap(), clones.get(b).unwrap());
+    /// let children = registry.components::<E, Children<E>>();
+    /// assert_eq!(children.get(&a2).unwrap().as_slice(), [b2]);
+    /// assert_eq!(children.get(&root).unwrap().as_slice(), [a]);
+    /// let parents = registry.components::<E, Parent<E>>();
+    /// assert_eq!(parents.get(&b2), Some(&Parent(a2)));
+    /// assert!(parents.get(&a2).is_none());
+    /// ```
+    #[inline]
+    pub fn clone_entities<E: Entity + Clone + Any>(
+        &self,
+        srcs: impl IntoIterator<Item = EntityId<E>>,
+    ) -> EntityMap {
+        self.clone_entities_with(srcs, |_| true)
+    }
+
+    /// Clones an entity together with its cloneable components accepted by a filter.
+    /// The filter receives the key of each component storage, i.e. `RegistryKey::from_type::<(E, C)>()`.
+    /// See [Registry::clone_entity].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::storage::{ArenaStorage, VecStorage};
+    /// #[derive(Clone)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// #[derive(Clone)]
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// #[derive(Clone)]
+    /// struct Name(&'static str);
+    /// impl Component<E> for Name { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_cloneable::<E, Pos>();
+    /// registry.register_cloneable::<E, Name>();
+    /// let a = registry.entities_mut::<E>().insert(E);
+    /// registry.components_mut::<E, Pos>().insert(a, Pos(1));
+    /// registry.components_mut::<E, Name>().insert(a, Name("a"));
+    ///
+    /// let name = RegistryKey::from_type::<(E, Name)>();
+    /// let b = registry.clone_entity_with(a, |key| key != name).unwrap();
+    /// assert!(registry.components::<E, Pos>().contains_key(&b));
+    /// assert!(!registry.components::<E, Name>().contains_key(&b));
+    /// ```
+    #[inline]
+    pub fn clone_entity_with<E: Entity + Clone + Any>(
+        &self,
+        src: EntityId<E>,
+        filter: impl FnMut(RegistryKey) -> bool,
+    ) -> Option<EntityId<E>> {
+        self.clone_entities_with([src], filter).get(src)
+    }
+
+    /// Clones a group of entities together with their cloneable components accepted by a filter.
+    /// See [Registry::clone_entities] and [Registry::clone_entity_with].
+    pub fn clone_entities_with<E: Entity + Clone + Any>(
+        &self,
+        srcs: impl IntoIterator<Item = EntityId<E>>,
+        mut filter: impl FnMut(RegistryKey) -> bool,
+    ) -> EntityMap {
+        let mut entities = EntityMap::new();
+        for src in srcs {
+            let entity = self.entities::<E>().get(&src).cloned();
+            if let Some(entity) = entity {
+                entities.insert(src, Arena::insert(&mut *self.entities_mut::<E>(), entity));
+            }
+        }
+        let key = RegistryKey::from_type::<E>();
+        for cloner in self.cloners(&key) {
+            if filter(cloner.key) {
+                for (src, dst) in entities.indices(key) {
+                    (cloner.clone)(self, self, &entities, src, dst);
+                }
+            }
+        }
+        entities
+    }
+
+    /// Registers the cloneable components of an entity type in a target registry,
+    /// which are then copied by [Registry::clone_components_into].
+    pub(super) fn register_cloners_into(&self, entity: RegistryKey, target: &mut Registry) {
+        for cloner in self.cloners(&entity) {
+            (cloner.register)(target);
+            let cloners = target.cloners_mut(entity);
+            if cloners
+                .iter()
+                .all(|registered| registered.key != cloner.key)
+            {
+                cloners.push(*cloner);
+            }
+        }
+    }
+
+    /// Copies the cloneable components of mapped entities of an entity type into a target registry.
+    pub(super) fn clone_components_into(
+        &self,
+        entity: RegistryKey,
+        target: &Registry,
+        entities: &EntityMap,
+    ) {
+        for cloner in self.cloners(&entity) {
+            for (src, dst) in entities.indices(entity) {
+                (cloner.clone)(self, target, entities, src, dst);
+            }
+        }
+    }
+
+    /// Registers a component cloner for an entity type, unless the component is already registered.
+    fn register_cloner<E: Entity + Any, C: Component<E> + Any>(
+        &mut self,
+        clone: fn(&Registry, &Registry, &EntityMap, GenIndexType, GenIndexType),
+    ) {
+        self.register_component::<E, C>();
+        let key = RegistryKey::from_type::<(E, C)>();
+        let cloners = self.cloners_mut(RegistryKey::from_type::<E>());
+        if cloners.iter().all(|cloner| cloner.key != key) {
+            cloners.push(ComponentCloner {
+                key,
+                register: |registry| registry.register_component::<E, C>(),
+                clone,
+            });
+        }
+    }
+}
+
+/// Clones the component of the source entity index in the source registry, if any,
+/// to the destination entity index in the destination registry.
+fn clone_component<E: Entity + Any, C: Component<E> + Any>(
+    src_registry: &Registry,
+    dst_registry: &Registry,
+    src: GenIndexType,
+    dst: GenIndexType,
+    clone: impl FnOnce(&C) -> Option<C>,
+) {
+    let value = src_registry
+        .components::<E, C>()
+        .get(&EntityId::from_index(src))
+        .and_then(clone);
+    if let Some(value) = value {
+        dst_registry
+            .components_mut::<E, C>()
+            .insert(EntityId::from_index(dst), value);
+    }
+}
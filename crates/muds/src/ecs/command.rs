@@ -0,0 +1,243 @@
+//! Deferred structural changes to a registry.
+
+use super::{
+    archetype::InsertComponents, Archetypes, Component, Components, Entities, Entity, EntityId,
+    Registry,
+};
+use crate::collections::{Cons, Map, MapMut};
+use alloc::{boxed::Box, vec::Vec};
+use core::{any::Any, fmt};
+
+/// A queue of structural changes to a [Registry], i.e. entity spawns and despawns and component inserts and removes,
+/// applied in order on [CommandBuffer::flush].
+///
+/// Storages cannot be modified while they are borrowed for iteration. Queuing the changes into a [CommandBuffer]
+/// instead avoids collecting the affected entities into temporary vectors.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::{IterableMap, Map};
+/// # use muds::ecs::{command::CommandBuffer, storage::{ArenaStorage, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Health(i32);
+/// impl Component<E> for Health { type Storage = VecStorage<E, Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_archetype::<E, Cons!(Health)>();
+/// let alive = registry.insert_archetype(E, cons!(Health(1)));
+/// let dead = registry.insert_archetype(E, cons!(Health(0)));
+///
+/// let mut commands = CommandBuffer::new();
+/// for (id, health) in registry.components::<E, Health>().iter() {
+///     if health.0 <= 0 {
+///         commands.despawn(*id);
+///     }
+/// }
+/// commands.flush(&mut registry);
+///
+/// assert!(commands.is_empty());
+/// assert!(registry.entities::<E>().contains_key(&alive));
+/// assert!(!registry.entities::<E>().contains_key(&dead));
+/// ```
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+/// A queued command.
+type Command = Box<dyn FnOnce(&mut Registry)>;
+
+impl CommandBuffer {
+    /// Creates an empty command buffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of queued commands.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns `true` if no command is queued.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Discards all queued commands without applying them.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Queues a custom command.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{command::CommandBuffer, Registry, Resources};
+    /// struct Score(u32);
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(Score(0));
+    ///
+    /// let mut commands = CommandBuffer::new();
+    /// commands.push(|registry| registry.resource_mut::<Score>().0 += 1);
+    /// commands.flush(&mut registry);
+    /// assert_eq!(registry.resource::<Score>().0, 1);
+    /// ```
+    #[inline]
+    pub fn push(&mut self, command: impl FnOnce(&mut Registry) + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Queues the insertion of an [Entity] with its [Component]s, as in [Archetypes::insert_archetype].
+    /// The archetype must be registered by the time the buffer is flushed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::{Map, MapMut};
+    /// # use muds::ecs::{command::CommandBuffer, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # struct Pos(u32);
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    ///
+    /// let mut commands = CommandBuffer::new();
+    /// commands.spawn(E, cons!(Pos(1)));
+    /// assert!(registry.entities::<E>().is_empty());
+    ///
+    /// commands.flush(&mut registry);
+    /// assert_eq!(registry.entities::<E>().len(), 1);
+    /// assert_eq!(registry.components::<E, Pos>().len(), 1);
+    /// ```
+    #[inline]
+    pub fn spawn<E: Entity + Any, C: Cons + InsertComponents<Registry, E> + 'static>(
+        &mut self,
+        entity: E,
+        components: C,
+    ) {
+        self.push(move |registry| {
+            registry.insert_archetype(entity, components);
+        });
+    }
+
+    /// Queues the removal of an [Entity] and all of its [Component]s, as in [Archetypes::remove_archetype].
+    /// The archetype must be registered by the time the buffer is flushed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{command::CommandBuffer, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # struct Pos(u32);
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// let id = registry.insert_archetype(E, cons!(Pos(1)));
+    ///
+    /// let mut commands = CommandBuffer::new();
+    /// commands.despawn(id);
+    /// commands.flush(&mut registry);
+    /// assert!(registry.entities::<E>().is_empty());
+    /// assert!(registry.components::<E, Pos>().is_empty());
+    /// ```
+    #[inline]
+    pub fn despawn<E: Entity + Any>(&mut self, id: EntityId<E>) {
+        self.push(move |registry| registry.remove_archetype(&id));
+    }
+
+    /// Queues the insertion of a [Component] to an [Entity], replacing any existing value.
+    /// The component is dropped instead if the entity no longer exists when the buffer is flushed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::{IterableMap, Map};
+    /// # use muds::ecs::{command::CommandBuffer, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # struct Pos(u32);
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// struct Moved;
+    /// impl Component<E> for Moved { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos, Moved)>();
+    /// let id = registry.insert_archetype(E, cons!(Pos(1)));
+    /// let despawned = registry.insert_archetype(E, cons!(Pos(2)));
+    ///
+    /// let mut commands = CommandBuffer::new();
+    /// commands.despawn(despawned);
+    /// for (id, _) in registry.components::<E, Pos>().iter() {
+    ///     commands.insert(*id, Moved);
+    /// }
+    /// commands.flush(&mut registry);
+    ///
+    /// assert!(registry.components::<E, Moved>().contains_key(&id));
+    /// assert_eq!(registry.components::<E, Moved>().len(), 1);
+    /// ```
+    #[inline]
+    pub fn insert<E: Entity + Any, C: Component<E> + Any>(
+        &mut self,
+        id: EntityId<E>,
+        component: C,
+    ) {
+        self.push(move |registry| {
+            if registry.entities::<E>().contains_key(&id) {
+                registry.components_mut::<E, C>().insert(id, component);
+            }
+        });
+    }
+
+    /// Queues the removal of a [Component] from an [Entity].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{command::CommandBuffer, storage::{ArenaStorage, VecStorage}};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// # struct Pos(u32);
+    /// # impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// let id = registry.insert_archetype(E, cons!(Pos(1)));
+    ///
+    /// let mut commands = CommandBuffer::new();
+    /// commands.remove::<E, Pos>(id);
+    /// commands.flush(&mut registry);
+    /// assert!(registry.entities::<E>().contains_key(&id));
+    /// assert!(registry.components::<E, Pos>().is_empty());
+    /// ```
+    #[inline]
+    pub fn remove<E: Entity + Any, C: Component<E> + Any>(&mut self, id: EntityId<E>) {
+        self.push(move |registry| {
+            registry.components_mut::<E, C>().remove(&id);
+        });
+    }
+
+    /// Applies all queued commands to the registry in the order they were queued, leaving the buffer empty.
+    pub fn flush(&mut self, registry: &mut Registry) {
+        for command in self.commands.drain(..) {
+            command(registry);
+        }
+    }
+}
+
+impl fmt::Debug for CommandBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandBuffer")
+            .field("len", &self.commands.len())
+            .finish()
+    }
+}
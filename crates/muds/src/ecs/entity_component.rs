@@ -1,9 +1,9 @@
 //! Entity and component types.
 
-use super::registry::{Ref, RefMut};
+use super::registry::{Ref, RefMut, Registry};
 use crate::{
-    collections::{Arena, IterableMapMut, MapMut},
-    TypedIndex,
+    collections::{Arena, IterableMapMut, Map, MapMut},
+    GenIndex, TypedIndex,
 };
 use core::any::Any;
 
@@ -17,6 +17,9 @@ pub type GenIndexType = crate::IndexF64;
 /// [Entity] ID type.
 pub type EntityId<E> = TypedIndex<E, GenIndexType>;
 
+/// Index of an [EntityId] without its generation.
+pub type EntityIndex = <GenIndexType as GenIndex>::Index;
+
 /// Entity type.
 pub trait Entity: Sized {
     /// Entity storage type.
@@ -36,9 +39,54 @@ pub trait Component<E: Entity>: Sized {
 }
 
 /// [Component] storage trait type.
+///
+/// A storage maps each [EntityId] to at most one component. An element inserted with a newer generation of an index
+/// replaces the element of the older [EntityId], which is no longer found.
+/// Custom storages can be checked against this behavior with [assert_storage_impl](crate::assert_storage_impl).
 pub trait ComponentStorage<E: Entity, C: Component<E>>:
     Default + MapMut<Key = EntityId<E>, Value = C> + for<'a> IterableMapMut<'a> + 'static
 {
+    /// Performs periodic maintenance, e.g. forgetting the removals all readers have seen.
+    /// Invoked on every registered component storage by [Registry::maintain](super::Registry::maintain).
+    #[inline]
+    fn maintain(&mut self) {}
+}
+
+/// A type that can be constructed with a default value from a registry of type `R`,
+/// e.g. a component holding a handle from an asset cache resource.
+/// [Default] types can opt in to construct their default value from any registry
+/// with `#[derive(FromRegistry)]`.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::{FromRegistry, Registry, Resources};
+/// # use core::marker::PhantomData;
+/// struct Texture(u32);
+/// impl FromRegistry for Texture {
+///     fn from_registry(registry: &Registry) -> Self {
+///         Texture(*registry.resource::<u32>())
+///     }
+/// }
+///
+/// let mut registry = Registry::default();
+/// registry.register_resource(7u32);
+/// assert_eq!(Texture::from_registry(&registry).0, 7);
+///
+/// # #[cfg(feature = "derive")]
+/// # {
+/// #[derive(FromRegistry, Default)]
+/// struct Scale(f32);
+/// assert_eq!(Scale::from_registry(&registry).0, 0.);
+///
+/// // Lifetime and const generics are kept
+/// #[derive(FromRegistry, Default)]
+/// struct Slot<'a, Registry, const N: usize>(Option<&'a Registry>, PhantomData<[u8; N]>);
+/// assert!(Slot::<'_, u32, 2>::from_registry(&registry).0.is_none());
+/// # }
+/// ```
+pub trait FromRegistry<R: ?Sized = Registry>: Sized {
+    /// Creates a default value from the registry.
+    fn from_registry(registry: &R) -> Self;
 }
 
 /// Type alias for the storage of an [Entity].
@@ -185,4 +233,45 @@ pub trait Components {
     fn components_mut<'a, E: Entity + Any, C: Component<E> + Any>(
         &'a self,
     ) -> RefMut<'a, C::Storage>;
+
+    /// Inserts a component with its [FromRegistry] default value for an entity, if the entity does not have one.
+    /// Returns `true` if the component is inserted.
+    ///
+    /// # Panics
+    /// Panics if the component is not registered, or its storage is already borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{Registry, Component, Components, Entity, Entities, FromRegistry, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// #[derive(Debug, PartialEq)]
+    /// struct Pos(u32, u32);
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    /// impl FromRegistry for Pos {
+    ///     fn from_registry(_registry: &Registry) -> Self { Pos(0, 0) }
+    /// }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_component::<E, Pos>();
+    /// let e = registry.entities_mut::<E>().insert(E);
+    ///
+    /// assert!(registry.insert_or_default::<E, Pos>(e));
+    /// assert!(!registry.insert_or_default::<E, Pos>(e));
+    /// assert_eq!(registry.components::<E, Pos>().get(&e), Some(&Pos(0, 0)));
+    /// ```
+    fn insert_or_default<E: Entity + Any, C: Component<E> + FromRegistry<Self> + Any>(
+        &self,
+        entity: EntityId<E>,
+    ) -> bool {
+        if self.components::<E, C>().contains_key(&entity) {
+            return false;
+        }
+        // Construct before borrowing the storage mutably, as the value may read from other resources
+        let value = C::from_registry(self);
+        self.components_mut::<E, C>().insert(entity, value);
+        true
+    }
 }
@@ -0,0 +1,429 @@
+//! Event channels between systems.
+
+use alloc::collections::{vec_deque, VecDeque};
+use core::{
+    cmp,
+    iter::{repeat, Chain, Repeat, Zip},
+    marker::PhantomData,
+    mem,
+};
+
+/// A double-buffered channel of events of type `T`, usually registered as a resource.
+///
+/// Events sent during a frame stay readable until the end of the next frame, i.e. for two calls of
+/// [Events::update], so that systems running before the sender in a frame still receive them.
+/// Each reader keeps its own cursor and receives every event at most once.
+///
+/// Every event is stamped with the frame it was sent in, see [Events::frame].
+/// A channel can also be bounded with [Events::bounded], in which case its [OverflowPolicy]
+/// decides what happens to events sent while it is full.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::{events::Events, Registry, ResourcesExt};
+/// struct Hit(u32);
+///
+/// let mut registry = Registry::default();
+/// registry.register_events::<Hit>();
+/// let mut reader = registry.events::<Hit>().reader();
+///
+/// registry.events_mut::<Hit>().writer().send(Hit(1));
+/// assert_eq!(reader.read(&registry.events::<Hit>()).map(|hit| hit.0).collect::<Vec<_>>(), [1]);
+/// assert_eq!(reader.read(&registry.events::<Hit>()).count(), 0);
+///
+/// // Events are dropped after two updates
+/// registry.events_mut::<Hit>().send(Hit(2));
+/// registry.events_mut::<Hit>().update();
+/// registry.events_mut::<Hit>().update();
+/// assert_eq!(reader.read(&registry.events::<Hit>()).count(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Events<T> {
+    previous: VecDeque<T>,
+    current: VecDeque<T>,
+    /// Sequence number of the first event in `previous`.
+    previous_start: usize,
+    /// Sequence number of the first event in `current`.
+    current_start: usize,
+    /// Frame of the events in `current`.
+    frame: u64,
+    capacity: Option<usize>,
+    policy: OverflowPolicy,
+    overflows: u64,
+    last_overflow_frame: Option<u64>,
+}
+
+/// Policy of a bounded [Events] channel for events sent while it is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverflowPolicy {
+    /// Drops the oldest retained event to make room for the new one.
+    /// Readers that have not read the dropped event skip it.
+    DropOldest,
+    /// Drops the new event.
+    DropNewest,
+    /// Panics.
+    Panic,
+    /// Keeps the new event beyond the capacity, and logs a warning at most once per frame.
+    #[cfg(feature = "log")]
+    Log,
+}
+
+impl Default for OverflowPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::DropOldest
+    }
+}
+
+impl<T> Events<T> {
+    /// Creates an empty, unbounded event channel.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            previous: VecDeque::new(),
+            current: VecDeque::new(),
+            previous_start: 0,
+            current_start: 0,
+            frame: 0,
+            capacity: None,
+            policy: OverflowPolicy::default(),
+            overflows: 0,
+            last_overflow_frame: None,
+        }
+    }
+
+    /// Creates an empty event channel retaining at most `capacity` events,
+    /// with given policy for events sent while it is full.
+    ///
+    /// # Panics
+    /// Panics if the capacity is 0.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::events::{Events, OverflowPolicy};
+    /// let mut events = Events::bounded(2, OverflowPolicy::DropOldest);
+    /// let mut reader = events.reader();
+    /// events.send_batch([1, 2, 3]);
+    /// assert_eq!(reader.read(&events).collect::<Vec<_>>(), [&2, &3]);
+    /// assert_eq!(events.overflows(), 1);
+    ///
+    /// let mut events = Events::bounded(2, OverflowPolicy::DropNewest);
+    /// events.send_batch([1, 2, 3]);
+    /// assert_eq!(events.iter().collect::<Vec<_>>(), [&1, &2]);
+    /// ```
+    pub fn bounded(capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0, "event capacity must not be 0");
+        Self {
+            capacity: Some(capacity),
+            policy,
+            previous: VecDeque::with_capacity(capacity),
+            current: VecDeque::with_capacity(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the maximum number of retained events, or `None` if unbounded.
+    #[inline]
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// Returns the overflow policy.
+    #[inline]
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Returns the total number of events sent while the channel was full.
+    /// A growing count indicates an event storm, or readers falling behind.
+    #[inline]
+    pub fn overflows(&self) -> u64 {
+        self.overflows
+    }
+
+    /// Returns the frame in which the channel last overflowed, if any.
+    #[inline]
+    pub fn last_overflow_frame(&self) -> Option<u64> {
+        self.last_overflow_frame
+    }
+
+    /// Returns the current frame, i.e. the number of [Events::update] calls so far.
+    /// Events sent now are stamped with this frame.
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Sends an event.
+    ///
+    /// # Panics
+    /// Panics if the channel is full and its policy is [OverflowPolicy::Panic].
+    pub fn send(&mut self, event: T) {
+        match self.capacity {
+            Some(capacity) if self.len() >= capacity => {
+                self.overflows += 1;
+                #[cfg(feature = "log")]
+                let first_overflow_in_frame = self.last_overflow_frame != Some(self.frame);
+                self.last_overflow_frame = Some(self.frame);
+                match self.policy {
+                    OverflowPolicy::DropOldest => self.drop_oldest(),
+                    OverflowPolicy::DropNewest => return,
+                    OverflowPolicy::Panic => panic!(
+                        "event channel of {} overflowed its capacity of {}",
+                        core::any::type_name::<T>(),
+                        capacity
+                    ),
+                    #[cfg(feature = "log")]
+                    OverflowPolicy::Log => {
+                        if first_overflow_in_frame {
+                            log::warn!(
+                                "event channel of {} overflowed its capacity of {} in frame {}",
+                                core::any::type_name::<T>(),
+                                capacity,
+                                self.frame
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.current.push_back(event);
+    }
+
+    /// Sends a batch of events.
+    #[inline]
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        if self.capacity.is_some() {
+            for event in events {
+                self.send(event);
+            }
+        } else {
+            self.current.extend(events);
+        }
+    }
+
+    /// Returns a writer for sending events.
+    #[inline]
+    pub fn writer(&mut self) -> EventWriter<'_, T> {
+        EventWriter { events: self }
+    }
+
+    /// Returns a reader that only receives events sent after this call.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::events::{EventReader, Events};
+    /// let mut events = Events::new();
+    /// events.send(1);
+    /// let mut reader = events.reader();
+    /// events.send(2);
+    /// assert_eq!(reader.read(&events).collect::<Vec<_>>(), [&2]);
+    ///
+    /// // A default reader receives all retained events
+    /// assert_eq!(EventReader::default().read(&events).collect::<Vec<_>>(), [&1, &2]);
+    /// ```
+    #[inline]
+    pub fn reader(&self) -> EventReader<T> {
+        EventReader {
+            next: self.end(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Swaps the event buffers, dropping the events sent before the previous update, and advances the frame.
+    /// This should be called once per frame.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::events::Events;
+    /// let mut events = Events::new();
+    /// events.send(1);
+    /// events.update();
+    /// assert_eq!(events.len(), 1);
+    /// events.update();
+    /// assert!(events.is_empty());
+    /// assert_eq!(events.frame(), 2);
+    /// ```
+    pub fn update(&mut self) {
+        mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+        self.previous_start = self.current_start;
+        self.current_start += self.previous.len();
+        self.frame += 1;
+    }
+
+    /// Returns the number of retained events.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    /// Returns `true` if no event is retained.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all retained events. Readers will not receive them.
+    /// This does not advance the frame.
+    #[inline]
+    pub fn clear(&mut self) {
+        let frame = self.frame;
+        self.update();
+        self.update();
+        self.frame = frame;
+    }
+
+    /// Returns an iterator over all retained events, oldest first.
+    #[inline]
+    pub fn iter(&self) -> EventIter<'_, T> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    /// Returns an iterator over all retained events and the frames they were sent in, oldest first.
+    #[inline]
+    pub fn iter_stamped(&self) -> StampedEventIter<'_, T> {
+        self.stamped(0, 0)
+    }
+
+    /// Returns the sequence number of the next event.
+    #[inline]
+    fn end(&self) -> usize {
+        self.current_start + self.current.len()
+    }
+
+    /// Drops the oldest retained event.
+    fn drop_oldest(&mut self) {
+        if self.previous.pop_front().is_some() {
+            self.previous_start += 1;
+        } else if self.current.pop_front().is_some() {
+            self.current_start += 1;
+            self.previous_start = self.current_start;
+        }
+    }
+
+    /// Returns the stamped events starting from given positions in the previous and current buffers.
+    #[inline]
+    fn stamped(&self, previous: usize, current: usize) -> StampedEventIter<'_, T> {
+        repeat(self.frame.wrapping_sub(1))
+            .zip(self.previous.range(previous..))
+            .chain(repeat(self.frame).zip(self.current.range(current..)))
+    }
+}
+
+impl<T> Default for Events<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator over events.
+pub type EventIter<'a, T> = Chain<vec_deque::Iter<'a, T>, vec_deque::Iter<'a, T>>;
+
+/// Iterator over events and the frames they were sent in.
+pub type StampedEventIter<'a, T> =
+    Chain<Zip<Repeat<u64>, vec_deque::Iter<'a, T>>, Zip<Repeat<u64>, vec_deque::Iter<'a, T>>>;
+
+/// Sends events into an [Events] channel.
+#[derive(Debug)]
+pub struct EventWriter<'a, T> {
+    events: &'a mut Events<T>,
+}
+
+impl<'a, T> EventWriter<'a, T> {
+    /// Sends an event.
+    #[inline]
+    pub fn send(&mut self, event: T) {
+        self.events.send(event);
+    }
+
+    /// Sends a batch of events.
+    #[inline]
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        self.events.send_batch(events);
+    }
+}
+
+/// Reads events from an [Events] channel. Each reader is usually owned by a system.
+#[derive(Debug)]
+pub struct EventReader<T> {
+    /// Sequence number of the next event to read.
+    next: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> EventReader<T> {
+    /// Returns an iterator over the events not yet read by this reader, oldest first.
+    /// Events dropped by [Events::update] or overflow before being read are skipped.
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> EventIter<'a, T> {
+        let (previous, current) = self.advance(events);
+        events
+            .previous
+            .range(previous..)
+            .chain(events.current.range(current..))
+    }
+
+    /// Returns an iterator over the events not yet read by this reader and the frames they were sent in,
+    /// oldest first. This allows a system to process the events of each frame exactly once,
+    /// even if it does not run every frame.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::events::Events;
+    /// let mut events = Events::new();
+    /// let mut reader = events.reader();
+    /// events.send('a');
+    /// events.update();
+    /// events.send('b');
+    /// assert_eq!(reader.read_stamped(&events).collect::<Vec<_>>(), [(0, &'a'), (1, &'b')]);
+    /// ```
+    pub fn read_stamped<'a>(&mut self, events: &'a Events<T>) -> StampedEventIter<'a, T> {
+        let (previous, current) = self.advance(events);
+        events.stamped(previous, current)
+    }
+
+    /// Returns the number of events not yet read by this reader.
+    #[inline]
+    pub fn len(&self, events: &Events<T>) -> usize {
+        events.end() - cmp::max(self.next, events.previous_start)
+    }
+
+    /// Returns `true` if there is no event left to read.
+    #[inline]
+    pub fn is_empty(&self, events: &Events<T>) -> bool {
+        self.len(events) == 0
+    }
+
+    /// Marks all events as read, and returns the positions of the first unread event
+    /// in the previous and current buffers.
+    fn advance(&mut self, events: &Events<T>) -> (usize, usize) {
+        let next = cmp::max(self.next, events.previous_start);
+        self.next = events.end();
+
+        let previous = cmp::min(next - events.previous_start, events.previous.len());
+        let current = next.saturating_sub(events.current_start);
+        (previous, current)
+    }
+}
+
+impl<T> Clone for EventReader<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for EventReader<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            next: 0,
+            phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,351 @@
+//! Parent-child hierarchy of entities.
+//!
+//! The hierarchy is stored in the [Parent] and [Children] components, which are kept consistent by
+//! [attach] and [detach]. Systems such as transform propagation can then walk the hierarchy depth-first
+//! with [descendants], visiting each parent before its children.
+
+use super::{
+    prefab::{CloneComponent, EntityMap},
+    storage::VecStorage,
+    Component, ComponentStorageOf, Components, Entities, Entity, EntityId, Registry,
+};
+use crate::collections::Map;
+use alloc::vec::Vec;
+use core::{any::Any, fmt, slice};
+
+/// Component referencing the parent of an entity.
+pub struct Parent<E: Entity>(pub EntityId<E>);
+
+impl<E: Entity + Any> Component<E> for Parent<E> {
+    type Storage = VecStorage<E, Self>;
+}
+
+impl<E: Entity> Clone for Parent<E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Entity> Copy for Parent<E> {}
+
+/// The parent is only kept if it is cloned as well, as the clone would not be one of the original parent's [Children].
+/// The clone of a subtree root is then a root, which can be attached with [attach].
+impl<E: Entity + Any> CloneComponent for Parent<E> {
+    #[inline]
+    fn clone_component(&self, entities: &EntityMap) -> Option<Self> {
+        entities.get(self.0).map(Parent)
+    }
+}
+
+impl<E: Entity> fmt::Debug for Parent<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Parent").field(&self.0).finish()
+    }
+}
+
+impl<E: Entity> PartialEq for Parent<E> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// Component listing the children of an entity in attachment order.
+pub struct Children<E: Entity>(Vec<EntityId<E>>);
+
+impl<E: Entity + Any> Component<E> for Children<E> {
+    type Storage = VecStorage<E, Self>;
+}
+
+impl<E: Entity> Children<E> {
+    /// Returns the children as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[EntityId<E>] {
+        &self.0
+    }
+
+    /// Returns an iterator over the children.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, EntityId<E>> {
+        self.0.iter()
+    }
+
+    /// Returns the number of children.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there is no child.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<E: Entity> Clone for Children<E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// Only the children that are cloned as well are kept, as the other children still belong to the original entity.
+impl<E: Entity + Any> CloneComponent for Children<E> {
+    fn clone_component(&self, entities: &EntityMap) -> Option<Self> {
+        let children = self
+            .0
+            .iter()
+            .filter_map(|&child| entities.get(child))
+            .collect::<Vec<_>>();
+        if children.is_empty() {
+            None
+        } else {
+            Some(Children(children))
+        }
+    }
+}
+
+impl<E: Entity> fmt::Debug for Children<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Children").field(&self.0).finish()
+    }
+}
+
+impl<'a, E: Entity> IntoIterator for &'a Children<E> {
+    type Item = &'a EntityId<E>;
+    type IntoIter = slice::Iter<'a, EntityId<E>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Error of a hierarchy operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyError {
+    /// The entity does not exist.
+    EntityNotFound,
+    /// The parent is the child itself or one of its descendants.
+    Cycle,
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EntityNotFound => write!(f, "entity not found"),
+            Self::Cycle => write!(f, "parent is a descendant of the child"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HierarchyError {}
+
+/// Registers the [Parent] and [Children] components of an entity type.
+/// They are registered as cloneable by [Registry::register_clone_component],
+/// so that [Registry::clone_entities] clones subtrees with their hierarchy remapped to the clones.
+pub fn register_hierarchy<E: Entity + Any>(registry: &mut Registry) {
+    registry.register_clone_component::<E, Parent<E>>();
+    registry.register_clone_component::<E, Children<E>>();
+}
+
+/// Attaches an entity to a parent, detaching it from its previous parent if any.
+///
+/// # Errors
+/// Returns [HierarchyError::EntityNotFound] if either entity does not exist,
+/// or [HierarchyError::Cycle] if the parent is the child itself or one of its descendants.
+/// The hierarchy is left unchanged in that case.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::Map;
+/// # use muds::ecs::{hierarchy::*, storage::ArenaStorage};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_entity::<E>();
+/// register_hierarchy::<E>(&mut registry);
+/// let (a, b, c) = {
+///     let mut entities = registry.entities_mut::<E>();
+///     (entities.insert(E), entities.insert(E), entities.insert(E))
+/// };
+///
+/// attach(&registry, b, a).unwrap();
+/// attach(&registry, c, b).unwrap();
+/// assert_eq!(registry.components::<E, Parent<E>>().get(&c), Some(&Parent(b)));
+/// assert_eq!(attach(&registry, a, c), Err(HierarchyError::Cycle));
+///
+/// // Reattaching moves the entity
+/// attach(&registry, c, a).unwrap();
+/// assert_eq!(registry.components::<E, Children<E>>().get(&a).unwrap().as_slice(), [b, c]);
+/// assert!(registry.components::<E, Children<E>>().get(&b).is_none());
+/// ```
+pub fn attach<E: Entity + Any>(
+    registry: &Registry,
+    child: EntityId<E>,
+    parent: EntityId<E>,
+) -> Result<(), HierarchyError> {
+    {
+        let entities = registry.entities::<E>();
+        if !entities.contains_key(&child) || !entities.contains_key(&parent) {
+            return Err(HierarchyError::EntityNotFound);
+        }
+    }
+
+    let mut parents = registry.components_mut::<E, Parent<E>>();
+    if child == parent || is_ancestor(&*parents, child, parent) {
+        return Err(HierarchyError::Cycle);
+    }
+
+    let mut children = registry.components_mut::<E, Children<E>>();
+    if let Some((_, Parent(previous))) = parents.insert(child, Parent(parent)) {
+        remove_child(&mut *children, previous, child);
+    }
+    match children.get_mut(&parent) {
+        Some(siblings) => siblings.0.push(child),
+        None => {
+            children.insert(parent, Children(alloc::vec![child]));
+        }
+    }
+    Ok(())
+}
+
+/// Detaches an entity from its parent, making it a root. Its own children stay attached.
+/// Returns the previous parent if any.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::Map;
+/// # use muds::ecs::{hierarchy::*, storage::ArenaStorage};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_entity::<E>();
+/// register_hierarchy::<E>(&mut registry);
+/// let (a, b) = {
+///     let mut entities = registry.entities_mut::<E>();
+///     (entities.insert(E), entities.insert(E))
+/// };
+///
+/// attach(&registry, b, a).unwrap();
+/// assert_eq!(detach(&registry, b), Some(a));
+/// assert_eq!(detach(&registry, b), None);
+/// assert!(registry.components::<E, Children<E>>().get(&a).is_none());
+/// ```
+pub fn detach<E: Entity + Any>(registry: &Registry, child: EntityId<E>) -> Option<EntityId<E>> {
+    let Parent(parent) = registry.components_mut::<E, Parent<E>>().remove(&child)?;
+    remove_child(
+        &mut *registry.components_mut::<E, Children<E>>(),
+        parent,
+        child,
+    );
+    Some(parent)
+}
+
+/// Returns `true` if `ancestor` is a proper ancestor of `entity`.
+pub fn is_ancestor<E: Entity + Any>(
+    parents: &ComponentStorageOf<E, Parent<E>>,
+    ancestor: EntityId<E>,
+    entity: EntityId<E>,
+) -> bool {
+    let mut current = entity;
+    // Bound the walk by the number of parent links, so that manually corrupted hierarchies cannot loop forever
+    for _ in 0..parents.len() {
+        match parents.get(&current) {
+            Some(Parent(parent)) if *parent == ancestor => return true,
+            Some(Parent(parent)) => current = *parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Returns a depth-first iterator over the descendants of an entity, excluding the entity itself.
+/// Each item is a pair of a descendant and its parent. Parents are always visited before their children.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::ecs::{hierarchy::*, storage::ArenaStorage};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_entity::<E>();
+/// register_hierarchy::<E>(&mut registry);
+/// let (a, b, c, d) = {
+///     let mut entities = registry.entities_mut::<E>();
+///     (entities.insert(E), entities.insert(E), entities.insert(E), entities.insert(E))
+/// };
+/// attach(&registry, b, a).unwrap();
+/// attach(&registry, c, b).unwrap();
+/// attach(&registry, d, a).unwrap();
+///
+/// let children = registry.components::<E, Children<E>>();
+/// assert_eq!(descendants(&children, a).collect::<Vec<_>>(), [(b, a), (c, b), (d, a)]);
+/// ```
+pub fn descendants<E: Entity + Any>(
+    children: &ComponentStorageOf<E, Children<E>>,
+    entity: EntityId<E>,
+) -> Descendants<'_, E> {
+    let mut descendants = Descendants {
+        children,
+        stack: Vec::new(),
+    };
+    descendants.push_children(entity);
+    descendants
+}
+
+/// Depth-first iterator over the descendants of an entity. See [descendants].
+pub struct Descendants<'a, E: Entity + Any> {
+    children: &'a ComponentStorageOf<E, Children<E>>,
+    stack: Vec<(EntityId<E>, EntityId<E>)>,
+}
+
+impl<'a, E: Entity + Any> Descendants<'a, E> {
+    fn push_children(&mut self, parent: EntityId<E>) {
+        if let Some(children) = self.children.get(&parent) {
+            self.stack
+                .extend(children.iter().rev().map(|child| (*child, parent)));
+        }
+    }
+}
+
+impl<'a, E: Entity + Any> Iterator for Descendants<'a, E> {
+    type Item = (EntityId<E>, EntityId<E>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (entity, parent) = self.stack.pop()?;
+        self.push_children(entity);
+        Some((entity, parent))
+    }
+}
+
+impl<'a, E: Entity + Any> fmt::Debug for Descendants<'a, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Descendants")
+            .field("stack", &self.stack)
+            .finish()
+    }
+}
+
+/// Removes a child from the children of a parent, removing the component once empty.
+fn remove_child<E: Entity + Any>(
+    children: &mut ComponentStorageOf<E, Children<E>>,
+    parent: EntityId<E>,
+    child: EntityId<E>,
+) {
+    if let Some(siblings) = children.get_mut(&parent) {
+        siblings.0.retain(|sibling| *sibling != child);
+        if siblings.0.is_empty() {
+            children.remove(&parent);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Interpolation of transforms for rendering fixed timestep simulations.
+//!
+//! A simulation updated at a fixed timestep rarely lines up with the frame rate, so rendering its latest state
+//! makes motion stutter. [run_fixed_updates] saves the transform component of every entity as a [PreviousTransform]
+//! before each update, so that rendering can blend the last 2 states with [interpolate_transforms],
+//! by the fraction of a timestep elapsed since the last update.
+
+use super::{storage::VecStorage, Component, Components, Entity, EntityId, Registry, Resources};
+use crate::collections::{IterableMap, Map};
+use core::any::Any;
+
+/// A value that can be linearly interpolated, e.g. a transform.
+pub trait Interpolate {
+    /// Returns the value at `alpha` in `[0, 1]` between this value (at 0) and `to` (at 1).
+    fn interpolate(&self, to: &Self, alpha: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    #[inline]
+    fn interpolate(&self, to: &Self, alpha: f32) -> Self {
+        self + (to - self) * alpha
+    }
+}
+
+impl Interpolate for f64 {
+    #[inline]
+    fn interpolate(&self, to: &Self, alpha: f32) -> Self {
+        self + (to - self) * alpha as f64
+    }
+}
+
+impl<T: Interpolate + Copy, const N: usize> Interpolate for [T; N] {
+    #[inline]
+    fn interpolate(&self, to: &Self, alpha: f32) -> Self {
+        let mut result = *self;
+        for i in 0..N {
+            result[i] = self[i].interpolate(&to[i], alpha);
+        }
+        result
+    }
+}
+
+/// Component holding the value of transform component `T` before the last fixed timestep update.
+/// Maintained by [run_fixed_updates].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PreviousTransform<T>(pub T);
+
+impl<E: Entity + Any, T: Any> Component<E> for PreviousTransform<T> {
+    type Storage = VecStorage<E, Self>;
+}
+
+/// Resource holding the timing state of fixed timestep updates. See [run_fixed_updates].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedTimestep {
+    step: f64,
+    max_steps: u32,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    /// Default maximum number of updates per frame.
+    pub const DEFAULT_MAX_STEPS: u32 = 10;
+
+    /// Creates a fixed timestep of `step` seconds.
+    ///
+    /// # Panics
+    /// Panics if `step` is not positive.
+    pub fn new(step: f64) -> Self {
+        assert!(step > 0., "timestep must be positive");
+        Self {
+            step,
+            max_steps: Self::DEFAULT_MAX_STEPS,
+            accumulator: 0.,
+        }
+    }
+
+    /// Sets the maximum number of updates per frame.
+    /// Time beyond the maximum updates is dropped, so that a slow frame does not cause ever more updates to catch up.
+    #[inline]
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Returns the timestep in seconds.
+    #[inline]
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Returns the fraction in `[0, 1)` of a timestep elapsed since the last update,
+    /// for interpolating between the last 2 simulation states.
+    #[inline]
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step) as f32
+    }
+
+    /// Adds `dt` seconds of elapsed time, and returns the number of updates now due.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::interpolation::FixedTimestep;
+    /// let mut timestep = FixedTimestep::new(0.5).with_max_steps(2);
+    /// assert_eq!(timestep.advance(0.75), 1);
+    /// assert_eq!(timestep.alpha(), 0.5);
+    /// assert_eq!(timestep.advance(2.), 2);
+    /// assert_eq!(timestep.alpha(), 0.5);
+    /// ```
+    pub fn advance(&mut self, dt: f64) -> u32 {
+        self.accumulator += dt.max(0.);
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            if steps >= self.max_steps {
+                self.accumulator %= self.step;
+                break;
+            }
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+        steps
+    }
+}
+
+/// Advances the [FixedTimestep] resource by `dt` seconds, and runs `update` with the timestep for each update due.
+/// Before each update, transform component `T` of all `E` entities is saved as their [PreviousTransform].
+/// Returns the alpha to render with [interpolate_transforms].
+///
+/// # Panics
+/// Panics if the resource or components are not registered, or are borrowed.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::MapMut;
+/// # use muds::ecs::storage::{ArenaStorage, VecStorage};
+/// use muds::ecs::interpolation::{
+///     interpolate_transforms, run_fixed_updates, FixedTimestep, Interpolate, PreviousTransform,
+/// };
+///
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// #[derive(Clone)]
+/// struct Pos(f32);
+/// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+/// impl Interpolate for Pos {
+///     fn interpolate(&self, to: &Self, alpha: f32) -> Self {
+///         Pos(self.0.interpolate(&to.0, alpha))
+///     }
+/// }
+///
+/// let mut registry = Registry::default();
+/// registry.register_archetype::<E, Cons!(Pos, PreviousTransform<Pos>)>();
+/// registry.register_resource(FixedTimestep::new(0.1));
+/// let id = registry.insert_archetype(E, cons!(Pos(0.)));
+///
+/// // Moves by 1 unit per update
+/// let alpha = run_fixed_updates::<E, Pos>(&mut registry, 0.25, |registry, _| {
+///     for (_, pos) in registry.components_mut::<E, Pos>().iter_mut() {
+///         pos.0 += 1.;
+///     }
+/// });
+/// assert!((alpha - 0.5).abs() < 1e-5);
+///
+/// // Renders halfway between the last 2 updates
+/// interpolate_transforms::<E, Pos>(&registry, alpha, |entity, pos| {
+///     assert_eq!(entity, id);
+///     assert!((pos.0 - 1.5).abs() < 1e-5);
+/// });
+/// ```
+pub fn run_fixed_updates<E, T>(
+    registry: &mut Registry,
+    dt: f64,
+    mut update: impl FnMut(&mut Registry, f64),
+) -> f32
+where
+    E: Entity + Any,
+    T: Component<E> + Clone + Any,
+{
+    let (steps, step) = {
+        let mut timestep = registry.resource_mut::<FixedTimestep>();
+        (timestep.advance(dt), timestep.step())
+    };
+    for _ in 0..steps {
+        store_previous_transforms::<E, T>(registry);
+        update(registry, step);
+    }
+    registry.resource::<FixedTimestep>().alpha()
+}
+
+/// Saves transform component `T` of all `E` entities as their [PreviousTransform],
+/// and removes the previous transforms of entities without `T`.
+/// This is done by [run_fixed_updates] before each update.
+///
+/// # Panics
+/// Panics if the components are not registered, or are borrowed mutably.
+pub fn store_previous_transforms<E, T>(registry: &Registry)
+where
+    E: Entity + Any,
+    T: Component<E> + Clone + Any,
+{
+    let current = registry.components::<E, T>();
+    let mut previous = registry.components_mut::<E, PreviousTransform<T>>();
+    previous.retain(|id, _| current.contains_key(id));
+    for (id, transform) in current.iter() {
+        match previous.get_mut(id) {
+            Some(PreviousTransform(value)) => value.clone_from(transform),
+            None => {
+                previous.insert(*id, PreviousTransform(transform.clone()));
+            }
+        }
+    }
+}
+
+/// Calls `f` with the render transform of every `E` entity with transform component `T`,
+/// interpolated from its [PreviousTransform] to its current value by `alpha`.
+/// Entities without a previous transform, e.g. ones spawned after the last update, use their current value.
+///
+/// # Panics
+/// Panics if the components are not registered, or are borrowed mutably.
+pub fn interpolate_transforms<E, T>(
+    registry: &Registry,
+    alpha: f32,
+    mut f: impl FnMut(EntityId<E>, T),
+) where
+    E: Entity + Any,
+    T: Component<E> + Interpolate + Clone + Any,
+{
+    let current = registry.components::<E, T>();
+    let previous = registry.components::<E, PreviousTransform<T>>();
+    for (id, transform) in current.iter() {
+        let transform = match previous.get(id) {
+            Some(PreviousTransform(previous)) => previous.interpolate(transform, alpha),
+            None => transform.clone(),
+        };
+        f(*id, transform);
+    }
+}
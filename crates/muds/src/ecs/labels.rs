@@ -0,0 +1,135 @@
+//! Entity labels, i.e. unique names to look entities up by.
+//!
+//! [Labels] is a resource mapping names to entities and back, e.g. for tools, debug UIs and scripts
+//! to find entities by name without scanning the component storages.
+//! Register it with [Archetypes::register_labels](super::Archetypes::register_labels),
+//! so that removing an archetype also removes its label.
+//!
+//! # Examples
+//! ```rust
+//! # use muds::ecs::{Archetypes, Entity, Registry, Resources, storage::ArenaStorage, labels::Labels};
+//! struct E;
+//! impl Entity for E { type Storage = ArenaStorage<Self>; }
+//!
+//! let mut registry = Registry::default();
+//! registry.register_labels::<E>();
+//! let (player, camera) = (registry.insert_archetype(E, ()), registry.insert_archetype(E, ()));
+//! {
+//!     let mut labels = registry.resource_mut::<Labels<E>>();
+//!     labels.insert(player, "player");
+//!     labels.insert(camera, "camera");
+//!     assert_eq!(labels.get("player"), Some(player));
+//!     assert_eq!(labels.name(&camera), Some("camera"));
+//! }
+//!
+//! // Removing the archetype removes its label
+//! registry.remove_archetype(&player);
+//! assert_eq!(registry.resource::<Labels<E>>().get("player"), None);
+//! ```
+
+use super::{Entity, EntityId};
+use crate::collections::GenIndexVecMap;
+use alloc::{collections::BTreeMap, string::String};
+use core::fmt;
+
+/// Bidirectional map between unique names and entities of an [Entity] type.
+/// See the [module docs](self).
+///
+/// Each entity has at most one name, and each name labels at most one entity.
+/// Entities removed directly from their storage keep their labels until removed with [Labels::remove].
+pub struct Labels<E: Entity> {
+    entities: BTreeMap<String, EntityId<E>>,
+    names: GenIndexVecMap<String, EntityId<E>>,
+}
+
+impl<E: Entity> Labels<E> {
+    /// Creates an empty [Labels].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            names: GenIndexVecMap::default(),
+        }
+    }
+
+    /// Returns the number of labeled entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if no entity is labeled.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Gets the entity labeled with a name.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<EntityId<E>> {
+        self.entities.get(name).copied()
+    }
+
+    /// Gets the name of an entity.
+    #[inline]
+    pub fn name(&self, id: &EntityId<E>) -> Option<&str> {
+        self.names.get(id).map(String::as_str)
+    }
+
+    /// Returns `true` if a name labels any entity.
+    #[inline]
+    pub fn contains_name(&self, name: &str) -> bool {
+        self.entities.contains_key(name)
+    }
+
+    /// Labels an entity with a name. Returns the previous name of the entity.
+    /// The name is taken from the entity it previously labeled, if any.
+    pub fn insert(&mut self, id: EntityId<E>, name: impl Into<String>) -> Option<String> {
+        let name = name.into();
+        let previous = self.remove(&id);
+        if let Some(other) = self.entities.insert(name.clone(), id) {
+            self.names.remove(&other);
+        }
+        self.names.insert(id, name);
+        previous
+    }
+
+    /// Removes the label of an entity. Returns its name.
+    pub fn remove(&mut self, id: &EntityId<E>) -> Option<String> {
+        let name = self.names.remove(id)?;
+        self.entities.remove(&name);
+        Some(name)
+    }
+
+    /// Removes a name. Returns the entity it labeled.
+    pub fn remove_name(&mut self, name: &str) -> Option<EntityId<E>> {
+        let id = self.entities.remove(name)?;
+        self.names.remove(&id);
+        Some(id)
+    }
+
+    /// Removes all labels.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.names.clear();
+    }
+
+    /// Returns an iterator over the names and their entities, ordered by name.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, EntityId<E>)> + '_ {
+        self.entities.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+}
+
+impl<E: Entity> Default for Labels<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> fmt::Debug for Labels<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,314 @@
+//! Incremental mirroring of component storages into GPU buffers.
+
+use super::{storage::Tracked, Entity, EntityId};
+use crate::collections::{IterableMap, Map, MapMut, VecMap};
+use crate::GenIndex;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Mirrors the components of a [Tracked] storage into a tightly packed byte buffer,
+/// tracking a change tick per element so that only dirty ranges need to be re-uploaded to the GPU.
+///
+/// Changes are detected from the change ticks of the [Tracked] storage, so that only the components
+/// inserted or mutably borrowed since the last sync are encoded. Writes through [Tracked::get_mut_untracked]
+/// are not mirrored.
+///
+/// Each component is assigned a stable slot in the buffer, which can be queried via [BufferMirror::slot],
+/// e.g. to index instance data in shaders. Removing a component moves the last slot into the freed one.
+///
+/// The mirror does not depend on a graphics backend. Uploads are done via a callback,
+/// which would typically forward to `GPUDevice::write_buffer` of a `mugl` device.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::ecs::{mirror::BufferMirror, storage::{ArenaStorage, Tracked, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos(f32);
+/// impl Component<E> for Pos { type Storage = Tracked<E, VecStorage<E, Self>>; }
+///
+/// let mut entities = ArenaStorage::<E>::default();
+/// let mut positions = Tracked::<E, VecStorage<E, Pos>>::default();
+/// let (e1, e2) = (entities.insert(E), entities.insert(E));
+/// positions.insert(e1, Pos(1.));
+/// positions.insert(e2, Pos(2.));
+///
+/// let mut mirror = BufferMirror::new(4);
+/// let encode = |pos: &Pos, bytes: &mut [u8]| bytes.copy_from_slice(&pos.0.to_le_bytes());
+/// let mut uploads = Vec::new();
+/// mirror.sync(&positions, encode, |offset, data| uploads.push((offset, data.len())));
+/// assert_eq!(uploads, [(0, 8)]);
+///
+/// positions.get_mut(&e2).unwrap().0 = 3.;
+/// uploads.clear();
+/// mirror.sync(&positions, encode, |offset, data| uploads.push((offset, data.len())));
+/// assert_eq!(uploads, [(mirror.slot(&e2).unwrap() * 4, 4)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BufferMirror<K: GenIndex> {
+    stride: usize,
+    data: Vec<u8>,
+    keys: Vec<K>,
+    ticks: Vec<u64>,
+    slots: VecMap<usize, K::Index>,
+    tick: u64,
+    /// Tick of the storage at the last sync.
+    storage_tick: Option<u64>,
+    /// Whether all elements are marked as changed since the last sync.
+    all_changed: bool,
+    scratch: Vec<u8>,
+}
+
+impl<K: GenIndex> BufferMirror<K> {
+    /// Creates a new, empty [BufferMirror] for elements of `stride` bytes.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::mirror::BufferMirror, Index};
+    /// let mirror = BufferMirror::<Index>::new(16);
+    /// assert_eq!(mirror.stride(), 16);
+    /// assert!(mirror.is_empty());
+    /// ```
+    pub fn new(stride: usize) -> Self {
+        Self {
+            stride,
+            data: Vec::new(),
+            keys: Vec::new(),
+            ticks: Vec::new(),
+            slots: VecMap::new(),
+            tick: 0,
+            storage_tick: None,
+            all_changed: false,
+            scratch: alloc::vec![0; stride],
+        }
+    }
+
+    /// Returns the byte size of each element.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns the number of mirrored elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there is no mirrored element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the mirrored bytes. The GPU buffer needs to be at least this large.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the tick of the last [BufferMirror::sync].
+    #[inline]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the slot of an element in the buffer.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{collections::MapMut, ecs::{mirror::BufferMirror, storage::{ArenaStorage, Tracked, VecStorage}, Entity, EntityId}, GenIndex};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut set = Tracked::<E, VecStorage<E, u8>>::default();
+    /// let key = EntityId::from_raw_parts(3, 1);
+    /// set.insert(key, 1);
+    ///
+    /// let mut mirror = BufferMirror::new(1);
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    /// assert_eq!(mirror.slot(&key), Some(0));
+    /// assert_eq!(mirror.slot(&EntityId::from_raw_parts(3, 2)), None);
+    /// ```
+    pub fn slot(&self, key: &K) -> Option<usize> {
+        let slot = *self.slots.get(&key.index())?;
+        if self.keys[slot] == *key {
+            Some(slot)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the tick at which the element in given slot was last changed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{collections::MapMut, ecs::{mirror::BufferMirror, storage::{ArenaStorage, Tracked, VecStorage}, Entity, EntityId}, GenIndex};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut set = Tracked::<E, VecStorage<E, u8>>::default();
+    /// set.insert(EntityId::from_raw_parts(0, 1), 1);
+    /// set.insert(EntityId::from_raw_parts(1, 1), 2);
+    ///
+    /// let mut mirror = BufferMirror::new(1);
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    /// *set.get_mut(&EntityId::from_raw_parts(1, 1)).unwrap() = 3;
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    /// assert_eq!(mirror.tick(), 2);
+    /// assert_eq!(mirror.changed_tick(0), Some(1));
+    /// assert_eq!(mirror.changed_tick(1), Some(2));
+    ///
+    /// // Syncing an unchanged storage does nothing
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    /// assert_eq!(mirror.tick(), 2);
+    /// ```
+    #[inline]
+    pub fn changed_tick(&self, slot: usize) -> Option<u64> {
+        self.ticks.get(slot).copied()
+    }
+
+    /// Marks all elements as changed, so that the next [BufferMirror::sync] uploads the whole buffer.
+    /// This should be called after the GPU buffer is recreated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{collections::MapMut, ecs::{mirror::BufferMirror, storage::{ArenaStorage, Tracked, VecStorage}, Entity, EntityId}, GenIndex};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut set = Tracked::<E, VecStorage<E, u8>>::default();
+    /// set.insert(EntityId::from_raw_parts(0, 1), 1);
+    /// set.insert(EntityId::from_raw_parts(1, 1), 2);
+    ///
+    /// let mut mirror = BufferMirror::new(1);
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    /// mirror.mark_all_changed();
+    ///
+    /// let mut uploads = Vec::new();
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |offset, data| uploads.push((offset, data.to_vec())));
+    /// assert_eq!(uploads.len(), 1);
+    /// assert_eq!(uploads[0].1.len(), 2);
+    /// ```
+    pub fn mark_all_changed(&mut self) {
+        let next_tick = self.tick + 1;
+        for tick in &mut self.ticks {
+            *tick = next_tick;
+        }
+        self.all_changed = true;
+    }
+
+    fn swap_remove(&mut self, slot: usize) {
+        self.slots.remove(&self.keys[slot].index());
+        self.keys.swap_remove(slot);
+        self.ticks.swap_remove(slot);
+
+        let last = self.keys.len();
+        if slot < last {
+            let (range, last_range) = (self.byte_range(slot), self.byte_range(last));
+            self.data.copy_within(last_range, range.start);
+            self.slots.insert(self.keys[slot].index(), slot);
+            self.ticks[slot] = self.tick;
+        }
+        self.data.truncate(last * self.stride);
+    }
+
+    #[inline]
+    fn byte_range(&self, slot: usize) -> Range<usize> {
+        (slot * self.stride)..((slot + 1) * self.stride)
+    }
+}
+
+impl<E: Entity> BufferMirror<EntityId<E>> {
+    /// Synchronizes the mirror with a [Tracked] storage, and uploads the changed byte ranges.
+    /// Only the elements changed since the last sync are encoded, and nothing is done if the storage is unchanged.
+    /// `encode` writes a value into a slice of [BufferMirror::stride] bytes.
+    /// `upload` receives the byte offset and data of each contiguous dirty range.
+    /// Returns the new tick.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{collections::MapMut, ecs::{mirror::BufferMirror, storage::{ArenaStorage, Tracked, VecStorage}, Entity, EntityId}, GenIndex};
+    /// # struct E;
+    /// # impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// let mut set = Tracked::<E, VecStorage<E, u8>>::default();
+    /// let (k1, k2, k3) = (EntityId::from_raw_parts(0, 1), EntityId::from_raw_parts(1, 1), EntityId::from_raw_parts(2, 1));
+    /// set.insert(k1, 1);
+    /// set.insert(k2, 2);
+    /// set.insert(k3, 3);
+    ///
+    /// let mut mirror = BufferMirror::new(1);
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |_, _| {});
+    ///
+    /// // Removing the first element moves the last element into its slot
+    /// set.remove(&k1);
+    /// let mut uploads = Vec::new();
+    /// mirror.sync(&set, |v, bytes| bytes[0] = *v, |offset, data| uploads.push((offset, data.to_vec())));
+    /// assert_eq!(mirror.len(), 2);
+    /// assert_eq!(mirror.slot(&k3), Some(0));
+    /// assert_eq!(uploads, [(0, vec![3])]);
+    /// ```
+    pub fn sync<S, V>(
+        &mut self,
+        storage: &Tracked<E, S>,
+        mut encode: impl FnMut(&V, &mut [u8]),
+        mut upload: impl FnMut(usize, &[u8]),
+    ) -> u64
+    where
+        S: MapMut<Key = EntityId<E>, Value = V> + for<'a> IterableMap<'a, Key = EntityId<E>>,
+    {
+        let since = self.storage_tick;
+        if since == Some(storage.tick()) && !self.all_changed {
+            return self.tick;
+        }
+        self.storage_tick = Some(storage.tick());
+        self.all_changed = false;
+        self.tick += 1;
+
+        // Remove elements no longer in storage
+        let mut slot = 0;
+        while slot < self.keys.len() {
+            if storage.contains_key(&self.keys[slot]) {
+                slot += 1;
+            } else {
+                self.swap_remove(slot);
+            }
+        }
+
+        // Add or update elements
+        for (key, value) in storage.iter() {
+            let slot = self.slot(key);
+            if slot.is_some() && since.map_or(false, |since| !storage.is_changed(key, since)) {
+                continue;
+            }
+            self.scratch.fill(0);
+            encode(value, &mut self.scratch);
+
+            if let Some(slot) = slot {
+                let range = self.byte_range(slot);
+                self.data[range].copy_from_slice(&self.scratch);
+                self.ticks[slot] = self.tick;
+            } else {
+                self.slots.insert(key.index(), self.keys.len());
+                self.keys.push(*key);
+                self.ticks.push(self.tick);
+                self.data.extend_from_slice(&self.scratch);
+            }
+        }
+
+        // Upload contiguous dirty ranges
+        let mut start = None;
+        for slot in 0..=self.ticks.len() {
+            let dirty = slot < self.ticks.len() && self.ticks[slot] >= self.tick;
+            match (dirty, start) {
+                (true, None) => start = Some(slot),
+                (false, Some(first)) => {
+                    let range = first * self.stride..slot * self.stride;
+                    upload(range.start, &self.data[range]);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        self.tick
+    }
+}
@@ -1,12 +1,30 @@
 //! Entity-Component-Resource registry.
 
+mod clone;
 mod entity_component;
 mod registry_traits;
 mod resource;
 
 pub mod archetype;
+pub mod command;
+pub mod events;
+pub mod hierarchy;
+pub mod interpolation;
+pub mod labels;
+pub mod mirror;
+pub mod prefab;
+pub mod query;
+pub mod reactive;
 pub mod registry;
+pub mod rng;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod snapshot;
+pub mod spatial;
 pub mod storage;
+pub mod table;
+#[cfg(feature = "munum")]
+pub mod transform;
 
 /// Commonly used types.
 pub mod prelude {
@@ -16,7 +34,7 @@ pub mod prelude {
     pub use super::resource::*;
 
     #[cfg(feature = "muds-derive")]
-    pub use muds_derive::{Component, Entity};
+    pub use muds_derive::{Component, Entity, FromRegistry};
 }
 
 pub use prelude::*;
@@ -0,0 +1,223 @@
+//! Prefabs, i.e. template registries instantiated into other registries.
+//!
+//! A prefab is a [Registry] holding template entities, e.g. a character with its equipment.
+//! [PrefabTypes::instantiate] copies all entities of the registered types into a target registry,
+//! then copies their components registered as cloneable in the prefab registry, by
+//! [Registry::register_cloneable] or [Registry::register_clone_component]. Components are copied with
+//! [CloneComponent], which remaps the [EntityId]s they hold from the prefab entities to the new entities
+//! through an [EntityMap].
+//!
+//! # Examples
+//! ```rust
+//! # use muds::prelude::*;
+//! # use muds::collections::Map;
+//! # use muds::ecs::storage::{ArenaStorage, VecStorage};
+//! use muds::ecs::prefab::{CloneComponent, EntityMap, PrefabTypes};
+//!
+//! #[derive(Clone)]
+//! struct E;
+//! impl Entity for E { type Storage = ArenaStorage<Self>; }
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct Pos(u32);
+//! impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+//! struct Target(EntityId<E>);
+//! impl Component<E> for Target { type Storage = VecStorage<E, Self>; }
+//! impl CloneComponent for Target {
+//!     fn clone_component(&self, entities: &EntityMap) -> Option<Self> {
+//!         Some(Target(entities.map(self.0)))
+//!     }
+//! }
+//!
+//! let mut types = PrefabTypes::new();
+//! types.register_entity::<E>();
+//!
+//! // A prefab of 2 entities targeting each other
+//! let mut prefab = Registry::default();
+//! prefab.register_archetype::<E, Cons!(Pos, Target)>();
+//! prefab.register_cloneable::<E, Pos>();
+//! prefab.register_clone_component::<E, Target>();
+//! let a = prefab.entities_mut::<E>().insert(E);
+//! let b = prefab.insert_archetype(E, cons!(Pos(2), Target(a)));
+//! prefab.components_mut::<E, Target>().insert(a, Target(b));
+//!
+//! // Instantiate the prefab twice
+//! let mut registry = Registry::default();
+//! registry.register_entity::<E>();
+//! registry.entities_mut::<E>().insert(E);
+//! let first = types.instantiate(&prefab, &mut registry);
+//! let second = types.instantiate(&prefab, &mut registry);
+//! assert_eq!(registry.entities::<E>().len(), 5);
+//!
+//! let (a1, b1) = (first.get(a).unwrap(), first.get(b).unwrap());
+//! let b2 = second.get(b).unwrap();
+//! assert_eq!(registry.components::<E, Pos>().get(&b1), Some(&Pos(2)));
+//! assert_eq!(registry.components::<E, Target>().get(&a1).unwrap().0, b1);
+//! assert_eq!(registry.components::<E, Target>().get(&b1).unwrap().0, a1);
+//! assert_eq!(registry.components::<E, Target>().get(&b2).unwrap().0, second.get(a).unwrap());
+//! ```
+
+use super::{Entities, Entity, EntityId, EntityIndex, GenIndexType, Registry, RegistryKey};
+use crate::{
+    collections::{Arena, IterableMap},
+    GenIndex,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::any::Any;
+
+/// A component that can be cloned into another registry, remapping the [EntityId]s it holds.
+///
+/// Components are registered for cloning with [Registry::register_clone_component].
+/// Components without entity references can be cloned as they are, by registering them with
+/// [Registry::register_cloneable] instead.
+pub trait CloneComponent: Sized {
+    /// Clones this component, mapping its entity references with given map.
+    /// Returns `None` to leave the cloned entity without the component, e.g. if it references an entity
+    /// that is not cloned, and the reference would not be valid for the clone.
+    fn clone_component(&self, entities: &EntityMap) -> Option<Self>;
+}
+
+/// A map from the entities of a prefab registry to the entities instantiated from them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntityMap {
+    /// Maps entity type key and prefab index to the prefab and instantiated entity indices.
+    map: BTreeMap<(RegistryKey, EntityIndex), (GenIndexType, GenIndexType)>,
+}
+
+impl EntityMap {
+    /// Creates an empty map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of mapped entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no entity is mapped.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the pairs of prefab and instantiated entities of an entity type.
+    pub fn iter<E: Entity + Any>(&self) -> impl Iterator<Item = (EntityId<E>, EntityId<E>)> + '_ {
+        self.indices(RegistryKey::from_type::<E>())
+            .map(|(src, dst)| (EntityId::from_index(src), EntityId::from_index(dst)))
+    }
+
+    /// Returns an iterator over the pairs of prefab and instantiated entity indices of an entity type key.
+    pub(super) fn indices(
+        &self,
+        entity: RegistryKey,
+    ) -> impl Iterator<Item = (GenIndexType, GenIndexType)> + '_ {
+        self.map
+            .range((entity, 0)..=(entity, EntityIndex::MAX))
+            .map(|(_, indices)| *indices)
+    }
+
+    /// Maps a prefab entity to an instantiated entity.
+    pub fn insert<E: Entity + Any>(&mut self, src: EntityId<E>, dst: EntityId<E>) {
+        let src = src.to_index();
+        self.map.insert(
+            (RegistryKey::from_type::<E>(), src.index()),
+            (src, dst.to_index()),
+        );
+    }
+
+    /// Gets the entity instantiated from a prefab entity, or `None` if the entity is not mapped.
+    pub fn get<E: Entity + Any>(&self, src: EntityId<E>) -> Option<EntityId<E>> {
+        let src = src.to_index();
+        self.map
+            .get(&(RegistryKey::from_type::<E>(), src.index()))
+            .filter(|(mapped, _)| *mapped == src)
+            .map(|(_, dst)| EntityId::from_index(*dst))
+    }
+
+    /// Maps an entity reference, keeping references to entities outside of the prefab unchanged.
+    #[inline]
+    pub fn map<E: Entity + Any>(&self, id: EntityId<E>) -> EntityId<E> {
+        self.get(id).unwrap_or(id)
+    }
+}
+
+/// Registry of the entity types to copy when instantiating prefabs.
+/// The components to copy are the ones registered as cloneable in the prefab registry.
+#[derive(Clone, Debug, Default)]
+pub struct PrefabTypes {
+    entities: Vec<PrefabType>,
+}
+
+/// Type-erased copier of an entity storage.
+#[derive(Clone, Copy, Debug)]
+struct PrefabType {
+    /// Key of the storage.
+    key: RegistryKey,
+    /// Registers the storage in the target registry.
+    register: fn(&mut Registry),
+    /// Copies the entities from the prefab to the target registry.
+    instantiate: fn(&Registry, &Registry, &mut EntityMap),
+}
+
+impl PrefabTypes {
+    /// Creates an empty registry of prefab types.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an entity type to instantiate.
+    pub fn register_entity<E: Entity + Clone + Any>(&mut self) {
+        Self::register(
+            &mut self.entities,
+            PrefabType {
+                key: RegistryKey::from_type::<E>(),
+                register: |target| target.register_entity::<E>(),
+                instantiate: |prefab, target, entities| {
+                    if !prefab.has_entity::<E>() {
+                        return;
+                    }
+                    let src = prefab.entities::<E>();
+                    let mut dst = target.entities_mut::<E>();
+                    for (id, entity) in src.iter() {
+                        entities.insert(*id, Arena::insert(&mut *dst, entity.clone()));
+                    }
+                },
+            },
+        );
+    }
+
+    /// Instantiates all entities of the registered types in a prefab registry into a target registry,
+    /// together with their components registered as cloneable in the prefab registry.
+    /// The entity and component storages are registered in the target registry if needed,
+    /// and the components are registered as cloneable in the target registry as well.
+    /// Returns the map from the prefab entities to the new entities.
+    ///
+    /// Entities are instantiated before any component, so that components can reference entities of any type.
+    ///
+    /// # Panics
+    /// Panics if any of the storages is currently borrowed.
+    pub fn instantiate(&self, prefab: &Registry, target: &mut Registry) -> EntityMap {
+        for ty in &self.entities {
+            (ty.register)(target);
+            prefab.register_cloners_into(ty.key, target);
+        }
+        let mut entities = EntityMap::new();
+        for ty in &self.entities {
+            (ty.instantiate)(prefab, target, &mut entities);
+        }
+        for ty in &self.entities {
+            prefab.clone_components_into(ty.key, target, &entities);
+        }
+        entities
+    }
+
+    /// Adds a type if it is not yet registered.
+    fn register(types: &mut Vec<PrefabType>, ty: PrefabType) {
+        if types.iter().all(|registered| registered.key != ty.key) {
+            types.push(ty);
+        }
+    }
+}
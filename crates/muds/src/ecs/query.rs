@@ -0,0 +1,469 @@
+//! Cached queries over entity and component storages.
+
+use super::{
+    registry::RefMut, Component, Components, Entities, Entity, EntityId, EntityIndex, Registry,
+    RegistryKey,
+};
+use crate::{
+    collections::{IterableMap, IterableMapMut, Map, MapMut},
+    GenIndex,
+};
+use alloc::vec::Vec;
+use core::{
+    any::Any,
+    fmt,
+    ops::{Deref, Range},
+    slice,
+};
+
+/// A cached set of entities matching component filters.
+///
+/// Joining storages re-checks the membership of every entity on each iteration, which is wasteful
+/// when only a few entities of a large storage match, e.g. with fragmented components.
+/// A [Query] instead remembers the matched entities, together with the [Registry::version]s of the storages
+/// it depends on, and only rebuilds the set when any of those storages may have changed.
+///
+/// Mutably borrowing a storage via [Entities::entities_mut] or [Components::components_mut] invalidates
+/// the queries depending on it, as it allows inserting or removing elements.
+/// To update the values of matched entities without invalidating queries,
+/// use [Registry::entity_values_mut] and [Registry::component_values_mut] instead.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::MapMut;
+/// # use muds::ecs::{query::Query, storage::{ArenaStorage, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos(u32);
+/// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+/// struct Vel(u32);
+/// impl Component<E> for Vel { type Storage = VecStorage<E, Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_archetype::<E, Cons!(Pos, Vel)>();
+/// let a = registry.insert_archetype(E, cons!(Pos(0), Vel(1)));
+/// let _b = registry.insert_archetype(E, cons!(Pos(0)));
+///
+/// let mut moving = Query::<E>::new().with::<Pos>().with::<Vel>();
+/// for _ in 0..2 {
+///     // Refresh the query before borrowing its storages mutably
+///     let entities = moving.entities(&registry);
+///     let mut pos = registry.component_values_mut::<E, Pos>();
+///     let vel = registry.components::<E, Vel>();
+///     for id in entities {
+///         pos.get_mut(id).unwrap().0 += vel.get(id).unwrap().0;
+///     }
+/// }
+/// assert_eq!(moving.entities(&registry), [a]);
+/// assert_eq!(registry.components::<E, Pos>().get(&a).unwrap().0, 2);
+/// assert_eq!(moving.rebuilds(), 1);
+/// ```
+pub struct Query<E: Entity> {
+    filters: Vec<Filter<E>>,
+    /// Sorted disjoint entity index ranges to match, or `None` to match all indices.
+    ranges: Option<Vec<Range<EntityIndex>>>,
+    entities: Vec<EntityId<E>>,
+    /// Versions of the entity storage and then the filtered component storages, at the last rebuild.
+    /// Empty if the query has never been built.
+    versions: Vec<Option<u64>>,
+    rebuilds: usize,
+}
+
+/// A type-erased component filter of a [Query].
+struct Filter<E: Entity> {
+    key: RegistryKey,
+    required: bool,
+    len: fn(&Registry) -> usize,
+    contains: fn(&Registry, &EntityId<E>) -> bool,
+    collect: fn(&Registry, &mut Vec<EntityId<E>>),
+}
+
+impl<E: Entity + Any> Query<E> {
+    /// Creates a query matching all entities of type `E`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            filters: Vec::new(),
+            ranges: None,
+            entities: Vec::new(),
+            versions: Vec::new(),
+            rebuilds: 0,
+        }
+    }
+
+    /// Returns this query, restricted to entities with component `C`.
+    #[inline]
+    pub fn with<C: Component<E> + Any>(self) -> Self {
+        self.filter::<C>(true)
+    }
+
+    /// Returns this query, restricted to entities without component `C`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::MapMut;
+    /// # use muds::ecs::{query::Query, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct Frozen;
+    /// impl Component<E> for Frozen { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Frozen)>();
+    /// let a = registry.insert_archetype(E, cons!());
+    /// let b = registry.insert_archetype(E, cons!());
+    ///
+    /// let mut active = Query::<E>::new().without::<Frozen>();
+    /// assert_eq!(active.entities(&registry), [a, b]);
+    ///
+    /// registry.components_mut::<E, Frozen>().insert(a, Frozen);
+    /// assert!(active.is_stale(&registry));
+    /// assert_eq!(active.entities(&registry), [b]);
+    /// ```
+    #[inline]
+    pub fn without<C: Component<E> + Any>(self) -> Self {
+        self.filter::<C>(false)
+    }
+
+    /// Returns this query, restricted to entities with indices in given ranges. See [Query::set_ranges].
+    #[inline]
+    pub fn in_ranges(mut self, ranges: impl IntoIterator<Item = Range<EntityIndex>>) -> Self {
+        self.set_ranges(ranges);
+        self
+    }
+
+    /// Returns this query, restricted to entities in given pages of `page_size` indices,
+    /// i.e. page `p` holds the indices in `p * page_size..(p + 1) * page_size`. See [Query::set_ranges].
+    #[inline]
+    pub fn in_pages(
+        self,
+        page_size: EntityIndex,
+        pages: impl IntoIterator<Item = EntityIndex>,
+    ) -> Self {
+        self.in_ranges(pages.into_iter().map(|page| {
+            page.saturating_mul(page_size)..page.saturating_add(1).saturating_mul(page_size)
+        }))
+    }
+
+    /// Restricts the matched entities to the ones with indices in given ranges,
+    /// e.g. the pages of entities belonging to the loaded chunks of a streaming world.
+    /// The matched entities are only rebuilt if the ranges change.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::GenIndex;
+    /// # use muds::ecs::{query::Query, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// let ids: Vec<_> = (0..8).map(|i| registry.insert_archetype(E, cons!(Pos(i)))).collect();
+    ///
+    /// // Pages of 2 entities
+    /// let mut query = Query::<E>::new().with::<Pos>().in_pages(2, [1, 3]);
+    /// assert_eq!(query.entities(&registry), [ids[2], ids[3], ids[6], ids[7]]);
+    ///
+    /// query.set_ranges([0..1, 5..6]);
+    /// assert_eq!(query.entities(&registry), [ids[0], ids[5]]);
+    /// query.set_ranges([5..6, 0..1]);
+    /// assert_eq!(query.entities(&registry), [ids[0], ids[5]]);
+    /// assert_eq!(query.rebuilds(), 2);
+    ///
+    /// query.clear_ranges();
+    /// assert_eq!(query.entities(&registry).len(), 8);
+    /// ```
+    pub fn set_ranges(&mut self, ranges: impl IntoIterator<Item = Range<EntityIndex>>) {
+        let mut ranges: Vec<_> = ranges
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .collect();
+        ranges.sort_unstable_by_key(|range| range.start);
+        let mut merged: Vec<Range<EntityIndex>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        if self.ranges.as_ref() != Some(&merged) {
+            self.ranges = Some(merged);
+            self.invalidate();
+        }
+    }
+
+    /// Removes the index ranges set by [Query::set_ranges], matching entities of all indices.
+    #[inline]
+    pub fn clear_ranges(&mut self) {
+        if self.ranges.take().is_some() {
+            self.invalidate();
+        }
+    }
+
+    /// Returns the matched entities, rebuilding them first if any storage of the query changed.
+    ///
+    /// # Panics
+    /// Panics if any storage of the query is not registered, or is currently mutably borrowed.
+    pub fn entities(&mut self, registry: &Registry) -> &[EntityId<E>] {
+        if self.is_stale(registry) {
+            self.rebuild(registry);
+        }
+        &self.entities
+    }
+
+    /// Returns an iterator over the matched entities, rebuilding them first if any storage of the query changed.
+    ///
+    /// Unlike the slice from [Query::entities], the iterator asserts in debug builds that no storage of the query
+    /// is mutably borrowed via [Entities::entities_mut] or [Components::components_mut] during iteration,
+    /// as the matched entities could then be stale, e.g. yielding entities whose components have been removed.
+    ///
+    /// # Panics
+    /// Panics if any storage of the query is not registered, or is currently mutably borrowed.
+    /// In debug builds, the iterator panics on the next step after any storage of the query is modified.
+    ///
+    /// # Examples
+    /// ```rust,should_panic
+    /// # use muds::prelude::*;
+    /// # use muds::collections::MapMut;
+    /// # use muds::ecs::{query::Query, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// registry.insert_archetype(E, cons!(Pos(0)));
+    /// registry.insert_archetype(E, cons!(Pos(1)));
+    ///
+    /// let mut query = Query::<E>::new().with::<Pos>();
+    /// # if !cfg!(debug_assertions) { panic!("iteration is not checked in release builds"); }
+    /// for id in query.iter(&registry) {
+    ///     // Removing components while iterating panics in debug builds
+    ///     registry.components_mut::<E, Pos>().remove(id);
+    /// }
+    /// ```
+    pub fn iter<'a>(&'a mut self, registry: &'a Registry) -> QueryIter<'a, E> {
+        if self.is_stale(registry) {
+            self.rebuild(registry);
+        }
+        let query: &'a Self = self;
+        QueryIter {
+            iter: query.entities.iter(),
+            #[cfg(debug_assertions)]
+            query,
+            #[cfg(debug_assertions)]
+            registry,
+        }
+    }
+
+    /// Returns `true` if any storage of the query may have changed since the last rebuild.
+    pub fn is_stale(&self, registry: &Registry) -> bool {
+        self.versions.is_empty()
+            || self
+                .storage_keys()
+                .zip(&self.versions)
+                .any(|(key, version)| registry.version(&key) != *version)
+    }
+
+    /// Forces the next access to rebuild the matched entities.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.versions.clear();
+    }
+
+    /// Returns the number of times the matched entities have been rebuilt.
+    #[inline]
+    pub fn rebuilds(&self) -> usize {
+        self.rebuilds
+    }
+
+    fn filter<C: Component<E> + Any>(mut self, required: bool) -> Self {
+        self.filters.push(Filter {
+            key: RegistryKey::from_type::<(E, C)>(),
+            required,
+            len: |registry| registry.components::<E, C>().len(),
+            contains: |registry, entity| registry.components::<E, C>().contains_key(entity),
+            collect: |registry, entities| {
+                entities.extend(registry.components::<E, C>().iter().map(|(id, _)| *id))
+            },
+        });
+        self.invalidate();
+        self
+    }
+
+    /// Returns the keys of the entity storage and then the filtered component storages.
+    fn storage_keys(&self) -> impl Iterator<Item = RegistryKey> + '_ {
+        core::iter::once(RegistryKey::from_type::<E>())
+            .chain(self.filters.iter().map(|filter| filter.key))
+    }
+
+    fn rebuild(&mut self, registry: &Registry) {
+        self.entities.clear();
+
+        // Scan the smallest required storage, and check the other filters for each of its entities
+        let scanned = self
+            .filters
+            .iter()
+            .enumerate()
+            .filter(|(_, filter)| filter.required)
+            .min_by_key(|(_, filter)| (filter.len)(registry));
+        let scanned = match scanned {
+            Some((i, filter)) => {
+                (filter.collect)(registry, &mut self.entities);
+                Some(i)
+            }
+            None => {
+                self.entities
+                    .extend(registry.entities::<E>().iter().map(|(id, _)| *id));
+                None
+            }
+        };
+
+        // Components may outlive their entities, so entity existence is checked as well
+        let entities = registry.entities::<E>();
+        let filters = &self.filters;
+        let ranges = &self.ranges;
+        self.entities.retain(|id| {
+            contains_index(ranges, id.index())
+                && entities.contains_key(id)
+                && filters.iter().enumerate().all(|(i, filter)| {
+                    Some(i) == scanned || (filter.contains)(registry, id) == filter.required
+                })
+        });
+
+        self.versions = self
+            .storage_keys()
+            .map(|key| registry.version(&key))
+            .collect();
+        self.rebuilds += 1;
+    }
+}
+
+/// Returns `true` if an index is in any of given sorted disjoint ranges, or if there is no range restriction.
+fn contains_index(ranges: &Option<Vec<Range<EntityIndex>>>, index: EntityIndex) -> bool {
+    match ranges {
+        Some(ranges) => {
+            let i = ranges.partition_point(|range| range.end <= index);
+            ranges.get(i).map_or(false, |range| range.start <= index)
+        }
+        None => true,
+    }
+}
+
+impl<E: Entity + Any> Default for Query<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> fmt::Debug for Query<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("ranges", &self.ranges)
+            .field("entities", &self.entities)
+            .field("versions", &self.versions)
+            .field("rebuilds", &self.rebuilds)
+            .finish()
+    }
+}
+
+/// Iterator over the matched entities of a [Query], which asserts in debug builds that
+/// the storages of the query are not modified during iteration. See [Query::iter].
+pub struct QueryIter<'a, E: Entity> {
+    iter: slice::Iter<'a, EntityId<E>>,
+    #[cfg(debug_assertions)]
+    query: &'a Query<E>,
+    #[cfg(debug_assertions)]
+    registry: &'a Registry,
+}
+
+impl<'a, E: Entity + Any> Iterator for QueryIter<'a, E> {
+    type Item = &'a EntityId<E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        #[cfg(debug_assertions)]
+        assert!(
+            !self.query.is_stale(self.registry),
+            "storage of Query<{}> modified during iteration",
+            core::any::type_name::<E>()
+        );
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<E: Entity + Any> ExactSizeIterator for QueryIter<'_, E> {}
+
+impl<E: Entity + Any> core::iter::FusedIterator for QueryIter<'_, E> {}
+
+impl<E: Entity> fmt::Debug for QueryIter<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("QueryIter").field(&self.iter).finish()
+    }
+}
+
+impl Registry {
+    /// Mutably borrows the entity storage of type `E`, only allowing its values to be modified.
+    /// Unlike [Entities::entities_mut], this does not invalidate [Query]s.
+    #[inline]
+    pub fn entity_values_mut<E: Entity + Any>(&self) -> ValuesMut<'_, E::Storage> {
+        ValuesMut(
+            self.get_mut_untracked(&RegistryKey::from_type::<E>())
+                .expect("entity not registered"),
+        )
+    }
+
+    /// Mutably borrows the component storage of type `C`, only allowing its values to be modified.
+    /// Unlike [Components::components_mut], this does not invalidate [Query]s.
+    #[inline]
+    pub fn component_values_mut<E: Entity + Any, C: Component<E> + Any>(
+        &self,
+    ) -> ValuesMut<'_, C::Storage> {
+        ValuesMut(
+            self.get_mut_untracked(&RegistryKey::from_type::<(E, C)>())
+                .expect("component not registered"),
+        )
+    }
+}
+
+/// A mutably borrowed storage that only allows modifying existing values, but not inserting or removing them.
+/// See [Registry::component_values_mut].
+pub struct ValuesMut<'a, S: Any>(RefMut<'a, S>);
+
+impl<'a, S: MapMut + Any> ValuesMut<'a, S> {
+    /// Returns a mutable reference to the value corresponding to the `key` if exists.
+    #[inline]
+    pub fn get_mut(&mut self, key: &S::Key) -> Option<&mut S::Value> {
+        self.0.get_mut(key)
+    }
+
+    /// Creates an iterator over the keys and mutable values.
+    #[inline]
+    pub fn iter_mut<'b>(&'b mut self) -> <S as IterableMapMut<'b>>::IterMut
+    where
+        S: IterableMapMut<'b>,
+    {
+        self.0.iter_mut()
+    }
+}
+
+impl<'a, S: Any> Deref for ValuesMut<'a, S> {
+    type Target = S;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
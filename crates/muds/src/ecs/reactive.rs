@@ -0,0 +1,106 @@
+//! Reactive run criteria for systems.
+
+use super::{Registry, RegistryKey};
+use alloc::vec::Vec;
+
+/// Run criteria that lets a system run only when the storages it reads changed since its last run.
+///
+/// Changes are detected from the [Registry::version]s of the storages, which advance whenever they are mutably
+/// borrowed. A system that rarely needs to update, e.g. UI layout or AI planning, can then skip its work
+/// on frames where none of its entity or component storages were written, without visiting their elements.
+/// As mutable borrows count as changes whether or not a value is actually modified, a run may still find no change.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::MapMut;
+/// # use muds::ecs::{reactive::RunIfChanged, storage::{ArenaStorage, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Label(&'static str);
+/// impl Component<E> for Label { type Storage = VecStorage<E, Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_entity::<E>();
+/// registry.register_component::<E, Label>();
+/// let entity = registry.entities_mut::<E>().insert(E);
+/// registry.components_mut::<E, Label>().insert(entity, Label("a"));
+///
+/// let labels = [RegistryKey::from_type::<(E, Label)>()];
+/// let mut layout = RunIfChanged::new();
+/// let mut runs = 0;
+/// for _ in 0..3 {
+///     layout.run(&registry, labels, || runs += 1);
+/// }
+/// assert_eq!(runs, 1);
+///
+/// registry.components_mut::<E, Label>().get_mut(&entity).unwrap().0 = "b";
+/// layout.run(&registry, labels, || runs += 1);
+/// assert_eq!(runs, 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RunIfChanged {
+    versions: Option<Vec<Option<u64>>>,
+}
+
+impl RunIfChanged {
+    /// Creates a new [RunIfChanged] that runs on its first check.
+    #[inline]
+    pub fn new() -> Self {
+        Self { versions: None }
+    }
+
+    /// Checks whether any of the storages of given keys changed since the last check, and records their versions.
+    /// Always returns `true` on the first check, after [RunIfChanged::reset], or when the keys change.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{reactive::RunIfChanged, Registry, RegistryKey};
+    /// let mut registry = Registry::default();
+    /// let (a, b) = (RegistryKey::from_id(1), RegistryKey::from_id(2));
+    /// registry.register(a, 0u32);
+    /// registry.register(b, 0u32);
+    ///
+    /// let mut criteria = RunIfChanged::new();
+    /// assert!(criteria.check(&registry, [a, b]));
+    /// assert!(!criteria.check(&registry, [a, b]));
+    /// *registry.get_mut::<u32>(&b).unwrap() = 1;
+    /// assert!(criteria.check(&registry, [a, b]));
+    /// assert!(criteria.check(&registry, [a]));
+    /// ```
+    pub fn check(
+        &mut self,
+        registry: &Registry,
+        keys: impl IntoIterator<Item = RegistryKey>,
+    ) -> bool {
+        let versions = keys
+            .into_iter()
+            .map(|key| registry.version(&key))
+            .collect::<Vec<_>>();
+        let changed = self.versions.as_ref() != Some(&versions);
+        self.versions = Some(versions);
+        changed
+    }
+
+    /// Runs a system if any of the storages of given keys changed since the last check.
+    /// See [RunIfChanged::check]. Returns the result of the system, or `None` if it did not run.
+    #[inline]
+    pub fn run<R>(
+        &mut self,
+        registry: &Registry,
+        keys: impl IntoIterator<Item = RegistryKey>,
+        system: impl FnOnce() -> R,
+    ) -> Option<R> {
+        if self.check(registry, keys) {
+            Some(system())
+        } else {
+            None
+        }
+    }
+
+    /// Forgets the recorded versions, so that the next check always runs the system.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.versions = None;
+    }
+}
@@ -1,8 +1,10 @@
 //! Registry of resources.
 
-use alloc::boxed::Box;
+use super::{clone::ComponentCloner, storage::AnyStorage, Component, ComponentStorage, Entity};
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     any::{Any, TypeId},
+    cell::Cell,
     convert::TryFrom,
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -12,6 +14,10 @@ use core::{
 #[derive(Debug)]
 pub struct Registry {
     data: RegistryData,
+    storages: BackingMap<RegistryKey, StorageCast>,
+    versions: BackingMap<RegistryKey, Cell<u64>>,
+    cloners: BackingMap<RegistryKey, Vec<ComponentCloner>>,
+    drop_callbacks: Vec<fn(&Registry)>,
 }
 
 impl Registry {
@@ -20,6 +26,10 @@ impl Registry {
     pub fn new() -> Self {
         Self {
             data: Default::default(),
+            storages: Default::default(),
+            versions: Default::default(),
+            cloners: Default::default(),
+            drop_callbacks: Default::default(),
         }
     }
 
@@ -30,6 +40,7 @@ impl Registry {
     }
 
     /// Gets the underlying map mutably.
+    /// Mutable borrows of values through this map do not advance their versions, see [Registry::version].
     #[inline]
     pub fn data_mut(&mut self) -> &mut RegistryData {
         &mut self.data
@@ -46,9 +57,231 @@ impl Registry {
     pub fn register<R: Any>(&mut self, key: RegistryKey, value: R) {
         if !self.contains_key(&key) {
             self.data.insert(key, RefCell::new(Box::new(value)));
+            self.versions.insert(key, Cell::new(0));
         }
     }
 
+    /// Registers an entity or component storage with given key and initial value.
+    /// Unlike [Registry::register], the storage is also accessible via [Registry::storages_dyn].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Entity, Registry, RegistryKey, storage::ArenaStorage};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_storage(RegistryKey::from_id(1), ArenaStorage::<E>::default());
+    /// assert_eq!(registry.storages_dyn().count(), 1);
+    /// ```
+    #[inline]
+    pub fn register_storage<S: AnyStorage + Any>(&mut self, key: RegistryKey, value: S) {
+        self.insert_storage(key, value, None);
+    }
+
+    /// Registers a component storage with given key and initial value.
+    /// Unlike [Registry::register_storage], the storage is also listed by [Registry::component_type_names].
+    #[inline]
+    pub(super) fn register_component_storage<E: Entity, C: Component<E>>(
+        &mut self,
+        key: RegistryKey,
+        value: C::Storage,
+    ) {
+        self.insert_storage(
+            key,
+            value,
+            Some(|value| {
+                value
+                    .downcast_mut::<C::Storage>()
+                    .expect("storage type mismatch")
+                    .maintain()
+            }),
+        );
+    }
+
+    /// Returns an iterator over all registered entity and component storages as [AnyStorage].
+    ///
+    /// # Panics
+    /// Panics if any storage is currently mutably borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Component, Components, Entity, Entities, Registry, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// struct Pos(u32, u32);
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_component::<E, Pos>();
+    /// registry.components_mut::<E, Pos>().insert(registry.entities_mut::<E>().insert(E), Pos(1, 2));
+    ///
+    /// let mut names = registry
+    ///     .storages_dyn()
+    ///     .map(|(_, storage)| (storage.type_name().rsplit("::").next().unwrap(), storage.len()))
+    ///     .collect::<Vec<_>>();
+    /// names.sort();
+    /// assert_eq!(names, [("E", 1), ("Pos", 1)]);
+    /// ```
+    pub fn storages_dyn(&self) -> impl Iterator<Item = (RegistryKey, StorageRef<'_>)> {
+        self.storages.iter().filter_map(move |(key, cast)| {
+            let as_ref = cast.as_ref;
+            self.data.get(key).map(|value| {
+                (
+                    *key,
+                    core::cell::Ref::map(value.borrow(), |value| as_ref(value.deref())),
+                )
+            })
+        })
+    }
+
+    /// Returns an iterator over all registered entity and component storages as mutable [AnyStorage].
+    ///
+    /// # Panics
+    /// Panics if any storage is currently borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Component, Components, Entity, Entities, Registry, storage::{ArenaStorage, VecStorage}};
+    /// # use muds::collections::Map;
+    /// struct E;
+    /// struct Pos(u32, u32);
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_component::<E, Pos>();
+    /// let e = registry.entities_mut::<E>().insert(E);
+    /// registry.components_mut::<E, Pos>().insert(e, Pos(1, 2));
+    ///
+    /// for (_, mut storage) in registry.storages_dyn_mut() {
+    ///     assert!(storage.remove(e.to_index()));
+    /// }
+    /// assert!(registry.entities::<E>().is_empty());
+    /// assert!(registry.components::<E, Pos>().is_empty());
+    /// ```
+    pub fn storages_dyn_mut(&self) -> impl Iterator<Item = (RegistryKey, StorageRefMut<'_>)> {
+        self.storages.iter().filter_map(move |(key, cast)| {
+            let as_mut = cast.as_mut;
+            self.touch(key);
+            self.data.get(key).map(|value| {
+                (
+                    *key,
+                    core::cell::RefMut::map(value.borrow_mut(), |value| {
+                        as_mut(value.deref_mut())
+                    }),
+                )
+            })
+        })
+    }
+
+    /// Maintains all registered component storages, e.g. forgetting the removals kept by
+    /// [Tracked](super::storage::Tracked) storages once all readers have seen them.
+    /// Should be called regularly, e.g. at the end of each frame. See [ComponentStorage::maintain].
+    ///
+    /// # Panics
+    /// Panics if any component storage is currently borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::MapMut;
+    /// # use muds::ecs::storage::{ArenaStorage, Tracked, VecStorage};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// struct Pos(u32);
+    /// impl Component<E> for Pos { type Storage = Tracked<E, VecStorage<E, Self>>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Pos)>();
+    /// let a = registry.insert_archetype(E, cons!(Pos(0)));
+    /// registry.components_mut::<E, Pos>().remove(&a);
+    ///
+    /// // Removals are kept until the second maintenance after them
+    /// registry.maintain();
+    /// assert_eq!(registry.components::<E, Pos>().removed(0).count(), 1);
+    /// registry.maintain();
+    /// assert_eq!(registry.components::<E, Pos>().removed(0).count(), 0);
+    /// ```
+    pub fn maintain(&self) {
+        for (key, cast) in self.storages.iter() {
+            if let (Some(maintain), Some(value)) = (cast.maintain, self.data.get(key)) {
+                maintain(value.borrow_mut().deref_mut().deref_mut());
+            }
+        }
+    }
+
+    /// Returns the statistics of all registered entity and component storages, e.g. for debug overlays,
+    /// or to find storages that keep growing.
+    ///
+    /// # Panics
+    /// Panics if any storage is currently mutably borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Component, Components, Entity, Entities, Registry, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// struct Pos(u32, u32);
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_component::<E, Pos>();
+    /// registry.components_mut::<E, Pos>().insert(registry.entities_mut::<E>().insert(E), Pos(1, 2));
+    ///
+    /// let stats = registry.stats().find(|stats| stats.is_component).unwrap();
+    /// assert!(stats.type_name.ends_with("Pos"));
+    /// assert_eq!(stats.len, 1);
+    /// assert!(stats.capacity >= 1);
+    /// assert_eq!(stats.memory, stats.capacity * 8);
+    /// ```
+    pub fn stats(&self) -> impl Iterator<Item = StorageStats> + '_ {
+        self.storages_dyn().map(move |(key, storage)| {
+            let capacity = storage.capacity();
+            StorageStats {
+                key,
+                type_name: storage.type_name(),
+                is_component: self
+                    .storages
+                    .get(&key)
+                    .map_or(false, |cast| cast.maintain.is_some()),
+                len: storage.len(),
+                capacity,
+                memory: capacity * storage.element_size(),
+            }
+        })
+    }
+
+    /// Returns an iterator over the type names of all registered components.
+    /// Unlike [Registry::stats], this does not borrow the storages.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Component, Components, Entity, Entities, Registry, storage::{ArenaStorage, VecStorage}};
+    /// struct E;
+    /// struct Pos(u32, u32);
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// registry.register_component::<E, Pos>();
+    /// let _entities = registry.entities_mut::<E>();
+    ///
+    /// let names = registry.component_type_names().collect::<Vec<_>>();
+    /// assert_eq!(names.len(), 1);
+    /// assert!(names[0].ends_with("Pos"));
+    /// ```
+    pub fn component_type_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.storages
+            .values()
+            .filter(|cast| cast.maintain.is_some())
+            .map(|cast| cast.type_name)
+    }
+
     /// Returns `true` if the registry contains given [RegistryKey].
     ///
     /// # Examples
@@ -79,7 +312,7 @@ impl Registry {
         self.data.get(key).map(RefCell::borrow).try_into().ok()
     }
 
-    /// Gets a resource mutably.
+    /// Gets a resource mutably. This advances its version.
     ///
     /// # Examples
     /// ```rust
@@ -92,8 +325,116 @@ impl Registry {
     /// ```
     #[inline]
     pub fn get_mut<'a, R: Any>(&'a self, key: &RegistryKey) -> Option<RefMut<'a, R>> {
+        let value = self.get_mut_untracked(key);
+        if value.is_some() {
+            self.touch(key);
+        }
+        value
+    }
+
+    /// Returns the version of a resource, which starts at 0 and advances every time the resource is mutably borrowed.
+    /// Returns `None` if the resource is not registered.
+    ///
+    /// Versions allow caches derived from a resource, e.g. a [crate::ecs::query::Query] over entity and component
+    /// storages, to detect that the resource may have changed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, RegistryKey};
+    /// let mut registry = Registry::default();
+    /// let key = RegistryKey::from_type::<u32>();
+    /// registry.register(key, 1u32);
+    /// assert_eq!(registry.version(&key), Some(0));
+    /// let _ = registry.get::<u32>(&key);
+    /// assert_eq!(registry.version(&key), Some(0));
+    /// *registry.get_mut::<u32>(&key).unwrap() = 2;
+    /// assert_eq!(registry.version(&key), Some(1));
+    /// ```
+    #[inline]
+    pub fn version(&self, key: &RegistryKey) -> Option<u64> {
+        self.versions.get(key).map(Cell::get)
+    }
+
+    /// Gets a resource mutably, advancing its version only when it is mutated through the returned [ResMut].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, RegistryKey};
+    /// let mut registry = Registry::default();
+    /// let key = RegistryKey::from_type::<u32>();
+    /// registry.register(key, 1u32);
+    /// assert_eq!(*registry.get_tracked_mut::<u32>(&key).unwrap(), 1);
+    /// assert_eq!(registry.version(&key), Some(0));
+    /// *registry.get_tracked_mut::<u32>(&key).unwrap() = 2;
+    /// assert_eq!(registry.version(&key), Some(1));
+    /// ```
+    #[inline]
+    pub fn get_tracked_mut<'a, R: Any>(&'a self, key: &RegistryKey) -> Option<ResMut<'a, R>> {
+        let value = self.get_mut_untracked(key)?;
+        Some(ResMut {
+            value,
+            version: self.versions.get(key)?,
+            changed: false,
+        })
+    }
+
+    /// Gets a resource mutably without advancing its version.
+    /// Callers must only expose access that cannot invalidate caches depending on the version.
+    #[inline]
+    pub(crate) fn get_mut_untracked<'a, R: Any>(
+        &'a self,
+        key: &RegistryKey,
+    ) -> Option<RefMut<'a, R>> {
         self.data.get(key).map(RefCell::borrow_mut).try_into().ok()
     }
+
+    /// Gets the component cloners registered for an entity type key.
+    #[inline]
+    pub(super) fn cloners(&self, entity: &RegistryKey) -> &[ComponentCloner] {
+        self.cloners.get(entity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gets the component cloners registered for an entity type key mutably.
+    #[inline]
+    pub(super) fn cloners_mut(&mut self, entity: RegistryKey) -> &mut Vec<ComponentCloner> {
+        self.cloners.entry(entity).or_default()
+    }
+
+    /// Gets the callbacks to invoke when the registry is dropped mutably.
+    #[inline]
+    pub(super) fn drop_callbacks_mut(&mut self) -> &mut Vec<fn(&Registry)> {
+        &mut self.drop_callbacks
+    }
+
+    /// Registers a storage, recording whether it stores components.
+    fn insert_storage<S: AnyStorage + Any>(
+        &mut self,
+        key: RegistryKey,
+        value: S,
+        maintain: Option<fn(&mut dyn Any)>,
+    ) {
+        if !self.contains_key(&key) {
+            let type_name = value.type_name();
+            self.register(key, value);
+            self.storages.insert(
+                key,
+                StorageCast {
+                    as_ref: |value| value.downcast_ref::<S>().expect("storage type mismatch"),
+                    as_mut: |value| value.downcast_mut::<S>().expect("storage type mismatch"),
+                    type_name,
+                    maintain,
+                },
+            );
+        }
+    }
+
+    /// Advances the version of a resource.
+    #[inline]
+    fn touch(&self, key: &RegistryKey) {
+        if let Some(version) = self.versions.get(key) {
+            version.set(version.get().wrapping_add(1));
+        }
+    }
 }
 
 impl Default for Registry {
@@ -103,9 +444,52 @@ impl Default for Registry {
     }
 }
 
+impl Drop for Registry {
+    fn drop(&mut self) {
+        for callback in core::mem::take(&mut self.drop_callbacks) {
+            callback(self);
+        }
+    }
+}
+
 /// Registry data map type.
 pub type RegistryData = BackingMap<RegistryKey, RefCell<Box<dyn Any>>>;
 
+/// A borrowed type-erased storage in a [Registry].
+pub type StorageRef<'a> = core::cell::Ref<'a, dyn AnyStorage>;
+
+/// A mutably borrowed type-erased storage in a [Registry].
+pub type StorageRefMut<'a> = core::cell::RefMut<'a, dyn AnyStorage>;
+
+/// Statistics of an entity or component storage in a [Registry]. See [Registry::stats].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Key of the storage.
+    pub key: RegistryKey,
+    /// Type name of the stored entity or component.
+    pub type_name: &'static str,
+    /// Whether the storage is registered as a component storage.
+    pub is_component: bool,
+    /// Number of stored elements.
+    pub len: usize,
+    /// Number of elements the storage can hold without reallocating.
+    pub capacity: usize,
+    /// Estimated memory of the stored elements in bytes, i.e. capacity times element size.
+    /// This excludes indices and heap memory owned by the elements.
+    pub memory: usize,
+}
+
+/// Casts a type-erased registry value into [AnyStorage].
+#[derive(Clone, Copy, Debug)]
+struct StorageCast {
+    as_ref: fn(&dyn Any) -> &(dyn AnyStorage + 'static),
+    as_mut: fn(&mut dyn Any) -> &mut (dyn AnyStorage + 'static),
+    /// Type name of the stored entity or component.
+    type_name: &'static str,
+    /// Maintains the storage, or `None` if the storage is not a component storage.
+    maintain: Option<fn(&mut dyn Any)>,
+}
+
 // TODO: Add a feature to enable the use of RwLock for multithreaded applications. 
 /// Ref cell type.
 type RefCell<T> = core::cell::RefCell<T>;
@@ -223,3 +607,40 @@ impl<T: Any> DerefMut for RefMut<'_, T> {
             .expect("resource not registered")
     }
 }
+
+/// Wraps a mutably borrowed resource in a [Registry], which advances the resource version
+/// on its first mutable dereference instead of when borrowed.
+/// Systems can then compare versions to skip work when the resource is not modified.
+pub struct ResMut<'a, T> {
+    value: RefMut<'a, T>,
+    version: &'a Cell<u64>,
+    changed: bool,
+}
+
+impl<T> ResMut<'_, T> {
+    /// Returns `true` if the resource has been mutably dereferenced through this wrapper.
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+}
+
+impl<T: Any> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.value.deref()
+    }
+}
+
+impl<T: Any> DerefMut for ResMut<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if !self.changed {
+            self.changed = true;
+            self.version.set(self.version.get().wrapping_add(1));
+        }
+        self.value.deref_mut()
+    }
+}
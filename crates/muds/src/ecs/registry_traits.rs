@@ -1,6 +1,6 @@
 use super::{
-    registry::{Ref, RefMut},
-    Component, Components, Entities, Entity, Registry, RegistryKey, Resources,
+    registry::{Ref, RefMut, ResMut},
+    Component, Components, Entities, Entity, Registry, RegistryKey, Resources, ResourcesExt,
 };
 use core::any::Any;
 
@@ -28,10 +28,29 @@ impl Resources for Registry {
     }
 }
 
+impl ResourcesExt for Registry {
+    #[inline]
+    fn tracked_resource_mut<'a, R: Any>(&'a self) -> ResMut<'a, R> {
+        self.get_tracked_mut::<R>(&RegistryKey::from_type::<R>())
+            .expect("resource not registered")
+    }
+
+    #[inline]
+    fn resource_version<R: Any>(&self) -> u64 {
+        self.version(&RegistryKey::from_type::<R>())
+            .expect("resource not registered")
+    }
+
+    #[inline]
+    fn register_drop_callback(&mut self, callback: fn(&Self)) {
+        self.drop_callbacks_mut().push(callback);
+    }
+}
+
 impl Entities for Registry {
     #[inline]
     fn register_entity<E: Entity + Any>(&mut self) {
-        self.register(RegistryKey::from_type::<E>(), E::Storage::default());
+        self.register_storage(RegistryKey::from_type::<E>(), E::Storage::default());
     }
 
     #[inline]
@@ -55,7 +74,10 @@ impl Entities for Registry {
 impl Components for Registry {
     #[inline]
     fn register_component<E: Entity + Any, C: Component<E> + Any>(&mut self) {
-        self.register(RegistryKey::from_type::<(E, C)>(), C::Storage::default());
+        self.register_component_storage::<E, C>(
+            RegistryKey::from_type::<(E, C)>(),
+            C::Storage::default(),
+        );
     }
 
     #[inline]
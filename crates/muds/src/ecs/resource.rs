@@ -1,6 +1,7 @@
 //! Resource registry types.
 
-use super::registry::{Ref, RefMut};
+use super::events::Events;
+use super::registry::{Ref, RefMut, ResMut};
 use core::any::Any;
 
 /// Registry of resources.
@@ -49,3 +50,114 @@ pub trait Resources {
     /// ```
     fn resource_mut<'a, R: Any>(&'a self) -> RefMut<'a, R>;
 }
+
+/// Extension of [Resources] for resource change detection, teardown callbacks and event channels.
+pub trait ResourcesExt: Resources {
+    /// Gets a resource mutably for change detection.
+    /// Unlike [Resources::resource_mut], its version only advances when it is mutated through the returned [ResMut].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, Resources, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(1u32);
+    /// let version = registry.resource_version::<u32>();
+    ///
+    /// let value = registry.tracked_resource_mut::<u32>();
+    /// assert_eq!(*value, 1);
+    /// drop(value);
+    /// assert!(!registry.resource_changed::<u32>(version));
+    ///
+    /// *registry.tracked_resource_mut::<u32>() = 2;
+    /// assert!(registry.resource_changed::<u32>(version));
+    /// ```
+    fn tracked_resource_mut<'a, R: Any>(&'a self) -> ResMut<'a, R>;
+
+    /// Gets the version of a resource, which advances whenever the resource is mutated.
+    /// A system can record the version after each run to detect changes on its next run.
+    /// See [ResourcesExt::resource_changed].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, Resources, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(1u32);
+    /// assert_eq!(registry.resource_version::<u32>(), 0);
+    /// *registry.resource_mut::<u32>() = 2;
+    /// assert_eq!(registry.resource_version::<u32>(), 1);
+    /// ```
+    fn resource_version<R: Any>(&self) -> u64;
+
+    /// Returns `true` if a resource has changed since the given version from [ResourcesExt::resource_version].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, Resources, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(1u32);
+    /// let version = registry.resource_version::<u32>();
+    /// assert!(!registry.resource_changed::<u32>(version));
+    /// *registry.tracked_resource_mut::<u32>() += 1;
+    /// assert!(registry.resource_changed::<u32>(version));
+    /// ```
+    #[inline]
+    fn resource_changed<R: Any>(&self, since: u64) -> bool {
+        self.resource_version::<R>() != since
+    }
+
+    /// Registers a callback to be invoked with the registry when it is dropped,
+    /// e.g. to release external resources held by its values.
+    /// Callbacks are invoked in registration order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, Resources, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_resource(1u32);
+    /// registry.register_drop_callback(|registry| assert_eq!(*registry.resource::<u32>(), 1));
+    /// ```
+    fn register_drop_callback(&mut self, callback: fn(&Self));
+
+    /// Registers an [Events] channel resource for events of type `T`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{events::Events, Registry, Resources, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_events::<u32>();
+    /// assert!(registry.has_resource::<Events<u32>>());
+    /// ```
+    #[inline]
+    fn register_events<T: Any>(&mut self) {
+        self.register_resource(Events::<T>::new());
+    }
+
+    /// Gets the [Events] channel of type `T`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_events::<u32>();
+    /// assert!(registry.events::<u32>().is_empty());
+    /// ```
+    #[inline]
+    fn events<'a, T: Any>(&'a self) -> Ref<'a, Events<T>> {
+        self.resource::<Events<T>>()
+    }
+
+    /// Gets the [Events] channel of type `T` mutably.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::{Registry, ResourcesExt};
+    /// let mut registry = Registry::default();
+    /// registry.register_events::<u32>();
+    /// registry.events_mut::<u32>().send(1);
+    /// assert_eq!(registry.events::<u32>().len(), 1);
+    /// ```
+    #[inline]
+    fn events_mut<'a, T: Any>(&'a self) -> RefMut<'a, Events<T>> {
+        self.resource_mut::<Events<T>>()
+    }
+}
@@ -0,0 +1,200 @@
+//! Deterministic random number generation.
+
+use crate::hash::{fnv1a, splitmix64, FNV_OFFSET_BASIS};
+use core::ops::Range;
+
+/// A small, fast and deterministic PCG random number generator (PCG-XSH-RR with 64-bit state).
+/// Generators with the same seed and stream produce the same sequence on every platform.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::rng::Pcg32;
+/// let mut rng = Pcg32::new(42, 0);
+/// let mut other = Pcg32::new(42, 0);
+/// assert_eq!(rng.next_u32(), other.next_u32());
+/// assert_ne!(Pcg32::new(42, 1).next_u32(), Pcg32::new(42, 2).next_u32());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+impl Pcg32 {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Creates a new generator from a seed and a stream ID.
+    /// Different streams of the same seed produce independent sequences.
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Generates a random `u32`.
+    pub fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        xorshifted.rotate_right((state >> 59) as u32)
+    }
+
+    /// Generates a random `u64`.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Generates a random `f32` in range `[0, 1)`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::rng::Pcg32;
+    /// let mut rng = Pcg32::new(1, 0);
+    /// for _ in 0..100 {
+    ///     let value = rng.next_f32();
+    ///     assert!(value >= 0. && value < 1.);
+    /// }
+    /// ```
+    #[inline]
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1. / (1u32 << 24) as f32)
+    }
+
+    /// Generates a random `bool`.
+    #[inline]
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u32() >> 31 == 1
+    }
+
+    /// Generates a uniformly distributed random `u32` in given range.
+    ///
+    /// # Panics
+    /// Panics if the range is empty.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::rng::Pcg32;
+    /// let mut rng = Pcg32::new(1, 0);
+    /// for _ in 0..100 {
+    ///     assert!((10..20).contains(&rng.range_u32(10..20)));
+    /// }
+    /// ```
+    pub fn range_u32(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "empty range");
+        let span = range.end - range.start;
+        // Reject values below 2^32 % span to avoid modulo bias
+        let threshold = span.wrapping_neg() % span;
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return range.start + value % span;
+            }
+        }
+    }
+
+    /// Generates a random `f32` in given range.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::rng::Pcg32;
+    /// let mut rng = Pcg32::new(1, 0);
+    /// for _ in 0..100 {
+    ///     let value = rng.range_f32(-1.0..1.0);
+    ///     assert!(value >= -1. && value < 1.);
+    /// }
+    /// ```
+    #[inline]
+    pub fn range_f32(&mut self, range: Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.next_f32()
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+/// World random number generator resource.
+///
+/// Instead of sharing a single sequence, each system derives its own stream from the world seed,
+/// the current frame and a stream ID. The numbers generated by a system are therefore reproducible
+/// given the seed and frame, regardless of which other systems ran or in which order,
+/// which keeps gameplay randomness deterministic for replays and tests.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::{rng::RngResource, Registry, Resources};
+/// let mut registry = Registry::default();
+/// registry.register_resource(RngResource::new(1234));
+///
+/// let first = registry.resource::<RngResource>().stream_named("spawner").next_u32();
+/// assert_eq!(registry.resource::<RngResource>().stream_named("spawner").next_u32(), first);
+///
+/// registry.resource_mut::<RngResource>().next_frame();
+/// assert_ne!(registry.resource::<RngResource>().stream_named("spawner").next_u32(), first);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RngResource {
+    seed: u64,
+    frame: u64,
+}
+
+impl RngResource {
+    /// Creates a new [RngResource] with given world seed, starting at frame 0.
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self { seed, frame: 0 }
+    }
+
+    /// Returns the world seed.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the current frame.
+    #[inline]
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Sets the current frame, e.g. to resume a replay.
+    #[inline]
+    pub fn set_frame(&mut self, frame: u64) {
+        self.frame = frame;
+    }
+
+    /// Advances to the next frame. This should be called once per frame, so that streams produce new sequences.
+    #[inline]
+    pub fn next_frame(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+    }
+
+    /// Returns the generator of a stream for the current frame.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::rng::RngResource;
+    /// let rng = RngResource::new(1);
+    /// assert_eq!(rng.stream(0).next_u32(), rng.stream(0).next_u32());
+    /// assert_ne!(rng.stream(0).next_u32(), rng.stream(1).next_u32());
+    /// ```
+    #[inline]
+    pub fn stream(&self, stream: u64) -> Pcg32 {
+        Pcg32::new(splitmix64(self.seed ^ splitmix64(self.frame)), stream)
+    }
+
+    /// Returns the generator of a stream identified by name, e.g. the name of a system, for the current frame.
+    pub fn stream_named(&self, name: &str) -> Pcg32 {
+        self.stream(fnv1a(FNV_OFFSET_BASIS, name.as_bytes()))
+    }
+}
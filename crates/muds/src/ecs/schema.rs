@@ -0,0 +1,279 @@
+//! Versioned schema descriptors and migrations of serialized components.
+
+use crate::hash::{fnv1a, FNV_OFFSET_BASIS};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+
+/// A type with a versioned serialization schema, usually a component.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::schema::{field_hash, Versioned};
+/// struct Position { x: f32, y: f32 }
+/// impl Versioned for Position {
+///     const NAME: &'static str = "Position";
+///     const VERSION: u32 = 1;
+///     const FIELD_HASH: u64 = field_hash(&["x: f32", "y: f32"]);
+/// }
+/// ```
+pub trait Versioned {
+    /// Stable name of the type, which should stay the same across versions.
+    const NAME: &'static str;
+
+    /// Schema version, which should be incremented whenever the serialized layout changes.
+    const VERSION: u32;
+
+    /// Hash of the serialized fields, e.g. computed by [field_hash].
+    /// It is used to detect layout changes without a version increment. Zero means unknown.
+    const FIELD_HASH: u64 = 0;
+}
+
+/// Computes a stable hash of a list of field descriptions using 64-bit FNV-1a.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::schema::field_hash;
+/// assert_eq!(field_hash(&["x: f32", "y: f32"]), field_hash(&["x: f32", "y: f32"]));
+/// assert_ne!(field_hash(&["x: f32", "y: f32"]), field_hash(&["y: f32", "x: f32"]));
+/// assert_ne!(field_hash(&["x: f32"]), field_hash(&["x: f64"]));
+/// ```
+pub const fn field_hash(fields: &[&str]) -> u64 {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut i = 0;
+    while i < fields.len() {
+        hash = fnv1a(hash, fields[i].as_bytes());
+        // Separate fields with a byte that cannot appear in UTF-8 strings
+        hash = fnv1a(hash, &[0xff]);
+        i += 1;
+    }
+    hash
+}
+
+/// Schema descriptor of a serialized type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComponentSchema {
+    /// Name of the type.
+    pub name: String,
+    /// Schema version.
+    pub version: u32,
+    /// Hash of the serialized fields.
+    pub field_hash: u64,
+}
+
+impl ComponentSchema {
+    /// Returns the schema of a [Versioned] type.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::schema::{ComponentSchema, Versioned};
+    /// struct Health(u32);
+    /// impl Versioned for Health {
+    ///     const NAME: &'static str = "Health";
+    ///     const VERSION: u32 = 2;
+    /// }
+    ///
+    /// let schema = ComponentSchema::of::<Health>();
+    /// assert_eq!(schema.name, "Health");
+    /// assert_eq!(schema.version, 2);
+    /// ```
+    pub fn of<T: Versioned>() -> Self {
+        Self {
+            name: T::NAME.into(),
+            version: T::VERSION,
+            field_hash: T::FIELD_HASH,
+        }
+    }
+}
+
+/// Schema of a set of serialized types, to be embedded into saved data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Schema {
+    /// Schema of each type.
+    pub components: Vec<ComponentSchema>,
+}
+
+impl Schema {
+    /// Returns the schema of a type by name.
+    pub fn get(&self, name: &str) -> Option<&ComponentSchema> {
+        self.components.iter().find(|schema| schema.name == name)
+    }
+}
+
+/// Error of a schema migration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The type is not registered.
+    Unknown(String),
+    /// The saved data is of a newer version than the registered type.
+    NewerVersion(String, u32),
+    /// There is no migration from the given version of the type.
+    MissingMigration(String, u32),
+    /// The fields of the type are changed without a version increment.
+    FieldMismatch(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "unknown type: {}", name),
+            Self::NewerVersion(name, version) => {
+                write!(f, "unsupported newer version {} of type: {}", version, name)
+            }
+            Self::MissingMigration(name, version) => {
+                write!(
+                    f,
+                    "missing migration from version {} of type: {}",
+                    version, name
+                )
+            }
+            Self::FieldMismatch(name) => write!(f, "fields changed without new version: {}", name),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaError {}
+
+/// Registry of the current schema of serialized types, and the migrations of their older versions.
+/// The migrations operate on data of type `D`, e.g. a deserialized JSON value,
+/// and are invoked on load when the saved version differs from the current version.
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::schema::{ComponentSchema, Migrations, Versioned};
+/// struct Health(u32);
+/// impl Versioned for Health {
+///     const NAME: &'static str = "Health";
+///     const VERSION: u32 = 2;
+/// }
+///
+/// let mut migrations = Migrations::<u32>::new();
+/// migrations.register::<Health>();
+/// // Version 1 stored health in [0, 1], version 2 stores health in [0, 100]
+/// migrations.register_migration::<Health>(1, |health| *health *= 100);
+///
+/// let saved = ComponentSchema { name: "Health".into(), version: 1, field_hash: 0 };
+/// let mut data = 1;
+/// migrations.migrate(&saved, &mut data).unwrap();
+/// assert_eq!(data, 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Migrations<D> {
+    schema: Schema,
+    migrations: BTreeMap<(String, u32), fn(&mut D)>,
+}
+
+impl<D> Migrations<D> {
+    /// Creates a new, empty [Migrations] registry.
+    pub fn new() -> Self {
+        Self {
+            schema: Schema::default(),
+            migrations: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the current schema of all registered types.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::schema::{ComponentSchema, Migrations, Versioned};
+    /// struct Health(u32);
+    /// impl Versioned for Health {
+    ///     const NAME: &'static str = "Health";
+    ///     const VERSION: u32 = 1;
+    /// }
+    ///
+    /// let mut migrations = Migrations::<()>::new();
+    /// migrations.register::<Health>();
+    /// assert_eq!(migrations.schema().components, [ComponentSchema::of::<Health>()]);
+    /// ```
+    #[inline]
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Registers the current schema of a type, replacing any existing schema of the same name.
+    pub fn register<T: Versioned>(&mut self) {
+        let schema = ComponentSchema::of::<T>();
+        match self
+            .schema
+            .components
+            .iter_mut()
+            .find(|s| s.name == schema.name)
+        {
+            Some(existing) => *existing = schema,
+            None => self.schema.components.push(schema),
+        }
+    }
+
+    /// Registers a migration of a type that upgrades data from version `from_version` to `from_version + 1`.
+    pub fn register_migration<T: Versioned>(&mut self, from_version: u32, migration: fn(&mut D)) {
+        self.migrations
+            .insert((T::NAME.into(), from_version), migration);
+    }
+
+    /// Migrates saved data of given schema to the current version, by invoking the registered migrations in order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::ecs::schema::{ComponentSchema, Migrations, SchemaError, Versioned};
+    /// struct Health(u32);
+    /// impl Versioned for Health {
+    ///     const NAME: &'static str = "Health";
+    ///     const VERSION: u32 = 3;
+    ///     const FIELD_HASH: u64 = 1;
+    /// }
+    ///
+    /// let mut migrations = Migrations::<u32>::new();
+    /// migrations.register::<Health>();
+    /// migrations.register_migration::<Health>(2, |_| {});
+    ///
+    /// let mut saved = ComponentSchema { name: "Health".into(), version: 1, field_hash: 1 };
+    /// assert_eq!(migrations.migrate(&saved, &mut 0), Err(SchemaError::MissingMigration("Health".into(), 1)));
+    ///
+    /// saved.version = 3;
+    /// saved.field_hash = 2;
+    /// assert_eq!(migrations.migrate(&saved, &mut 0), Err(SchemaError::FieldMismatch("Health".into())));
+    /// ```
+    pub fn migrate(&self, saved: &ComponentSchema, data: &mut D) -> Result<(), SchemaError> {
+        let current = self
+            .schema
+            .get(&saved.name)
+            .ok_or_else(|| SchemaError::Unknown(saved.name.clone()))?;
+
+        if saved.version > current.version {
+            return Err(SchemaError::NewerVersion(saved.name.clone(), saved.version));
+        }
+        if saved.version == current.version {
+            if saved.field_hash != 0
+                && current.field_hash != 0
+                && saved.field_hash != current.field_hash
+            {
+                return Err(SchemaError::FieldMismatch(saved.name.clone()));
+            }
+            return Ok(());
+        }
+
+        // Check that the full migration path exists before modifying the data
+        let mut path = Vec::with_capacity((current.version - saved.version) as usize);
+        for version in saved.version..current.version {
+            match self.migrations.get(&(saved.name.clone(), version)) {
+                Some(migration) => path.push(migration),
+                None => return Err(SchemaError::MissingMigration(saved.name.clone(), version)),
+            }
+        }
+        for migration in path {
+            migration(data);
+        }
+        Ok(())
+    }
+}
+
+impl<D> Default for Migrations<D> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
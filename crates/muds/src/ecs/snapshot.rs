@@ -0,0 +1,353 @@
+//! Snapshots of the entity and component storages of a registry, e.g. for save games.
+//!
+//! A [Snapshot] maps the names of storages registered in [SnapshotTypes] to their serialized data,
+//! in a self-describing data type `D` of a [SnapshotFormat], e.g. a JSON value.
+//! The snapshot itself is serializable, so it can be written as a single document.
+//! It also records the [Schema] of the versioned storages, so that data saved by older versions
+//! is upgraded by the registered [Migrations] on load.
+//!
+//! # Examples
+//! ```rust
+//! # use muds::prelude::*;
+//! # use muds::collections::Map;
+//! # use muds::ecs::{snapshot::{SnapshotFormat, SnapshotTypes}, storage::{ArenaStorage, VecStorage}};
+//! # use serde::{de::DeserializeOwned, Deserialize, Serialize};
+//! struct Json;
+//! impl SnapshotFormat for Json {
+//!     type Data = serde_json::Value;
+//!     type Error = serde_json::Error;
+//!
+//!     fn to_data<T: Serialize>(value: &T) -> Result<Self::Data, Self::Error> {
+//!         serde_json::to_value(value)
+//!     }
+//!
+//!     fn from_data<T: DeserializeOwned>(data: Self::Data) -> Result<T, Self::Error> {
+//!         serde_json::from_value(data)
+//!     }
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct E(u32);
+//! impl Entity for E { type Storage = ArenaStorage<Self>; }
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Pos(u32, u32);
+//! impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+//!
+//! let mut types = SnapshotTypes::<Json>::new();
+//! types.register_entity::<E>("E");
+//! types.register_component::<E, Pos>("Pos");
+//!
+//! let mut registry = Registry::default();
+//! registry.register_archetype::<E, Cons!(Pos)>();
+//! let e = registry.insert_archetype(E(0), cons!(Pos(1, 2)));
+//!
+//! // Save the registry into a single document
+//! let document = serde_json::to_string(&registry.serialize(&types).unwrap()).unwrap();
+//!
+//! // Load the document into another registry
+//! let mut loaded = Registry::default();
+//! loaded.register_archetype::<E, Cons!(Pos)>();
+//! loaded.deserialize(&types, serde_json::from_str(&document).unwrap()).unwrap();
+//! assert!(loaded.entities::<E>().contains_key(&e));
+//! assert_eq!(loaded.components::<E, Pos>().get(&e), Some(&Pos(1, 2)));
+//! ```
+
+use super::{
+    schema::{Migrations, Schema, SchemaError, Versioned},
+    Component, Entity, Registry, RegistryKey,
+};
+use alloc::{collections::BTreeMap, string::String};
+use core::{any::Any, fmt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// A self-describing data format of snapshots, e.g. a JSON value.
+pub trait SnapshotFormat {
+    /// Serialized data type.
+    type Data;
+
+    /// Serialization error type.
+    type Error;
+
+    /// Serializes a value into data.
+    fn to_data<T: Serialize>(value: &T) -> Result<Self::Data, Self::Error>;
+
+    /// Deserializes a value from data.
+    fn from_data<T: DeserializeOwned>(data: Self::Data) -> Result<T, Self::Error>;
+}
+
+/// Serialized entity and component storages of a registry, keyed by their names in [SnapshotTypes].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<D> {
+    /// Schema of the versioned storages at the time of the snapshot.
+    #[serde(default)]
+    pub schema: Schema,
+    /// Serialized data of each storage.
+    pub storages: BTreeMap<String, D>,
+}
+
+/// Error of a snapshot operation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotError<E> {
+    /// The snapshot contains a storage name that is not registered in [SnapshotTypes].
+    Unknown(String),
+    /// The storage of a registered name is not registered in the registry.
+    NotRegistered(String),
+    /// The storage of given name failed to serialize or deserialize.
+    Format(String, E),
+    /// The saved data of the storage of given name cannot be migrated to its current schema.
+    Schema(String, SchemaError),
+}
+
+impl<E: fmt::Display> fmt::Display for SnapshotError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "unknown storage: {}", name),
+            Self::NotRegistered(name) => write!(f, "storage not registered: {}", name),
+            Self::Format(name, error) => write!(f, "invalid storage {}: {}", name, error),
+            Self::Schema(name, error) => write!(f, "incompatible storage {}: {}", name, error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for SnapshotError<E> {}
+
+/// Registry of the names of entity and component storages to include in snapshots,
+/// with their type-erased serializers and deserializers.
+///
+/// The schema of a storage is the [Versioned] schema registered in [SnapshotTypes::migrations] under the same name.
+/// Migrations of a storage operate on its whole serialized data.
+pub struct SnapshotTypes<F: SnapshotFormat> {
+    types: BTreeMap<String, SnapshotType<F>>,
+    migrations: Migrations<F::Data>,
+}
+
+/// Type-erased serializer and deserializer of a storage.
+struct SnapshotType<F: SnapshotFormat> {
+    key: RegistryKey,
+    save: SaveFn<F>,
+    load: LoadFn<F>,
+}
+
+/// Serializes a storage, or returns `None` if it is not registered.
+type SaveFn<F> = fn(
+    &Registry,
+    &RegistryKey,
+) -> Option<Result<<F as SnapshotFormat>::Data, <F as SnapshotFormat>::Error>>;
+
+/// Deserializes and replaces a storage, or returns `None` if it is not registered.
+type LoadFn<F> = fn(
+    &Registry,
+    &RegistryKey,
+    <F as SnapshotFormat>::Data,
+) -> Option<Result<(), <F as SnapshotFormat>::Error>>;
+
+impl<F: SnapshotFormat> SnapshotTypes<F> {
+    /// Creates a new, empty [SnapshotTypes] registry.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            types: BTreeMap::new(),
+            migrations: Migrations::new(),
+        }
+    }
+
+    /// Registers the storage of an entity type under given name, replacing any existing type of the same name.
+    #[inline]
+    pub fn register_entity<E: Entity + Any>(&mut self, name: &str)
+    where
+        E::Storage: Serialize + DeserializeOwned,
+    {
+        self.register::<E::Storage>(name, RegistryKey::from_type::<E>());
+    }
+
+    /// Registers the storage of a component type under given name, replacing any existing type of the same name.
+    #[inline]
+    pub fn register_component<E: Entity + Any, C: Component<E> + Any>(&mut self, name: &str)
+    where
+        C::Storage: Serialize + DeserializeOwned,
+    {
+        self.register::<C::Storage>(name, RegistryKey::from_type::<(E, C)>());
+    }
+
+    /// Registers the storage of a versioned component type under its [Versioned::NAME], with its current schema.
+    /// Migrations from older versions are registered with [SnapshotTypes::migrations_mut].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::Map;
+    /// # use muds::ecs::{schema::Versioned, snapshot::{SnapshotFormat, SnapshotTypes}, storage::{ArenaStorage, VecStorage}};
+    /// # use serde::{de::DeserializeOwned, Deserialize, Serialize};
+    /// # struct Json;
+    /// # impl SnapshotFormat for Json {
+    /// #     type Data = serde_json::Value;
+    /// #     type Error = serde_json::Error;
+    /// #     fn to_data<T: Serialize>(value: &T) -> Result<Self::Data, Self::Error> { serde_json::to_value(value) }
+    /// #     fn from_data<T: DeserializeOwned>(data: Self::Data) -> Result<T, Self::Error> { serde_json::from_value(data) }
+    /// # }
+    /// #[derive(Serialize, Deserialize)]
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct Health(u32);
+    /// impl Component<E> for Health { type Storage = VecStorage<E, Self>; }
+    /// impl Versioned for Health {
+    ///     const NAME: &'static str = "Health";
+    ///     const VERSION: u32 = 2;
+    /// }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_archetype::<E, Cons!(Health)>();
+    /// let e = registry.insert_archetype(E, cons!(Health(1)));
+    ///
+    /// // Save data in version 1, which stored health in [0, 1]
+    /// let mut old_types = SnapshotTypes::<Json>::new();
+    /// old_types.register_component::<E, Health>("Health");
+    /// let mut snapshot = registry.serialize(&old_types).unwrap();
+    /// snapshot.schema.components.push(muds::ecs::schema::ComponentSchema { name: "Health".into(), version: 1, field_hash: 0 });
+    ///
+    /// // Version 2 stores health in [0, 100]
+    /// let mut types = SnapshotTypes::<Json>::new();
+    /// types.register_versioned_component::<E, Health>();
+    /// types.migrations_mut().register_migration::<Health>(1, |storage| {
+    ///     for health in storage.as_array_mut().into_iter().flatten().filter_map(|entry| entry.get_mut(1)) {
+    ///         *health = (health.as_u64().unwrap() * 100).into();
+    ///     }
+    /// });
+    /// registry.deserialize(&types, snapshot).unwrap();
+    /// assert_eq!(registry.components::<E, Health>().get(&e), Some(&Health(100)));
+    /// ```
+    pub fn register_versioned_component<E: Entity + Any, C: Component<E> + Versioned + Any>(
+        &mut self,
+    ) where
+        C::Storage: Serialize + DeserializeOwned,
+    {
+        self.register_component::<E, C>(C::NAME);
+        self.migrations.register::<C>();
+    }
+
+    /// Returns the current schema and migrations of the versioned storages.
+    #[inline]
+    pub fn migrations(&self) -> &Migrations<F::Data> {
+        &self.migrations
+    }
+
+    /// Returns the current schema and migrations of the versioned storages mutably, e.g. to register migrations.
+    #[inline]
+    pub fn migrations_mut(&mut self) -> &mut Migrations<F::Data> {
+        &mut self.migrations
+    }
+
+    /// Registers a storage of given registry key under given name, replacing any existing type of the same name.
+    pub fn register<S: Serialize + DeserializeOwned + Any>(
+        &mut self,
+        name: &str,
+        key: RegistryKey,
+    ) {
+        self.types.insert(
+            name.into(),
+            SnapshotType {
+                key,
+                save: |registry, key| registry.get::<S>(key).map(|storage| F::to_data(&*storage)),
+                load: |registry, key, data| {
+                    if !registry.contains_key(key) {
+                        return None;
+                    }
+                    // Deserialize before borrowing the storage, which is left unchanged on error
+                    Some(F::from_data::<S>(data).map(|value| {
+                        if let Some(mut storage) = registry.get_mut::<S>(key) {
+                            *storage = value;
+                        }
+                    }))
+                },
+            },
+        );
+    }
+
+    /// Returns an iterator over the registered storage names.
+    #[inline]
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+}
+
+impl<F: SnapshotFormat> Default for SnapshotTypes<F> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: SnapshotFormat> fmt::Debug for SnapshotTypes<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.types.iter().map(|(name, ty)| (name, ty.key)))
+            .finish()
+    }
+}
+
+impl Registry {
+    /// Serializes all storages registered in [SnapshotTypes] into a [Snapshot], together with their current schema.
+    ///
+    /// # Errors
+    /// Returns [SnapshotError::NotRegistered] if a storage is not registered in this registry,
+    /// or [SnapshotError::Format] if a storage fails to serialize.
+    ///
+    /// # Panics
+    /// Panics if any storage is currently mutably borrowed.
+    pub fn serialize<F: SnapshotFormat>(
+        &self,
+        types: &SnapshotTypes<F>,
+    ) -> Result<Snapshot<F::Data>, SnapshotError<F::Error>> {
+        let mut storages = BTreeMap::new();
+        for (name, ty) in &types.types {
+            let data = (ty.save)(self, &ty.key)
+                .ok_or_else(|| SnapshotError::NotRegistered(name.clone()))?
+                .map_err(|error| SnapshotError::Format(name.clone(), error))?;
+            storages.insert(name.clone(), data);
+        }
+        Ok(Snapshot {
+            schema: types.migrations.schema().clone(),
+            storages,
+        })
+    }
+
+    /// Replaces the storages in a [Snapshot] with their deserialized data.
+    /// Storages not in the snapshot are left unchanged.
+    /// Data of a storage saved with an older schema is first upgraded by the registered [Migrations].
+    ///
+    /// Storages are replaced one by one, so on error, the storages before the failed one have already been replaced.
+    ///
+    /// # Errors
+    /// Returns [SnapshotError::Unknown] if a storage name is not registered in [SnapshotTypes],
+    /// [SnapshotError::NotRegistered] if a storage is not registered in this registry,
+    /// [SnapshotError::Schema] if the data of a storage cannot be migrated to its current schema,
+    /// or [SnapshotError::Format] if a storage fails to deserialize.
+    ///
+    /// # Panics
+    /// Panics if any storage in the snapshot is currently borrowed.
+    pub fn deserialize<F: SnapshotFormat>(
+        &self,
+        types: &SnapshotTypes<F>,
+        snapshot: Snapshot<F::Data>,
+    ) -> Result<(), SnapshotError<F::Error>> {
+        for (name, mut data) in snapshot.storages {
+            let ty = match types.types.get(&name) {
+                Some(ty) => ty,
+                None => return Err(SnapshotError::Unknown(name)),
+            };
+            if let Some(saved) = snapshot.schema.get(&name) {
+                if let Err(error) = types.migrations.migrate(saved, &mut data) {
+                    return Err(SnapshotError::Schema(name, error));
+                }
+            }
+            match (ty.load)(self, &ty.key, data) {
+                Some(Ok(())) => {}
+                Some(Err(error)) => return Err(SnapshotError::Format(name, error)),
+                None => return Err(SnapshotError::NotRegistered(name)),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,362 @@
+//! Spatial index of entity positions.
+
+use super::{storage::Tracked, Component, Components, Entity, EntityId, Registry, Resources};
+use crate::collections::{IterableMap, Map, MapMut, VecMap};
+use crate::GenIndex;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::any::Any;
+
+/// A component that has a position in `D`-dimensional space, e.g. 2D or 3D.
+pub trait SpatialPosition<const D: usize> {
+    /// Returns the position.
+    fn position(&self) -> [f32; D];
+}
+
+/// Uniform grid spatial index of keys with `D`-dimensional positions.
+///
+/// The index is usually registered as a resource and kept in sync with the positions of a component storage
+/// via [SpatialIndex::sync] or [update_spatial_index], before being queried by other systems.
+/// Syncing only visits the positions changed since the last sync, so the storage must be [Tracked].
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::ecs::{spatial::{update_spatial_index, SpatialIndex, SpatialPosition}, storage::{ArenaStorage, Tracked, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos([f32; 2]);
+/// impl Component<E> for Pos { type Storage = Tracked<E, VecStorage<E, Self>>; }
+/// impl SpatialPosition<2> for Pos {
+///     fn position(&self) -> [f32; 2] { self.0 }
+/// }
+///
+/// let mut registry = Registry::default();
+/// registry.register_entity::<E>();
+/// registry.register_component::<E, Pos>();
+/// registry.register_resource(SpatialIndex::<EntityId<E>, 2>::new(10.));
+///
+/// let (e1, e2) = {
+///     let mut entities = registry.entities_mut::<E>();
+///     (entities.insert(E), entities.insert(E))
+/// };
+/// registry.components_mut::<E, Pos>().insert(e1, Pos([1., 1.]));
+/// registry.components_mut::<E, Pos>().insert(e2, Pos([50., 50.]));
+/// update_spatial_index::<E, Pos, _, 2>(&registry);
+///
+/// let index = registry.resource::<SpatialIndex<EntityId<E>, 2>>();
+/// assert_eq!(index.query_radius([0., 0.], 5.).collect::<Vec<_>>(), [e1]);
+/// assert_eq!(index.query_aabb([0., 0.], [100., 100.]).count(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpatialIndex<K: GenIndex, const D: usize> {
+    cell_size: f32,
+    cells: BTreeMap<[i32; D], Vec<K>>,
+    entries: VecMap<(K, [f32; D]), K::Index>,
+    /// Tick of the synced storage at the last sync.
+    storage_tick: Option<u64>,
+}
+
+impl<K: GenIndex, const D: usize> SpatialIndex<K, D> {
+    /// Creates a new, empty [SpatialIndex] with grid cells of given size.
+    /// For best performance, the cell size should be close to the typical query radius.
+    ///
+    /// # Panics
+    /// Panics if `cell_size` is not positive.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, Index};
+    /// let index = SpatialIndex::<Index, 3>::new(4.);
+    /// assert_eq!(index.cell_size(), 4.);
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0., "cell size must be positive");
+        Self {
+            cell_size,
+            cells: BTreeMap::new(),
+            entries: VecMap::new(),
+            storage_tick: None,
+        }
+    }
+
+    /// Returns the grid cell size.
+    #[inline]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Returns the number of indexed keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is no indexed key.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.len() == 0
+    }
+
+    /// Returns the indexed position of a key.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, GenIndex, Index};
+    /// let mut index = SpatialIndex::<Index, 2>::new(1.);
+    /// let key = Index::from_raw_parts(0, 1);
+    /// index.insert(key, [1., 2.]);
+    /// assert_eq!(index.get(&key), Some([1., 2.]));
+    /// assert_eq!(index.get(&Index::from_raw_parts(0, 2)), None);
+    /// ```
+    pub fn get(&self, key: &K) -> Option<[f32; D]> {
+        match self.entries.get(&key.index()) {
+            Some((k, position)) if k == key => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Inserts or moves a key to given position. The previous position is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, GenIndex, Index};
+    /// let mut index = SpatialIndex::<Index, 2>::new(1.);
+    /// let key = Index::from_raw_parts(0, 1);
+    /// assert_eq!(index.insert(key, [1., 2.]), None);
+    /// assert_eq!(index.insert(key, [3., 4.]), Some([1., 2.]));
+    /// assert_eq!(index.query_aabb([3., 4.], [3., 4.]).collect::<Vec<_>>(), [key]);
+    /// ```
+    pub fn insert(&mut self, key: K, position: [f32; D]) -> Option<[f32; D]> {
+        let previous = self.remove(&key);
+        if let Some((old_key, old_position)) = self.entries.insert(key.index(), (key, position)) {
+            // Replaced a key of an older generation
+            remove_from_cell(&mut self.cells, self.cell_size, &old_key, &old_position);
+        }
+        self.cells
+            .entry(cell_of(self.cell_size, &position))
+            .or_default()
+            .push(key);
+        previous
+    }
+
+    /// Removes a key from the index. The removed position is returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, GenIndex, Index};
+    /// let mut index = SpatialIndex::<Index, 2>::new(1.);
+    /// let key = Index::from_raw_parts(0, 1);
+    /// index.insert(key, [1., 2.]);
+    /// assert_eq!(index.remove(&key), Some([1., 2.]));
+    /// assert!(index.is_empty());
+    /// ```
+    pub fn remove(&mut self, key: &K) -> Option<[f32; D]> {
+        let position = self.get(key)?;
+        self.entries.remove(&key.index());
+        remove_from_cell(&mut self.cells, self.cell_size, key, &position);
+        Some(position)
+    }
+
+    /// Removes all keys from the index. The next [SpatialIndex::sync] re-inserts all keys of the storage.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.storage_tick = None;
+    }
+
+    /// Returns the keys with positions inside the axis-aligned bounding box from `min` to `max` inclusive.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, GenIndex, Index};
+    /// let mut index = SpatialIndex::<Index, 2>::new(2.);
+    /// let (k1, k2) = (Index::from_raw_parts(0, 1), Index::from_raw_parts(1, 1));
+    /// index.insert(k1, [1., 1.]);
+    /// index.insert(k2, [-3., 5.]);
+    /// assert_eq!(index.query_aabb([-4., 0.], [0., 10.]).collect::<Vec<_>>(), [k2]);
+    /// ```
+    pub fn query_aabb(&self, min: [f32; D], max: [f32; D]) -> impl Iterator<Item = K> + '_ {
+        let (min_cell, max_cell) = (cell_of(self.cell_size, &min), cell_of(self.cell_size, &max));
+        let cells = if min_cell <= max_cell {
+            Some(self.cells.range(min_cell..=max_cell))
+        } else {
+            None
+        };
+        cells
+            .into_iter()
+            .flatten()
+            .filter(move |(cell, _)| {
+                (0..D).all(|i| cell[i] >= min_cell[i] && cell[i] <= max_cell[i])
+            })
+            .flat_map(|(_, keys)| keys.iter().copied())
+            .filter(move |key| {
+                self.get(key)
+                    .map_or(false, |p| (0..D).all(|i| p[i] >= min[i] && p[i] <= max[i]))
+            })
+    }
+
+    /// Returns the keys with positions within `radius` distance from `center`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{ecs::spatial::SpatialIndex, GenIndex, Index};
+    /// let mut index = SpatialIndex::<Index, 3>::new(1.);
+    /// let (k1, k2) = (Index::from_raw_parts(0, 1), Index::from_raw_parts(1, 1));
+    /// index.insert(k1, [1., 1., 1.]);
+    /// index.insert(k2, [2., 2., 2.]);
+    /// assert_eq!(index.query_radius([0., 0., 0.], 2.).collect::<Vec<_>>(), [k1]);
+    /// ```
+    pub fn query_radius(&self, center: [f32; D], radius: f32) -> impl Iterator<Item = K> + '_ {
+        let (mut min, mut max) = (center, center);
+        for i in 0..D {
+            min[i] -= radius;
+            max[i] += radius;
+        }
+        self.query_aabb(min, max).filter(move |key| {
+            self.get(key).map_or(false, |p| {
+                (0..D)
+                    .map(|i| (p[i] - center[i]) * (p[i] - center[i]))
+                    .sum::<f32>()
+                    <= radius * radius
+            })
+        })
+    }
+
+    /// Moves a key to given position, only updating the grid cells if its cell changes.
+    fn update(&mut self, key: K, position: [f32; D]) {
+        match self.entries.get_mut(&key.index()) {
+            Some((k, p))
+                if *k == key
+                    && cell_of(self.cell_size, p) == cell_of(self.cell_size, &position) =>
+            {
+                *p = position;
+            }
+            _ => {
+                self.insert(key, position);
+            }
+        }
+    }
+}
+
+impl<E: Entity, const D: usize> SpatialIndex<EntityId<E>, D> {
+    /// Synchronizes the index with the positions of a [Tracked] storage.
+    /// Only the positions inserted or changed since the last sync are updated,
+    /// and the entities removed since then are removed from the index.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::{collections::{Map, MapMut}, ecs::{spatial::SpatialIndex, storage::{ArenaStorage, Tracked, VecStorage}, Entity}};
+    /// # use muds::{GenIndex, ecs::EntityId};
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    ///
+    /// let mut storage = Tracked::<E, VecStorage<E, [f32; 2]>>::default();
+    /// let (k1, k2) = (EntityId::from_raw_parts(0, 1), EntityId::from_raw_parts(1, 1));
+    /// storage.insert(k1, [0., 0.]);
+    /// storage.insert(k2, [1., 0.]);
+    ///
+    /// let mut index = SpatialIndex::new(1.);
+    /// index.sync(&storage, |p| *p);
+    /// assert_eq!(index.len(), 2);
+    ///
+    /// storage.remove(&k1);
+    /// *storage.get_mut(&k2).unwrap() = [5., 5.];
+    /// index.sync(&storage, |p| *p);
+    /// assert_eq!(index.len(), 1);
+    /// assert_eq!(index.get(&k2), Some([5., 5.]));
+    ///
+    /// // Untracked writes are not seen
+    /// *storage.get_mut_untracked(&k2).unwrap() = [0., 0.];
+    /// index.sync(&storage, |p| *p);
+    /// assert_eq!(index.get(&k2), Some([5., 5.]));
+    /// ```
+    pub fn sync<S, V>(&mut self, storage: &Tracked<E, S>, mut position: impl FnMut(&V) -> [f32; D])
+    where
+        S: MapMut<Key = EntityId<E>, Value = V> + for<'a> IterableMap<'a, Key = EntityId<E>>,
+    {
+        let since = self.storage_tick;
+        if since == Some(storage.tick()) {
+            return;
+        }
+        self.storage_tick = Some(storage.tick());
+
+        match since {
+            Some(since) => {
+                for key in storage.removed(since) {
+                    self.remove(key);
+                }
+                for (key, value) in storage.changed(since) {
+                    self.update(*key, position(value));
+                }
+            }
+            None => {
+                for (key, value) in storage.iter() {
+                    self.update(*key, position(value));
+                }
+            }
+        }
+
+        // The index now holds every key of the storage, so any extra key is stale,
+        // e.g. a removal forgotten on maintenance before this sync.
+        if self.len() != storage.len() {
+            let (cells, cell_size) = (&mut self.cells, self.cell_size);
+            self.entries.retain(|_, (key, position)| {
+                let keep = storage.contains_key(key);
+                if !keep {
+                    remove_from_cell(cells, cell_size, key, position);
+                }
+                keep
+            });
+        }
+    }
+}
+
+/// Synchronizes the [SpatialIndex] resource of entity `E` with the positions of [Tracked] component `C`.
+/// The resource must be registered beforehand.
+///
+/// # Panics
+/// Panics if the resource or component is not registered, or is already borrowed mutably.
+pub fn update_spatial_index<E, C, S, const D: usize>(registry: &Registry)
+where
+    E: Entity + Any,
+    C: Component<E, Storage = Tracked<E, S>> + SpatialPosition<D> + Any,
+    S: MapMut<Key = EntityId<E>, Value = C> + for<'a> IterableMap<'a, Key = EntityId<E>> + Any,
+{
+    let components = registry.components::<E, C>();
+    registry
+        .resource_mut::<SpatialIndex<EntityId<E>, D>>()
+        .sync(&*components, C::position);
+}
+
+/// Returns the grid cell containing a position.
+fn cell_of<const D: usize>(cell_size: f32, position: &[f32; D]) -> [i32; D] {
+    let mut cell = [0; D];
+    for i in 0..D {
+        let value = position[i] / cell_size;
+        let floor = value as i32;
+        cell[i] = if (floor as f32) > value {
+            floor - 1
+        } else {
+            floor
+        };
+    }
+    cell
+}
+
+fn remove_from_cell<K: GenIndex, const D: usize>(
+    cells: &mut BTreeMap<[i32; D], Vec<K>>,
+    cell_size: f32,
+    key: &K,
+    position: &[f32; D],
+) {
+    let cell = cell_of(cell_size, position);
+    if let Some(keys) = cells.get_mut(&cell) {
+        if let Some(i) = keys.iter().position(|k| k == key) {
+            keys.swap_remove(i);
+        }
+        if keys.is_empty() {
+            cells.remove(&cell);
+        }
+    }
+}
@@ -1,7 +1,91 @@
 //! Entity and component storages.
 
-use super::{Component, ComponentStorage, Entity, EntityId, EntityStorage};
-use crate::collections::{GenIndexArena, GenIndexBTreeMap, GenIndexSparseSet, GenIndexVecMap};
+use super::{Component, ComponentStorage, Entity, EntityId, EntityStorage, GenIndexType};
+use crate::collections::{
+    GenIndexArena, GenIndexBTreeMap, GenIndexSparseSet, GenIndexVecMap, IterableMap,
+    IterableMapMut, Map, MapJoin, MapMut,
+};
+use crate::GenIndex;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt, marker::PhantomData, mem};
+
+/// Type-erased access to an entity or component storage, e.g. for editors and debuggers.
+pub trait AnyStorage {
+    /// Returns the number of elements in the storage.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the storage contains no elements.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements the storage can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Returns the size of a stored element in bytes.
+    fn element_size(&self) -> usize;
+
+    /// Returns the type name of the stored entity or component.
+    fn type_name(&self) -> &'static str;
+
+    /// Removes the element of given entity index. Returns `true` if an element was removed.
+    fn remove(&mut self, index: GenIndexType) -> bool;
+}
+
+/// Implement AnyStorage for all storages keyed by [EntityId].
+///
+/// # Examples
+/// ```rust
+/// # use muds::ecs::{Component, Entity, storage::{AnyStorage, ArenaStorage, VecStorage}};
+/// # use muds::collections::{Arena, MapMut};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos(u32, u32);
+/// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+///
+/// let mut entities = ArenaStorage::<E>::default();
+/// let mut positions = VecStorage::<E, Pos>::default();
+/// let e = entities.insert(E);
+/// positions.insert(e, Pos(1, 2));
+///
+/// let storage: &mut dyn AnyStorage = &mut positions;
+/// assert_eq!(storage.len(), 1);
+/// assert!(storage.capacity() >= 1);
+/// assert_eq!(storage.element_size(), 8);
+/// assert!(storage.type_name().ends_with("Pos"));
+/// assert!(storage.remove(e.to_index()));
+/// assert!(storage.is_empty());
+/// ```
+impl<T, S> AnyStorage for S
+where
+    S: MapMut<Key = EntityId<T>> + 'static,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        crate::collections::Map::len(self)
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        crate::collections::Map::capacity(self)
+    }
+
+    #[inline]
+    fn element_size(&self) -> usize {
+        mem::size_of::<S::Value>()
+    }
+
+    #[inline]
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<S::Value>()
+    }
+
+    #[inline]
+    fn remove(&mut self, index: GenIndexType) -> bool {
+        MapMut::remove(self, &EntityId::from_index(index)).is_some()
+    }
+}
 
 /// Entity storage backed by a `GenIndexArena`.
 pub type ArenaStorage<E> = GenIndexArena<E, EntityId<E>>;
@@ -36,3 +120,658 @@ impl<E: Entity + 'static, C: Component<E> + 'static> ComponentStorage<E, C>
     for HashMapStorage<E, C>
 {
 }
+
+/// Component storage for zero-sized tag components, which only stores the [EntityId]s of tagged entities.
+///
+/// As tags hold no data, filter by them with [Query::with](super::query::Query::with) /
+/// [Query::without](super::query::Query::without), or [MapJoin::map_join_with](crate::collections::MapJoin::map_join_with) /
+/// [MapJoin::map_join_left_excl](crate::collections::MapJoin::map_join_left_excl), which only check membership
+/// without fetching or yielding the tag values.
+/// Using this storage for a component that is not zero-sized fails to compile.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::{IterableMap, MapJoin, MapMut};
+/// # use muds::ecs::storage::{ArenaStorage, NullStorage, VecStorage};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos(u32);
+/// impl Component<E> for Pos { type Storage = VecStorage<E, Self>; }
+/// struct Player;
+/// impl Component<E> for Player { type Storage = NullStorage<E, Self>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_archetype::<E, Cons!(Pos, Player)>();
+/// let player = registry.insert_archetype(E, cons!(Pos(1), Player));
+/// registry.insert_archetype(E, cons!(Pos(2)));
+///
+/// let cons!(_e, pos, players) = registry.storage::<&E, Cons!(&Pos, &Player)>();
+/// let tagged: Vec<_> = pos.iter().map_join_with(&*players).map(|(id, _)| *id).collect();
+/// assert_eq!(tagged, [player]);
+/// ```
+pub struct NullStorage<E: Entity, C> {
+    ids: GenIndexVecMap<C, EntityId<E>>,
+}
+
+impl<E: Entity, C> NullStorage<E, C> {
+    const ZERO_SIZED: () = assert!(mem::size_of::<C>() == 0, "NullStorage of sized component");
+
+    /// Creates an empty storage.
+    #[inline]
+    pub fn new() -> Self {
+        let () = Self::ZERO_SIZED;
+        Self {
+            ids: GenIndexVecMap::default(),
+        }
+    }
+}
+
+impl<E: Entity, C> Default for NullStorage<E, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity, C> fmt::Debug for NullStorage<E, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.ids.iter().map(|(id, _)| id))
+            .finish()
+    }
+}
+
+impl<E: Entity, C> Map for NullStorage<E, C> {
+    type Key = EntityId<E>;
+    type Value = C;
+
+    #[inline]
+    fn get(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.ids.get(key)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &Self::Key) -> bool {
+        self.ids.contains_key(key)
+    }
+}
+
+impl<E: Entity, C> MapMut for NullStorage<E, C> {
+    #[inline]
+    fn clear(&mut self) {
+        self.ids.clear()
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+        self.ids.get_mut(key)
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
+        MapMut::insert(&mut self.ids, key, value)
+    }
+
+    #[inline]
+    fn remove(&mut self, key: &Self::Key) -> Option<Self::Value> {
+        MapMut::remove(&mut self.ids, key)
+    }
+
+    #[inline]
+    fn retain(&mut self, f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
+        self.ids.retain(f)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        MapMut::reserve(&mut self.ids, additional)
+    }
+}
+
+impl<'a, E: Entity + 'a, C: 'a> IterableMap<'a> for NullStorage<E, C> {
+    type Iter = <GenIndexVecMap<C, EntityId<E>> as IterableMap<'a>>::Iter;
+
+    #[inline]
+    fn iter(&'a self) -> Self::Iter {
+        self.ids.iter()
+    }
+}
+
+impl<'a, E: Entity + 'a, C: 'a> IterableMapMut<'a> for NullStorage<E, C> {
+    type IterMut = <GenIndexVecMap<C, EntityId<E>> as IterableMapMut<'a>>::IterMut;
+
+    #[inline]
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        self.ids.iter_mut()
+    }
+}
+
+impl<E: Entity + 'static, C: Component<E> + 'static> ComponentStorage<E, C> for NullStorage<E, C> {}
+
+/// Ticks of the last changes to an element of a [Tracked] storage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChangeTicks {
+    /// Tick when the element was inserted.
+    pub added: u64,
+    /// Tick when the element was last inserted or mutably borrowed.
+    pub changed: u64,
+}
+
+/// A component storage wrapper that tracks added, changed and removed elements.
+///
+/// Every write through [MapMut] or [IterableMapMut] advances the storage tick, and stamps the written elements
+/// with it. Systems remember the [Tracked::tick] they last ran at, and on the next run visit
+/// only the elements added or changed after that tick, and the entities whose elements were removed.
+/// Mutable borrows count as changes, whether or not the value is actually modified;
+/// use [Tracked::get_mut_untracked] for writes that should not be detected.
+///
+/// Removals are kept until the second [Registry::maintain] after them, so that systems running once between
+/// maintenances see every removal. Registries must be maintained regularly, e.g. once per frame,
+/// or the removal log grows unbounded.
+///
+/// # Examples
+/// ```rust
+/// # use muds::prelude::*;
+/// # use muds::collections::MapMut;
+/// # use muds::ecs::storage::{ArenaStorage, RemovedComponents, Tracked, VecStorage};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// struct Pos(u32);
+/// impl Component<E> for Pos { type Storage = Tracked<E, VecStorage<E, Self>>; }
+///
+/// let mut registry = Registry::default();
+/// registry.register_archetype::<E, Cons!(Pos)>();
+/// let a = registry.insert_archetype(E, cons!(Pos(0)));
+/// let b = registry.insert_archetype(E, cons!(Pos(0)));
+///
+/// let since = registry.components::<E, Pos>().tick();
+/// let mut removed = RemovedComponents::<Pos>::new();
+/// {
+///     let mut pos = registry.components_mut::<E, Pos>();
+///     pos.get_mut(&a).unwrap().0 = 1;
+///     pos.remove(&b);
+/// }
+///
+/// let pos = registry.components::<E, Pos>();
+/// assert_eq!(pos.added(since).count(), 0);
+/// assert_eq!(pos.changed(since).map(|(id, _)| *id).collect::<Vec<_>>(), [a]);
+/// assert_eq!(removed.read(&pos).copied().collect::<Vec<_>>(), [b]);
+/// assert_eq!(removed.read(&pos).count(), 0);
+/// ```
+#[derive(Debug)]
+pub struct Tracked<E: Entity, S> {
+    storage: S,
+    ticks: GenIndexVecMap<ChangeTicks, EntityId<E>>,
+    removed: Vec<(u64, EntityId<E>)>,
+    tick: u64,
+    /// Tick of the last maintenance.
+    maintained: u64,
+}
+
+impl<E: Entity, S: MapMut<Key = EntityId<E>>> Tracked<E, S> {
+    /// Wraps a storage, treating its existing elements as added at tick 0.
+    pub fn new(storage: S) -> Self
+    where
+        S: for<'a> IterableMap<'a>,
+    {
+        let mut ticks = GenIndexVecMap::default();
+        for (id, _) in storage.iter() {
+            ticks.insert(*id, ChangeTicks::default());
+        }
+        Self {
+            storage,
+            ticks,
+            removed: Vec::new(),
+            tick: 0,
+            maintained: 0,
+        }
+    }
+
+    /// Returns the tick of the last write to this storage.
+    #[inline]
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the wrapped storage.
+    #[inline]
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the change ticks of an element.
+    #[inline]
+    pub fn ticks(&self, id: &EntityId<E>) -> Option<ChangeTicks> {
+        self.ticks.get(id).copied()
+    }
+
+    /// Returns `true` if the element of given entity was inserted after tick `since`.
+    #[inline]
+    pub fn is_added(&self, id: &EntityId<E>, since: u64) -> bool {
+        self.ticks(id).map_or(false, |ticks| ticks.added > since)
+    }
+
+    /// Returns `true` if the element of given entity was inserted or mutably borrowed after tick `since`.
+    #[inline]
+    pub fn is_changed(&self, id: &EntityId<E>, since: u64) -> bool {
+        self.ticks(id).map_or(false, |ticks| ticks.changed > since)
+    }
+
+    /// Returns an iterator over the elements inserted after tick `since`.
+    pub fn added(&self, since: u64) -> impl Iterator<Item = (&EntityId<E>, &S::Value)> + '_ {
+        self.ticks
+            .iter()
+            .filter(move |(_, ticks)| ticks.added > since)
+            .filter_map(move |(id, _)| Some((id, self.storage.get(id)?)))
+    }
+
+    /// Returns an iterator over the elements inserted or mutably borrowed after tick `since`.
+    pub fn changed(&self, since: u64) -> impl Iterator<Item = (&EntityId<E>, &S::Value)> + '_ {
+        self.ticks
+            .iter()
+            .filter(move |(_, ticks)| ticks.changed > since)
+            .filter_map(move |(id, _)| Some((id, self.storage.get(id)?)))
+    }
+
+    /// Returns an iterator over the entities whose elements were removed after tick `since`.
+    /// Removals are kept until [Tracked::clear_removed] is called, or forgotten on maintenance.
+    pub fn removed(&self, since: u64) -> impl Iterator<Item = &EntityId<E>> + '_ {
+        let start = self.removed.partition_point(|(tick, _)| *tick <= since);
+        self.removed[start..].iter().map(|(_, id)| id)
+    }
+
+    /// Forgets the removals up to tick `until`, e.g. once all readers have seen them.
+    pub fn clear_removed(&mut self, until: u64) {
+        let end = self.removed.partition_point(|(tick, _)| *tick <= until);
+        self.removed.drain(..end);
+    }
+
+    /// Returns a mutable reference to an element without marking it as changed.
+    #[inline]
+    pub fn get_mut_untracked(&mut self, id: &EntityId<E>) -> Option<&mut S::Value> {
+        self.storage.get_mut(id)
+    }
+
+    #[inline]
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+impl<E: Entity, S: Default> Default for Tracked<E, S> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            storage: S::default(),
+            ticks: GenIndexVecMap::default(),
+            removed: Vec::new(),
+            tick: 0,
+            maintained: 0,
+        }
+    }
+}
+
+impl<E: Entity, S: Map<Key = EntityId<E>>> Map for Tracked<E, S> {
+    type Key = EntityId<E>;
+    type Value = S::Value;
+
+    #[inline]
+    fn get(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.storage.get(key)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &Self::Key) -> bool {
+        self.storage.contains_key(key)
+    }
+}
+
+impl<E: Entity, S: MapMut<Key = EntityId<E>>> MapMut for Tracked<E, S> {
+    fn clear(&mut self) {
+        let tick = self.next_tick();
+        self.removed
+            .extend(self.ticks.iter().map(|(id, _)| (tick, *id)));
+        self.ticks.clear();
+        self.storage.clear();
+    }
+
+    fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value> {
+        if !self.storage.contains_key(key) {
+            return None;
+        }
+        let tick = self.next_tick();
+        if let Some(ticks) = self.ticks.get_mut(key) {
+            ticks.changed = tick;
+        }
+        self.storage.get_mut(key)
+    }
+
+    fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
+        let tick = self.next_tick();
+        let prev = self.storage.insert(key, value);
+        let added = match (&prev, self.ticks.get(&key)) {
+            (Some(_), Some(ticks)) => ticks.added,
+            _ => tick,
+        };
+        self.ticks.insert(
+            key,
+            ChangeTicks {
+                added,
+                changed: tick,
+            },
+        );
+        prev
+    }
+
+    fn remove(&mut self, key: &Self::Key) -> Option<Self::Value> {
+        let value = self.storage.remove(key)?;
+        let tick = self.next_tick();
+        self.ticks.remove(key);
+        self.removed.push((tick, *key));
+        Some(value)
+    }
+
+    /// Retains only the elements specified by the predicate.
+    /// The retained elements are not marked as changed, even though the predicate can modify them.
+    fn retain(&mut self, mut f: impl FnMut(&Self::Key, &mut Self::Value) -> bool) {
+        let tick = self.tick + 1;
+        let (ticks, removed) = (&mut self.ticks, &mut self.removed);
+        self.storage.retain(|id, value| {
+            let keep = f(id, value);
+            if !keep {
+                ticks.remove(id);
+                removed.push((tick, *id));
+            }
+            keep
+        });
+        self.tick = tick;
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        self.ticks.reserve(additional);
+        self.storage.reserve(additional);
+    }
+}
+
+impl<'a, E: Entity + 'a, S: IterableMap<'a, Key = EntityId<E>>> IterableMap<'a> for Tracked<E, S> {
+    type Iter = S::Iter;
+
+    #[inline]
+    fn iter(&'a self) -> Self::Iter {
+        self.storage.iter()
+    }
+}
+
+/// Mutable iteration marks all elements as changed.
+impl<'a, E: Entity + 'a, S: MapMut<Key = EntityId<E>> + IterableMapMut<'a, Key = EntityId<E>>>
+    IterableMapMut<'a> for Tracked<E, S>
+{
+    type IterMut = S::IterMut;
+
+    fn iter_mut(&'a mut self) -> Self::IterMut {
+        let tick = self.next_tick();
+        for (_, ticks) in self.ticks.iter_mut() {
+            ticks.changed = tick;
+        }
+        self.storage.iter_mut()
+    }
+}
+
+impl<E: Entity + 'static, C: Component<E> + 'static, S: ComponentStorage<E, C>>
+    ComponentStorage<E, C> for Tracked<E, S>
+{
+    /// Forgets the removals made before the previous maintenance.
+    fn maintain(&mut self) {
+        self.clear_removed(self.maintained);
+        self.maintained = self.tick;
+        self.storage.maintain();
+    }
+}
+
+/// A reader of the removals of component `C` from a [Tracked] storage, which visits each removal once.
+pub struct RemovedComponents<C> {
+    tick: u64,
+    marker: PhantomData<fn() -> C>,
+}
+
+impl<C> RemovedComponents<C> {
+    /// Creates a reader. The first read visits all removals still kept by the storage.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            tick: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entities whose components were removed since the last read.
+    pub fn read<'a, E: Entity, S: MapMut<Key = EntityId<E>, Value = C>>(
+        &mut self,
+        storage: &'a Tracked<E, S>,
+    ) -> impl Iterator<Item = &'a EntityId<E>> + 'a {
+        let since = mem::replace(&mut self.tick, storage.tick());
+        storage.removed(since)
+    }
+}
+
+impl<C> Default for RemovedComponents<C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> fmt::Debug for RemovedComponents<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemovedComponents")
+            .field("tick", &self.tick)
+            .finish()
+    }
+}
+
+/// Asserts that a component storage type behaves like the built-in storages. See [assert_storage_conformance].
+///
+/// Values are created from distinct `u32`s by `S::Value::from`, or by the given function.
+///
+/// # Examples
+/// ```rust
+/// # use muds::assert_storage_impl;
+/// # use muds::ecs::{Entity, storage::{ArenaStorage, BTreeStorage, NullStorage, SparseSetStorage, Tracked, VecStorage}};
+/// struct E;
+/// impl Entity for E { type Storage = ArenaStorage<Self>; }
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Pos(u32);
+/// impl From<u32> for Pos {
+///     fn from(value: u32) -> Self { Pos(value) }
+/// }
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Player;
+///
+/// assert_storage_impl!(VecStorage<E, Pos>);
+/// assert_storage_impl!(SparseSetStorage<E, Pos>);
+/// assert_storage_impl!(BTreeStorage<E, Pos>);
+/// assert_storage_impl!(Tracked<E, VecStorage<E, Pos>>);
+/// assert_storage_impl!(NullStorage<E, Player>, |_| Player);
+/// ```
+#[macro_export]
+macro_rules! assert_storage_impl {
+    ($S:ty) => {
+        $crate::ecs::storage::assert_storage_conformance::<_, $S>(::core::convert::From::from)
+    };
+    ($S:ty, $value:expr) => {
+        $crate::ecs::storage::assert_storage_conformance::<_, $S>($value)
+    };
+}
+
+/// Checks a component storage type against a model of the expected map behavior, panicking on the first mismatch.
+/// Use this to test custom storages, e.g. paged, networked or GPU-mirrored ones, before registering them.
+///
+/// The storage is exercised by inserting, replacing, mutating, removing, retaining, clearing, iterating,
+/// and joining with another map, including keys of the same index with another generation,
+/// which must replace the element of the stale key.
+/// `value` must create distinct values from distinct inputs, unless values are zero-sized.
+///
+/// # Panics
+/// Panics if the storage does not behave as expected.
+pub fn assert_storage_conformance<E, S>(mut value: impl FnMut(u32) -> S::Value)
+where
+    S: Default + MapMut<Key = EntityId<E>> + for<'a> IterableMapMut<'a>,
+    S::Value: Clone + PartialEq + fmt::Debug,
+{
+    let id = |index: u32, generation: u32| EntityId::<E>::from_raw_parts(index, generation);
+    let mut storage = S::default();
+    let mut model = BTreeMap::new();
+
+    for index in [3, 0, 7, 1, 2] {
+        let (key, v) = (id(index, 1), value(index));
+        assert!(
+            storage.insert(key, v.clone()).is_none(),
+            "insert of a new key returned a value"
+        );
+        model.insert(index, (key, v));
+    }
+    assert_matches_model(&storage, &model);
+
+    // Replace with the same key
+    let v = value(100);
+    assert_eq!(
+        storage.insert(id(3, 1), v.clone()).as_ref(),
+        Some(&model[&3].1),
+        "insert did not return the old value"
+    );
+    model.insert(3, (id(3, 1), v));
+
+    // Replace with a newer generation
+    let v = value(101);
+    storage.insert(id(7, 2), v.clone());
+    model.insert(7, (id(7, 2), v));
+    assert!(
+        storage.get(&id(7, 1)).is_none(),
+        "stale key of a replaced element is still found"
+    );
+    assert_matches_model(&storage, &model);
+
+    // Mutate
+    let v = value(102);
+    *storage
+        .get_mut(&id(0, 1))
+        .expect("get_mut of an existing key returned None") = v.clone();
+    model.insert(0, (id(0, 1), v));
+    assert!(
+        storage.get_mut(&id(0, 2)).is_none(),
+        "get_mut of a stale key returned a value"
+    );
+    for (key, v) in storage.iter_mut() {
+        if key.index() == 1 {
+            *v = value(103);
+        }
+    }
+    model.insert(1, (id(1, 1), value(103)));
+    assert_matches_model(&storage, &model);
+
+    // Remove
+    assert!(
+        storage.remove(&id(2, 2)).is_none(),
+        "remove of a stale key returned a value"
+    );
+    assert_eq!(
+        storage.remove(&id(2, 1)).as_ref(),
+        model.remove(&2).map(|(_, v)| v).as_ref(),
+        "remove did not return the value"
+    );
+    assert!(
+        storage.remove(&id(2, 1)).is_none(),
+        "remove of a removed key returned a value"
+    );
+    assert_matches_model(&storage, &model);
+
+    // Join
+    let mut other = GenIndexVecMap::<u32, EntityId<E>>::default();
+    for (index, generation) in [(0, 1), (2, 1), (7, 1), (7, 2), (9, 1)] {
+        other.insert(id(index, generation), index);
+    }
+    let mut joined: Vec<_> = storage
+        .iter()
+        .map_join(&other)
+        .map(|(key, (_, v))| (*key, v.clone()))
+        .collect();
+    joined.sort_by_key(|(key, _)| key.index());
+    let mut joined_rev: Vec<_> = other
+        .iter()
+        .map_join(&storage)
+        .map(|(key, (v, _))| (*key, v.clone()))
+        .collect();
+    joined_rev.sort_by_key(|(key, _)| key.index());
+    let expected: Vec<_> = model
+        .values()
+        .filter(|(key, _)| other.contains_key(key))
+        .cloned()
+        .collect();
+    assert_eq!(joined, expected, "join did not yield the common keys");
+    assert_eq!(
+        joined_rev, expected,
+        "join with the storage did not yield the common keys"
+    );
+
+    // Retain
+    storage.retain(|key, _| key.index() != 1);
+    model.remove(&1);
+    assert_matches_model(&storage, &model);
+
+    storage.clear();
+    model.clear();
+    assert_matches_model(&storage, &model);
+    assert!(
+        storage.insert(id(3, 1), value(3)).is_none(),
+        "insert after clear returned a value"
+    );
+}
+
+/// Asserts that a storage contains exactly the elements of a model keyed by index.
+fn assert_matches_model<E, S>(storage: &S, model: &BTreeMap<u32, (EntityId<E>, S::Value)>)
+where
+    S: Map<Key = EntityId<E>> + for<'a> IterableMap<'a>,
+    S::Value: PartialEq + fmt::Debug,
+{
+    assert_eq!(storage.len(), model.len(), "storage length does not match");
+    assert_eq!(
+        storage.is_empty(),
+        model.is_empty(),
+        "is_empty does not match"
+    );
+    for (key, v) in model.values() {
+        assert!(
+            storage.contains_key(key),
+            "contains_key of {:?} returned false",
+            key
+        );
+        assert_eq!(storage.get(key), Some(v), "get of {:?} does not match", key);
+    }
+    let mut elements: Vec<_> = storage.iter().collect();
+    elements.sort_by_key(|(key, _)| key.index());
+    assert!(
+        elements
+            .iter()
+            .map(|(key, v)| (*key, *v))
+            .eq(model.values().map(|(key, v)| (key, v))),
+        "iteration does not match"
+    );
+}
@@ -0,0 +1,497 @@
+//! Archetype tables, which group the components of entities sharing the same component set.
+//!
+//! Component storages keep each component type in its own storage, so iterating over multiple components
+//! needs a join, which checks every entity against every storage.
+//! [ArchetypeTables] instead stores the components of entities with the same set of component types
+//! together in a [Table], where each component type is a contiguous column.
+//! Iterating over a component set then visits the matching tables column by column, without any lookup.
+//! The tradeoff is that adding or removing a component moves all components of the entity to another table.
+//!
+//! Tables are selected per [Entity] type, by registering [ArchetypeTables] of the entity type as a resource,
+//! e.g. with [Archetypes::register_tables](super::Archetypes::register_tables).
+//!
+//! # Examples
+//! ```rust
+//! # use muds::{cons, Cons};
+//! # use muds::ecs::{Archetypes, Entity, Registry, Resources, storage::ArenaStorage, table::ArchetypeTables};
+//! struct E;
+//! impl Entity for E { type Storage = ArenaStorage<Self>; }
+//! struct Pos(u32);
+//! struct Vel(u32);
+//!
+//! let mut registry = Registry::default();
+//! registry.register_tables::<E>();
+//! let (a, b) = (registry.insert_archetype(E, ()), registry.insert_archetype(E, ()));
+//! {
+//!     let mut tables = registry.resource_mut::<ArchetypeTables<E>>();
+//!     tables.insert(a, Pos(0));
+//!     tables.insert(a, Vel(1));
+//!     tables.insert(b, Pos(0));
+//!
+//!     for (_ids, cons!(pos, vel)) in tables.query_mut::<Cons!(&mut [Pos], &[Vel])>() {
+//!         for (p, v) in pos.iter_mut().zip(vel) {
+//!             p.0 += v.0;
+//!         }
+//!     }
+//!     assert_eq!(tables.get::<Pos>(&a).unwrap().0, 1);
+//!     assert_eq!(tables.get::<Pos>(&b).unwrap().0, 0);
+//! }
+//!
+//! // Removing the archetype removes its components from the tables
+//! registry.remove_archetype(&a);
+//! assert!(!registry.resource::<ArchetypeTables<E>>().contains_key(&a));
+//! ```
+
+use super::{Entity, EntityId};
+use crate::collections::{GenIndexVecMap, Map};
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+use core::{
+    any::{Any, TypeId},
+    fmt, mem,
+};
+
+/// Storage of the components of an [Entity] type, grouped into [Table]s by component set.
+/// See the [module docs](self).
+pub struct ArchetypeTables<E: Entity> {
+    tables: Vec<Table<E>>,
+    /// Table index by its sorted component types.
+    index: BTreeMap<Vec<TypeId>, usize>,
+    locations: GenIndexVecMap<Location, EntityId<E>>,
+}
+
+/// Location of an entity in [ArchetypeTables].
+#[derive(Clone, Copy, Debug)]
+struct Location {
+    table: usize,
+    row: usize,
+}
+
+impl<E: Entity> ArchetypeTables<E> {
+    /// Creates an empty [ArchetypeTables].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            tables: Vec::new(),
+            index: BTreeMap::new(),
+            locations: GenIndexVecMap::default(),
+        }
+    }
+
+    /// Returns the number of entities with any component.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns `true` if no entity has any component.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the tables, one for each component set ever stored.
+    #[inline]
+    pub fn tables(&self) -> &[Table<E>] {
+        &self.tables
+    }
+
+    /// Returns `true` if the entity has any component.
+    #[inline]
+    pub fn contains_key(&self, id: &EntityId<E>) -> bool {
+        self.locations.contains_key(id)
+    }
+
+    /// Returns `true` if the entity has component `C`.
+    #[inline]
+    pub fn contains<C: Any>(&self, id: &EntityId<E>) -> bool {
+        self.get::<C>(id).is_some()
+    }
+
+    /// Returns a reference to component `C` of an entity.
+    pub fn get<C: Any>(&self, id: &EntityId<E>) -> Option<&C> {
+        let location = self.locations.get(id)?;
+        self.tables[location.table].column::<C>()?.get(location.row)
+    }
+
+    /// Returns a mutable reference to component `C` of an entity.
+    pub fn get_mut<C: Any>(&mut self, id: &EntityId<E>) -> Option<&mut C> {
+        let location = *self.locations.get(id)?;
+        self.tables[location.table]
+            .column_mut::<C>()?
+            .get_mut(location.row)
+    }
+
+    /// Inserts component `C` to an entity, returning the previous value if any.
+    /// If the entity does not have the component yet, all its components are moved to the table of the new set.
+    pub fn insert<C: Any>(&mut self, id: EntityId<E>, value: C) -> Option<C> {
+        let location = self.locations.get(&id).copied();
+        if let Some(location) = location {
+            if let Some(column) = self.tables[location.table].column_mut::<C>() {
+                return Some(mem::replace(&mut column[location.row], value));
+            }
+        }
+
+        let ty = TypeId::of::<C>();
+        let mut types = match location {
+            Some(location) => self.tables[location.table].types.clone(),
+            None => Vec::new(),
+        };
+        let column = match types.binary_search(&ty) {
+            Ok(i) | Err(i) => i,
+        };
+        types.insert(column, ty);
+        let table = match self.index.get(&types) {
+            Some(table) => *table,
+            None => {
+                let mut columns: Vec<Box<dyn Column>> = match location {
+                    Some(location) => self.tables[location.table]
+                        .columns
+                        .iter()
+                        .map(|column| column.empty())
+                        .collect(),
+                    None => Vec::new(),
+                };
+                columns.insert(column, Box::new(Vec::<C>::new()));
+                self.create_table(types, columns)
+            }
+        };
+
+        self.migrate(id, location, table, None);
+        if let Some(column) = self.tables[table].columns[column]
+            .as_any_mut()
+            .downcast_mut::<Vec<C>>()
+        {
+            column.push(value);
+        }
+        None
+    }
+
+    /// Removes component `C` from an entity, returning the value if any.
+    /// The remaining components of the entity are moved to the table of the new set.
+    pub fn remove<C: Any>(&mut self, id: &EntityId<E>) -> Option<C> {
+        let location = *self.locations.get(id)?;
+        let ty = TypeId::of::<C>();
+        let column = self.tables[location.table].column_index(ty)?;
+        let value = self.tables[location.table].columns[column]
+            .as_any_mut()
+            .downcast_mut::<Vec<C>>()?
+            .swap_remove(location.row);
+
+        let mut types = self.tables[location.table].types.clone();
+        types.remove(column);
+        if types.is_empty() {
+            self.detach(location, Some(ty));
+            self.locations.remove(id);
+            return Some(value);
+        }
+        let table = match self.index.get(&types) {
+            Some(table) => *table,
+            None => {
+                let mut columns: Vec<Box<dyn Column>> = self.tables[location.table]
+                    .columns
+                    .iter()
+                    .map(|column| column.empty())
+                    .collect();
+                columns.remove(column);
+                self.create_table(types, columns)
+            }
+        };
+        self.migrate(*id, Some(location), table, Some(ty));
+        Some(value)
+    }
+
+    /// Removes all components of an entity. Returns `true` if the entity had any component.
+    pub fn remove_entity(&mut self, id: &EntityId<E>) -> bool {
+        match self.locations.remove(id) {
+            Some(location) => {
+                self.detach(location, None);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over the entities with component `C`, table by table.
+    pub fn iter<C: Any>(&self) -> impl Iterator<Item = (EntityId<E>, &C)> + '_ {
+        self.tables
+            .iter()
+            .filter_map(|table| Some((table.entities(), table.column::<C>()?)))
+            .flat_map(|(ids, column)| ids.iter().copied().zip(column))
+    }
+
+    /// Returns an iterator over the non-empty tables containing all components of `Q`,
+    /// with their entities and component columns.
+    /// `Q` is a [Cons](crate::collections::Cons) of `&[C]` and `&mut [C]` column slices.
+    ///
+    /// # Panics
+    /// Panics if `Q` contains the same component type more than once.
+    pub fn query_mut<'a, Q: TableColumns<'a>>(
+        &'a mut self,
+    ) -> impl Iterator<Item = (&'a [EntityId<E>], Q)> + 'a {
+        self.tables
+            .iter_mut()
+            .filter(|table| !table.is_empty())
+            .filter_map(|table| table.query_mut::<Q>())
+    }
+
+    fn create_table(&mut self, types: Vec<TypeId>, columns: Vec<Box<dyn Column>>) -> usize {
+        let table = self.tables.len();
+        self.index.insert(types.clone(), table);
+        self.tables.push(Table {
+            types,
+            entities: Vec::new(),
+            columns,
+        });
+        table
+    }
+
+    /// Moves the components of an entity from its current location (if any) to the end of given table.
+    /// Components not in the destination table are dropped, except the `taken` column which is already removed.
+    fn migrate(
+        &mut self,
+        id: EntityId<E>,
+        from: Option<Location>,
+        table: usize,
+        taken: Option<TypeId>,
+    ) {
+        if let Some(from) = from {
+            let (src, dst) = pair_mut(&mut self.tables, from.table, table);
+            for (ty, column) in src.types.iter().zip(src.columns.iter_mut()) {
+                if Some(*ty) == taken {
+                    continue;
+                }
+                match dst.column_index(*ty) {
+                    Some(i) => column.move_row(from.row, &mut *dst.columns[i]),
+                    None => column.swap_remove(from.row),
+                }
+            }
+            self.remove_row(from);
+        }
+        let row = self.tables[table].entities.len();
+        self.tables[table].entities.push(id);
+        self.locations.insert(id, Location { table, row });
+    }
+
+    /// Drops the components of an entity, except the `taken` column which is already removed.
+    fn detach(&mut self, location: Location, taken: Option<TypeId>) {
+        let table = &mut self.tables[location.table];
+        for (ty, column) in table.types.iter().zip(table.columns.iter_mut()) {
+            if Some(*ty) != taken {
+                column.swap_remove(location.row);
+            }
+        }
+        self.remove_row(location);
+    }
+
+    /// Removes an entity row whose components are already removed, updating the entity swapped into the row.
+    fn remove_row(&mut self, location: Location) {
+        let entities = &mut self.tables[location.table].entities;
+        entities.swap_remove(location.row);
+        if let Some(moved) = entities.get(location.row) {
+            if let Some(moved) = self.locations.get_mut(moved) {
+                moved.row = location.row;
+            }
+        }
+    }
+}
+
+impl<E: Entity> Default for ArchetypeTables<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> fmt::Debug for ArchetypeTables<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchetypeTables")
+            .field("tables", &self.tables)
+            .finish()
+    }
+}
+
+/// A table of the entities sharing the same component set, with a column for each component type.
+pub struct Table<E: Entity> {
+    /// Sorted component types.
+    types: Vec<TypeId>,
+    entities: Vec<EntityId<E>>,
+    /// Column of each component type, in the same order as `types`.
+    columns: Vec<Box<dyn Column>>,
+}
+
+impl<E: Entity> Table<E> {
+    /// Returns the number of entities in this table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if this table has no entity.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns the sorted component types of this table.
+    #[inline]
+    pub fn component_types(&self) -> &[TypeId] {
+        &self.types
+    }
+
+    /// Returns the entities of this table, in the same order as the component columns.
+    #[inline]
+    pub fn entities(&self) -> &[EntityId<E>] {
+        &self.entities
+    }
+
+    /// Returns `true` if this table has a column of component `C`.
+    #[inline]
+    pub fn contains<C: Any>(&self) -> bool {
+        self.column_index(TypeId::of::<C>()).is_some()
+    }
+
+    /// Returns the column of component `C`.
+    pub fn column<C: Any>(&self) -> Option<&[C]> {
+        let column = self.column_index(TypeId::of::<C>())?;
+        Some(self.columns[column].as_any().downcast_ref::<Vec<C>>()?)
+    }
+
+    /// Returns the column of component `C` mutably.
+    pub fn column_mut<C: Any>(&mut self) -> Option<&mut [C]> {
+        let column = self.column_index(TypeId::of::<C>())?;
+        Some(self.columns[column].as_any_mut().downcast_mut::<Vec<C>>()?)
+    }
+
+    /// Returns the entities and the columns of `Q`, or `None` if this table does not have all the columns.
+    /// See [ArchetypeTables::query_mut].
+    pub fn query_mut<'a, Q: TableColumns<'a>>(&'a mut self) -> Option<(&'a [EntityId<E>], Q)> {
+        let mut columns: Vec<(TypeId, Option<&'a mut dyn Any>)> = self
+            .types
+            .iter()
+            .copied()
+            .zip(
+                self.columns
+                    .iter_mut()
+                    .map(|column| Some(column.as_any_mut())),
+            )
+            .collect();
+        let query = Q::take(&mut columns)?;
+        Some((&self.entities, query))
+    }
+
+    #[inline]
+    fn column_index(&self, ty: TypeId) -> Option<usize> {
+        self.types.binary_search(&ty).ok()
+    }
+}
+
+impl<E: Entity> fmt::Debug for Table<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("types", &self.types)
+            .field("entities", &self.entities)
+            .finish()
+    }
+}
+
+/// A [Cons](crate::collections::Cons) of table column slices, i.e. `&[C]` or `&mut [C]`, to query from a [Table].
+pub trait TableColumns<'a>: Sized {
+    /// Takes the columns of self from the columns of a table, keyed by component type.
+    /// Returns `None` if any column is missing.
+    ///
+    /// # Panics
+    /// Panics if a column is taken more than once.
+    fn take(columns: &mut [(TypeId, Option<&'a mut dyn Any>)]) -> Option<Self>;
+}
+
+impl<'a> TableColumns<'a> for () {
+    #[inline]
+    fn take(_columns: &mut [(TypeId, Option<&'a mut dyn Any>)]) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl<'a, C: Any, Tail: TableColumns<'a>> TableColumns<'a> for (&'a mut [C], Tail) {
+    fn take(columns: &mut [(TypeId, Option<&'a mut dyn Any>)]) -> Option<Self> {
+        let column = take_column::<C>(columns)?;
+        Some((column.as_mut_slice(), Tail::take(columns)?))
+    }
+}
+
+impl<'a, C: Any, Tail: TableColumns<'a>> TableColumns<'a> for (&'a [C], Tail) {
+    fn take(columns: &mut [(TypeId, Option<&'a mut dyn Any>)]) -> Option<Self> {
+        let column = take_column::<C>(columns)?;
+        Some((column.as_slice(), Tail::take(columns)?))
+    }
+}
+
+/// Takes the column of component `C`, or returns `None` if the column does not exist.
+fn take_column<'a, C: Any>(
+    columns: &mut [(TypeId, Option<&'a mut dyn Any>)],
+) -> Option<&'a mut Vec<C>> {
+    let (_, column) = columns
+        .iter_mut()
+        .find(|(ty, _)| *ty == TypeId::of::<C>())?;
+    column
+        .take()
+        .expect("duplicated component in table query")
+        .downcast_mut::<Vec<C>>()
+}
+
+/// Returns mutable references to 2 different tables.
+fn pair_mut<T>(tables: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    assert_ne!(a, b);
+    if a < b {
+        let (left, right) = tables.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = tables.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+/// A type-erased column of components.
+trait Column: Any {
+    /// Creates an empty column of the same component type.
+    fn empty(&self) -> Box<dyn Column>;
+
+    /// Drops the component at given row, replacing it with the last component.
+    fn swap_remove(&mut self, row: usize);
+
+    /// Moves the component at given row to the end of a column of the same component type,
+    /// replacing it with the last component.
+    fn move_row(&mut self, row: usize, dst: &mut dyn Column);
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C: Any> Column for Vec<C> {
+    #[inline]
+    fn empty(&self) -> Box<dyn Column> {
+        Box::new(Vec::<C>::new())
+    }
+
+    #[inline]
+    fn swap_remove(&mut self, row: usize) {
+        Vec::swap_remove(self, row);
+    }
+
+    fn move_row(&mut self, row: usize, dst: &mut dyn Column) {
+        let value = Vec::swap_remove(self, row);
+        if let Some(dst) = dst.as_any_mut().downcast_mut::<Vec<C>>() {
+            dst.push(value);
+        }
+    }
+
+    #[inline]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,311 @@
+//! Hierarchical transforms, using [munum] types.
+//!
+//! Each entity positioned in the world has a [LocalTransform] relative to its [Parent](super::hierarchy::Parent).
+//! [TransformPropagation] walks the hierarchy from its roots and computes the [GlobalTransform] of each entity,
+//! i.e. the world matrix of its parent multiplied by its local matrix.
+//! [LocalTransform] is stored in a [Tracked] storage, so that only the subtrees of changed or reparented entities
+//! are recomputed. Removals of [LocalTransform] are kept until [Registry::maintain], which should be called
+//! regularly, e.g. once per frame after running the system.
+//!
+//! # Examples
+//! ```rust
+//! # use muds::prelude::*;
+//! # use muds::collections::{Map, MapMut};
+//! # use muds::ecs::{hierarchy::attach, storage::ArenaStorage};
+//! use muds::ecs::transform::*;
+//! use munum::vec3;
+//!
+//! struct E;
+//! impl Entity for E { type Storage = ArenaStorage<Self>; }
+//!
+//! let mut registry = Registry::default();
+//! registry.register_entity::<E>();
+//! register_transform::<E>(&mut registry);
+//! let (a, b, c) = {
+//!     let mut entities = registry.entities_mut::<E>();
+//!     (entities.insert(E), entities.insert(E), entities.insert(E))
+//! };
+//! {
+//!     let mut locals = registry.components_mut::<E, LocalTransform>();
+//!     locals.insert(a, LocalTransform::from_translation(vec3(1., 0., 0.)));
+//!     locals.insert(b, LocalTransform::from_translation(vec3(0., 2., 0.)));
+//!     locals.insert(c, LocalTransform::from_translation(vec3(0., 0., 3.)));
+//! }
+//! attach(&registry, b, a).unwrap();
+//! attach(&registry, c, b).unwrap();
+//!
+//! let mut propagation = TransformPropagation::<E>::new();
+//! assert_eq!(propagation.run(&registry), 3);
+//! assert_eq!(registry.components::<E, GlobalTransform>().get(&c).unwrap().translation(), vec3(1., 2., 3.));
+//!
+//! // Only the changed subtree is recomputed
+//! registry.components_mut::<E, LocalTransform>().get_mut(&b).unwrap().translation = vec3(0., 5., 0.);
+//! assert_eq!(propagation.run(&registry), 2);
+//! assert_eq!(registry.components::<E, GlobalTransform>().get(&c).unwrap().translation(), vec3(1., 5., 3.));
+//! assert_eq!(propagation.run(&registry), 0);
+//! ```
+
+use super::{
+    hierarchy::{register_hierarchy, Children, Parent},
+    storage::{Tracked, VecStorage},
+    Component, Components, Entity, EntityId, Registry,
+};
+use crate::collections::{GenIndexVecMap, IterableMap, Map};
+use alloc::vec::Vec;
+use core::{any::Any, fmt};
+use munum::{transform, vec3, Mat4, Quaternion, Vec3};
+
+/// Component of the transform of an entity relative to its parent, or to the world for roots.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalTransform {
+    /// Translation.
+    pub translation: Vec3,
+    /// Rotation.
+    pub rotation: Quaternion,
+    /// Scale.
+    pub scale: Vec3,
+}
+
+impl<E: Entity + Any> Component<E> for LocalTransform {
+    type Storage = Tracked<E, VecStorage<E, Self>>;
+}
+
+impl LocalTransform {
+    /// Returns the identity transform.
+    #[inline]
+    pub fn identity() -> Self {
+        Self {
+            translation: Vec3::default(),
+            rotation: Quaternion::identity(),
+            scale: vec3(1., 1., 1.),
+        }
+    }
+
+    /// Returns a transform of given translation.
+    #[inline]
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns a transform of given rotation.
+    #[inline]
+    pub fn from_rotation(rotation: Quaternion) -> Self {
+        Self {
+            rotation,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns a transform of given scale.
+    #[inline]
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::identity()
+        }
+    }
+
+    /// Returns the transformation matrix, which scales, then rotates, then translates.
+    #[inline]
+    pub fn matrix(&self) -> Mat4 {
+        transform::transformation(self.translation, self.rotation, self.scale)
+    }
+}
+
+impl Default for LocalTransform {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Component of the world matrix of an entity, computed by [TransformPropagation].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GlobalTransform(pub Mat4);
+
+impl<E: Entity + Any> Component<E> for GlobalTransform {
+    type Storage = VecStorage<E, Self>;
+}
+
+impl GlobalTransform {
+    /// Returns the world position, i.e. the translation of the matrix.
+    #[inline]
+    pub fn translation(&self) -> Vec3 {
+        vec3(self.0[(0, 3)], self.0[(1, 3)], self.0[(2, 3)])
+    }
+}
+
+/// Registers the [LocalTransform] and [GlobalTransform] components of an entity type,
+/// together with its hierarchy components.
+pub fn register_transform<E: Entity + Any>(registry: &mut Registry) {
+    register_hierarchy::<E>(registry);
+    registry.register_component::<E, LocalTransform>();
+    registry.register_component::<E, GlobalTransform>();
+}
+
+/// System propagating [LocalTransform]s down the hierarchy into [GlobalTransform]s.
+///
+/// An entity is recomputed when its [LocalTransform] changed or its parent changed since the last run,
+/// or when its parent was recomputed. Entities without [LocalTransform] are not part of the transform hierarchy:
+/// their children with a [LocalTransform] are treated as roots.
+/// The [GlobalTransform] of an entity is removed when its [LocalTransform] is removed.
+pub struct TransformPropagation<E: Entity> {
+    /// Tick of the [LocalTransform] storage at the last run.
+    since: u64,
+    /// Parent of each entity within the transform hierarchy at the last run.
+    parents: GenIndexVecMap<Option<EntityId<E>>, EntityId<E>>,
+}
+
+impl<E: Entity + Any> TransformPropagation<E> {
+    /// Creates the system, which computes all [GlobalTransform]s on its first run.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            since: 0,
+            parents: GenIndexVecMap::default(),
+        }
+    }
+
+    /// Updates the [GlobalTransform]s of changed entities and their descendants.
+    /// Returns the number of recomputed entities.
+    /// The [GlobalTransform] storage is only borrowed mutably if any of them needs to be updated or removed.
+    ///
+    /// Removals of [LocalTransform] are read from its [Tracked] storage, which keeps them until
+    /// [Registry::maintain]. The system should run at least once between maintenances, e.g. once per frame.
+    ///
+    /// # Panics
+    /// Panics if any of the transform or hierarchy storages is mutably borrowed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use muds::prelude::*;
+    /// # use muds::collections::{Map, MapMut};
+    /// # use muds::ecs::{hierarchy::attach, storage::ArenaStorage};
+    /// use muds::ecs::transform::*;
+    /// use munum::vec3;
+    ///
+    /// struct E;
+    /// impl Entity for E { type Storage = ArenaStorage<Self>; }
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.register_entity::<E>();
+    /// register_transform::<E>(&mut registry);
+    /// let (a, b) = {
+    ///     let mut entities = registry.entities_mut::<E>();
+    ///     (entities.insert(E), entities.insert(E))
+    /// };
+    /// {
+    ///     let mut locals = registry.components_mut::<E, LocalTransform>();
+    ///     locals.insert(a, LocalTransform::from_translation(vec3(1., 0., 0.)));
+    ///     locals.insert(b, LocalTransform::from_translation(vec3(0., 2., 0.)));
+    /// }
+    /// attach(&registry, b, a).unwrap();
+    ///
+    /// let mut propagation = TransformPropagation::<E>::new();
+    /// assert_eq!(propagation.run(&registry), 2);
+    /// registry.maintain();
+    ///
+    /// // Unchanged transforms do not borrow the global transforms mutably
+    /// let version = registry.version(&RegistryKey::from_type::<(E, GlobalTransform)>());
+    /// assert_eq!(propagation.run(&registry), 0);
+    /// assert_eq!(registry.version(&RegistryKey::from_type::<(E, GlobalTransform)>()), version);
+    ///
+    /// // Without a transformed parent, b becomes a root
+    /// registry.components_mut::<E, LocalTransform>().remove(&a);
+    /// assert_eq!(propagation.run(&registry), 1);
+    /// registry.maintain();
+    /// assert!(registry.components::<E, GlobalTransform>().get(&a).is_none());
+    /// assert_eq!(registry.components::<E, GlobalTransform>().get(&b).unwrap().translation(), vec3(0., 2., 0.));
+    /// ```
+    pub fn run(&mut self, registry: &Registry) -> usize {
+        let locals = registry.components::<E, LocalTransform>();
+        let parents = registry.components::<E, Parent<E>>();
+        let children = registry.components::<E, Children<E>>();
+        let globals = registry.components::<E, GlobalTransform>();
+
+        let removed = locals
+            .removed(self.since)
+            .filter(|id| !locals.contains_key(id) && globals.contains_key(id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        // Parent of an entity within the transform hierarchy, i.e. only if the parent has a LocalTransform
+        let parent_of = |id: &EntityId<E>| {
+            parents
+                .get(id)
+                .map(|Parent(parent)| *parent)
+                .filter(|parent| locals.contains_key(parent))
+        };
+
+        // (entity, world matrix of its parent, whether its parent was recomputed)
+        let mut stack = locals
+            .iter()
+            .filter(|(id, _)| parent_of(id).is_none())
+            .map(|(id, _)| (*id, Mat4::identity(), false))
+            .collect::<Vec<_>>();
+
+        // (entity, recomputed world matrix, parent)
+        let mut updates = Vec::new();
+        while let Some((id, parent_matrix, parent_dirty)) = stack.pop() {
+            let local = match locals.get(&id) {
+                Some(local) => local,
+                None => continue,
+            };
+            let parent = parent_of(&id);
+            let dirty = parent_dirty
+                || locals.is_changed(&id, self.since)
+                || self.parents.get(&id) != Some(&parent)
+                || !globals.contains_key(&id);
+
+            let matrix = match globals.get(&id) {
+                Some(GlobalTransform(matrix)) if !dirty => *matrix,
+                _ => {
+                    let matrix = parent_matrix * local.matrix();
+                    updates.push((id, matrix, parent));
+                    matrix
+                }
+            };
+
+            if let Some(children) = children.get(&id) {
+                stack.extend(children.iter().rev().map(|child| (*child, matrix, dirty)));
+            }
+        }
+
+        self.since = locals.tick();
+        for id in &removed {
+            self.parents.remove(id);
+        }
+        if removed.is_empty() && updates.is_empty() {
+            return 0;
+        }
+
+        drop(globals);
+        let mut globals = registry.components_mut::<E, GlobalTransform>();
+        for id in &removed {
+            globals.remove(id);
+        }
+        for (id, matrix, parent) in &updates {
+            globals.insert(*id, GlobalTransform(*matrix));
+            self.parents.insert(*id, *parent);
+        }
+        updates.len()
+    }
+}
+
+impl<E: Entity + Any> Default for TransformPropagation<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Entity> fmt::Debug for TransformPropagation<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformPropagation")
+            .field("since", &self.since)
+            .finish()
+    }
+}
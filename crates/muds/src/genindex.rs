@@ -7,7 +7,7 @@ use core::{
     hash::{Hash, Hasher},
     marker::PhantomData,
 };
-use num::Bounded;
+use num::{Bounded, NumCast};
 
 /// An index with generation that can be used as a weak reference to array values.
 /// The generation part allows indices to be reused without suffering from [ABA problem](https://en.wikipedia.org/wiki/ABA_problem),
@@ -209,6 +209,141 @@ impl From<(u32, u32)> for IndexU64 {
 
 // endregion: IndexU64
 
+// region: IndexU32
+
+/// Generation type of [IndexU32], which is narrower than 32 bits. Implemented for `u8` and `u16` only.
+pub trait IndexU32Generation: Bounded + UnsignedNum + sealed::Sealed {}
+
+impl IndexU32Generation for u8 {}
+impl IndexU32Generation for u16 {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+}
+
+/// A compact [GenIndex] that is stored as u32, with generation of type `G` in the high bits
+/// and index in the remaining low bits, i.e. 24bit index and 8bit generation by default.
+///
+/// `G` is `u8` or `u16`, so that there are bits left for the index. See [IndexU32Generation].
+///
+/// # Examples
+/// ```rust
+/// # use muds::{GenIndex, IndexU32};
+/// let idx = <IndexU32>::from_raw_parts(123, 45);
+/// assert_eq!((idx.index(), idx.generation()), (123, 45));
+/// assert_eq!(<IndexU32>::max_index(), (1 << 24) - 1);
+/// assert_eq!(IndexU32::<u16>::max_index(), (1 << 16) - 1);
+///
+/// // Used as the index type of allocators and storages
+/// # use muds::collections::GenIndexAllocator;
+/// let mut allocator = GenIndexAllocator::<IndexU32>::new();
+/// let idx = allocator.create();
+/// allocator.remove(&idx);
+/// let idx2 = allocator.create();
+/// assert_eq!((idx2.index(), idx2.generation()), (idx.index(), idx.generation() + 1));
+/// ```
+///
+/// Wider generation types leave no bits for the index, and are rejected:
+/// ```compile_fail
+/// # use muds::IndexU32;
+/// let idx = IndexU32::<u32>::default();
+/// ```
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+#[repr(transparent)]
+pub struct IndexU32<G: IndexU32Generation = u8>(u32, PhantomData<G>);
+
+impl<G: IndexU32Generation> Default for IndexU32<G> {
+    #[inline]
+    fn default() -> Self {
+        Self(0, PhantomData)
+    }
+}
+
+impl<G: IndexU32Generation> IndexU32<G> {
+    /// Returns the maximum index value.
+    #[inline]
+    pub fn max_index() -> u32 {
+        u32::MAX >> Self::generation_bits()
+    }
+
+    #[inline]
+    fn generation_bits() -> u32 {
+        (core::mem::size_of::<G>() * 8) as u32
+    }
+}
+
+impl<G: IndexU32Generation> GenIndex for IndexU32<G> {
+    type Index = u32;
+    type Generation = G;
+
+    #[inline]
+    fn max_generation() -> Self::Generation {
+        G::max_value()
+    }
+
+    /// Creates a new `IndexU32` from its raw parts.
+    ///
+    /// # Panics
+    /// Panics if the index exceeds [IndexU32::max_index].
+    #[inline]
+    fn from_raw_parts(index: Self::Index, generation: Self::Generation) -> Self {
+        assert!(index <= Self::max_index(), "index out of bounds");
+        let generation = generation.to_u32().unwrap_or(0);
+        Self(
+            index | (generation << (32 - Self::generation_bits())),
+            PhantomData,
+        )
+    }
+
+    #[inline]
+    fn index(&self) -> Self::Index {
+        self.0 & Self::max_index()
+    }
+
+    #[inline]
+    fn generation(&self) -> Self::Generation {
+        NumCast::from(self.0 >> (32 - Self::generation_bits())).unwrap_or_else(G::zero)
+    }
+}
+
+impl<G: IndexU32Generation> From<IndexU32<G>> for (u32, G) {
+    #[inline]
+    fn from(idx: IndexU32<G>) -> Self {
+        (idx.index(), idx.generation())
+    }
+}
+
+impl<G: IndexU32Generation> From<(u32, G)> for IndexU32<G> {
+    #[inline]
+    fn from((index, generation): (u32, G)) -> Self {
+        IndexU32::from_raw_parts(index, generation)
+    }
+}
+
+impl<G: IndexU32Generation> From<IndexU32<G>> for u32 {
+    #[inline]
+    fn from(idx: IndexU32<G>) -> Self {
+        idx.0
+    }
+}
+
+impl<G: IndexU32Generation> From<u32> for IndexU32<G> {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self(value, PhantomData)
+    }
+}
+
+// endregion: IndexU32
+
 // region: TypedIndex
 
 /// A [GenIndex] newtype.
@@ -358,6 +493,34 @@ mod tests {
         assert_eq!(json, expected_json);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_indexu32_deserialize() {
+        use crate::{GenIndex, IndexU32};
+        use serde_json::{json, Value};
+
+        let expected_index = <IndexU32>::from_raw_parts(123, 45);
+        let json: Value = json!(45u32 << 24 | 123);
+
+        let index: IndexU32 = serde_json::from_value(json).unwrap();
+
+        assert_eq!(index, expected_index);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_indexu32_serialize() {
+        use crate::{GenIndex, IndexU32};
+        use serde_json::{json, Value};
+
+        let index = IndexU32::<u16>::from_raw_parts(123, 456);
+        let expected_json: Value = json!(456u32 << 16 | 123);
+
+        let json: Value = serde_json::to_value(index).unwrap();
+
+        assert_eq!(json, expected_json);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_index_deserialize() {
@@ -11,6 +11,7 @@ pub mod collections;
 pub mod ecs;
 
 mod genindex;
+mod hash;
 mod markers;
 
 pub use genindex::*;
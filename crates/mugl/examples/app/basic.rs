@@ -64,6 +64,7 @@ impl App for BasicExample {
             primitive: Default::default(),
             depth_stencil: Default::default(),
             multisample: Default::default(),
+            multiview: None,
         });
 
         let pass = device.create_render_pass(RenderPassDescriptor::Default {
@@ -84,7 +85,7 @@ impl App for BasicExample {
         &self.device
     }
 
-    fn render(&mut self, _t: f64) -> bool {
+    fn render(&mut self, _alpha: f64) -> bool {
         {
             let encoder = self.device.render(&self.pass);
             encoder.pipeline(&self.pipeline);
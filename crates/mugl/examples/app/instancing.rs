@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use mugl::prelude::*;
+use mugl::{instance::InstanceBuffer, prelude::*};
 
 use crate::common::App;
 
@@ -11,7 +11,7 @@ const INDICES: &[u16] = &[0, 1, 2, 0]; // last index is a padding
 const POSITIONS: &[f32] = &[0.0, -0.05, -0.05, 0.0, 0.05, 0.05];
 
 static mut OFFSET_COLORS: &mut [f32] = &mut [0.; N * N * 5];
-static mut ANGLES: &mut [f32] = &mut [0.; N * N * 5];
+static mut ANGLES: &mut [f32] = &mut [0.; N * N];
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
@@ -28,10 +28,12 @@ pub struct InstancingExample {
     pass: RenderPass,
     indices: Buffer,
     vertices: Buffer,
-    offset_colors: Buffer,
-    angles: Buffer,
+    offset_colors: InstanceBuffer<DefaultGPU>,
+    angles: InstanceBuffer<DefaultGPU>,
     bind_group: BindGroup,
     ambient: Buffer,
+    time: f64,
+    prev_time: f64,
 }
 
 #[async_trait(?Send)]
@@ -53,16 +55,20 @@ impl App for InstancingExample {
 
         unsafe { generate_offset_colors() };
         let offset_colors_data = bytemuck::cast_slice(unsafe { OFFSET_COLORS });
-        let offset_colors = device.create_buffer(BufferDescriptor {
-            usage: BufferUsage::VERTEX,
-            size: offset_colors_data.len() as BufferSize,
-        });
-        device.write_buffer(&offset_colors, 0, offset_colors_data);
-
-        let angles = device.create_buffer(BufferDescriptor {
-            usage: BufferUsage::VERTEX | BufferUsage::STREAM,
-            size: bytemuck::cast_slice::<f32, u8>(unsafe { ANGLES }).len() as BufferSize,
-        });
+        let mut offset_colors = InstanceBuffer::new(
+            &device,
+            std::mem::size_of::<[f32; 5]>() as BufferSize,
+            INSTANCE_COUNT,
+            BufferUsage::empty(),
+        );
+        offset_colors.write(&device, offset_colors_data);
+
+        let angles = InstanceBuffer::new(
+            &device,
+            std::mem::size_of::<f32>() as BufferSize,
+            INSTANCE_COUNT,
+            BufferUsage::STREAM,
+        );
 
         let ambient_size = std::mem::size_of::<Data>() as BufferSize;
         let ambient = device.create_buffer(BufferDescriptor {
@@ -117,40 +123,31 @@ impl App for InstancingExample {
             vertex,
             fragment,
             buffers: &[
-                VertexBufferLayout {
-                    stride: std::mem::size_of::<[f32; 2]>() as BufferSize,
-                    step_mode: VertexStepMode::Vertex,
-                    attributes: &[VertexAttribute {
+                VertexBufferLayout::per_vertex(
+                    std::mem::size_of::<[f32; 2]>() as BufferSize,
+                    &[VertexAttribute {
                         offset: 0,
                         shader_location: 0,
                         format: VertexFormat::F32x2,
                     }],
-                },
-                VertexBufferLayout {
-                    stride: std::mem::size_of::<[f32; 5]>() as BufferSize,
-                    step_mode: VertexStepMode::Instance,
-                    attributes: &[
-                        VertexAttribute {
-                            offset: 0,
-                            shader_location: 1,
-                            format: VertexFormat::F32x2,
-                        },
-                        VertexAttribute {
-                            offset: std::mem::size_of::<[f32; 2]>() as BufferSize,
-                            shader_location: 2,
-                            format: VertexFormat::F32x3,
-                        },
-                    ],
-                },
-                VertexBufferLayout {
-                    stride: std::mem::size_of::<f32>() as BufferSize,
-                    step_mode: VertexStepMode::Instance,
-                    attributes: &[VertexAttribute {
+                ),
+                offset_colors.layout(&[
+                    VertexAttribute {
                         offset: 0,
-                        shader_location: 3,
-                        format: VertexFormat::F32,
-                    }],
-                },
+                        shader_location: 1,
+                        format: VertexFormat::F32x2,
+                    },
+                    VertexAttribute {
+                        offset: std::mem::size_of::<[f32; 2]>() as BufferSize,
+                        shader_location: 2,
+                        format: VertexFormat::F32x3,
+                    },
+                ]),
+                angles.layout(&[VertexAttribute {
+                    offset: 0,
+                    shader_location: 3,
+                    format: VertexFormat::F32,
+                }]),
             ],
             bind_groups: &[&layout],
             targets: Default::default(),
@@ -160,6 +157,7 @@ impl App for InstancingExample {
             },
             depth_stencil: None,
             multisample: Default::default(),
+            multiview: None,
         });
 
         let pass = device.create_render_pass(RenderPassDescriptor::Default {
@@ -178,6 +176,8 @@ impl App for InstancingExample {
             angles,
             ambient,
             bind_group,
+            time: 0.,
+            prev_time: 0.,
         }
     }
 
@@ -185,8 +185,13 @@ impl App for InstancingExample {
         &self.device
     }
 
-    fn render(&mut self, t: f64) -> bool {
-        let t = t as f32;
+    fn update(&mut self, dt: f64) {
+        self.prev_time = self.time;
+        self.time += dt;
+    }
+
+    fn render(&mut self, alpha: f64) -> bool {
+        let t = (self.prev_time + (self.time - self.prev_time) * alpha) as f32;
 
         for i in 0..(N * N) {
             unsafe {
@@ -194,16 +199,14 @@ impl App for InstancingExample {
                 ANGLES[i] -= (ANGLES[i] / PI_2).trunc() * PI_2;
             }
         }
-        self
-            .device
-            .write_buffer(&self.angles, 0, bytemuck::cast_slice(unsafe { ANGLES }));
+        self.angles
+            .write(&self.device, bytemuck::cast_slice(unsafe { ANGLES }));
 
         let a = f32::sin(t) / 2.0;
         let data = &[Data {
             ambient: Color::<f32>(a, a, a, 1.0),
         }];
-        self
-            .device
+        self.device
             .write_buffer(&self.ambient, 0, bytemuck::cast_slice(data));
 
         {
@@ -211,10 +214,10 @@ impl App for InstancingExample {
             encoder.pipeline(&self.pipeline);
             encoder.index(&self.indices);
             encoder.vertex(0, &self.vertices, 0);
-            encoder.vertex(1, &self.offset_colors, 0);
-            encoder.vertex(2, &self.angles, 0);
+            self.offset_colors.bind(&encoder, 1);
+            self.angles.bind(&encoder, 2);
             encoder.bind_group(0, &self.bind_group, &[]);
-            encoder.draw_indexed(0..3, 0..INSTANCE_COUNT);
+            encoder.draw_indexed(0..3, self.angles.instances());
             encoder.submit();
         }
         self.device.present();
@@ -23,6 +23,8 @@ pub struct StencilExample {
     cube: RenderBundle,
     outline: RenderBundle,
     sky: RenderBundle,
+    time: f64,
+    prev_time: f64,
     #[allow(dead_code)]
     image_loaded: bool,
     #[cfg(all(target_family = "wasm", feature = "backend-webgl"))]
@@ -141,6 +143,8 @@ impl App for StencilExample {
             cube,
             outline,
             sky,
+            time: 0.,
+            prev_time: 0.,
             image_loaded: false,
             #[cfg(all(target_family = "wasm", feature = "backend-webgl"))]
             image: mugl::webgl::ImageSource::from_uri("airplane.png"),
@@ -151,8 +155,13 @@ impl App for StencilExample {
         &self.device
     }
 
-    fn render(&mut self, t: f64) -> bool {
-        let t = t as f32;
+    fn update(&mut self, dt: f64) {
+        self.prev_time = self.time;
+        self.time += dt;
+    }
+
+    fn render(&mut self, alpha: f64) -> bool {
+        let t = (self.prev_time + (self.time - self.prev_time) * alpha) as f32;
 
         #[cfg(all(target_family = "wasm", feature = "backend-webgl"))]
         if !self.image_loaded && self.image.size().0 > 0 {
@@ -304,6 +313,7 @@ impl RenderBundle {
                 ..Default::default()
             }),
             multisample: Default::default(),
+            multiview: None,
         });
 
         let indices_data = bytemuck::cast_slice(CUBE_INDICES);
@@ -32,11 +32,18 @@ pub trait App {
     where
         Self: Sized,
     {
-        use mugl::webgl::{WebGL, WebGL2Features, WebGLContextAttribute};
+        use mugl::{
+            webgl::{WebGL, WebGL2Features, WebGLContextAttribute, WebGLSurfaceDescriptor},
+            TextureFormat,
+        };
         WebGL::request_device(
             &canvas,
-            WebGLContextAttribute::default() | WebGLContextAttribute::STENCIL,
+            WebGLContextAttribute::default(),
             WebGL2Features::all(),
+            WebGLSurfaceDescriptor {
+                depth_stencil_format: Some(TextureFormat::DEPTH24STENCIL8),
+                sample_count: 4,
+            },
         )
     }
 
@@ -46,7 +53,12 @@ pub trait App {
 
     fn device(&self) -> &mugl::Device;
 
-    fn render(&mut self, _t: f64) -> bool {
+    /// Advances the simulation by a fixed timestep of `dt` seconds.
+    fn update(&mut self, _dt: f64) {}
+
+    /// Renders a frame, where `alpha` in [0, 1) is the fraction of a timestep elapsed since the last update,
+    /// for interpolating between the last 2 simulation states. Returns false to stop rendering.
+    fn render(&mut self, _alpha: f64) -> bool {
         false
     }
 
@@ -55,3 +67,82 @@ pub trait App {
         self.device().resize_surface(new_size);
     }
 }
+
+/// Drives an [App] with fixed timestep updates, decoupled from the frame rate, and interpolated rendering.
+pub struct AppRunner {
+    app: Box<dyn App>,
+    step: f64,
+    max_steps: u32,
+    accumulator: f64,
+    last_time: Option<f64>,
+    paused: bool,
+}
+
+impl AppRunner {
+    /// Default timestep of 60 updates per second.
+    pub const DEFAULT_STEP: f64 = 1. / 60.;
+
+    /// Default maximum number of updates per frame.
+    pub const DEFAULT_MAX_STEPS: u32 = 10;
+
+    pub fn new(app: Box<dyn App>) -> Self {
+        Self::with_step(app, Self::DEFAULT_STEP, Self::DEFAULT_MAX_STEPS)
+    }
+
+    /// Creates a runner with given timestep in seconds, and maximum number of updates per frame.
+    /// Time beyond the maximum updates is dropped, so that a slow frame does not cause ever more updates to catch up.
+    pub fn with_step(app: Box<dyn App>, step: f64, max_steps: u32) -> Self {
+        Self {
+            app,
+            step,
+            max_steps,
+            accumulator: 0.,
+            last_time: None,
+            paused: false,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses updates. Frames are still rendered with the last simulation state.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Resumes updates, without catching up the time elapsed while paused.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.last_time = None;
+    }
+
+    /// Runs the updates due by time `t` in seconds, then renders a frame. Returns false to stop rendering.
+    pub fn frame(&mut self, t: f64) -> bool {
+        let dt = match self.last_time {
+            Some(last_time) => (t - last_time).max(0.),
+            None => 0.,
+        };
+        self.last_time = Some(t);
+
+        if !self.paused {
+            self.accumulator += dt;
+            let mut steps = 0;
+            while self.accumulator >= self.step {
+                if steps >= self.max_steps {
+                    self.accumulator %= self.step;
+                    break;
+                }
+                self.app.update(self.step);
+                self.accumulator -= self.step;
+                steps += 1;
+            }
+        }
+
+        self.app.render(self.accumulator / self.step)
+    }
+
+    pub fn resize(&mut self, new_size: mugl::Extent2D) {
+        self.app.resize(new_size);
+    }
+}
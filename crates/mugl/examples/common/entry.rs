@@ -12,7 +12,10 @@ pub fn init<A: super::app::App + 'static>() {
     let instant = std::time::Instant::now();
     let device = pollster::block_on(A::request_wgpu_device(&window)).unwrap();
     let size = window.inner_size();
-    let mut app = A::new(device, mugl::Extent2D(size.width, size.height));
+    let mut runner = super::app::AppRunner::new(Box::new(A::new(
+        device,
+        mugl::Extent2D(size.width, size.height),
+    )));
 
     event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent {
@@ -30,17 +33,34 @@ pub fn init<A: super::app::App + 'static>() {
                         },
                     ..
                 } => *control_flow = ControlFlow::Exit,
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::Space),
+                            ..
+                        },
+                    ..
+                } => {
+                    if runner.is_paused() {
+                        runner.resume();
+                    } else {
+                        runner.pause();
+                    }
+                }
+                WindowEvent::Focused(false) => runner.pause(),
+                WindowEvent::Focused(true) => runner.resume(),
                 WindowEvent::Resized(physical_size) => {
-                    app.resize(mugl::Extent2D(physical_size.width, physical_size.height));
+                    runner.resize(mugl::Extent2D(physical_size.width, physical_size.height));
                 }
                 WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                    app.resize(mugl::Extent2D(new_inner_size.width, new_inner_size.height));
+                    runner.resize(mugl::Extent2D(new_inner_size.width, new_inner_size.height));
                 }
                 _ => {}
             }
         }
         Event::RedrawRequested(window_id) if window_id == window.id() => {
-            app.render(instant.elapsed().as_secs_f64());
+            runner.frame(instant.elapsed().as_secs_f64());
         }
         Event::MainEventsCleared => {
             window.request_redraw();
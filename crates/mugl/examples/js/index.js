@@ -1,5 +1,5 @@
 import { set_context_memory } from 'mugl/wasm';
-import { app_id, memory, init, render, resize, destroy } from 'examples.wasm';
+import { app_id, memory, init, render, resize, destroy, pause, resume } from 'examples.wasm';
 
 const EXAMPLES = [
   'basic',
@@ -28,6 +28,14 @@ function renderLoop(now = 0) {
   }
 }
 
+document.addEventListener('visibilitychange', () => {
+  if (document.hidden) {
+    pause();
+  } else {
+    resume();
+  }
+});
+
 window.loadExample = function (hash = location.hash) {
   const nextExample = EXAMPLES.indexOf(hash.replace('#', ''));
   if (nextExample >= 0) {
@@ -6,13 +6,13 @@ mod app;
 mod common;
 
 use alloc::boxed::Box;
-use common::App;
+use common::{App, AppRunner};
 use mugl::{prelude::*, webgl::*};
 
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-static mut APP: Option<Box<dyn App>> = None;
+static mut APP: Option<AppRunner> = None;
 static mut CANVAS: Option<Canvas> = None;
 
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
@@ -30,7 +30,7 @@ pub extern "C" fn init(id: u32) {
             CANVAS = Some(Canvas::from_id("canvas"));
         }
         CANVAS.as_ref().map(|canvas| {
-            APP = select_app(id, canvas);
+            APP = select_app(id, canvas).map(AppRunner::new);
         });
     }
 }
@@ -53,12 +53,28 @@ fn create_app<A: App + 'static>(canvas: &Canvas) -> Option<Box<dyn App>> {
 #[no_mangle]
 pub extern "C" fn render(t: f64) -> bool {
     if let Some(app) = unsafe { APP.as_mut() } {
-        app.render(t)
+        app.frame(t)
     } else {
         false
     }
 }
 
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[no_mangle]
+pub extern "C" fn pause() {
+    if let Some(app) = unsafe { APP.as_mut() } {
+        app.pause();
+    }
+}
+
+#[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
+#[no_mangle]
+pub extern "C" fn resume() {
+    if let Some(app) = unsafe { APP.as_mut() } {
+        app.resume();
+    }
+}
+
 #[cfg_attr(feature = "wasm-bindgen", wasm_bindgen::prelude::wasm_bindgen)]
 #[no_mangle]
 pub extern "C" fn resize(width: u32, height: u32) {
@@ -45,9 +45,15 @@ pub type BindGroup<G = DefaultGPU> = <G as GPU>::BindGroup;
 /// A GPU bind group layout.
 pub type BindGroupLayout<G = DefaultGPU> = <G as GPU>::BindGroupLayout;
 
+/// A GPU render bundle.
+pub type RenderBundle<G = DefaultGPU> = <G as GPU>::RenderBundle;
+
 /// The GPU render pass encoder type.
 pub type RenderPassEncoder<'a, G = DefaultGPU> = <G as GPURefTypes<'a, G>>::RenderPassEncoder;
 
+/// The GPU render bundle encoder type.
+pub type RenderBundleEncoder<'a, G = DefaultGPU> = <G as GPURefTypes<'a, G>>::RenderBundleEncoder;
+
 /// A mapped view into a GPU buffer.
 pub type BufferView<'a, G = DefaultGPU> = <G as GPURefTypes<'a, G>>::BufferView;
 
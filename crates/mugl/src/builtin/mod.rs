@@ -0,0 +1,224 @@
+//! Built-in fallback resources.
+//!
+//! [BuiltinResources] lazily creates small device resources that renderers commonly need:
+//! - a 1x1 white texture, for materials without a base color, metallic-roughness or occlusion texture,
+//! - a 1x1 flat normal map texture, for materials without a normal map,
+//! - a magenta / black checkerboard texture, to make missing or failed-to-load textures stand out,
+//! - a full-screen triangle vertex buffer and vertex shader, for post-processing passes.
+//!
+//! Each resource is created on first use and shared afterwards.
+
+use crate::descriptor::{
+    BufferDescriptor, ColorTargetStates, ImageCopyTexture, ImageDataLayout,
+    RenderPipelineDescriptor, ShaderDescriptor, TextureDescriptor, VertexAttribute,
+    VertexBufferLayout,
+};
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::primitive::{
+    BufferUsage, Extent3D, Origin3D, ShaderStage, VertexFormat, VertexStepMode,
+};
+
+/// Size of the checkerboard "missing" texture.
+const MISSING_TEXTURE_SIZE: u32 = 8;
+
+/// Size of each checkerboard square in the "missing" texture.
+const MISSING_TEXTURE_CHECKER_SIZE: u32 = 4;
+
+/// Vertex positions of a triangle covering the whole clip space, as pairs of f32.
+pub const FULLSCREEN_TRIANGLE: [f32; 6] = [-1., -1., 3., -1., -1., 3.];
+
+/// Vertex buffer layout of [FULLSCREEN_TRIANGLE], with the position at shader location 0.
+pub const FULLSCREEN_TRIANGLE_LAYOUT: VertexBufferLayout<'static> = VertexBufferLayout {
+    stride: 8,
+    step_mode: VertexStepMode::Vertex,
+    attributes: &[VertexAttribute {
+        format: VertexFormat::F32x2,
+        offset: 0,
+        shader_location: 0,
+    }],
+};
+
+/// Shader sources for the built-in resources.
+///
+/// The full-screen vertex shader reads the position from [FULLSCREEN_TRIANGLE_LAYOUT],
+/// and outputs UV coordinates at location 0 (`vUv` in GLSL), with (0, 0) at the top-left corner on WebGPU
+/// and at the bottom-left corner on WebGL, matching the texture coordinates of the framebuffer.
+#[derive(Clone, Copy, Debug)]
+pub struct BuiltinShaders<'a> {
+    pub fullscreen_vertex: &'a str,
+}
+
+impl BuiltinShaders<'static> {
+    /// Built-in WGSL shaders, for the WebGPU backend.
+    pub const WGSL: Self = Self {
+        fullscreen_vertex: include_str!("shader/fullscreen.vs.wgsl"),
+    };
+
+    /// Built-in GLSL ES 3.0 shaders, for the WebGL backend.
+    pub const GLSL: Self = Self {
+        fullscreen_vertex: include_str!("shader/fullscreen.vs.glsl"),
+    };
+}
+
+impl Default for BuiltinShaders<'static> {
+    /// Returns the built-in shaders matching [crate::DefaultGPU].
+    fn default() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_family = "wasm", feature = "backend-webgl"))] {
+                Self::GLSL
+            } else {
+                Self::WGSL
+            }
+        }
+    }
+}
+
+/// Lazily-created built-in device resources.
+#[derive(Debug)]
+pub struct BuiltinResources<G: GPU> {
+    shaders: BuiltinShaders<'static>,
+    white_texture: Option<G::Texture>,
+    normal_texture: Option<G::Texture>,
+    missing_texture: Option<G::Texture>,
+    fullscreen_buffer: Option<G::Buffer>,
+    fullscreen_shader: Option<G::Shader>,
+}
+
+impl<G: GPU> BuiltinResources<G> {
+    /// Creates an empty set of built-in resources, using given shaders for the full-screen vertex shader.
+    #[inline]
+    pub fn new(shaders: BuiltinShaders<'static>) -> Self {
+        Self {
+            shaders,
+            white_texture: None,
+            normal_texture: None,
+            missing_texture: None,
+            fullscreen_buffer: None,
+            fullscreen_shader: None,
+        }
+    }
+
+    /// Gets a 1x1 opaque white RGBA8 texture.
+    pub fn white_texture(&mut self, device: &G::Device) -> &G::Texture {
+        self.white_texture
+            .get_or_insert_with(|| create_texture::<G>(device, 1, &[255, 255, 255, 255]))
+    }
+
+    /// Gets a 1x1 RGBA8 normal map texture, encoding the unperturbed tangent-space normal (0, 0, 1).
+    pub fn normal_texture(&mut self, device: &G::Device) -> &G::Texture {
+        self.normal_texture
+            .get_or_insert_with(|| create_texture::<G>(device, 1, &[128, 128, 255, 255]))
+    }
+
+    /// Gets an 8x8 magenta / black checkerboard RGBA8 texture, to substitute missing textures.
+    pub fn missing_texture(&mut self, device: &G::Device) -> &G::Texture {
+        self.missing_texture.get_or_insert_with(|| {
+            let mut data = [0; (MISSING_TEXTURE_SIZE * MISSING_TEXTURE_SIZE * 4) as usize];
+            for (i, texel) in data.chunks_exact_mut(4).enumerate() {
+                let (x, y) = (
+                    i as u32 % MISSING_TEXTURE_SIZE,
+                    i as u32 / MISSING_TEXTURE_SIZE,
+                );
+                let magenta =
+                    (x / MISSING_TEXTURE_CHECKER_SIZE + y / MISSING_TEXTURE_CHECKER_SIZE) & 1 == 0;
+                texel.copy_from_slice(if magenta {
+                    &[255, 0, 255, 255]
+                } else {
+                    &[0, 0, 0, 255]
+                });
+            }
+            create_texture::<G>(device, MISSING_TEXTURE_SIZE, &data)
+        })
+    }
+
+    /// Gets the vertex buffer of [FULLSCREEN_TRIANGLE].
+    pub fn fullscreen_vertex_buffer(&mut self, device: &G::Device) -> &G::Buffer {
+        self.fullscreen_buffer.get_or_insert_with(|| {
+            let mut data = [0; FULLSCREEN_TRIANGLE.len() * 4];
+            for (bytes, value) in data.chunks_exact_mut(4).zip(FULLSCREEN_TRIANGLE) {
+                bytes.copy_from_slice(&value.to_le_bytes());
+            }
+            let buffer = device.create_buffer(BufferDescriptor {
+                size: data.len(),
+                usage: BufferUsage::VERTEX,
+            });
+            device.write_buffer(&buffer, 0, &data);
+            buffer
+        })
+    }
+
+    /// Gets the full-screen triangle vertex shader.
+    pub fn fullscreen_vertex_shader(&mut self, device: &G::Device) -> &G::Shader {
+        let code = self.shaders.fullscreen_vertex;
+        self.fullscreen_shader.get_or_insert_with(|| {
+            device.create_shader(ShaderDescriptor {
+                code,
+                usage: ShaderStage::VERTEX,
+            })
+        })
+    }
+
+    /// Creates a full-screen render pipeline from a fragment shader,
+    /// using the built-in full-screen vertex shader and [FULLSCREEN_TRIANGLE_LAYOUT].
+    /// The pipeline has no depth / stencil state and no blending.
+    pub fn create_fullscreen_pipeline(
+        &mut self,
+        device: &G::Device,
+        fragment: &G::Shader,
+        bind_groups: &[&G::BindGroupLayout],
+        targets: ColorTargetStates,
+    ) -> G::RenderPipeline {
+        let vertex = self.fullscreen_vertex_shader(device);
+        device.create_render_pipeline(RenderPipelineDescriptor {
+            vertex,
+            fragment,
+            buffers: &[FULLSCREEN_TRIANGLE_LAYOUT],
+            bind_groups,
+            primitive: Default::default(),
+            multisample: Default::default(),
+            depth_stencil: None,
+            targets,
+            multiview: None,
+        })
+    }
+}
+
+impl<G: GPU> Default for BuiltinResources<G> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(BuiltinShaders::default())
+    }
+}
+
+/// Draws the full-screen triangle from its vertex buffer, see [BuiltinResources::fullscreen_vertex_buffer].
+/// The buffer is bound to slot 0. The pipeline and bind groups must be set beforehand.
+pub fn draw_fullscreen<'a, G: GPU, P: GPURenderPassEncoder<'a, G>>(
+    pass: &P,
+    buffer: &'a G::Buffer,
+) {
+    pass.vertex(0, buffer, 0);
+    pass.draw(0..3, 0..1);
+}
+
+/// Creates a square RGBA8 texture with given data.
+fn create_texture<G: GPU>(device: &G::Device, size: u32, data: &[u8]) -> G::Texture {
+    let texture = device.create_texture(TextureDescriptor {
+        size: Extent3D(size, size, 1),
+        ..Default::default()
+    });
+    device.write_texture(
+        ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3D(0, 0, 0),
+        },
+        data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: size * 4,
+            rows_per_image: size,
+        },
+        Extent3D(size, size, 1),
+    );
+    texture
+}
@@ -0,0 +1,8 @@
+#version 300 es
+precision highp float;
+layout(location = 0) in vec2 position;
+out vec2 vUv;
+void main(void) {
+  vUv = position * 0.5 + 0.5;
+  gl_Position = vec4(position, 0.0, 1.0);
+}
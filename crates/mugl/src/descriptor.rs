@@ -1,11 +1,13 @@
 //! GPU object descriptors.
 
+use core::num::NonZeroU32;
+
 use crate::gpu::{GPUWebExt, GPU};
 use crate::primitive::{
     AddressMode, BlendFactor, BlendOperation, BufferSize, BufferUsage, Color, ColorWrite,
     CompareFunction, CullMode, Extent3D, FilterMode, FrontFace, IndexFormat, Origin2D, Origin3D,
-    PrimitiveTopology, SamplerBindingType, ShaderStage, StencilOperation, TextureDimension,
-    TextureFormat, TextureSampleType, TextureUsage, VertexFormat, VertexStepMode,
+    PrimitiveTopology, QueryType, SamplerBindingType, ShaderStage, StencilOperation,
+    TextureDimension, TextureFormat, TextureSampleType, TextureUsage, VertexFormat, VertexStepMode,
 };
 
 /// This specifies the options to use in creating a Buffer.
@@ -16,6 +18,14 @@ pub struct BufferDescriptor {
     pub usage: BufferUsage,
 }
 
+/// This specifies the options to use in creating a QuerySet.
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct QuerySetDescriptor {
+    pub ty: QueryType,
+    pub count: u32,
+}
+
 /// This specifies the options to use in creating a Texture.
 #[derive(Clone, Copy, Debug)]
 #[repr(C)]
@@ -192,6 +202,10 @@ pub struct RenderPipelineDescriptor<'a, G: GPU> {
     pub multisample: MultisampleState,
     pub depth_stencil: Option<DepthStencilState>,
     pub targets: ColorTargetStates<'a>,
+    /// Number of array layers to render in a single pass, for layered rendering (e.g. cascaded shadow maps).
+    /// Shaders select per-layer data via the view index builtin.
+    /// Requires the multiview feature, and must match the render pass.
+    pub multiview: Option<NonZeroU32>,
 }
 
 /// This describes the primitive state of a render pipeline.
@@ -223,7 +237,7 @@ impl Default for MultisampleState {
 
 /// This describes the depth-stencil state of a render pipeline.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpudepthstencilstate>
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct DepthStencilState {
     pub format: TextureFormat,
     pub depth_write: bool,
@@ -256,7 +270,7 @@ impl Default for DepthStencilState {
 
 /// This describes a stencil face state of a DepthStencilState.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpudepthstencilstate>
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[repr(C)]
 pub struct StencilFaceState {
     pub compare: CompareFunction,
@@ -299,7 +313,7 @@ pub struct ColorTargetState {
 
 /// This describes the blend state of a color target.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpublendstate>
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[repr(C)]
 pub struct BlendState {
     pub color: BlendComponent,
@@ -308,7 +322,7 @@ pub struct BlendState {
 
 /// This describes the blend component state.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpublendcomponent>
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
 pub struct BlendComponent {
     pub operation: BlendOperation,
@@ -336,6 +350,29 @@ pub struct VertexBufferLayout<'a> {
     pub attributes: &'a [VertexAttribute],
 }
 
+impl<'a> VertexBufferLayout<'a> {
+    /// Creates the layout of a vertex buffer that advances per vertex.
+    #[inline]
+    pub const fn per_vertex(stride: BufferSize, attributes: &'a [VertexAttribute]) -> Self {
+        Self {
+            stride,
+            step_mode: VertexStepMode::Vertex,
+            attributes,
+        }
+    }
+
+    /// Creates the layout of a vertex buffer that advances per instance, e.g. an [crate::instance::InstanceBuffer].
+    /// On WebGL, its attributes get a divisor of 1.
+    #[inline]
+    pub const fn per_instance(stride: BufferSize, attributes: &'a [VertexAttribute]) -> Self {
+        Self {
+            stride,
+            step_mode: VertexStepMode::Instance,
+            attributes,
+        }
+    }
+}
+
 /// This describes the layout of a vertex buffer.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpuvertexbufferlayout>
 #[derive(Clone, Copy, Debug, Default)]
@@ -363,6 +400,9 @@ pub enum RenderPassDescriptor<'a, 'b, G: GPU> {
         depth_stencil: Option<TextureView<'a, G>>,
         clear_depth: Option<f32>,
         clear_stencil: Option<u32>,
+        /// If set, each attachment covers this number of array layers, starting from its view slice.
+        /// Requires the multiview feature.
+        multiview: Option<NonZeroU32>,
     },
 }
 
@@ -377,6 +417,30 @@ impl<'a, 'b, G: GPU> Default for RenderPassDescriptor<'a, 'b, G> {
     }
 }
 
+/// This describes the render passes that a render bundle can be executed in.
+/// See: <https://www.w3.org/TR/webgpu/#dictdef-gpurenderbundleencoderdescriptor>
+#[derive(Clone, Copy, Debug)]
+pub enum RenderBundleDescriptor<'a> {
+    /// Bundle for default render passes, i.e. rendering to the surface with its depth format and sample count.
+    Default,
+
+    /// Bundle for offscreen render passes with given attachment formats.
+    Offscreen {
+        color_formats: &'a [TextureFormat],
+        depth_stencil_format: Option<TextureFormat>,
+        sample_count: u32,
+        /// Must match the multiview layer count of the render passes.
+        multiview: Option<NonZeroU32>,
+    },
+}
+
+impl Default for RenderBundleDescriptor<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
 /// This describes a color attachment for a render pass.
 /// See: <https://www.w3.org/TR/webgpu/#dictdef-gpurenderpasscolorattachment>
 #[derive(Clone, Copy, Debug)]
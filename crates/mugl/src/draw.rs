@@ -0,0 +1,281 @@
+//! Draw call sorting and batching.
+//!
+//! Draws are recorded into a [DrawList] with a [DrawKey], then sorted by key and submitted to a render pass.
+//! Sorting groups draws sharing the same pipeline and material together,
+//! and submission skips rebinding pipelines, bind groups and buffers that are already bound.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::ptr;
+
+use crate::gpu::{GPURenderPassEncoder, GPU};
+
+/// A 64-bit draw call sort key.
+///
+/// From the most to the least significant bits, the key consists of:
+/// - 8 bits of pass (or layer) ID, e.g. to draw opaque objects before transparent ones
+/// - 16 bits of pipeline ID
+/// - 16 bits of material ID, e.g. the bind group of material textures and uniforms
+/// - 24 bits of quantized depth
+///
+/// Sorting opaque draws by pipeline and material minimizes state changes, and the depth orders the draws
+/// within a batch. Blended draws usually need to be strictly ordered by depth,
+/// which is achieved by leaving their pipeline and material IDs as 0 and using
+/// [DrawKey::with_depth_back_to_front].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DrawKey(pub u64);
+
+impl DrawKey {
+    const PASS_SHIFT: u32 = 56;
+    const PIPELINE_SHIFT: u32 = 40;
+    const MATERIAL_SHIFT: u32 = 24;
+    const DEPTH_MASK: u64 = (1 << Self::MATERIAL_SHIFT) - 1;
+
+    /// Creates a key with all fields set to 0.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Returns a copy of this key with given pass ID.
+    #[inline]
+    pub const fn with_pass(self, pass: u8) -> Self {
+        self.with_field(Self::PASS_SHIFT, 0xFF, pass as u64)
+    }
+
+    /// Returns a copy of this key with given pipeline ID.
+    #[inline]
+    pub const fn with_pipeline(self, pipeline: u16) -> Self {
+        self.with_field(Self::PIPELINE_SHIFT, 0xFFFF, pipeline as u64)
+    }
+
+    /// Returns a copy of this key with given material ID.
+    #[inline]
+    pub const fn with_material(self, material: u16) -> Self {
+        self.with_field(Self::MATERIAL_SHIFT, 0xFFFF, material as u64)
+    }
+
+    /// Returns a copy of this key with given raw 24-bit depth value. Higher bits are ignored.
+    #[inline]
+    pub const fn with_depth_bits(self, depth: u32) -> Self {
+        self.with_field(0, Self::DEPTH_MASK, depth as u64)
+    }
+
+    /// Returns a copy of this key with given normalized depth in [0, 1], sorted front to back.
+    /// Values outside of the range are clamped.
+    #[inline]
+    pub fn with_depth(self, depth: f32) -> Self {
+        self.with_depth_bits(quantize_depth(depth))
+    }
+
+    /// Returns a copy of this key with given normalized depth in [0, 1], sorted back to front.
+    /// Values outside of the range are clamped.
+    #[inline]
+    pub fn with_depth_back_to_front(self, depth: f32) -> Self {
+        self.with_depth_bits(Self::DEPTH_MASK as u32 - quantize_depth(depth))
+    }
+
+    /// Returns the pass ID.
+    #[inline]
+    pub const fn pass(&self) -> u8 {
+        (self.0 >> Self::PASS_SHIFT) as u8
+    }
+
+    /// Returns the pipeline ID.
+    #[inline]
+    pub const fn pipeline(&self) -> u16 {
+        (self.0 >> Self::PIPELINE_SHIFT) as u16
+    }
+
+    /// Returns the material ID.
+    #[inline]
+    pub const fn material(&self) -> u16 {
+        (self.0 >> Self::MATERIAL_SHIFT) as u16
+    }
+
+    /// Returns the raw 24-bit depth value.
+    #[inline]
+    pub const fn depth_bits(&self) -> u32 {
+        (self.0 & Self::DEPTH_MASK) as u32
+    }
+
+    #[inline]
+    const fn with_field(self, shift: u32, mask: u64, value: u64) -> Self {
+        Self((self.0 & !(mask << shift)) | ((value & mask) << shift))
+    }
+}
+
+impl From<u64> for DrawKey {
+    #[inline]
+    fn from(key: u64) -> Self {
+        Self(key)
+    }
+}
+
+impl From<DrawKey> for u64 {
+    #[inline]
+    fn from(key: DrawKey) -> Self {
+        key.0
+    }
+}
+
+/// Quantizes a normalized depth into 24 bits.
+#[inline]
+fn quantize_depth(depth: f32) -> u32 {
+    let max = DrawKey::DEPTH_MASK as f32;
+    // NaN is mapped to 0 by the float to int cast
+    (depth * max).max(0.).min(max) as u32
+}
+
+/// A draw call recorded in a [DrawList].
+#[derive(Debug)]
+pub struct Draw<'a, G: GPU> {
+    /// The sort key.
+    pub key: DrawKey,
+    /// The render pipeline.
+    pub pipeline: &'a G::RenderPipeline,
+    /// The bind groups, bound to the slots of their indices.
+    pub bind_groups: &'a [&'a G::BindGroup],
+    /// The vertex buffers, bound to the slots of their indices.
+    pub vertex_buffers: &'a [&'a G::Buffer],
+    /// The index buffer for an indexed draw.
+    pub index_buffer: Option<&'a G::Buffer>,
+    /// The range of vertices to draw, or the range of indices for an indexed draw.
+    pub elements: Range<u32>,
+    /// The range of instances to draw.
+    pub instances: Range<u32>,
+}
+
+impl<'a, G: GPU> Clone for Draw<'a, G> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key,
+            pipeline: self.pipeline,
+            bind_groups: self.bind_groups,
+            vertex_buffers: self.vertex_buffers,
+            index_buffer: self.index_buffer,
+            elements: self.elements.clone(),
+            instances: self.instances.clone(),
+        }
+    }
+}
+
+/// A list of draw calls to be sorted and submitted to a render pass.
+#[derive(Debug)]
+pub struct DrawList<'a, G: GPU> {
+    draws: Vec<Draw<'a, G>>,
+    sorted: bool,
+}
+
+impl<'a, G: GPU> DrawList<'a, G> {
+    /// Creates an empty draw list.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            draws: Vec::new(),
+            sorted: true,
+        }
+    }
+
+    /// Records a draw call.
+    #[inline]
+    pub fn push(&mut self, draw: Draw<'a, G>) {
+        self.sorted = false;
+        self.draws.push(draw);
+    }
+
+    /// Returns the number of recorded draw calls.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// Returns true if no draw call is recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    /// Removes all recorded draw calls, keeping the allocated memory for reuse in the next frame.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.draws.clear();
+        self.sorted = true;
+    }
+
+    /// Sorts the draw calls by key. Draws with equal keys keep their recording order.
+    pub fn sort(&mut self) {
+        if !self.sorted {
+            self.draws.sort_by_key(|draw| draw.key);
+            self.sorted = true;
+        }
+    }
+
+    /// Returns the recorded draw calls, in sorted order if [DrawList::sort] has been called since the last push.
+    #[inline]
+    pub fn draws(&self) -> &[Draw<'a, G>] {
+        &self.draws
+    }
+
+    /// Sorts and submits the draw calls to a render pass.
+    /// Pipelines, bind groups and buffers are only bound when they differ from the previous draw.
+    pub fn submit<P: GPURenderPassEncoder<'a, G>>(&mut self, pass: &P) {
+        self.sort();
+
+        let mut pipeline: Option<&G::RenderPipeline> = None;
+        let mut bind_groups: Vec<Option<&G::BindGroup>> = Vec::new();
+        let mut vertex_buffers: Vec<Option<&G::Buffer>> = Vec::new();
+        let mut index_buffer: Option<&G::Buffer> = None;
+
+        for draw in &self.draws {
+            if !same(pipeline, draw.pipeline) {
+                pass.pipeline(draw.pipeline);
+                pipeline = Some(draw.pipeline);
+            }
+
+            for (slot, bind_group) in draw.bind_groups.iter().enumerate() {
+                if slot >= bind_groups.len() {
+                    bind_groups.resize(slot + 1, None);
+                }
+                if !same(bind_groups[slot], *bind_group) {
+                    pass.bind_group(slot as u32, bind_group, &[]);
+                    bind_groups[slot] = Some(*bind_group);
+                }
+            }
+
+            for (slot, buffer) in draw.vertex_buffers.iter().enumerate() {
+                if slot >= vertex_buffers.len() {
+                    vertex_buffers.resize(slot + 1, None);
+                }
+                if !same(vertex_buffers[slot], *buffer) {
+                    pass.vertex(slot as u32, buffer, 0);
+                    vertex_buffers[slot] = Some(*buffer);
+                }
+            }
+
+            match draw.index_buffer {
+                Some(buffer) => {
+                    if !same(index_buffer, buffer) {
+                        pass.index(buffer);
+                        index_buffer = Some(buffer);
+                    }
+                    pass.draw_indexed(draw.elements.clone(), draw.instances.clone());
+                }
+                None => pass.draw(draw.elements.clone(), draw.instances.clone()),
+            }
+        }
+    }
+}
+
+impl<'a, G: GPU> Default for DrawList<'a, G> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns true if the currently bound resource is the given resource.
+#[inline]
+fn same<T>(bound: Option<&T>, resource: &T) -> bool {
+    matches!(bound, Some(bound) if ptr::eq(bound, resource))
+}
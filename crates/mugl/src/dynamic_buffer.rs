@@ -0,0 +1,183 @@
+//! Sub-allocation of per-draw uniform data from large buffers, bound with dynamic offsets.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::descriptor::{BindingResource, BufferDescriptor};
+use crate::gpu::{GPUDevice, GPU};
+use crate::limits::MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT;
+use crate::primitive::{BufferSize, BufferUsage};
+
+/// Required alignment of buffer write offsets and sizes on WebGPU.
+const COPY_BUFFER_ALIGNMENT: BufferSize = 4;
+
+/// A sub-allocation of a [DynamicBufferAllocator].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DynamicAllocation {
+    /// Index of the buffer holding the data. See [DynamicBufferAllocator::buffer].
+    pub buffer: usize,
+    /// Offset of the data in the buffer, to be passed as the dynamic offset when binding a bind group of the buffer.
+    pub offset: u32,
+}
+
+/// A per-frame allocator of uniform data, e.g. per-object transforms, that sub-allocates from large uniform buffers
+/// instead of creating one buffer per object.
+///
+/// Each allocation is placed at an offset aligned to [MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT], with room for
+/// [DynamicBufferAllocator::binding_size] bytes, so that a single bind group per buffer, created with
+/// [DynamicBufferAllocator::binding] for a binding with dynamic offset, can bind any allocation of the buffer.
+/// Buffers are created when the existing ones are full, so create the bind groups of new buffers
+/// when an allocation refers to a buffer index without one.
+///
+/// Allocated data is staged in memory and uploaded by [DynamicBufferAllocator::flush] with one write per buffer,
+/// which must be called before submitting the draws using the allocations.
+/// Call [DynamicBufferAllocator::reset] at the start of each frame to reuse the buffers.
+#[derive(Debug)]
+pub struct DynamicBufferAllocator<G: GPU> {
+    blocks: Vec<Block<G>>,
+    block_size: BufferSize,
+    binding_size: BufferSize,
+    /// Index of the block being allocated from.
+    current: usize,
+}
+
+/// A buffer of a [DynamicBufferAllocator] with its staged data.
+#[derive(Debug)]
+struct Block<G: GPU> {
+    buffer: G::Buffer,
+    data: Vec<u8>,
+    /// End of the allocated data.
+    len: BufferSize,
+    /// End of the uploaded data.
+    flushed: BufferSize,
+}
+
+impl<G: GPU> DynamicBufferAllocator<G> {
+    /// Creates an allocator of buffers of `block_size` bytes, for allocations of up to `binding_size` bytes.
+    /// No buffer is created until the first allocation.
+    ///
+    /// # Panics
+    /// Panics if `binding_size` is 0 or larger than `block_size`.
+    pub fn new(block_size: BufferSize, binding_size: BufferSize) -> Self {
+        assert!(
+            binding_size > 0 && binding_size <= block_size,
+            "binding size must be within (0, block_size]"
+        );
+        Self {
+            blocks: Vec::new(),
+            block_size: align(block_size, COPY_BUFFER_ALIGNMENT),
+            binding_size,
+            current: 0,
+        }
+    }
+
+    /// Gets the size of each buffer in bytes.
+    #[inline]
+    pub fn block_size(&self) -> BufferSize {
+        self.block_size
+    }
+
+    /// Gets the maximum size of an allocation, which is also the size of the buffer bindings.
+    #[inline]
+    pub fn binding_size(&self) -> BufferSize {
+        self.binding_size
+    }
+
+    /// Gets the number of buffers created.
+    #[inline]
+    pub fn buffer_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Gets a buffer by index.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn buffer(&self, index: usize) -> &G::Buffer {
+        &self.blocks[index].buffer
+    }
+
+    /// Gets the binding resource of a buffer, for a bind group entry of a binding with dynamic offset.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn binding(&self, index: usize) -> BindingResource<'_, G> {
+        BindingResource::Buffer {
+            buffer: self.buffer(index),
+            offset: 0,
+            size: self.binding_size,
+        }
+    }
+
+    /// Allocates and stages data, creating a new buffer if the current one is full.
+    ///
+    /// # Panics
+    /// Panics if the data is larger than the binding size.
+    pub fn allocate(&mut self, device: &G::Device, data: &[u8]) -> DynamicAllocation {
+        let size = data.len();
+        assert!(
+            size <= self.binding_size,
+            "allocation of {} bytes exceeds the binding size of {} bytes",
+            size,
+            self.binding_size
+        );
+
+        let mut offset = self.blocks.get(self.current).map_or(0, |block| {
+            align(block.len, MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT as BufferSize)
+        });
+        if offset + self.binding_size > self.block_size {
+            self.current += 1;
+            offset = 0;
+        }
+        if self.current == self.blocks.len() {
+            self.blocks.push(Block {
+                buffer: device.create_buffer(BufferDescriptor {
+                    size: self.block_size,
+                    usage: BufferUsage::UNIFORM | BufferUsage::DYNAMIC,
+                }),
+                data: vec![0; self.block_size],
+                len: 0,
+                flushed: 0,
+            });
+        }
+
+        let block = &mut self.blocks[self.current];
+        block.data[offset..(offset + size)].copy_from_slice(data);
+        block.len = offset + size;
+
+        DynamicAllocation {
+            buffer: self.current,
+            offset: offset as u32,
+        }
+    }
+
+    /// Uploads the data staged since the last flush.
+    pub fn flush(&mut self, device: &G::Device) {
+        for block in &mut self.blocks {
+            if block.len > block.flushed {
+                let start = block.flushed - block.flushed % COPY_BUFFER_ALIGNMENT;
+                let end = align(block.len, COPY_BUFFER_ALIGNMENT);
+                device.write_buffer(&block.buffer, start, &block.data[start..end]);
+                block.flushed = block.len;
+            }
+        }
+    }
+
+    /// Frees all allocations, keeping the buffers for reuse.
+    /// Data of a frame must be flushed before resetting for the next frame.
+    pub fn reset(&mut self) {
+        for block in &mut self.blocks {
+            block.len = 0;
+            block.flushed = 0;
+        }
+        self.current = 0;
+    }
+}
+
+/// Rounds up a size to a multiple of the alignment.
+#[inline]
+fn align(size: BufferSize, alignment: BufferSize) -> BufferSize {
+    size.div_ceil(alignment) * alignment
+}
@@ -1,14 +1,17 @@
 //! Empty backend
 
 use alloc::boxed::Box;
+use alloc::{vec, vec::Vec};
 use async_trait::async_trait;
 use core::ops::Range;
 
+use crate::limits::Limits;
 use crate::{
-    BufferDescriptor, BufferSize, Color, Extent2D, Extent3D, GPUDevice, GPUDeviceWebExt,
-    GPURefTypes, GPURenderPassEncoder, GPUWebExt, ImageCopyExternalImage, ImageCopyTexture,
-    ImageDataLayout, MipmapHint, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
-    ShaderDescriptor, TextureDescriptor, GPU,
+    BufferDescriptor, BufferSize, Color, Extent2D, Extent3D, FrameCallback, GPUDevice,
+    GPUDeviceWebExt, GPURefTypes, GPURenderBundleEncoder, GPURenderPassEncoder, GPUWebExt,
+    ImageCopyExternalImage, ImageCopyTexture, ImageDataLayout, LogSink, MipmapHint,
+    QuerySetDescriptor, RenderBundleDescriptor, RenderPassDescriptor, RenderPipelineDescriptor,
+    SamplerDescriptor, ShaderDescriptor, TextureDescriptor, TextureFormat, GPU,
 };
 
 /// Empty GPU backend
@@ -23,6 +26,10 @@ pub struct EmptyGPUDevice;
 #[derive(Debug)]
 pub struct EmptyGPURenderPassEncoder;
 
+/// Empty / No-op GPU render bundle encoder
+#[derive(Debug)]
+pub struct EmptyGPURenderBundleEncoder;
+
 impl GPU for EmptyGPU {
     type Features = ();
     type Device = EmptyGPUDevice;
@@ -34,10 +41,13 @@ impl GPU for EmptyGPU {
     type RenderPass = ();
     type BindGroup = ();
     type BindGroupLayout = ();
+    type QuerySet = ();
+    type RenderBundle = ();
 }
 
 impl<'a> GPURefTypes<'a, EmptyGPU> for EmptyGPU {
     type RenderPassEncoder = EmptyGPURenderPassEncoder;
+    type RenderBundleEncoder = EmptyGPURenderBundleEncoder;
     type BufferView = &'a [u8];
 }
 
@@ -50,6 +60,10 @@ impl GPUWebExt for EmptyGPU {
 impl GPUDevice<EmptyGPU> for EmptyGPUDevice {
     fn features(&self) -> () {}
 
+    fn limits(&self) -> Limits {
+        Limits::default()
+    }
+
     fn create_buffer(&self, _descriptor: BufferDescriptor) -> () {}
 
     fn create_texture(&self, _descriptor: TextureDescriptor) -> () {}
@@ -76,10 +90,16 @@ impl GPUDevice<EmptyGPU> for EmptyGPUDevice {
         todo!()
     }
 
+    fn create_query_set(&self, _descriptor: QuerySetDescriptor) -> () {}
+
     fn render<'a>(&'a self, _pass: &'a ()) -> EmptyGPURenderPassEncoder {
         EmptyGPURenderPassEncoder
     }
 
+    fn render_bundle(&self, _descriptor: RenderBundleDescriptor) -> EmptyGPURenderBundleEncoder {
+        EmptyGPURenderBundleEncoder
+    }
+
     async fn read_buffer<'a>(
         &self,
         _buffer: &'a (),
@@ -126,14 +146,43 @@ impl GPUDevice<EmptyGPU> for EmptyGPUDevice {
     ) {
     }
 
+    fn resolve_query_set(
+        &self,
+        _query_set: &(),
+        _queries: Range<u32>,
+        _dst: &(),
+        _dst_offset: BufferSize,
+    ) {
+    }
+
+    fn timestamp_period(&self) -> f32 {
+        1.
+    }
+
+    fn supported_sample_counts(&self, _format: TextureFormat) -> Vec<u32> {
+        vec![1]
+    }
+
     fn is_srgb_surface(&self) -> bool {
         false
     }
 
+    fn surface_texture(&self) -> Option<()> {
+        None
+    }
+
+    fn set_log_sink(&self, _sink: Option<LogSink>) {}
+
+    fn capture_frames(&self, _frames: u32, _callback: FrameCallback) {}
+
     fn is_lost(&self) -> bool {
         false
     }
 
+    fn is_fallback_adapter(&self) -> bool {
+        false
+    }
+
     fn flush(&self) {}
 
     fn present(&self) {}
@@ -166,6 +215,10 @@ impl<'a> GPURenderPassEncoder<'a, EmptyGPU> for EmptyGPURenderPassEncoder {
 
     fn draw_indexed(&self, _indices: Range<u32>, _instances: Range<u32>) {}
 
+    fn draw_indirect(&self, _buffer: &'a (), _offset: BufferSize) {}
+
+    fn draw_indexed_indirect(&self, _buffer: &'a (), _offset: BufferSize) {}
+
     fn viewport(
         &self,
         _x: f32,
@@ -181,7 +234,31 @@ impl<'a> GPURenderPassEncoder<'a, EmptyGPU> for EmptyGPURenderPassEncoder {
 
     fn blend_const(&self, _color: Color) {}
 
+    fn begin_occlusion_query(&self, _query_set: &'a (), _index: u32) {}
+
+    fn end_occlusion_query(&self) {}
+
+    fn write_timestamp(&self, _query_set: &'a (), _index: u32) {}
+
     fn stencil_ref(&self, _reference: u32) {}
 
+    fn execute_bundles(&self, _bundles: &[&'a ()]) {}
+
     fn submit(self) {}
 }
+
+impl<'a> GPURenderBundleEncoder<'a, EmptyGPU> for EmptyGPURenderBundleEncoder {
+    fn pipeline(&self, _pipeline: &'a ()) {}
+
+    fn index(&self, _buffer: &'a ()) {}
+
+    fn vertex(&self, _slot: u32, _buffer: &'a (), _offset: BufferSize) {}
+
+    fn bind_group(&self, _slot: u32, _bind_group: &'a (), _offsets: &[u32]) {}
+
+    fn draw(&self, _vertices: Range<u32>, _instances: Range<u32>) {}
+
+    fn draw_indexed(&self, _indices: Range<u32>, _instances: Range<u32>) {}
+
+    fn finish(self) {}
+}
@@ -1,16 +1,81 @@
 //! GPU interface.
 
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use async_trait::async_trait;
 use core::fmt::Debug;
 use core::ops::{Deref, Range};
 
 use crate::descriptor::{
     BindGroupDescriptor, BindGroupLayoutDescriptor, BufferDescriptor, ImageCopyExternalImage,
-    ImageCopyTexture, ImageDataLayout, RenderPassDescriptor, RenderPipelineDescriptor,
-    SamplerDescriptor, ShaderDescriptor, TextureDescriptor,
+    ImageCopyTexture, ImageDataLayout, QuerySetDescriptor, RenderBundleDescriptor,
+    RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor, ShaderDescriptor,
+    TextureDescriptor,
 };
-use crate::primitive::{BufferSize, Color, Extent2D, Extent3D, MipmapHint};
+use crate::limits::Limits;
+use crate::primitive::{
+    BufferSize, Color, Extent2D, Extent3D, LogLevel, MipmapHint, TextureFormat,
+};
+
+/// Callback function of a [LogSink].
+pub type LogFn = dyn Fn(LogLevel, &str) + Send + Sync;
+
+/// A callback that receives messages reported by a GPU backend, e.g. to route them to the app's logger.
+pub struct LogSink(Box<LogFn>);
+
+impl LogSink {
+    /// Creates a new log sink from a callback.
+    #[inline]
+    pub fn new(sink: impl Fn(LogLevel, &str) + Send + Sync + 'static) -> Self {
+        Self(Box::new(sink))
+    }
+
+    /// Reports a message to the sink.
+    #[inline]
+    pub fn log(&self, level: LogLevel, message: &str) {
+        (self.0)(level, message)
+    }
+}
+
+impl Debug for LogSink {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("LogSink")
+    }
+}
+
+/// A frame captured from the surface. See [GPUDevice::capture_frames].
+#[derive(Clone, Copy, Debug)]
+pub struct CapturedFrame<'a> {
+    /// Index of the frame since the capture started, from 0.
+    pub index: u32,
+    /// Size of the frame.
+    pub size: Extent2D,
+    /// Tightly packed RGBA8 pixel data, row by row from the top.
+    pub data: &'a [u8],
+}
+
+/// A callback that receives frames captured from the surface, e.g. to encode them into a video or animated image.
+pub struct FrameCallback(Box<dyn FnMut(CapturedFrame) + Send>);
+
+impl FrameCallback {
+    /// Creates a new frame callback.
+    #[inline]
+    pub fn new(callback: impl FnMut(CapturedFrame) + Send + 'static) -> Self {
+        Self(Box::new(callback))
+    }
+
+    /// Delivers a frame to the callback.
+    #[inline]
+    pub fn call(&mut self, frame: CapturedFrame) {
+        (self.0)(frame)
+    }
+}
+
+impl Debug for FrameCallback {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("FrameCallback")
+    }
+}
 
 /// Defines a GPU backend.
 pub trait GPU: Sized + for<'s> GPURefTypes<'s, Self> {
@@ -43,6 +108,12 @@ pub trait GPU: Sized + for<'s> GPURefTypes<'s, Self> {
 
     /// A GPU bind group layout.
     type BindGroupLayout: Debug;
+
+    /// A GPU query set.
+    type QuerySet: Debug;
+
+    /// A GPU render bundle.
+    type RenderBundle: Debug;
 }
 
 /// Defines the resource reference types for a GPU backend.
@@ -50,6 +121,9 @@ pub trait GPURefTypes<'s, G: GPU> {
     /// The GPU render pass encoder type.
     type RenderPassEncoder: GPURenderPassEncoder<'s, G>;
 
+    /// The GPU render bundle encoder type.
+    type RenderBundleEncoder: GPURenderBundleEncoder<'s, G>;
+
     // A mapped view into a GPU buffer.
     type BufferView: Debug + Deref<Target = [u8]> + 's;
 }
@@ -83,6 +157,10 @@ pub trait GPUDevice<G: GPU> {
     /// Gets the enabled features for the device.
     fn features(&self) -> G::Features;
 
+    /// Gets the texture and sampler capabilities of the device.
+    /// Resources created beyond these limits are rejected in debug builds.
+    fn limits(&self) -> Limits;
+
     /// Creates a Buffer.
     fn create_buffer(&self, descriptor: BufferDescriptor) -> G::Buffer;
 
@@ -108,12 +186,23 @@ pub trait GPUDevice<G: GPU> {
     /// Creates a BindGroup.
     fn create_bind_group(&self, descriptor: BindGroupDescriptor<G>) -> G::BindGroup;
 
+    /// Creates a QuerySet.
+    /// Timestamp queries require the timer query feature of the backend.
+    fn create_query_set(&self, descriptor: QuerySetDescriptor) -> G::QuerySet;
+
     /// Begins a render pass.
     fn render<'a>(
         &'a self,
         pass: &'a G::RenderPass,
     ) -> <G as GPURefTypes<'a, G>>::RenderPassEncoder;
 
+    /// Begins recording a render bundle, a reusable list of draw commands,
+    /// e.g. to draw static scenes without encoding their commands every frame.
+    fn render_bundle<'a>(
+        &'a self,
+        descriptor: RenderBundleDescriptor,
+    ) -> <G as GPURefTypes<'a, G>>::RenderBundleEncoder;
+
     /// Asynchronously reads a Buffer.
     async fn read_buffer<'a>(
         &self,
@@ -156,12 +245,55 @@ pub trait GPUDevice<G: GPU> {
         size: Extent3D,
     );
 
+    /// Writes the results of a range of queries into a buffer at given offset, as 64-bit unsigned integers.
+    /// The offset must be a multiple of 256. Read the buffer with [GPUDevice::read_buffer] to get the results.
+    ///
+    /// Occlusion query results are non-zero if any sample passed. Timestamp query results are in ticks of
+    /// [GPUDevice::timestamp_period] nanoseconds. On WebGL, results that are not yet available are written as 0.
+    fn resolve_query_set(
+        &self,
+        query_set: &G::QuerySet,
+        queries: Range<u32>,
+        dst: &G::Buffer,
+        dst_offset: BufferSize,
+    );
+
+    /// Returns the number of nanoseconds per tick of timestamp query results.
+    fn timestamp_period(&self) -> f32;
+
+    /// Returns the sample counts supported for multisampled render attachments of given format, in ascending order.
+    /// The result always contains 1, i.e. no multisampling.
+    fn supported_sample_counts(&self, format: TextureFormat) -> Vec<u32>;
+
     /// Returns if the surface is using SRGB.
     fn is_srgb_surface(&self) -> bool;
 
+    /// Acquires the surface texture of the current frame, which can be used as a color attachment of offscreen
+    /// render passes, e.g. to composite offscreen textures into the surface using custom passes.
+    /// The texture is only valid until the next [GPUDevice::present], so render passes using it must be recreated
+    /// every frame. It cannot be sampled or copied. Its format is the surface format, e.g. [TextureFormat::BGRA8],
+    /// which pipelines rendering into it must target.
+    /// Returns `None` if unsupported by the backend, or if the surface texture cannot be acquired.
+    fn surface_texture(&self) -> Option<G::Texture>;
+
+    /// Sets the sink that receives errors and warnings reported by the backend, or `None` to remove the sink.
+    /// WebGL errors are only polled in debug builds, and only while a sink is set.
+    fn set_log_sink(&self, sink: Option<LogSink>);
+
+    /// Captures the next `frames` presented frames of the surface, after MSAA resolve.
+    /// Each frame is copied into a staging buffer on [GPUDevice::present], and read back without blocking.
+    /// Frames are delivered to the callback in order during later calls to [GPUDevice::present], once their
+    /// readbacks complete. Replaces any capture in progress, and drops its pending frames.
+    /// Unsupported backends never call the callback.
+    fn capture_frames(&self, frames: u32, callback: FrameCallback);
+
     /// Returns if the device is lost.
     fn is_lost(&self) -> bool;
 
+    /// Returns if the device runs on a fallback adapter, i.e. a software implementation like SwiftShader or llvmpipe.
+    /// Such devices are much slower than hardware ones, so apps may want to reduce quality or warn the user.
+    fn is_fallback_adapter(&self) -> bool;
+
     /// Submits any buffered commands
     fn flush(&self);
 
@@ -183,7 +315,9 @@ pub trait GPURenderPassEncoder<'a, G: GPU> {
     /// Sets the vertex buffer
     fn vertex(&self, slot: u32, buffer: &'a G::Buffer, offset: BufferSize);
 
-    /// Sets the bind group
+    /// Sets the bind group.
+    /// `offsets` must contain one offset for each dynamic offset buffer of the bind group layout, in binding order,
+    /// aligned to [crate::limits::MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT]. This is validated in debug builds.
     fn bind_group(&self, slot: u32, bind_group: &'a G::BindGroup, offsets: &[u32]);
 
     /// Draws primitives
@@ -192,7 +326,28 @@ pub trait GPURenderPassEncoder<'a, G: GPU> {
     /// Draws indexed primitives
     fn draw_indexed(&self, indices: Range<u32>, instances: Range<u32>);
 
-    /// Sets the viewport used during the rasterization stage.
+    /// Draws primitives with parameters read from a buffer at given offset, e.g. written by the GPU.
+    /// The buffer must be created with [BufferUsage::INDIRECT](crate::primitive::BufferUsage::INDIRECT),
+    /// and contain 4 `u32` values of vertex count, instance count, first vertex and first instance.
+    ///
+    /// First instance must be 0, unless supported by the device, i.e. with
+    /// the `WGPUFeatures::INDIRECT_FIRST_INSTANCE` feature of the WGPU backend.
+    /// Indirect draws are not supported on WebGL, where an error is reported instead.
+    fn draw_indirect(&self, buffer: &'a G::Buffer, offset: BufferSize);
+
+    /// Draws indexed primitives with parameters read from a buffer created with
+    /// [BufferUsage::INDIRECT](crate::primitive::BufferUsage::INDIRECT), at given offset: 5 values of
+    /// index count (`u32`), instance count (`u32`), first index (`u32`), base vertex (`i32`) and first instance (`u32`).
+    /// See [GPURenderPassEncoder::draw_indirect].
+    fn draw_indexed_indirect(&self, buffer: &'a G::Buffer, offset: BufferSize);
+
+    /// Sets the viewport used during the rasterization stage, for the draws that follow.
+    /// `min_depth` and `max_depth` must form a valid [DepthRange](crate::primitive::DepthRange),
+    /// which is validated in debug builds.
+    ///
+    /// On WebGL, where the depth range is global context state rather than part of the viewport,
+    /// the viewport is applied when the next draw is issued, so that each draw uses the depth range
+    /// of the last viewport set before it, as on WebGPU.
     fn viewport(&self, x: f32, y: f32, width: f32, height: f32, min_depth: f32, max_depth: f32);
 
     /// Sets the scissor rectangle used during the rasterization stage.
@@ -201,9 +356,53 @@ pub trait GPURenderPassEncoder<'a, G: GPU> {
     /// Sets the constant blend color and alpha values used with "constant" and "one-minus-constant" BlendFactors.
     fn blend_const(&self, color: Color);
 
+    /// Begins an occlusion query of given index. Queries must not be nested.
+    fn begin_occlusion_query(&self, query_set: &'a G::QuerySet, index: u32);
+
+    /// Ends the active occlusion query.
+    fn end_occlusion_query(&self);
+
+    /// Writes a timestamp into a timestamp query of given index, once all previous commands have finished.
+    fn write_timestamp(&self, query_set: &'a G::QuerySet, index: u32);
+
     /// Sets the stencil reference value used during stencil tests with the "replace" StencilOperation.
     fn stencil_ref(&self, reference: u32);
 
+    /// Executes the commands of render bundles in order.
+    /// The bundles must be recorded for the attachment formats of this pass.
+    /// Afterwards, the pipeline, index, vertex and bind group states are undefined, and must be set again
+    /// before further draws.
+    fn execute_bundles(&self, bundles: &[&'a G::RenderBundle]);
+
     /// Ends and submits the render pass
     fn submit(self);
 }
+
+/// A GPU render bundle encoder, which records draw commands into a render bundle.
+/// Bundles do not inherit any state of the render pass they are executed in,
+/// so the pipeline and all buffers and bind groups used must be set in the bundle.
+pub trait GPURenderBundleEncoder<'a, G: GPU> {
+    /// Sets the render pipeline
+    fn pipeline(&self, pipeline: &'a G::RenderPipeline);
+
+    /// Sets the index buffer
+    fn index(&self, buffer: &'a G::Buffer);
+
+    /// Sets the vertex buffer
+    fn vertex(&self, slot: u32, buffer: &'a G::Buffer, offset: BufferSize);
+
+    /// Sets the bind group. See [GPURenderPassEncoder::bind_group].
+    fn bind_group(&self, slot: u32, bind_group: &'a G::BindGroup, offsets: &[u32]);
+
+    /// Draws primitives
+    fn draw(&self, vertices: Range<u32>, instances: Range<u32>);
+
+    /// Draws indexed primitives
+    fn draw_indexed(&self, indices: Range<u32>, instances: Range<u32>);
+
+    /// Ends recording and returns the render bundle.
+    ///
+    /// On WebGL, the bundle refers to the resources used without keeping them alive,
+    /// so they must outlive the bundle.
+    fn finish(self) -> G::RenderBundle;
+}
@@ -0,0 +1,459 @@
+//! Image-based lighting (IBL) precomputation passes.
+//!
+//! [IblBaker] convolves an environment cubemap into:
+//! - a diffuse irradiance cubemap (pre-divided by PI, i.e. multiply by albedo directly),
+//! - a GGX-prefiltered specular cubemap, with roughness increasing linearly per mip level,
+//! - a split-sum BRDF lookup table, indexed by (n.v, roughness) and storing (scale, bias) in RG.
+//!
+//! The passes are plain offscreen render passes, so they run on any backend that can render to float textures.
+//! On WebGL, this requires the `EXT_color_buffer_float` extension for the default [TextureFormat::RGBA16F] format.
+
+use alloc::vec::Vec;
+
+use crate::descriptor::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BufferDescriptor, ColorAttachment, ColorTargetState,
+    ColorTargetStates, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
+    ShaderDescriptor, TextureDescriptor, TextureView,
+};
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::limits;
+use crate::primitive::{
+    AddressMode, BufferSize, BufferUsage, Extent3D, FilterMode, SamplerBindingType, ShaderStage,
+    TextureDimension, TextureFormat, TextureSampleType, TextureUsage,
+};
+
+/// Size of a uniform slot. Matches the minimum uniform buffer offset alignment.
+const PARAMS_SLOT_SIZE: BufferSize = limits::MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT as BufferSize;
+
+/// Size of the uniform data in a slot.
+const PARAMS_SIZE: BufferSize = 16;
+
+/// Number of faces in a cubemap.
+const CUBE_FACES: u32 = 6;
+
+/// Shader sources for the IBL passes.
+/// All passes share the same fullscreen triangle vertex shader.
+#[derive(Clone, Copy, Debug)]
+pub struct IblShaders<'a> {
+    pub vertex: &'a str,
+    pub irradiance: &'a str,
+    pub prefilter: &'a str,
+    pub brdf_lut: &'a str,
+}
+
+impl IblShaders<'static> {
+    /// Built-in WGSL shaders, for the WebGPU backend.
+    pub const WGSL: Self = Self {
+        vertex: include_str!("shader/ibl.vs.wgsl"),
+        irradiance: include_str!("shader/irradiance.fs.wgsl"),
+        prefilter: include_str!("shader/prefilter.fs.wgsl"),
+        brdf_lut: include_str!("shader/brdf.fs.wgsl"),
+    };
+
+    /// Built-in GLSL ES 3.0 shaders, for the WebGL backend.
+    pub const GLSL: Self = Self {
+        vertex: include_str!("shader/ibl.vs.glsl"),
+        irradiance: include_str!("shader/irradiance.fs.glsl"),
+        prefilter: include_str!("shader/prefilter.fs.glsl"),
+        brdf_lut: include_str!("shader/brdf.fs.glsl"),
+    };
+}
+
+impl Default for IblShaders<'static> {
+    /// Returns the built-in shaders matching [crate::DefaultGPU].
+    fn default() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_family = "wasm", feature = "backend-webgl"))] {
+                Self::GLSL
+            } else {
+                Self::WGSL
+            }
+        }
+    }
+}
+
+/// This specifies the outputs of the IBL passes.
+#[derive(Clone, Copy, Debug)]
+pub struct IblDescriptor {
+    /// Size of each face of the irradiance cubemap.
+    pub irradiance_size: u32,
+    /// Size of each face of the prefiltered cubemap at mip level 0.
+    pub prefiltered_size: u32,
+    /// Number of prefiltered mip levels. Mip level i has roughness i / (count - 1).
+    pub prefiltered_mip_level_count: u32,
+    /// Size of the BRDF lookup table.
+    pub brdf_lut_size: u32,
+    /// Number of samples taken per texel.
+    pub sample_count: u32,
+    /// Format of the irradiance and prefiltered cubemaps.
+    pub format: TextureFormat,
+    /// Format of the BRDF lookup table. Only the RG channels are used.
+    pub brdf_lut_format: TextureFormat,
+}
+
+impl Default for IblDescriptor {
+    fn default() -> Self {
+        Self {
+            irradiance_size: 32,
+            prefiltered_size: 128,
+            prefiltered_mip_level_count: 5,
+            brdf_lut_size: 256,
+            sample_count: 1024,
+            format: TextureFormat::RGBA16F,
+            brdf_lut_format: TextureFormat::RGBA16F,
+        }
+    }
+}
+
+/// The textures produced by [IblBaker::bake].
+#[derive(Debug)]
+pub struct IblTextures<G: GPU> {
+    /// Diffuse irradiance cubemap.
+    pub irradiance: G::Texture,
+    /// GGX-prefiltered specular cubemap.
+    pub prefiltered: G::Texture,
+    /// Split-sum BRDF lookup table.
+    pub brdf_lut: G::Texture,
+}
+
+/// Precomputes image-based lighting textures from an environment cubemap.
+#[derive(Debug)]
+pub struct IblBaker<G: GPU> {
+    descriptor: IblDescriptor,
+    irradiance_pipeline: G::RenderPipeline,
+    prefilter_pipeline: G::RenderPipeline,
+    brdf_lut_pipeline: G::RenderPipeline,
+    environment_layout: G::BindGroupLayout,
+    params_buffer: G::Buffer,
+    params_bind_group: G::BindGroup,
+    sampler: G::Sampler,
+}
+
+impl<G: GPU> IblBaker<G> {
+    /// Creates the pipelines and resources for the IBL passes.
+    pub fn new(device: &G::Device, shaders: IblShaders, descriptor: IblDescriptor) -> Self {
+        let params_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                label: "Params",
+                binding: 0,
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::Buffer {
+                    dynamic_offset: true,
+                },
+            }],
+        });
+
+        let environment_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    label: "env",
+                    binding: 0,
+                    visibility: ShaderStage::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float,
+                        dimension: TextureDimension::CubeMap,
+                        multisampled: false,
+                    },
+                },
+                BindGroupLayoutEntry {
+                    label: "env",
+                    binding: 1,
+                    visibility: ShaderStage::FRAGMENT,
+                    ty: BindingType::Sampler {
+                        ty: SamplerBindingType::Filtering,
+                    },
+                },
+            ],
+        });
+
+        let vertex = device.create_shader(ShaderDescriptor {
+            code: shaders.vertex,
+            usage: ShaderStage::VERTEX,
+        });
+        let create_pipeline =
+            |fragment: &str, format: TextureFormat, bind_groups: &[&G::BindGroupLayout]| {
+                let fragment = device.create_shader(ShaderDescriptor {
+                    code: fragment,
+                    usage: ShaderStage::FRAGMENT,
+                });
+                device.create_render_pipeline(RenderPipelineDescriptor {
+                    vertex: &vertex,
+                    fragment: &fragment,
+                    buffers: &[],
+                    bind_groups,
+                    primitive: Default::default(),
+                    multisample: Default::default(),
+                    depth_stencil: None,
+                    targets: ColorTargetStates::Offscreen {
+                        targets: &[ColorTargetState {
+                            format,
+                            ..Default::default()
+                        }],
+                    },
+                    multiview: None,
+                })
+            };
+        let irradiance_pipeline = create_pipeline(
+            shaders.irradiance,
+            descriptor.format,
+            &[&params_layout, &environment_layout],
+        );
+        let prefilter_pipeline = create_pipeline(
+            shaders.prefilter,
+            descriptor.format,
+            &[&params_layout, &environment_layout],
+        );
+        let brdf_lut_pipeline = create_pipeline(
+            shaders.brdf_lut,
+            descriptor.brdf_lut_format,
+            &[&params_layout],
+        );
+
+        // One slot for the BRDF LUT, then one per face for irradiance and each prefiltered mip level
+        let slot_count = 1 + CUBE_FACES * (1 + descriptor.prefiltered_mip_level_count);
+        let params_buffer = device.create_buffer(BufferDescriptor {
+            size: PARAMS_SLOT_SIZE * slot_count as BufferSize,
+            usage: BufferUsage::UNIFORM | BufferUsage::DYNAMIC,
+        });
+        let params_bind_group = device.create_bind_group(BindGroupDescriptor {
+            layout: &params_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::Buffer {
+                    buffer: &params_buffer,
+                    offset: 0,
+                    size: PARAMS_SIZE,
+                },
+            }],
+        });
+
+        let sampler = device.create_sampler(SamplerDescriptor {
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            descriptor,
+            irradiance_pipeline,
+            prefilter_pipeline,
+            brdf_lut_pipeline,
+            environment_layout,
+            params_buffer,
+            params_bind_group,
+            sampler,
+        }
+    }
+
+    /// Gets the descriptor of the IBL outputs.
+    #[inline]
+    pub fn descriptor(&self) -> &IblDescriptor {
+        &self.descriptor
+    }
+
+    /// Runs all IBL passes on an environment cubemap of given face size.
+    /// The environment should have a full mip chain to avoid aliasing artifacts.
+    pub fn bake(
+        &self,
+        device: &G::Device,
+        environment: &G::Texture,
+        environment_size: u32,
+    ) -> IblTextures<G> {
+        self.write_params(device, environment_size);
+        let environment = self.environment_bind_group(device, environment);
+        IblTextures {
+            irradiance: self.render_irradiance(device, &environment),
+            prefiltered: self.render_prefiltered(device, &environment),
+            brdf_lut: self.render_brdf_lut(device),
+        }
+    }
+
+    /// Renders the diffuse irradiance cubemap of an environment cubemap of given face size.
+    pub fn irradiance(
+        &self,
+        device: &G::Device,
+        environment: &G::Texture,
+        environment_size: u32,
+    ) -> G::Texture {
+        self.write_params(device, environment_size);
+        let environment = self.environment_bind_group(device, environment);
+        self.render_irradiance(device, &environment)
+    }
+
+    /// Renders the GGX-prefiltered mip chain of an environment cubemap of given face size.
+    pub fn prefiltered(
+        &self,
+        device: &G::Device,
+        environment: &G::Texture,
+        environment_size: u32,
+    ) -> G::Texture {
+        self.write_params(device, environment_size);
+        let environment = self.environment_bind_group(device, environment);
+        self.render_prefiltered(device, &environment)
+    }
+
+    /// Renders the split-sum BRDF lookup table. This does not depend on the environment.
+    pub fn brdf_lut(&self, device: &G::Device) -> G::Texture {
+        self.write_params(device, 1);
+        self.render_brdf_lut(device)
+    }
+
+    /// Writes the uniforms of all passes at once, as buffer writes may only be applied on next submit.
+    fn write_params(&self, device: &G::Device, environment_size: u32) {
+        let mip_count = self.descriptor.prefiltered_mip_level_count;
+        let sample_count = self.descriptor.sample_count as f32;
+        let environment_size = environment_size.max(1) as f32;
+
+        let slot_count = 1 + CUBE_FACES * (1 + mip_count);
+        let mut data = Vec::with_capacity(PARAMS_SLOT_SIZE * slot_count as usize);
+        let mut push_slot = |face: u32, roughness: f32| {
+            for value in [face as f32, roughness, environment_size, sample_count] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.resize(data.len() + PARAMS_SLOT_SIZE - PARAMS_SIZE, 0);
+        };
+
+        push_slot(0, 0.);
+        for face in 0..CUBE_FACES {
+            push_slot(face, 0.);
+        }
+        for mip_level in 0..mip_count {
+            let roughness = if mip_count > 1 {
+                mip_level as f32 / (mip_count - 1) as f32
+            } else {
+                0.
+            };
+            for face in 0..CUBE_FACES {
+                push_slot(face, roughness);
+            }
+        }
+
+        device.write_buffer(&self.params_buffer, 0, &data);
+    }
+
+    fn environment_bind_group(&self, device: &G::Device, environment: &G::Texture) -> G::BindGroup {
+        device.create_bind_group(BindGroupDescriptor {
+            layout: &self.environment_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Texture(environment),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    fn render_irradiance(&self, device: &G::Device, environment: &G::BindGroup) -> G::Texture {
+        let size = self.descriptor.irradiance_size;
+        let texture = self.create_cubemap(device, size, 1);
+        for face in 0..CUBE_FACES {
+            self.render_face(
+                device,
+                &self.irradiance_pipeline,
+                Some(environment),
+                TextureView {
+                    texture: &texture,
+                    mip_level: 0,
+                    slice: face,
+                },
+                size,
+                1 + face,
+            );
+        }
+        texture
+    }
+
+    fn render_prefiltered(&self, device: &G::Device, environment: &G::BindGroup) -> G::Texture {
+        let mip_count = self.descriptor.prefiltered_mip_level_count;
+        let texture = self.create_cubemap(device, self.descriptor.prefiltered_size, mip_count);
+        for mip_level in 0..mip_count {
+            let size = (self.descriptor.prefiltered_size >> mip_level).max(1);
+            for face in 0..CUBE_FACES {
+                self.render_face(
+                    device,
+                    &self.prefilter_pipeline,
+                    Some(environment),
+                    TextureView {
+                        texture: &texture,
+                        mip_level,
+                        slice: face,
+                    },
+                    size,
+                    1 + CUBE_FACES * (1 + mip_level) + face,
+                );
+            }
+        }
+        texture
+    }
+
+    fn render_brdf_lut(&self, device: &G::Device) -> G::Texture {
+        let size = self.descriptor.brdf_lut_size;
+        let texture = device.create_texture(TextureDescriptor {
+            size: Extent3D(size, size, 1),
+            format: self.descriptor.brdf_lut_format,
+            usage: TextureUsage::TEXTURE_BINDING | TextureUsage::RENDER_ATTACHMENT,
+            ..Default::default()
+        });
+        self.render_face(
+            device,
+            &self.brdf_lut_pipeline,
+            None,
+            TextureView::from(&texture),
+            size,
+            0,
+        );
+        texture
+    }
+
+    fn create_cubemap(&self, device: &G::Device, size: u32, mip_level_count: u32) -> G::Texture {
+        device.create_texture(TextureDescriptor {
+            size: Extent3D(size, size, CUBE_FACES),
+            mip_level_count,
+            dimension: TextureDimension::CubeMap,
+            format: self.descriptor.format,
+            usage: TextureUsage::TEXTURE_BINDING | TextureUsage::RENDER_ATTACHMENT,
+            ..Default::default()
+        })
+    }
+
+    fn render_face(
+        &self,
+        device: &G::Device,
+        pipeline: &G::RenderPipeline,
+        environment: Option<&G::BindGroup>,
+        view: TextureView<G>,
+        size: u32,
+        slot: u32,
+    ) {
+        let pass = device.create_render_pass(RenderPassDescriptor::Offscreen {
+            colors: &[ColorAttachment { view, clear: None }],
+            depth_stencil: None,
+            clear_depth: None,
+            clear_stencil: None,
+            multiview: None,
+        });
+
+        let encoder = device.render(&pass);
+        encoder.pipeline(pipeline);
+        encoder.viewport(0., 0., size as f32, size as f32, 0., 1.);
+        encoder.bind_group(
+            0,
+            &self.params_bind_group,
+            &[slot * PARAMS_SLOT_SIZE as u32],
+        );
+        if let Some(environment) = environment {
+            encoder.bind_group(1, environment, &[]);
+        }
+        encoder.draw(0..3, 0..1);
+        encoder.submit();
+    }
+}
@@ -0,0 +1,56 @@
+#version 300 es
+precision highp float;
+layout(std140) uniform Params {
+  // face, roughness, environment size, sample count
+  vec4 params;
+};
+in vec2 vUv;
+out vec4 outColor;
+const float PI = 3.14159265359;
+
+vec2 hammersley(uint i, uint n) {
+  uint bits = i;
+  bits = (bits << 16u) | (bits >> 16u);
+  bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+  bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+  bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+  bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+  return vec2(float(i) / float(n), float(bits) * 2.3283064365386963e-10);
+}
+
+vec3 importanceSampleGGX(vec2 xi, float a) {
+  float phi = 2.0 * PI * xi.x;
+  float cosTheta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+  float sinTheta = sqrt(1.0 - cosTheta * cosTheta);
+  return vec3(cos(phi) * sinTheta, sin(phi) * sinTheta, cosTheta);
+}
+
+float geometrySchlickGGX(float nDotX, float k) {
+  return nDotX / (nDotX * (1.0 - k) + k);
+}
+
+void main() {
+  // x = n.v, y = roughness
+  float nDotV = max(vUv.x, 0.0001);
+  float roughness = vUv.y;
+  float a = roughness * roughness;
+  float k = a / 2.0;
+  vec3 v = vec3(sqrt(1.0 - nDotV * nDotV), 0.0, nDotV);
+  uint count = uint(params.w);
+
+  vec2 lut = vec2(0.0);
+  for (uint i = 0u; i < count; ++i) {
+    vec3 h = importanceSampleGGX(hammersley(i, count), a);
+    float vDotH = max(dot(v, h), 0.0);
+    vec3 l = 2.0 * vDotH * h - v;
+    float nDotL = max(l.z, 0.0);
+    if (nDotL > 0.0) {
+      float nDotH = max(h.z, 0.0);
+      float g = geometrySchlickGGX(nDotV, k) * geometrySchlickGGX(nDotL, k);
+      float gVis = g * vDotH / max(nDotH * nDotV, 0.0001);
+      float fc = pow(1.0 - vDotH, 5.0);
+      lut += vec2((1.0 - fc) * gVis, fc * gVis);
+    }
+  }
+  outColor = vec4(lut / float(count), 0.0, 1.0);
+}
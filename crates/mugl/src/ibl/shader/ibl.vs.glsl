@@ -0,0 +1,9 @@
+#version 300 es
+precision highp float;
+out vec2 vUv;
+void main(void) {
+  // Fullscreen triangle
+  vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+  vUv = position;
+  gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
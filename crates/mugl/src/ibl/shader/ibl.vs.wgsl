@@ -0,0 +1,16 @@
+// Fullscreen triangle vertex shader
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let position = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.clip_position = vec4<f32>(position * 2.0 - 1.0, 0.0, 1.0);
+    // WebGPU framebuffer rows start from the top
+    out.uv = vec2<f32>(position.x, 1.0 - position.y);
+    return out;
+}
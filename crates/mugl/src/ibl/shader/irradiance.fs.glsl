@@ -0,0 +1,63 @@
+#version 300 es
+precision highp float;
+layout(std140) uniform Params {
+  // face, roughness, environment size, sample count
+  vec4 params;
+};
+uniform samplerCube env;
+in vec2 vUv;
+out vec4 outColor;
+const float PI = 3.14159265359;
+
+vec3 faceDirection(float face, vec2 uv) {
+  vec2 p = uv * 2.0 - 1.0;
+  int f = int(face + 0.5);
+  vec3 dir;
+  if (f == 0) dir = vec3(1.0, -p.y, -p.x);
+  else if (f == 1) dir = vec3(-1.0, -p.y, p.x);
+  else if (f == 2) dir = vec3(p.x, 1.0, p.y);
+  else if (f == 3) dir = vec3(p.x, -1.0, -p.y);
+  else if (f == 4) dir = vec3(p.x, -p.y, 1.0);
+  else dir = vec3(-p.x, -p.y, -1.0);
+  return normalize(dir);
+}
+
+vec2 hammersley(uint i, uint n) {
+  uint bits = i;
+  bits = (bits << 16u) | (bits >> 16u);
+  bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+  bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+  bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+  bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+  return vec2(float(i) / float(n), float(bits) * 2.3283064365386963e-10);
+}
+
+mat3 tangentFrame(vec3 n) {
+  vec3 up = abs(n.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+  vec3 t = normalize(cross(up, n));
+  return mat3(t, cross(n, t), n);
+}
+
+void main() {
+  vec3 n = faceDirection(params.x, vUv);
+  mat3 frame = tangentFrame(n);
+  uint count = uint(params.w);
+  float texelSolidAngle = 4.0 * PI / (6.0 * params.z * params.z);
+
+  vec3 irradiance = vec3(0.0);
+  for (uint i = 0u; i < count; ++i) {
+    // Cosine-weighted hemisphere sampling
+    vec2 xi = hammersley(i, count);
+    float phi = 2.0 * PI * xi.x;
+    float cosTheta = sqrt(1.0 - xi.y);
+    float sinTheta = sqrt(xi.y);
+    vec3 l = frame * vec3(cos(phi) * sinTheta, sin(phi) * sinTheta, cosTheta);
+
+    // Filtered importance sampling: pick the mip that matches the sample footprint
+    float pdf = cosTheta / PI;
+    float sampleSolidAngle = 1.0 / (float(count) * pdf + 0.0001);
+    float lod = max(0.5 * log2(sampleSolidAngle / texelSolidAngle) + 1.0, 0.0);
+    irradiance += textureLod(env, l, lod).rgb;
+  }
+  outColor = vec4(irradiance / float(count), 1.0);
+}
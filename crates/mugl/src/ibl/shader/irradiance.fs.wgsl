@@ -0,0 +1,79 @@
+struct Params {
+    // face, roughness, environment size, sample count
+    params: vec4<f32>;
+};
+[[group(0), binding(0)]]
+var<uniform> params: Params;
+
+[[group(1), binding(0)]]
+var env: texture_cube<f32>;
+[[group(1), binding(1)]]
+var env_sampler: sampler;
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+let PI: f32 = 3.14159265359;
+
+fn face_direction(face: f32, uv: vec2<f32>) -> vec3<f32> {
+    let p = uv * 2.0 - 1.0;
+    let f = i32(face + 0.5);
+    var dir: vec3<f32>;
+    if (f == 0) {
+        dir = vec3<f32>(1.0, -p.y, -p.x);
+    } else if (f == 1) {
+        dir = vec3<f32>(-1.0, -p.y, p.x);
+    } else if (f == 2) {
+        dir = vec3<f32>(p.x, 1.0, p.y);
+    } else if (f == 3) {
+        dir = vec3<f32>(p.x, -1.0, -p.y);
+    } else if (f == 4) {
+        dir = vec3<f32>(p.x, -p.y, 1.0);
+    } else {
+        dir = vec3<f32>(-p.x, -p.y, -1.0);
+    }
+    return normalize(dir);
+}
+
+fn hammersley(i: u32, n: u32) -> vec2<f32> {
+    var bits = i;
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return vec2<f32>(f32(i) / f32(n), f32(bits) * 2.3283064365386963e-10);
+}
+
+fn tangent_frame(n: vec3<f32>) -> mat3x3<f32> {
+    let up = select(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), abs(n.z) < 0.999);
+    let t = normalize(cross(up, n));
+    return mat3x3<f32>(t, cross(n, t), n);
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let n = face_direction(params.params.x, in.uv);
+    let frame = tangent_frame(n);
+    let count = u32(params.params.w);
+    let texel_solid_angle = 4.0 * PI / (6.0 * params.params.z * params.params.z);
+
+    var irradiance = vec3<f32>(0.0);
+    for (var i: u32 = 0u; i < count; i = i + 1u) {
+        // Cosine-weighted hemisphere sampling
+        let xi = hammersley(i, count);
+        let phi = 2.0 * PI * xi.x;
+        let cos_theta = sqrt(1.0 - xi.y);
+        let sin_theta = sqrt(xi.y);
+        let l = frame * vec3<f32>(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+        // Filtered importance sampling: pick the mip that matches the sample footprint
+        let pdf = cos_theta / PI;
+        let sample_solid_angle = 1.0 / (f32(count) * pdf + 0.0001);
+        let lod = max(0.5 * log2(sample_solid_angle / texel_solid_angle) + 1.0, 0.0);
+        irradiance = irradiance + textureSampleLevel(env, env_sampler, l, lod).rgb;
+    }
+    return vec4<f32>(irradiance / f32(count), 1.0);
+}
@@ -0,0 +1,84 @@
+#version 300 es
+precision highp float;
+layout(std140) uniform Params {
+  // face, roughness, environment size, sample count
+  vec4 params;
+};
+uniform samplerCube env;
+in vec2 vUv;
+out vec4 outColor;
+const float PI = 3.14159265359;
+
+vec3 faceDirection(float face, vec2 uv) {
+  vec2 p = uv * 2.0 - 1.0;
+  int f = int(face + 0.5);
+  vec3 dir;
+  if (f == 0) dir = vec3(1.0, -p.y, -p.x);
+  else if (f == 1) dir = vec3(-1.0, -p.y, p.x);
+  else if (f == 2) dir = vec3(p.x, 1.0, p.y);
+  else if (f == 3) dir = vec3(p.x, -1.0, -p.y);
+  else if (f == 4) dir = vec3(p.x, -p.y, 1.0);
+  else dir = vec3(-p.x, -p.y, -1.0);
+  return normalize(dir);
+}
+
+vec2 hammersley(uint i, uint n) {
+  uint bits = i;
+  bits = (bits << 16u) | (bits >> 16u);
+  bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+  bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+  bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+  bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+  return vec2(float(i) / float(n), float(bits) * 2.3283064365386963e-10);
+}
+
+mat3 tangentFrame(vec3 n) {
+  vec3 up = abs(n.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+  vec3 t = normalize(cross(up, n));
+  return mat3(t, cross(n, t), n);
+}
+
+vec3 importanceSampleGGX(vec2 xi, float a) {
+  float phi = 2.0 * PI * xi.x;
+  float cosTheta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+  float sinTheta = sqrt(1.0 - cosTheta * cosTheta);
+  return vec3(cos(phi) * sinTheta, sin(phi) * sinTheta, cosTheta);
+}
+
+float distributionGGX(float nDotH, float a) {
+  float a2 = a * a;
+  float d = nDotH * nDotH * (a2 - 1.0) + 1.0;
+  return a2 / (PI * d * d);
+}
+
+void main() {
+  vec3 n = faceDirection(params.x, vUv);
+  float roughness = params.y;
+  if (roughness <= 0.0) {
+    outColor = vec4(textureLod(env, n, 0.0).rgb, 1.0);
+    return;
+  }
+
+  // Split-sum approximation assumes n = v = r
+  mat3 frame = tangentFrame(n);
+  float a = roughness * roughness;
+  uint count = uint(params.w);
+  float texelSolidAngle = 4.0 * PI / (6.0 * params.z * params.z);
+
+  vec3 color = vec3(0.0);
+  float weight = 0.0;
+  for (uint i = 0u; i < count; ++i) {
+    vec3 h = frame * importanceSampleGGX(hammersley(i, count), a);
+    float nDotH = max(dot(n, h), 0.0);
+    vec3 l = 2.0 * nDotH * h - n;
+    float nDotL = dot(n, l);
+    if (nDotL > 0.0) {
+      float pdf = distributionGGX(nDotH, a) / 4.0;
+      float sampleSolidAngle = 1.0 / (float(count) * pdf + 0.0001);
+      float lod = max(0.5 * log2(sampleSolidAngle / texelSolidAngle) + 1.0, 0.0);
+      color += textureLod(env, l, lod).rgb * nDotL;
+      weight += nDotL;
+    }
+  }
+  outColor = vec4(color / max(weight, 0.0001), 1.0);
+}
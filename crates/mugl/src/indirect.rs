@@ -0,0 +1,64 @@
+//! Argument layouts of indirect draw calls.
+//!
+//! Indirect buffers are filled with tightly packed arrays of [DrawIndirectArgs] or [DrawIndexedIndirectArgs],
+//! either on the CPU via [DrawIndirectArgs::slice_as_bytes], or by a compute shader using the same layout.
+
+use core::mem::size_of;
+use core::slice;
+
+/// Required alignment in bytes of indirect buffer offsets.
+/// See: <https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-drawindirect>
+pub const INDIRECT_OFFSET_ALIGNMENT: u32 = 4;
+
+/// Arguments of a non-indexed indirect draw.
+/// See: <https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-drawindirect>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct DrawIndirectArgs {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+/// Arguments of an indexed indirect draw.
+/// See: <https://www.w3.org/TR/webgpu/#dom-gpurendercommandsmixin-drawindexedindirect>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct DrawIndexedIndirectArgs {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub first_instance: u32,
+}
+
+macro_rules! impl_indirect_args {
+    ($args:ty) => {
+        impl $args {
+            /// Size of the arguments in bytes.
+            pub const SIZE: u32 = size_of::<Self>() as u32;
+
+            /// Returns the byte representation of the arguments, to be written to an indirect buffer.
+            #[inline]
+            pub fn as_bytes(&self) -> &[u8] {
+                Self::slice_as_bytes(slice::from_ref(self))
+            }
+
+            /// Returns the byte representation of tightly packed arguments, to be written to an indirect buffer.
+            #[inline]
+            pub fn slice_as_bytes(args: &[Self]) -> &[u8] {
+                // Safety: the struct is repr(C) with only 4-byte integer fields, thus has no padding
+                unsafe {
+                    slice::from_raw_parts(
+                        args.as_ptr() as *const u8,
+                        args.len() * size_of::<Self>(),
+                    )
+                }
+            }
+        }
+    };
+}
+
+impl_indirect_args!(DrawIndirectArgs);
+impl_indirect_args!(DrawIndexedIndirectArgs);
@@ -0,0 +1,132 @@
+//! Per-instance vertex buffers for instanced rendering.
+
+use core::ops::Range;
+
+use crate::descriptor::{BufferDescriptor, VertexAttribute, VertexBufferLayout};
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::primitive::{BufferSize, BufferUsage};
+
+/// A growable vertex buffer of per-instance data, e.g. model matrices or colors.
+///
+/// The buffer stores tightly packed instances of a fixed stride, and is reallocated with doubled capacity
+/// when written with more instances than it can hold. Bind it to a slot described by
+/// [InstanceBuffer::layout], then draw with [InstanceBuffer::instances] as the instance range.
+#[derive(Debug)]
+pub struct InstanceBuffer<G: GPU> {
+    buffer: G::Buffer,
+    usage: BufferUsage,
+    stride: BufferSize,
+    capacity: u32,
+    len: u32,
+}
+
+impl<G: GPU> InstanceBuffer<G> {
+    /// Creates an empty instance buffer for instances of `stride` bytes, with room for `capacity` instances.
+    /// `usage` is combined with [BufferUsage::VERTEX], and should hint how often the data changes.
+    ///
+    /// # Panics
+    /// Panics if the stride is 0.
+    pub fn new(device: &G::Device, stride: BufferSize, capacity: u32, usage: BufferUsage) -> Self {
+        assert!(stride > 0, "instance stride must not be 0");
+        let usage = usage | BufferUsage::VERTEX;
+        let capacity = capacity.max(1);
+        Self {
+            buffer: create_buffer::<G>(device, usage, stride, capacity),
+            usage,
+            stride,
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// Gets the underlying vertex buffer.
+    #[inline]
+    pub fn buffer(&self) -> &G::Buffer {
+        &self.buffer
+    }
+
+    /// Gets the size of each instance in bytes.
+    #[inline]
+    pub fn stride(&self) -> BufferSize {
+        self.stride
+    }
+
+    /// Gets the number of instances the buffer can hold without reallocation.
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Gets the number of instances written.
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns true if no instance is written.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the instance range to draw all written instances.
+    #[inline]
+    pub fn instances(&self) -> Range<u32> {
+        0..self.len
+    }
+
+    /// Gets the per-instance vertex buffer layout of this buffer with given attributes.
+    #[inline]
+    pub fn layout<'a>(&self, attributes: &'a [VertexAttribute]) -> VertexBufferLayout<'a> {
+        VertexBufferLayout::per_instance(self.stride, attributes)
+    }
+
+    /// Replaces the instance data, growing the buffer if needed.
+    /// Returns true if the buffer was reallocated, in which case existing bind groups of the buffer are invalidated.
+    ///
+    /// # Panics
+    /// Panics if the data length is not a multiple of the stride.
+    pub fn write(&mut self, device: &G::Device, data: &[u8]) -> bool {
+        let size = data.len() as BufferSize;
+        let len = size / self.stride;
+        assert!(
+            len * self.stride == size,
+            "instance data length must be a multiple of the stride"
+        );
+        let len = len as u32;
+        let reallocated = len > self.capacity;
+        if reallocated {
+            self.capacity = len.max(self.capacity.saturating_mul(2));
+            self.buffer = create_buffer::<G>(device, self.usage, self.stride, self.capacity);
+        }
+        if !data.is_empty() {
+            device.write_buffer(&self.buffer, 0, data);
+        }
+        self.len = len;
+        reallocated
+    }
+
+    /// Removes all instances. The buffer memory is kept.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Binds the buffer to a vertex buffer slot of a render pass.
+    #[inline]
+    pub fn bind<'a, P: GPURenderPassEncoder<'a, G>>(&'a self, pass: &P, slot: u32) {
+        pass.vertex(slot, &self.buffer, 0);
+    }
+}
+
+fn create_buffer<G: GPU>(
+    device: &G::Device,
+    usage: BufferUsage,
+    stride: BufferSize,
+    capacity: u32,
+) -> G::Buffer {
+    device.create_buffer(BufferDescriptor {
+        size: stride * capacity as BufferSize,
+        usage,
+    })
+}
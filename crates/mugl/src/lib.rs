@@ -12,6 +12,19 @@ pub mod alias;
 pub mod descriptor;
 pub mod primitive;
 pub mod gl_const;
+pub mod limits;
+pub mod ibl;
+pub mod particle;
+pub mod pipeline;
+pub mod shader;
+pub mod draw;
+pub mod indirect;
+pub mod instance;
+pub mod dynamic_buffer;
+pub mod material;
+pub mod texture_array;
+pub mod sprite;
+pub mod builtin;
 
 pub use alias::*;
 pub use descriptor::*;
@@ -0,0 +1,346 @@
+//! Portable resource binding limits, device capabilities, and their validation.
+//!
+//! The binding limits are the defaults guaranteed by WebGPU, which are also supported by the WebGL backend.
+//! Exceeding them, passing dynamic offsets that do not match a bind group layout, or setting an invalid
+//! viewport depth range, produces backend-specific undefined behavior, so backends validate them in debug builds.
+//!
+//! Texture and sampler capabilities vary by device, so they are reported as [Limits] by
+//! [GPUDevice::limits](crate::gpu::GPUDevice::limits), and validated when creating resources.
+
+use alloc::{format, string::String};
+use bitflags::bitflags;
+
+use crate::descriptor::{
+    BindGroupLayoutDescriptor, BindingType, SamplerDescriptor, TextureDescriptor,
+};
+use crate::primitive::{
+    BufferSize, DepthRange, SamplerBindingType, TextureDimension, TextureFormat,
+};
+
+/// Maximum number of bind groups that can be bound at the same time.
+pub const MAX_BIND_GROUPS: u32 = 4;
+
+/// Maximum number of entries in a bind group layout.
+pub const MAX_BINDINGS_PER_BIND_GROUP: u32 = 1000;
+
+/// Maximum number of buffer bindings with dynamic offset in a bind group layout.
+pub const MAX_DYNAMIC_UNIFORM_BUFFERS_PER_BIND_GROUP: u32 = 8;
+
+/// Required alignment of dynamic buffer offsets, in bytes.
+pub const MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT: u32 = 256;
+
+/// Maximum size of a uniform buffer binding, in bytes.
+/// This is the minimum `MAX_UNIFORM_BLOCK_SIZE` of WebGL 2, which is lower than the WebGPU default of 64 KiB.
+pub const MAX_UNIFORM_BUFFER_BINDING_SIZE: BufferSize = 16384;
+
+/// Maximum number of texture units that can be used by a render pipeline on WebGL 2.
+/// Each pair of texture and sampler bindings combined by [BindGroupLayoutDescriptor::combined_samplers] takes one unit.
+pub const MAX_COMBINED_TEXTURE_IMAGE_UNITS: u32 = 32;
+
+bitflags! {
+    /// Depth/stencil texture formats supported by a device.
+    #[repr(transparent)]
+    pub struct DepthFormats: u32 {
+        const DEPTH16 = 0x0001;
+        const DEPTH24 = 0x0002;
+        const DEPTH24STENCIL8 = 0x0004;
+        const DEPTH32F = 0x0008;
+        const DEPTH32FSTENCIL8 = 0x0010;
+    }
+
+    /// Compressed texture format families supported by a device.
+    #[repr(transparent)]
+    #[derive(Default)]
+    pub struct TextureCompression: u32 {
+        /// BC (S3TC / DXT / RGTC / BPTC) formats, common on desktop.
+        const BC = 0x0001;
+        /// ETC2 / EAC formats, common on mobile.
+        const ETC2 = 0x0002;
+        /// ASTC LDR formats, common on mobile.
+        const ASTC = 0x0004;
+    }
+}
+
+/// Texture and sampler capabilities of a device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum width and height of 2D and cube map textures.
+    pub max_texture_dimension_2d: u32,
+    /// Maximum width, height and depth of 3D textures.
+    pub max_texture_dimension_3d: u32,
+    /// Maximum number of layers of 2D array textures.
+    pub max_texture_array_layers: u32,
+    /// Maximum number of bind groups of a render pipeline.
+    pub max_bind_groups: u32,
+    /// Maximum anisotropy of samplers, or 1 if anisotropic filtering is unsupported.
+    pub max_sampler_anisotropy: u8,
+    /// Supported depth/stencil formats.
+    pub depth_formats: DepthFormats,
+    /// Supported compressed texture formats.
+    pub texture_compression: TextureCompression,
+}
+
+impl Limits {
+    /// Returns if a texture format is supported.
+    pub fn supports_format(&self, format: TextureFormat) -> bool {
+        let depth_format = match format {
+            TextureFormat::DEPTH16 => DepthFormats::DEPTH16,
+            TextureFormat::DEPTH24 => DepthFormats::DEPTH24,
+            TextureFormat::DEPTH24STENCIL8 => DepthFormats::DEPTH24STENCIL8,
+            TextureFormat::DEPTH32F => DepthFormats::DEPTH32F,
+            TextureFormat::DEPTH32FSTENCIL8 => DepthFormats::DEPTH32FSTENCIL8,
+            _ => return true,
+        };
+        self.depth_formats.contains(depth_format)
+    }
+}
+
+impl Default for Limits {
+    /// Returns the minimum capabilities guaranteed by WebGL 2, which are also supported by WebGPU.
+    fn default() -> Self {
+        Self {
+            max_texture_dimension_2d: 2048,
+            max_texture_dimension_3d: 256,
+            max_texture_array_layers: 256,
+            max_bind_groups: MAX_BIND_GROUPS,
+            max_sampler_anisotropy: 1,
+            depth_formats: DepthFormats::DEPTH16
+                | DepthFormats::DEPTH24
+                | DepthFormats::DEPTH24STENCIL8
+                | DepthFormats::DEPTH32F,
+            texture_compression: TextureCompression::empty(),
+        }
+    }
+}
+
+impl<'a> BindGroupLayoutDescriptor<'a> {
+    /// Returns the number of buffer bindings with dynamic offset,
+    /// i.e. the number of offsets to pass when binding a bind group of this layout.
+    pub fn dynamic_offset_count(&self) -> u32 {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.ty,
+                    BindingType::Buffer {
+                        dynamic_offset: true
+                    }
+                )
+            })
+            .count() as u32
+    }
+
+    /// Returns the `(texture, sampler)` binding pairs that are combined into texture units on WebGL,
+    /// which binds sampler objects to texture units together with textures.
+    ///
+    /// A texture binding is paired with every sampler binding of the same label, so a texture can be sampled
+    /// by multiple samplers, and a sampler can be shared by multiple textures.
+    pub fn combined_samplers(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.ty, BindingType::Texture { .. }))
+            .flat_map(move |texture| {
+                self.entries
+                    .iter()
+                    .filter(move |sampler| {
+                        matches!(sampler.ty, BindingType::Sampler { .. })
+                            && sampler.label == texture.label
+                    })
+                    .map(move |sampler| (texture.binding, sampler.binding))
+            })
+    }
+
+    /// Returns the number of texture units used by this layout on WebGL.
+    /// Textures without a paired sampler take one unit each.
+    pub fn texture_unit_count(&self) -> u32 {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.ty, BindingType::Texture { .. }))
+            .map(|texture| {
+                let samplers = self
+                    .combined_samplers()
+                    .filter(|(binding, _)| *binding == texture.binding)
+                    .count();
+                samplers.max(1) as u32
+            })
+            .sum()
+    }
+}
+
+/// Validates a bind group layout against the binding limits.
+/// Returns the error message if any limit is exceeded.
+pub fn validate_bind_group_layout(descriptor: &BindGroupLayoutDescriptor) -> Result<(), String> {
+    if descriptor.entries.len() > MAX_BINDINGS_PER_BIND_GROUP as usize {
+        return Err(format!(
+            "bind group layout has {} entries, exceeding the limit of {}",
+            descriptor.entries.len(),
+            MAX_BINDINGS_PER_BIND_GROUP
+        ));
+    }
+    let dynamic_offsets = descriptor.dynamic_offset_count();
+    if dynamic_offsets > MAX_DYNAMIC_UNIFORM_BUFFERS_PER_BIND_GROUP {
+        return Err(format!(
+            "bind group layout has {} dynamic offset buffers, exceeding the limit of {}",
+            dynamic_offsets, MAX_DYNAMIC_UNIFORM_BUFFERS_PER_BIND_GROUP
+        ));
+    }
+    validate_texture_units([descriptor.texture_unit_count()])
+}
+
+/// Validates the slot and dynamic offsets of a bind group binding,
+/// where `dynamic_offset_count` is the number of dynamic offset buffers of the bind group layout.
+/// Returns the error message if the binding is invalid.
+pub fn validate_dynamic_offsets(
+    slot: u32,
+    dynamic_offset_count: u32,
+    offsets: &[u32],
+) -> Result<(), String> {
+    if slot >= MAX_BIND_GROUPS {
+        return Err(format!(
+            "bind group slot {} exceeds the limit of {} bind groups",
+            slot, MAX_BIND_GROUPS
+        ));
+    }
+    if offsets.len() != dynamic_offset_count as usize {
+        return Err(format!(
+            "bind group at slot {} requires {} dynamic offsets, but {} were given",
+            slot,
+            dynamic_offset_count,
+            offsets.len()
+        ));
+    }
+    if let Some(offset) = offsets
+        .iter()
+        .find(|offset| *offset % MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT != 0)
+    {
+        return Err(format!(
+            "dynamic offset {} of bind group at slot {} is not a multiple of {}",
+            offset, slot, MIN_UNIFORM_BUFFER_OFFSET_ALIGNMENT
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the depth range of a viewport, which must be a valid [DepthRange].
+/// Returns the error message if the range is out of `[0, 1]` or reversed.
+pub fn validate_viewport_depth(min_depth: f32, max_depth: f32) -> Result<(), String> {
+    if !DepthRange::new(min_depth, max_depth).is_valid() {
+        return Err(format!(
+            "viewport depth range [{}, {}] must be within [0, 1] with min_depth <= max_depth",
+            min_depth, max_depth
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the total number of texture units used by the bind group layouts of a render pipeline,
+/// where `unit_counts` are the [BindGroupLayoutDescriptor::texture_unit_count] of each layout.
+/// Returns the error message if the limit is exceeded.
+pub fn validate_texture_units(unit_counts: impl IntoIterator<Item = u32>) -> Result<(), String> {
+    let units: u32 = unit_counts.into_iter().sum();
+    if units > MAX_COMBINED_TEXTURE_IMAGE_UNITS {
+        return Err(format!(
+            "render pipeline uses {} texture units, exceeding MAX_COMBINED_TEXTURE_IMAGE_UNITS of {}",
+            units, MAX_COMBINED_TEXTURE_IMAGE_UNITS
+        ));
+    }
+    Ok(())
+}
+
+/// Validates a texture against the size and format capabilities of a device.
+/// Returns the error message if the texture is not supported.
+pub fn validate_texture(limits: &Limits, descriptor: &TextureDescriptor) -> Result<(), String> {
+    let (width, height, depth) = (descriptor.size.0, descriptor.size.1, descriptor.size.2);
+    let (max_size, max_depth, depth_name) = match descriptor.dimension {
+        TextureDimension::D3 => (
+            limits.max_texture_dimension_3d,
+            limits.max_texture_dimension_3d,
+            "depth",
+        ),
+        TextureDimension::D2Array => (
+            limits.max_texture_dimension_2d,
+            limits.max_texture_array_layers,
+            "array layers",
+        ),
+        TextureDimension::D2 | TextureDimension::CubeMap => {
+            (limits.max_texture_dimension_2d, u32::MAX, "depth")
+        }
+    };
+    if width > max_size || height > max_size {
+        return Err(format!(
+            "{:?} texture size {}x{} exceeds the maximum of {}",
+            descriptor.dimension, width, height, max_size
+        ));
+    }
+    if depth > max_depth {
+        return Err(format!(
+            "{:?} texture {} {} exceeds the maximum of {}",
+            descriptor.dimension, depth_name, depth, max_depth
+        ));
+    }
+    if !limits.supports_format(descriptor.format) {
+        return Err(format!(
+            "texture format {:?} is not supported by the device, which supports depth formats {:?}",
+            descriptor.format, limits.depth_formats
+        ));
+    }
+    Ok(())
+}
+
+/// Validates a sampler against the anisotropy limit of a device.
+/// Returns the error message if the anisotropy is not supported.
+pub fn validate_sampler(limits: &Limits, descriptor: &SamplerDescriptor) -> Result<(), String> {
+    if descriptor.max_anisotropy > limits.max_sampler_anisotropy {
+        return Err(format!(
+            "sampler anisotropy {} exceeds the maximum of {}",
+            descriptor.max_anisotropy, limits.max_sampler_anisotropy
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the number of bind groups of a render pipeline against the limit of a device.
+/// Returns the error message if the limit is exceeded.
+pub fn validate_bind_group_count(limits: &Limits, count: usize) -> Result<(), String> {
+    if count > limits.max_bind_groups as usize {
+        return Err(format!(
+            "render pipeline has {} bind groups, exceeding the limit of {}",
+            count, limits.max_bind_groups
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the attachments of an offscreen render pass, which needs at least one color attachment,
+/// or a depth-stencil attachment for a depth-only pass, e.g. a shadow map.
+/// Returns the error message if the pass has no attachment.
+pub fn validate_render_pass_attachments(
+    color_count: usize,
+    depth_stencil: bool,
+) -> Result<(), String> {
+    if color_count == 0 && !depth_stencil {
+        return Err(String::from(
+            "offscreen render pass must have a color or depth-stencil attachment",
+        ));
+    }
+    Ok(())
+}
+
+/// Validates that a sampler bound at given binding matches its sampler binding type,
+/// i.e. only [SamplerBindingType::Comparison] bindings take samplers with a compare function.
+/// Returns the error message if they do not match.
+pub fn validate_sampler_binding(
+    binding: u32,
+    ty: SamplerBindingType,
+    compare: bool,
+) -> Result<(), String> {
+    if (ty == SamplerBindingType::Comparison) != compare {
+        return Err(format!(
+            "sampler at binding {} must {}have a compare function for a {:?} sampler binding",
+            binding,
+            if compare { "not " } else { "" },
+            ty
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,325 @@
+//! Material parameters of many draws in a single uniform buffer, selected per draw by index.
+//!
+//! Material parameter structs are stored in a uniform array, laid out by [UniformStruct] to match both the
+//! WGSL uniform and the GLSL std140 layouts, so that one bind group serves all materials.
+//! As neither WebGPU nor WebGL support push constants, the material index of a draw is passed as a per-instance
+//! vertex attribute instead, by binding a buffer of indices at the offset of the material with
+//! [MaterialBuffer::bind_index].
+//!
+//! In shaders, declare the struct with [UniformStruct::declaration], then the uniform array of materials,
+//! e.g. `var<uniform> materials: array<Material, 256>;` in WGSL or
+//! `layout(std140) uniform Materials { Material materials[256]; };` in GLSL,
+//! and index it with the index attribute converted to an integer.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::descriptor::{BindingResource, BufferDescriptor, VertexAttribute, VertexBufferLayout};
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::limits::MAX_UNIFORM_BUFFER_BINDING_SIZE;
+use crate::primitive::{BufferSize, BufferUsage, VertexFormat};
+use crate::shader::ShaderLanguage;
+
+/// Alignment of uniform structs and arrays.
+const UNIFORM_STRUCT_ALIGNMENT: BufferSize = 16;
+
+/// Byte size of a material index in the index buffer.
+const INDEX_SIZE: BufferSize = 4;
+
+/// Type of a [UniformStruct] field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UniformType {
+    F32,
+    I32,
+    U32,
+    Vec2,
+    Vec3,
+    Vec4,
+    Mat4,
+}
+
+impl UniformType {
+    /// Returns the byte size of this type.
+    pub const fn size(&self) -> BufferSize {
+        match self {
+            Self::F32 | Self::I32 | Self::U32 => 4,
+            Self::Vec2 => 8,
+            Self::Vec3 => 12,
+            Self::Vec4 => 16,
+            Self::Mat4 => 64,
+        }
+    }
+
+    /// Returns the byte alignment of this type.
+    pub const fn align(&self) -> BufferSize {
+        match self {
+            Self::F32 | Self::I32 | Self::U32 => 4,
+            Self::Vec2 => 8,
+            Self::Vec3 | Self::Vec4 | Self::Mat4 => 16,
+        }
+    }
+
+    /// Returns the name of this type in a shader language.
+    pub const fn name(&self, language: ShaderLanguage) -> &'static str {
+        match (language, self) {
+            (ShaderLanguage::Wgsl, Self::F32) => "f32",
+            (ShaderLanguage::Wgsl, Self::I32) => "i32",
+            (ShaderLanguage::Wgsl, Self::U32) => "u32",
+            (ShaderLanguage::Wgsl, Self::Vec2) => "vec2<f32>",
+            (ShaderLanguage::Wgsl, Self::Vec3) => "vec3<f32>",
+            (ShaderLanguage::Wgsl, Self::Vec4) => "vec4<f32>",
+            (ShaderLanguage::Wgsl, Self::Mat4) => "mat4x4<f32>",
+            (ShaderLanguage::Glsl, Self::F32) => "float",
+            (ShaderLanguage::Glsl, Self::I32) => "int",
+            (ShaderLanguage::Glsl, Self::U32) => "uint",
+            (ShaderLanguage::Glsl, Self::Vec2) => "vec2",
+            (ShaderLanguage::Glsl, Self::Vec3) => "vec3",
+            (ShaderLanguage::Glsl, Self::Vec4) => "vec4",
+            (ShaderLanguage::Glsl, Self::Mat4) => "mat4",
+        }
+    }
+}
+
+/// A field of a [UniformStruct].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UniformField {
+    pub name: String,
+    pub ty: UniformType,
+    /// Byte offset of the field in the struct.
+    pub offset: BufferSize,
+}
+
+/// Layout of a struct in uniform buffers, identical in WGSL and GLSL std140, e.g. for material parameters.
+///
+/// Fields are placed in order at the next offset aligned for their type, as done by the shader compilers,
+/// so that the struct can be filled by writing each field at its [UniformStruct::offset].
+/// The struct size is padded to 16 bytes, the array stride of uniform structs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UniformStruct {
+    fields: Vec<UniformField>,
+    end: BufferSize,
+}
+
+impl UniformStruct {
+    /// Creates an empty struct layout.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns this layout with a field appended.
+    pub fn field(mut self, name: &str, ty: UniformType) -> Self {
+        let offset = align(self.end, ty.align());
+        self.fields.push(UniformField {
+            name: name.into(),
+            ty,
+            offset,
+        });
+        self.end = offset + ty.size();
+        self
+    }
+
+    /// Gets the fields.
+    #[inline]
+    pub fn fields(&self) -> &[UniformField] {
+        &self.fields
+    }
+
+    /// Gets the byte offset of a field by name.
+    pub fn offset(&self, name: &str) -> Option<BufferSize> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.offset)
+    }
+
+    /// Gets the padded byte size of the struct, which is also its array stride.
+    #[inline]
+    pub fn size(&self) -> BufferSize {
+        align(self.end, UNIFORM_STRUCT_ALIGNMENT).max(UNIFORM_STRUCT_ALIGNMENT)
+    }
+
+    /// Returns the declaration of the struct in a shader language, with trailing `f32` padding fields
+    /// named `_pad0`, `_pad1`, etc., so that arrays of the struct have the same stride in all languages.
+    pub fn declaration(&self, name: &str, language: ShaderLanguage) -> String {
+        let mut code = String::new();
+        let _ = writeln!(code, "struct {} {{", name);
+        let padding = (0..(self.size() - self.end) / UniformType::F32.size())
+            .map(|i| (format!("_pad{}", i), UniformType::F32));
+        for (name, ty) in self
+            .fields
+            .iter()
+            .map(|field| (field.name.clone(), field.ty))
+            .chain(padding)
+        {
+            let _ = match language {
+                ShaderLanguage::Wgsl => writeln!(code, "    {}: {};", name, ty.name(language)),
+                ShaderLanguage::Glsl => writeln!(code, "    {} {};", ty.name(language), name),
+            };
+        }
+        code.push_str("};\n");
+        code
+    }
+}
+
+/// A uniform buffer of material parameter structs, with a vertex buffer of indices to select them per draw.
+///
+/// Bind [MaterialBuffer::binding] to a uniform array binding of [MaterialBuffer::capacity] structs, and
+/// the indices to a vertex buffer slot described by [MaterialBuffer::index_layout].
+/// Before each draw, [MaterialBuffer::bind_index] selects the material read by the per-instance `f32`
+/// index attribute. As the index advances per instance, instanced draws read consecutive indices,
+/// so instanced meshes should pass their material index with the instance data instead.
+///
+/// Material data is staged in memory, and uploaded by [MaterialBuffer::flush].
+#[derive(Debug)]
+pub struct MaterialBuffer<G: GPU> {
+    buffer: G::Buffer,
+    indices: G::Buffer,
+    stride: BufferSize,
+    capacity: u32,
+    data: Vec<u8>,
+    /// Range of data changed since the last flush.
+    dirty: Option<(BufferSize, BufferSize)>,
+}
+
+impl<G: GPU> MaterialBuffer<G> {
+    /// Creates a buffer of `capacity` materials, each of `stride` bytes, e.g. [UniformStruct::size].
+    ///
+    /// # Panics
+    /// Panics if the stride is not a positive multiple of 16, or the buffer exceeds
+    /// [MAX_UNIFORM_BUFFER_BINDING_SIZE].
+    pub fn new(device: &G::Device, stride: BufferSize, capacity: u32) -> Self {
+        assert!(
+            stride > 0 && align(stride, UNIFORM_STRUCT_ALIGNMENT) == stride,
+            "material stride must be a positive multiple of 16"
+        );
+        let capacity = capacity.max(1);
+        let size = stride * capacity as BufferSize;
+        assert!(
+            size <= MAX_UNIFORM_BUFFER_BINDING_SIZE,
+            "material buffer of {} bytes exceeds MAX_UNIFORM_BUFFER_BINDING_SIZE of {}",
+            size,
+            MAX_UNIFORM_BUFFER_BINDING_SIZE
+        );
+
+        let indices = device.create_buffer(BufferDescriptor {
+            size: INDEX_SIZE * capacity as BufferSize,
+            usage: BufferUsage::VERTEX,
+        });
+        let index_data = (0..capacity)
+            .flat_map(|index| (index as f32).to_le_bytes())
+            .collect::<Vec<_>>();
+        device.write_buffer(&indices, 0, &index_data);
+
+        Self {
+            buffer: device.create_buffer(BufferDescriptor {
+                size,
+                usage: BufferUsage::UNIFORM | BufferUsage::DYNAMIC,
+            }),
+            indices,
+            stride,
+            capacity,
+            data: vec![0; size],
+            dirty: None,
+        }
+    }
+
+    /// Gets the size of each material in bytes.
+    #[inline]
+    pub fn stride(&self) -> BufferSize {
+        self.stride
+    }
+
+    /// Gets the number of materials the buffer holds.
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Gets the uniform buffer of materials.
+    #[inline]
+    pub fn buffer(&self) -> &G::Buffer {
+        &self.buffer
+    }
+
+    /// Gets the binding resource of the materials, for a bind group entry of a uniform array binding.
+    #[inline]
+    pub fn binding(&self) -> BindingResource<'_, G> {
+        BindingResource::Buffer {
+            buffer: &self.buffer,
+            offset: 0,
+            size: self.data.len(),
+        }
+    }
+
+    /// Gets the vertex attribute of material indices at given shader location.
+    #[inline]
+    pub const fn index_attribute(shader_location: u32) -> VertexAttribute {
+        VertexAttribute {
+            format: VertexFormat::F32,
+            offset: 0,
+            shader_location,
+        }
+    }
+
+    /// Gets the per-instance vertex buffer layout of material indices, with given attributes,
+    /// usually `[MaterialBuffer::index_attribute(location)]`.
+    #[inline]
+    pub const fn index_layout(attributes: &[VertexAttribute]) -> VertexBufferLayout<'_> {
+        VertexBufferLayout::per_instance(INDEX_SIZE, attributes)
+    }
+
+    /// Stages the data of a material, which may be shorter than the stride.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds, or the data is longer than the stride.
+    pub fn set(&mut self, index: u32, data: &[u8]) {
+        assert!(index < self.capacity, "material index out of bounds");
+        assert!(
+            data.len() <= self.stride,
+            "material data exceeds the stride of {} bytes",
+            self.stride
+        );
+        let start = self.stride * index as BufferSize;
+        let end = start + data.len();
+        self.data[start..end].copy_from_slice(data);
+        self.dirty = Some(match self.dirty {
+            Some((dirty_start, dirty_end)) => (dirty_start.min(start), dirty_end.max(end)),
+            None => (start, end),
+        });
+    }
+
+    /// Uploads the materials staged since the last flush.
+    pub fn flush(&mut self, device: &G::Device) {
+        if let Some((start, end)) = self.dirty.take() {
+            // Writes are aligned to 4 bytes, which the stride is a multiple of
+            let (start, end) = (start - start % 4, align(end, 4));
+            device.write_buffer(&self.buffer, start, &self.data[start..end]);
+        }
+    }
+
+    /// Binds the material indices to a vertex buffer slot, so that the next draw of a single instance
+    /// reads given material index.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    #[inline]
+    pub fn bind_index<'a, P: GPURenderPassEncoder<'a, G>>(
+        &'a self,
+        pass: &P,
+        slot: u32,
+        index: u32,
+    ) {
+        assert!(index < self.capacity, "material index out of bounds");
+        pass.vertex(slot, &self.indices, INDEX_SIZE * index as BufferSize);
+    }
+}
+
+/// Rounds up a size to a multiple of the alignment.
+#[inline]
+fn align(size: BufferSize, alignment: BufferSize) -> BufferSize {
+    size.div_ceil(alignment) * alignment
+}
@@ -0,0 +1,432 @@
+//! Portable GPU particle simulation.
+//!
+//! [ParticleSim] stores the state of each particle in a texel of float textures, and updates all particles
+//! in a fullscreen fragment shader pass that ping-pongs between two sets of state textures.
+//! This does not need compute shaders or transform feedback, so it runs on both the WebGL and WebGPU backends.
+//! On WebGL, rendering to float textures requires the `EXT_color_buffer_float` extension.
+//!
+//! Particles can be drawn by fetching their states in the vertex shader using the instance index,
+//! from the textures returned by [ParticleSim::positions] and [ParticleSim::velocities].
+
+use alloc::vec::Vec;
+
+use crate::descriptor::{
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, BufferDescriptor, ColorAttachment, ColorTargetState,
+    ColorTargetStates, ImageCopyTexture, ImageDataLayout, RenderPassDescriptor,
+    RenderPipelineDescriptor, SamplerDescriptor, ShaderDescriptor, TextureDescriptor,
+};
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::primitive::{
+    AddressMode, BufferSize, BufferUsage, Color, Extent3D, FilterMode, Origin3D,
+    SamplerBindingType, ShaderStage, TextureDimension, TextureFormat, TextureSampleType,
+    TextureUsage,
+};
+
+/// Maximum width of the state textures.
+const MAX_TEXTURE_WIDTH: u32 = 256;
+
+/// Size of the uniform data.
+const PARAMS_SIZE: BufferSize = 16;
+
+/// Shader sources for the particle update pass.
+#[derive(Clone, Copy, Debug)]
+pub struct ParticleShaders<'a> {
+    pub vertex: &'a str,
+    pub update: &'a str,
+}
+
+impl ParticleShaders<'static> {
+    /// Built-in WGSL shaders, for the WebGPU backend.
+    pub const WGSL: Self = Self {
+        vertex: include_str!("shader/particle.vs.wgsl"),
+        update: include_str!("shader/update.fs.wgsl"),
+    };
+
+    /// Built-in GLSL ES 3.0 shaders, for the WebGL backend.
+    pub const GLSL: Self = Self {
+        vertex: include_str!("shader/particle.vs.glsl"),
+        update: include_str!("shader/update.fs.glsl"),
+    };
+}
+
+impl Default for ParticleShaders<'static> {
+    /// Returns the built-in shaders matching [crate::DefaultGPU].
+    fn default() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_family = "wasm", feature = "backend-webgl"))] {
+                Self::GLSL
+            } else {
+                Self::WGSL
+            }
+        }
+    }
+}
+
+/// This specifies the particle state storage.
+#[derive(Clone, Copy, Debug)]
+pub struct ParticleDescriptor {
+    /// Maximum number of particles.
+    pub capacity: u32,
+    /// Format of the state textures. Must be [TextureFormat::RGBA16F] or [TextureFormat::RGBA32F].
+    /// Note that 32-bit float textures are not filterable, thus cannot be sampled on WebGPU.
+    pub format: TextureFormat,
+}
+
+impl Default for ParticleDescriptor {
+    fn default() -> Self {
+        Self {
+            capacity: 65536,
+            format: TextureFormat::RGBA16F,
+        }
+    }
+}
+
+/// Initial state of a particle.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Particle {
+    pub position: [f32; 3],
+    pub velocity: [f32; 3],
+    /// Lifetime in seconds. The particle stops updating once its age reaches the lifetime.
+    pub lifetime: f32,
+}
+
+/// Simulates particles on the GPU under constant gravity.
+#[derive(Debug)]
+pub struct ParticleSim<G: GPU> {
+    descriptor: ParticleDescriptor,
+    width: u32,
+    height: u32,
+    pipeline: G::RenderPipeline,
+    params_buffer: G::Buffer,
+    params_bind_group: G::BindGroup,
+    positions: [G::Texture; 2],
+    velocities: [G::Texture; 2],
+    // Bind group reading state i, and pass writing into state i
+    state_bind_groups: [G::BindGroup; 2],
+    passes: [G::RenderPass; 2],
+    current: usize,
+}
+
+impl<G: GPU> ParticleSim<G> {
+    /// Creates the state textures, pipeline and passes for a particle simulation.
+    /// All particles are initially dead.
+    ///
+    /// # Panics
+    /// Panics if the descriptor format is not a 4-channel float format.
+    pub fn new(
+        device: &G::Device,
+        shaders: ParticleShaders,
+        descriptor: ParticleDescriptor,
+    ) -> Self {
+        assert!(
+            matches!(
+                descriptor.format,
+                TextureFormat::RGBA16F | TextureFormat::RGBA32F
+            ),
+            "particle state format must be RGBA16F or RGBA32F"
+        );
+        let width = descriptor.capacity.clamp(1, MAX_TEXTURE_WIDTH);
+        let height = descriptor.capacity.div_ceil(width).max(1);
+
+        let params_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                label: "Params",
+                binding: 0,
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::Buffer {
+                    dynamic_offset: false,
+                },
+            }],
+        });
+        let texture_entries = |label, binding| {
+            [
+                BindGroupLayoutEntry {
+                    label,
+                    binding,
+                    visibility: ShaderStage::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float,
+                        dimension: TextureDimension::D2,
+                        multisampled: false,
+                    },
+                },
+                BindGroupLayoutEntry {
+                    label,
+                    binding: binding + 1,
+                    visibility: ShaderStage::FRAGMENT,
+                    ty: BindingType::Sampler {
+                        ty: SamplerBindingType::NonFiltering,
+                    },
+                },
+            ]
+        };
+        let [position_texture, position_sampler] = texture_entries("position", 0);
+        let [velocity_texture, velocity_sampler] = texture_entries("velocity", 2);
+        let state_layout = device.create_bind_group_layout(BindGroupLayoutDescriptor {
+            entries: &[
+                position_texture,
+                position_sampler,
+                velocity_texture,
+                velocity_sampler,
+            ],
+        });
+
+        let vertex = device.create_shader(ShaderDescriptor {
+            code: shaders.vertex,
+            usage: ShaderStage::VERTEX,
+        });
+        let fragment = device.create_shader(ShaderDescriptor {
+            code: shaders.update,
+            usage: ShaderStage::FRAGMENT,
+        });
+        let target = ColorTargetState {
+            format: descriptor.format,
+            ..Default::default()
+        };
+        let pipeline = device.create_render_pipeline(RenderPipelineDescriptor {
+            vertex: &vertex,
+            fragment: &fragment,
+            buffers: &[],
+            bind_groups: &[&params_layout, &state_layout],
+            primitive: Default::default(),
+            multisample: Default::default(),
+            depth_stencil: None,
+            targets: ColorTargetStates::Offscreen {
+                targets: &[target, target],
+            },
+            multiview: None,
+        });
+
+        let params_buffer = device.create_buffer(BufferDescriptor {
+            size: PARAMS_SIZE,
+            usage: BufferUsage::UNIFORM | BufferUsage::DYNAMIC,
+        });
+        let params_bind_group = device.create_bind_group(BindGroupDescriptor {
+            layout: &params_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::Buffer {
+                    buffer: &params_buffer,
+                    offset: 0,
+                    size: PARAMS_SIZE,
+                },
+            }],
+        });
+
+        let sampler = device.create_sampler(SamplerDescriptor {
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            mipmap_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+        let create_state = || {
+            device.create_texture(TextureDescriptor {
+                size: Extent3D(width, height, 1),
+                format: descriptor.format,
+                usage: TextureUsage::TEXTURE_BINDING | TextureUsage::RENDER_ATTACHMENT,
+                ..Default::default()
+            })
+        };
+        let positions = [create_state(), create_state()];
+        let velocities = [create_state(), create_state()];
+
+        let create_state_bind_group = |i: usize| {
+            device.create_bind_group(BindGroupDescriptor {
+                layout: &state_layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::Texture(&positions[i]),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::Texture(&velocities[i]),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::Sampler(&sampler),
+                    },
+                ],
+            })
+        };
+        let state_bind_groups = [create_state_bind_group(0), create_state_bind_group(1)];
+
+        let create_pass = |i: usize, clear| {
+            device.create_render_pass(RenderPassDescriptor::Offscreen {
+                colors: &[
+                    ColorAttachment {
+                        view: (&positions[i]).into(),
+                        clear,
+                    },
+                    ColorAttachment {
+                        view: (&velocities[i]).into(),
+                        clear,
+                    },
+                ],
+                depth_stencil: None,
+                clear_depth: None,
+                clear_stencil: None,
+                multiview: None,
+            })
+        };
+
+        // Clear the initial state, so that all particles are dead with zero lifetime
+        let clear_pass = create_pass(0, Some(Color(0., 0., 0., 0.)));
+        device.render(&clear_pass).submit();
+
+        let passes = [create_pass(0, None), create_pass(1, None)];
+
+        Self {
+            descriptor,
+            width,
+            height,
+            pipeline,
+            params_buffer,
+            params_bind_group,
+            positions,
+            velocities,
+            state_bind_groups,
+            passes,
+            current: 0,
+        }
+    }
+
+    /// Gets the descriptor of the particle state storage.
+    #[inline]
+    pub fn descriptor(&self) -> &ParticleDescriptor {
+        &self.descriptor
+    }
+
+    /// Gets the size of the state textures. Particle i is stored at texel `(i % width, i / width)`.
+    #[inline]
+    pub fn texture_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Gets the current position texture. Each texel stores the position in XYZ and the age in W.
+    #[inline]
+    pub fn positions(&self) -> &G::Texture {
+        &self.positions[self.current]
+    }
+
+    /// Gets the current velocity texture. Each texel stores the velocity in XYZ and the lifetime in W.
+    #[inline]
+    pub fn velocities(&self) -> &G::Texture {
+        &self.velocities[self.current]
+    }
+
+    /// Spawns particles starting from index `first`, replacing existing particles at those indices.
+    /// Particles beyond the capacity are ignored.
+    pub fn spawn(&self, device: &G::Device, first: u32, particles: &[Particle]) {
+        let capacity = self.width * self.height;
+        let count = (particles.len() as u32).min(capacity.saturating_sub(first));
+
+        let mut i = 0;
+        while i < count {
+            // Write one texture row at a time
+            let index = first + i;
+            let (x, y) = (index % self.width, index / self.width);
+            let row_count = (self.width - x).min(count - i);
+            let row = &particles[(i as usize)..((i + row_count) as usize)];
+
+            let mut positions = Vec::new();
+            let mut velocities = Vec::new();
+            for particle in row {
+                let [px, py, pz] = particle.position;
+                let [vx, vy, vz] = particle.velocity;
+                self.push_texel(&mut positions, [px, py, pz, 0.]);
+                self.push_texel(&mut velocities, [vx, vy, vz, particle.lifetime]);
+            }
+
+            let layout = ImageDataLayout {
+                offset: 0,
+                bytes_per_row: positions.len() as u32,
+                rows_per_image: 1,
+            };
+            let size = Extent3D(row_count, 1, 1);
+            for (texture, data) in [
+                (self.positions(), &positions),
+                (self.velocities(), &velocities),
+            ] {
+                device.write_texture(
+                    ImageCopyTexture {
+                        texture,
+                        mip_level: 0,
+                        origin: Origin3D(x, y, 0),
+                    },
+                    data,
+                    layout,
+                    size,
+                );
+            }
+
+            i += row_count;
+        }
+    }
+
+    /// Advances the simulation by a time step of `dt` seconds under given gravity.
+    pub fn update(&mut self, device: &G::Device, dt: f32, gravity: [f32; 3]) {
+        let mut data = Vec::with_capacity(PARAMS_SIZE);
+        for value in [gravity[0], gravity[1], gravity[2], dt] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        device.write_buffer(&self.params_buffer, 0, &data);
+
+        let next = 1 - self.current;
+        let encoder = device.render(&self.passes[next]);
+        encoder.pipeline(&self.pipeline);
+        encoder.viewport(0., 0., self.width as f32, self.height as f32, 0., 1.);
+        encoder.bind_group(0, &self.params_bind_group, &[]);
+        encoder.bind_group(1, &self.state_bind_groups[self.current], &[]);
+        encoder.draw(0..3, 0..1);
+        encoder.submit();
+
+        self.current = next;
+    }
+
+    fn push_texel(&self, data: &mut Vec<u8>, texel: [f32; 4]) {
+        for value in texel {
+            if matches!(self.descriptor.format, TextureFormat::RGBA32F) {
+                data.extend_from_slice(&value.to_le_bytes());
+            } else {
+                data.extend_from_slice(&f16_bits(value).to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Converts a float into the bits of a half float, rounding to nearest.
+fn f16_bits(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity or NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        if exponent < -10 {
+            sign
+        } else {
+            // Subnormal
+            let mantissa = (mantissa | 0x80_0000) >> (1 - exponent);
+            sign | ((mantissa + 0x1000) >> 13) as u16
+        }
+    } else {
+        // Rounding may carry into the exponent, which is still correct
+        sign | ((((exponent as u32) << 10) | (mantissa >> 13)) + ((mantissa >> 12) & 1)) as u16
+    }
+}
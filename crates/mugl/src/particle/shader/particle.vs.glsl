@@ -0,0 +1,7 @@
+#version 300 es
+precision highp float;
+void main(void) {
+  // Fullscreen triangle
+  vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+  gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
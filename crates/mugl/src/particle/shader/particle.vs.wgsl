@@ -0,0 +1,7 @@
+// Fullscreen triangle vertex shader
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> [[builtin(position)]] vec4<f32> {
+    let position = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(position * 2.0 - 1.0, 0.0, 1.0);
+}
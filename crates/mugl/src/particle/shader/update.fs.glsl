@@ -0,0 +1,29 @@
+#version 300 es
+precision highp float;
+layout(std140) uniform Params {
+  // xyz = gravity, w = time step
+  vec4 gravityDt;
+};
+// xyz = position, w = age
+uniform sampler2D position;
+// xyz = velocity, w = lifetime
+uniform sampler2D velocity;
+layout(location = 0) out vec4 outPosition;
+layout(location = 1) out vec4 outVelocity;
+
+void main() {
+  ivec2 texel = ivec2(gl_FragCoord.xy);
+  vec4 p = texelFetch(position, texel, 0);
+  vec4 v = texelFetch(velocity, texel, 0);
+
+  // Only alive particles are updated
+  if (p.w < v.w) {
+    float dt = gravityDt.w;
+    v.xyz += gravityDt.xyz * dt;
+    p.xyz += v.xyz * dt;
+    p.w += dt;
+  }
+
+  outPosition = p;
+  outVelocity = v;
+}
@@ -0,0 +1,36 @@
+// Particle update shader. Each texel stores the state of one particle.
+
+struct Params {
+    // xyz = gravity, w = time step
+    gravity_dt: vec4<f32>;
+};
+
+struct FragmentOutput {
+    // xyz = position, w = age
+    [[location(0)]] position: vec4<f32>;
+    // xyz = velocity, w = lifetime
+    [[location(1)]] velocity: vec4<f32>;
+};
+
+[[group(0), binding(0)]] var<uniform> params: Params;
+[[group(1), binding(0)]] var position_texture: texture_2d<f32>;
+[[group(1), binding(2)]] var velocity_texture: texture_2d<f32>;
+
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] coord: vec4<f32>) -> FragmentOutput {
+    let texel = vec2<i32>(coord.xy);
+    var position = textureLoad(position_texture, texel, 0);
+    var velocity = textureLoad(velocity_texture, texel, 0);
+
+    // Only alive particles are updated
+    if (position.w < velocity.w) {
+        let dt = params.gravity_dt.w;
+        velocity = vec4<f32>(velocity.xyz + params.gravity_dt.xyz * dt, velocity.w);
+        position = vec4<f32>(position.xyz + velocity.xyz * dt, position.w + dt);
+    }
+
+    var out: FragmentOutput;
+    out.position = position;
+    out.velocity = velocity;
+    return out;
+}
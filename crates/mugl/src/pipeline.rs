@@ -0,0 +1,155 @@
+//! Shared pipeline state objects and render pipeline cache.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::descriptor::{
+    BlendState, ColorTargetState, ColorTargetStates, DepthStencilState, RenderPipelineDescriptor,
+};
+use crate::gpu::{GPUDevice, GPU};
+
+/// Handle to a shared depth-stencil state of a [PipelineCache].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DepthStencilStateHandle(u32);
+
+/// Handle to a shared blend state of a [PipelineCache].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlendStateHandle(u32);
+
+/// Key of a cached render pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PipelineKey {
+    /// Application-defined ID of the remaining pipeline states, e.g. a shader program with its vertex layout.
+    pub base: u32,
+    /// Depth-stencil state of the pipeline. `None` keeps the state of the base descriptor.
+    pub depth_stencil: Option<DepthStencilStateHandle>,
+    /// Blend state of all color targets. `None` keeps the states of the base descriptor.
+    pub blend: Option<BlendStateHandle>,
+}
+
+/// Cache of render pipelines that share immutable depth-stencil and blend state objects.
+///
+/// GPU backends only support monolithic pipelines, so a pipeline is still created per combination of states.
+/// The cache interns states into small handles, so that materials using the same combination share one pipeline,
+/// and switching between common combinations does not require duplicating full pipeline descriptors.
+#[derive(Debug)]
+pub struct PipelineCache<G: GPU> {
+    depth_stencil_states: Vec<DepthStencilState>,
+    blend_states: Vec<BlendState>,
+    pipelines: BTreeMap<PipelineKey, G::RenderPipeline>,
+}
+
+impl<G: GPU> PipelineCache<G> {
+    /// Creates an empty pipeline cache.
+    pub fn new() -> Self {
+        Self {
+            depth_stencil_states: Vec::new(),
+            blend_states: Vec::new(),
+            pipelines: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the shared handle of a depth-stencil state. Equal states share the same handle.
+    pub fn depth_stencil_state(&mut self, state: DepthStencilState) -> DepthStencilStateHandle {
+        DepthStencilStateHandle(intern(&mut self.depth_stencil_states, state))
+    }
+
+    /// Returns the shared handle of a blend state. Equal states share the same handle.
+    pub fn blend_state(&mut self, state: BlendState) -> BlendStateHandle {
+        BlendStateHandle(intern(&mut self.blend_states, state))
+    }
+
+    /// Gets the depth-stencil state of a handle.
+    #[inline]
+    pub fn get_depth_stencil_state(&self, handle: DepthStencilStateHandle) -> &DepthStencilState {
+        &self.depth_stencil_states[handle.0 as usize]
+    }
+
+    /// Gets the blend state of a handle.
+    #[inline]
+    pub fn get_blend_state(&self, handle: BlendStateHandle) -> &BlendState {
+        &self.blend_states[handle.0 as usize]
+    }
+
+    /// Gets a cached pipeline.
+    #[inline]
+    pub fn get(&self, key: &PipelineKey) -> Option<&G::RenderPipeline> {
+        self.pipelines.get(key)
+    }
+
+    /// Gets a cached pipeline, or creates it from a base descriptor with the states of the key applied.
+    /// The base descriptor must describe the same pipeline for the same `key.base`.
+    pub fn get_or_create(
+        &mut self,
+        device: &G::Device,
+        key: PipelineKey,
+        base: RenderPipelineDescriptor<G>,
+    ) -> &G::RenderPipeline {
+        if !self.pipelines.contains_key(&key) {
+            let mut descriptor = base;
+            if let Some(handle) = key.depth_stencil {
+                descriptor.depth_stencil = Some(*self.get_depth_stencil_state(handle));
+            }
+
+            let blend = key.blend.map(|handle| *self.get_blend_state(handle));
+            let targets: Vec<ColorTargetState>;
+            if let Some(blend) = blend {
+                descriptor.targets = match descriptor.targets {
+                    ColorTargetStates::Default { write_mask, .. } => ColorTargetStates::Default {
+                        write_mask,
+                        blend: Some(blend),
+                    },
+                    ColorTargetStates::Offscreen { targets: base } => {
+                        targets = base
+                            .iter()
+                            .map(|target| ColorTargetState {
+                                blend: Some(blend),
+                                ..*target
+                            })
+                            .collect();
+                        ColorTargetStates::Offscreen { targets: &targets }
+                    }
+                };
+            }
+
+            let pipeline = device.create_render_pipeline(descriptor);
+            self.pipelines.insert(key, pipeline);
+        }
+        &self.pipelines[&key]
+    }
+
+    /// Returns the number of cached pipelines.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    /// Returns true if there is no cached pipeline.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.is_empty()
+    }
+
+    /// Destroys all cached pipelines. State handles remain valid.
+    pub fn clear(&mut self) {
+        self.pipelines.clear();
+    }
+}
+
+impl<G: GPU> Default for PipelineCache<G> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the index of a value in a list, inserting it if not found.
+fn intern<T: PartialEq>(values: &mut Vec<T>, value: T) -> u32 {
+    match values.iter().position(|v| *v == value) {
+        Some(i) => i as u32,
+        None => {
+            values.push(value);
+            (values.len() - 1) as u32
+        }
+    }
+}
@@ -94,6 +94,55 @@ impl From<Color<f32>> for Color {
     }
 }
 
+/// A viewport depth range, mapping normalized depth in `[0, 1]` to depth buffer values.
+/// Valid ranges are within `[0, 1]` with `min <= max`. Reversed-Z is done with the projection matrix instead.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct DepthRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for DepthRange {
+    #[inline]
+    fn default() -> Self {
+        Self { min: 0., max: 1. }
+    }
+}
+
+impl DepthRange {
+    /// Creates a depth range.
+    #[inline]
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the depth buffer value of a normalized depth in `[0, 1]`.
+    #[inline]
+    pub fn remap(&self, depth: f32) -> f32 {
+        self.min + depth * (self.max - self.min)
+    }
+
+    /// Returns the `index`-th of `count` equal partitions of this range, ordered from near to far.
+    /// Drawing scene layers into separate partitions, e.g. a far landscape and near objects with their own
+    /// near and far planes, keeps the depth precision of each layer while depth testing them against each other.
+    #[inline]
+    pub fn partition(&self, index: u32, count: u32) -> Self {
+        let count = count.max(1) as f32;
+        Self {
+            min: self.remap(index as f32 / count),
+            max: self.remap((index + 1) as f32 / count),
+        }
+    }
+
+    /// Returns `true` if this range is within `[0, 1]`, and is not reversed.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        0. <= self.min && self.min <= self.max && self.max <= 1.
+    }
+}
+
 bitflags! {
     /// Buffer usage.
     /// See: <https://www.w3.org/TR/webgpu/#buffer-usage>
@@ -108,6 +157,7 @@ bitflags! {
         const INDEX = 0x0010;
         const VERTEX = 0x0020;
         const UNIFORM = 0x0040;
+        const INDIRECT = 0x0100;
 
         // OpenGL buffer usage hints
         const DYNAMIC = 0x1000;
@@ -236,6 +286,9 @@ pub enum TextureFormat {
     RGBA8I = gl_const::RGBA8I,
     // Packed 32-bit formats
     RGB10A2 = gl_const::RGB10_A2,
+    // BGRA formats, only used by WebGPU surfaces. These have no WebGL equivalent.
+    BGRA8 = 0x93A1, // GL_BGRA8_EXT
+    SBGRA8 = 0x93A2,
 
     // 64-bit formats
     RG32UI = gl_const::RG32UI,
@@ -310,6 +363,8 @@ impl TextureFormat {
             | TextureFormat::RGBA8UI
             | TextureFormat::RGBA8I
             | TextureFormat::RGB10A2
+            | TextureFormat::BGRA8
+            | TextureFormat::SBGRA8
             | TextureFormat::R32F
             | TextureFormat::RG16F
             | TextureFormat::RG11B10F => 4,
@@ -645,3 +700,25 @@ pub enum MipmapHint {
     Fast = gl_const::FASTEST,
     Nice = gl_const::NICEST,
 }
+
+/// Type of the queries in a query set.
+/// See: <https://www.w3.org/TR/webgpu/#enumdef-gpuquerytype>
+#[cfg_attr(feature = "serde", derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum QueryType {
+    /// Counts the samples that pass the depth and stencil tests between the begin and end of a query.
+    Occlusion = 0,
+    /// Records the GPU time at which all previous commands have finished.
+    Timestamp = 1,
+}
+
+/// Severity of a message reported by a GPU backend.
+#[cfg_attr(feature = "serde", derive(serde_repr::Serialize_repr, serde_repr::Deserialize_repr))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum LogLevel {
+    Error = 0,
+    Warning = 1,
+    Info = 2,
+}
@@ -0,0 +1,325 @@
+//! Shader preprocessor, for sharing shader code across backends.
+//!
+//! The preprocessor supports the following directives, each on its own line:
+//! - `#include "name"` / `#include <name>` inserts the source resolved by the include resolver.
+//!   Each name is included at most once per shader, so include guards are not needed.
+//! - `#define NAME value` / `#undef NAME` defines / undefines a name. Following occurrences of the name
+//!   are replaced by its value.
+//! - `#ifdef NAME` / `#ifndef NAME` / `#else` / `#endif` conditionally include code.
+//!
+//! The target language is predefined as `WGSL` or `GLSL`, so that a single source can hold the code of both.
+//! Other directives, e.g. `#extension` in GLSL, are kept as-is.
+
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::descriptor::ShaderDescriptor;
+use crate::gpu::{GPUDevice, GPU};
+use crate::primitive::ShaderStage;
+
+/// Version directive of GLSL ES 3.0 shaders.
+const GLSL_VERSION: &str = "#version 300 es";
+
+/// Resolver of included shader sources by name.
+pub type ShaderIncludeResolver<'a> = dyn Fn(&str) -> Option<Cow<'a, str>> + 'a;
+
+/// Target language of preprocessed shaders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderLanguage {
+    /// WGSL, for the WebGPU backend.
+    Wgsl,
+    /// GLSL ES 3.0, for the WebGL backend.
+    Glsl,
+}
+
+impl ShaderLanguage {
+    /// Returns the name predefined when preprocessing shaders of this language.
+    pub const fn define(&self) -> &'static str {
+        match self {
+            ShaderLanguage::Wgsl => "WGSL",
+            ShaderLanguage::Glsl => "GLSL",
+        }
+    }
+}
+
+impl Default for ShaderLanguage {
+    /// Returns the shader language of [crate::DefaultGPU].
+    fn default() -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_family = "wasm", feature = "backend-webgl"))] {
+                Self::Glsl
+            } else {
+                Self::Wgsl
+            }
+        }
+    }
+}
+
+/// Error when preprocessing a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderPreprocessError {
+    /// The include resolver cannot resolve the named source.
+    IncludeNotFound(String),
+    /// A directive is malformed, or a conditional block is not terminated.
+    /// `file` is the name of the included source, or empty for the main source. `line` starts from 1.
+    InvalidDirective { file: String, line: usize },
+}
+
+impl fmt::Display for ShaderPreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderPreprocessError::IncludeNotFound(name) => {
+                write!(f, "shader include not found: {}", name)
+            }
+            ShaderPreprocessError::InvalidDirective { file, line } => {
+                write!(f, "invalid shader directive at {}:{}", file, line)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ShaderPreprocessError {}
+
+/// Preprocesses shader sources for a target [ShaderLanguage]. See the [module documentation](self) for the syntax.
+///
+/// GLSL outputs always start with a `#version` directive, which defaults to GLSL ES 3.0 if the source has none.
+pub struct ShaderPreprocessor<'a> {
+    language: ShaderLanguage,
+    defines: BTreeMap<String, String>,
+    resolver: Option<Box<ShaderIncludeResolver<'a>>>,
+}
+
+impl<'a> ShaderPreprocessor<'a> {
+    /// Creates a preprocessor for given target language.
+    pub fn new(language: ShaderLanguage) -> Self {
+        let mut defines = BTreeMap::new();
+        defines.insert(language.define().to_string(), String::new());
+        Self {
+            language,
+            defines,
+            resolver: None,
+        }
+    }
+
+    /// Defines a name for all shaders, as if by a `#define` directive.
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the resolver of `#include` directives. Without a resolver, all includes fail to resolve.
+    pub fn resolver(mut self, resolver: impl Fn(&str) -> Option<Cow<'a, str>> + 'a) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Gets the target language.
+    #[inline]
+    pub fn language(&self) -> ShaderLanguage {
+        self.language
+    }
+
+    /// Preprocesses a shader source.
+    pub fn preprocess(&self, source: &str) -> Result<String, ShaderPreprocessError> {
+        let mut state = State {
+            defines: self.defines.clone(),
+            included: BTreeSet::new(),
+            version: None,
+            output: String::with_capacity(source.len()),
+        };
+        self.process(&mut state, "", source)?;
+
+        Ok(match self.language {
+            ShaderLanguage::Wgsl => state.output,
+            ShaderLanguage::Glsl => {
+                let version = state.version.as_deref().unwrap_or(GLSL_VERSION);
+                let mut code = String::with_capacity(version.len() + 1 + state.output.len());
+                code.push_str(version);
+                code.push('\n');
+                code.push_str(&state.output);
+                code
+            }
+        })
+    }
+
+    /// Preprocesses a shader source and creates the shader.
+    pub fn create_shader<G: GPU>(
+        &self,
+        device: &G::Device,
+        source: &str,
+        usage: ShaderStage,
+    ) -> Result<G::Shader, ShaderPreprocessError> {
+        let code = self.preprocess(source)?;
+        Ok(device.create_shader(ShaderDescriptor { code: &code, usage }))
+    }
+
+    fn process(
+        &self,
+        state: &mut State,
+        file: &str,
+        source: &str,
+    ) -> Result<(), ShaderPreprocessError> {
+        let mut conditions = Vec::<Condition>::new();
+        let mut line_number = 0;
+
+        for line in source.lines() {
+            line_number += 1;
+            let error = || ShaderPreprocessError::InvalidDirective {
+                file: file.into(),
+                line: line_number,
+            };
+            let active = conditions.iter().all(|condition| condition.active);
+
+            if let Some(directive) = line.trim_start().strip_prefix('#') {
+                let directive = directive.trim();
+                let (keyword, args) = directive
+                    .split_once(char::is_whitespace)
+                    .map_or((directive, ""), |(keyword, args)| (keyword, args.trim()));
+
+                match keyword {
+                    "ifdef" | "ifndef" => {
+                        let name = identifier(args).ok_or_else(error)?;
+                        let defined = state.defines.contains_key(name);
+                        conditions.push(Condition {
+                            parent: active,
+                            active: active && (defined == (keyword == "ifdef")),
+                            has_else: false,
+                        });
+                    }
+                    "else" => {
+                        let condition = conditions
+                            .last_mut()
+                            .filter(|condition| !condition.has_else)
+                            .ok_or_else(error)?;
+                        condition.has_else = true;
+                        condition.active = condition.parent && !condition.active;
+                    }
+                    "endif" => {
+                        conditions.pop().ok_or_else(error)?;
+                    }
+                    _ if !active => {}
+                    "define" => {
+                        let (name, value) = args
+                            .split_once(char::is_whitespace)
+                            .map_or((args, ""), |(name, value)| (name, value.trim()));
+                        let name = identifier(name).ok_or_else(error)?;
+                        let value = substitute(&state.defines, value);
+                        state.defines.insert(name.into(), value);
+                    }
+                    "undef" => {
+                        let name = identifier(args).ok_or_else(error)?;
+                        state.defines.remove(name);
+                    }
+                    "include" => {
+                        let name = args
+                            .strip_prefix('"')
+                            .and_then(|name| name.strip_suffix('"'))
+                            .or_else(|| args.strip_prefix('<')?.strip_suffix('>'))
+                            .ok_or_else(error)?;
+                        if state.included.insert(name.into()) {
+                            let source = self
+                                .resolver
+                                .as_ref()
+                                .and_then(|resolver| resolver(name))
+                                .ok_or_else(|| {
+                                    ShaderPreprocessError::IncludeNotFound(name.into())
+                                })?;
+                            self.process(state, name, &source)?;
+                        }
+                    }
+                    "version" if self.language == ShaderLanguage::Glsl => {
+                        state.version = Some(line.trim().into());
+                    }
+                    _ => {
+                        state.output.push_str(line);
+                        state.output.push('\n');
+                    }
+                }
+            } else if active {
+                let line = substitute(&state.defines, line);
+                state.output.push_str(&line);
+                state.output.push('\n');
+            }
+        }
+
+        if conditions.is_empty() {
+            Ok(())
+        } else {
+            Err(ShaderPreprocessError::InvalidDirective {
+                file: file.into(),
+                line: line_number,
+            })
+        }
+    }
+}
+
+impl fmt::Debug for ShaderPreprocessor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShaderPreprocessor")
+            .field("language", &self.language)
+            .field("defines", &self.defines)
+            .field("resolver", &self.resolver.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
+
+/// Preprocessing state of a shader.
+struct State {
+    defines: BTreeMap<String, String>,
+    included: BTreeSet<String>,
+    version: Option<String>,
+    output: String,
+}
+
+/// State of a conditional block.
+struct Condition {
+    /// Whether the enclosing block is active.
+    parent: bool,
+    /// Whether the current branch is active.
+    active: bool,
+    has_else: bool,
+}
+
+/// Returns the string if it is a valid identifier.
+fn identifier(name: &str) -> Option<&str> {
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    if (first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// Replaces every defined identifier in a line of code by its value.
+fn substitute(defines: &BTreeMap<String, String>, line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        let (before, from) = rest.split_at(start);
+        let end = from
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(from.len());
+        let word = &from[..end];
+        result.push_str(before);
+        match defines.get(word) {
+            // Skip letters of numeric literals, e.g. 1.0f or 0x1F
+            Some(value) if !before.ends_with(|c: char| c.is_ascii_digit()) => {
+                result.push_str(value)
+            }
+            _ => result.push_str(word),
+        }
+        rest = &from[end..];
+    }
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,370 @@
+//! 2D sprite batching, and rectangle packing for sprite atlases.
+//!
+//! [SpriteBatch] collects the textured quads of a frame with a [DrawKey] each, sorts them by key, and submits
+//! one indexed draw per run of consecutive sprites sharing the same pipeline and material,
+//! binding pipelines and materials only when they change.
+//! [RectPacker] allocates the sub-images of a texture atlas, so that sprites of many images can share a material
+//! and be drawn together.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::descriptor::{BufferDescriptor, VertexAttribute, VertexBufferLayout};
+use crate::draw::DrawKey;
+use crate::gpu::{GPUDevice, GPURenderPassEncoder, GPU};
+use crate::primitive::{BufferSize, BufferUsage, Extent2D, Origin2D, VertexFormat};
+
+/// Size of a sprite vertex in bytes.
+pub const SPRITE_VERTEX_STRIDE: BufferSize = 20;
+
+/// Attributes of a sprite vertex: position (`vec2<f32>`) at location 0, texture coordinates (`vec2<f32>`)
+/// at location 1, and normalized RGBA color (`vec4<f32>`) at location 2.
+pub const SPRITE_VERTEX_ATTRIBUTES: [VertexAttribute; 3] = [
+    VertexAttribute {
+        format: VertexFormat::F32x2,
+        offset: 0,
+        shader_location: 0,
+    },
+    VertexAttribute {
+        format: VertexFormat::F32x2,
+        offset: 8,
+        shader_location: 1,
+    },
+    VertexAttribute {
+        format: VertexFormat::UNORM8x4,
+        offset: 16,
+        shader_location: 2,
+    },
+];
+
+/// Vertex buffer layout of sprite vertices, for the render pipelines drawing a [SpriteBatch].
+pub const SPRITE_VERTEX_LAYOUT: VertexBufferLayout<'static> =
+    VertexBufferLayout::per_vertex(SPRITE_VERTEX_STRIDE, &SPRITE_VERTEX_ATTRIBUTES);
+
+/// Maximum number of sprites drawn from one vertex buffer binding, limited by 16-bit indices.
+const MAX_SPRITES_PER_BINDING: u32 = 0x10000 / 4;
+
+/// An axis-aligned textured quad.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprite {
+    /// Position of the top-left corner.
+    pub position: [f32; 2],
+    /// Width and height. The bottom-right corner is at `position + size`.
+    pub size: [f32; 2],
+    /// Texture coordinates of the top-left and bottom-right corners, as `[u0, v0, u1, v1]`.
+    pub uv: [f32; 4],
+    /// Color multiplied with the texture, in RGBA.
+    pub color: [u8; 4],
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Self {
+            position: [0., 0.],
+            size: [1., 1.],
+            uv: [0., 0., 1., 1.],
+            color: [255; 4],
+        }
+    }
+}
+
+/// A run of sprites drawn with a single draw call.
+#[derive(Clone, Debug)]
+struct SpriteRun {
+    pipeline: u16,
+    material: u16,
+    sprites: Range<u32>,
+}
+
+/// A batch of sprites, drawn with as few draw calls and state changes as possible.
+///
+/// Each frame, [SpriteBatch::push] the sprites with a [DrawKey] whose pipeline and material IDs index the
+/// pipelines and material bind groups passed to [SpriteBatch::submit], then [SpriteBatch::flush] them
+/// to the GPU before submitting them to a render pass. The material bind group is bound to slot 0.
+/// Sprites with equal keys keep their push order, so blended sprites can be ordered by depth or by push order.
+///
+/// The vertex buffer grows as needed, and uses [SPRITE_VERTEX_LAYOUT] at slot 0.
+/// Sprites are drawn as triangle lists with 16-bit indices, in the winding order of
+/// top-left, top-right, bottom-left for y-down coordinates.
+#[derive(Debug)]
+pub struct SpriteBatch<G: GPU> {
+    vertex_buffer: G::Buffer,
+    index_buffer: G::Buffer,
+    capacity: u32,
+    sprites: Vec<(DrawKey, Sprite)>,
+    vertices: Vec<u8>,
+    runs: Vec<SpriteRun>,
+}
+
+impl<G: GPU> SpriteBatch<G> {
+    /// Creates an empty batch with buffers for `capacity` sprites.
+    pub fn new(device: &G::Device, capacity: u32) -> Self {
+        let capacity = capacity.max(1);
+        let (vertex_buffer, index_buffer) = create_buffers::<G>(device, capacity);
+        Self {
+            vertex_buffer,
+            index_buffer,
+            capacity,
+            sprites: Vec::new(),
+            vertices: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// Gets the number of sprites the buffers can hold without reallocation.
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Gets the number of pushed sprites.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Returns true if no sprite is pushed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Gets the number of draw calls of the flushed sprites.
+    #[inline]
+    pub fn draw_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Adds a sprite to draw.
+    #[inline]
+    pub fn push(&mut self, key: DrawKey, sprite: Sprite) {
+        self.sprites.push((key, sprite));
+    }
+
+    /// Removes all sprites, keeping the buffers for reuse in the next frame.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+        self.runs.clear();
+    }
+
+    /// Sorts the sprites by key, and uploads their vertices, growing the buffers if needed.
+    pub fn flush(&mut self, device: &G::Device) {
+        self.sprites.sort_by_key(|(key, _)| *key);
+
+        let len = self.sprites.len() as u32;
+        if len > self.capacity {
+            self.capacity = len.max(self.capacity.saturating_mul(2));
+            let (vertex_buffer, index_buffer) = create_buffers::<G>(device, self.capacity);
+            self.vertex_buffer = vertex_buffer;
+            self.index_buffer = index_buffer;
+        }
+
+        self.vertices.clear();
+        self.runs.clear();
+        for (i, (key, sprite)) in self.sprites.iter().enumerate() {
+            write_vertices(&mut self.vertices, sprite);
+
+            let i = i as u32;
+            match self.runs.last_mut() {
+                Some(run)
+                    if run.pipeline == key.pipeline()
+                        && run.material == key.material()
+                        && run.sprites.start / MAX_SPRITES_PER_BINDING
+                            == i / MAX_SPRITES_PER_BINDING =>
+                {
+                    run.sprites.end = i + 1;
+                }
+                _ => self.runs.push(SpriteRun {
+                    pipeline: key.pipeline(),
+                    material: key.material(),
+                    sprites: i..(i + 1),
+                }),
+            }
+        }
+
+        if !self.vertices.is_empty() {
+            device.write_buffer(&self.vertex_buffer, 0, &self.vertices);
+        }
+    }
+
+    /// Submits the flushed sprites to a render pass, where the pipeline and material IDs of the sprite keys
+    /// index `pipelines` and `materials`.
+    ///
+    /// # Panics
+    /// Panics if a key refers to a pipeline or material out of bounds.
+    pub fn submit<'a, P: GPURenderPassEncoder<'a, G>>(
+        &'a self,
+        pass: &P,
+        pipelines: &[&'a G::RenderPipeline],
+        materials: &[&'a G::BindGroup],
+    ) {
+        let mut pipeline = None;
+        let mut material = None;
+        let mut binding = None;
+
+        for run in &self.runs {
+            if pipeline != Some(run.pipeline) {
+                pass.pipeline(pipelines[run.pipeline as usize]);
+                pass.index(&self.index_buffer);
+                pipeline = Some(run.pipeline);
+            }
+            if material != Some(run.material) {
+                pass.bind_group(0, materials[run.material as usize], &[]);
+                material = Some(run.material);
+            }
+
+            // Runs never cross a binding, as each binding covers the sprites reachable by 16-bit indices
+            let run_binding = run.sprites.start / MAX_SPRITES_PER_BINDING;
+            if binding != Some(run_binding) {
+                let offset = run_binding * MAX_SPRITES_PER_BINDING * 4;
+                pass.vertex(
+                    0,
+                    &self.vertex_buffer,
+                    offset as BufferSize * SPRITE_VERTEX_STRIDE,
+                );
+                binding = Some(run_binding);
+            }
+
+            let start = run.sprites.start - run_binding * MAX_SPRITES_PER_BINDING;
+            let end = run.sprites.end - run_binding * MAX_SPRITES_PER_BINDING;
+            pass.draw_indexed((start * 6)..(end * 6), 0..1);
+        }
+    }
+}
+
+/// Creates the vertex and index buffers for `capacity` sprites, and writes the indices.
+fn create_buffers<G: GPU>(device: &G::Device, capacity: u32) -> (G::Buffer, G::Buffer) {
+    let vertex_buffer = device.create_buffer(BufferDescriptor {
+        size: capacity as BufferSize * 4 * SPRITE_VERTEX_STRIDE,
+        usage: BufferUsage::VERTEX | BufferUsage::STREAM,
+    });
+
+    let quads = capacity.min(MAX_SPRITES_PER_BINDING) as u16;
+    let mut indices = Vec::with_capacity(quads as usize * 12);
+    for quad in 0..quads {
+        let base = quad * 4;
+        for index in [base, base + 1, base + 2, base + 2, base + 1, base + 3] {
+            indices.extend_from_slice(&index.to_le_bytes());
+        }
+    }
+    let index_buffer = device.create_buffer(BufferDescriptor {
+        size: indices.len(),
+        usage: BufferUsage::INDEX,
+    });
+    device.write_buffer(&index_buffer, 0, &indices);
+
+    (vertex_buffer, index_buffer)
+}
+
+/// Writes the 4 vertices of a sprite, in the order of top-left, top-right, bottom-left, bottom-right.
+fn write_vertices(vertices: &mut Vec<u8>, sprite: &Sprite) {
+    let [x0, y0] = sprite.position;
+    let (x1, y1) = (x0 + sprite.size[0], y0 + sprite.size[1]);
+    let [u0, v0, u1, v1] = sprite.uv;
+    for [x, y, u, v] in [
+        [x0, y0, u0, v0],
+        [x1, y0, u1, v0],
+        [x0, y1, u0, v1],
+        [x1, y1, u1, v1],
+    ] {
+        for value in [x, y, u, v] {
+            vertices.extend_from_slice(&value.to_le_bytes());
+        }
+        vertices.extend_from_slice(&sprite.color);
+    }
+}
+
+/// A shelf packer of rectangles into a texture atlas.
+///
+/// Rectangles are placed left to right on horizontal shelves, each as tall as the first rectangle placed on it.
+/// A rectangle goes to the shelf that fits it with the least wasted height, or to a new shelf at the bottom.
+/// This works well for rectangles of similar heights, e.g. glyphs or sprites, so packing them
+/// sorted by descending height gives the tightest result.
+#[derive(Clone, Debug)]
+pub struct RectPacker {
+    size: Extent2D,
+    padding: u32,
+    shelves: Vec<Shelf>,
+    /// Bottom of the last shelf.
+    bottom: u32,
+}
+
+/// A row of packed rectangles.
+#[derive(Clone, Copy, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Right end of the packed rectangles.
+    x: u32,
+}
+
+impl RectPacker {
+    /// Creates an empty packer for an atlas of given size,
+    /// keeping `padding` texels between rectangles to avoid bleeding when sampling with filtering.
+    pub fn new(size: Extent2D, padding: u32) -> Self {
+        Self {
+            size,
+            padding,
+            shelves: Vec::new(),
+            bottom: 0,
+        }
+    }
+
+    /// Gets the size of the atlas.
+    #[inline]
+    pub fn size(&self) -> Extent2D {
+        self.size
+    }
+
+    /// Allocates a rectangle of given size. Returns its top-left corner, or `None` if the atlas is full.
+    pub fn pack(&mut self, size: Extent2D) -> Option<Origin2D> {
+        let width = size.0 + self.padding;
+        let height = size.1 + self.padding;
+        let atlas_width = self.size.0 + self.padding;
+        let atlas_height = self.size.1 + self.padding;
+
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| shelf.height >= height && atlas_width - shelf.x >= width)
+            .min_by_key(|shelf| shelf.height - height);
+        let shelf = match shelf {
+            Some(shelf) => shelf,
+            None => {
+                if width > atlas_width || self.bottom + height > atlas_height {
+                    return None;
+                }
+                self.shelves.push(Shelf {
+                    y: self.bottom,
+                    height,
+                    x: 0,
+                });
+                self.bottom += height;
+                self.shelves.last_mut()?
+            }
+        };
+
+        let origin = Origin2D(shelf.x, shelf.y);
+        shelf.x += width;
+        Some(origin)
+    }
+
+    /// Enlarges the atlas, keeping the packed rectangles in place.
+    ///
+    /// # Panics
+    /// Panics if the new size is smaller than the current size.
+    pub fn grow(&mut self, size: Extent2D) {
+        assert!(
+            size.0 >= self.size.0 && size.1 >= self.size.1,
+            "atlas cannot shrink"
+        );
+        self.size = size;
+    }
+
+    /// Removes all rectangles.
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+        self.bottom = 0;
+    }
+}
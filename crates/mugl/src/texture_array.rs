@@ -0,0 +1,226 @@
+//! Builder of 2D array textures from same-sized images.
+
+use alloc::vec::Vec;
+
+use crate::descriptor::{ImageCopyTexture, ImageDataLayout, TextureDescriptor};
+use crate::gpu::{GPUDevice, GPUDeviceWebExt, GPUWebExt, GPU};
+use crate::primitive::{
+    Extent2D, Extent3D, MipmapHint, Origin3D, TextureDimension, TextureFormat, TextureUsage,
+};
+
+/// Builds a [TextureDimension::D2Array] texture, with one layer per image.
+///
+/// Each layer is tightly packed image data of the given size and format, i.e. rows of `width * format.size()` bytes.
+/// When mipmaps are enabled, the texture has a full mip chain, which is filled either on the CPU
+/// by [TextureArrayBuilder::build], or on the GPU by [TextureArrayBuilder::build_web].
+#[derive(Clone, Debug)]
+pub struct TextureArrayBuilder<'a> {
+    size: Extent2D,
+    format: TextureFormat,
+    usage: TextureUsage,
+    mipmaps: bool,
+    layers: Vec<&'a [u8]>,
+}
+
+impl<'a> TextureArrayBuilder<'a> {
+    /// Creates a builder of an array texture with layers of given size and format.
+    #[inline]
+    pub fn new(size: Extent2D, format: TextureFormat) -> Self {
+        Self {
+            size,
+            format,
+            usage: TextureUsage::TEXTURE_BINDING,
+            mipmaps: false,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Sets the texture usage. Defaults to [TextureUsage::TEXTURE_BINDING].
+    #[inline]
+    pub fn usage(mut self, usage: TextureUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Sets whether to generate mipmaps for every layer. Defaults to `false`.
+    #[inline]
+    pub fn mipmaps(mut self, mipmaps: bool) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Appends a layer of image data.
+    ///
+    /// # Panics
+    /// Panics if the data size does not match the layer size.
+    pub fn layer(mut self, data: &'a [u8]) -> Self {
+        assert_eq!(
+            data.len(),
+            self.layer_size(),
+            "texture array layer {} has invalid data size",
+            self.layers.len()
+        );
+        self.layers.push(data);
+        self
+    }
+
+    /// Appends layers of image data.
+    ///
+    /// # Panics
+    /// Panics if the data size of any layer does not match the layer size.
+    pub fn layers(self, layers: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        layers.into_iter().fold(self, Self::layer)
+    }
+
+    /// Gets the number of layers added.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if no layer is added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Gets the data size of each layer in bytes.
+    #[inline]
+    pub fn layer_size(&self) -> usize {
+        (self.size.0 * self.size.1 * self.format.size()) as usize
+    }
+
+    /// Gets the number of mip levels of the texture.
+    #[inline]
+    pub fn mip_level_count(&self) -> u32 {
+        if self.mipmaps {
+            u32::BITS - self.size.0.max(self.size.1).max(1).leading_zeros()
+        } else {
+            1
+        }
+    }
+
+    /// Gets the descriptor of the texture to create.
+    pub fn descriptor(&self) -> TextureDescriptor {
+        TextureDescriptor {
+            size: Extent3D(self.size.0, self.size.1, self.layers.len().max(1) as u32),
+            mip_level_count: self.mip_level_count(),
+            sample_count: 1,
+            dimension: TextureDimension::D2Array,
+            format: self.format,
+            usage: self.usage,
+        }
+    }
+
+    /// Creates the texture and uploads all layers. Mipmaps, if enabled, are downsampled on the CPU with a box filter.
+    /// sRGB data is filtered without conversion to linear space.
+    ///
+    /// # Panics
+    /// Panics if mipmaps are enabled for a format other than [TextureFormat::R8], [TextureFormat::RG8],
+    /// [TextureFormat::RGBA8] and [TextureFormat::SRGBA8].
+    pub fn build<G: GPU>(&self, device: &G::Device) -> G::Texture {
+        let channels = if self.mipmaps {
+            match self.format {
+                TextureFormat::R8
+                | TextureFormat::RG8
+                | TextureFormat::RGBA8
+                | TextureFormat::SRGBA8 => self.format.size() as usize,
+                _ => panic!(
+                    "cannot generate mipmaps for texture format {:?} on the CPU",
+                    self.format
+                ),
+            }
+        } else {
+            0
+        };
+
+        let texture = self.create::<G, _>(device);
+        for (layer, data) in self.layers.iter().enumerate() {
+            let mut size = self.size;
+            let mut level_data = Vec::new();
+            for mip_level in 1..self.mip_level_count() {
+                let src: &[u8] = if mip_level == 1 { data } else { &level_data };
+                let (next, next_size) = downsample(src, size, channels);
+                level_data = next;
+                size = next_size;
+                self.write::<G, _>(device, &texture, layer as u32, mip_level, size, &level_data);
+            }
+        }
+        texture
+    }
+
+    /// Creates the texture and uploads all layers. Mipmaps, if enabled,
+    /// are generated for every layer on the GPU by [GPUDeviceWebExt::generate_mipmap].
+    pub fn build_web<G: GPUWebExt>(
+        &self,
+        device: &<G as GPUWebExt>::Device,
+        hint: MipmapHint,
+    ) -> G::Texture {
+        let texture = self.create::<G, _>(device);
+        if self.mipmaps {
+            device.generate_mipmap(&texture, hint);
+        }
+        texture
+    }
+
+    /// Creates the texture and uploads the base level of all layers.
+    fn create<G: GPU, D: GPUDevice<G>>(&self, device: &D) -> G::Texture {
+        let texture = device.create_texture(self.descriptor());
+        for (layer, data) in self.layers.iter().enumerate() {
+            self.write::<G, D>(device, &texture, layer as u32, 0, self.size, data);
+        }
+        texture
+    }
+
+    fn write<G: GPU, D: GPUDevice<G>>(
+        &self,
+        device: &D,
+        texture: &G::Texture,
+        layer: u32,
+        mip_level: u32,
+        size: Extent2D,
+        data: &[u8],
+    ) {
+        device.write_texture(
+            ImageCopyTexture {
+                texture,
+                mip_level,
+                origin: Origin3D(0, 0, layer),
+            },
+            data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: size.0 * self.format.size(),
+                rows_per_image: size.1,
+            },
+            Extent3D(size.0, size.1, 1),
+        );
+    }
+}
+
+/// Downsamples 8-bit image data to the next mip level by averaging each 2x2 block.
+fn downsample(data: &[u8], size: Extent2D, channels: usize) -> (Vec<u8>, Extent2D) {
+    let (width, height) = (size.0 as usize, size.1 as usize);
+    let next = Extent2D((size.0 / 2).max(1), (size.1 / 2).max(1));
+    let (next_width, next_height) = (next.0 as usize, next.1 as usize);
+
+    let mut result = Vec::with_capacity(next_width * next_height * channels);
+    for y in 0..next_height {
+        let rows = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+        for x in 0..next_width {
+            let cols = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+            for c in 0..channels {
+                let sum: u32 = rows
+                    .iter()
+                    .flat_map(|row| {
+                        cols.iter()
+                            .map(move |col| (row * width + col) * channels + c)
+                    })
+                    .map(|i| data[i] as u32)
+                    .sum();
+                result.push(((sum + 2) / 4) as u8);
+            }
+        }
+    }
+    (result, next)
+}
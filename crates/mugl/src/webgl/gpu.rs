@@ -1,27 +1,45 @@
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
 use async_trait::async_trait;
+use core::cell::{Cell, RefCell};
 use core::ops::Range;
 
 use super::dom::{Canvas, ImageSource, JsFuture};
 use super::interop::{
-    DeviceId, JsBindGroupEntry, JsBindGroupLayoutEntry, JsColorAttachment, JsColorTargetState,
-    JsRenderPassDescriptor, JsRenderPipelineDescriptor, JsVertexBufferLayout, Slice, TextureId,
+    DeviceId, FutureId, FutureStatus, JsBindGroupEntry, JsBindGroupLayoutEntry, JsColorAttachment,
+    JsColorTargetState, JsRenderPassDescriptor, JsRenderPipelineDescriptor, JsVertexBufferLayout,
+    RenderbufferId, Slice, TextureId,
 };
 use super::mugl;
-use super::primitive::{WebGL2Features, WebGLContextAttribute};
+use super::primitive::{
+    WebGL2Features, WebGLContextAttribute, WebGLPipelineFlags, WebGLSurfaceDescriptor,
+};
 use super::resource::{
-    WebGLBindGroup, WebGLBindGroupLayout, WebGLBuffer, WebGLBufferView, WebGLRenderPass,
-    WebGLRenderPipeline, WebGLSampler, WebGLShader, WebGLTexture,
+    WebGLBindGroup, WebGLBindGroupLayout, WebGLBuffer, WebGLBufferView, WebGLQuerySet,
+    WebGLRenderBundle, WebGLRenderCommand, WebGLRenderPass, WebGLRenderPipeline, WebGLSampler,
+    WebGLShader, WebGLTexture,
 };
 use crate::descriptor::{
-    BindGroupDescriptor, BindGroupLayoutDescriptor, BlendState, BufferDescriptor,
-    ColorTargetStates, DepthStencilState, ImageCopyExternalImage, ImageCopyTexture,
-    ImageDataLayout, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
-    ShaderDescriptor, TextureDescriptor, VertexAttribute,
+    BindGroupDescriptor, BindGroupLayoutDescriptor, BindingResource, BindingType, BlendState,
+    BufferDescriptor, ColorTargetStates, DepthStencilState, ImageCopyExternalImage,
+    ImageCopyTexture, ImageDataLayout, QuerySetDescriptor, RenderBundleDescriptor,
+    RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor, ShaderDescriptor,
+    TextureDescriptor, VertexAttribute,
+};
+use crate::gl_const;
+use crate::gpu::{
+    CapturedFrame, FrameCallback, GPUDevice, GPUDeviceWebExt, GPURefTypes, GPURenderBundleEncoder,
+    GPURenderPassEncoder, GPUWebExt, LogSink, GPU,
+};
+use crate::limits::{self, DepthFormats, Limits, TextureCompression};
+use crate::primitive::{
+    BufferSize, BufferUsage, Color, ColorWrite, DepthRange, Extent2D, Extent3D, LogLevel,
+    MipmapHint, QueryType, TextureFormat, TextureUsage,
 };
-use crate::gpu::{GPUDevice, GPUDeviceWebExt, GPURefTypes, GPURenderPassEncoder, GPUWebExt, GPU};
-use crate::primitive::{BufferSize, Color, ColorWrite, Extent2D, Extent3D, MipmapHint};
 
 /// WebGL GPU interface.
 #[derive(Debug)]
@@ -29,16 +47,25 @@ pub struct WebGL;
 
 impl WebGL {
     /// Requests a new WebGL GPU device.
+    /// The depth, stencil and antialias context attributes are determined by the surface descriptor.
+    /// Returns `None` if the context cannot be created, e.g. if [WebGLContextAttribute::FAIL_IF_MAJOR_PERFORMANCE_CAVEAT]
+    /// is set and only a software implementation is available.
     pub fn request_device(
         canvas: &Canvas,
         desc: WebGLContextAttribute,
         features: WebGL2Features,
+        surface_descriptor: WebGLSurfaceDescriptor,
     ) -> Option<WebGLDevice> {
+        let desc = desc.with_surface(surface_descriptor);
         let id = unsafe { mugl::webgl_request_device(canvas.id, desc, features) };
         if id.is_null() {
             None
         } else {
-            Some(WebGLDevice { id })
+            Some(WebGLDevice {
+                id,
+                log_sink: RefCell::default(),
+                frame_capture: RefCell::default(),
+            })
         }
     }
 }
@@ -47,12 +74,55 @@ impl WebGL {
 #[derive(Debug)]
 pub struct WebGLDevice {
     id: DeviceId,
+    log_sink: RefCell<Option<LogSink>>,
+    frame_capture: RefCell<FrameCapture>,
+}
+
+/// State of the frame capture of a device.
+#[derive(Debug, Default)]
+struct FrameCapture {
+    /// Number of frames yet to be read back.
+    remaining: u32,
+    next_index: u32,
+    callback: Option<FrameCallback>,
+    pending: VecDeque<PendingFrame>,
+}
+
+/// A frame being read back from the surface.
+#[derive(Debug)]
+struct PendingFrame {
+    index: u32,
+    size: Extent2D,
+    data: Vec<u8>,
+    future: FutureId,
+    /// Whether to deliver the frame, i.e. it belongs to the current capture.
+    /// Frames of a replaced capture are kept alive until their readbacks complete, as JS writes into their data.
+    deliver: bool,
 }
 
 /// WebGL GPU render pass encoder.
 #[derive(Debug)]
 pub struct WebGLRenderPassEncoder<'a> {
     device: &'a WebGLDevice,
+    /// Viewport set since the last draw, to be applied with its depth range at the next draw.
+    viewport: Cell<Option<WebGLViewport>>,
+}
+
+/// WebGL GPU render bundle encoder, which records commands to be replayed by [WebGLRenderPassEncoder].
+#[derive(Debug)]
+pub struct WebGLRenderBundleEncoder<'a> {
+    device: &'a WebGLDevice,
+    commands: RefCell<Vec<WebGLRenderCommand>>,
+}
+
+/// Viewport of a [WebGLRenderPassEncoder].
+#[derive(Clone, Copy, Debug)]
+struct WebGLViewport {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    depth: DepthRange,
 }
 
 impl GPU for WebGL {
@@ -66,10 +136,13 @@ impl GPU for WebGL {
     type RenderPass = WebGLRenderPass;
     type BindGroup = WebGLBindGroup;
     type BindGroupLayout = WebGLBindGroupLayout;
+    type QuerySet = WebGLQuerySet;
+    type RenderBundle = WebGLRenderBundle;
 }
 
 impl<'a> GPURefTypes<'a, WebGL> for WebGL {
     type RenderPassEncoder = WebGLRenderPassEncoder<'a>;
+    type RenderBundleEncoder = WebGLRenderBundleEncoder<'a>;
     type BufferView = WebGLBufferView;
 }
 
@@ -84,77 +157,19 @@ impl WebGLDevice {
     pub fn reset(&self) {
         unsafe { mugl::reset_device(self.id) }
     }
-}
 
-impl Drop for WebGLDevice {
-    fn drop(&mut self) {
-        unsafe { mugl::delete_device(self.id) }
-    }
-}
-
-#[async_trait(?Send)]
-impl GPUDevice<WebGL> for WebGLDevice {
-    fn features(&self) -> WebGL2Features {
-        unsafe { WebGL2Features::from_bits_unchecked(mugl::get_device_features(self.id)) }
-    }
-
-    fn create_buffer(&self, descriptor: BufferDescriptor) -> WebGLBuffer {
-        WebGLBuffer {
-            id: unsafe { mugl::create_buffer(self.id, descriptor) },
-        }
-    }
-
-    fn create_texture(&self, descriptor: TextureDescriptor) -> WebGLTexture {
-        WebGLTexture {
-            id: unsafe { mugl::create_texture(self.id, descriptor) },
-        }
-    }
-
-    fn create_sampler(&self, descriptor: SamplerDescriptor) -> WebGLSampler {
-        WebGLSampler {
-            id: unsafe { mugl::create_sampler(self.id, descriptor.into()) },
-        }
-    }
-
-    fn create_shader(&self, descriptor: ShaderDescriptor) -> WebGLShader {
-        WebGLShader {
-            id: unsafe { mugl::create_shader(self.id, descriptor.code.into(), descriptor.usage) },
-        }
-    }
-
-    fn create_bind_group_layout(
-        &self,
-        descriptor: BindGroupLayoutDescriptor,
-    ) -> WebGLBindGroupLayout {
-        let entries: Vec<JsBindGroupLayoutEntry> = descriptor
-            .entries
-            .iter()
-            .map(Into::into)
-            .collect::<Vec<_>>();
-
-        WebGLBindGroupLayout {
-            id: unsafe { mugl::create_bind_group_layout(self.id, (&entries).into()) },
-        }
-    }
-
-    fn create_bind_group(&self, descriptor: BindGroupDescriptor<WebGL>) -> WebGLBindGroup {
-        let entries: Vec<JsBindGroupEntry> = descriptor
-            .entries
-            .iter()
-            .map(Into::into)
-            .collect::<Vec<_>>();
-
-        WebGLBindGroup {
-            id: unsafe {
-                mugl::create_bind_group(self.id, descriptor.layout.id, (&entries).into())
-            },
-        }
-    }
-
-    fn create_render_pipeline(
+    /// Creates a render pipeline with WebGL-specific options.
+    /// See [WebGLPipelineFlags].
+    pub fn create_render_pipeline_with_flags(
         &self,
         descriptor: RenderPipelineDescriptor<WebGL>,
+        flags: WebGLPipelineFlags,
     ) -> WebGLRenderPipeline {
+        assert!(
+            descriptor.multiview.is_none(),
+            "multiview rendering is not supported by WebGL"
+        );
+
         let mut attributes = Vec::<VertexAttribute>::new();
         let buffers = descriptor
             .buffers
@@ -172,6 +187,17 @@ impl GPUDevice<WebGL> for WebGLDevice {
                 }
             })
             .collect::<Vec<_>>();
+        self.validate_limits(|| {
+            limits::validate_bind_group_count(&self.limits(), descriptor.bind_groups.len())
+        });
+        self.validate_limits(|| {
+            limits::validate_texture_units(
+                descriptor
+                    .bind_groups
+                    .iter()
+                    .map(|bind_group| bind_group.texture_unit_count),
+            )
+        });
         let bind_groups = descriptor
             .bind_groups
             .iter()
@@ -229,6 +255,7 @@ impl GPUDevice<WebGL> for WebGLDevice {
             targets: color_states.0,
             write_mask: color_states.1,
             blend: color_states.2,
+            flags,
         };
 
         WebGLRenderPipeline {
@@ -236,6 +263,300 @@ impl GPUDevice<WebGL> for WebGLDevice {
         }
     }
 
+    /// Polls the GL error flags and reports them to the log sink.
+    /// Polling stalls the GL pipeline, so it is only done in debug builds while a sink is set.
+    fn poll_errors(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Some(sink) = &*self.log_sink.borrow() {
+            // Each call clears one flag. Limit the number of calls in case the context is lost.
+            for _ in 0..8 {
+                let message = match unsafe { mugl::get_device_error(self.id) } {
+                    gl_const::NO_ERROR => break,
+                    gl_const::INVALID_ENUM => "GL error: INVALID_ENUM",
+                    gl_const::INVALID_VALUE => "GL error: INVALID_VALUE",
+                    gl_const::INVALID_OPERATION => "GL error: INVALID_OPERATION",
+                    gl_const::INVALID_FRAMEBUFFER_OPERATION => {
+                        "GL error: INVALID_FRAMEBUFFER_OPERATION"
+                    }
+                    gl_const::OUT_OF_MEMORY => "GL error: OUT_OF_MEMORY",
+                    gl_const::CONTEXT_LOST_WEBGL => "GL error: CONTEXT_LOST_WEBGL",
+                    _ => "GL error: unknown",
+                };
+                sink.log(LogLevel::Error, message);
+            }
+        }
+    }
+
+    /// Reads back the surface if a frame capture is in progress, and delivers the completed frames.
+    fn capture_frame(&self) {
+        let mut capture = self.frame_capture.borrow_mut();
+        let capture = &mut *capture;
+
+        if capture.remaining > 0 {
+            let size = unsafe {
+                Extent2D(
+                    mugl::get_surface_width(self.id),
+                    mugl::get_surface_height(self.id),
+                )
+            };
+            let data = vec![0; (size.0 * size.1 * 4) as usize];
+            let future = unsafe { mugl::read_surface(self.id, (&data).into()) };
+            capture.pending.push_back(PendingFrame {
+                index: capture.next_index,
+                size,
+                data,
+                future,
+                deliver: true,
+            });
+            capture.remaining -= 1;
+            capture.next_index += 1;
+        }
+
+        while let Some(frame) = capture.pending.front() {
+            let status = unsafe { mugl::get_future_status(frame.future) };
+            if status == FutureStatus::Pending {
+                break;
+            }
+            let frame = capture.pending.pop_front().unwrap();
+            if let (FutureStatus::Done, true, Some(callback)) =
+                (status, frame.deliver, capture.callback.as_mut())
+            {
+                callback.call(CapturedFrame {
+                    index: frame.index,
+                    size: frame.size,
+                    data: &frame.data,
+                });
+            }
+        }
+
+        if capture.remaining == 0 && capture.pending.is_empty() {
+            capture.callback = None;
+        }
+    }
+
+    /// Validates that a buffer is created with the given usage in debug builds.
+    /// WebGL silently renders nothing on misuse, so an error is reported to the log sink,
+    /// or raised as a panic if there is no sink.
+    fn validate_buffer_usage(&self, buffer: &WebGLBuffer, usage: BufferUsage, binding: &str) {
+        if !cfg!(debug_assertions) || buffer.usage.contains(usage) {
+            return;
+        }
+        self.report_error(&format!(
+            "{} buffer binding requires {:?}, but the buffer was created with {:?}",
+            binding, usage, buffer.usage
+        ));
+    }
+
+    /// Validates a binding against the [limits] in debug builds, reporting any error like [Self::validate_buffer_usage].
+    fn validate_limits(&self, validate: impl FnOnce() -> Result<(), String>) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        if let Err(message) = validate() {
+            self.report_error(&message);
+        }
+    }
+
+    /// Reports an error to the log sink, or raises it as a panic if there is no sink.
+    fn report_error(&self, message: &str) {
+        match &*self.log_sink.borrow() {
+            Some(sink) => sink.log(LogLevel::Error, message),
+            None => panic!("{}", message),
+        }
+    }
+}
+
+impl Drop for WebGLDevice {
+    fn drop(&mut self) {
+        unsafe { mugl::delete_device(self.id) }
+    }
+}
+
+#[async_trait(?Send)]
+impl GPUDevice<WebGL> for WebGLDevice {
+    fn features(&self) -> WebGL2Features {
+        unsafe { WebGL2Features::from_bits_unchecked(mugl::get_device_features(self.id)) }
+    }
+
+    fn limits(&self) -> Limits {
+        let features = self.features();
+        let parameter = |pname| unsafe { mugl::get_device_parameter(self.id, pname) };
+        let mut texture_compression = TextureCompression::empty();
+        texture_compression.set(
+            TextureCompression::BC,
+            features.contains(WebGL2Features::TEXTURE_COMPRESSION_BC),
+        );
+        texture_compression.set(
+            TextureCompression::ETC2,
+            features.contains(WebGL2Features::TEXTURE_COMPRESSION_ETC2),
+        );
+        texture_compression.set(
+            TextureCompression::ASTC,
+            features.contains(WebGL2Features::TEXTURE_COMPRESSION_ASTC),
+        );
+
+        Limits {
+            max_texture_dimension_2d: parameter(gl_const::MAX_TEXTURE_SIZE),
+            max_texture_dimension_3d: parameter(gl_const::MAX_3D_TEXTURE_SIZE),
+            max_texture_array_layers: parameter(gl_const::MAX_ARRAY_TEXTURE_LAYERS),
+            max_bind_groups: limits::MAX_BIND_GROUPS,
+            max_sampler_anisotropy: if features.contains(WebGL2Features::TEXTURE_ANISOTROPIC) {
+                parameter(gl_const::MAX_TEXTURE_MAX_ANISOTROPY_EXT).min(u8::MAX as u32) as u8
+            } else {
+                1
+            },
+            depth_formats: DepthFormats::all(),
+            texture_compression,
+        }
+    }
+
+    fn create_buffer(&self, descriptor: BufferDescriptor) -> WebGLBuffer {
+        WebGLBuffer {
+            id: unsafe { mugl::create_buffer(self.id, descriptor) },
+            usage: descriptor.usage,
+        }
+    }
+
+    fn create_texture(&self, descriptor: TextureDescriptor) -> WebGLTexture {
+        self.validate_limits(|| limits::validate_texture(&self.limits(), &descriptor));
+        let msaa = descriptor.sample_count > 1;
+        if msaa {
+            self.validate_limits(|| {
+                if !descriptor.usage.contains(TextureUsage::RENDER_ATTACHMENT) {
+                    return Err(String::from(
+                        "multisampled texture requires RENDER_ATTACHMENT usage",
+                    ));
+                }
+                if !self
+                    .supported_sample_counts(descriptor.format)
+                    .contains(&descriptor.sample_count)
+                {
+                    return Err(format!(
+                        "sample count {} is not supported for texture format {:?}",
+                        descriptor.sample_count, descriptor.format
+                    ));
+                }
+                Ok(())
+            });
+        }
+
+        // WebGL textures cannot be multisampled. Instead, passes render to a multisampled renderbuffer,
+        // which is then resolved into the single-sampled texture.
+        let msaa_renderbuffer = if msaa {
+            unsafe {
+                mugl::create_renderbuffer(
+                    self.id,
+                    descriptor.format,
+                    descriptor.sample_count,
+                    descriptor.size.0,
+                    descriptor.size.1,
+                )
+            }
+        } else {
+            RenderbufferId::null()
+        };
+        WebGLTexture {
+            id: unsafe {
+                mugl::create_texture(
+                    self.id,
+                    TextureDescriptor {
+                        sample_count: 1,
+                        ..descriptor
+                    },
+                )
+            },
+            sample_count: descriptor.sample_count.max(1),
+            msaa_renderbuffer,
+        }
+    }
+
+    fn create_sampler(&self, descriptor: SamplerDescriptor) -> WebGLSampler {
+        self.validate_limits(|| limits::validate_sampler(&self.limits(), &descriptor));
+        WebGLSampler {
+            compare: descriptor.compare.is_some(),
+            id: unsafe { mugl::create_sampler(self.id, descriptor.into()) },
+        }
+    }
+
+    fn create_shader(&self, descriptor: ShaderDescriptor) -> WebGLShader {
+        WebGLShader {
+            id: unsafe { mugl::create_shader(self.id, descriptor.code.into(), descriptor.usage) },
+        }
+    }
+
+    fn create_bind_group_layout(
+        &self,
+        descriptor: BindGroupLayoutDescriptor,
+    ) -> WebGLBindGroupLayout {
+        self.validate_limits(|| limits::validate_bind_group_layout(&descriptor));
+
+        let entries: Vec<JsBindGroupLayoutEntry> = descriptor
+            .entries
+            .iter()
+            .map(Into::into)
+            .collect::<Vec<_>>();
+
+        WebGLBindGroupLayout {
+            dynamic_offset_count: descriptor.dynamic_offset_count(),
+            texture_unit_count: descriptor.texture_unit_count(),
+            samplers: descriptor
+                .entries
+                .iter()
+                .filter_map(|entry| match entry.ty {
+                    BindingType::Sampler { ty } => Some((entry.binding, ty)),
+                    _ => None,
+                })
+                .collect(),
+            id: unsafe { mugl::create_bind_group_layout(self.id, (&entries).into()) },
+        }
+    }
+
+    fn create_bind_group(&self, descriptor: BindGroupDescriptor<WebGL>) -> WebGLBindGroup {
+        for entry in descriptor.entries {
+            match entry.resource {
+                BindingResource::Buffer { buffer, .. } => {
+                    self.validate_buffer_usage(buffer, BufferUsage::UNIFORM, "uniform");
+                }
+                BindingResource::Sampler(sampler) => {
+                    if let Some((_, ty)) = descriptor
+                        .layout
+                        .samplers
+                        .iter()
+                        .find(|(binding, _)| *binding == entry.binding)
+                    {
+                        self.validate_limits(|| {
+                            limits::validate_sampler_binding(entry.binding, *ty, sampler.compare)
+                        });
+                    }
+                }
+                BindingResource::Texture(_) => {}
+            }
+        }
+
+        let entries: Vec<JsBindGroupEntry> = descriptor
+            .entries
+            .iter()
+            .map(Into::into)
+            .collect::<Vec<_>>();
+
+        WebGLBindGroup {
+            dynamic_offset_count: descriptor.layout.dynamic_offset_count,
+            id: unsafe {
+                mugl::create_bind_group(self.id, descriptor.layout.id, (&entries).into())
+            },
+        }
+    }
+
+    #[inline]
+    fn create_render_pipeline(
+        &self,
+        descriptor: RenderPipelineDescriptor<WebGL>,
+    ) -> WebGLRenderPipeline {
+        self.create_render_pipeline_with_flags(descriptor, WebGLPipelineFlags::empty())
+    }
+
     fn create_render_pass(&self, descriptor: RenderPassDescriptor<WebGL>) -> WebGLRenderPass {
         let color_atts: Vec<JsColorAttachment>;
         let js_desc = match descriptor {
@@ -251,6 +572,7 @@ impl GPUDevice<WebGL> for WebGLDevice {
                 texture: TextureId::null(),
                 mip_level: 0,
                 slice: 0,
+                renderbuffer: RenderbufferId::null(),
                 colors: Slice::empty(),
             },
             RenderPassDescriptor::Offscreen {
@@ -258,7 +580,28 @@ impl GPUDevice<WebGL> for WebGLDevice {
                 clear_stencil,
                 depth_stencil,
                 colors,
+                multiview,
             } => {
+                assert!(
+                    multiview.is_none(),
+                    "multiview rendering is not supported by WebGL"
+                );
+                self.validate_limits(|| {
+                    limits::validate_render_pass_attachments(colors.len(), depth_stencil.is_some())
+                });
+                self.validate_limits(|| {
+                    let mut sample_counts = colors
+                        .iter()
+                        .map(|color| color.view)
+                        .chain(depth_stencil)
+                        .map(|view| view.texture.sample_count);
+                    match sample_counts.next() {
+                        Some(first) if sample_counts.any(|count| count != first) => Err(
+                            String::from("render pass attachments must have the same sample count"),
+                        ),
+                        _ => Ok(()),
+                    }
+                });
                 color_atts = colors
                     .iter()
                     .map(Into::into)
@@ -275,6 +618,9 @@ impl GPUDevice<WebGL> for WebGLDevice {
                         .unwrap_or(TextureId::null()),
                     mip_level: depth_stencil.map(|ds| ds.mip_level).unwrap_or(0),
                     slice: depth_stencil.map(|ds| ds.slice).unwrap_or(0),
+                    renderbuffer: depth_stencil
+                        .map(|ds| ds.texture.msaa_renderbuffer)
+                        .unwrap_or(RenderbufferId::null()),
                     colors: (&color_atts).into(),
                 }
             }
@@ -284,11 +630,33 @@ impl GPUDevice<WebGL> for WebGLDevice {
         }
     }
 
+    fn create_query_set(&self, descriptor: QuerySetDescriptor) -> WebGLQuerySet {
+        assert!(
+            descriptor.ty != QueryType::Timestamp
+                || self.features().contains(WebGL2Features::TIMER_QUERY),
+            "timestamp queries require the TIMER_QUERY feature"
+        );
+        WebGLQuerySet {
+            id: unsafe { mugl::create_query_set(self.id, descriptor) },
+        }
+    }
+
     fn render<'a>(&'a self, pass: &'a WebGLRenderPass) -> WebGLRenderPassEncoder {
         unsafe {
             mugl::begin_render_pass(self.id, pass.id);
         }
-        WebGLRenderPassEncoder { device: self }
+        WebGLRenderPassEncoder {
+            device: self,
+            viewport: Cell::new(None),
+        }
+    }
+
+    fn render_bundle(&self, _descriptor: RenderBundleDescriptor) -> WebGLRenderBundleEncoder<'_> {
+        // Commands are replayed as is, so the bundle does not depend on the attachment formats
+        WebGLRenderBundleEncoder {
+            device: self,
+            commands: RefCell::default(),
+        }
     }
 
     async fn read_buffer<'a>(
@@ -388,23 +756,81 @@ impl GPUDevice<WebGL> for WebGLDevice {
         }
     }
 
+    fn resolve_query_set(
+        &self,
+        query_set: &WebGLQuerySet,
+        queries: Range<u32>,
+        dst: &WebGLBuffer,
+        dst_offset: BufferSize,
+    ) {
+        unsafe {
+            mugl::resolve_query_set(
+                self.id,
+                query_set.id,
+                queries.start,
+                queries.len() as u32,
+                dst.id,
+                dst_offset,
+            )
+        }
+    }
+
+    #[inline]
+    fn timestamp_period(&self) -> f32 {
+        1. // GL timer queries are in nanoseconds
+    }
+
+    fn supported_sample_counts(&self, format: TextureFormat) -> Vec<u32> {
+        let mask = unsafe { mugl::get_supported_sample_counts(self.id, format) } | 1;
+        (0..u32::BITS)
+            .map(|i| 1 << i)
+            .filter(|count| mask & count != 0)
+            .collect()
+    }
+
     #[inline]
     fn is_srgb_surface(&self) -> bool {
         false // WebGL does not have SRGB backbuffer
     }
 
+    #[inline]
+    fn surface_texture(&self) -> Option<WebGLTexture> {
+        None // The default framebuffer cannot be attached to offscreen passes
+    }
+
     fn is_lost(&self) -> bool {
         unsafe { mugl::is_device_lost(self.id) }
     }
 
+    fn is_fallback_adapter(&self) -> bool {
+        unsafe { mugl::is_device_fallback(self.id) }
+    }
+
+    fn set_log_sink(&self, sink: Option<LogSink>) {
+        *self.log_sink.borrow_mut() = sink;
+    }
+
+    fn capture_frames(&self, frames: u32, callback: FrameCallback) {
+        if !cfg!(feature = "backend-webgl-ext") {
+            return; // Reading back the surface requires the extended glue code
+        }
+        let mut capture = self.frame_capture.borrow_mut();
+        for frame in capture.pending.iter_mut() {
+            frame.deliver = false;
+        }
+        capture.remaining = frames;
+        capture.next_index = 0;
+        capture.callback = Some(callback);
+    }
+
     #[inline]
     fn flush(&self) {
-        // noop
+        self.poll_errors();
     }
 
-    #[inline]
     fn present(&self) {
-        // noop
+        self.poll_errors();
+        self.capture_frame();
     }
 
     #[inline]
@@ -438,24 +864,51 @@ impl GPUDeviceWebExt<WebGL> for WebGLDevice {
     }
 }
 
+impl WebGLRenderPassEncoder<'_> {
+    /// Applies the viewport set since the last draw, if any.
+    fn apply_viewport(&self) {
+        if let Some(viewport) = self.viewport.take() {
+            unsafe {
+                mugl::set_viewport(
+                    self.device.id,
+                    viewport.x,
+                    viewport.y,
+                    viewport.width,
+                    viewport.height,
+                    viewport.depth.min,
+                    viewport.depth.max,
+                )
+            }
+        }
+    }
+}
+
 impl<'a> GPURenderPassEncoder<'a, WebGL> for WebGLRenderPassEncoder<'a> {
     fn pipeline(&self, pipeline: &'a WebGLRenderPipeline) {
         unsafe { mugl::set_render_pipeline(self.device.id, pipeline.id) }
     }
 
     fn index(&self, buffer: &'a WebGLBuffer) {
+        self.device
+            .validate_buffer_usage(buffer, BufferUsage::INDEX, "index");
         unsafe { mugl::set_index(self.device.id, buffer.id) }
     }
 
     fn vertex(&self, slot: u32, buffer: &'a WebGLBuffer, offset: BufferSize) {
+        self.device
+            .validate_buffer_usage(buffer, BufferUsage::VERTEX, "vertex");
         unsafe { mugl::set_vertex(self.device.id, slot, buffer.id, offset) }
     }
 
     fn bind_group(&self, slot: u32, bind_group: &'a WebGLBindGroup, offsets: &[u32]) {
+        self.device.validate_limits(|| {
+            limits::validate_dynamic_offsets(slot, bind_group.dynamic_offset_count, offsets)
+        });
         unsafe { mugl::set_bind_group(self.device.id, slot, bind_group.id, offsets.into()) }
     }
 
     fn draw(&self, vertices: Range<u32>, instances: Range<u32>) {
+        self.apply_viewport();
         unsafe {
             mugl::draw(
                 self.device.id,
@@ -468,6 +921,7 @@ impl<'a> GPURenderPassEncoder<'a, WebGL> for WebGLRenderPassEncoder<'a> {
     }
 
     fn draw_indexed(&self, indices: Range<u32>, instances: Range<u32>) {
+        self.apply_viewport();
         unsafe {
             mugl::draw_indexed(
                 self.device.id,
@@ -479,18 +933,26 @@ impl<'a> GPURenderPassEncoder<'a, WebGL> for WebGLRenderPassEncoder<'a> {
         }
     }
 
+    fn draw_indirect(&self, _buffer: &'a WebGLBuffer, _offset: BufferSize) {
+        self.device
+            .report_error("indirect draws are not supported by WebGL");
+    }
+
+    fn draw_indexed_indirect(&self, _buffer: &'a WebGLBuffer, _offset: BufferSize) {
+        self.device
+            .report_error("indirect draws are not supported by WebGL");
+    }
+
     fn viewport(&self, x: f32, y: f32, width: f32, height: f32, min_depth: f32, max_depth: f32) {
-        unsafe {
-            mugl::set_viewport(
-                self.device.id,
-                x as u32,
-                y as u32,
-                width as u32,
-                height as u32,
-                min_depth,
-                max_depth,
-            )
-        }
+        self.device
+            .validate_limits(|| limits::validate_viewport_depth(min_depth, max_depth));
+        self.viewport.set(Some(WebGLViewport {
+            x: x as u32,
+            y: y as u32,
+            width: width as u32,
+            height: height as u32,
+            depth: DepthRange::new(min_depth, max_depth),
+        }));
     }
 
     fn scissor_rect(&self, x: u32, y: u32, width: u32, height: u32) {
@@ -501,10 +963,51 @@ impl<'a> GPURenderPassEncoder<'a, WebGL> for WebGLRenderPassEncoder<'a> {
         unsafe { mugl::set_blend_const(self.device.id, color.into()) }
     }
 
+    fn begin_occlusion_query(&self, query_set: &'a WebGLQuerySet, index: u32) {
+        unsafe { mugl::begin_occlusion_query(self.device.id, query_set.id, index) }
+    }
+
+    fn end_occlusion_query(&self) {
+        unsafe { mugl::end_occlusion_query(self.device.id) }
+    }
+
+    fn write_timestamp(&self, query_set: &'a WebGLQuerySet, index: u32) {
+        unsafe { mugl::write_timestamp(self.device.id, query_set.id, index) }
+    }
+
     fn stencil_ref(&self, reference: u32) {
         unsafe { mugl::set_stencil_ref(self.device.id, reference) }
     }
 
+    fn execute_bundles(&self, bundles: &[&'a WebGLRenderBundle]) {
+        let device = self.device.id;
+        for command in bundles.iter().flat_map(|bundle| &bundle.commands) {
+            match command {
+                WebGLRenderCommand::Pipeline(pipeline) => unsafe {
+                    mugl::set_render_pipeline(device, *pipeline)
+                },
+                WebGLRenderCommand::Index(buffer) => unsafe { mugl::set_index(device, *buffer) },
+                WebGLRenderCommand::Vertex {
+                    slot,
+                    buffer,
+                    offset,
+                } => unsafe { mugl::set_vertex(device, *slot, *buffer, *offset) },
+                WebGLRenderCommand::BindGroup {
+                    slot,
+                    bind_group,
+                    offsets,
+                } => unsafe { mugl::set_bind_group(device, *slot, *bind_group, offsets.into()) },
+                WebGLRenderCommand::Draw {
+                    vertices,
+                    instances,
+                } => self.draw(vertices.clone(), instances.clone()),
+                WebGLRenderCommand::DrawIndexed { indices, instances } => {
+                    self.draw_indexed(indices.clone(), instances.clone())
+                }
+            }
+        }
+    }
+
     fn submit(self) {
         // noop. Submit pass on drop
     }
@@ -517,3 +1020,61 @@ impl<'a> Drop for WebGLRenderPassEncoder<'a> {
         unsafe { mugl::submit_render_pass(self.device.id) }
     }
 }
+
+impl<'a> GPURenderBundleEncoder<'a, WebGL> for WebGLRenderBundleEncoder<'a> {
+    fn pipeline(&self, pipeline: &'a WebGLRenderPipeline) {
+        self.commands
+            .borrow_mut()
+            .push(WebGLRenderCommand::Pipeline(pipeline.id));
+    }
+
+    fn index(&self, buffer: &'a WebGLBuffer) {
+        self.device
+            .validate_buffer_usage(buffer, BufferUsage::INDEX, "index");
+        self.commands
+            .borrow_mut()
+            .push(WebGLRenderCommand::Index(buffer.id));
+    }
+
+    fn vertex(&self, slot: u32, buffer: &'a WebGLBuffer, offset: BufferSize) {
+        self.device
+            .validate_buffer_usage(buffer, BufferUsage::VERTEX, "vertex");
+        self.commands.borrow_mut().push(WebGLRenderCommand::Vertex {
+            slot,
+            buffer: buffer.id,
+            offset,
+        });
+    }
+
+    fn bind_group(&self, slot: u32, bind_group: &'a WebGLBindGroup, offsets: &[u32]) {
+        self.device.validate_limits(|| {
+            limits::validate_dynamic_offsets(slot, bind_group.dynamic_offset_count, offsets)
+        });
+        self.commands
+            .borrow_mut()
+            .push(WebGLRenderCommand::BindGroup {
+                slot,
+                bind_group: bind_group.id,
+                offsets: offsets.to_vec(),
+            });
+    }
+
+    fn draw(&self, vertices: Range<u32>, instances: Range<u32>) {
+        self.commands.borrow_mut().push(WebGLRenderCommand::Draw {
+            vertices,
+            instances,
+        });
+    }
+
+    fn draw_indexed(&self, indices: Range<u32>, instances: Range<u32>) {
+        self.commands
+            .borrow_mut()
+            .push(WebGLRenderCommand::DrawIndexed { indices, instances });
+    }
+
+    fn finish(self) -> WebGLRenderBundle {
+        WebGLRenderBundle {
+            commands: self.commands.into_inner(),
+        }
+    }
+}
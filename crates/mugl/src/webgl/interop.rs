@@ -2,6 +2,7 @@ use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use super::gpu::WebGL;
+use super::primitive::WebGLPipelineFlags;
 use crate::descriptor::{
     BindGroupEntry, BindGroupLayoutEntry, BindingResource, BindingType, BlendState,
     ColorAttachment, ColorTargetState, SamplerDescriptor, StencilFaceState, VertexAttribute,
@@ -101,6 +102,8 @@ impl ResourceType {
     pub const BIND_GROUP: usize = 10;
     pub const RENDER_PIPELINE: usize = 11;
     pub const RENDER_PASS: usize = 12;
+    pub const QUERY_SET: usize = 13;
+    pub const RENDERBUFFER: usize = 14;
 }
 
 /// App context Id.
@@ -118,6 +121,8 @@ pub type BindGroupLayoutId = Id<{ ResourceType::BIND_GROUP_LAYOUT }>;
 pub type BindGroupId = Id<{ ResourceType::BIND_GROUP }>;
 pub type RenderPipelineId = Id<{ ResourceType::RENDER_PIPELINE }>;
 pub type RenderPassId = Id<{ ResourceType::RENDER_PASS }>;
+pub type QuerySetId = Id<{ ResourceType::QUERY_SET }>;
+pub type RenderbufferId = Id<{ ResourceType::RENDERBUFFER }>;
 
 impl ContextId {
     /// Creates a new context ID.
@@ -177,7 +182,8 @@ pub struct JsSamplerDescriptor {
     pub mipmap_filter: FilterMode,
     pub lod_min_clamp: f32,
     pub lod_max_clamp: f32,
-    /// Compare function or 0
+    /// Compare function or 0.
+    /// If set, TEXTURE_COMPARE_MODE is COMPARE_REF_TO_TEXTURE, for depth textures bound to shadow samplers.
     pub compare: u32,
     pub max_anisotropy: u8,
 }
@@ -299,6 +305,9 @@ pub struct JsRenderPassDescriptor {
     pub texture: TextureId,
     pub mip_level: u32,
     pub slice: u32,
+    /// Multisampled renderbuffer to render depth-stencil to instead of the texture, or null
+    pub renderbuffer: RenderbufferId,
+    /// Color attachments. Empty for a depth-only offscreen pass, whose draw and read buffers are NONE
     pub colors: Slice<JsColorAttachment>,
 }
 
@@ -309,6 +318,8 @@ pub struct JsColorAttachment {
     pub mip_level: u32,
     pub slice: u32,
     pub clear_color: Color<f32>,
+    /// Multisampled renderbuffer to render to and resolve into the texture, or null
+    pub renderbuffer: RenderbufferId,
 }
 
 impl<'a> From<&ColorAttachment<'a, WebGL>> for JsColorAttachment {
@@ -318,6 +329,7 @@ impl<'a> From<&ColorAttachment<'a, WebGL>> for JsColorAttachment {
             mip_level: color.view.mip_level,
             slice: color.view.slice,
             clear_color: color.clear.into(),
+            renderbuffer: color.view.texture.msaa_renderbuffer,
         }
     }
 }
@@ -354,6 +366,7 @@ pub struct JsRenderPipelineDescriptor {
     pub targets: Slice<JsColorTargetState>,
     pub write_mask: ColorWrite,
     pub blend: BlendState,
+    pub flags: WebGLPipelineFlags,
 }
 
 #[repr(C)]
@@ -362,6 +375,7 @@ pub struct JsVertexBufferLayout {
     pub attributes_offset: u32,
     pub attributes_len: u32,
     pub stride: BufferSize,
+    /// Attribute divisor of the buffer: 0 for [VertexStepMode::Vertex], 1 for [VertexStepMode::Instance].
     pub step_mode: VertexStepMode,
 }
 
@@ -1,13 +1,14 @@
 use super::interop::{
     BindGroupId, BindGroupLayoutId, BufferId, CanvasId, ContextId, DeviceId, FutureId,
     FutureStatus, ImageSourceId, JsBindGroupEntry, JsBindGroupLayoutEntry, JsRenderPassDescriptor,
-    JsRenderPipelineDescriptor, JsSamplerDescriptor, RenderPassId, RenderPipelineId, SamplerId,
-    ShaderId, Slice, TextureId,
+    JsRenderPipelineDescriptor, JsSamplerDescriptor, QuerySetId, RenderPassId, RenderPipelineId,
+    RenderbufferId, SamplerId, ShaderId, Slice, TextureId,
 };
 use super::primitive::{WebGL2Features, WebGLContextAttribute};
-use crate::descriptor::{BufferDescriptor, ImageDataLayout, TextureDescriptor};
+use crate::descriptor::{BufferDescriptor, ImageDataLayout, QuerySetDescriptor, TextureDescriptor};
 use crate::primitive::{
     BufferSize, Color, Extent2D, Extent3D, MipmapHint, Origin2D, Origin3D, ShaderStage,
+    TextureFormat,
 };
 
 #[cfg(feature = "wasm-bindgen")]
@@ -256,3 +257,167 @@ extern "C" {
     /// Sets the stencil reference value for the current render pass.
     pub fn set_stencil_ref(device: DeviceId, reference: u32);
 }
+
+// Imports that are not implemented by the `mugl/wasm` glue code up to 0.1.4.
+#[cfg(feature = "backend-webgl-ext")]
+#[link(wasm_import_module = "mugl/wasm")]
+extern "C" {
+    /// Gets the next error flag of a device, i.e. the result of glGetError.
+    pub fn get_device_error(device: DeviceId) -> u32;
+
+    /// Checks if the device runs on a software renderer, as reported by the WEBGL_debug_renderer_info extension.
+    pub fn is_device_fallback(device: DeviceId) -> bool;
+
+    /// Gets an integer parameter of a device, i.e. the result of glGetParameter.
+    pub fn get_device_parameter(device: DeviceId, pname: u32) -> u32;
+
+    /// Gets the sample counts supported for renderbuffers of given format,
+    /// as a bitmask where bit `i` is set if `2^i` samples are supported.
+    pub fn get_supported_sample_counts(device: DeviceId, format: TextureFormat) -> u32;
+
+    /// Creates a multisampled renderbuffer.
+    /// Render passes attach the renderbuffer in place of the texture it is created for,
+    /// and resolve color renderbuffers into their textures with `blitFramebuffer` when submitted.
+    pub fn create_renderbuffer(
+        device: DeviceId,
+        format: TextureFormat,
+        sample_count: u32,
+        width: u32,
+        height: u32,
+    ) -> RenderbufferId;
+
+    /// Deletes a renderbuffer.
+    pub fn delete_renderbuffer(renderbuffer: RenderbufferId);
+
+    /// Creates a GPU query set.
+    pub fn create_query_set(device: DeviceId, descriptor: QuerySetDescriptor) -> QuerySetId;
+
+    /// Deletes a GPU query set.
+    pub fn delete_query_set(query_set: QuerySetId);
+
+    /// Writes the available results of a range of queries to a GPU buffer as 64-bit integers.
+    pub fn resolve_query_set(
+        device: DeviceId,
+        query_set: QuerySetId,
+        first_query: u32,
+        query_count: u32,
+        dst: BufferId,
+        dst_offset: BufferSize,
+    );
+
+    /// Gets the width of the drawing buffer of the device surface.
+    pub fn get_surface_width(device: DeviceId) -> u32;
+
+    /// Gets the height of the drawing buffer of the device surface.
+    pub fn get_surface_height(device: DeviceId) -> u32;
+
+    /// Reads the RGBA8 pixels of the device surface without stalling, e.g. via a pixel buffer and a fence sync.
+    /// Rows are written from the top into `out`, which must stay alive until the future completes.
+    pub fn read_surface(device: DeviceId, out: Slice) -> FutureId;
+
+    /// Begins an occlusion query in the current render pass.
+    pub fn begin_occlusion_query(device: DeviceId, query_set: QuerySetId, index: u32);
+
+    /// Ends the active occlusion query in the current render pass.
+    pub fn end_occlusion_query(device: DeviceId);
+
+    /// Writes a timestamp query in the current render pass.
+    pub fn write_timestamp(device: DeviceId, query_set: QuerySetId, index: u32);
+}
+
+#[cfg(not(feature = "backend-webgl-ext"))]
+pub use self::fallback::*;
+
+/// Fallbacks of the imports that are not implemented by the `mugl/wasm` glue code up to 0.1.4.
+/// They report the corresponding features as unsupported.
+#[cfg(not(feature = "backend-webgl-ext"))]
+mod fallback {
+    use super::{
+        BufferId, BufferSize, DeviceId, FutureId, QuerySetDescriptor, QuerySetId, RenderbufferId,
+        Slice, TextureFormat,
+    };
+    use crate::gl_const;
+    use crate::limits::Limits;
+
+    /// Reports no error, as errors cannot be polled.
+    pub unsafe fn get_device_error(_device: DeviceId) -> u32 {
+        gl_const::NO_ERROR
+    }
+
+    /// Reports a hardware device, as the renderer cannot be queried.
+    pub unsafe fn is_device_fallback(_device: DeviceId) -> bool {
+        false
+    }
+
+    /// Returns the minimum values guaranteed by WebGL 2, as parameters cannot be queried.
+    pub unsafe fn get_device_parameter(_device: DeviceId, pname: u32) -> u32 {
+        let limits = Limits::default();
+        match pname {
+            gl_const::MAX_TEXTURE_SIZE => limits.max_texture_dimension_2d,
+            gl_const::MAX_3D_TEXTURE_SIZE => limits.max_texture_dimension_3d,
+            gl_const::MAX_ARRAY_TEXTURE_LAYERS => limits.max_texture_array_layers,
+            gl_const::MAX_TEXTURE_MAX_ANISOTROPY_EXT => 2, // Minimum of EXT_texture_filter_anisotropic
+            _ => 0,
+        }
+    }
+
+    /// Reports no multisampling support, as renderbuffers cannot be created.
+    pub unsafe fn get_supported_sample_counts(_device: DeviceId, _format: TextureFormat) -> u32 {
+        0
+    }
+
+    /// Returns a null renderbuffer, so that passes render to the texture directly.
+    pub unsafe fn create_renderbuffer(
+        _device: DeviceId,
+        _format: TextureFormat,
+        _sample_count: u32,
+        _width: u32,
+        _height: u32,
+    ) -> RenderbufferId {
+        RenderbufferId::null()
+    }
+
+    pub unsafe fn delete_renderbuffer(_renderbuffer: RenderbufferId) {}
+
+    /// Returns a null query set, whose queries are ignored.
+    pub unsafe fn create_query_set(
+        _device: DeviceId,
+        _descriptor: QuerySetDescriptor,
+    ) -> QuerySetId {
+        QuerySetId::null()
+    }
+
+    pub unsafe fn delete_query_set(_query_set: QuerySetId) {}
+
+    /// Writes nothing, as query results are never available.
+    pub unsafe fn resolve_query_set(
+        _device: DeviceId,
+        _query_set: QuerySetId,
+        _first_query: u32,
+        _query_count: u32,
+        _dst: BufferId,
+        _dst_offset: BufferSize,
+    ) {
+    }
+
+    /// Returns 0. Frame capture is unsupported, so the surface is never read.
+    pub unsafe fn get_surface_width(_device: DeviceId) -> u32 {
+        0
+    }
+
+    /// Returns 0. Frame capture is unsupported, so the surface is never read.
+    pub unsafe fn get_surface_height(_device: DeviceId) -> u32 {
+        0
+    }
+
+    /// Returns a null future. Frame capture is unsupported, so the surface is never read.
+    pub unsafe fn read_surface(_device: DeviceId, _out: Slice) -> FutureId {
+        FutureId::null()
+    }
+
+    pub unsafe fn begin_occlusion_query(_device: DeviceId, _query_set: QuerySetId, _index: u32) {}
+
+    pub unsafe fn end_occlusion_query(_device: DeviceId) {}
+
+    pub unsafe fn write_timestamp(_device: DeviceId, _query_set: QuerySetId, _index: u32) {}
+}
@@ -1,5 +1,7 @@
 use bitflags::bitflags;
 
+use crate::primitive::{PowerPreference, TextureFormat};
+
 bitflags! {
     /// WebGL2 features.
     #[repr(transparent)]
@@ -9,20 +11,51 @@ bitflags! {
         const TEXTURE_HALF_FLOAT_LINEAR = 0x0002;
         const TEXTURE_FLOAT_LINEAR = 0x0004;
         const COLOR_BUFFER_FLOAT = 0x0008;
+        /// Timestamp queries, using the EXT_disjoint_timer_query_webgl2 extension.
+        const TIMER_QUERY = 0x0010;
+        /// BC compressed texture formats, using the WEBGL_compressed_texture_s3tc extension.
+        const TEXTURE_COMPRESSION_BC = 0x0020;
+        /// ETC2 compressed texture formats, using the WEBGL_compressed_texture_etc extension.
+        const TEXTURE_COMPRESSION_ETC2 = 0x0040;
+        /// ASTC compressed texture formats, using the WEBGL_compressed_texture_astc extension.
+        const TEXTURE_COMPRESSION_ASTC = 0x0080;
     }
 
-    /// WebGL context attribute flags.
+    /// WebGL render pipeline flags.
+    #[repr(transparent)]
+    #[derive(Default)]
+    pub struct WebGLPipelineFlags: u32 {
+        /// Emulates uniform buffer bindings with plain uniforms, for drivers with broken uniform buffer support.
+        ///
+        /// The uniform blocks of the shaders are reflected into individual `uniform*` calls at draw time,
+        /// reading from CPU-side shadow copies of the bound buffers.
+        /// This costs extra memory and uniform calls, so it should only be enabled on affected devices.
+        /// Uniform block members must use the `std140` layout.
+        const EMULATE_UNIFORM_BUFFERS = 0x0001;
+    }
+
+    /// WebGL context attribute flags, each enabling the context creation attribute of the same name.
+    ///
+    /// The `antialias`, `depth` and `stencil` attributes describe the default framebuffer,
+    /// so they are set from the [WebGLSurfaceDescriptor] when requesting a device.
+    /// The `powerPreference` attribute is `"high-performance"` if [WebGLContextAttribute::HIGH_PEFORMANCE] is set,
+    /// `"low-power"` if [WebGLContextAttribute::LOW_POWER] is set, or `"default"` if neither is set.
     #[repr(transparent)]
     pub struct WebGLContextAttribute: u32 {
+        /// The canvas has an alpha channel, composited with the page.
         const ALPHA = 0x0001;
         const ANTIALIAS = 0x0002;
         const DEPTH = 0x0004;
+        /// Decouples the canvas from the page compositing, reducing the latency of drawing e.g. pen strokes.
         const DESYNCHRONIZED = 0x0008;
         const FAIL_IF_MAJOR_PERFORMANCE_CAVEAT = 0x0010;
         const HIGH_PEFORMANCE = 0x0020;
+        /// The colors of the canvas are premultiplied by alpha.
         const PREMULTIPLIED_ALPHA = 0x0040;
+        /// Keeps the canvas content after it is presented, e.g. for reading back screenshots outside of a frame.
         const PRESERVE_DRAWING_BUFFER = 0x0080;
         const STENCIL = 0x0100;
+        const LOW_POWER = 0x0200;
     }
 }
 
@@ -36,3 +69,98 @@ impl Default for WebGLContextAttribute {
             | Self::PREMULTIPLIED_ALPHA
     }
 }
+
+impl WebGLContextAttribute {
+    /// Returns the context attributes with depth, stencil and antialias flags configured from the given surface descriptor.
+    pub fn with_surface(self, surface: WebGLSurfaceDescriptor) -> Self {
+        let mut attr = self - (Self::ANTIALIAS | Self::DEPTH | Self::STENCIL);
+        if let Some(format) = surface.depth_stencil_format {
+            attr |= Self::DEPTH;
+            if matches!(
+                format,
+                TextureFormat::DEPTH24STENCIL8 | TextureFormat::DEPTH32FSTENCIL8
+            ) {
+                attr |= Self::STENCIL;
+            }
+        }
+        if surface.sample_count > 1 {
+            attr |= Self::ANTIALIAS;
+        }
+        attr
+    }
+
+    /// Returns the context attributes with the `powerPreference` attribute set to the given preference.
+    pub fn with_power_preference(self, preference: PowerPreference) -> Self {
+        let attr = self - (Self::HIGH_PEFORMANCE | Self::LOW_POWER);
+        match preference {
+            PowerPreference::LowPower => attr | Self::LOW_POWER,
+            PowerPreference::HighPerformance => attr | Self::HIGH_PEFORMANCE,
+        }
+    }
+
+    /// Returns the context attributes with the `powerPreference` attribute set to `"default"`,
+    /// letting the browser choose the GPU.
+    pub fn with_default_power_preference(self) -> Self {
+        self - (Self::HIGH_PEFORMANCE | Self::LOW_POWER)
+    }
+
+    /// Returns the context attributes with the `alpha` attribute set.
+    pub fn with_alpha(self, alpha: bool) -> Self {
+        let mut attr = self;
+        attr.set(Self::ALPHA, alpha);
+        attr
+    }
+
+    /// Returns the context attributes with the `premultipliedAlpha` attribute set.
+    pub fn with_premultiplied_alpha(self, premultiplied: bool) -> Self {
+        let mut attr = self;
+        attr.set(Self::PREMULTIPLIED_ALPHA, premultiplied);
+        attr
+    }
+
+    /// Returns the context attributes with the `desynchronized` attribute set.
+    /// If enabled, the browser may present frames without waiting for the page compositor, for lower latency,
+    /// at the cost of possible tearing.
+    pub fn with_desynchronized(self, desynchronized: bool) -> Self {
+        let mut attr = self;
+        attr.set(Self::DESYNCHRONIZED, desynchronized);
+        attr
+    }
+
+    /// Returns the context attributes with the `preserveDrawingBuffer` attribute set.
+    /// If enabled, the canvas is not cleared after presenting, so that it can be read back at any time,
+    /// at the cost of performance on some platforms.
+    pub fn with_preserve_drawing_buffer(self, preserve: bool) -> Self {
+        let mut attr = self;
+        attr.set(Self::PRESERVE_DRAWING_BUFFER, preserve);
+        attr
+    }
+
+    /// Returns the context attributes with the `failIfMajorPerformanceCaveat` attribute set.
+    /// If enabled, requesting a device fails instead of falling back to a slow (e.g. software) implementation.
+    pub fn with_fail_if_major_performance_caveat(self, fail: bool) -> Self {
+        let mut attr = self;
+        attr.set(Self::FAIL_IF_MAJOR_PERFORMANCE_CAVEAT, fail);
+        attr
+    }
+}
+
+/// WebGL surface (default framebuffer) descriptor.
+///
+/// WebGL does not allow choosing the exact depth precision or sample count of the default framebuffer.
+/// The depth-stencil format only determines whether depth and stencil buffers are requested,
+/// and any sample count greater than 1 enables antialiasing.
+#[derive(Clone, Copy, Debug)]
+pub struct WebGLSurfaceDescriptor {
+    pub depth_stencil_format: Option<TextureFormat>,
+    pub sample_count: u32,
+}
+
+impl Default for WebGLSurfaceDescriptor {
+    fn default() -> Self {
+        Self {
+            depth_stencil_format: Some(TextureFormat::DEPTH24STENCIL8),
+            sample_count: 1,
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use super::interop::{
-    BindGroupId, BindGroupLayoutId, BufferId, RenderPassId, RenderPipelineId, SamplerId, ShaderId,
-    TextureId,
+    BindGroupId, BindGroupLayoutId, BufferId, QuerySetId, RenderPassId, RenderPipelineId,
+    RenderbufferId, SamplerId, ShaderId, TextureId,
 };
 use super::mugl;
+use crate::primitive::{BufferSize, BufferUsage, SamplerBindingType};
 use alloc::vec::Vec;
-use core::ops::Deref;
+use core::ops::{Deref, Range};
 
 /// WebGL GPU buffer.
 #[derive(Debug)]
 pub struct WebGLBuffer {
     pub(crate) id: BufferId,
+    pub(crate) usage: BufferUsage,
 }
 
 impl Drop for WebGLBuffer {
@@ -23,12 +25,20 @@ impl Drop for WebGLBuffer {
 #[derive(Debug)]
 pub struct WebGLTexture {
     pub(crate) id: TextureId,
+    pub(crate) sample_count: u32,
+    /// Multisampled renderbuffer to render to in place of the texture, or null if not multisampled.
+    pub(crate) msaa_renderbuffer: RenderbufferId,
 }
 
 impl Drop for WebGLTexture {
     #[inline]
     fn drop(&mut self) {
-        unsafe { mugl::delete_texture(self.id) }
+        unsafe {
+            mugl::delete_texture(self.id);
+            if !self.msaa_renderbuffer.is_null() {
+                mugl::delete_renderbuffer(self.msaa_renderbuffer);
+            }
+        }
     }
 }
 
@@ -36,6 +46,8 @@ impl Drop for WebGLTexture {
 #[derive(Debug)]
 pub struct WebGLSampler {
     pub(crate) id: SamplerId,
+    /// Whether the sampler has a compare function, i.e. TEXTURE_COMPARE_MODE is COMPARE_REF_TO_TEXTURE
+    pub(crate) compare: bool,
 }
 
 impl Drop for WebGLSampler {
@@ -62,6 +74,7 @@ impl Drop for WebGLShader {
 #[derive(Debug)]
 pub struct WebGLBindGroup {
     pub(crate) id: BindGroupId,
+    pub(crate) dynamic_offset_count: u32,
 }
 
 impl Drop for WebGLBindGroup {
@@ -75,6 +88,10 @@ impl Drop for WebGLBindGroup {
 #[derive(Debug)]
 pub struct WebGLBindGroupLayout {
     pub(crate) id: BindGroupLayoutId,
+    pub(crate) dynamic_offset_count: u32,
+    pub(crate) texture_unit_count: u32,
+    /// Binding and type of each sampler entry
+    pub(crate) samplers: Vec<(u32, SamplerBindingType)>,
 }
 
 impl Drop for WebGLBindGroupLayout {
@@ -110,6 +127,50 @@ impl Drop for WebGLRenderPass {
     }
 }
 
+/// WebGL GPU query set.
+#[derive(Debug)]
+pub struct WebGLQuerySet {
+    pub(crate) id: QuerySetId,
+}
+
+impl Drop for WebGLQuerySet {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { mugl::delete_query_set(self.id) }
+    }
+}
+
+/// WebGL GPU render bundle, whose commands are replayed when executed in a render pass.
+#[derive(Debug)]
+pub struct WebGLRenderBundle {
+    pub(crate) commands: Vec<WebGLRenderCommand>,
+}
+
+/// A command recorded in a [WebGLRenderBundle].
+#[derive(Clone, Debug)]
+pub(crate) enum WebGLRenderCommand {
+    Pipeline(RenderPipelineId),
+    Index(BufferId),
+    Vertex {
+        slot: u32,
+        buffer: BufferId,
+        offset: BufferSize,
+    },
+    BindGroup {
+        slot: u32,
+        bind_group: BindGroupId,
+        offsets: Vec<u32>,
+    },
+    Draw {
+        vertices: Range<u32>,
+        instances: Range<u32>,
+    },
+    DrawIndexed {
+        indices: Range<u32>,
+        instances: Range<u32>,
+    },
+}
+
 /// Readonly GPU buffer view.
 #[derive(Debug)]
 pub struct WebGLBufferView {
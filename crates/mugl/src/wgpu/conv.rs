@@ -1,4 +1,4 @@
-use super::WGPU;
+use super::{WGPUFeatures, WGPU};
 use crate::descriptor::{
     BindingResource, BindingType, BlendComponent, BlendState, DepthStencilState, ImageCopyTexture,
     ImageDataLayout, MultisampleState, PrimitiveState, StencilFaceState, TextureView,
@@ -6,8 +6,8 @@ use crate::descriptor::{
 use crate::primitive::{
     AddressMode, BlendFactor, BlendOperation, BufferUsage, Color, ColorWrite, CompareFunction,
     CullMode, Extent2D, Extent3D, FilterMode, FrontFace, IndexFormat, Origin3D, PowerPreference,
-    PrimitiveTopology, SamplerBindingType, ShaderStage, StencilOperation, TextureDimension,
-    TextureFormat, TextureSampleType, TextureUsage, VertexFormat, VertexStepMode,
+    PrimitiveTopology, QueryType, SamplerBindingType, ShaderStage, StencilOperation,
+    TextureDimension, TextureFormat, TextureSampleType, TextureUsage, VertexFormat, VertexStepMode,
 };
 
 pub fn wgpu_operations<T>(ops: Option<T>) -> wgpu::Operations<T> {
@@ -20,6 +20,65 @@ pub fn wgpu_operations<T>(ops: Option<T>) -> wgpu::Operations<T> {
     }
 }
 
+impl From<WGPUFeatures> for wgpu::Features {
+    fn from(features: WGPUFeatures) -> Self {
+        let mut result = wgpu::Features::empty();
+        if features.contains(WGPUFeatures::MULTIVIEW) {
+            result |= wgpu::Features::MULTIVIEW;
+        }
+        if features.contains(WGPUFeatures::TIMESTAMP_QUERY) {
+            result |= wgpu::Features::TIMESTAMP_QUERY;
+        }
+        if features.contains(WGPUFeatures::INDIRECT_FIRST_INSTANCE) {
+            result |= wgpu::Features::INDIRECT_FIRST_INSTANCE;
+        }
+        if features.contains(WGPUFeatures::TEXTURE_COMPRESSION_BC) {
+            result |= wgpu::Features::TEXTURE_COMPRESSION_BC;
+        }
+        if features.contains(WGPUFeatures::TEXTURE_COMPRESSION_ETC2) {
+            result |= wgpu::Features::TEXTURE_COMPRESSION_ETC2;
+        }
+        if features.contains(WGPUFeatures::TEXTURE_COMPRESSION_ASTC) {
+            result |= wgpu::Features::TEXTURE_COMPRESSION_ASTC_LDR;
+        }
+        result
+    }
+}
+
+impl From<wgpu::Features> for WGPUFeatures {
+    fn from(features: wgpu::Features) -> Self {
+        let mut result = WGPUFeatures::empty();
+        if features.contains(wgpu::Features::MULTIVIEW) {
+            result |= WGPUFeatures::MULTIVIEW;
+        }
+        if features.contains(wgpu::Features::TIMESTAMP_QUERY) {
+            result |= WGPUFeatures::TIMESTAMP_QUERY;
+        }
+        if features.contains(wgpu::Features::INDIRECT_FIRST_INSTANCE) {
+            result |= WGPUFeatures::INDIRECT_FIRST_INSTANCE;
+        }
+        if features.contains(wgpu::Features::TEXTURE_COMPRESSION_BC) {
+            result |= WGPUFeatures::TEXTURE_COMPRESSION_BC;
+        }
+        if features.contains(wgpu::Features::TEXTURE_COMPRESSION_ETC2) {
+            result |= WGPUFeatures::TEXTURE_COMPRESSION_ETC2;
+        }
+        if features.contains(wgpu::Features::TEXTURE_COMPRESSION_ASTC_LDR) {
+            result |= WGPUFeatures::TEXTURE_COMPRESSION_ASTC;
+        }
+        result
+    }
+}
+
+impl From<QueryType> for wgpu::QueryType {
+    fn from(ty: QueryType) -> Self {
+        match ty {
+            QueryType::Occlusion => wgpu::QueryType::Occlusion,
+            QueryType::Timestamp => wgpu::QueryType::Timestamp,
+        }
+    }
+}
+
 impl From<PowerPreference> for wgpu::PowerPreference {
     fn from(preference: PowerPreference) -> Self {
         match preference {
@@ -43,6 +102,9 @@ impl From<BufferUsage> for wgpu::BufferUsages {
         if usage.contains(BufferUsage::UNIFORM) {
             result.insert(wgpu::BufferUsages::UNIFORM);
         }
+        if usage.contains(BufferUsage::INDIRECT) {
+            result.insert(wgpu::BufferUsages::INDIRECT);
+        }
         result
     }
 }
@@ -88,14 +150,26 @@ impl From<Color> for wgpu::Color {
     }
 }
 
-impl<'a> From<ImageCopyTexture<'a, WGPU>> for wgpu::ImageCopyTexture<'a> {
-    fn from(texture: ImageCopyTexture<'a, WGPU>) -> Self {
-        Self {
-            texture: &texture.texture.texture,
-            mip_level: texture.mip_level,
-            origin: texture.origin.into(),
-            aspect: wgpu::TextureAspect::All,
-        }
+/// Converts an image copy texture. Returns `None` for the surface texture, which cannot be copied.
+pub fn wgpu_image_copy_texture(texture: ImageCopyTexture<WGPU>) -> Option<wgpu::ImageCopyTexture> {
+    Some(wgpu::ImageCopyTexture {
+        texture: texture.texture.texture.as_ref()?,
+        mip_level: texture.mip_level,
+        origin: texture.origin.into(),
+        aspect: wgpu::TextureAspect::All,
+    })
+}
+
+/// Converts a surface texture format. Returns `None` if the format is not supported.
+pub fn mugl_surface_format(format: wgpu::TextureFormat) -> Option<TextureFormat> {
+    match format {
+        wgpu::TextureFormat::Rgba8Unorm => Some(TextureFormat::RGBA8),
+        wgpu::TextureFormat::Rgba8UnormSrgb => Some(TextureFormat::SRGBA8),
+        wgpu::TextureFormat::Bgra8Unorm => Some(TextureFormat::BGRA8),
+        wgpu::TextureFormat::Bgra8UnormSrgb => Some(TextureFormat::SBGRA8),
+        wgpu::TextureFormat::Rgb10a2Unorm => Some(TextureFormat::RGB10A2),
+        wgpu::TextureFormat::Rgba16Float => Some(TextureFormat::RGBA16F),
+        _ => None,
     }
 }
 
@@ -188,6 +262,8 @@ impl From<TextureFormat> for wgpu::TextureFormat {
             TextureFormat::RGBA8UI => wgpu::TextureFormat::Rgba8Uint,
             TextureFormat::RGBA8I => wgpu::TextureFormat::Rgba8Sint,
             TextureFormat::RGB10A2 => wgpu::TextureFormat::Rgb10a2Unorm,
+            TextureFormat::BGRA8 => wgpu::TextureFormat::Bgra8Unorm,
+            TextureFormat::SBGRA8 => wgpu::TextureFormat::Bgra8UnormSrgb,
             TextureFormat::RG32UI => wgpu::TextureFormat::Rg32Uint,
             TextureFormat::RG32I => wgpu::TextureFormat::Rg32Sint,
             TextureFormat::RGBA16UI => wgpu::TextureFormat::Rgba16Uint,
@@ -223,21 +299,32 @@ impl From<TextureUsage> for wgpu::TextureUsages {
     }
 }
 
-impl<'a> From<TextureView<'a, WGPU>> for wgpu::TextureView {
-    fn from(view: TextureView<'a, WGPU>) -> Self {
+/// Creates an attachment view of a texture. Returns `None` for the surface texture, which is attached separately.
+/// For multiview rendering, the view covers the given number of array layers starting from the view slice.
+pub fn wgpu_attachment_view(
+    view: TextureView<WGPU>,
+    multiview: Option<core::num::NonZeroU32>,
+) -> Option<wgpu::TextureView> {
+    Some(
         view.texture
             .texture
+            .as_ref()?
             .create_view(&wgpu::TextureViewDescriptor {
                 label: None,
                 format: Some(view.texture.format.into()),
-                dimension: Some(view.texture.dimension.into()),
+                // A single slice / face is attached as a 2D view, multiple layers as a 2D array
+                dimension: Some(if multiview.is_some() {
+                    wgpu::TextureViewDimension::D2Array
+                } else {
+                    wgpu::TextureViewDimension::D2
+                }),
                 aspect: wgpu::TextureAspect::All,
                 base_mip_level: view.mip_level,
                 mip_level_count: core::num::NonZeroU32::new(1),
                 base_array_layer: view.slice,
-                array_layer_count: core::num::NonZeroU32::new(1),
-            })
-    }
+                array_layer_count: multiview.or(core::num::NonZeroU32::new(1)),
+            }),
+    )
 }
 
 impl From<IndexFormat> for wgpu::IndexFormat {
@@ -1,27 +1,45 @@
 use alloc::borrow::Cow;
 use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt;
+use core::future::Future;
+use core::num::NonZeroU32;
 use core::ops::Range;
+use core::pin::Pin;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
 
-use std::sync::{RwLock, RwLockWriteGuard};
+use std::string::ToString;
+use std::sync::{Arc, Mutex, RwLock, RwLockWriteGuard};
 
 use async_trait::async_trait;
 use raw_window_handle::HasRawWindowHandle;
 
-use super::conv::wgpu_operations;
+use super::conv::{
+    mugl_surface_format, wgpu_attachment_view, wgpu_image_copy_texture, wgpu_operations,
+};
+use super::mipmap::WGPUMipmapGenerator;
 use super::resource::{
     WGPUBindGroup, WGPUBindGroupLayout, WGPUBuffer, WGPUBufferView, WGPUDeviceDescriptor,
-    WGPUFeatures, WGPURenderPass, WGPURenderPipeline, WGPUSampler, WGPUShader,
-    WGPUSurfaceDescriptor, WGPUTexture,
+    WGPUFeatures, WGPUImageSource, WGPUQuerySet, WGPURenderBundle, WGPURenderPass,
+    WGPURenderPipeline, WGPUSampler, WGPUShader, WGPUSurfaceDescriptor, WGPUTexture,
 };
 use crate::descriptor::{
     BindGroupDescriptor, BindGroupLayoutDescriptor, BufferDescriptor, ColorTargetStates,
-    ImageCopyTexture, ImageDataLayout, RenderPassDescriptor, RenderPipelineDescriptor,
-    SamplerDescriptor, ShaderDescriptor, TextureDescriptor,
+    ImageCopyExternalImage, ImageCopyTexture, ImageDataLayout, QuerySetDescriptor,
+    RenderBundleDescriptor, RenderPassDescriptor, RenderPipelineDescriptor, SamplerDescriptor,
+    ShaderDescriptor, TextureDescriptor,
+};
+use crate::gpu::{
+    CapturedFrame, FrameCallback, GPUDevice, GPUDeviceWebExt, GPURefTypes, GPURenderBundleEncoder,
+    GPURenderPassEncoder, GPUWebExt, LogSink, GPU,
+};
+use crate::limits::{self, DepthFormats, Limits, TextureCompression};
+use crate::primitive::{
+    BufferSize, Color, Extent2D, Extent3D, LogLevel, MipmapHint, QueryType, TextureDimension,
+    TextureFormat, TextureUsage,
 };
-use crate::gpu::{GPUDevice, GPURefTypes, GPURenderPassEncoder, GPU};
-use crate::primitive::{BufferSize, Color, Extent2D, Extent3D, TextureUsage};
 
 const DEFAULT_SURFACE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8UnormSrgb;
 
@@ -34,11 +52,11 @@ pub struct WGPU;
 pub struct WGPUDevice {
     #[allow(dead_code)]
     instance: wgpu::Instance,
-    #[allow(dead_code)]
     adapter: wgpu::Adapter,
     device: wgpu::Device,
     queue: wgpu::Queue,
     surface: wgpu::Surface,
+    features: WGPUFeatures,
 
     surface_config: RwLock<wgpu::SurfaceConfiguration>,
     surface_texture: RwLock<WGPUSurfaceTexture>,
@@ -47,6 +65,11 @@ pub struct WGPUDevice {
 
     commands: RwLock<Vec<wgpu::CommandBuffer>>,
     encoder: RwLock<Option<wgpu::CommandEncoder>>,
+
+    log_sink: Arc<RwLock<Option<LogSink>>>,
+    frame_capture: Mutex<WGPUFrameCapture>,
+    /// Mipmap generator, created on first use.
+    mipmap_generator: Mutex<Option<WGPUMipmapGenerator>>,
 }
 
 /// WebGPU surface texture.
@@ -60,6 +83,52 @@ struct WGPUSurfaceTexture {
     depth_texture_view: Option<wgpu::TextureView>,
 }
 
+/// Future of a buffer mapping.
+type WGPUMapFuture = Pin<Box<dyn Future<Output = Result<(), wgpu::BufferAsyncError>> + Send>>;
+
+/// State of the frame capture of a device.
+#[derive(Debug, Default)]
+struct WGPUFrameCapture {
+    /// Number of frames yet to be copied.
+    remaining: u32,
+    next_index: u32,
+    callback: Option<FrameCallback>,
+    pending: VecDeque<WGPUPendingFrame>,
+}
+
+impl WGPUFrameCapture {
+    /// Releases the callback once all requested frames are copied and delivered.
+    /// The callback is kept while frames remain to be copied, even if none is pending yet,
+    /// e.g. before the surface is reconfigured to be copied from.
+    fn release_if_done(&mut self) {
+        if self.remaining == 0 && self.pending.is_empty() {
+            self.callback = None;
+        }
+    }
+}
+
+/// A frame being read back from the surface.
+struct WGPUPendingFrame {
+    index: u32,
+    size: Extent2D,
+    /// Row pitch of the buffer, padded to [wgpu::COPY_BYTES_PER_ROW_ALIGNMENT].
+    bytes_per_row: u32,
+    /// Whether the surface is in BGRA order, which needs to be swizzled to RGBA.
+    bgra: bool,
+    buffer: wgpu::Buffer,
+    mapped: Option<WGPUMapFuture>,
+}
+
+impl fmt::Debug for WGPUPendingFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WGPUPendingFrame")
+            .field("index", &self.index)
+            .field("size", &self.size)
+            .field("buffer", &self.buffer)
+            .finish()
+    }
+}
+
 /// WebGPU render pass encoder.
 #[derive(Debug)]
 pub struct WGPURenderPassEncoder<'a> {
@@ -71,6 +140,13 @@ pub struct WGPURenderPassEncoder<'a> {
     index_format: RwLock<wgpu::IndexFormat>,
 }
 
+/// WebGPU render bundle encoder.
+#[derive(Debug)]
+pub struct WGPURenderBundleEncoder<'a> {
+    encoder: RwLock<wgpu::RenderBundleEncoder<'a>>,
+    index_format: RwLock<wgpu::IndexFormat>,
+}
+
 impl WGPU {
     /// Requests a new WGPU device asynchronously
     pub async fn request_device<W: HasRawWindowHandle>(
@@ -89,10 +165,11 @@ impl WGPU {
             })
             .await?;
 
+        let features = wgpu::Features::from(descriptor.features) & adapter.features();
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
-                    features: wgpu::Features::empty(),
+                    features,
                     limits: wgpu::Limits::default(),
                     label: None,
                 },
@@ -129,12 +206,16 @@ impl WGPU {
             device,
             queue,
             surface,
+            features: features.into(),
             surface_config: RwLock::new(surface_config),
             surface_texture: RwLock::new(surface_texture),
             surface_depth_format,
             surface_msaa_sample_count,
             commands: RwLock::default(),
             encoder: RwLock::default(),
+            log_sink: Arc::default(),
+            frame_capture: Mutex::default(),
+            mipmap_generator: Mutex::default(),
         })
     }
 }
@@ -150,13 +231,21 @@ impl GPU for WGPU {
     type RenderPipeline = WGPURenderPipeline;
     type BindGroup = WGPUBindGroup;
     type BindGroupLayout = WGPUBindGroupLayout;
+    type QuerySet = WGPUQuerySet;
+    type RenderBundle = WGPURenderBundle;
 }
 
 impl<'a> GPURefTypes<'a, WGPU> for WGPU {
     type RenderPassEncoder = WGPURenderPassEncoder<'a>;
+    type RenderBundleEncoder = WGPURenderBundleEncoder<'a>;
     type BufferView = WGPUBufferView<'a>;
 }
 
+impl GPUWebExt for WGPU {
+    type ImageSource = WGPUImageSource;
+    type Device = WGPUDevice;
+}
+
 impl WGPUDevice {
     /// Gets the surface format being used.
     #[inline]
@@ -189,12 +278,161 @@ impl WGPUDevice {
         }
         encoder
     }
+
+    /// Encodes a copy of the surface texture into a readback buffer if a frame capture is in progress.
+    fn copy_surface_frame(&self) -> Option<WGPUPendingFrame> {
+        let mut capture = self.frame_capture.lock().unwrap();
+        if capture.remaining == 0 {
+            return None;
+        }
+
+        let surface_config = self.surface_config.read().unwrap().clone();
+        if !surface_config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            // The surface is reconfigured after present, so that frames can be copied from the next one
+            return None;
+        }
+        let surface_texture = self.surface_texture.read().unwrap();
+        let texture = &surface_texture.texture.as_ref()?.texture;
+
+        let size = Extent2D(surface_config.width, surface_config.height);
+        let bytes_per_row = (size.0 * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (bytes_per_row * size.1) as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        if let Some(encoder) = self.get_encoder().as_mut() {
+            encoder.copy_texture_to_buffer(
+                wgpu::ImageCopyTexture {
+                    texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::ImageCopyBuffer {
+                    buffer: &buffer,
+                    layout: wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: NonZeroU32::new(bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                wgpu::Extent3d {
+                    width: size.0,
+                    height: size.1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        if let Some(encoder) = self.encoder.write().unwrap().take() {
+            self.commands.write().unwrap().push(encoder.finish());
+        }
+
+        let frame = WGPUPendingFrame {
+            index: capture.next_index,
+            size,
+            bytes_per_row,
+            bgra: matches!(
+                surface_config.format,
+                wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+            ),
+            buffer,
+            mapped: None,
+        };
+        capture.remaining -= 1;
+        capture.next_index += 1;
+        Some(frame)
+    }
+
+    /// Delivers the captured frames that are read back, in order.
+    /// Also enables copying from the surface while a frame capture is in progress.
+    fn deliver_captured_frames(&self) {
+        let mut capture = self.frame_capture.lock().unwrap();
+        let capture = &mut *capture;
+
+        if capture.remaining > 0 {
+            let mut surface_config = self.surface_config.write().unwrap();
+            if !surface_config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+                surface_config.usage |= wgpu::TextureUsages::COPY_SRC;
+                self.surface.configure(&self.device, &surface_config);
+            }
+        }
+
+        if capture.pending.is_empty() {
+            capture.release_if_done();
+            return;
+        }
+
+        self.device.poll(wgpu::Maintain::Poll);
+        let waker = noop_waker();
+        let mut context = Context::from_waker(&waker);
+        while let Some(frame) = capture.pending.front_mut() {
+            let result = match frame
+                .mapped
+                .as_mut()
+                .map(|mapped| mapped.as_mut().poll(&mut context))
+            {
+                Some(Poll::Ready(result)) => result,
+                _ => break,
+            };
+            let frame = capture.pending.pop_front().unwrap();
+            if let (Ok(()), Some(callback)) = (result, capture.callback.as_mut()) {
+                let data = read_frame(&frame);
+                callback.call(CapturedFrame {
+                    index: frame.index,
+                    size: frame.size,
+                    data: &data,
+                });
+            }
+        }
+
+        capture.release_if_done();
+    }
+
+    /// Reports a message to the log sink, if any.
+    fn log(&self, level: LogLevel, message: &str) {
+        if let Some(sink) = &*self.log_sink.read().unwrap() {
+            sink.log(level, message);
+        }
+    }
 }
 
 #[async_trait(?Send)]
 impl GPUDevice<WGPU> for WGPUDevice {
     fn features(&self) -> WGPUFeatures {
-        WGPUFeatures::empty()
+        self.features
+    }
+
+    fn limits(&self) -> Limits {
+        let limits = self.device.limits();
+        let mut texture_compression = TextureCompression::empty();
+        texture_compression.set(
+            TextureCompression::BC,
+            self.features.contains(WGPUFeatures::TEXTURE_COMPRESSION_BC),
+        );
+        texture_compression.set(
+            TextureCompression::ETC2,
+            self.features
+                .contains(WGPUFeatures::TEXTURE_COMPRESSION_ETC2),
+        );
+        texture_compression.set(
+            TextureCompression::ASTC,
+            self.features
+                .contains(WGPUFeatures::TEXTURE_COMPRESSION_ASTC),
+        );
+
+        Limits {
+            max_texture_dimension_2d: limits.max_texture_dimension_2d,
+            max_texture_dimension_3d: limits.max_texture_dimension_3d,
+            max_texture_array_layers: limits.max_texture_array_layers,
+            max_bind_groups: limits.max_bind_groups,
+            max_sampler_anisotropy: 16,
+            // DEPTH16 is substituted by a higher precision format, while DEPTH32FSTENCIL8 has no substitute
+            depth_formats: DepthFormats::all() - DepthFormats::DEPTH32FSTENCIL8,
+            texture_compression,
+        }
     }
 
     fn create_buffer(&self, descriptor: BufferDescriptor) -> WGPUBuffer {
@@ -209,10 +447,26 @@ impl GPUDevice<WGPU> for WGPUDevice {
     }
 
     fn create_texture(&self, descriptor: TextureDescriptor) -> WGPUTexture {
+        if cfg!(debug_assertions) {
+            if let Err(message) = limits::validate_texture(&self.limits(), &descriptor) {
+                panic!("{}", message);
+            }
+        }
+
         let msaa_resolve = !descriptor.format.is_depth_stencil()  // depth-stencil cannot be MSAA resolved
             && descriptor.sample_count > 1
             && descriptor.usage.contains(TextureUsage::RENDER_ATTACHMENT);
 
+        let format = descriptor.format.into();
+        let mut usage: wgpu::TextureUsages = descriptor.usage.into();
+        if descriptor.mip_level_count > 1
+            && descriptor.dimension != TextureDimension::D3
+            && WGPUMipmapGenerator::supports(format)
+        {
+            // Mip levels are generated by rendering from the previous level
+            usage |= wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT;
+        }
+
         let texture = self.device.create_texture(&wgpu::TextureDescriptor {
             label: None,
             size: descriptor.size.into(),
@@ -223,13 +477,16 @@ impl GPUDevice<WGPU> for WGPUDevice {
                 descriptor.sample_count
             },
             dimension: descriptor.dimension.into(),
-            format: descriptor.format.into(),
-            usage: descriptor.usage.into(),
+            format,
+            usage,
         });
 
         WGPUTexture {
-            view: texture.create_view(&wgpu::TextureViewDescriptor::default()),
-            texture,
+            view: texture.create_view(&wgpu::TextureViewDescriptor {
+                dimension: Some(descriptor.dimension.into()),
+                ..Default::default()
+            }),
+            texture: Some(texture),
             msaa_texture: if msaa_resolve {
                 Some(self.device.create_texture(&wgpu::TextureDescriptor {
                     label: None,
@@ -249,10 +506,18 @@ impl GPUDevice<WGPU> for WGPUDevice {
             },
             format: descriptor.format,
             dimension: descriptor.dimension,
+            size: descriptor.size,
+            mip_level_count: descriptor.mip_level_count,
         }
     }
 
     fn create_sampler(&self, descriptor: SamplerDescriptor) -> WGPUSampler {
+        if cfg!(debug_assertions) {
+            if let Err(message) = limits::validate_sampler(&self.limits(), &descriptor) {
+                panic!("{}", message);
+            }
+        }
+
         WGPUSampler {
             sampler: self.device.create_sampler(&wgpu::SamplerDescriptor {
                 label: None,
@@ -286,6 +551,18 @@ impl GPUDevice<WGPU> for WGPUDevice {
         &self,
         descriptor: RenderPipelineDescriptor<WGPU>,
     ) -> WGPURenderPipeline {
+        assert!(
+            descriptor.multiview.is_none() || self.features.contains(WGPUFeatures::MULTIVIEW),
+            "multiview rendering requires the MULTIVIEW feature"
+        );
+        if cfg!(debug_assertions) {
+            if let Err(message) =
+                limits::validate_bind_group_count(&self.limits(), descriptor.bind_groups.len())
+            {
+                panic!("{}", message);
+            }
+        }
+
         let attributes = {
             let mut attributes = Vec::<wgpu::VertexAttribute>::new();
             for layout in descriptor.buffers {
@@ -360,7 +637,7 @@ impl GPUDevice<WGPU> for WGPUDevice {
                                 .collect::<Vec<_>>(),
                         }),
                     }),
-                    multiview: None, // TODO: multiview
+                    multiview: descriptor.multiview,
                 }),
             index_format: descriptor
                 .primitive
@@ -392,39 +669,65 @@ impl GPUDevice<WGPU> for WGPUDevice {
                 depth_stencil,
                 clear_depth,
                 clear_stencil,
-            } => WGPURenderPass {
-                color_views: colors
+                multiview,
+            } => {
+                assert!(
+                    multiview.is_none() || self.features.contains(WGPUFeatures::MULTIVIEW),
+                    "multiview rendering requires the MULTIVIEW feature"
+                );
+                if cfg!(debug_assertions) {
+                    if let Err(message) = limits::validate_render_pass_attachments(
+                        colors.len(),
+                        depth_stencil.is_some(),
+                    ) {
+                        panic!("{}", message);
+                    }
+                }
+                let surface_texture = self.surface_texture.read().unwrap();
+                let (color_views, resolve_targets) = colors
                     .iter()
-                    .map(|color| {
-                        color
-                            .view
-                            .texture
-                            .msaa_texture
-                            .as_ref()
-                            .map(|texture| {
-                                texture.create_view(&wgpu::TextureViewDescriptor::default())
+                    .filter_map(|color| {
+                        if color.view.texture.texture.is_none() {
+                            assert!(
+                                multiview.is_none(),
+                                "multiview rendering to surface texture is not supported"
+                            );
+                            let view = surface_texture
+                                .texture
+                                .as_ref()
+                                .expect("surface texture is not acquired")
+                                .texture
+                                .create_view(&wgpu::TextureViewDescriptor::default());
+                            Some(match &surface_texture.msaa_texture {
+                                Some(texture) => (
+                                    texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                                    Some(view),
+                                ),
+                                None => (view, None),
                             })
-                            .unwrap_or_else(|| color.view.into())
-                    })
-                    .collect(),
-                resolve_targets: colors
-                    .iter()
-                    .map(|color| {
-                        if color.view.texture.msaa_texture.is_some() {
-                            Some(color.view.into())
+                        } else if let Some(texture) = &color.view.texture.msaa_texture {
+                            Some((
+                                texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                                wgpu_attachment_view(color.view, None),
+                            ))
                         } else {
-                            None
+                            Some((wgpu_attachment_view(color.view, multiview)?, None))
                         }
                     })
-                    .collect(),
-                depth_view: depth_stencil.map(Into::into),
-                color_ops: colors
-                    .iter()
-                    .map(|color| wgpu_operations(color.clear.map(Into::into)))
-                    .collect(),
-                depth_ops: depth_stencil.map(|_| wgpu_operations(clear_depth)),
-                stencil_ops: depth_stencil.map(|_| wgpu_operations(clear_stencil)),
-            },
+                    .unzip();
+                WGPURenderPass {
+                    color_views,
+                    resolve_targets,
+                    depth_view: depth_stencil
+                        .and_then(|view| wgpu_attachment_view(view, multiview)),
+                    color_ops: colors
+                        .iter()
+                        .map(|color| wgpu_operations(color.clear.map(Into::into)))
+                        .collect(),
+                    depth_ops: depth_stencil.map(|_| wgpu_operations(clear_depth)),
+                    stencil_ops: depth_stencil.map(|_| wgpu_operations(clear_stencil)),
+                }
+            }
         }
     }
 
@@ -432,7 +735,13 @@ impl GPUDevice<WGPU> for WGPUDevice {
         &self,
         descriptor: BindGroupLayoutDescriptor,
     ) -> WGPUBindGroupLayout {
+        if cfg!(debug_assertions) {
+            if let Err(message) = limits::validate_bind_group_layout(&descriptor) {
+                panic!("{}", message);
+            }
+        }
         WGPUBindGroupLayout {
+            dynamic_offset_count: descriptor.dynamic_offset_count(),
             layout: self
                 .device
                 .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -453,6 +762,7 @@ impl GPUDevice<WGPU> for WGPUDevice {
 
     fn create_bind_group(&self, descriptor: BindGroupDescriptor<WGPU>) -> WGPUBindGroup {
         WGPUBindGroup {
+            dynamic_offset_count: descriptor.layout.dynamic_offset_count,
             bind_group: self.device.create_bind_group(&wgpu::BindGroupDescriptor {
                 label: None,
                 layout: &descriptor.layout.layout,
@@ -468,10 +778,25 @@ impl GPUDevice<WGPU> for WGPUDevice {
         }
     }
 
+    fn create_query_set(&self, descriptor: QuerySetDescriptor) -> WGPUQuerySet {
+        assert!(
+            descriptor.ty != QueryType::Timestamp
+                || self.features.contains(WGPUFeatures::TIMESTAMP_QUERY),
+            "timestamp queries require the TIMESTAMP_QUERY feature"
+        );
+        WGPUQuerySet {
+            query_set: self.device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: None,
+                ty: descriptor.ty.into(),
+                count: descriptor.count,
+            }),
+        }
+    }
+
     fn render<'a>(&'a self, pass: &'a WGPURenderPass) -> WGPURenderPassEncoder<'a> {
         let is_default_pass = pass.color_views.is_empty();
 
-        if is_default_pass {
+        if is_default_pass && self.surface_texture.read().unwrap().texture.is_none() {
             update_surface_texture(self);
         }
 
@@ -536,6 +861,56 @@ impl GPUDevice<WGPU> for WGPUDevice {
         }
     }
 
+    fn render_bundle<'a>(
+        &'a self,
+        descriptor: RenderBundleDescriptor,
+    ) -> WGPURenderBundleEncoder<'a> {
+        let depth_stencil = |format| wgpu::RenderBundleDepthStencil {
+            format,
+            depth_read_only: false,
+            stencil_read_only: false,
+        };
+        let encoder = match descriptor {
+            RenderBundleDescriptor::Default => {
+                self.device
+                    .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                        label: None,
+                        color_formats: &[self.get_surface_format()],
+                        depth_stencil: self.surface_depth_format.map(depth_stencil),
+                        sample_count: self.surface_msaa_sample_count.max(1),
+                        multiview: None,
+                    })
+            }
+            RenderBundleDescriptor::Offscreen {
+                color_formats,
+                depth_stencil_format,
+                sample_count,
+                multiview,
+            } => {
+                assert!(
+                    multiview.is_none() || self.features.contains(WGPUFeatures::MULTIVIEW),
+                    "multiview rendering requires the MULTIVIEW feature"
+                );
+                self.device
+                    .create_render_bundle_encoder(&wgpu::RenderBundleEncoderDescriptor {
+                        label: None,
+                        color_formats: &color_formats
+                            .iter()
+                            .map(|format| (*format).into())
+                            .collect::<Vec<_>>(),
+                        depth_stencil: depth_stencil_format
+                            .map(|format| depth_stencil(format.into())),
+                        sample_count,
+                        multiview,
+                    })
+            }
+        };
+        WGPURenderBundleEncoder {
+            encoder: RwLock::new(encoder),
+            index_format: RwLock::new(wgpu::IndexFormat::Uint16),
+        }
+    }
+
     fn write_buffer(&self, buffer: &WGPUBuffer, buffer_offset: BufferSize, data: &[u8]) {
         self.queue
             .write_buffer(&buffer.buffer, buffer_offset as u64, data);
@@ -548,8 +923,10 @@ impl GPUDevice<WGPU> for WGPUDevice {
         layout: ImageDataLayout,
         size: Extent3D,
     ) {
-        self.queue
-            .write_texture(texture.into(), data, layout.into(), size.into())
+        if let Some(texture) = wgpu_image_copy_texture(texture) {
+            self.queue
+                .write_texture(texture, data, layout.into(), size.into())
+        }
     }
 
     async fn read_buffer<'a>(
@@ -595,9 +972,13 @@ impl GPUDevice<WGPU> for WGPUDevice {
         dst: ImageCopyTexture<WGPU>,
         size: Extent3D,
     ) {
-        self.get_encoder()
-            .as_mut()
-            .map(|encoder| encoder.copy_texture_to_texture(src.into(), dst.into(), size.into()));
+        if let (Some(src), Some(dst), Some(encoder)) = (
+            wgpu_image_copy_texture(src),
+            wgpu_image_copy_texture(dst),
+            self.get_encoder().as_mut(),
+        ) {
+            encoder.copy_texture_to_texture(src, dst, size.into());
+        }
     }
 
     fn copy_texture_to_buffer(
@@ -607,9 +988,13 @@ impl GPUDevice<WGPU> for WGPUDevice {
         layout: ImageDataLayout,
         size: Extent3D,
     ) {
+        let src = match wgpu_image_copy_texture(src) {
+            Some(src) => src,
+            None => return,
+        };
         self.get_encoder().as_mut().map(|encoder| {
             encoder.copy_texture_to_buffer(
-                src.into(),
+                src,
                 wgpu::ImageCopyBuffer {
                     buffer: &dst.buffer,
                     layout: layout.into(),
@@ -619,19 +1004,108 @@ impl GPUDevice<WGPU> for WGPUDevice {
         });
     }
 
+    fn resolve_query_set(
+        &self,
+        query_set: &WGPUQuerySet,
+        queries: Range<u32>,
+        dst: &WGPUBuffer,
+        dst_offset: BufferSize,
+    ) {
+        if let Some(encoder) = self.get_encoder().as_mut() {
+            encoder.resolve_query_set(
+                &query_set.query_set,
+                queries,
+                &dst.buffer,
+                dst_offset as u64,
+            );
+        }
+    }
+
+    #[inline]
+    fn timestamp_period(&self) -> f32 {
+        self.queue.get_timestamp_period()
+    }
+
+    fn supported_sample_counts(&self, format: TextureFormat) -> Vec<u32> {
+        // WebGPU only supports 4x multisampling, for formats that can be rendered to
+        let features = self.adapter.get_texture_format_features(format.into());
+        if features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+        {
+            vec![1, 4]
+        } else {
+            vec![1]
+        }
+    }
+
     #[inline]
     fn is_srgb_surface(&self) -> bool {
         self.get_surface_format().describe().srgb
     }
 
+    fn surface_texture(&self) -> Option<WGPUTexture> {
+        if self.surface_texture.read().unwrap().texture.is_none() {
+            update_surface_texture(self);
+        }
+
+        let format = mugl_surface_format(self.surface_config.read().unwrap().format)?;
+        let surface_texture = self.surface_texture.read().unwrap();
+        let texture = surface_texture.texture.as_ref()?;
+        Some(WGPUTexture {
+            texture: None,
+            view: texture
+                .texture
+                .create_view(&wgpu::TextureViewDescriptor::default()),
+            msaa_texture: None,
+            format,
+            dimension: TextureDimension::D2,
+            size: {
+                let config = self.surface_config.read().unwrap();
+                Extent3D(config.width, config.height, 1)
+            },
+            mip_level_count: 1,
+        })
+    }
+
+    fn set_log_sink(&self, sink: Option<LogSink>) {
+        let has_sink = sink.is_some();
+        *self.log_sink.write().unwrap() = sink;
+
+        if has_sink {
+            // Route validation errors to the sink instead of panicking
+            let log_sink = self.log_sink.clone();
+            self.device
+                .on_uncaptured_error(move |error| match &*log_sink.read().unwrap() {
+                    Some(sink) => sink.log(LogLevel::Error, &error.to_string()),
+                    None => panic!("wgpu error: {}", error),
+                });
+        }
+    }
+
     #[inline]
+    fn capture_frames(&self, frames: u32, callback: FrameCallback) {
+        let mut capture = self.frame_capture.lock().unwrap();
+        capture.pending.clear();
+        capture.remaining = frames;
+        capture.next_index = 0;
+        capture.callback = Some(callback);
+    }
+
     fn is_lost(&self) -> bool {
         // TODO
-        #[cfg(debug_assertions)]
-        std::dbg!("is_lost is currently unsupported by WGPU backend");
+        self.log(
+            LogLevel::Warning,
+            "is_lost is currently unsupported by WGPU backend",
+        );
         false
     }
 
+    #[inline]
+    fn is_fallback_adapter(&self) -> bool {
+        self.adapter.get_info().device_type == wgpu::DeviceType::Cpu
+    }
+
     fn flush(&self) {
         if let Ok(mut commands) = self.commands.write() {
             if commands.len() > 0 {
@@ -641,11 +1115,22 @@ impl GPUDevice<WGPU> for WGPUDevice {
     }
 
     fn present(&self) {
+        let copied = self.copy_surface_frame();
         self.flush();
 
+        // Buffers can only be mapped after the copy is submitted
+        if let Some(mut frame) = copied {
+            frame.mapped = Some(Box::pin(
+                frame.buffer.slice(..).map_async(wgpu::MapMode::Read),
+            ));
+            self.frame_capture.lock().unwrap().pending.push_back(frame);
+        }
+
         if let Some(texture) = self.surface_texture.write().unwrap().texture.take() {
             texture.present();
         }
+
+        self.deliver_captured_frames();
     }
 
     fn resize_surface(&self, size: Extent2D) {
@@ -669,6 +1154,27 @@ impl GPUDevice<WGPU> for WGPUDevice {
     }
 }
 
+impl GPUDeviceWebExt<WGPU> for WGPUDevice {
+    /// Generates mipmaps for a texture by rendering each mip level from the previous level.
+    /// Does nothing for 3D textures, or textures of formats that are not both filterable and renderable.
+    fn generate_mipmap(&self, texture: &WGPUTexture, hint: MipmapHint) {
+        let mut generator = self.mipmap_generator.lock().unwrap();
+        let generator = generator.get_or_insert_with(|| WGPUMipmapGenerator::new(&self.device));
+        if let Some(encoder) = self.get_encoder().as_mut() {
+            generator.generate(&self.device, encoder, texture, hint == MipmapHint::Nice);
+        }
+    }
+
+    fn copy_external_image_to_texture(
+        &self,
+        src: ImageCopyExternalImage<WGPU>,
+        _dst: ImageCopyTexture<WGPU>,
+        _size: Extent2D,
+    ) {
+        match *src.src {}
+    }
+}
+
 impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
     fn pipeline(&self, pipeline: &'a WGPURenderPipeline) {
         if let Ok(mut lock) = self.pass.write() {
@@ -696,6 +1202,13 @@ impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
     }
 
     fn bind_group(&self, slot: u32, bind_group: &'a WGPUBindGroup, offsets: &[u32]) {
+        if cfg!(debug_assertions) {
+            if let Err(message) =
+                limits::validate_dynamic_offsets(slot, bind_group.dynamic_offset_count, offsets)
+            {
+                panic!("{}", message);
+            }
+        }
         if let Ok(mut lock) = self.pass.write() {
             if let Some(pass) = lock.as_mut() {
                 pass.set_bind_group(
@@ -723,7 +1236,28 @@ impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
         }
     }
 
+    fn draw_indirect(&self, buffer: &'a WGPUBuffer, offset: BufferSize) {
+        if let Ok(mut lock) = self.pass.write() {
+            if let Some(pass) = lock.as_mut() {
+                pass.draw_indirect(&buffer.buffer, offset as u64);
+            }
+        }
+    }
+
+    fn draw_indexed_indirect(&self, buffer: &'a WGPUBuffer, offset: BufferSize) {
+        if let Ok(mut lock) = self.pass.write() {
+            if let Some(pass) = lock.as_mut() {
+                pass.draw_indexed_indirect(&buffer.buffer, offset as u64);
+            }
+        }
+    }
+
     fn viewport(&self, x: f32, y: f32, width: f32, height: f32, min_depth: f32, max_depth: f32) {
+        if cfg!(debug_assertions) {
+            if let Err(message) = limits::validate_viewport_depth(min_depth, max_depth) {
+                panic!("{}", message);
+            }
+        }
         if let Ok(mut lock) = self.pass.write() {
             if let Some(pass) = lock.as_mut() {
                 pass.set_viewport(x, y, width, height, min_depth, max_depth);
@@ -747,6 +1281,24 @@ impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
         }
     }
 
+    fn begin_occlusion_query(&self, _query_set: &'a WGPUQuerySet, _index: u32) {
+        // TODO: wgpu 0.12 does not support occlusion queries in render passes
+        self.device.log(
+            LogLevel::Warning,
+            "occlusion queries are currently unsupported by WGPU backend",
+        );
+    }
+
+    fn end_occlusion_query(&self) {}
+
+    fn write_timestamp(&self, query_set: &'a WGPUQuerySet, index: u32) {
+        if let Ok(mut lock) = self.pass.write() {
+            if let Some(pass) = lock.as_mut() {
+                pass.write_timestamp(&query_set.query_set, index);
+            }
+        }
+    }
+
     fn stencil_ref(&self, reference: u32) {
         if let Ok(mut lock) = self.pass.write() {
             if let Some(pass) = lock.as_mut() {
@@ -755,6 +1307,14 @@ impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
         }
     }
 
+    fn execute_bundles(&self, bundles: &[&'a WGPURenderBundle]) {
+        if let Ok(mut lock) = self.pass.write() {
+            if let Some(pass) = lock.as_mut() {
+                pass.execute_bundles(bundles.iter().map(|bundle| &bundle.bundle));
+            }
+        }
+    }
+
     fn submit(self) {
         {
             // Drops the render pass before consuming encoder
@@ -765,6 +1325,94 @@ impl<'a> GPURenderPassEncoder<'a, WGPU> for WGPURenderPassEncoder<'a> {
     }
 }
 
+impl<'a> GPURenderBundleEncoder<'a, WGPU> for WGPURenderBundleEncoder<'a> {
+    fn pipeline(&self, pipeline: &'a WGPURenderPipeline) {
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.set_pipeline(&pipeline.pipeline);
+            *self.index_format.write().unwrap() = pipeline.index_format;
+        }
+    }
+
+    fn index(&self, buffer: &'a WGPUBuffer) {
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.set_index_buffer(buffer.buffer.slice(..), *self.index_format.read().unwrap());
+        }
+    }
+
+    fn vertex(&self, slot: u32, buffer: &'a WGPUBuffer, offset: BufferSize) {
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.set_vertex_buffer(slot, buffer.buffer.slice((offset as u64)..));
+        }
+    }
+
+    fn bind_group(&self, slot: u32, bind_group: &'a WGPUBindGroup, offsets: &[u32]) {
+        if cfg!(debug_assertions) {
+            if let Err(message) =
+                limits::validate_dynamic_offsets(slot, bind_group.dynamic_offset_count, offsets)
+            {
+                panic!("{}", message);
+            }
+        }
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.set_bind_group(slot, &bind_group.bind_group, offsets);
+        }
+    }
+
+    fn draw(&self, vertices: Range<u32>, instances: Range<u32>) {
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.draw(vertices, instances);
+        }
+    }
+
+    fn draw_indexed(&self, indices: Range<u32>, instances: Range<u32>) {
+        if let Ok(mut encoder) = self.encoder.write() {
+            encoder.draw_indexed(indices, 0, instances);
+        }
+    }
+
+    fn finish(self) -> WGPURenderBundle {
+        WGPURenderBundle {
+            bundle: self
+                .encoder
+                .into_inner()
+                .unwrap()
+                .finish(&wgpu::RenderBundleDescriptor { label: None }),
+        }
+    }
+}
+
+/// Reads a mapped frame buffer as tightly packed RGBA pixels.
+fn read_frame(frame: &WGPUPendingFrame) -> Vec<u8> {
+    let row_size = (frame.size.0 * 4) as usize;
+    let mut data = Vec::with_capacity(row_size * frame.size.1 as usize);
+    {
+        let mapped = frame.buffer.slice(..).get_mapped_range();
+        for row in mapped.chunks(frame.bytes_per_row as usize) {
+            data.extend_from_slice(&row[..row_size]);
+        }
+    }
+    frame.buffer.unmap();
+
+    if frame.bgra {
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    data
+}
+
+/// Returns a waker that does nothing, for polling buffer mappings without blocking.
+fn noop_waker() -> Waker {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    // Safety: the vtable functions do nothing with the data pointer
+    unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+}
+
 fn update_surface_texture(device: &WGPUDevice) {
     match device.surface.get_current_texture() {
         Ok(surface_texture) => {
@@ -830,3 +1478,26 @@ fn create_surface_texture(
         format,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_capture_requested_before_first_present() {
+        let mut capture = WGPUFrameCapture {
+            remaining: 2,
+            callback: Some(FrameCallback::new(|_| {})),
+            ..Default::default()
+        };
+
+        // The first present cannot copy from the surface yet, so no frame is pending
+        capture.release_if_done();
+        assert!(capture.callback.is_some());
+
+        // The callback is released once all frames are copied and delivered
+        capture.remaining = 0;
+        capture.release_if_done();
+        assert!(capture.callback.is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Mipmap generation for the WGPU backend, which lacks a native equivalent of WebGL `generateMipmap`.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+use super::resource::WGPUTexture;
+use crate::primitive::TextureDimension;
+
+/// Shader drawing a fullscreen triangle that samples the previous mip level.
+const BLIT_SHADER: &str = r#"
+struct VertexOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    out.uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.position = vec4<f32>(out.uv.x * 2.0 - 1.0, 1.0 - out.uv.y * 2.0, 0.0, 1.0);
+    return out;
+}
+
+[[group(0), binding(0)]]
+var src: texture_2d<f32>;
+[[group(0), binding(1)]]
+var src_sampler: sampler;
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return textureSample(src, src_sampler, in.uv);
+}
+"#;
+
+/// Generates mipmaps by rendering each mip level from the previous one, with a blit pipeline per texture format.
+#[derive(Debug)]
+pub(super) struct WGPUMipmapGenerator {
+    shader: wgpu::ShaderModule,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    /// Bilinear sampler, which averages 2x2 texels of the previous level.
+    linear_sampler: wgpu::Sampler,
+    /// Nearest sampler, which picks 1 texel of the previous level.
+    nearest_sampler: wgpu::Sampler,
+    pipelines: Vec<(wgpu::TextureFormat, wgpu::RenderPipeline)>,
+}
+
+impl WGPUMipmapGenerator {
+    pub(super) fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("mipmap"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = |filter| {
+            device.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some("mipmap"),
+                mag_filter: filter,
+                min_filter: filter,
+                ..Default::default()
+            })
+        };
+
+        Self {
+            shader: device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                label: Some("mipmap"),
+                source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(BLIT_SHADER)),
+            }),
+            pipeline_layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("mipmap"),
+                bind_group_layouts: &[&bind_group_layout],
+                push_constant_ranges: &[],
+            }),
+            bind_group_layout,
+            linear_sampler: sampler(wgpu::FilterMode::Linear),
+            nearest_sampler: sampler(wgpu::FilterMode::Nearest),
+            pipelines: Vec::new(),
+        }
+    }
+
+    /// Returns if mipmaps can be generated for a texture format, i.e. the format is filterable and renderable.
+    pub(super) fn supports(format: wgpu::TextureFormat) -> bool {
+        let info = format.describe();
+        info.sample_type == wgpu::TextureSampleType::Float { filterable: true }
+            && info
+                .guaranteed_format_features
+                .allowed_usages
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+    }
+
+    /// Encodes the generation of all mip levels of every layer of a texture from its base level.
+    /// With `linear` filter, each texel is the average of 2x2 texels of the previous level,
+    /// otherwise the nearest texel is picked.
+    /// Does nothing for 3D textures, and textures of unsupported formats.
+    pub(super) fn generate(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &WGPUTexture,
+        linear: bool,
+    ) {
+        let format = texture.format.into();
+        let wgpu_texture = match &texture.texture {
+            Some(wgpu_texture)
+                if texture.mip_level_count > 1
+                    && texture.dimension != TextureDimension::D3
+                    && Self::supports(format) =>
+            {
+                wgpu_texture
+            }
+            _ => return,
+        };
+
+        let pipeline = self.pipeline(device, format);
+        let sampler = if linear {
+            &self.linear_sampler
+        } else {
+            &self.nearest_sampler
+        };
+
+        for layer in 0..texture.size.2 {
+            let views = (0..texture.mip_level_count)
+                .map(|mip_level| {
+                    wgpu_texture.create_view(&wgpu::TextureViewDescriptor {
+                        label: Some("mipmap"),
+                        dimension: Some(wgpu::TextureViewDimension::D2),
+                        base_mip_level: mip_level,
+                        mip_level_count: NonZeroU32::new(1),
+                        base_array_layer: layer,
+                        array_layer_count: NonZeroU32::new(1),
+                        ..Default::default()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for (src, dst) in views.iter().zip(&views[1..]) {
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("mipmap"),
+                    layout: &self.bind_group_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(src),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(sampler),
+                        },
+                    ],
+                });
+
+                let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("mipmap"),
+                    color_attachments: &[wgpu::RenderPassColorAttachment {
+                        view: dst,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                            store: true,
+                        },
+                    }],
+                    depth_stencil_attachment: None,
+                });
+                pass.set_pipeline(&self.pipelines[pipeline].1);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.draw(0..3, 0..1);
+            }
+        }
+    }
+
+    /// Gets the index of the blit pipeline for a format, creating it on first use.
+    fn pipeline(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat) -> usize {
+        if let Some(index) = self.pipelines.iter().position(|(f, _)| *f == format) {
+            return index;
+        }
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("mipmap"),
+            layout: Some(&self.pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &self.shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shader,
+                entry_point: "fs_main",
+                targets: &[format.into()],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+        self.pipelines.push((format, pipeline));
+        self.pipelines.len() - 1
+    }
+}
@@ -2,6 +2,7 @@
 
 mod conv;
 mod gpu;
+mod mipmap;
 mod resource;
 
 pub use gpu::*;
@@ -2,12 +2,25 @@ use alloc::vec::Vec;
 use bitflags::bitflags;
 use core::ops::Deref;
 
-use crate::primitive::{Extent2D, PowerPreference, TextureDimension, TextureFormat};
+use crate::primitive::{Extent2D, Extent3D, PowerPreference, TextureDimension, TextureFormat};
 
 bitflags! {
     /// WebGPU features.
     #[repr(transparent)]
+    #[derive(Default)]
     pub struct WGPUFeatures: u32 {
+        /// Render to multiple texture array layers in a single pass.
+        const MULTIVIEW = 0x0001;
+        /// Timestamp queries.
+        const TIMESTAMP_QUERY = 0x0002;
+        /// Non-zero first instance in indirect draws.
+        const INDIRECT_FIRST_INSTANCE = 0x0004;
+        /// BC compressed texture formats.
+        const TEXTURE_COMPRESSION_BC = 0x0008;
+        /// ETC2 compressed texture formats.
+        const TEXTURE_COMPRESSION_ETC2 = 0x0010;
+        /// ASTC LDR compressed texture formats.
+        const TEXTURE_COMPRESSION_ASTC = 0x0020;
     }
 }
 
@@ -15,7 +28,10 @@ bitflags! {
 #[derive(Clone, Copy, Debug, Default)]
 pub struct WGPUDeviceDescriptor {
     pub power_preference: PowerPreference,
+    /// Forces a fallback (software) adapter. See [crate::gpu::GPUDevice::is_fallback_adapter].
     pub force_fallback_adapter: bool,
+    /// Features to enable. Features unsupported by the adapter are ignored.
+    pub features: WGPUFeatures,
 }
 
 /// WebGPU surface descriptor.
@@ -70,13 +86,22 @@ impl<'a> Drop for WGPUBufferView<'a> {
 /// WebGPU texture.
 #[derive(Debug)]
 pub struct WGPUTexture {
-    pub(super) texture: wgpu::Texture,
+    /// The texture, or None for the surface texture of current frame.
+    pub(super) texture: Option<wgpu::Texture>,
     pub(super) view: wgpu::TextureView,
     pub(super) msaa_texture: Option<wgpu::Texture>,
     pub(super) format: TextureFormat,
     pub(super) dimension: TextureDimension,
+    pub(super) size: Extent3D,
+    pub(super) mip_level_count: u32,
 }
 
+/// External image source of the WGPU backend.
+/// External images only exist on the Web, so this type has no values. Upload image data with
+/// [GPUDevice::write_texture](crate::gpu::GPUDevice::write_texture) instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WGPUImageSource {}
+
 /// WebGPU sampler.
 #[derive(Debug)]
 pub struct WGPUSampler {
@@ -107,14 +132,34 @@ pub struct WGPURenderPass {
     pub(super) stencil_ops: Option<wgpu::Operations<u32>>,
 }
 
+/// WebGPU render bundle.
+#[derive(Debug)]
+pub struct WGPURenderBundle {
+    pub(super) bundle: wgpu::RenderBundle,
+}
+
 /// WebGPU bind group.
 #[derive(Debug)]
 pub struct WGPUBindGroup {
     pub(super) bind_group: wgpu::BindGroup,
+    pub(super) dynamic_offset_count: u32,
+}
+
+/// WebGPU query set.
+pub struct WGPUQuerySet {
+    pub(super) query_set: wgpu::QuerySet,
+}
+
+impl core::fmt::Debug for WGPUQuerySet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // wgpu::QuerySet does not implement Debug
+        f.write_str("WGPUQuerySet")
+    }
 }
 
 /// WebGPU bind group layout.
 #[derive(Debug)]
 pub struct WGPUBindGroupLayout {
     pub(super) layout: wgpu::BindGroupLayout,
+    pub(super) dynamic_offset_count: u32,
 }
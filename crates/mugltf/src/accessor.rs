@@ -0,0 +1,600 @@
+//! Accessor data readers.
+
+use crate::{
+    model::{
+        Accessor, AccessorComponentType, AccessorIndicesComponentType, AccessorType, Float, Gltf,
+        Id,
+    },
+    GltfAsset,
+};
+use alloc::vec::Vec;
+use core::{marker::PhantomData, mem, ptr, slice};
+
+/// Element data used for accessors without buffer view. The largest element is a MAT4 of floats.
+const ZEROS: [u8; 64] = [0; 64];
+
+impl Accessor {
+    /// Decodes the elements of this accessor into arrays of `N` floats,
+    /// where `N` must be the number of components of the accessor type.
+    /// Normalized integer components are converted into the [0, 1] or [-1, 1] range.
+    ///
+    /// Sparse values are substituted over the base buffer view, or over zeros if the accessor has no buffer view.
+    /// Returns `None` if `N` does not match the accessor type, or the accessor data cannot be resolved.
+    pub fn decode<'a, const N: usize>(
+        &self,
+        gltf: &Gltf,
+        buffers: &'a [Vec<u8>],
+    ) -> Option<AccessorIter<'a, N>> {
+        if self.ty.components() != N {
+            return None;
+        }
+        Some(AccessorIter {
+            elements: AccessorElements::new(gltf, buffers, self)?,
+            component_type: self.component_type,
+            normalized: self.normalized,
+            index: 0,
+            count: self.count,
+        })
+    }
+}
+
+impl Accessor {
+    /// Returns a typed view of the elements of this accessor, without converting components.
+    /// Normalized integer components are kept as raw integers.
+    ///
+    /// Returns `None` if `T` does not match the type and component type of the accessor,
+    /// or the accessor data cannot be resolved.
+    pub fn view<'a, T: AccessorElement>(
+        &self,
+        gltf: &Gltf,
+        buffers: &'a [Vec<u8>],
+    ) -> Option<AccessorView<'a, T>> {
+        if self.ty as u32 != T::TYPE as u32
+            || self.component_type as u32 != T::Component::COMPONENT_TYPE as u32
+        {
+            return None;
+        }
+        Some(AccessorView {
+            elements: AccessorElements::new(gltf, buffers, self)?,
+            count: self.count,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Decodes the elements of an accessor into arrays of `N` floats, applying sparse substitution.
+    /// See [Accessor::decode].
+    ///
+    /// Returns `None` if the accessor does not exist, `N` does not match the accessor type,
+    /// or the accessor data cannot be resolved.
+    pub fn read_accessor<const N: usize>(&self, accessor: Id) -> Option<AccessorIter<'_, N>> {
+        self.gltf
+            .accessors
+            .get(accessor)?
+            .decode(&self.gltf, &self.buffers)
+    }
+
+    /// Returns a typed view of the elements of an accessor. See [Accessor::view].
+    ///
+    /// Returns `None` if the accessor does not exist, `T` does not match the accessor type,
+    /// or the accessor data cannot be resolved.
+    pub fn view_accessor<T: AccessorElement>(&self, accessor: Id) -> Option<AccessorView<'_, T>> {
+        self.gltf
+            .accessors
+            .get(accessor)?
+            .view(&self.gltf, &self.buffers)
+    }
+}
+
+/// Iterator over the decoded elements of an accessor. See [Accessor::decode].
+#[derive(Clone, Debug)]
+pub struct AccessorIter<'a, const N: usize> {
+    elements: AccessorElements<'a>,
+    component_type: AccessorComponentType,
+    normalized: bool,
+    index: usize,
+    count: usize,
+}
+
+impl<'a, const N: usize> Iterator for AccessorIter<'a, N> {
+    type Item = [Float; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let bytes = self.elements.get(self.index);
+        self.index += 1;
+
+        let component_size = self.component_type.size();
+        let mut element = [0.; N];
+        for (c, value) in element.iter_mut().enumerate() {
+            *value = read_component(
+                &bytes[(c * component_size)..((c + 1) * component_size)],
+                self.component_type,
+                self.normalized,
+            );
+        }
+        Some(element)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.count - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, const N: usize> ExactSizeIterator for AccessorIter<'a, N> {}
+
+/// A component type of accessors, which can be read directly from little-endian buffer bytes.
+///
+/// # Safety
+/// Implementors must be plain old data of [AccessorComponentType::size] bytes, valid for any bit pattern.
+pub unsafe trait AccessorComponent: Copy + 'static {
+    /// The matching component type.
+    const COMPONENT_TYPE: AccessorComponentType;
+
+    /// Converts a value read from little-endian bytes into the native byte order.
+    fn le_to_ne(self) -> Self;
+}
+
+/// An element type of accessors, which can be read directly from buffer bytes,
+/// e.g. `[f32; 3]` for VEC3 float accessors.
+///
+/// # Safety
+/// Implementors must consist of exactly [AccessorType::components] values of [AccessorElement::Component],
+/// without padding.
+pub unsafe trait AccessorElement: Copy + 'static {
+    /// The component type.
+    type Component: AccessorComponent;
+
+    /// The matching accessor type.
+    const TYPE: AccessorType;
+}
+
+macro_rules! impl_accessor_component {
+    ($($ty:ty => $component_type:ident),*) => {
+        $(
+            unsafe impl AccessorComponent for $ty {
+                const COMPONENT_TYPE: AccessorComponentType = AccessorComponentType::$component_type;
+
+                #[inline]
+                fn le_to_ne(self) -> Self {
+                    <$ty>::from_le(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_accessor_element {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl AccessorElement for $ty {
+                type Component = $ty;
+                const TYPE: AccessorType = AccessorType::Scalar;
+            }
+
+            unsafe impl AccessorElement for [$ty; 2] {
+                type Component = $ty;
+                const TYPE: AccessorType = AccessorType::Vec2;
+            }
+
+            unsafe impl AccessorElement for [$ty; 3] {
+                type Component = $ty;
+                const TYPE: AccessorType = AccessorType::Vec3;
+            }
+
+            unsafe impl AccessorElement for [$ty; 4] {
+                type Component = $ty;
+                const TYPE: AccessorType = AccessorType::Vec4;
+            }
+        )*
+    };
+}
+
+impl_accessor_component!(
+    i8 => Byte,
+    u8 => UnsignedByte,
+    i16 => Short,
+    u16 => UnsignedShort,
+    u32 => UnsignedInt
+);
+
+unsafe impl AccessorComponent for f32 {
+    const COMPONENT_TYPE: AccessorComponentType = AccessorComponentType::Float;
+
+    #[inline]
+    fn le_to_ne(self) -> Self {
+        f32::from_bits(u32::from_le(self.to_bits()))
+    }
+}
+
+impl_accessor_element!(i8, u8, i16, u16, u32, f32);
+
+// Matrices of smaller components have padded columns, so only float matrices are supported.
+
+unsafe impl AccessorElement for [[f32; 2]; 2] {
+    type Component = f32;
+    const TYPE: AccessorType = AccessorType::Mat2;
+}
+
+unsafe impl AccessorElement for [[f32; 3]; 3] {
+    type Component = f32;
+    const TYPE: AccessorType = AccessorType::Mat3;
+}
+
+unsafe impl AccessorElement for [[f32; 4]; 4] {
+    type Component = f32;
+    const TYPE: AccessorType = AccessorType::Mat4;
+}
+
+/// Typed view of the elements of an accessor. See [Accessor::view].
+///
+/// Tightly packed accessors can be borrowed as a slice with [AccessorView::as_slice], without copying.
+/// Other accessors, i.e. interleaved, sparse or unaligned ones, are read element by element with [AccessorView::iter].
+#[derive(Clone, Debug)]
+pub struct AccessorView<'a, T> {
+    elements: AccessorElements<'a>,
+    count: usize,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: AccessorElement> AccessorView<'a, T> {
+    /// Returns the number of elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the accessor has no element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Borrows the elements as a slice of the buffer.
+    /// Returns `None` if the elements are not tightly packed or not aligned for `T`,
+    /// the accessor is sparse or has no buffer view, or the target is not little-endian.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        if cfg!(target_endian = "big") || self.elements.sparse.is_some() {
+            return None;
+        }
+        let (data, start, stride) = self.elements.base?;
+        if stride != mem::size_of::<T>() {
+            return None;
+        }
+        let data = data.get(start..start.checked_add(stride.checked_mul(self.count)?)?)?;
+        if data.as_ptr() as usize % mem::align_of::<T>() != 0 {
+            return None;
+        }
+        // Safety: T is plain old data of the element size, and the bytes are in bounds and aligned
+        Some(unsafe { slice::from_raw_parts(data.as_ptr() as *const T, self.count) })
+    }
+
+    /// Returns an iterator over copies of the elements.
+    #[inline]
+    pub fn iter(&self) -> AccessorViewIter<'a, T> {
+        AccessorViewIter {
+            elements: self.elements.clone(),
+            index: 0,
+            count: self.count,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: AccessorElement> IntoIterator for &AccessorView<'a, T> {
+    type Item = T;
+    type IntoIter = AccessorViewIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of an [AccessorView].
+#[derive(Clone, Debug)]
+pub struct AccessorViewIter<'a, T> {
+    elements: AccessorElements<'a>,
+    index: usize,
+    count: usize,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: AccessorElement> Iterator for AccessorViewIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let bytes = self.elements.get(self.index);
+        self.index += 1;
+
+        debug_assert_eq!(bytes.len(), mem::size_of::<T>());
+        // Safety: T is plain old data of the element size
+        let mut element = unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) };
+        let components = unsafe {
+            slice::from_raw_parts_mut(
+                &mut element as *mut T as *mut T::Component,
+                T::TYPE.components(),
+            )
+        };
+        for component in components {
+            *component = component.le_to_ne();
+        }
+        Some(element)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.count - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: AccessorElement> ExactSizeIterator for AccessorViewIter<'a, T> {}
+
+/// Reads the elements of an accessor as a flat list of floats.
+/// Normalized integer components are converted into the [0, 1] or [-1, 1] range.
+/// Returns `None` if the accessor data cannot be resolved.
+#[cfg(any(feature = "std", feature = "libm"))]
+pub(crate) fn read_accessor_floats(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    accessor: &Accessor,
+) -> Option<Vec<Float>> {
+    let mut elements = AccessorElements::new(gltf, buffers, accessor)?;
+    let components = accessor.ty.components();
+    let component_size = accessor.component_type.size();
+
+    // Not preallocated, as the count of accessors without buffer view is not bounded by any data
+    let mut values = Vec::new();
+    for i in 0..accessor.count {
+        let bytes = elements.get(i);
+        for c in 0..components {
+            let offset = c * component_size;
+            values.push(read_component(
+                &bytes[offset..(offset + component_size)],
+                accessor.component_type,
+                accessor.normalized,
+            ));
+        }
+    }
+    Some(values)
+}
+
+/// Reads the elements of a scalar unsigned integer accessor, e.g. vertex indices.
+/// Returns `None` if the accessor data cannot be resolved or is not of an unsigned integer type.
+#[cfg(any(feature = "std", feature = "libm", feature = "render"))]
+pub(crate) fn read_accessor_indices(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    accessor: &Accessor,
+) -> Option<Vec<u32>> {
+    if !matches!(accessor.ty, AccessorType::Scalar) {
+        return None;
+    }
+    let mut elements = AccessorElements::new(gltf, buffers, accessor)?;
+
+    // Not preallocated, as the count of accessors without buffer view is not bounded by any data
+    let mut values = Vec::new();
+    for i in 0..accessor.count {
+        let bytes = elements.get(i);
+        values.push(match accessor.component_type {
+            AccessorComponentType::UnsignedByte => bytes[0] as u32,
+            AccessorComponentType::UnsignedShort => u16::from_le_bytes([bytes[0], bytes[1]]) as u32,
+            AccessorComponentType::UnsignedInt => {
+                u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+            }
+            _ => return None,
+        });
+    }
+    Some(values)
+}
+
+/// Resolved element data of an accessor, with bounds already validated.
+#[derive(Clone, Debug)]
+struct AccessorElements<'a> {
+    /// Buffer data, byte offset of the first element and stride. `None` if the accessor has no buffer view.
+    base: Option<(&'a [u8], usize, usize)>,
+    sparse: Option<SparseElements<'a>>,
+    element_size: usize,
+}
+
+/// Sparse substitution data of an accessor.
+#[derive(Clone, Debug)]
+struct SparseElements<'a> {
+    indices: &'a [u8],
+    index_type: AccessorIndicesComponentType,
+    values: &'a [u8],
+    count: usize,
+    /// Position of the next sparse index to look at. Sparse indices are strictly increasing.
+    next: usize,
+}
+
+impl<'a> AccessorElements<'a> {
+    /// Resolves the buffer data of an accessor.
+    /// Returns `None` if any data is out of bounds.
+    fn new(gltf: &Gltf, buffers: &'a [Vec<u8>], accessor: &Accessor) -> Option<Self> {
+        let element_size = accessor.ty.components() * accessor.component_type.size();
+
+        let base = match accessor.buffer_view {
+            Some(buffer_view) => {
+                let buffer_view = gltf.buffer_views.get(buffer_view)?;
+                let stride = if buffer_view.byte_stride > 0 {
+                    buffer_view.byte_stride
+                } else {
+                    element_size
+                };
+                let start = buffer_view.byte_offset.checked_add(accessor.byte_offset)?;
+                let end = buffer_view
+                    .byte_offset
+                    .checked_add(buffer_view.byte_length)?;
+                let data: &[u8] = buffers.get(buffer_view.buffer)?;
+                if accessor.count > 0 {
+                    let last = stride
+                        .checked_mul(accessor.count - 1)
+                        .and_then(|offset| offset.checked_add(start))
+                        .and_then(|offset| offset.checked_add(element_size))?;
+                    if last > end.min(data.len()) {
+                        return None;
+                    }
+                }
+                Some((data, start, stride))
+            }
+            None => None,
+        };
+
+        let sparse = match &accessor.sparse {
+            Some(sparse) => {
+                let index_size = sparse.indices.component_type.size();
+                Some(SparseElements {
+                    indices: sparse_data(
+                        gltf,
+                        buffers,
+                        sparse.indices.buffer_view,
+                        sparse.indices.byte_offset,
+                        index_size.checked_mul(sparse.count)?,
+                    )?,
+                    index_type: sparse.indices.component_type,
+                    values: sparse_data(
+                        gltf,
+                        buffers,
+                        sparse.values.buffer_view,
+                        sparse.values.byte_offset,
+                        element_size.checked_mul(sparse.count)?,
+                    )?,
+                    count: sparse.count,
+                    next: 0,
+                })
+            }
+            None => None,
+        };
+
+        Some(Self {
+            base,
+            sparse,
+            element_size,
+        })
+    }
+
+    /// Returns the bytes of the element at given index.
+    /// Accessing elements in increasing order is the most efficient for sparse accessors.
+    fn get(&mut self, index: usize) -> &'a [u8] {
+        if let Some(sparse) = &mut self.sparse {
+            if let Some(i) = sparse.find(index) {
+                let offset = i * self.element_size;
+                return &sparse.values[offset..(offset + self.element_size)];
+            }
+        }
+        match self.base {
+            Some((data, start, stride)) => {
+                let offset = start + stride * index;
+                &data[offset..(offset + self.element_size)]
+            }
+            None => &ZEROS[..self.element_size],
+        }
+    }
+}
+
+impl<'a> SparseElements<'a> {
+    /// Finds the position of an element index within the sparse indices.
+    fn find(&mut self, index: usize) -> Option<usize> {
+        if self.next > 0 && self.index(self.next - 1) >= index {
+            // Restart on backward access
+            self.next = 0;
+        }
+        while self.next < self.count {
+            let sparse_index = self.index(self.next);
+            if sparse_index > index {
+                return None;
+            }
+            self.next += 1;
+            if sparse_index == index {
+                return Some(self.next - 1);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn index(&self, i: usize) -> usize {
+        let bytes = &self.indices[(i * self.index_type.size())..];
+        match self.index_type {
+            AccessorIndicesComponentType::UnsignedByte => bytes[0] as usize,
+            AccessorIndicesComponentType::UnsignedShort => {
+                u16::from_le_bytes([bytes[0], bytes[1]]) as usize
+            }
+            AccessorIndicesComponentType::UnsignedInt => {
+                u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+            }
+        }
+    }
+}
+
+/// Returns `length` bytes of tightly packed sparse data at given offset of a buffer view.
+fn sparse_data<'a>(
+    gltf: &Gltf,
+    buffers: &'a [Vec<u8>],
+    buffer_view: Id,
+    byte_offset: usize,
+    length: usize,
+) -> Option<&'a [u8]> {
+    let buffer_view = gltf.buffer_views.get(buffer_view)?;
+    if byte_offset.checked_add(length)? > buffer_view.byte_length {
+        return None;
+    }
+    let start = buffer_view.byte_offset.checked_add(byte_offset)?;
+    buffers
+        .get(buffer_view.buffer)?
+        .get(start..start.checked_add(length)?)
+}
+
+/// Reads a single little-endian component as float.
+fn read_component(bytes: &[u8], component_type: AccessorComponentType, normalized: bool) -> Float {
+    match component_type {
+        AccessorComponentType::Byte => {
+            let value = bytes[0] as i8 as Float;
+            if normalized {
+                (value / 127.).max(-1.)
+            } else {
+                value
+            }
+        }
+        AccessorComponentType::UnsignedByte => {
+            let value = bytes[0] as Float;
+            if normalized {
+                value / 255.
+            } else {
+                value
+            }
+        }
+        AccessorComponentType::Short => {
+            let value = i16::from_le_bytes([bytes[0], bytes[1]]) as Float;
+            if normalized {
+                (value / 32767.).max(-1.)
+            } else {
+                value
+            }
+        }
+        AccessorComponentType::UnsignedShort => {
+            let value = u16::from_le_bytes([bytes[0], bytes[1]]) as Float;
+            if normalized {
+                value / 65535.
+            } else {
+                value
+            }
+        }
+        AccessorComponentType::UnsignedInt => {
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as Float
+        }
+        AccessorComponentType::Float => {
+            Float::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    }
+}
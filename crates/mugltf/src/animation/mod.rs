@@ -0,0 +1,9 @@
+//! glTF animation utilities.
+
+mod player;
+mod reduce;
+mod sample;
+
+pub use player::*;
+pub use reduce::*;
+pub use sample::*;
@@ -0,0 +1,320 @@
+//! Animation playback and blending.
+
+use super::{AnimationClip, AnimationSample, AnimationValue};
+use crate::model::{Float, Id};
+use alloc::{collections::BTreeMap, vec::Vec};
+use munum::Quaternion;
+
+/// Animated properties of a node. Properties not targeted by any channel are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodePose {
+    /// Translation of the node.
+    pub translation: Option<[Float; 3]>,
+    /// Unit quaternion rotation of the node, in (x, y, z, w) order.
+    pub rotation: Option<[Float; 4]>,
+    /// Scale of the node.
+    pub scale: Option<[Float; 3]>,
+    /// Morph target weights of the node's mesh.
+    pub weights: Option<Vec<Float>>,
+}
+
+/// Animated properties of every node targeted by an animation, keyed by node ID.
+pub type Pose = BTreeMap<Id, NodePose>;
+
+impl NodePose {
+    /// Sets the property targeted by an animation value.
+    pub fn set(&mut self, value: AnimationValue) {
+        match value {
+            AnimationValue::Translation(translation) => self.translation = Some(translation),
+            AnimationValue::Rotation(rotation) => self.rotation = Some(rotation),
+            AnimationValue::Scale(scale) => self.scale = Some(scale),
+            AnimationValue::Weights(weights) => self.weights = Some(weights),
+        }
+    }
+
+    /// Blends this pose toward another by given weight in [0, 1].
+    /// Translations, scales and weights are interpolated linearly, and rotations using slerp.
+    /// Properties set in only one of the poses are kept as is.
+    ///
+    /// # Examples
+    /// ```
+    /// # use mugltf::animation::NodePose;
+    /// let from = NodePose { translation: Some([0., 0., 0.]), scale: Some([1., 1., 1.]), ..Default::default() };
+    /// let to = NodePose { translation: Some([2., 4., 0.]), ..Default::default() };
+    /// let pose = from.blend(&to, 0.5);
+    /// assert_eq!(pose.translation, Some([1., 2., 0.]));
+    /// assert_eq!(pose.scale, Some([1., 1., 1.]));
+    /// ```
+    pub fn blend(&self, other: &NodePose, weight: Float) -> NodePose {
+        NodePose {
+            translation: blend_option(&self.translation, &other.translation, |a, b| {
+                lerp_array(a, b, weight)
+            }),
+            rotation: blend_option(&self.rotation, &other.rotation, |a, b| {
+                Quaternion::from_slice(a)
+                    .slerp(Quaternion::from_slice(b), weight)
+                    .into()
+            }),
+            scale: blend_option(&self.scale, &other.scale, |a, b| lerp_array(a, b, weight)),
+            weights: blend_option(&self.weights, &other.weights, |a, b| {
+                // Missing weights of the shorter list are treated as 0
+                (0..a.len().max(b.len()))
+                    .map(|i| {
+                        let (a, b) = (
+                            a.get(i).copied().unwrap_or(0.),
+                            b.get(i).copied().unwrap_or(0.),
+                        );
+                        a + (b - a) * weight
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Blends a pose toward another by given weight in [0, 1]. See [NodePose::blend].
+/// Nodes in only one of the poses are kept as is.
+pub fn blend_poses(from: &Pose, to: &Pose, weight: Float) -> Pose {
+    let mut pose = from.clone();
+    for (node, to_pose) in to {
+        let blended = match pose.get(node) {
+            Some(from_pose) => from_pose.blend(to_pose, weight),
+            None => to_pose.clone(),
+        };
+        pose.insert(*node, blended);
+    }
+    pose
+}
+
+/// Playback state of an [AnimationClip].
+#[derive(Clone, Debug)]
+struct Playback {
+    clip: AnimationClip,
+    time: Float,
+}
+
+/// Plays [AnimationClip]s, managing the playback time, speed and looping,
+/// and cross-fading from the previous clip when switching clips.
+///
+/// # Examples
+/// ```
+/// # use mugltf::animation::{AnimationClip, AnimationPlayer};
+/// # let (walk, run) = (AnimationClip::default(), AnimationClip::default());
+/// let mut player = AnimationPlayer::new();
+/// player.set_looping(true);
+/// player.play(walk);
+/// player.update(0.5);
+///
+/// // Blend into the run cycle over 0.25s
+/// player.cross_fade(run, 0.25);
+/// player.update(0.1);
+/// let pose = player.pose();
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnimationPlayer {
+    current: Option<Playback>,
+    /// Clip faded out by a cross-fade.
+    previous: Option<Playback>,
+    fade_duration: Float,
+    fade_time: Float,
+    speed: Float,
+    looping: bool,
+}
+
+impl AnimationPlayer {
+    /// Creates a player with no clip, playing at normal speed without looping.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            previous: None,
+            fade_duration: 0.,
+            fade_time: 0.,
+            speed: 1.,
+            looping: false,
+        }
+    }
+
+    /// Gets the current clip.
+    #[inline]
+    pub fn clip(&self) -> Option<&AnimationClip> {
+        self.current.as_ref().map(|playback| &playback.clip)
+    }
+
+    /// Gets the playback time of the current clip in seconds.
+    #[inline]
+    pub fn time(&self) -> Float {
+        self.current.as_ref().map_or(0., |playback| playback.time)
+    }
+
+    /// Seeks the current clip to given time in seconds.
+    #[inline]
+    pub fn set_time(&mut self, time: Float) {
+        let looping = self.looping;
+        if let Some(playback) = &mut self.current {
+            playback.time = wrap_time(time, playback.clip.duration(), looping);
+        }
+    }
+
+    /// Gets the playback speed, which scales the elapsed time passed to [AnimationPlayer::update].
+    #[inline]
+    pub fn speed(&self) -> Float {
+        self.speed
+    }
+
+    /// Sets the playback speed. Negative speed plays backward.
+    #[inline]
+    pub fn set_speed(&mut self, speed: Float) {
+        self.speed = speed;
+    }
+
+    /// Returns true if clips restart when reaching their ends.
+    #[inline]
+    pub fn looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Sets whether clips restart when reaching their ends.
+    #[inline]
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Returns the weight of the current clip in the cross-fade, from 0 when it starts to 1 when it is done.
+    #[inline]
+    pub fn fade_weight(&self) -> Float {
+        if self.previous.is_some() && self.fade_duration > 0. {
+            (self.fade_time / self.fade_duration).min(1.)
+        } else {
+            1.
+        }
+    }
+
+    /// Returns true if the current clip is not looping and has reached its end, or if there is no clip.
+    pub fn is_finished(&self) -> bool {
+        match &self.current {
+            Some(playback) if !self.looping => {
+                if self.speed < 0. {
+                    playback.time <= 0.
+                } else {
+                    playback.time >= playback.clip.duration()
+                }
+            }
+            Some(_) => false,
+            None => true,
+        }
+    }
+
+    /// Plays a clip from the start, replacing the current clip without blending.
+    pub fn play(&mut self, clip: AnimationClip) {
+        let time = if self.speed < 0. { clip.duration() } else { 0. };
+        self.current = Some(Playback { clip, time });
+        self.previous = None;
+    }
+
+    /// Plays a clip from the start, blending from the current clip over given duration in seconds.
+    /// The current clip keeps playing until the cross-fade completes.
+    pub fn cross_fade(&mut self, clip: AnimationClip, duration: Float) {
+        let previous = self.current.take();
+        self.play(clip);
+        if duration > 0. {
+            self.previous = previous;
+            self.fade_duration = duration;
+            self.fade_time = 0.;
+        }
+    }
+
+    /// Stops playback, removing all clips.
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.previous = None;
+    }
+
+    /// Advances playback by given elapsed time in seconds, scaled by the playback speed.
+    /// Cross-fades progress by the unscaled elapsed time.
+    pub fn update(&mut self, elapsed: Float) {
+        let delta = elapsed * self.speed;
+        let looping = self.looping;
+        for playback in self.current.iter_mut().chain(self.previous.iter_mut()) {
+            playback.time = wrap_time(playback.time + delta, playback.clip.duration(), looping);
+        }
+        if self.previous.is_some() {
+            self.fade_time += elapsed;
+            if self.fade_time >= self.fade_duration {
+                self.previous = None;
+            }
+        }
+    }
+
+    /// Evaluates all channels of the current clip at the playback time into a pose,
+    /// blended with the previous clip during a cross-fade.
+    pub fn pose(&self) -> Pose {
+        let mut samples = Vec::new();
+        let pose = evaluate(self.current.as_ref(), &mut samples);
+        match &self.previous {
+            Some(previous) => {
+                let from = evaluate(Some(previous), &mut samples);
+                blend_poses(&from, &pose, self.fade_weight())
+            }
+            None => pose,
+        }
+    }
+}
+
+impl Default for AnimationPlayer {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Samples a clip at its playback time into a pose, using `samples` as scratch space.
+fn evaluate(playback: Option<&Playback>, samples: &mut Vec<AnimationSample>) -> Pose {
+    let mut pose = Pose::new();
+    if let Some(playback) = playback {
+        samples.clear();
+        playback.clip.sample_into(playback.time, samples);
+        for AnimationSample { node, value } in samples.drain(..) {
+            pose.entry(node).or_default().set(value);
+        }
+    }
+    pose
+}
+
+/// Wraps a time into [0, duration] if looping, or clamps it otherwise.
+fn wrap_time(time: Float, duration: Float, looping: bool) -> Float {
+    if duration <= 0. {
+        0.
+    } else if looping {
+        let time = time % duration;
+        if time < 0. {
+            time + duration
+        } else {
+            time
+        }
+    } else {
+        time.max(0.).min(duration)
+    }
+}
+
+/// Blends 2 optional values if both are set, otherwise returns the one that is set.
+fn blend_option<T: Clone>(
+    a: &Option<T>,
+    b: &Option<T>,
+    blend: impl FnOnce(&T, &T) -> T,
+) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(blend(a, b)),
+        (Some(value), None) | (None, Some(value)) => Some(value.clone()),
+        (None, None) => None,
+    }
+}
+
+/// Linearly interpolates 2 arrays.
+fn lerp_array<const N: usize>(a: &[Float; N], b: &[Float; N], t: Float) -> [Float; N] {
+    let mut result = *a;
+    for (value, b) in result.iter_mut().zip(b) {
+        *value += (b - *value) * t;
+    }
+    result
+}
@@ -0,0 +1,380 @@
+//! Animation keyframe reduction.
+
+use crate::accessor::read_accessor_floats;
+use crate::model::{
+    Accessor, AccessorComponentType, Buffer, BufferView, Float, Gltf, Id, Interpolation, NodePath,
+};
+use crate::GltfAsset;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use munum::Quaternion;
+
+/// Options of the animation keyframe reduction pass.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyframeReduction {
+    /// Maximum absolute error allowed for translation, scale and morph target weight values.
+    pub tolerance: Float,
+    /// Maximum angular error in radians allowed for rotation values.
+    pub rotation_tolerance: Float,
+    /// Stores the outputs using the smallest component type allowed by glTF that stays within tolerance.
+    pub quantize: bool,
+}
+
+impl Default for KeyframeReduction {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-4,
+            rotation_tolerance: 1e-3,
+            quantize: true,
+        }
+    }
+}
+
+/// Finds the keyframes of an animation sampler that cannot be removed without exceeding the tolerance.
+/// `values` contains the output values of every keyframe, i.e. `times.len()` groups of equal size.
+/// If `rotation` is true, the values are treated as unit quaternions interpolated using slerp.
+/// Returns the sorted indices of the keyframes to keep. The first and last keyframes are always kept.
+/// Cubic spline samplers are not reduced.
+///
+/// # Examples
+/// ```
+/// # use mugltf::{animation::{reduce_keyframes, KeyframeReduction}, Interpolation};
+/// let times = [0., 1., 2., 3.];
+/// let values = [0., 1., 2., 0.];
+/// let kept = reduce_keyframes(&times, &values, Interpolation::Linear, false, &KeyframeReduction::default());
+/// assert_eq!(kept, [0, 2, 3]);
+/// ```
+pub fn reduce_keyframes(
+    times: &[Float],
+    values: &[Float],
+    interpolation: Interpolation,
+    rotation: bool,
+    options: &KeyframeReduction,
+) -> Vec<usize> {
+    let len = times.len();
+    if len <= 2 || values.len() % len != 0 || matches!(interpolation, Interpolation::Cubicspline) {
+        return (0..len).collect();
+    }
+    let components = values.len() / len;
+    let rotation = rotation && components == 4;
+
+    let mut kept = Vec::with_capacity(len);
+    kept.push(0);
+    let mut start = 0;
+    let mut end = 2;
+    while end < len {
+        let removable = ((start + 1)..end).all(|i| match interpolation {
+            Interpolation::Step => {
+                is_within_tolerance(values, components, start, i, rotation, options)
+            }
+            _ => is_interpolation_within_tolerance(
+                times, values, components, start, end, i, rotation, options,
+            ),
+        });
+        if removable {
+            end += 1;
+        } else {
+            start = end - 1;
+            kept.push(start);
+            end = start + 2;
+        }
+    }
+    kept.push(len - 1);
+    kept
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Removes redundant animation keyframes within the given tolerance, optionally quantizing the outputs.
+    /// Resources of the asset must be loaded, otherwise nothing is changed.
+    ///
+    /// Reduced keyframes are written into new accessors backed by a new buffer (without URI) appended to the asset.
+    /// The original accessors are left untouched, as they may be referenced elsewhere.
+    /// Returns the number of keyframes removed.
+    pub fn reduce_keyframes(&mut self, options: KeyframeReduction) -> usize {
+        if self.buffers.len() != self.gltf.buffers.len() {
+            return 0;
+        }
+
+        let buffer_id = self.gltf.buffers.len();
+        let mut data = Vec::new();
+        let mut reduced_inputs = BTreeMap::<(Id, Vec<usize>), Id>::new();
+        let mut removed = 0;
+
+        for animation_id in 0..self.gltf.animations.len() {
+            for sampler_id in 0..self.gltf.animations[animation_id].samplers.len() {
+                let animation = &self.gltf.animations[animation_id];
+                let sampler = &animation.samplers[sampler_id];
+                let path = match animation.channels.iter().find(|c| c.sampler == sampler_id) {
+                    Some(channel) => channel.target.path,
+                    None => continue,
+                };
+                let (input, output, interpolation) =
+                    (sampler.input, sampler.output, sampler.interpolation);
+                let (times, values) = match (
+                    self.gltf.accessors.get(input).and_then(|accessor| {
+                        read_accessor_floats(&self.gltf, &self.buffers, accessor)
+                    }),
+                    self.gltf.accessors.get(output).and_then(|accessor| {
+                        read_accessor_floats(&self.gltf, &self.buffers, accessor)
+                    }),
+                ) {
+                    (Some(times), Some(values)) => (times, values),
+                    _ => continue,
+                };
+                if times.is_empty() || values.len() % times.len() != 0 {
+                    continue;
+                }
+
+                let rotation = matches!(path, NodePath::Rotation);
+                let kept = reduce_keyframes(&times, &values, interpolation, rotation, &options);
+                let components = values.len() / times.len();
+                let values: Vec<Float> = kept
+                    .iter()
+                    .flat_map(|&i| {
+                        values[(i * components)..((i + 1) * components)]
+                            .iter()
+                            .copied()
+                    })
+                    .collect();
+
+                let (component_type, normalized) = if options.quantize {
+                    quantize_type(&values, path, &options)
+                } else {
+                    (AccessorComponentType::Float, false)
+                };
+                let output_accessor = self.gltf.accessors[output].clone();
+                if kept.len() == times.len()
+                    && output_accessor.component_type as u32 == component_type as u32
+                    && output_accessor.normalized == normalized
+                {
+                    continue;
+                }
+                removed += times.len() - kept.len();
+
+                let new_input = if kept.len() == times.len() {
+                    input
+                } else if let Some(id) = reduced_inputs.get(&(input, kept.clone())) {
+                    *id
+                } else {
+                    let kept_times: Vec<Float> = kept.iter().map(|&i| times[i]).collect();
+                    let mut accessor = self.gltf.accessors[input].clone();
+                    accessor.component_type = AccessorComponentType::Float;
+                    accessor.normalized = false;
+                    accessor.count = kept_times.len();
+                    accessor.min = alloc::vec![kept_times[0] as f64];
+                    accessor.max = alloc::vec![kept_times[kept_times.len() - 1] as f64];
+                    let id =
+                        push_accessor(&mut self.gltf, &mut data, buffer_id, accessor, &kept_times);
+                    reduced_inputs.insert((input, kept), id);
+                    id
+                };
+
+                let mut accessor = output_accessor;
+                accessor.component_type = component_type;
+                accessor.normalized = normalized;
+                accessor.count = values.len() / accessor.ty.components();
+                accessor.min.clear();
+                accessor.max.clear();
+                let new_output =
+                    push_accessor(&mut self.gltf, &mut data, buffer_id, accessor, &values);
+
+                let sampler = &mut self.gltf.animations[animation_id].samplers[sampler_id];
+                sampler.input = new_input;
+                sampler.output = new_output;
+            }
+        }
+
+        if !data.is_empty() {
+            self.gltf.buffers.push(Buffer {
+                uri: String::new(),
+                byte_length: data.len(),
+                #[cfg(feature = "gltf-name")]
+                name: String::new(),
+                #[cfg(feature = "gltf-extras")]
+                extras: Default::default(),
+                #[cfg(feature = "gltf-extensions")]
+                extensions: Default::default(),
+            });
+            self.buffers.push(data);
+        }
+
+        removed
+    }
+}
+
+/// Checks if keyframe `i` is equal to keyframe `start` within tolerance.
+fn is_within_tolerance(
+    values: &[Float],
+    components: usize,
+    start: usize,
+    i: usize,
+    rotation: bool,
+    options: &KeyframeReduction,
+) -> bool {
+    let expected = &values[(start * components)..((start + 1) * components)];
+    let actual = &values[(i * components)..((i + 1) * components)];
+    if rotation {
+        is_rotation_within_tolerance(Quaternion::from_slice(expected), actual, options)
+    } else {
+        expected
+            .iter()
+            .zip(actual)
+            .all(|(a, b)| abs(a - b) <= options.tolerance)
+    }
+}
+
+/// Checks if keyframe `i` can be interpolated from keyframes `start` and `end` within tolerance.
+#[allow(clippy::too_many_arguments)]
+fn is_interpolation_within_tolerance(
+    times: &[Float],
+    values: &[Float],
+    components: usize,
+    start: usize,
+    end: usize,
+    i: usize,
+    rotation: bool,
+    options: &KeyframeReduction,
+) -> bool {
+    let duration = times[end] - times[start];
+    let t = if duration > 0. {
+        (times[i] - times[start]) / duration
+    } else {
+        0.
+    };
+    let from = &values[(start * components)..((start + 1) * components)];
+    let to = &values[(end * components)..((end + 1) * components)];
+    let actual = &values[(i * components)..((i + 1) * components)];
+
+    if rotation {
+        let expected = Quaternion::from_slice(from).slerp(Quaternion::from_slice(to), t);
+        is_rotation_within_tolerance(expected, actual, options)
+    } else {
+        (0..components).all(|c| {
+            let expected = from[c] + (to[c] - from[c]) * t;
+            abs(expected - actual[c]) <= options.tolerance
+        })
+    }
+}
+
+/// Checks if the angle between 2 rotations is within tolerance.
+fn is_rotation_within_tolerance(
+    expected: Quaternion<Float>,
+    actual: &[Float],
+    options: &KeyframeReduction,
+) -> bool {
+    let actual = Quaternion::from_slice(actual);
+    let len = expected.len() * actual.len();
+    if len <= 0. {
+        return false;
+    }
+    // For small angles, 1 - cos(angle / 2) ~= angle^2 / 8
+    let cos_half_angle = abs(expected.dot(actual)) / len;
+    1. - cos_half_angle <= options.rotation_tolerance * options.rotation_tolerance / 8.
+}
+
+/// Finds the smallest component type allowed for the values of an animation channel path.
+/// Only rotations and morph target weights can be stored as normalized integers.
+fn quantize_type(
+    values: &[Float],
+    path: NodePath,
+    options: &KeyframeReduction,
+) -> (AccessorComponentType, bool) {
+    let (tolerance, unsigned) = match path {
+        // Component error of e can cause an angle error of up to ~4e
+        NodePath::Rotation => (options.rotation_tolerance / 4., false),
+        NodePath::Weights => (options.tolerance, values.iter().all(|v| *v >= 0.)),
+        _ => return (AccessorComponentType::Float, false),
+    };
+
+    let candidates = if unsigned {
+        [
+            AccessorComponentType::UnsignedByte,
+            AccessorComponentType::UnsignedShort,
+        ]
+    } else {
+        [AccessorComponentType::Byte, AccessorComponentType::Short]
+    };
+    for component_type in candidates {
+        if values
+            .iter()
+            .all(|v| abs(dequantize(quantize(*v, component_type), component_type) - v) <= tolerance)
+        {
+            return (component_type, true);
+        }
+    }
+    (AccessorComponentType::Float, false)
+}
+
+/// Appends an accessor with the given values stored in a new buffer view of `data`.
+fn push_accessor(
+    gltf: &mut Gltf,
+    data: &mut Vec<u8>,
+    buffer: Id,
+    mut accessor: Accessor,
+    values: &[Float],
+) -> Id {
+    // Buffer views of float data must be 4-byte aligned
+    data.resize((data.len() + 3) & !3, 0);
+    let byte_offset = data.len();
+    for value in values {
+        match accessor.component_type {
+            AccessorComponentType::Byte | AccessorComponentType::UnsignedByte => {
+                data.push(quantize(*value, accessor.component_type) as u8)
+            }
+            AccessorComponentType::Short | AccessorComponentType::UnsignedShort => data
+                .extend_from_slice(
+                    &(quantize(*value, accessor.component_type) as u16).to_le_bytes(),
+                ),
+            _ => data.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    gltf.buffer_views.push(BufferView {
+        buffer,
+        byte_offset,
+        byte_length: data.len() - byte_offset,
+        ..Default::default()
+    });
+    accessor.buffer_view = Some(gltf.buffer_views.len() - 1);
+    accessor.byte_offset = 0;
+    accessor.sparse = None;
+    gltf.accessors.push(accessor);
+    gltf.accessors.len() - 1
+}
+
+/// Converts a float into a normalized integer.
+fn quantize(value: Float, component_type: AccessorComponentType) -> i32 {
+    let (min, max) = match component_type {
+        AccessorComponentType::Byte => (-127., 127.),
+        AccessorComponentType::UnsignedByte => (0., 255.),
+        AccessorComponentType::Short => (-32767., 32767.),
+        AccessorComponentType::UnsignedShort => (0., 65535.),
+        _ => return value as i32,
+    };
+    let value = (value * max).max(min).min(max);
+    if value < 0. {
+        (value - 0.5) as i32
+    } else {
+        (value + 0.5) as i32
+    }
+}
+
+/// Converts a normalized integer into float.
+fn dequantize(value: i32, component_type: AccessorComponentType) -> Float {
+    let max = match component_type {
+        AccessorComponentType::Byte => 127.,
+        AccessorComponentType::UnsignedByte => 255.,
+        AccessorComponentType::Short => 32767.,
+        AccessorComponentType::UnsignedShort => 65535.,
+        _ => return value as Float,
+    };
+    (value as Float / max).max(-1.)
+}
+
+#[inline]
+fn abs(value: Float) -> Float {
+    if value < 0. {
+        -value
+    } else {
+        value
+    }
+}
@@ -0,0 +1,261 @@
+//! Animation sampling.
+
+use crate::accessor::read_accessor_floats;
+use crate::model::{Animation, Float, Gltf, Id, Interpolation, NodePath};
+use crate::GltfAsset;
+use alloc::vec::Vec;
+use munum::Quaternion;
+
+/// An interpolated value of an animation channel.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationValue {
+    /// Morph target weights of the node's mesh.
+    Weights(Vec<Float>),
+    /// Translation of the node.
+    Translation([Float; 3]),
+    /// Unit quaternion rotation of the node, in (x, y, z, w) order.
+    Rotation([Float; 4]),
+    /// Scale of the node.
+    Scale([Float; 3]),
+}
+
+/// An interpolated value targeting a node, produced by [AnimationClip::sample].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationSample {
+    /// ID of the target node.
+    pub node: Id,
+    /// The interpolated value.
+    pub value: AnimationValue,
+}
+
+/// An animation with its keyframes decoded from the glTF accessors, ready to be sampled at any time.
+#[derive(Clone, Debug, Default)]
+pub struct AnimationClip {
+    channels: Vec<ClipChannel>,
+    duration: Float,
+}
+
+/// A decoded channel of an [AnimationClip].
+#[derive(Clone, Debug)]
+struct ClipChannel {
+    node: Id,
+    path: NodePath,
+    interpolation: Interpolation,
+    times: Vec<Float>,
+    values: Vec<Float>,
+}
+
+impl AnimationClip {
+    /// Decodes the keyframes of an animation from the glTF accessors.
+    /// Channels without a target node, or with invalid sampler data, are skipped.
+    pub fn new(gltf: &Gltf, buffers: &[Vec<u8>], animation: &Animation) -> Self {
+        let mut channels = Vec::with_capacity(animation.channels.len());
+        let mut duration: Float = 0.;
+        for channel in &animation.channels {
+            let sampler = match animation.samplers.get(channel.sampler) {
+                Some(sampler) => sampler,
+                None => continue,
+            };
+            let (times, values) = match (
+                gltf.accessors
+                    .get(sampler.input)
+                    .and_then(|accessor| read_accessor_floats(gltf, buffers, accessor)),
+                gltf.accessors
+                    .get(sampler.output)
+                    .and_then(|accessor| read_accessor_floats(gltf, buffers, accessor)),
+            ) {
+                (Some(times), Some(values)) => (times, values),
+                _ => continue,
+            };
+            if keyframe_components(&times, &values, sampler.interpolation) == 0 {
+                continue;
+            }
+            duration = duration.max(times[times.len() - 1]);
+            channels.push(ClipChannel {
+                node: channel.target.node,
+                path: channel.target.path,
+                interpolation: sampler.interpolation,
+                times,
+                values,
+            });
+        }
+        Self { channels, duration }
+    }
+
+    /// Returns the time of the last keyframe of this animation in seconds.
+    #[inline]
+    pub fn duration(&self) -> Float {
+        self.duration
+    }
+
+    /// Returns the number of valid channels of this animation.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Returns true if this animation has no valid channel.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Samples every channel of this animation at given time in seconds.
+    /// Times outside of the keyframe range are clamped. Looping is left to the caller.
+    pub fn sample(&self, time: Float) -> Vec<AnimationSample> {
+        let mut samples = Vec::with_capacity(self.channels.len());
+        self.sample_into(time, &mut samples);
+        samples
+    }
+
+    /// Samples every channel of this animation at given time in seconds, appending the results to `samples`.
+    /// See [AnimationClip::sample].
+    pub fn sample_into(&self, time: Float, samples: &mut Vec<AnimationSample>) {
+        samples.extend(self.channels.iter().map(|channel| {
+            let rotation = matches!(channel.path, NodePath::Rotation);
+            let value = sample_keyframes(
+                &channel.times,
+                &channel.values,
+                channel.interpolation,
+                rotation,
+                time,
+            );
+            AnimationSample {
+                node: channel.node,
+                value: match channel.path {
+                    NodePath::Weights => AnimationValue::Weights(value),
+                    NodePath::Translation => AnimationValue::Translation(to_array(&value)),
+                    NodePath::Rotation => AnimationValue::Rotation(to_array(&value)),
+                    NodePath::Scale => AnimationValue::Scale(to_array(&value)),
+                },
+            }
+        }));
+    }
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Decodes the keyframes of an animation for sampling.
+    /// Returns `None` if the animation does not exist. Resources of the asset must be loaded.
+    pub fn animation_clip(&self, animation: Id) -> Option<AnimationClip> {
+        self.gltf
+            .animations
+            .get(animation)
+            .map(|animation| AnimationClip::new(&self.gltf, &self.buffers, animation))
+    }
+}
+
+/// Interpolates the keyframes of an animation sampler at given time.
+/// `values` contains the output values of every keyframe, i.e. `times.len()` groups of equal size,
+/// or `times.len()` triplets of (in-tangent, value, out-tangent) groups for cubic spline samplers.
+/// If `rotation` is true, the values are treated as unit quaternions, interpolated using slerp
+/// and normalized after cubic spline interpolation.
+/// Times outside of the keyframe range are clamped.
+/// Returns the interpolated group of values, or an empty `Vec` if the keyframes are invalid.
+///
+/// # Examples
+/// ```
+/// # use mugltf::{animation::sample_keyframes, Interpolation};
+/// let times = [0., 1., 2.];
+/// let values = [0., 10., 2., 20., 4., 30.];
+/// assert_eq!(sample_keyframes(&times, &values, Interpolation::Linear, false, 0.5), [1., 15.]);
+/// assert_eq!(sample_keyframes(&times, &values, Interpolation::Step, false, 1.5), [2., 20.]);
+/// assert_eq!(sample_keyframes(&times, &values, Interpolation::Linear, false, 3.), [4., 30.]);
+/// ```
+pub fn sample_keyframes(
+    times: &[Float],
+    values: &[Float],
+    interpolation: Interpolation,
+    rotation: bool,
+    time: Float,
+) -> Vec<Float> {
+    let components = keyframe_components(times, values, interpolation);
+    if components == 0 {
+        return Vec::new();
+    }
+    let rotation = rotation && components == 4;
+    let cubic = matches!(interpolation, Interpolation::Cubicspline);
+    // Offset of the value group within each keyframe: cubic spline keyframes start with the in-tangent
+    let (stride, offset) = if cubic {
+        (3 * components, components)
+    } else {
+        (components, 0)
+    };
+    let value = |i: usize| &values[(i * stride + offset)..(i * stride + offset + components)];
+
+    // Index of the last keyframe at or before the time
+    let next = times.partition_point(|t| *t <= time);
+    if next == 0 {
+        return value(0).to_vec();
+    }
+    let prev = next - 1;
+    if next == times.len() {
+        return value(prev).to_vec();
+    }
+    let delta = times[next] - times[prev];
+    if delta <= 0. {
+        return value(prev).to_vec();
+    }
+    let t = (time - times[prev]) / delta;
+
+    match interpolation {
+        Interpolation::Step => value(prev).to_vec(),
+        Interpolation::Linear if rotation => {
+            let from = Quaternion::from_slice(value(prev));
+            let to = Quaternion::from_slice(value(next));
+            <[Float; 4]>::from(from.slerp(to, t)).to_vec()
+        }
+        Interpolation::Linear => value(prev)
+            .iter()
+            .zip(value(next))
+            .map(|(a, b)| a + (b - a) * t)
+            .collect(),
+        Interpolation::Cubicspline => {
+            let out_tangent = &values[(prev * stride + 2 * components)..((prev + 1) * stride)];
+            let in_tangent = &values[(next * stride)..(next * stride + components)];
+            let (t2, t3) = (t * t, t * t * t);
+            let (h00, h10, h01, h11) = (
+                2. * t3 - 3. * t2 + 1.,
+                t3 - 2. * t2 + t,
+                -2. * t3 + 3. * t2,
+                t3 - t2,
+            );
+            let result: Vec<Float> = (0..components)
+                .map(|c| {
+                    h00 * value(prev)[c]
+                        + h10 * delta * out_tangent[c]
+                        + h01 * value(next)[c]
+                        + h11 * delta * in_tangent[c]
+                })
+                .collect();
+            if rotation {
+                let mut q = Quaternion::from_slice(&result);
+                q.normalize();
+                <[Float; 4]>::from(q).to_vec()
+            } else {
+                result
+            }
+        }
+    }
+}
+
+/// Returns the number of components of each keyframe value, or 0 if the keyframes are invalid.
+fn keyframe_components(times: &[Float], values: &[Float], interpolation: Interpolation) -> usize {
+    let groups = match interpolation {
+        Interpolation::Cubicspline => 3 * times.len(),
+        _ => times.len(),
+    };
+    if groups == 0 || values.len() % groups != 0 {
+        0
+    } else {
+        values.len() / groups
+    }
+}
+
+/// Copies a value group into a fixed-size array, padding missing components with 0.
+fn to_array<const N: usize>(values: &[Float]) -> [Float; N] {
+    let mut array = [0.; N];
+    for (dst, src) in array.iter_mut().zip(values) {
+        *dst = *src;
+    }
+    array
+}
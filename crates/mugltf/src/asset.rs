@@ -5,19 +5,29 @@ use crate::{
     ParseGltfError, ParseGltfErrorKind,
 };
 use alloc::{borrow::Cow, boxed::Box, vec::Vec};
-use core::{mem, str};
+use core::{
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
 use mugl::Extent2D;
 
+#[cfg(feature = "serde")]
+use crate::ParseOptions;
+#[cfg(feature = "serde")]
+use core::str;
+
 /// glTF in ASCII
 #[allow(dead_code)]
-const GLB_HEADER_MAGIC: &[u8] = &[0x67, 0x6C, 0x54, 0x46];
+pub(crate) const GLB_HEADER_MAGIC: &[u8] = &[0x67, 0x6C, 0x54, 0x46];
 #[allow(dead_code)]
-const GLB_HEADER_LENGTH: usize = 12;
+pub(crate) const GLB_HEADER_LENGTH: usize = 12;
 
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 #[repr(u32)]
-enum GLBChunk {
+pub(crate) enum GLBChunk {
     Json = 0x4E4F534A,
     Bin = 0x004E4942,
 }
@@ -63,7 +73,14 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
 
     /// Parses a binary glTF.
     #[cfg(feature = "serde")]
+    #[inline]
     pub fn parse_glb(data: &'a [u8]) -> Result<Self, ParseGltfError> {
+        Self::parse_glb_with(data, &ParseOptions::default())
+    }
+
+    /// Parses a binary glTF, skipping the JSON sections excluded by given options.
+    #[cfg(feature = "serde")]
+    pub fn parse_glb_with(data: &'a [u8], options: &ParseOptions) -> Result<Self, ParseGltfError> {
         if data.len() < GLB_HEADER_LENGTH || GLB_HEADER_MAGIC != &data[0..4] {
             return Err(ParseGltfErrorKind::InvalidHeader.into());
         }
@@ -88,7 +105,7 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
 
             i += chunk_len;
 
-            Self::parse_gltf(gltf_str)?.gltf
+            Self::parse_gltf_with(gltf_str, options)?.gltf
         };
 
         let bin = if i < data.len() {
@@ -119,8 +136,15 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
 
     /// Parses a glTF JSON string.
     #[cfg(feature = "serde")]
+    #[inline]
     pub fn parse_gltf(data: &str) -> Result<Self, ParseGltfError> {
-        let gltf: Gltf = serde_json::from_str(data).map_err(|err| {
+        Self::parse_gltf_with(data, &ParseOptions::default())
+    }
+
+    /// Parses a glTF JSON string, skipping the sections excluded by given options.
+    #[cfg(feature = "serde")]
+    pub fn parse_gltf_with(data: &str, options: &ParseOptions) -> Result<Self, ParseGltfError> {
+        let gltf = options.parse(data).map_err(|err| {
             ParseGltfError::new::<Box<Error>>(ParseGltfErrorKind::InvalidJson, Box::new(err))
         })?;
 
@@ -167,6 +191,9 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
 
     /// Loads all resources of this glTF asset.
     /// The bin chunk, if exists, will be consumed and left empty.
+    ///
+    /// Images are loaded concurrently after the buffers, so that loaders decoding images asynchronously,
+    /// e.g. with an [AsyncImageDecoder](crate::AsyncImageDecoder) running on worker threads, can decode them in parallel.
     pub async fn load_resources<L: GltfResourceLoader<ImageData = ImageData>>(
         mut self,
         loader: &L,
@@ -190,15 +217,16 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
             }
         }
 
+        let mut image_loads = Vec::with_capacity(self.gltf.images.len());
         for (image_id, image) in self.gltf.images.iter().enumerate() {
+            let image_error = move |err| {
+                LoadGltfResourceError::new(LoadGltfResourceErrorKind::LoadImageError(image_id), err)
+            };
             if !image.uri.is_empty() {
-                let data = loader.get_image(&image.uri).await.map_err(|err| {
-                    LoadGltfResourceError::new(
-                        LoadGltfResourceErrorKind::LoadImageError(image_id),
-                        err,
-                    )
-                })?;
-                images.push(data);
+                let load: ImageLoad<'_, ImageData> = Box::pin(async move {
+                    loader.get_image(&image.uri).await.map_err(image_error)
+                });
+                image_loads.push(load);
             } else {
                 let buffer_id_and_range = image.buffer_view.and_then(|buffer_view_id| {
                     let buffer_view = self.gltf.buffer_views.get(buffer_view_id)?;
@@ -215,21 +243,21 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
 
                 if let Some((buffer_id, range)) = buffer_id_and_range {
                     let data_slice = &buffers[buffer_id][range];
-                    let image_data = loader
-                        .decode_image(data_slice, &image.mime_type)
-                        .await
-                        .map_err(|err| {
-                            LoadGltfResourceError::new(
-                                LoadGltfResourceErrorKind::LoadImageError(image_id),
-                                err,
-                            )
-                        })?;
-                    images.push(image_data);
+                    let load: ImageLoad<'_, ImageData> = Box::pin(async move {
+                        loader
+                            .decode_image(data_slice, &image.mime_type)
+                            .await
+                            .map_err(image_error)
+                    });
+                    image_loads.push(load);
                 } else {
                     return Err(LoadGltfResourceErrorKind::LoadImageError(image_id).into());
                 }
             }
         }
+        for image in JoinAll::new(image_loads).await {
+            images.push(image?);
+        }
 
         let mut owned = self.into_owned();
         owned.buffers = buffers;
@@ -239,6 +267,55 @@ impl<'a, ImageData> GltfAsset<'a, ImageData> {
     }
 }
 
+/// Future of loading an image.
+type ImageLoad<'a, ImageData> =
+    Pin<Box<dyn Future<Output = Result<ImageData, LoadGltfResourceError>> + 'a>>;
+
+/// Future that polls a list of futures concurrently, and resolves to their outputs in order.
+struct JoinAll<'a, T> {
+    futures: Vec<Option<Pin<Box<dyn Future<Output = T> + 'a>>>>,
+    outputs: Vec<Option<T>>,
+}
+
+impl<'a, T> JoinAll<'a, T> {
+    fn new(futures: Vec<Pin<Box<dyn Future<Output = T> + 'a>>>) -> Self {
+        let outputs = futures.iter().map(|_| None).collect();
+        Self {
+            futures: futures.into_iter().map(Some).collect(),
+            outputs,
+        }
+    }
+}
+
+// The futures are boxed, and outputs are never pinned
+impl<T> Unpin for JoinAll<'_, T> {}
+
+impl<'a, T> Future for JoinAll<'a, T> {
+    type Output = Vec<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut done = true;
+        for (future, output) in this.futures.iter_mut().zip(&mut this.outputs) {
+            if let Some(pending) = future {
+                match pending.as_mut().poll(cx) {
+                    Poll::Ready(value) => {
+                        *output = Some(value);
+                        *future = None;
+                    }
+                    Poll::Pending => done = false,
+                }
+            }
+        }
+
+        if done {
+            Poll::Ready(this.outputs.drain(..).flatten().collect())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
 impl<'a, ImageData> From<Gltf> for GltfAsset<'a, ImageData> {
     fn from(gltf: Gltf) -> Self {
         Self {
@@ -249,7 +326,7 @@ impl<'a, ImageData> From<Gltf> for GltfAsset<'a, ImageData> {
 }
 
 #[allow(dead_code)]
-fn read_u32(
+pub(crate) fn read_u32(
     data: &[u8],
     offset: usize,
     error_kind: ParseGltfErrorKind,
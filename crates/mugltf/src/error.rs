@@ -1,7 +1,7 @@
 //! Error types.
 
-use super::Id;
-use alloc::boxed::Box;
+use super::{Id, Size};
+use alloc::{boxed::Box, string::String};
 use core::fmt;
 
 /// Error type.
@@ -99,6 +99,7 @@ impl fmt::Display for LoadGltfResourceError {
             LoadGltfResourceErrorKind::LoadBufferError(id) => write!(f, "failed to load buffer {}", id),
             LoadGltfResourceErrorKind::LoadImageError(id) => write!(f, "failed to load image {}", id),
             LoadGltfResourceErrorKind::ParseGltfError => write!(f, "failed to parse glTF or GLB file"),
+            LoadGltfResourceErrorKind::DecompressMeshError(id) => write!(f, "failed to decompress mesh {}", id),
             _ => write!(f, "failed to load resource"),
         }
     }
@@ -118,6 +119,7 @@ pub enum LoadGltfResourceErrorKind {
     LoadBufferError(Id),
     LoadError,
     ParseGltfError,
+    DecompressMeshError(Id),
 }
 
 impl Default for LoadGltfResourceErrorKind {
@@ -131,3 +133,123 @@ impl From<LoadGltfResourceErrorKind> for LoadGltfResourceError {
         Self { kind, error: None }
     }
 }
+
+/// Error when instantiating GPU resources of a glTF asset.
+#[cfg(feature = "render")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstantiateGltfError {
+    /// An accessor is missing, out of bounds, or of an unsupported type.
+    InvalidAccessor(Id),
+    /// An image is not tightly packed RGBA8 or RGB8 data.
+    InvalidImage(Id),
+    /// A mesh references an invalid material.
+    InvalidMesh(Id),
+}
+
+#[cfg(feature = "render")]
+impl fmt::Display for InstantiateGltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAccessor(id) => write!(f, "invalid accessor {}", id),
+            Self::InvalidImage(id) => write!(f, "invalid image data {}", id),
+            Self::InvalidMesh(id) => write!(f, "invalid mesh {}", id),
+        }
+    }
+}
+
+#[cfg(all(feature = "render", feature = "std"))]
+impl std::error::Error for InstantiateGltfError {}
+
+/// A glTF object, referenced by [ValidationError]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GltfObject {
+    /// The root glTF object.
+    Root,
+    Accessor(Id),
+    Animation(Id),
+    /// A sampler of an animation, by animation ID and sampler index.
+    AnimationSampler(Id, Id),
+    Buffer(Id),
+    BufferView(Id),
+    Camera(Id),
+    Image(Id),
+    Material(Id),
+    Mesh(Id),
+    Node(Id),
+    Sampler(Id),
+    Scene(Id),
+    Skin(Id),
+    Texture(Id),
+}
+
+impl fmt::Display for GltfObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root => write!(f, "glTF root"),
+            Self::Accessor(id) => write!(f, "accessor {}", id),
+            Self::Animation(id) => write!(f, "animation {}", id),
+            Self::AnimationSampler(animation, id) => {
+                write!(f, "sampler {} of animation {}", id, animation)
+            }
+            Self::Buffer(id) => write!(f, "buffer {}", id),
+            Self::BufferView(id) => write!(f, "buffer view {}", id),
+            Self::Camera(id) => write!(f, "camera {}", id),
+            Self::Image(id) => write!(f, "image {}", id),
+            Self::Material(id) => write!(f, "material {}", id),
+            Self::Mesh(id) => write!(f, "mesh {}", id),
+            Self::Node(id) => write!(f, "node {}", id),
+            Self::Sampler(id) => write!(f, "sampler {}", id),
+            Self::Scene(id) => write!(f, "scene {}", id),
+            Self::Skin(id) => write!(f, "skin {}", id),
+            Self::Texture(id) => write!(f, "texture {}", id),
+        }
+    }
+}
+
+/// Error found by [Gltf::validate](crate::Gltf::validate).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// A property of an object references a missing object.
+    /// `property` is the JSON name of the property, e.g. `bufferView`, prefixed by its parent properties if nested.
+    InvalidReference {
+        object: GltfObject,
+        property: &'static str,
+        target: GltfObject,
+    },
+    /// The data of an accessor or buffer view ends after the end of its buffer view or buffer.
+    /// `byte_end` is saturated to the maximum size if computing it overflows.
+    OutOfBounds {
+        object: GltfObject,
+        byte_end: Size,
+        byte_length: Size,
+    },
+    /// An extension required to load the asset is not supported.
+    UnsupportedExtension(String),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidReference {
+                object,
+                property,
+                target,
+            } => write!(f, "{} references missing {} in {}", object, target, property),
+            Self::OutOfBounds {
+                object,
+                byte_end,
+                byte_length,
+            } => write!(
+                f,
+                "{} data ends at byte {}, exceeding the available {} bytes",
+                object, byte_end, byte_length
+            ),
+            Self::UnsupportedExtension(name) => {
+                write!(f, "unsupported required extension {}", name)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
@@ -7,12 +7,38 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+mod accessor;
 mod asset;
 mod error;
 mod loader;
 pub mod model;
+mod parse;
+mod scene;
+mod stream;
+mod texture;
+mod validate;
+mod writer;
 
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod animation;
+
+#[cfg(any(feature = "std", feature = "libm"))]
+pub mod mesh;
+
+#[cfg(feature = "render")]
+pub mod render;
+
+pub use accessor::*;
 pub use asset::*;
 pub use error::*;
 pub use loader::*;
 pub use model::*;
+#[cfg(feature = "serde")]
+pub use parse::*;
+pub use scene::*;
+#[cfg(feature = "serde")]
+pub use stream::*;
+pub use texture::*;
+pub use validate::*;
+#[cfg(feature = "serde")]
+pub use writer::*;
@@ -1,10 +1,11 @@
 #![cfg(feature = "fetch-loader")]
 
 use super::GltfResourceLoader;
+use crate::AsyncByteStream;
 use alloc::{boxed::Box, string::String, vec::Vec};
 use async_trait::async_trait;
 use core::fmt;
-use js_sys::{Promise, Uint8Array};
+use js_sys::{Function, Promise, Reflect, Uint8Array};
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{HtmlImageElement, Request, RequestInit, RequestMode, Response};
@@ -63,7 +64,89 @@ impl GltfResourceLoader for GltfResourceFetchLoader {
     }
 }
 
+/// A response body streamed with [fetch](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API) API,
+/// e.g. to parse a large GLB with [GlbStream::new_async](crate::GlbStream::new_async)
+/// without holding the whole file in memory.
+#[derive(Debug)]
+pub struct FetchByteStream {
+    /// `ReadableStreamDefaultReader` of the response body.
+    reader: JsValue,
+    /// `read` method of the reader.
+    read: Function,
+    /// Last chunk received from the stream.
+    chunk: Vec<u8>,
+    /// Offset of the unread bytes in the chunk.
+    offset: usize,
+    done: bool,
+}
+
+impl FetchByteStream {
+    /// Fetches an URL, returning a stream of the response body.
+    pub async fn fetch(url: &str) -> Result<Self, FetchLoaderError> {
+        Self::from_response(fetch_response(url).await.map_err(FetchLoaderError)?)
+            .map_err(FetchLoaderError)
+    }
+
+    /// Creates a stream of a fetch response body.
+    pub fn from_response(response: Response) -> Result<Self, JsValue> {
+        // Streams are accessed by reflection, as web-sys only exposes them as unstable APIs
+        let body = Reflect::get(&response, &"body".into())?;
+        if body.is_null() || body.is_undefined() {
+            return Err(JsError::new("response has no body").into());
+        }
+        let reader = method(&body, "getReader")?.call0(&body)?;
+        Ok(Self {
+            read: method(&reader, "read")?,
+            reader,
+            chunk: Vec::new(),
+            offset: 0,
+            done: false,
+        })
+    }
+
+    /// Receives the next chunk from the stream.
+    async fn next_chunk(&mut self) -> Result<(), JsValue> {
+        let result = JsFuture::from(Promise::from(self.read.call0(&self.reader)?)).await?;
+        self.done = Reflect::get(&result, &"done".into())?.as_bool().unwrap_or(true);
+        self.chunk = if self.done {
+            Vec::new()
+        } else {
+            Uint8Array::new(&Reflect::get(&result, &"value".into())?).to_vec()
+        };
+        self.offset = 0;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncByteStream for FetchByteStream {
+    type Error = FetchLoaderError;
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        while self.offset >= self.chunk.len() && !self.done {
+            self.next_chunk().await.map_err(FetchLoaderError)?;
+        }
+        let len = buf.len().min(self.chunk.len() - self.offset);
+        buf[..len].copy_from_slice(&self.chunk[self.offset..(self.offset + len)]);
+        self.offset += len;
+        Ok(len)
+    }
+}
+
+fn method(target: &JsValue, name: &str) -> Result<Function, JsValue> {
+    Reflect::get(target, &name.into())?.dyn_into()
+}
+
 async fn fetch(url: &str) -> Result<Vec<u8>, JsValue> {
+    let response = fetch_response(url).await?;
+    let content =
+        Uint8Array::new_with_byte_offset(&JsFuture::from(response.array_buffer()?).await?, 0)
+            .to_vec();
+
+    Ok(content)
+}
+
+async fn fetch_response(url: &str) -> Result<Response, JsValue> {
     let mut opts = RequestInit::new();
     opts.method("GET");
     opts.mode(RequestMode::Cors);
@@ -79,11 +162,7 @@ async fn fetch(url: &str) -> Result<Vec<u8>, JsValue> {
         return Err(response.status().into());
     }
 
-    let content =
-        Uint8Array::new_with_byte_offset(&JsFuture::from(response.array_buffer()?).await?, 0)
-            .to_vec();
-
-    Ok(content)
+    Ok(response)
 }
 
 async fn load_image(uri: &str) -> Result<HtmlImageElement, JsValue> {
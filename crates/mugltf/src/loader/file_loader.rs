@@ -2,7 +2,10 @@
 
 #![cfg(feature = "file-loader")]
 
-use super::GltfResourceLoader;
+use super::{
+    decode_ktx2, image_mime_type, include_loader::percent_decode, DecodeImageError,
+    GltfResourceLoader, KTX2_MIME_TYPE,
+};
 use crate::Error;
 use alloc::{boxed::Box, string::String, vec::Vec};
 use async_trait::async_trait;
@@ -12,18 +15,56 @@ use image::{
     error::{ImageFormatHint, UnsupportedError, UnsupportedErrorKind},
     ImageError, ImageFormat, ImageResult,
 };
-use mugl::Extent2D;
-use std::{fs::File, io::Read, path::PathBuf};
+use mugl::{Extent2D, TextureFormat};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+/// Function that reads the content of a resolved file path.
+pub type FileReader = dyn Fn(&Path) -> io::Result<Vec<u8>>;
 
 /// Loader of glTF resources from file system.
-#[derive(Debug)]
+///
+/// Relative URIs are percent-decoded and resolved against the root path using [resolve_path].
+/// By default, files are read from the file system. A custom reader can be set via
+/// [GltfResourceFileLoader::set_reader] to load from a virtual file system instead, e.g. a zip or pak archive.
 pub struct GltfResourceFileLoader {
     path: String,
+    reader: Option<Box<FileReader>>,
+}
+
+impl GltfResourceFileLoader {
+    /// Sets the reader of resolved file paths, replacing the default file system reader.
+    pub fn set_reader(&mut self, reader: impl Fn(&Path) -> io::Result<Vec<u8>> + 'static) {
+        self.reader = Some(Box::new(reader));
+    }
+
+    fn read(&self, uri: &str) -> io::Result<Vec<u8>> {
+        let path = resolve_path(&self.path, uri);
+        match &self.reader {
+            Some(reader) => reader(&path),
+            None => read_file(&path),
+        }
+    }
+}
+
+impl Debug for GltfResourceFileLoader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GltfResourceFileLoader")
+            .field("path", &self.path)
+            .field("reader", &self.reader.as_ref().map(|_| "Fn"))
+            .finish()
+    }
 }
 
 impl Default for GltfResourceFileLoader {
     fn default() -> Self {
-        Self { path: "./".into() }
+        Self {
+            path: "./".into(),
+            reader: None,
+        }
     }
 }
 
@@ -38,33 +79,43 @@ impl GltfResourceLoader for GltfResourceFileLoader {
     }
 
     async fn get_gltf(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
-        Ok(read_file(&self.path, uri)?)
+        Ok(self.read(uri)?)
     }
 
     async fn get_buffer(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
         if let Some(data) = try_read_data_url(uri, false)? {
             Ok(data)
         } else {
-            Ok(read_file(&self.path, uri)?)
+            Ok(self.read(uri)?)
         }
     }
 
     async fn get_image(&self, uri: &str) -> Result<Self::ImageData, Self::Error> {
-        let data = if let Some(data) = try_read_data_url(uri, true)? {
-            data
-        } else {
-            read_file(&self.path, uri)?
-        };
+        let data = self.get_image_file(uri).await?;
+        if image_mime_type(&data) == Some(KTX2_MIME_TYPE) {
+            return decode_ktx2_rgba(&data);
+        }
         let dynimage = image::load_from_memory(data.as_slice())?;
         let size = Extent2D(dynimage.width(), dynimage.height());
         Ok((dynimage.into_bytes(), size))
     }
 
+    async fn get_image_file(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        if let Some(data) = try_read_data_url(uri, true)? {
+            Ok(data)
+        } else {
+            Ok(self.read(uri)?)
+        }
+    }
+
     async fn decode_image(
         &self,
         img: &[u8],
         mime_type: &str,
     ) -> Result<Self::ImageData, Self::Error> {
+        if mime_type == KTX2_MIME_TYPE {
+            return decode_ktx2_rgba(img);
+        }
         let format = get_image_format(mime_type)?;
         let dynimage = image::load_from_memory_with_format(img, format)?;
         let size = Extent2D(dynimage.width(), dynimage.height());
@@ -72,10 +123,53 @@ impl GltfResourceLoader for GltfResourceFileLoader {
     }
 }
 
-fn read_file(path: &str, file: &str) -> Result<Vec<u8>, std::io::Error> {
-    let mut file_path = PathBuf::from(path);
-    file_path.push(file);
-    let mut file = File::open(file_path)?;
+/// Resolves a relative URI against a root path into a file path.
+///
+/// The URI is percent-decoded, and both `/` and `\` are treated as path separators.
+/// `.` and `..` segments are resolved lexically, so that the path is also usable as an archive entry name.
+/// Decoded bytes that are not valid UTF-8 are kept as-is on Unix, and replaced elsewhere.
+pub fn resolve_path(root: &str, uri: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    for component in Path::new(root).components() {
+        push_component(&mut path, component);
+    }
+    for segment in percent_decode(uri).split(|&b| b == b'/' || b == b'\\') {
+        match segment {
+            b"" | b"." => {}
+            b".." => push_component(&mut path, Component::ParentDir),
+            _ => path.push(bytes_to_path(segment)),
+        }
+    }
+    path
+}
+
+fn push_component(path: &mut PathBuf, component: Component) {
+    match component {
+        Component::CurDir => {}
+        Component::ParentDir => {
+            if matches!(path.components().next_back(), Some(Component::Normal(_))) {
+                path.pop();
+            } else {
+                path.push("..");
+            }
+        }
+        _ => path.push(component),
+    }
+}
+
+#[cfg(unix)]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    OsStr::from_bytes(bytes).into()
+}
+
+#[cfg(not(unix))]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    String::from_utf8_lossy(bytes).as_ref().into()
+}
+
+fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = File::open(path)?;
     let mut content = Vec::new();
     file.read_to_end(&mut content)?;
     Ok(content)
@@ -113,7 +207,18 @@ fn is_supported_buffer_mime(type_: &str, subtype: &str) -> bool {
 
 #[inline]
 fn is_supported_image_mime(type_: &str, subtype: &str) -> bool {
-    type_ == "image" && (subtype == "png" || subtype == "jpeg")
+    type_ == "image" && (subtype == "png" || subtype == "jpeg" || subtype == "ktx2")
+}
+
+/// Decodes a KTX2 image, which is only supported in 8-bit RGBA formats by this loader.
+fn decode_ktx2_rgba(data: &[u8]) -> Result<(Vec<u8>, Extent2D), Box<Error>> {
+    let image = decode_ktx2(data)?;
+    match image.format {
+        TextureFormat::RGBA8 | TextureFormat::SRGBA8 => {
+            Ok((image.data, Extent2D(image.width, image.height)))
+        }
+        _ => Err(DecodeImageError::UnsupportedFormat.into()),
+    }
 }
 
 fn get_image_format(mime_type: &str) -> ImageResult<ImageFormat> {
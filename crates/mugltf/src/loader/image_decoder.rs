@@ -0,0 +1,358 @@
+//! Decoding of glTF images into texture data.
+
+use super::GltfResourceLoader;
+use crate::Error;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use async_trait::async_trait;
+use core::{fmt, future::Future};
+use mugl::{Extent2D, TextureFormat};
+
+/// Mime type of KTX2 images, as used by KHR_texture_basisu.
+pub const KTX2_MIME_TYPE: &str = "image/ktx2";
+
+/// KTX2 file identifier.
+const KTX2_IDENTIFIER: &[u8] = &[
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+/// Byte length of the KTX2 header and index, up to the level index.
+const KTX2_HEADER_LENGTH: usize = 80;
+
+/// A decoded image, ready to be uploaded to a texture.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodedImage {
+    /// Image width in pixels.
+    pub width: u32,
+    /// Image height in pixels.
+    pub height: u32,
+    /// Texture format of the pixel data.
+    pub format: TextureFormat,
+    /// Tightly packed pixel data, row by row.
+    pub data: Vec<u8>,
+}
+
+impl DecodedImage {
+    /// Returns the image size.
+    #[inline]
+    pub fn size(&self) -> Extent2D {
+        Extent2D(self.width, self.height)
+    }
+}
+
+/// Decoder of image files into [DecodedImage]s.
+pub trait ImageDecoder {
+    /// Decoding error type.
+    type Error: Into<Box<Error>>;
+
+    /// Decodes an image file of given mime type.
+    /// The mime type is empty if unknown, in which case the decoder may detect it with [image_mime_type].
+    fn decode(&self, data: &[u8], mime_type: &str) -> Result<DecodedImage, Self::Error>;
+}
+
+/// Asynchronous decoder of image files into [DecodedImage]s, which can offload decoding to other threads or workers.
+///
+/// Every [ImageDecoder] is an [AsyncImageDecoder] that decodes on the calling thread.
+/// Use [DelegateImageDecoder] to decode with a user-provided executor instead.
+#[async_trait(?Send)]
+pub trait AsyncImageDecoder {
+    /// Decoding error type.
+    type Error: Into<Box<Error>>;
+
+    /// Decodes an image file of given mime type.
+    /// The mime type is empty if unknown, in which case the decoder may detect it with [image_mime_type].
+    async fn decode(&self, data: Vec<u8>, mime_type: &str) -> Result<DecodedImage, Self::Error>;
+}
+
+#[async_trait(?Send)]
+impl<D: ImageDecoder> AsyncImageDecoder for D {
+    type Error = D::Error;
+
+    #[inline]
+    async fn decode(&self, data: Vec<u8>, mime_type: &str) -> Result<DecodedImage, Self::Error> {
+        ImageDecoder::decode(self, &data, mime_type)
+    }
+}
+
+/// An [AsyncImageDecoder] that delegates decoding to a function returning a future,
+/// e.g. one that sends the image file to a thread pool or Web Worker, and resolves when it is decoded.
+///
+/// As images are decoded concurrently by [GltfAsset::load_resources](crate::GltfAsset::load_resources),
+/// the executor can decode multiple images in parallel without blocking the loading thread.
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[cfg(feature = "file-loader")]
+/// # async fn load() -> Result<(), mugltf::LoadGltfResourceError> {
+/// use mugltf::{
+///     DecodeImageError, DecodedImage, DelegateImageDecoder, GltfAsset, GltfResourceFileLoader,
+///     ImageDecodingLoader,
+/// };
+///
+/// /// Decodes an image on a worker thread.
+/// async fn decode_on_worker(
+///     data: Vec<u8>,
+///     mime_type: String,
+/// ) -> Result<DecodedImage, DecodeImageError> {
+///     // Send the image to a thread pool, and await its result
+/// #   unimplemented!()
+/// }
+///
+/// let decoder = DelegateImageDecoder::new(decode_on_worker);
+/// let loader = ImageDecodingLoader::new(GltfResourceFileLoader::default(), decoder);
+/// let asset = GltfAsset::load(&loader, "model.gltf", true).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DelegateImageDecoder<F> {
+    decode: F,
+}
+
+impl<F> DelegateImageDecoder<F> {
+    /// Creates a decoder that delegates to given function, which takes the image file and its mime type.
+    #[inline]
+    pub fn new(decode: F) -> Self {
+        Self { decode }
+    }
+}
+
+#[async_trait(?Send)]
+impl<F, Fut, E> AsyncImageDecoder for DelegateImageDecoder<F>
+where
+    F: Fn(Vec<u8>, String) -> Fut,
+    Fut: Future<Output = Result<DecodedImage, E>>,
+    E: Into<Box<Error>>,
+{
+    type Error = E;
+
+    #[inline]
+    async fn decode(&self, data: Vec<u8>, mime_type: &str) -> Result<DecodedImage, Self::Error> {
+        (self.decode)(data, mime_type.to_string()).await
+    }
+}
+
+/// Error when decoding an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeImageError {
+    /// The image file is malformed.
+    InvalidData,
+    /// The image file type or pixel format is not supported.
+    UnsupportedFormat,
+}
+
+impl fmt::Display for DecodeImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeImageError::InvalidData => write!(f, "invalid image data"),
+            DecodeImageError::UnsupportedFormat => write!(f, "unsupported image format"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeImageError {}
+
+#[cfg(not(feature = "std"))]
+impl From<DecodeImageError> for Box<Error> {
+    #[inline]
+    fn from(error: DecodeImageError) -> Self {
+        Box::new(error)
+    }
+}
+
+/// Detects the mime type of an image file from its signature.
+/// Returns `None` if it is not a PNG, JPEG or KTX2 image.
+pub fn image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(KTX2_IDENTIFIER) {
+        Some(KTX2_MIME_TYPE)
+    } else {
+        None
+    }
+}
+
+/// Decodes the base level of a 2D KTX2 image without supercompression.
+///
+/// Only uncompressed 8-bit UNORM / sRGB and floating point formats are supported.
+/// Basis Universal encoded images need to be transcoded, and are rejected as [DecodeImageError::UnsupportedFormat].
+/// See: <https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html>
+pub fn decode_ktx2(data: &[u8]) -> Result<DecodedImage, DecodeImageError> {
+    if data.len() < KTX2_HEADER_LENGTH + 24 || !data.starts_with(KTX2_IDENTIFIER) {
+        return Err(DecodeImageError::InvalidData);
+    }
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    };
+    let read_u64 = |offset: usize| read_u32(offset) as u64 | (read_u32(offset + 4) as u64) << 32;
+
+    let format = ktx2_texture_format(read_u32(12)).ok_or(DecodeImageError::UnsupportedFormat)?;
+    let (width, height, depth) = (read_u32(20), read_u32(24), read_u32(28));
+    let (layers, faces, supercompression) = (read_u32(32), read_u32(36), read_u32(44));
+    if depth > 0 || layers > 1 || faces != 1 || supercompression != 0 {
+        return Err(DecodeImageError::UnsupportedFormat);
+    }
+
+    // The level index starts with the base level
+    let offset = read_u64(KTX2_HEADER_LENGTH) as usize;
+    let length = read_u64(KTX2_HEADER_LENGTH + 8) as usize;
+    let expected = (width as usize)
+        .checked_mul(height.max(1) as usize)
+        .and_then(|pixels| pixels.checked_mul(format.size() as usize));
+    if expected != Some(length)
+        || offset
+            .checked_add(length)
+            .filter(|end| *end <= data.len())
+            .is_none()
+    {
+        return Err(DecodeImageError::InvalidData);
+    }
+
+    Ok(DecodedImage {
+        width,
+        height: height.max(1),
+        format,
+        data: data[offset..(offset + length)].to_vec(),
+    })
+}
+
+/// Maps a Vulkan format of KTX2 to a texture format.
+fn ktx2_texture_format(vk_format: u32) -> Option<TextureFormat> {
+    Some(match vk_format {
+        9 => TextureFormat::R8,
+        16 => TextureFormat::RG8,
+        37 => TextureFormat::RGBA8,
+        43 => TextureFormat::SRGBA8,
+        76 => TextureFormat::R16F,
+        83 => TextureFormat::RG16F,
+        97 => TextureFormat::RGBA16F,
+        100 => TextureFormat::R32F,
+        103 => TextureFormat::RG32F,
+        109 => TextureFormat::RGBA32F,
+        _ => return None,
+    })
+}
+
+/// Default [ImageDecoder] for PNG, JPEG and KTX2 images.
+///
+/// PNG and JPEG images are decoded by the [image] crate, and always converted to [TextureFormat::RGBA8].
+/// KTX2 images are decoded by [decode_ktx2].
+#[cfg(feature = "image-decode")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultImageDecoder;
+
+#[cfg(feature = "image-decode")]
+impl ImageDecoder for DefaultImageDecoder {
+    type Error = Box<Error>;
+
+    fn decode(&self, data: &[u8], mime_type: &str) -> Result<DecodedImage, Self::Error> {
+        let mime_type = if mime_type.is_empty() {
+            image_mime_type(data).unwrap_or_default()
+        } else {
+            mime_type
+        };
+        let format = match mime_type {
+            "image/png" => image::ImageFormat::Png,
+            "image/jpeg" => image::ImageFormat::Jpeg,
+            KTX2_MIME_TYPE => return Ok(decode_ktx2(data)?),
+            _ => return Err(DecodeImageError::UnsupportedFormat.into()),
+        };
+        let image = image::load_from_memory_with_format(data, format)?.into_rgba8();
+        Ok(DecodedImage {
+            width: image.width(),
+            height: image.height(),
+            format: TextureFormat::RGBA8,
+            data: image.into_raw(),
+        })
+    }
+}
+
+/// A [GltfResourceLoader] that loads images as [DecodedImage]s, by decoding the image files
+/// loaded by another loader with an [ImageDecoder] or [AsyncImageDecoder].
+///
+/// # Examples
+/// ```rust,no_run
+/// # #[cfg(all(feature = "image-decode", feature = "file-loader"))]
+/// # async fn load() -> Result<(), mugltf::LoadGltfResourceError> {
+/// use mugltf::{DefaultImageDecoder, GltfAsset, GltfResourceFileLoader, ImageDecodingLoader};
+///
+/// let loader = ImageDecodingLoader::new(GltfResourceFileLoader::default(), DefaultImageDecoder);
+/// let asset = GltfAsset::load(&loader, "model.gltf", true).await?;
+/// let image = &asset.images[0];
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ImageDecodingLoader<L, D> {
+    loader: L,
+    decoder: D,
+}
+
+impl<L, D> ImageDecodingLoader<L, D> {
+    /// Creates a loader that decodes the images loaded by `loader` using `decoder`.
+    #[inline]
+    pub fn new(loader: L, decoder: D) -> Self {
+        Self { loader, decoder }
+    }
+
+    /// Returns the wrapped loader.
+    #[inline]
+    pub fn loader(&self) -> &L {
+        &self.loader
+    }
+
+    /// Returns the image decoder.
+    #[inline]
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+}
+
+#[async_trait(?Send)]
+impl<L: GltfResourceLoader, D: AsyncImageDecoder> GltfResourceLoader for ImageDecodingLoader<L, D> {
+    type Error = Box<Error>;
+    type ImageData = DecodedImage;
+
+    #[inline]
+    fn set_path(&mut self, path: &str) {
+        self.loader.set_path(path);
+    }
+
+    async fn get_gltf(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        self.loader.get_gltf(uri).await.map_err(Into::into)
+    }
+
+    async fn get_buffer(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        self.loader.get_buffer(uri).await.map_err(Into::into)
+    }
+
+    async fn get_image(&self, uri: &str) -> Result<Self::ImageData, Self::Error> {
+        let data = self.loader.get_image_file(uri).await.map_err(Into::into)?;
+        self.decoder.decode(data, "").await.map_err(Into::into)
+    }
+
+    async fn get_image_file(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        self.loader.get_image_file(uri).await.map_err(Into::into)
+    }
+
+    async fn decode_image(
+        &self,
+        image: &[u8],
+        mime_type: &str,
+    ) -> Result<Self::ImageData, Self::Error> {
+        self.decoder
+            .decode(image.to_vec(), mime_type)
+            .await
+            .map_err(Into::into)
+    }
+}
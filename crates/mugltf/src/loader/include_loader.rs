@@ -0,0 +1,237 @@
+//! Loader of glTF resources embedded in the binary.
+
+use super::GltfResourceLoader;
+use crate::Error;
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use async_trait::async_trait;
+use core::fmt;
+
+/// Loader of glTF resources from byte slices embedded in the binary, e.g. using `include_bytes!`,
+/// for platforms without file system or fetch.
+///
+/// Resources are registered by path. Relative URIs are percent-decoded and resolved against the root path
+/// using [resolve_uri], so that assets reference their resources the same way as on a file system.
+/// Base64 data URIs are decoded. Images are loaded as undecoded image files,
+/// which can be decoded by wrapping this loader in an [ImageDecodingLoader](crate::ImageDecodingLoader).
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use mugltf::{GltfAsset, GltfResourceIncludeLoader, GltfResourceLoader};
+///
+/// # let (gltf, bin) = (br#"{"asset":{"version":"2.0"},"buffers":[{"uri":"bin/model.bin","byteLength":4}]}"#, &[1, 2, 3, 4]);
+/// // e.g. include_bytes!("../assets/model.gltf")
+/// let mut loader = GltfResourceIncludeLoader::new();
+/// loader.insert("assets/model.gltf", gltf);
+/// loader.insert("assets/bin/model.bin", bin);
+/// loader.set_path("assets");
+///
+/// let asset = pollster::block_on(GltfAsset::load(&loader, "model.gltf", true)).unwrap();
+/// assert_eq!(asset.buffers[0], [1, 2, 3, 4]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GltfResourceIncludeLoader<'a> {
+    path: String,
+    resources: BTreeMap<String, &'a [u8]>,
+}
+
+impl<'a> GltfResourceIncludeLoader<'a> {
+    /// Creates a loader with no resources.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of resources.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns true if there is no resource.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Registers a resource at given path, which is normalized using [resolve_uri].
+    /// Returns the resource previously registered at the path.
+    pub fn insert(&mut self, path: &str, data: &'a [u8]) -> Option<&'a [u8]> {
+        self.resources.insert(resolve_uri("", path), data)
+    }
+
+    /// Gets the resource of a URI relative to the root path.
+    pub fn get(&self, uri: &str) -> Option<&'a [u8]> {
+        self.resources.get(&resolve_uri(&self.path, uri)).copied()
+    }
+
+    fn read(&self, uri: &str) -> Result<Vec<u8>, IncludeResourceError> {
+        if let Some(data) = uri.strip_prefix("data:") {
+            return decode_data_uri(data).ok_or(IncludeResourceError::InvalidDataUri);
+        }
+        self.get(uri)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| IncludeResourceError::NotFound(resolve_uri(&self.path, uri)))
+    }
+}
+
+impl<'a, 'b> FromIterator<(&'b str, &'a [u8])> for GltfResourceIncludeLoader<'a> {
+    fn from_iter<T: IntoIterator<Item = (&'b str, &'a [u8])>>(iter: T) -> Self {
+        let mut loader = Self::new();
+        for (path, data) in iter {
+            loader.insert(path, data);
+        }
+        loader
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> GltfResourceLoader for GltfResourceIncludeLoader<'a> {
+    type Error = Box<Error>;
+    type ImageData = Vec<u8>;
+
+    #[inline]
+    fn set_path(&mut self, path: &str) {
+        self.path = path.into();
+    }
+
+    async fn get_gltf(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.read(uri)?)
+    }
+
+    async fn get_buffer(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.read(uri)?)
+    }
+
+    async fn get_image(&self, uri: &str) -> Result<Self::ImageData, Self::Error> {
+        Ok(self.read(uri)?)
+    }
+
+    async fn decode_image(
+        &self,
+        image: &[u8],
+        _mime_type: &str,
+    ) -> Result<Self::ImageData, Self::Error> {
+        Ok(image.to_vec())
+    }
+}
+
+/// Error when loading an embedded resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IncludeResourceError {
+    /// No resource is registered at the resolved path.
+    NotFound(String),
+    /// The data URI is not base64 encoded, or its data is malformed.
+    InvalidDataUri,
+}
+
+impl fmt::Display for IncludeResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IncludeResourceError::NotFound(path) => write!(f, "resource not found: {}", path),
+            IncludeResourceError::InvalidDataUri => write!(f, "invalid data URI"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IncludeResourceError {}
+
+#[cfg(not(feature = "std"))]
+impl From<IncludeResourceError> for Box<Error> {
+    #[inline]
+    fn from(error: IncludeResourceError) -> Self {
+        Box::new(error)
+    }
+}
+
+/// Resolves a relative URI against a root path into a normalized `/`-separated path.
+///
+/// The URI is percent-decoded, and both `/` and `\` are treated as path separators.
+/// `.` and `..` segments are resolved lexically. Decoded bytes that are not valid UTF-8 are replaced.
+///
+/// # Examples
+/// ```
+/// # use mugltf::include_loader::resolve_uri;
+/// assert_eq!(resolve_uri("assets/", "./bin\\..\\model%20data.bin"), "assets/model data.bin");
+/// assert_eq!(resolve_uri("", "../model.bin"), "../model.bin");
+/// ```
+pub fn resolve_uri(root: &str, uri: &str) -> String {
+    let mut segments = Vec::<String>::new();
+    let root = root.split(['/', '\\']).map(|s| s.as_bytes().to_vec());
+    let uri = percent_decode(uri);
+    let uri = uri.split(|&b| b == b'/' || b == b'\\').map(<[u8]>::to_vec);
+    for segment in root.chain(uri) {
+        match segment.as_slice() {
+            b"" | b"." => {}
+            b".." => {
+                if segments.last().map_or(true, |last| last == "..") {
+                    segments.push("..".to_string());
+                } else {
+                    segments.pop();
+                }
+            }
+            _ => segments.push(String::from_utf8_lossy(&segment).into_owned()),
+        }
+    }
+    segments.join("/")
+}
+
+/// Decodes the percent-encoded bytes of a URI.
+pub(crate) fn percent_decode(uri: &str) -> Vec<u8> {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// Decodes the data of a base64 data URI, given the part after `data:`.
+/// Returns `None` if the URI is not base64 encoded or is malformed.
+fn decode_data_uri(uri: &str) -> Option<Vec<u8>> {
+    let (_mime_type, data) = uri.split_once(";base64,")?;
+    let data = data.trim_end_matches('=').as_bytes();
+    let mut decoded = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let value = match c {
+                b'A'..=b'Z' => c - b'A',
+                b'a'..=b'z' => c - b'a' + 26,
+                b'0'..=b'9' => c - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return None,
+            };
+            bits |= (value as u32) << (18 - 6 * i);
+        }
+        decoded.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(decoded)
+}
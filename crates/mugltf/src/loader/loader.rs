@@ -23,6 +23,12 @@ pub trait GltfResourceLoader {
     /// Loads an image from path or data url.
     async fn get_image(&self, uri: &str) -> Result<Self::ImageData, Self::Error>;
 
+    /// Loads an image file from path or data url into bytes, without decoding it.
+    /// Defaults to [GltfResourceLoader::get_buffer].
+    async fn get_image_file(&self, uri: &str) -> Result<Vec<u8>, Self::Error> {
+        self.get_buffer(uri).await
+    }
+
     /// Decodes an image file binary of given mime type as image data.
     async fn decode_image(
         &self,
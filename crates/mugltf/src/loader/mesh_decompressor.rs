@@ -0,0 +1,196 @@
+//! Pluggable mesh decompression for KHR_draco_mesh_compression.
+
+use crate::{
+    model::{Accessor, Buffer, BufferView, BufferViewTarget, Id},
+    Error, GltfAsset, LoadGltfResourceError, LoadGltfResourceErrorKind,
+};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use async_trait::async_trait;
+
+/// Name of the Draco mesh compression extension.
+pub const KHR_DRACO_MESH_COMPRESSION: &str = "KHR_draco_mesh_compression";
+
+/// Decompressor of Draco compressed mesh primitives, e.g. a binding to an external Draco decoder.
+/// See [GltfAsset::decompress_meshes].
+#[async_trait(?Send)]
+pub trait MeshDecompressor {
+    /// Decompression error type.
+    type Error: Into<Box<Error>>;
+
+    /// Decodes a compressed Draco geometry.
+    ///
+    /// Returns the decoded data of `indices` (if any) and each of `attributes`,
+    /// tightly packed in the component type, type and count of their accessors.
+    async fn decompress(
+        &self,
+        data: &[u8],
+        indices: Option<&Accessor>,
+        attributes: &[DecompressAttribute<'_>],
+    ) -> Result<DecompressedMesh, Self::Error>;
+}
+
+/// A compressed attribute to decode.
+#[derive(Clone, Copy, Debug)]
+pub struct DecompressAttribute<'a> {
+    /// Attribute semantic, e.g. `POSITION`.
+    pub name: &'a str,
+    /// Draco unique ID of the attribute.
+    pub id: Id,
+    /// Accessor defining the decoded data layout.
+    pub accessor: &'a Accessor,
+}
+
+/// Decoded data of a mesh primitive. See [MeshDecompressor::decompress].
+#[derive(Clone, Debug, Default)]
+pub struct DecompressedMesh {
+    /// Index data. Ignored if the primitive has no indices.
+    pub indices: Vec<u8>,
+    /// Data of each attribute, in the requested order.
+    pub attributes: Vec<Vec<u8>>,
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Decompresses all Draco compressed mesh primitives, so that their accessors read the decoded data.
+    /// Resources of the asset must be loaded.
+    ///
+    /// The decoded data is stored in a new buffer with no URI, and the accessors of each decompressed primitive
+    /// are redirected to new buffer views of that buffer.
+    /// The extension is then removed from the primitives and [Gltf::extensions_required](crate::model::Gltf::extensions_required).
+    /// The asset is left unchanged on error.
+    pub async fn decompress_meshes<D: MeshDecompressor>(
+        &mut self,
+        decompressor: &D,
+    ) -> Result<(), LoadGltfResourceError> {
+        let buffer = self.buffers.len();
+        let mut data = Vec::new();
+        let mut redirects = Vec::new();
+        let mut decompressed = Vec::new();
+
+        for mesh_id in 0..self.gltf.meshes.len() {
+            for primitive_id in 0..self.gltf.meshes[mesh_id].primitives.len() {
+                let error = || {
+                    LoadGltfResourceError::from(LoadGltfResourceErrorKind::DecompressMeshError(
+                        mesh_id,
+                    ))
+                };
+                let primitive = &self.gltf.meshes[mesh_id].primitives[primitive_id];
+                let draco = match primitive
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.draco_mesh_compression.as_ref())
+                {
+                    Some(draco) => draco,
+                    None => continue,
+                };
+
+                // Accessors to redirect, with indices first
+                let mut accessors = Vec::with_capacity(draco.attributes.len() + 1);
+                let indices = match primitive.indices {
+                    Some(indices) => {
+                        accessors.push(indices);
+                        Some(self.gltf.accessors.get(indices).ok_or_else(error)?)
+                    }
+                    None => None,
+                };
+                let mut attributes = Vec::with_capacity(draco.attributes.len());
+                for (name, id) in &draco.attributes {
+                    let accessor = *primitive.attributes.get(name).ok_or_else(error)?;
+                    accessors.push(accessor);
+                    attributes.push(DecompressAttribute {
+                        name,
+                        id: *id,
+                        accessor: self.gltf.accessors.get(accessor).ok_or_else(error)?,
+                    });
+                }
+
+                let compressed = self
+                    .gltf
+                    .buffer_views
+                    .get(draco.buffer_view)
+                    .and_then(|view| {
+                        self.buffers
+                            .get(view.buffer)?
+                            .get(view.byte_offset..(view.byte_offset + view.byte_length))
+                    })
+                    .ok_or_else(error)?;
+                let mesh = decompressor
+                    .decompress(compressed, indices, &attributes)
+                    .await
+                    .map_err(|err| {
+                        LoadGltfResourceError::new(
+                            LoadGltfResourceErrorKind::DecompressMeshError(mesh_id),
+                            err,
+                        )
+                    })?;
+
+                let decoded = indices
+                    .map(|_| mesh.indices)
+                    .into_iter()
+                    .chain(mesh.attributes);
+                let mut views = Vec::with_capacity(accessors.len());
+                for (i, (accessor, decoded)) in accessors.iter().zip(decoded).enumerate() {
+                    let accessor = &self.gltf.accessors[*accessor];
+                    if decoded.len()
+                        != accessor.count
+                            * accessor.ty.components()
+                            * accessor.component_type.size()
+                    {
+                        return Err(error());
+                    }
+                    data.resize((data.len() + 3) & !3, 0);
+                    views.push(BufferView {
+                        buffer,
+                        byte_offset: data.len(),
+                        byte_length: decoded.len(),
+                        target: Some(if indices.is_some() && i == 0 {
+                            BufferViewTarget::Index
+                        } else {
+                            BufferViewTarget::Vertex
+                        }),
+                        ..Default::default()
+                    });
+                    data.extend_from_slice(&decoded);
+                }
+                if views.len() != accessors.len() {
+                    return Err(error());
+                }
+
+                redirects.extend(accessors.into_iter().zip(views));
+                decompressed.push((mesh_id, primitive_id));
+            }
+        }
+
+        // Only modify the asset after all primitives are decoded successfully
+        for (accessor, view) in redirects {
+            self.gltf.buffer_views.push(view);
+            let accessor = &mut self.gltf.accessors[accessor];
+            accessor.buffer_view = Some(self.gltf.buffer_views.len() - 1);
+            accessor.byte_offset = 0;
+        }
+        for (mesh_id, primitive_id) in decompressed {
+            if let Some(extensions) =
+                &mut self.gltf.meshes[mesh_id].primitives[primitive_id].extensions
+            {
+                extensions.draco_mesh_compression = None;
+            }
+        }
+
+        if !data.is_empty() {
+            self.gltf.buffers.push(Buffer {
+                uri: String::new(),
+                byte_length: data.len(),
+                #[cfg(feature = "gltf-name")]
+                name: String::new(),
+                #[cfg(feature = "gltf-extras")]
+                extras: Default::default(),
+                extensions: Default::default(),
+            });
+            self.buffers.push(data);
+        }
+        self.gltf
+            .extensions_required
+            .retain(|name| name != KHR_DRACO_MESH_COMPRESSION);
+
+        Ok(())
+    }
+}
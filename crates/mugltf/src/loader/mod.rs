@@ -1,7 +1,17 @@
+mod image_decoder;
 mod loader;
 
+pub use image_decoder::*;
 pub use loader::*;
 
+pub mod include_loader;
+pub use include_loader::GltfResourceIncludeLoader;
+
+#[cfg(feature = "gltf-extensions")]
+mod mesh_decompressor;
+#[cfg(feature = "gltf-extensions")]
+pub use mesh_decompressor::*;
+
 #[cfg(feature = "file-loader")]
 pub mod file_loader;
 #[cfg(feature = "file-loader")]
@@ -10,4 +20,4 @@ pub use file_loader::GltfResourceFileLoader;
 #[cfg(feature = "fetch-loader")]
 pub mod fetch_loader;
 #[cfg(feature = "fetch-loader")]
-pub use fetch_loader::GltfResourceFetchLoader;
+pub use fetch_loader::{FetchByteStream, GltfResourceFetchLoader};
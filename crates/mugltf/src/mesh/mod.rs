@@ -0,0 +1,7 @@
+//! glTF mesh utilities.
+
+mod simplify;
+mod skinning;
+
+pub use simplify::*;
+pub use skinning::*;
@@ -0,0 +1,495 @@
+//! Mesh simplification for level of detail (LOD) generation.
+
+use crate::accessor::{read_accessor_floats, read_accessor_indices};
+use crate::model::{
+    Accessor, AccessorComponentType, AccessorType, Buffer, BufferView, BufferViewTarget, Float,
+    Gltf, Id, Mesh, MeshPrimitive,
+};
+use crate::GltfAsset;
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use core::cmp::Ordering;
+use mugl::PrimitiveTopology;
+use munum::Vec3;
+
+/// Options of the mesh LOD generation pass.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshSimplification {
+    /// Maximum number of LOD levels to generate for each mesh.
+    pub lod_count: usize,
+    /// Target triangle count of each LOD level, relative to the previous level.
+    pub ratio: Float,
+    /// Maximum geometric error allowed, relative to the bounding box diagonal of each primitive.
+    pub max_error: Float,
+}
+
+impl Default for MeshSimplification {
+    fn default() -> Self {
+        Self {
+            lod_count: 3,
+            ratio: 0.5,
+            max_error: 0.01,
+        }
+    }
+}
+
+/// Quadric error matrix, stored as the upper triangle of a symmetric 4x4 matrix.
+type Quadric = [f64; 10];
+
+const SEAM: u8 = 1;
+const BORDER: u8 = 2;
+
+/// Simplifies a triangle list using quadric error metrics based edge collapses.
+/// `positions` contains the XYZ position of every vertex, and `indices` contains 3 vertex indices per triangle.
+///
+/// Vertices are collapsed onto existing vertices, so the result is a new index list that reuses the vertex data.
+/// Vertices on mesh borders and attribute seams (i.e. multiple vertices sharing the same position) are kept,
+/// which preserves the mesh outline and texture mapping.
+/// Simplification stops once `target_index_count` is reached, or when any further collapse
+/// would exceed `max_error` (in position units), so the result may have more indices than the target.
+///
+/// # Examples
+/// ```
+/// # use mugltf::mesh::simplify_triangles;
+/// // A flat 3x3 grid of vertices, with 8 triangles
+/// let mut positions = Vec::new();
+/// for y in 0..3 {
+///     for x in 0..3 {
+///         positions.extend_from_slice(&[x as f32, y as f32, 0.]);
+///     }
+/// }
+/// let mut indices = Vec::new();
+/// for y in 0..2 {
+///     for x in 0..2 {
+///         let i = y * 3 + x;
+///         indices.extend_from_slice(&[i, i + 1, i + 4, i, i + 4, i + 3]);
+///     }
+/// }
+///
+/// // The center vertex can be removed without any error
+/// let simplified = simplify_triangles(&positions, &indices, 0, 1e-3);
+/// assert_eq!(simplified.len(), 6 * 3);
+/// assert!(!simplified.contains(&4));
+/// ```
+pub fn simplify_triangles(
+    positions: &[Float],
+    indices: &[u32],
+    target_index_count: usize,
+    max_error: Float,
+) -> Vec<u32> {
+    let vertex_count = positions.len() / 3;
+    let mut result: Vec<u32> = indices
+        .chunks_exact(3)
+        .filter(|t| {
+            t.iter().all(|&i| (i as usize) < vertex_count) && !is_degenerate(t[0], t[1], t[2])
+        })
+        .flatten()
+        .copied()
+        .collect();
+    let position = |i: u32| {
+        let i = i as usize * 3;
+        Vec3::<f64>::new([[
+            positions[i] as f64,
+            positions[i + 1] as f64,
+            positions[i + 2] as f64,
+        ]])
+    };
+
+    // Find the vertices that must be kept
+    let mut flags = vec![0_u8; vertex_count];
+    let mut welded = BTreeMap::<[u32; 3], u32>::new();
+    let mut edges = BTreeMap::<(u32, u32), usize>::new();
+    for triangle in result.chunks_exact(3) {
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+
+            let p = a as usize * 3;
+            let key = [
+                positions[p].to_bits(),
+                positions[p + 1].to_bits(),
+                positions[p + 2].to_bits(),
+            ];
+            let first = *welded.entry(key).or_insert(a);
+            if first != a {
+                flags[first as usize] |= SEAM;
+                flags[a as usize] |= SEAM;
+            }
+        }
+    }
+    for ((a, b), count) in edges {
+        if count != 2 {
+            flags[a as usize] |= BORDER;
+            flags[b as usize] |= BORDER;
+        }
+    }
+
+    // Accumulate the plane quadrics of adjacent triangles for each vertex
+    let mut quadrics = vec![[0.; 10]; vertex_count];
+    for triangle in result.chunks_exact(3) {
+        let p0 = position(triangle[0]);
+        let normal = (position(triangle[1]) - p0)
+            .cross(position(triangle[2]) - p0)
+            .normalized();
+        let quadric = plane_quadric(normal, -normal.dot(p0));
+        for &i in triangle {
+            add_quadric(&mut quadrics[i as usize], &quadric);
+        }
+    }
+
+    let max_cost = max_error as f64 * max_error as f64;
+    while result.len() > target_index_count {
+        let mut adjacency = vec![Vec::new(); vertex_count];
+        for (t, triangle) in result.chunks_exact(3).enumerate() {
+            for &i in triangle {
+                adjacency[i as usize].push(t);
+            }
+        }
+
+        // Find the edge collapses within error bound, cheapest first
+        let mut candidates = Vec::new();
+        for triangle in result.chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                for (from, to) in [(a, b), (b, a)] {
+                    if flags[from as usize] != 0 || flags[to as usize] & SEAM != 0 {
+                        continue;
+                    }
+                    let mut quadric = quadrics[from as usize];
+                    add_quadric(&mut quadric, &quadrics[to as usize]);
+                    let cost = quadric_error(&quadric, position(to));
+                    if cost <= max_cost {
+                        candidates.push((cost, from, to));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+        // Collapse independent edges, so that each triangle is modified at most once per pass
+        let mut collapse: Vec<u32> = (0..vertex_count as u32).collect();
+        let mut touched = vec![false; vertex_count];
+        let mut index_count = result.len();
+        for (_, from, to) in candidates {
+            if index_count <= target_index_count {
+                break;
+            }
+            if touched[from as usize] || touched[to as usize] {
+                continue;
+            }
+            let triangles = &adjacency[from as usize];
+            if triangles
+                .iter()
+                .any(|&t| flips(&result[(t * 3)..(t * 3 + 3)], from, to, &position))
+            {
+                continue;
+            }
+
+            collapse[from as usize] = to;
+            for &t in triangles {
+                let triangle = &result[(t * 3)..(t * 3 + 3)];
+                if triangle.contains(&to) {
+                    index_count -= 3;
+                }
+                for &i in triangle {
+                    touched[i as usize] = true;
+                }
+            }
+            let quadric = quadrics[from as usize];
+            add_quadric(&mut quadrics[to as usize], &quadric);
+        }
+
+        let len = result.len();
+        result = result
+            .chunks_exact(3)
+            .map(|t| {
+                [
+                    collapse[t[0] as usize],
+                    collapse[t[1] as usize],
+                    collapse[t[2] as usize],
+                ]
+            })
+            .filter(|t| !is_degenerate(t[0], t[1], t[2]))
+            .flatten()
+            .collect();
+        if result.len() == len {
+            break;
+        }
+    }
+
+    result
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Generates simplified LOD meshes for every mesh of the asset.
+    /// Resources of the asset must be loaded, otherwise nothing is changed.
+    ///
+    /// Each LOD level is added as a new mesh that shares the vertex data of the source mesh,
+    /// with new index accessors backed by a new buffer (without URI) appended to the asset.
+    /// Only triangle list primitives are simplified. Other primitives are shared by all levels as-is.
+    /// Generation of a mesh stops early when a level cannot be simplified further.
+    ///
+    /// With the `gltf-extras` feature, the LOD meshes of each source mesh are recorded in its extras as
+    /// `"lods": [{ "mesh": <mesh id>, "ratio": <target triangle ratio> }, ...]`, ordered from the most detailed.
+    /// Returns the number of LOD meshes created.
+    pub fn generate_lods(&mut self, options: MeshSimplification) -> usize {
+        if self.buffers.len() != self.gltf.buffers.len() {
+            return 0;
+        }
+
+        let buffer_id = self.gltf.buffers.len();
+        let mut data = Vec::new();
+        let mut created = 0;
+
+        for mesh_id in 0..self.gltf.meshes.len() {
+            let sources: Vec<Option<(Vec<Float>, Vec<u32>)>> = self.gltf.meshes[mesh_id]
+                .primitives
+                .iter()
+                .map(|primitive| read_triangles(&self.gltf, &self.buffers, primitive))
+                .collect();
+            let mut index_counts: Vec<usize> = sources
+                .iter()
+                .map(|source| source.as_ref().map_or(0, |(_, indices)| indices.len()))
+                .collect();
+
+            let mut lods = Vec::new();
+            let mut ratio = 1.;
+            for _ in 0..options.lod_count {
+                ratio *= options.ratio;
+                let simplified: Vec<Option<Vec<u32>>> = sources
+                    .iter()
+                    .map(|source| {
+                        source.as_ref().map(|(positions, indices)| {
+                            let target = ((indices.len() / 3) as Float * ratio) as usize * 3;
+                            let max_error = options.max_error * bounding_diagonal(positions);
+                            simplify_triangles(positions, indices, target, max_error)
+                        })
+                    })
+                    .collect();
+                if !simplified.iter().zip(&index_counts).any(
+                    |(indices, count)| matches!(indices, Some(indices) if indices.len() < *count),
+                ) {
+                    break;
+                }
+
+                let mut primitives = self.gltf.meshes[mesh_id].primitives.clone();
+                for (primitive_id, indices) in simplified.iter().enumerate() {
+                    if let (Some(indices), Some((positions, _))) = (indices, &sources[primitive_id])
+                    {
+                        index_counts[primitive_id] = indices.len();
+                        primitives[primitive_id].indices = Some(push_indices(
+                            &mut self.gltf,
+                            &mut data,
+                            buffer_id,
+                            indices,
+                            positions.len() / 3,
+                        ));
+                    }
+                }
+
+                let source = &self.gltf.meshes[mesh_id];
+                let lod = Mesh {
+                    primitives,
+                    weights: source.weights.clone(),
+                    #[cfg(feature = "gltf-name")]
+                    name: if source.name.is_empty() {
+                        String::new()
+                    } else {
+                        alloc::format!("{}_LOD{}", source.name, lods.len() + 1)
+                    },
+                    #[cfg(feature = "gltf-extras")]
+                    extras: Default::default(),
+                    #[cfg(feature = "gltf-extensions")]
+                    extensions: Default::default(),
+                };
+                self.gltf.meshes.push(lod);
+                lods.push((self.gltf.meshes.len() - 1, ratio));
+            }
+
+            created += lods.len();
+            #[cfg(feature = "gltf-extras")]
+            record_lods(&mut self.gltf.meshes[mesh_id], &lods);
+        }
+
+        if !data.is_empty() {
+            self.gltf.buffers.push(Buffer {
+                uri: String::new(),
+                byte_length: data.len(),
+                #[cfg(feature = "gltf-name")]
+                name: String::new(),
+                #[cfg(feature = "gltf-extras")]
+                extras: Default::default(),
+                #[cfg(feature = "gltf-extensions")]
+                extensions: Default::default(),
+            });
+            self.buffers.push(data);
+        }
+
+        created
+    }
+}
+
+/// Reads the positions and triangle indices of a triangle list primitive.
+fn read_triangles(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    primitive: &MeshPrimitive,
+) -> Option<(Vec<Float>, Vec<u32>)> {
+    if primitive.mode != PrimitiveTopology::Triangles {
+        return None;
+    }
+    let accessor = gltf.accessors.get(*primitive.attributes.get("POSITION")?)?;
+    if !matches!(accessor.ty, AccessorType::Vec3) {
+        return None;
+    }
+    let positions = read_accessor_floats(gltf, buffers, accessor)?;
+    let indices = match primitive.indices {
+        Some(id) => read_accessor_indices(gltf, buffers, gltf.accessors.get(id)?)?,
+        None => (0..accessor.count as u32).collect(),
+    };
+    Some((positions, indices))
+}
+
+/// Records the LOD meshes of a mesh in its extras, if the extras is an object or null.
+#[cfg(feature = "gltf-extras")]
+fn record_lods(mesh: &mut Mesh, lods: &[(Id, Float)]) {
+    if lods.is_empty() {
+        return;
+    }
+    if mesh.extras.is_null() {
+        mesh.extras = serde_json::Value::Object(Default::default());
+    }
+    if let Some(extras) = mesh.extras.as_object_mut() {
+        let lods = lods
+            .iter()
+            .map(|(mesh, ratio)| serde_json::json!({ "mesh": mesh, "ratio": ratio }))
+            .collect();
+        extras.insert("lods".into(), serde_json::Value::Array(lods));
+    }
+}
+
+/// Appends a vertex index accessor stored in a new buffer view of `data`.
+fn push_indices(
+    gltf: &mut Gltf,
+    data: &mut Vec<u8>,
+    buffer: Id,
+    indices: &[u32],
+    vertex_count: usize,
+) -> Id {
+    // 65535 is reserved as primitive restart value for unsigned short indices
+    let component_type = if vertex_count < u16::MAX as usize {
+        AccessorComponentType::UnsignedShort
+    } else {
+        AccessorComponentType::UnsignedInt
+    };
+
+    data.resize((data.len() + 3) & !3, 0);
+    let byte_offset = data.len();
+    for &index in indices {
+        match component_type {
+            AccessorComponentType::UnsignedShort => {
+                data.extend_from_slice(&(index as u16).to_le_bytes())
+            }
+            _ => data.extend_from_slice(&index.to_le_bytes()),
+        }
+    }
+
+    gltf.buffer_views.push(BufferView {
+        buffer,
+        byte_offset,
+        byte_length: data.len() - byte_offset,
+        target: Some(BufferViewTarget::Index),
+        ..Default::default()
+    });
+    gltf.accessors.push(Accessor {
+        buffer_view: Some(gltf.buffer_views.len() - 1),
+        byte_offset: 0,
+        component_type,
+        normalized: false,
+        count: indices.len(),
+        ty: AccessorType::Scalar,
+        max: Vec::new(),
+        min: Vec::new(),
+        sparse: None,
+        #[cfg(feature = "gltf-name")]
+        name: String::new(),
+        #[cfg(feature = "gltf-extras")]
+        extras: Default::default(),
+        #[cfg(feature = "gltf-extensions")]
+        extensions: Default::default(),
+    });
+    gltf.accessors.len() - 1
+}
+
+/// Calculates the length of the bounding box diagonal of a list of XYZ positions.
+fn bounding_diagonal(positions: &[Float]) -> Float {
+    let mut min = [Float::MAX; 3];
+    let mut max = [Float::MIN; 3];
+    for position in positions.chunks_exact(3) {
+        for c in 0..3 {
+            min[c] = min[c].min(position[c]);
+            max[c] = max[c].max(position[c]);
+        }
+    }
+    if positions.len() < 3 {
+        return 0.;
+    }
+    (Vec3::<Float>::from_slice(&max) - Vec3::<Float>::from_slice(&min)).len()
+}
+
+/// Checks if collapsing vertex `from` onto `to` flips the given triangle.
+fn flips(triangle: &[u32], from: u32, to: u32, position: &impl Fn(u32) -> Vec3<f64>) -> bool {
+    if triangle.contains(&to) {
+        return false;
+    }
+    let normal = |moved: u32| {
+        let p = |i: u32| position(if i == from { moved } else { i });
+        let p0 = p(triangle[0]);
+        (p(triangle[1]) - p0).cross(p(triangle[2]) - p0)
+    };
+    normal(from).dot(normal(to)) <= 0.
+}
+
+#[inline]
+fn is_degenerate(a: u32, b: u32, c: u32) -> bool {
+    a == b || b == c || a == c
+}
+
+/// Calculates the quadric of plane `n . p + d = 0`.
+fn plane_quadric(n: Vec3<f64>, d: f64) -> Quadric {
+    let (a, b, c) = (n[0], n[1], n[2]);
+    [
+        a * a,
+        a * b,
+        a * c,
+        a * d,
+        b * b,
+        b * c,
+        b * d,
+        c * c,
+        c * d,
+        d * d,
+    ]
+}
+
+#[inline]
+fn add_quadric(q: &mut Quadric, rhs: &Quadric) {
+    for (a, b) in q.iter_mut().zip(rhs) {
+        *a += b;
+    }
+}
+
+/// Evaluates the quadric error at the given position, i.e. the sum of squared distances to the planes.
+fn quadric_error(q: &Quadric, p: Vec3<f64>) -> f64 {
+    let (x, y, z) = (p[0], p[1], p[2]);
+    let error = q[0] * x * x
+        + 2. * q[1] * x * y
+        + 2. * q[2] * x * z
+        + 2. * q[3] * x
+        + q[4] * y * y
+        + 2. * q[5] * y * z
+        + 2. * q[6] * y
+        + q[7] * z * z
+        + 2. * q[8] * z
+        + q[9];
+    error.max(0.)
+}
@@ -0,0 +1,231 @@
+//! Software (CPU) skinning of mesh vertices.
+
+use crate::accessor::read_accessor_floats;
+use crate::model::{Float, Gltf, Id, Skin};
+use crate::GltfAsset;
+use alloc::{format, vec, vec::Vec};
+use munum::{Mat4, Vec3};
+
+/// Vertex data transformed by [skin_vertices].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SkinnedVertices {
+    /// XYZ position of every vertex.
+    pub positions: Vec<Float>,
+    /// Normalized XYZ normal of every vertex. Empty if the source mesh has no normal.
+    pub normals: Vec<Float>,
+}
+
+/// Applies linear blend skinning to vertex positions and normals on the CPU,
+/// e.g. for physics proxies, hit testing, or renderers without GPU skinning shaders.
+///
+/// `positions` and `normals` contain the XYZ values of every vertex, where `normals` may be empty.
+/// `joints` and `weights` contain the same number of joint influences for every vertex,
+/// i.e. 4 per vertex for each `JOINTS_n` / `WEIGHTS_n` attribute set.
+/// `joint_matrices` contains the skinning matrix of each joint, i.e. the inverse of the mesh node's world transform
+/// multiplied by the joint's world transform and inverse bind matrix.
+///
+/// Weights are normalized to sum to 1, and influences of joints out of range are ignored.
+/// Vertices without any influence are left unchanged.
+/// Normals are transformed by the blended skinning matrix and renormalized,
+/// which is exact for joint transforms without non-uniform scaling.
+/// Returns empty vertices if the joint influences do not match the number of vertices.
+///
+/// # Examples
+/// ```
+/// # use mugltf::mesh::skin_vertices;
+/// # use munum::{transform, Mat4, Vec3};
+/// let joint_matrices = [
+///     Mat4::identity(),
+///     transform::translation(Vec3::new([[2., 0., 0.]])),
+/// ];
+/// // A vertex influenced equally by both joints
+/// let skinned = skin_vertices(&[0., 1., 0.], &[0., 1., 0.], &[0., 1., 0., 0.], &[1., 1., 0., 0.], &joint_matrices);
+/// assert_eq!(skinned.positions, [1., 1., 0.]);
+/// assert_eq!(skinned.normals, [0., 1., 0.]);
+/// ```
+pub fn skin_vertices(
+    positions: &[Float],
+    normals: &[Float],
+    joints: &[Float],
+    weights: &[Float],
+    joint_matrices: &[Mat4],
+) -> SkinnedVertices {
+    let vertex_count = positions.len() / 3;
+    if vertex_count == 0
+        || joints.len() != weights.len()
+        || joints.len() % vertex_count != 0
+        || (!normals.is_empty() && normals.len() != positions.len())
+    {
+        return SkinnedVertices::default();
+    }
+    let influences = joints.len() / vertex_count;
+
+    let mut skinned = SkinnedVertices {
+        positions: Vec::with_capacity(vertex_count * 3),
+        normals: Vec::with_capacity(normals.len()),
+    };
+    for v in 0..vertex_count {
+        let position = &positions[(v * 3)..(v * 3 + 3)];
+        let normal = normals.get((v * 3)..(v * 3 + 3));
+
+        // Blend the joint matrices of the vertex
+        let mut matrix = [0.; 16];
+        let mut total_weight = 0.;
+        for i in (v * influences)..((v + 1) * influences) {
+            let weight = weights[i];
+            let joint = match joint_matrices.get(joints[i] as usize) {
+                Some(joint) if weight > 0. => joint.as_ref(),
+                _ => continue,
+            };
+            for (m, j) in matrix.iter_mut().zip(joint) {
+                *m += weight * j;
+            }
+            total_weight += weight;
+        }
+        if total_weight <= 0. {
+            skinned.positions.extend_from_slice(position);
+            if let Some(normal) = normal {
+                skinned.normals.extend_from_slice(normal);
+            }
+            continue;
+        }
+        for m in matrix.iter_mut() {
+            *m /= total_weight;
+        }
+
+        // Matrices are column-major
+        for r in 0..3 {
+            skinned.positions.push(
+                matrix[r] * position[0]
+                    + matrix[4 + r] * position[1]
+                    + matrix[8 + r] * position[2]
+                    + matrix[12 + r],
+            );
+        }
+        if let Some(normal) = normal {
+            let mut n = Vec3::<Float>::default();
+            for r in 0..3 {
+                n[r] =
+                    matrix[r] * normal[0] + matrix[4 + r] * normal[1] + matrix[8 + r] * normal[2];
+            }
+            n.normalize();
+            skinned.normals.extend_from_slice(n.as_ref());
+        }
+    }
+    skinned
+}
+
+impl Skin {
+    /// Computes the skinning matrix of every joint of this skin, ready to upload for GPU skinning
+    /// or to pass to [skin_vertices]: the world transform of the joint node multiplied by its inverse bind matrix.
+    ///
+    /// `global_transforms` contains the world transform of every node, indexed by node ID,
+    /// e.g. collected from [Gltf::scene_nodes]. Joints without inverse bind matrices use the identity.
+    /// As required by glTF, the transform of the skinned mesh node itself is ignored; to render the mesh in the
+    /// space of its node instead of world space, premultiply each matrix by the inverse of the node's world transform.
+    ///
+    /// Returns `None` if the inverse bind matrices cannot be resolved or are fewer than the joints,
+    /// or a joint has no world transform.
+    pub fn joint_matrices(
+        &self,
+        gltf: &Gltf,
+        buffers: &[Vec<u8>],
+        global_transforms: &[Mat4],
+    ) -> Option<Vec<Mat4>> {
+        let inverse_bind_matrices: Vec<Mat4> = match self.inverse_bind_matrices {
+            Some(accessor) => gltf
+                .accessors
+                .get(accessor)?
+                .decode::<16>(gltf, buffers)?
+                .map(|matrix| Mat4::from_slice(&matrix))
+                .collect(),
+            None => vec![Mat4::identity(); self.joints.len()],
+        };
+        if inverse_bind_matrices.len() < self.joints.len() {
+            return None;
+        }
+
+        self.joints
+            .iter()
+            .zip(inverse_bind_matrices)
+            .map(|(&joint, inverse_bind_matrix)| {
+                Some(*global_transforms.get(joint)? * inverse_bind_matrix)
+            })
+            .collect()
+    }
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Computes the skinning matrices of the joints of a skin from the world transform of every node.
+    /// See [Skin::joint_matrices].
+    ///
+    /// Returns `None` if the skin does not exist, or its joint matrices cannot be computed.
+    /// Resources of the asset must be loaded.
+    pub fn joint_matrices(&self, skin: Id, global_transforms: &[Mat4]) -> Option<Vec<Mat4>> {
+        self.gltf
+            .skins
+            .get(skin)?
+            .joint_matrices(&self.gltf, &self.buffers, global_transforms)
+    }
+
+    /// Skins the `POSITION` and `NORMAL` attributes of a mesh primitive on the CPU,
+    /// using all of its `JOINTS_n` / `WEIGHTS_n` attribute sets. See [skin_vertices].
+    ///
+    /// Returns `None` if the primitive does not exist, has no position or joint influences,
+    /// or its accessor data cannot be resolved. Resources of the asset must be loaded.
+    pub fn skin_primitive(
+        &self,
+        mesh: Id,
+        primitive: usize,
+        joint_matrices: &[Mat4],
+    ) -> Option<SkinnedVertices> {
+        let primitive = self.gltf.meshes.get(mesh)?.primitives.get(primitive)?;
+        let read_attribute = |name: &str| {
+            let accessor = self.gltf.accessors.get(*primitive.attributes.get(name)?)?;
+            read_accessor_floats(&self.gltf, &self.buffers, accessor)
+        };
+
+        let positions = read_attribute("POSITION")?;
+        let normals = if primitive.attributes.contains_key("NORMAL") {
+            read_attribute("NORMAL")?
+        } else {
+            Vec::new()
+        };
+
+        // Interleave the influences of every attribute set per vertex
+        let mut sets = Vec::new();
+        while let (Some(joints), Some(weights)) = (
+            primitive.attributes.get(&format!("JOINTS_{}", sets.len())),
+            primitive.attributes.get(&format!("WEIGHTS_{}", sets.len())),
+        ) {
+            let (joints, weights) = (
+                self.gltf.accessors.get(*joints)?,
+                self.gltf.accessors.get(*weights)?,
+            );
+            sets.push((
+                read_accessor_floats(&self.gltf, &self.buffers, joints)?,
+                read_accessor_floats(&self.gltf, &self.buffers, weights)?,
+            ));
+        }
+        if sets.is_empty() {
+            return None;
+        }
+        let vertex_count = positions.len() / 3;
+        let mut joints = Vec::with_capacity(vertex_count * 4 * sets.len());
+        let mut weights = Vec::with_capacity(vertex_count * 4 * sets.len());
+        for v in 0..vertex_count {
+            for (set_joints, set_weights) in &sets {
+                joints.extend_from_slice(set_joints.get((v * 4)..(v * 4 + 4))?);
+                weights.extend_from_slice(set_weights.get((v * 4)..(v * 4 + 4))?);
+            }
+        }
+
+        Some(skin_vertices(
+            &positions,
+            &normals,
+            &joints,
+            &weights,
+            joint_matrices,
+        ))
+    }
+}
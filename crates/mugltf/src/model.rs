@@ -315,6 +315,20 @@ pub enum AccessorType {
     Mat4,
 }
 
+impl AccessorType {
+    /// Returns the number of components of this type.
+    pub const fn components(&self) -> usize {
+        match self {
+            Self::Scalar => 1,
+            Self::Vec2 => 2,
+            Self::Vec3 => 3,
+            Self::Vec4 | Self::Mat2 => 4,
+            Self::Mat3 => 9,
+            Self::Mat4 => 16,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(
     feature = "serde",
@@ -330,6 +344,17 @@ pub enum AccessorComponentType {
     Float = gl_const::FLOAT,
 }
 
+impl AccessorComponentType {
+    /// Returns the size of this component type in bytes.
+    pub const fn size(&self) -> usize {
+        match self {
+            Self::Byte | Self::UnsignedByte => 1,
+            Self::Short | Self::UnsignedShort => 2,
+            Self::UnsignedInt | Self::Float => 4,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(
     feature = "serde",
@@ -342,6 +367,17 @@ pub enum AccessorIndicesComponentType {
     UnsignedInt = gl_const::UNSIGNED_INT,
 }
 
+impl AccessorIndicesComponentType {
+    /// Returns the size of this component type in bytes.
+    pub const fn size(&self) -> usize {
+        match self {
+            Self::UnsignedByte => 1,
+            Self::UnsignedShort => 2,
+            Self::UnsignedInt => 4,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
@@ -746,6 +782,8 @@ pub struct Material {
     pub normal_texture: Option<NormalTextureInfo>,
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub occlusion_texture: Option<OcclusionTextureInfo>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub emissive_texture: Option<TextureInfo>,
     pub emissive_factor: [Float; 3],
     pub alpha_mode: AlphaMode,
     pub alpha_cutoff: Float,
@@ -766,7 +804,7 @@ pub struct Material {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<MaterialExtensions>,
 }
 
 impl Default for Material {
@@ -775,6 +813,7 @@ impl Default for Material {
             pbr_metallic_roughness: None,
             normal_texture: None,
             occlusion_texture: None,
+            emissive_texture: None,
             emissive_factor: [0., 0., 0.],
             alpha_mode: Default::default(),
             alpha_cutoff: 0.5,
@@ -861,7 +900,7 @@ pub struct TextureInfo {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 #[derive(Clone, Debug)]
@@ -889,7 +928,7 @@ pub struct NormalTextureInfo {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 impl Default for NormalTextureInfo {
@@ -931,7 +970,7 @@ pub struct OcclusionTextureInfo {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<TextureInfoExtensions>,
 }
 
 impl Default for OcclusionTextureInfo {
@@ -948,7 +987,141 @@ impl Default for OcclusionTextureInfo {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Material extensions, with typed common extensions.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct MaterialExtensions {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_materials_emissive_strength"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub emissive_strength: Option<MaterialEmissiveStrength>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_materials_ior"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub ior: Option<MaterialIor>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_materials_unlit"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub unlit: Option<MaterialUnlit>,
+    /// Other extensions.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub others: Extensions,
+}
+
+/// KHR_materials_emissive_strength extension.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase"),
+    serde(default)
+)]
+#[repr(C)]
+pub struct MaterialEmissiveStrength {
+    pub emissive_strength: Float,
+}
+
+#[cfg(feature = "gltf-extensions")]
+impl Default for MaterialEmissiveStrength {
+    fn default() -> Self {
+        Self {
+            emissive_strength: 1.,
+        }
+    }
+}
+
+/// KHR_materials_ior extension.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase"),
+    serde(default)
+)]
+#[repr(C)]
+pub struct MaterialIor {
+    pub ior: Float,
+}
+
+#[cfg(feature = "gltf-extensions")]
+impl Default for MaterialIor {
+    fn default() -> Self {
+        Self { ior: 1.5 }
+    }
+}
+
+/// KHR_materials_unlit extension.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct MaterialUnlit {}
+
+/// Texture info extensions, with typed common extensions.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct TextureInfoExtensions {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_texture_transform"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub texture_transform: Option<TextureTransform>,
+    /// Other extensions.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub others: Extensions,
+}
+
+/// KHR_texture_transform extension.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase"),
+    serde(default)
+)]
+#[repr(C)]
+pub struct TextureTransform {
+    pub offset: [Float; 2],
+    pub rotation: Float,
+    pub scale: [Float; 2],
+    /// Overrides the texture coordinate set of the texture info.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub tex_coord: Option<Size>,
+}
+
+#[cfg(feature = "gltf-extensions")]
+impl Default for TextureTransform {
+    fn default() -> Self {
+        Self {
+            offset: [0., 0.],
+            rotation: 0.,
+            scale: [1., 1.],
+            tex_coord: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(
     feature = "serde",
     derive(serde::Serialize, serde::Deserialize),
@@ -1047,7 +1220,44 @@ pub struct MeshPrimitive {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<MeshPrimitiveExtensions>,
+}
+
+/// Mesh primitive extensions, with typed common extensions.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct MeshPrimitiveExtensions {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_draco_mesh_compression"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub draco_mesh_compression: Option<DracoMeshCompression>,
+    /// Other extensions.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub others: Extensions,
+}
+
+/// KHR_draco_mesh_compression extension.
+/// See [GltfAsset::decompress_meshes](crate::GltfAsset::decompress_meshes) for decoding.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[repr(C)]
+pub struct DracoMeshCompression {
+    /// Buffer view of the compressed Draco geometry.
+    pub buffer_view: Id,
+    /// Draco unique ID of each compressed attribute, by attribute semantic.
+    pub attributes: Map<String, Id>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -1097,7 +1307,49 @@ pub struct Node {
         serde(default),
         serde(skip_serializing_if = "Option::is_none")
     )]
-    pub extensions: Option<Extensions>,
+    pub extensions: Option<NodeExtensions>,
+}
+
+/// Node extensions, with typed common extensions.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct NodeExtensions {
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "KHR_node_visibility"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    pub visibility: Option<NodeVisibility>,
+    /// Other extensions.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub others: Extensions,
+}
+
+/// KHR_node_visibility extension.
+#[cfg(feature = "gltf-extensions")]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase"),
+    serde(default)
+)]
+#[repr(C)]
+pub struct NodeVisibility {
+    /// Whether the node and its descendants are visible.
+    pub visible: bool,
+}
+
+#[cfg(feature = "gltf-extensions")]
+impl Default for NodeVisibility {
+    fn default() -> Self {
+        Self { visible: true }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,211 @@
+//! Partial parsing of glTF documents.
+
+#![cfg(feature = "serde")]
+
+use crate::model::Gltf;
+use alloc::string::String;
+use core::fmt;
+use serde::de::{
+    self, DeserializeSeed, Deserializer, IgnoredAny, IntoDeserializer, MapAccess, Visitor,
+};
+
+/// Options for parsing a glTF document, selecting the sections to parse.
+/// See [GltfAsset::parse_gltf_with](crate::GltfAsset::parse_gltf_with).
+///
+/// Skipped sections are only checked for JSON syntax, without being deserialized, which reduces the
+/// parse time and memory of large documents, e.g. skipping animations and skins for a static viewer,
+/// or materials and textures for a physics importer. References into skipped sections are cleared,
+/// so that the parsed document stays valid. Accessors, buffer views and buffers are always parsed.
+///
+/// # Examples
+/// ```
+/// # use mugltf::{GltfAsset, ParseOptions};
+/// let json = r#"{
+///     "asset": { "version": "2.0" },
+///     "nodes": [{ "mesh": 0, "skin": 0 }],
+///     "meshes": [{ "primitives": [{ "attributes": {}, "material": 0 }] }],
+///     "materials": [{}],
+///     "skins": [{ "joints": [0] }]
+/// }"#;
+///
+/// // Meshes only
+/// let options = ParseOptions {
+///     materials: false,
+///     skins: false,
+///     ..Default::default()
+/// };
+/// let asset = <GltfAsset>::parse_gltf_with(json, &options).unwrap();
+/// assert_eq!(asset.gltf.meshes.len(), 1);
+/// assert!(asset.gltf.materials.is_empty() && asset.gltf.skins.is_empty());
+/// assert_eq!(asset.gltf.meshes[0].primitives[0].material, None);
+/// assert_eq!(asset.gltf.nodes[0].skin, None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Whether to parse animations.
+    pub animations: bool,
+    /// Whether to parse cameras.
+    pub cameras: bool,
+    /// Whether to parse materials.
+    pub materials: bool,
+    /// Whether to parse meshes.
+    pub meshes: bool,
+    /// Whether to parse skins.
+    pub skins: bool,
+    /// Whether to parse textures, with their images and samplers.
+    pub textures: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            animations: true,
+            cameras: true,
+            materials: true,
+            meshes: true,
+            skins: true,
+            textures: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns if a top-level property of the document is skipped.
+    fn skips(&self, property: &str) -> bool {
+        match property {
+            "animations" => !self.animations,
+            "cameras" => !self.cameras,
+            "materials" => !self.materials,
+            "meshes" => !self.meshes,
+            "skins" => !self.skins,
+            "images" | "samplers" | "textures" => !self.textures,
+            _ => false,
+        }
+    }
+
+    /// Parses a glTF JSON string with these options.
+    pub(crate) fn parse(&self, data: &str) -> serde_json::Result<Gltf> {
+        let mut deserializer = serde_json::Deserializer::from_str(data);
+        let mut gltf: Gltf = de::Deserialize::deserialize(SkipProperties {
+            deserializer: &mut deserializer,
+            options: self,
+        })?;
+        deserializer.end()?;
+        self.clear_references(&mut gltf);
+        Ok(gltf)
+    }
+
+    /// Clears the references into skipped sections.
+    fn clear_references(&self, gltf: &mut Gltf) {
+        for node in &mut gltf.nodes {
+            if !self.cameras {
+                node.camera = None;
+            }
+            if !self.meshes {
+                node.mesh = None;
+            }
+            if !self.skins {
+                node.skin = None;
+            }
+        }
+        for primitive in gltf.meshes.iter_mut().flat_map(|mesh| &mut mesh.primitives) {
+            if !self.materials {
+                primitive.material = None;
+            }
+        }
+        if !self.textures {
+            for material in &mut gltf.materials {
+                if let Some(pbr) = &mut material.pbr_metallic_roughness {
+                    pbr.base_color_texture = None;
+                    pbr.metallic_roughness_texture = None;
+                }
+                material.normal_texture = None;
+                material.occlusion_texture = None;
+                material.emissive_texture = None;
+            }
+        }
+    }
+}
+
+/// A deserializer of the glTF root object that ignores the properties skipped by [ParseOptions].
+struct SkipProperties<'a, D> {
+    deserializer: D,
+    options: &'a ParseOptions,
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for SkipProperties<'_, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserializer.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserializer.deserialize_map(SkipPropertiesVisitor {
+            visitor,
+            options: self.options,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct SkipPropertiesVisitor<'a, V> {
+    visitor: V,
+    options: &'a ParseOptions,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for SkipPropertiesVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(SkipPropertiesMap {
+            map,
+            options: self.options,
+        })
+    }
+}
+
+struct SkipPropertiesMap<'a, A> {
+    map: A,
+    options: &'a ParseOptions,
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for SkipPropertiesMap<'_, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        while let Some(key) = self.map.next_key::<String>()? {
+            if self.options.skips(&key) {
+                self.map.next_value::<IgnoredAny>()?;
+            } else {
+                let key: de::value::StringDeserializer<A::Error> = key.into_deserializer();
+                return seed.deserialize(key).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.map.next_value_seed(seed)
+    }
+}
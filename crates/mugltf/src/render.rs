@@ -0,0 +1,667 @@
+//! Instantiation of glTF assets into mugl GPU resources.
+//!
+//! [GltfScene::new] creates the buffers, textures, samplers and render pipelines needed to draw a loaded asset:
+//! - vertex attributes that mugl can read directly are drawn from one vertex buffer per buffer view,
+//!   with interleaved attributes of a buffer view sharing a vertex buffer slot;
+//! - other attributes (sparse, without buffer view, or in formats without a matching [VertexFormat])
+//!   are decoded into dedicated F32 vertex buffers;
+//! - indices are copied into one index buffer per primitive, widening 8-bit indices to 16-bit;
+//! - images are uploaded as RGBA8 or SRGBA8 textures, depending on their usage in materials;
+//! - render pipelines are created by a user-supplied factory, and shared by primitives of the same [PipelineKey].
+
+use crate::{
+    accessor::read_accessor_indices,
+    model::{
+        Accessor, AccessorComponentType, AccessorType, AlphaMode, Gltf, Id, MinFilterMode, Sampler,
+    },
+    GltfAsset, InstantiateGltfError,
+};
+use alloc::vec::Vec;
+use core::ops::Range;
+use mugl::{
+    BufferDescriptor, BufferSize, BufferUsage, CullMode, Extent2D, Extent3D, FilterMode, GPUDevice,
+    GPURenderPassEncoder, ImageCopyTexture, ImageDataLayout, IndexFormat, Origin3D, PrimitiveState,
+    PrimitiveTopology, SamplerDescriptor, TextureDescriptor, TextureUsage, VertexAttribute,
+    VertexBufferLayout, VertexFormat, VertexStepMode, GPU,
+};
+use munum::Mat4;
+
+/// Returns the default shader location of a vertex attribute semantic:
+/// `POSITION` = 0, `NORMAL` = 1, `TANGENT` = 2, `TEXCOORD_0` = 3, `TEXCOORD_1` = 4,
+/// `COLOR_0` = 5, `JOINTS_0` = 6 and `WEIGHTS_0` = 7.
+pub fn default_attribute_location(name: &str) -> Option<u32> {
+    Some(match name {
+        "POSITION" => 0,
+        "NORMAL" => 1,
+        "TANGENT" => 2,
+        "TEXCOORD_0" => 3,
+        "TEXCOORD_1" => 4,
+        "COLOR_0" => 5,
+        "JOINTS_0" => 6,
+        "WEIGHTS_0" => 7,
+        _ => return None,
+    })
+}
+
+impl Accessor {
+    /// Returns the vertex format to read this accessor as a vertex attribute directly,
+    /// or `None` if mugl has no matching format, e.g. for 3-component 8-bit or 16-bit vectors.
+    pub fn vertex_format(&self) -> Option<VertexFormat> {
+        Some(match (self.component_type, self.ty, self.normalized) {
+            (AccessorComponentType::Float, AccessorType::Scalar, _) => VertexFormat::F32,
+            (AccessorComponentType::Float, AccessorType::Vec2, _) => VertexFormat::F32x2,
+            (AccessorComponentType::Float, AccessorType::Vec3, _) => VertexFormat::F32x3,
+            (AccessorComponentType::Float, AccessorType::Vec4, _) => VertexFormat::F32x4,
+            (AccessorComponentType::UnsignedByte, AccessorType::Vec2, true) => {
+                VertexFormat::UNORM8x2
+            }
+            (AccessorComponentType::UnsignedByte, AccessorType::Vec4, true) => {
+                VertexFormat::UNORM8x4
+            }
+            (AccessorComponentType::UnsignedByte, AccessorType::Vec2, false) => VertexFormat::UI8x2,
+            (AccessorComponentType::UnsignedByte, AccessorType::Vec4, false) => VertexFormat::UI8x4,
+            (AccessorComponentType::Byte, AccessorType::Vec2, true) => VertexFormat::SNORM8x2,
+            (AccessorComponentType::Byte, AccessorType::Vec4, true) => VertexFormat::SNORM8x4,
+            (AccessorComponentType::Byte, AccessorType::Vec2, false) => VertexFormat::I8x2,
+            (AccessorComponentType::Byte, AccessorType::Vec4, false) => VertexFormat::I8x4,
+            (AccessorComponentType::UnsignedShort, AccessorType::Vec2, true) => {
+                VertexFormat::UNORM16x2
+            }
+            (AccessorComponentType::UnsignedShort, AccessorType::Vec4, true) => {
+                VertexFormat::UNORM16x4
+            }
+            (AccessorComponentType::UnsignedShort, AccessorType::Vec2, false) => {
+                VertexFormat::UI16x2
+            }
+            (AccessorComponentType::UnsignedShort, AccessorType::Vec4, false) => {
+                VertexFormat::UI16x4
+            }
+            (AccessorComponentType::Short, AccessorType::Vec2, true) => VertexFormat::SNORM16x2,
+            (AccessorComponentType::Short, AccessorType::Vec4, true) => VertexFormat::SNORM16x4,
+            (AccessorComponentType::Short, AccessorType::Vec2, false) => VertexFormat::I16x2,
+            (AccessorComponentType::Short, AccessorType::Vec4, false) => VertexFormat::I16x4,
+            _ => return None,
+        })
+    }
+}
+
+impl Sampler {
+    /// Returns the mugl sampler descriptor of this sampler.
+    /// Undefined filters default to linear filtering, as commonly done by viewers.
+    pub fn descriptor(&self) -> SamplerDescriptor {
+        let (min_filter, mipmap_filter, mipmaps) = match self.min_filter {
+            Some(MinFilterMode::Nearest) => (FilterMode::Nearest, FilterMode::Nearest, false),
+            Some(MinFilterMode::Linear) | None => (FilterMode::Linear, FilterMode::Nearest, false),
+            Some(MinFilterMode::NearestMipmapNearest) => {
+                (FilterMode::Nearest, FilterMode::Nearest, true)
+            }
+            Some(MinFilterMode::NearestMipmapLinear) => {
+                (FilterMode::Nearest, FilterMode::Linear, true)
+            }
+            Some(MinFilterMode::LinearMipmapNearest) => {
+                (FilterMode::Linear, FilterMode::Nearest, true)
+            }
+            Some(MinFilterMode::LinearMipmapLinear) => {
+                (FilterMode::Linear, FilterMode::Linear, true)
+            }
+        };
+        SamplerDescriptor {
+            address_mode_u: self.wrap_s,
+            address_mode_v: self.wrap_t,
+            mag_filter: self.mag_filter.unwrap_or(FilterMode::Linear),
+            min_filter,
+            mipmap_filter,
+            // Sample only the base level for non-mipmap filters
+            lod_max_clamp: if mipmaps { 32. } else { 0. },
+            ..Default::default()
+        }
+    }
+}
+
+/// Options of [GltfScene::new].
+#[derive(Clone, Copy, Debug)]
+pub struct InstantiateOptions {
+    /// Returns the shader location of a vertex attribute semantic, or `None` to skip the attribute.
+    /// Defaults to [default_attribute_location].
+    pub attribute_location: fn(&str) -> Option<u32>,
+    /// The scene to instantiate the nodes of. Defaults to the default scene.
+    pub scene: Option<Id>,
+}
+
+impl Default for InstantiateOptions {
+    fn default() -> Self {
+        Self {
+            attribute_location: default_attribute_location,
+            scene: None,
+        }
+    }
+}
+
+/// Layout of a vertex buffer slot of a [PipelineKey].
+#[derive(Clone, Debug, Default)]
+pub struct VertexLayout {
+    pub stride: BufferSize,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl PartialEq for VertexLayout {
+    fn eq(&self, other: &Self) -> bool {
+        self.stride == other.stride
+            && self.attributes.len() == other.attributes.len()
+            && self.attributes.iter().zip(&other.attributes).all(|(a, b)| {
+                a.format == b.format
+                    && a.offset == b.offset
+                    && a.shader_location == b.shader_location
+            })
+    }
+}
+
+/// The states of a primitive that determine its render pipeline.
+/// Primitives with equal keys share the same pipeline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PipelineKey {
+    /// Vertex buffer layouts, in slot order.
+    pub buffers: Vec<VertexLayout>,
+    pub topology: PrimitiveTopology,
+    pub index_format: Option<IndexFormat>,
+    /// [CullMode::None] for double sided materials, [CullMode::Back] otherwise.
+    pub cull_mode: CullMode,
+    pub alpha_mode: AlphaMode,
+}
+
+impl PipelineKey {
+    /// Returns the vertex buffer layouts to create the pipeline with.
+    pub fn vertex_buffer_layouts(&self) -> Vec<VertexBufferLayout<'_>> {
+        self.buffers
+            .iter()
+            .map(|buffer| VertexBufferLayout {
+                stride: buffer.stride,
+                step_mode: VertexStepMode::Vertex,
+                attributes: &buffer.attributes,
+            })
+            .collect()
+    }
+
+    /// Returns the primitive state to create the pipeline with.
+    pub fn primitive_state(&self) -> PrimitiveState {
+        PrimitiveState {
+            topology: self.topology,
+            index_format: self.index_format,
+            cull_mode: self.cull_mode,
+            ..Default::default()
+        }
+    }
+}
+
+/// A mesh primitive ready to draw. Resources are referenced by their indices in [GltfScene].
+#[derive(Clone, Debug)]
+pub struct RenderPrimitive {
+    /// Index of the render pipeline.
+    pub pipeline: usize,
+    /// Vertex buffer and offset of each slot.
+    pub vertex_buffers: Vec<(usize, BufferSize)>,
+    /// Index buffer, if the primitive is indexed.
+    pub index_buffer: Option<usize>,
+    /// Number of indices, or vertices for a non-indexed primitive.
+    pub count: u32,
+    /// Material ID of the primitive.
+    pub material: Option<Id>,
+}
+
+/// A node of the instantiated scene with a mesh.
+#[derive(Clone, Debug)]
+pub struct RenderNode {
+    /// Node ID.
+    pub node: Id,
+    /// Mesh ID.
+    pub mesh: Id,
+    /// World transformation matrix of the node.
+    pub world_transform: Mat4,
+}
+
+/// GPU resources of a glTF asset, with the nodes of a scene to draw.
+#[derive(Debug)]
+pub struct GltfScene<G: GPU> {
+    /// Vertex and index buffers.
+    pub buffers: Vec<G::Buffer>,
+    /// Textures of each glTF image.
+    pub textures: Vec<G::Texture>,
+    /// Samplers of each glTF sampler, followed by the default sampler for textures without sampler.
+    pub samplers: Vec<G::Sampler>,
+    /// Render pipelines.
+    pub pipelines: Vec<G::RenderPipeline>,
+    /// Keys of each render pipeline.
+    pub pipeline_keys: Vec<PipelineKey>,
+    /// Primitives of each glTF mesh.
+    pub meshes: Vec<Vec<RenderPrimitive>>,
+    /// Visible nodes with a mesh, in depth-first order.
+    pub nodes: Vec<RenderNode>,
+}
+
+impl<G: GPU> GltfScene<G> {
+    /// Creates the GPU resources of a glTF asset with loaded resources,
+    /// with images decoded as tightly packed RGBA8 or RGB8 data.
+    /// `create_pipeline` is called once for each distinct [PipelineKey] of the mesh primitives.
+    pub fn new(
+        device: &G::Device,
+        asset: &GltfAsset<'_, (Vec<u8>, Extent2D)>,
+        options: InstantiateOptions,
+        mut create_pipeline: impl FnMut(&PipelineKey) -> G::RenderPipeline,
+    ) -> Result<Self, InstantiateGltfError> {
+        let gltf = &asset.gltf;
+        let mut scene = Self {
+            buffers: Vec::new(),
+            textures: Vec::with_capacity(asset.images.len()),
+            samplers: Vec::with_capacity(gltf.samplers.len() + 1),
+            pipelines: Vec::new(),
+            pipeline_keys: Vec::new(),
+            meshes: Vec::with_capacity(gltf.meshes.len()),
+            nodes: Vec::new(),
+        };
+
+        for (id, ((data, size), color_space)) in asset
+            .images
+            .iter()
+            .zip(gltf.image_color_spaces())
+            .enumerate()
+        {
+            let pixels = (size.0 as usize)
+                .checked_mul(size.1 as usize)
+                .filter(|_| size.0.checked_mul(4).is_some())
+                .ok_or(InstantiateGltfError::InvalidImage(id))?;
+            let rgba: Vec<u8>;
+            let data = if pixels.checked_mul(4) == Some(data.len()) {
+                data.as_slice()
+            } else if pixels.checked_mul(3) == Some(data.len()) {
+                rgba = data
+                    .chunks_exact(3)
+                    .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                    .collect();
+                &rgba
+            } else {
+                return Err(InstantiateGltfError::InvalidImage(id));
+            };
+            scene.textures.push(create_texture::<G>(
+                device,
+                data,
+                *size,
+                color_space.texture_format(),
+            ));
+        }
+
+        scene.samplers.extend(
+            gltf.samplers
+                .iter()
+                .chain(core::iter::once(&Sampler::default()))
+                .map(|sampler| device.create_sampler(sampler.descriptor())),
+        );
+
+        // Vertex buffer of each buffer view, created on first use
+        let mut view_buffers = Vec::new();
+        view_buffers.resize(gltf.buffer_views.len(), None);
+        // Decoded vertex buffer and format of each accessor, created on first use
+        let mut accessor_buffers = Vec::new();
+        accessor_buffers.resize(gltf.accessors.len(), None);
+        for (mesh_id, mesh) in gltf.meshes.iter().enumerate() {
+            let mut primitives = Vec::with_capacity(mesh.primitives.len());
+            for primitive in &mesh.primitives {
+                let mut buffers = Vec::<VertexLayout>::new();
+                let mut vertex_buffers = Vec::new();
+                let mut vertex_count = None;
+
+                for (name, &accessor_id) in &primitive.attributes {
+                    let shader_location = match (options.attribute_location)(name) {
+                        Some(location) => location,
+                        None => continue,
+                    };
+                    let accessor = gltf
+                        .accessors
+                        .get(accessor_id)
+                        .ok_or(InstantiateGltfError::InvalidAccessor(accessor_id))?;
+                    vertex_count = Some(vertex_count.unwrap_or(accessor.count).min(accessor.count));
+
+                    let element_size = accessor.ty.components() * accessor.component_type.size();
+                    let direct = match (accessor.vertex_format(), accessor.buffer_view) {
+                        (Some(format), Some(view_id)) if accessor.sparse.is_none() => gltf
+                            .buffer_views
+                            .get(view_id)
+                            .filter(|view| {
+                                // Vertex buffer offsets and strides must be 4-byte aligned,
+                                // interleaved elements must fit in their stride,
+                                // and all elements must be within the view
+                                let stride = if view.byte_stride > 0 {
+                                    view.byte_stride
+                                } else {
+                                    element_size
+                                };
+                                let fits_stride = view.byte_stride == 0
+                                    || accessor
+                                        .byte_offset
+                                        .checked_add(element_size)
+                                        .map_or(false, |end| end <= view.byte_stride);
+                                let end = match accessor.count.checked_sub(1) {
+                                    Some(last) => last
+                                        .checked_mul(stride)
+                                        .and_then(|offset| offset.checked_add(accessor.byte_offset))
+                                        .and_then(|offset| offset.checked_add(element_size)),
+                                    None => Some(accessor.byte_offset),
+                                };
+                                accessor.byte_offset % 4 == 0
+                                    && stride % 4 == 0
+                                    && fits_stride
+                                    && end.map_or(false, |end| end <= view.byte_length)
+                            })
+                            .map(|view| (format, view_id, view)),
+                        _ => None,
+                    };
+
+                    match direct {
+                        Some((format, view_id, view)) => {
+                            let buffer = match view_buffers[view_id] {
+                                Some(buffer) => buffer,
+                                None => {
+                                    let data = asset
+                                        .buffers
+                                        .get(view.buffer)
+                                        .and_then(|data| {
+                                            let end =
+                                                view.byte_offset.checked_add(view.byte_length)?;
+                                            data.get(view.byte_offset..end)
+                                        })
+                                        .ok_or(InstantiateGltfError::InvalidAccessor(
+                                            accessor_id,
+                                        ))?;
+                                    scene.buffers.push(create_buffer::<G>(
+                                        device,
+                                        BufferUsage::VERTEX,
+                                        data,
+                                    ));
+                                    view_buffers[view_id] = Some(scene.buffers.len() - 1);
+                                    scene.buffers.len() - 1
+                                }
+                            };
+
+                            if view.byte_stride > 0 {
+                                // Interleaved attributes of the same buffer view share a slot
+                                let slot = vertex_buffers.iter().zip(&buffers).position(
+                                    |(&(b, _), layout)| {
+                                        b == buffer && layout.stride == view.byte_stride
+                                    },
+                                );
+                                let attribute = VertexAttribute {
+                                    format,
+                                    offset: accessor.byte_offset,
+                                    shader_location,
+                                };
+                                match slot {
+                                    Some(slot) => buffers[slot].attributes.push(attribute),
+                                    None => {
+                                        vertex_buffers.push((buffer, 0));
+                                        buffers.push(VertexLayout {
+                                            stride: view.byte_stride,
+                                            attributes: alloc::vec![attribute],
+                                        });
+                                    }
+                                }
+                            } else {
+                                vertex_buffers.push((buffer, accessor.byte_offset));
+                                buffers.push(VertexLayout {
+                                    stride: element_size,
+                                    attributes: alloc::vec![VertexAttribute {
+                                        format,
+                                        offset: 0,
+                                        shader_location,
+                                    }],
+                                });
+                            }
+                        }
+                        None => {
+                            let (buffer, format) = match accessor_buffers[accessor_id] {
+                                Some(cached) => cached,
+                                None => {
+                                    let (data, format) =
+                                        decode_floats(gltf, &asset.buffers, accessor).ok_or(
+                                            InstantiateGltfError::InvalidAccessor(accessor_id),
+                                        )?;
+                                    scene.buffers.push(create_buffer::<G>(
+                                        device,
+                                        BufferUsage::VERTEX,
+                                        &data,
+                                    ));
+                                    accessor_buffers[accessor_id] =
+                                        Some((scene.buffers.len() - 1, format));
+                                    (scene.buffers.len() - 1, format)
+                                }
+                            };
+                            vertex_buffers.push((buffer, 0));
+                            buffers.push(VertexLayout {
+                                stride: accessor.ty.components() * 4,
+                                attributes: alloc::vec![VertexAttribute {
+                                    format,
+                                    offset: 0,
+                                    shader_location,
+                                }],
+                            });
+                        }
+                    }
+                }
+
+                let (index_buffer, index_format, count) = match primitive.indices {
+                    Some(indices_id) => {
+                        let indices = gltf
+                            .accessors
+                            .get(indices_id)
+                            .and_then(|accessor| {
+                                read_accessor_indices(gltf, &asset.buffers, accessor)
+                            })
+                            .ok_or(InstantiateGltfError::InvalidAccessor(indices_id))?;
+                        let (data, format) =
+                            if indices.iter().all(|&index| index <= u16::MAX as u32) {
+                                let data: Vec<u8> = indices
+                                    .iter()
+                                    .flat_map(|&index| (index as u16).to_le_bytes())
+                                    .collect();
+                                (data, IndexFormat::UI16)
+                            } else {
+                                let data: Vec<u8> = indices
+                                    .iter()
+                                    .flat_map(|index| index.to_le_bytes())
+                                    .collect();
+                                (data, IndexFormat::UI32)
+                            };
+                        scene
+                            .buffers
+                            .push(create_buffer::<G>(device, BufferUsage::INDEX, &data));
+                        (
+                            Some(scene.buffers.len() - 1),
+                            Some(format),
+                            indices.len() as u32,
+                        )
+                    }
+                    None => (None, None, vertex_count.unwrap_or(0) as u32),
+                };
+
+                let material = primitive
+                    .material
+                    .map(|material| {
+                        gltf.materials
+                            .get(material)
+                            .ok_or(InstantiateGltfError::InvalidMesh(mesh_id))
+                    })
+                    .transpose()?;
+                let key = PipelineKey {
+                    buffers,
+                    topology: primitive.mode,
+                    index_format,
+                    cull_mode: if material.map_or(false, |material| material.double_sided) {
+                        CullMode::None
+                    } else {
+                        CullMode::Back
+                    },
+                    alpha_mode: material.map_or(AlphaMode::Opaque, |material| material.alpha_mode),
+                };
+                let pipeline = match scene.pipeline_keys.iter().position(|k| *k == key) {
+                    Some(pipeline) => pipeline,
+                    None => {
+                        scene.pipelines.push(create_pipeline(&key));
+                        scene.pipeline_keys.push(key);
+                        scene.pipelines.len() - 1
+                    }
+                };
+
+                primitives.push(RenderPrimitive {
+                    pipeline,
+                    vertex_buffers,
+                    index_buffer,
+                    count,
+                    material: primitive.material,
+                });
+            }
+            scene.meshes.push(primitives);
+        }
+
+        let scene_id = options
+            .scene
+            .or_else(|| gltf.default_scene().map(|(id, _)| id));
+        if let Some(scene_id) = scene_id {
+            scene
+                .nodes
+                .extend(gltf.scene_nodes(scene_id).filter_map(|node| {
+                    if !node.visible {
+                        return None;
+                    }
+                    Some(RenderNode {
+                        node: node.id,
+                        mesh: node.node.mesh.filter(|&mesh| mesh < scene.meshes.len())?,
+                        world_transform: node.world_transform,
+                    })
+                }));
+        }
+
+        Ok(scene)
+    }
+
+    /// Gets the texture and sampler of a glTF texture.
+    pub fn texture(&self, gltf: &Gltf, texture: Id) -> Option<(&G::Texture, &G::Sampler)> {
+        let texture = gltf.textures.get(texture)?;
+        let sampler = texture
+            .sampler
+            .filter(|&sampler| sampler < gltf.samplers.len())
+            .unwrap_or(gltf.samplers.len());
+        Some((
+            self.textures.get(texture.source?)?,
+            self.samplers.get(sampler)?,
+        ))
+    }
+
+    /// Sets the pipeline, vertex buffers and index buffer of a primitive to a render pass.
+    /// Bind groups can then be set before calling [GltfScene::draw].
+    pub fn bind<'a>(
+        &'a self,
+        pass: &impl GPURenderPassEncoder<'a, G>,
+        primitive: &RenderPrimitive,
+    ) {
+        pass.pipeline(&self.pipelines[primitive.pipeline]);
+        for (slot, &(buffer, offset)) in primitive.vertex_buffers.iter().enumerate() {
+            pass.vertex(slot as u32, &self.buffers[buffer], offset);
+        }
+        if let Some(buffer) = primitive.index_buffer {
+            pass.index(&self.buffers[buffer]);
+        }
+    }
+
+    /// Draws instances of a primitive bound by [GltfScene::bind].
+    pub fn draw<'a>(
+        &'a self,
+        pass: &impl GPURenderPassEncoder<'a, G>,
+        primitive: &RenderPrimitive,
+        instances: Range<u32>,
+    ) {
+        if primitive.index_buffer.is_some() {
+            pass.draw_indexed(0..primitive.count, instances);
+        } else {
+            pass.draw(0..primitive.count, instances);
+        }
+    }
+}
+
+/// Creates a buffer with given data, padded to a multiple of 4 bytes.
+fn create_buffer<G: GPU>(device: &G::Device, usage: BufferUsage, data: &[u8]) -> G::Buffer {
+    let size = (data.len() + 3) & !3;
+    let buffer = device.create_buffer(BufferDescriptor {
+        size: size.max(4),
+        usage,
+    });
+    if size == data.len() {
+        device.write_buffer(&buffer, 0, data);
+    } else {
+        let mut padded = Vec::with_capacity(size);
+        padded.extend_from_slice(data);
+        padded.resize(size, 0);
+        device.write_buffer(&buffer, 0, &padded);
+    }
+    buffer
+}
+
+/// Creates a 2D texture with tightly packed RGBA data.
+fn create_texture<G: GPU>(
+    device: &G::Device,
+    data: &[u8],
+    size: Extent2D,
+    format: mugl::TextureFormat,
+) -> G::Texture {
+    let texture = device.create_texture(TextureDescriptor {
+        size: Extent3D(size.0, size.1, 1),
+        format,
+        usage: TextureUsage::TEXTURE_BINDING,
+        ..Default::default()
+    });
+    device.write_texture(
+        ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3D(0, 0, 0),
+        },
+        data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: size.0 * 4,
+            rows_per_image: size.1,
+        },
+        Extent3D(size.0, size.1, 1),
+    );
+    texture
+}
+
+/// Decodes an accessor of up to 4 components into tightly packed f32 data.
+fn decode_floats(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    accessor: &Accessor,
+) -> Option<(Vec<u8>, VertexFormat)> {
+    fn to_bytes<const N: usize>(elements: impl Iterator<Item = [f32; N]>) -> Vec<u8> {
+        elements
+            .flat_map(|element| element.into_iter().flat_map(f32::to_le_bytes))
+            .collect()
+    }
+
+    Some(match accessor.ty {
+        AccessorType::Scalar => (
+            to_bytes(accessor.decode::<1>(gltf, buffers)?),
+            VertexFormat::F32,
+        ),
+        AccessorType::Vec2 => (
+            to_bytes(accessor.decode::<2>(gltf, buffers)?),
+            VertexFormat::F32x2,
+        ),
+        AccessorType::Vec3 => (
+            to_bytes(accessor.decode::<3>(gltf, buffers)?),
+            VertexFormat::F32x3,
+        ),
+        AccessorType::Vec4 => (
+            to_bytes(accessor.decode::<4>(gltf, buffers)?),
+            VertexFormat::F32x4,
+        ),
+        _ => return None,
+    })
+}
@@ -0,0 +1,136 @@
+//! Scene and node traversal.
+
+use crate::model::{Gltf, Id, Node, Scene};
+use alloc::{vec, vec::Vec};
+use munum::{transform, Mat4, Quaternion, Vec3};
+
+impl Gltf {
+    /// Returns an iterator over the scenes with their IDs.
+    #[inline]
+    pub fn scenes_iter(&self) -> impl Iterator<Item = (Id, &Scene)> {
+        self.scenes.iter().enumerate()
+    }
+
+    /// Returns the default scene with its ID.
+    /// This is the scene referenced by the `scene` property, or the first scene if the property is not defined,
+    /// as commonly done by viewers.
+    pub fn default_scene(&self) -> Option<(Id, &Scene)> {
+        let id = self.scene.unwrap_or(0);
+        self.scenes.get(id).map(|scene| (id, scene))
+    }
+
+    /// Returns a depth-first iterator over the nodes of a scene with their world transforms.
+    /// Parent nodes are always visited before their children.
+    /// The iterator is empty if the scene does not exist.
+    pub fn scene_nodes(&self, scene: Id) -> SceneNodes<'_> {
+        let mut stack = Vec::new();
+        if let Some(scene) = self.scenes.get(scene) {
+            stack.extend(scene.nodes.iter().rev().map(|&id| (id, None)));
+        }
+        SceneNodes {
+            gltf: self,
+            stack,
+            visited: vec![false; self.nodes.len()],
+        }
+    }
+}
+
+impl Node {
+    /// Returns whether this node is visible by itself, ignoring its ancestors.
+    /// Visibility is read from the `KHR_node_visibility` extension if present.
+    /// Otherwise, a boolean `visible` property in the node extras is used as fallback,
+    /// e.g. `"extras": { "visible": false }` exported by authoring tools for helper nodes.
+    /// Nodes are visible by default, or if the corresponding features are disabled.
+    pub fn is_visible(&self) -> bool {
+        #[cfg(feature = "gltf-extensions")]
+        if let Some(visibility) = self
+            .extensions
+            .as_ref()
+            .and_then(|extensions| extensions.visibility.as_ref())
+        {
+            return visibility.visible;
+        }
+        #[cfg(feature = "gltf-extras")]
+        if let Some(visible) = self.extras.get("visible").and_then(|value| value.as_bool()) {
+            return visible;
+        }
+        true
+    }
+
+    /// Returns the local transformation matrix of this node,
+    /// either from its `matrix` or its translation, rotation and scale properties.
+    pub fn local_transform(&self) -> Mat4 {
+        if let Some(matrix) = &self.matrix {
+            return Mat4::from_slice(matrix);
+        }
+        transform::transformation(
+            Vec3::from_slice(&self.translation.unwrap_or([0., 0., 0.])),
+            Quaternion::from_slice(&self.rotation.unwrap_or([0., 0., 0., 1.])),
+            Vec3::from_slice(&self.scale.unwrap_or([1., 1., 1.])),
+        )
+    }
+}
+
+/// A node visited by [SceneNodes].
+#[derive(Clone, Debug)]
+pub struct SceneNode<'a> {
+    /// ID of the node.
+    pub id: Id,
+    /// The node.
+    pub node: &'a Node,
+    /// ID of the parent node, or `None` for a root node of the scene.
+    pub parent: Option<Id>,
+    /// World transformation matrix of the node.
+    pub world_transform: Mat4,
+    /// Whether the node is visible, i.e. the node and all of its ancestors are visible. See [Node::is_visible].
+    pub visible: bool,
+}
+
+/// Depth-first iterator over the nodes of a scene. See [Gltf::scene_nodes].
+#[derive(Clone, Debug)]
+pub struct SceneNodes<'a> {
+    gltf: &'a Gltf,
+    stack: Vec<(Id, Option<ParentNode>)>,
+    visited: Vec<bool>,
+}
+
+/// ID, world transform and visibility of the parent of a node to visit.
+type ParentNode = (Id, Mat4, bool);
+
+impl<'a> Iterator for SceneNodes<'a> {
+    type Item = SceneNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, parent)) = self.stack.pop() {
+            // Skip invalid and already visited nodes, so that malformed node graphs cannot loop forever
+            let node = match self.gltf.nodes.get(id) {
+                Some(node) if !self.visited[id] => node,
+                _ => continue,
+            };
+            self.visited[id] = true;
+
+            let (world_transform, visible) = match &parent {
+                Some((_, parent_transform, parent_visible)) => (
+                    *parent_transform * node.local_transform(),
+                    *parent_visible && node.is_visible(),
+                ),
+                None => (node.local_transform(), node.is_visible()),
+            };
+            self.stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|&child| (child, Some((id, world_transform, visible)))),
+            );
+
+            return Some(SceneNode {
+                id,
+                node,
+                parent: parent.map(|(parent, _, _)| parent),
+                world_transform,
+                visible,
+            });
+        }
+        None
+    }
+}
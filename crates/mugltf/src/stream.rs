@@ -0,0 +1,300 @@
+//! Streaming GLB parser.
+
+#![cfg(feature = "serde")]
+
+use crate::{
+    asset::{read_u32, GLBChunk, GLB_HEADER_LENGTH, GLB_HEADER_MAGIC},
+    model::Gltf,
+    Error, GltfAsset, ParseGltfError, ParseGltfErrorKind, ParseOptions,
+};
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use async_trait::async_trait;
+use core::str;
+
+/// Length of a GLB chunk header.
+const GLB_CHUNK_HEADER_LENGTH: usize = 8;
+
+/// Maximum number of bytes allocated ahead of reading a chunk.
+/// Chunk lengths come from the untrusted header, so chunks are read in blocks of this size.
+const CHUNK_READ_BLOCK_LENGTH: usize = 64 * 1024;
+
+/// An asynchronous source of bytes read in order, e.g. a fetch response body.
+#[async_trait(?Send)]
+pub trait AsyncByteStream {
+    /// Read error type.
+    type Error: Into<Box<Error>>;
+
+    /// Reads bytes into a buffer. Returns the number of bytes read, which is 0 only at the end of the stream.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// A GLB parsed from a byte stream, with its BIN chunk left unread in the stream.
+///
+/// Parsing a GLB with [GltfAsset::parse_glb] requires the whole file in memory.
+/// [GlbStream] instead reads the header and JSON chunk only, then exposes the BIN chunk as a region of the
+/// stream to be read on demand, e.g. directly into GPU buffers. The stream is read from
+/// an [io::Read](std::io::Read) with [GlbStream::new], or an [AsyncByteStream] with [GlbStream::new_async].
+///
+/// # Examples
+/// ```
+/// # use mugltf::{GltfAsset, GlbStream, ParseOptions};
+/// use std::io::Read;
+///
+/// # let glb = <GltfAsset>::default().to_glb().unwrap();
+/// # let file = std::io::Cursor::new(glb);
+/// let mut stream = GlbStream::new(file, &ParseOptions::default()).unwrap();
+/// assert_eq!(stream.gltf().asset.version, "2.0");
+///
+/// let mut bin = Vec::with_capacity(stream.bin_len());
+/// stream.read_to_end(&mut bin).unwrap();
+/// assert_eq!(bin.len(), stream.bin_len());
+/// ```
+#[derive(Debug)]
+pub struct GlbStream<R> {
+    reader: R,
+    gltf: Gltf,
+    bin_len: usize,
+    /// Remaining bytes of the BIN chunk to read.
+    bin_remaining: usize,
+}
+
+impl<R> GlbStream<R> {
+    /// Gets the glTF model.
+    #[inline]
+    pub fn gltf(&self) -> &Gltf {
+        &self.gltf
+    }
+
+    /// Gets the length of the BIN chunk, or 0 if the GLB has no BIN chunk.
+    #[inline]
+    pub fn bin_len(&self) -> usize {
+        self.bin_len
+    }
+
+    /// Gets the remaining bytes of the BIN chunk to read.
+    #[inline]
+    pub fn bin_remaining(&self) -> usize {
+        self.bin_remaining
+    }
+
+    /// Consumes this stream, returning the glTF model and the underlying reader,
+    /// which is positioned at the unread part of the BIN chunk.
+    #[inline]
+    pub fn into_parts(self) -> (Gltf, R) {
+        (self.gltf, self.reader)
+    }
+
+    /// Creates the stream from the bytes read for the header and JSON chunk.
+    fn from_chunks(
+        reader: R,
+        json: &[u8],
+        bin_header: &[u8],
+        options: &ParseOptions,
+    ) -> Result<Self, ParseGltfError> {
+        let json = str::from_utf8(json).map_err(|err| {
+            ParseGltfError::new::<Box<Error>>(ParseGltfErrorKind::InvalidChunk, Box::new(err))
+        })?;
+        let gltf = GltfAsset::<()>::parse_gltf_with(json, options)?.gltf;
+        let bin_len = bin_chunk_len(bin_header)?;
+        Ok(Self {
+            reader,
+            gltf,
+            bin_len,
+            bin_remaining: bin_len,
+        })
+    }
+
+    /// Converts the stream into an asset with given BIN chunk.
+    fn take_asset<ImageData>(&mut self, bin: Vec<u8>) -> GltfAsset<'static, ImageData> {
+        GltfAsset {
+            gltf: core::mem::take(&mut self.gltf),
+            bin: Cow::Owned(bin),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> GlbStream<R> {
+    /// Parses the header and JSON chunk of a GLB from a reader, skipping the JSON sections excluded by given options.
+    /// The BIN chunk is then read from this stream as an [io::Read](std::io::Read).
+    pub fn new(mut reader: R, options: &ParseOptions) -> Result<Self, ParseGltfError> {
+        let mut header = [0; GLB_HEADER_LENGTH + GLB_CHUNK_HEADER_LENGTH];
+        read_fully(&mut reader, &mut header)
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidHeader, err))?;
+        let json = read_chunk(&mut reader, json_chunk_len(&header)?)?;
+        let mut bin_header = [0; GLB_CHUNK_HEADER_LENGTH];
+        let bin_header_len = read_fully(&mut reader, &mut bin_header)
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidChunkHeader, err))?;
+        Self::from_chunks(reader, &json, &bin_header[..bin_header_len], options)
+    }
+
+    /// Reads the remaining BIN chunk, returning the parsed asset.
+    pub fn into_asset<ImageData>(
+        mut self,
+    ) -> Result<GltfAsset<'static, ImageData>, ParseGltfError> {
+        let bin = read_chunk(&mut self.reader, self.bin_remaining)?;
+        self.bin_remaining = 0;
+        Ok(self.take_asset(bin))
+    }
+}
+
+/// Reads the BIN chunk.
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for GlbStream<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = buf.len().min(self.bin_remaining);
+        let read = self.reader.read(&mut buf[..len])?;
+        self.bin_remaining -= read;
+        Ok(read)
+    }
+}
+
+impl<R: AsyncByteStream> GlbStream<R> {
+    /// Parses the header and JSON chunk of a GLB from an asynchronous stream,
+    /// skipping the JSON sections excluded by given options.
+    /// The BIN chunk is then read with [GlbStream::read_bin_async].
+    pub async fn new_async(mut reader: R, options: &ParseOptions) -> Result<Self, ParseGltfError> {
+        let mut header = [0; GLB_HEADER_LENGTH + GLB_CHUNK_HEADER_LENGTH];
+        read_fully_async(&mut reader, &mut header)
+            .await
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidHeader, err))?;
+        let json = read_chunk_async(&mut reader, json_chunk_len(&header)?).await?;
+        let mut bin_header = [0; GLB_CHUNK_HEADER_LENGTH];
+        let bin_header_len = read_fully_async(&mut reader, &mut bin_header)
+            .await
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidChunkHeader, err))?;
+        Self::from_chunks(reader, &json, &bin_header[..bin_header_len], options)
+    }
+
+    /// Reads bytes of the BIN chunk into a buffer. Returns the number of bytes read,
+    /// which is 0 only at the end of the chunk.
+    pub async fn read_bin_async(&mut self, buf: &mut [u8]) -> Result<usize, R::Error> {
+        let len = buf.len().min(self.bin_remaining);
+        if len == 0 {
+            return Ok(0);
+        }
+        let read = self.reader.read(&mut buf[..len]).await?;
+        self.bin_remaining -= read;
+        Ok(read)
+    }
+
+    /// Reads the remaining BIN chunk, returning the parsed asset.
+    pub async fn into_asset_async<ImageData>(
+        mut self,
+    ) -> Result<GltfAsset<'static, ImageData>, ParseGltfError> {
+        let bin = read_chunk_async(&mut self.reader, self.bin_remaining).await?;
+        self.bin_remaining = 0;
+        Ok(self.take_asset(bin))
+    }
+}
+
+/// Validates the GLB header, returning the length of the JSON chunk that follows.
+fn json_chunk_len(header: &[u8]) -> Result<usize, ParseGltfError> {
+    if GLB_HEADER_MAGIC != &header[0..4] {
+        return Err(ParseGltfErrorKind::InvalidHeader.into());
+    }
+    if read_u32(header, 4, ParseGltfErrorKind::UnsupportedVersion)? != 2 {
+        return Err(ParseGltfErrorKind::UnsupportedVersion.into());
+    }
+    let chunk_len = read_u32(
+        header,
+        GLB_HEADER_LENGTH,
+        ParseGltfErrorKind::InvalidChunkHeader,
+    )?;
+    let chunk_type = read_u32(
+        header,
+        GLB_HEADER_LENGTH + 4,
+        ParseGltfErrorKind::InvalidChunkHeader,
+    )?;
+    if chunk_type != GLBChunk::Json as u32 {
+        return Err(ParseGltfErrorKind::InvalidChunkHeader.into());
+    }
+    Ok(chunk_len as usize)
+}
+
+/// Returns the length of the BIN chunk from the chunk header after the JSON chunk,
+/// or 0 if the GLB ends after the JSON chunk or the next chunk is not BIN.
+fn bin_chunk_len(header: &[u8]) -> Result<usize, ParseGltfError> {
+    if header.is_empty() {
+        return Ok(0);
+    }
+    if header.len() < GLB_CHUNK_HEADER_LENGTH {
+        return Err(ParseGltfErrorKind::InvalidChunkHeader.into());
+    }
+    let chunk_len = read_u32(header, 0, ParseGltfErrorKind::InvalidChunkHeader)?;
+    let chunk_type = read_u32(header, 4, ParseGltfErrorKind::InvalidChunkHeader)?;
+    Ok(if chunk_type == GLBChunk::Bin as u32 {
+        chunk_len as usize
+    } else {
+        0
+    })
+}
+
+/// Reads into a buffer until it is full or the reader ends. Returns the number of bytes read.
+#[cfg(feature = "std")]
+fn read_fully<R: std::io::Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(len)
+}
+
+/// Reads a chunk of given length in blocks, so that a corrupted length fails at the end of the reader
+/// instead of allocating the whole length upfront.
+#[cfg(feature = "std")]
+fn read_chunk<R: std::io::Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, ParseGltfError> {
+    let mut chunk = Vec::new();
+    while chunk.len() < len {
+        let start = chunk.len();
+        chunk.resize(start + (len - start).min(CHUNK_READ_BLOCK_LENGTH), 0);
+        if read_fully(reader, &mut chunk[start..])
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidChunk, err))?
+            < chunk.len() - start
+        {
+            return Err(ParseGltfErrorKind::InvalidChunk.into());
+        }
+    }
+    Ok(chunk)
+}
+
+/// Reads a chunk of given length in blocks from an asynchronous stream.
+async fn read_chunk_async<R: AsyncByteStream>(
+    reader: &mut R,
+    len: usize,
+) -> Result<Vec<u8>, ParseGltfError> {
+    let mut chunk = Vec::new();
+    while chunk.len() < len {
+        let start = chunk.len();
+        chunk.resize(start + (len - start).min(CHUNK_READ_BLOCK_LENGTH), 0);
+        if read_fully_async(reader, &mut chunk[start..])
+            .await
+            .map_err(|err| ParseGltfError::new(ParseGltfErrorKind::InvalidChunk, err))?
+            < chunk.len() - start
+        {
+            return Err(ParseGltfErrorKind::InvalidChunk.into());
+        }
+    }
+    Ok(chunk)
+}
+
+/// Reads into a buffer until it is full or the stream ends. Returns the number of bytes read.
+async fn read_fully_async<R: AsyncByteStream>(
+    reader: &mut R,
+    buf: &mut [u8],
+) -> Result<usize, R::Error> {
+    let mut len = 0;
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]).await? {
+            0 => break,
+            read => len += read,
+        }
+    }
+    Ok(len)
+}
@@ -0,0 +1,154 @@
+//! Texture color space classification.
+
+use crate::model::{Gltf, Id, Material, Texture};
+use alloc::{vec, vec::Vec};
+use mugl::TextureFormat;
+
+/// Usage of a texture in a material.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureUsage {
+    BaseColor,
+    MetallicRoughness,
+    Normal,
+    Occlusion,
+    Emissive,
+}
+
+impl TextureUsage {
+    /// Returns the color space of textures of this usage, as defined by the glTF specification:
+    /// base color and emissive textures are sRGB encoded, while other textures contain linear data.
+    #[inline]
+    pub fn color_space(self) -> ColorSpace {
+        match self {
+            Self::BaseColor | Self::Emissive => ColorSpace::Srgb,
+            Self::MetallicRoughness | Self::Normal | Self::Occlusion => ColorSpace::Linear,
+        }
+    }
+}
+
+/// Color space of texture data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// sRGB encoded color, to be decoded to linear when sampled.
+    Srgb,
+    /// Linear values, sampled as is.
+    Linear,
+}
+
+impl ColorSpace {
+    /// Returns the 8-bit RGBA texture format to upload texture data of this color space.
+    #[inline]
+    pub fn texture_format(self) -> TextureFormat {
+        match self {
+            Self::Srgb => TextureFormat::SRGBA8,
+            Self::Linear => TextureFormat::RGBA8,
+        }
+    }
+}
+
+/// Color space of a texture or image, merged from all of its usages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureColorSpace {
+    /// Not used by any material.
+    Unused,
+    /// Used only in the given color space.
+    Used(ColorSpace),
+    /// Used in both color spaces, which needs separate sRGB and linear textures.
+    Mixed,
+}
+
+impl TextureColorSpace {
+    /// Returns the texture format to upload the data with, assuming linear for unused and mixed textures.
+    #[inline]
+    pub fn texture_format(self) -> TextureFormat {
+        match self {
+            Self::Used(color_space) => color_space.texture_format(),
+            Self::Unused | Self::Mixed => TextureFormat::RGBA8,
+        }
+    }
+
+    fn merge(self, color_space: ColorSpace) -> Self {
+        match self {
+            Self::Unused => Self::Used(color_space),
+            Self::Used(current) if current == color_space => self,
+            _ => Self::Mixed,
+        }
+    }
+}
+
+impl Texture {
+    /// Returns `true` if this texture holds sRGB encoded data when used for given material usage.
+    /// See [TextureUsage::color_space].
+    #[inline]
+    pub fn is_srgb(&self, usage: TextureUsage) -> bool {
+        usage.color_space() == ColorSpace::Srgb
+    }
+}
+
+impl Material {
+    /// Returns an iterator over the textures referenced by this material, with their usages.
+    pub fn textures(&self) -> impl Iterator<Item = (TextureUsage, Id)> {
+        let pbr = self.pbr_metallic_roughness.as_ref();
+        [
+            (
+                TextureUsage::BaseColor,
+                pbr.and_then(|pbr| pbr.base_color_texture.as_ref())
+                    .map(|info| info.index),
+            ),
+            (
+                TextureUsage::MetallicRoughness,
+                pbr.and_then(|pbr| pbr.metallic_roughness_texture.as_ref())
+                    .map(|info| info.index),
+            ),
+            (
+                TextureUsage::Normal,
+                self.normal_texture.as_ref().map(|info| info.index),
+            ),
+            (
+                TextureUsage::Occlusion,
+                self.occlusion_texture.as_ref().map(|info| info.index),
+            ),
+            (
+                TextureUsage::Emissive,
+                self.emissive_texture.as_ref().map(|info| info.index),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(usage, texture)| Some((usage, texture?)))
+    }
+}
+
+impl Gltf {
+    /// Classifies the color space of every texture by its usages in all materials.
+    pub fn texture_color_spaces(&self) -> Vec<TextureColorSpace> {
+        let mut color_spaces = vec![TextureColorSpace::Unused; self.textures.len()];
+        for material in &self.materials {
+            for (usage, texture) in material.textures() {
+                if let Some(color_space) = color_spaces.get_mut(texture) {
+                    *color_space = color_space.merge(usage.color_space());
+                }
+            }
+        }
+        color_spaces
+    }
+
+    /// Classifies the color space of every image by the usages of all textures using it as source,
+    /// e.g. to select the [TextureFormat] when uploading images.
+    pub fn image_color_spaces(&self) -> Vec<TextureColorSpace> {
+        let mut color_spaces = vec![TextureColorSpace::Unused; self.images.len()];
+        for (texture, texture_color_space) in self.textures.iter().zip(self.texture_color_spaces())
+        {
+            if let Some(color_space) = texture
+                .source
+                .and_then(|source| color_spaces.get_mut(source))
+            {
+                *color_space = match texture_color_space {
+                    TextureColorSpace::Unused => continue,
+                    TextureColorSpace::Used(used) => color_space.merge(used),
+                    TextureColorSpace::Mixed => TextureColorSpace::Mixed,
+                };
+            }
+        }
+        color_spaces
+    }
+}
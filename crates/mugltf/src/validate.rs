@@ -0,0 +1,308 @@
+//! Validation of glTF object references and data bounds.
+
+use crate::{
+    model::{Accessor, Gltf, Size},
+    GltfObject, TextureUsage, ValidationError,
+};
+use alloc::vec::Vec;
+
+/// Extensions supported by this crate, which are accepted as required extensions by [Gltf::validate].
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "KHR_draco_mesh_compression",
+    "KHR_materials_emissive_strength",
+    "KHR_materials_ior",
+    "KHR_materials_unlit",
+    "KHR_node_visibility",
+    "KHR_texture_transform",
+];
+
+impl Gltf {
+    /// Validates this glTF, returning all errors found, or an empty list if it is valid.
+    ///
+    /// This checks that all object references exist, that accessors and buffer views are within the bounds of
+    /// their buffer views and buffers, and that all required extensions are [supported](SUPPORTED_EXTENSIONS).
+    /// Validating an asset once after parsing avoids failures when its data are used later, e.g. during rendering.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut validator = Validator {
+            gltf: self,
+            errors: Vec::new(),
+        };
+        validator.validate_root();
+        validator.validate_buffer_data();
+        validator.validate_animations();
+        validator.validate_images();
+        validator.validate_materials();
+        validator.validate_meshes();
+        validator.validate_nodes();
+        validator.validate_skins();
+        validator.validate_textures();
+        validator.errors
+    }
+}
+
+/// Validation state.
+struct Validator<'a> {
+    gltf: &'a Gltf,
+    errors: Vec<ValidationError>,
+}
+
+impl Validator<'_> {
+    /// Checks a reference, recording an error if the target does not exist.
+    fn reference(
+        &mut self,
+        object: GltfObject,
+        property: &'static str,
+        target: GltfObject,
+    ) -> bool {
+        let gltf = self.gltf;
+        let exists = match target {
+            GltfObject::Root => true,
+            GltfObject::Accessor(id) => id < gltf.accessors.len(),
+            GltfObject::Animation(id) => id < gltf.animations.len(),
+            GltfObject::AnimationSampler(animation, id) => gltf
+                .animations
+                .get(animation)
+                .map_or(false, |animation| id < animation.samplers.len()),
+            GltfObject::Buffer(id) => id < gltf.buffers.len(),
+            GltfObject::BufferView(id) => id < gltf.buffer_views.len(),
+            GltfObject::Camera(id) => id < gltf.cameras.len(),
+            GltfObject::Image(id) => id < gltf.images.len(),
+            GltfObject::Material(id) => id < gltf.materials.len(),
+            GltfObject::Mesh(id) => id < gltf.meshes.len(),
+            GltfObject::Node(id) => id < gltf.nodes.len(),
+            GltfObject::Sampler(id) => id < gltf.samplers.len(),
+            GltfObject::Scene(id) => id < gltf.scenes.len(),
+            GltfObject::Skin(id) => id < gltf.skins.len(),
+            GltfObject::Texture(id) => id < gltf.textures.len(),
+        };
+        if !exists {
+            self.errors.push(ValidationError::InvalidReference {
+                object,
+                property,
+                target,
+            });
+        }
+        exists
+    }
+
+    /// Checks that data ending at `byte_end` fits in `byte_length` bytes. `None` end means its computation overflowed.
+    fn bounds(&mut self, object: GltfObject, byte_end: Option<Size>, byte_length: Size) {
+        if byte_end.map_or(true, |byte_end| byte_end > byte_length) {
+            self.errors.push(ValidationError::OutOfBounds {
+                object,
+                byte_end: byte_end.unwrap_or(Size::MAX),
+                byte_length,
+            });
+        }
+    }
+
+    fn validate_root(&mut self) {
+        if let Some(scene) = self.gltf.scene {
+            self.reference(GltfObject::Root, "scene", GltfObject::Scene(scene));
+        }
+        for (id, scene) in self.gltf.scenes.iter().enumerate() {
+            for &node in &scene.nodes {
+                self.reference(GltfObject::Scene(id), "nodes", GltfObject::Node(node));
+            }
+        }
+
+        #[cfg(feature = "gltf-extensions")]
+        for name in &self.gltf.extensions_required {
+            if !SUPPORTED_EXTENSIONS.contains(&name.as_str()) {
+                self.errors
+                    .push(ValidationError::UnsupportedExtension(name.clone()));
+            }
+        }
+    }
+
+    fn validate_buffer_data(&mut self) {
+        let gltf = self.gltf;
+        for (id, view) in gltf.buffer_views.iter().enumerate() {
+            let object = GltfObject::BufferView(id);
+            if self.reference(object, "buffer", GltfObject::Buffer(view.buffer)) {
+                let byte_length = gltf.buffers[view.buffer].byte_length;
+                let byte_end = view.byte_offset.checked_add(view.byte_length);
+                self.bounds(object, byte_end, byte_length);
+            }
+        }
+
+        for (id, accessor) in gltf.accessors.iter().enumerate() {
+            let object = GltfObject::Accessor(id);
+            let element_size = accessor_element_size(accessor);
+            if let Some(view) = accessor.buffer_view {
+                if self.reference(object, "bufferView", GltfObject::BufferView(view)) {
+                    let view = &gltf.buffer_views[view];
+                    let stride = if view.byte_stride > 0 {
+                        view.byte_stride
+                    } else {
+                        element_size
+                    };
+                    if accessor.count > 0 {
+                        let byte_end = stride
+                            .checked_mul(accessor.count - 1)
+                            .and_then(|end| end.checked_add(accessor.byte_offset))
+                            .and_then(|end| end.checked_add(element_size));
+                        self.bounds(object, byte_end, view.byte_length);
+                    }
+                }
+            }
+
+            if let Some(sparse) = &accessor.sparse {
+                let indices = &sparse.indices;
+                let indices_view = GltfObject::BufferView(indices.buffer_view);
+                if self.reference(object, "sparse.indices.bufferView", indices_view) {
+                    let byte_end = sparse
+                        .count
+                        .checked_mul(indices.component_type.size())
+                        .and_then(|end| end.checked_add(indices.byte_offset));
+                    let byte_length = gltf.buffer_views[indices.buffer_view].byte_length;
+                    self.bounds(object, byte_end, byte_length);
+                }
+
+                let values = &sparse.values;
+                let values_view = GltfObject::BufferView(values.buffer_view);
+                if self.reference(object, "sparse.values.bufferView", values_view) {
+                    let byte_end = sparse
+                        .count
+                        .checked_mul(element_size)
+                        .and_then(|end| end.checked_add(values.byte_offset));
+                    let byte_length = gltf.buffer_views[values.buffer_view].byte_length;
+                    self.bounds(object, byte_end, byte_length);
+                }
+            }
+        }
+    }
+
+    fn validate_animations(&mut self) {
+        for (id, animation) in self.gltf.animations.iter().enumerate() {
+            let object = GltfObject::Animation(id);
+            for channel in &animation.channels {
+                let sampler = GltfObject::AnimationSampler(id, channel.sampler);
+                self.reference(object, "channels.sampler", sampler);
+                let node = GltfObject::Node(channel.target.node);
+                self.reference(object, "channels.target.node", node);
+            }
+            for sampler in &animation.samplers {
+                let input = GltfObject::Accessor(sampler.input);
+                self.reference(object, "samplers.input", input);
+                let output = GltfObject::Accessor(sampler.output);
+                self.reference(object, "samplers.output", output);
+            }
+        }
+    }
+
+    fn validate_images(&mut self) {
+        for (id, image) in self.gltf.images.iter().enumerate() {
+            if let Some(view) = image.buffer_view {
+                let view = GltfObject::BufferView(view);
+                self.reference(GltfObject::Image(id), "bufferView", view);
+            }
+        }
+    }
+
+    fn validate_materials(&mut self) {
+        for (id, material) in self.gltf.materials.iter().enumerate() {
+            for (usage, texture) in material.textures() {
+                let property = match usage {
+                    TextureUsage::BaseColor => "pbrMetallicRoughness.baseColorTexture",
+                    TextureUsage::MetallicRoughness => {
+                        "pbrMetallicRoughness.metallicRoughnessTexture"
+                    }
+                    TextureUsage::Normal => "normalTexture",
+                    TextureUsage::Occlusion => "occlusionTexture",
+                    TextureUsage::Emissive => "emissiveTexture",
+                };
+                let texture = GltfObject::Texture(texture);
+                self.reference(GltfObject::Material(id), property, texture);
+            }
+        }
+    }
+
+    fn validate_meshes(&mut self) {
+        for (id, mesh) in self.gltf.meshes.iter().enumerate() {
+            let object = GltfObject::Mesh(id);
+            for primitive in &mesh.primitives {
+                for &accessor in primitive.attributes.values() {
+                    let accessor = GltfObject::Accessor(accessor);
+                    self.reference(object, "primitives.attributes", accessor);
+                }
+                if let Some(indices) = primitive.indices {
+                    let indices = GltfObject::Accessor(indices);
+                    self.reference(object, "primitives.indices", indices);
+                }
+                if let Some(material) = primitive.material {
+                    let material = GltfObject::Material(material);
+                    self.reference(object, "primitives.material", material);
+                }
+                for target in &primitive.targets {
+                    for &accessor in target.values() {
+                        let accessor = GltfObject::Accessor(accessor);
+                        self.reference(object, "primitives.targets", accessor);
+                    }
+                }
+
+                #[cfg(feature = "gltf-extensions")]
+                if let Some(draco) = primitive
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.draco_mesh_compression.as_ref())
+                {
+                    let view = GltfObject::BufferView(draco.buffer_view);
+                    let property = "primitives.extensions.KHR_draco_mesh_compression.bufferView";
+                    self.reference(object, property, view);
+                }
+            }
+        }
+    }
+
+    fn validate_nodes(&mut self) {
+        for (id, node) in self.gltf.nodes.iter().enumerate() {
+            let object = GltfObject::Node(id);
+            if let Some(camera) = node.camera {
+                self.reference(object, "camera", GltfObject::Camera(camera));
+            }
+            for &child in &node.children {
+                self.reference(object, "children", GltfObject::Node(child));
+            }
+            if let Some(skin) = node.skin {
+                self.reference(object, "skin", GltfObject::Skin(skin));
+            }
+            if let Some(mesh) = node.mesh {
+                self.reference(object, "mesh", GltfObject::Mesh(mesh));
+            }
+        }
+    }
+
+    fn validate_skins(&mut self) {
+        for (id, skin) in self.gltf.skins.iter().enumerate() {
+            let object = GltfObject::Skin(id);
+            if let Some(accessor) = skin.inverse_bind_matrices {
+                let accessor = GltfObject::Accessor(accessor);
+                self.reference(object, "inverseBindMatrices", accessor);
+            }
+            if let Some(skeleton) = skin.skeleton {
+                self.reference(object, "skeleton", GltfObject::Node(skeleton));
+            }
+            for &joint in &skin.joints {
+                self.reference(object, "joints", GltfObject::Node(joint));
+            }
+        }
+    }
+
+    fn validate_textures(&mut self) {
+        for (id, texture) in self.gltf.textures.iter().enumerate() {
+            let object = GltfObject::Texture(id);
+            if let Some(sampler) = texture.sampler {
+                self.reference(object, "sampler", GltfObject::Sampler(sampler));
+            }
+            if let Some(source) = texture.source {
+                self.reference(object, "source", GltfObject::Image(source));
+            }
+        }
+    }
+}
+
+/// Returns the byte size of an element of an accessor.
+fn accessor_element_size(accessor: &Accessor) -> Size {
+    accessor.component_type.size() * accessor.ty.components()
+}
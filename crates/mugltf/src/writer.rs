@@ -0,0 +1,228 @@
+//! glTF asset writer.
+
+#![cfg(feature = "serde")]
+
+use crate::{
+    asset::{GLBChunk, GLB_HEADER_LENGTH, GLB_HEADER_MAGIC},
+    model::{Gltf, Id},
+    GltfAsset,
+};
+use alloc::{string::String, vec::Vec};
+
+/// Options for writing a single-file glTF with embedded data URIs. See [GltfAsset::to_gltf_embedded].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmbedOptions {
+    /// Whether to move images stored in buffer views into data URIs.
+    pub embed_images: bool,
+    /// Size in bytes of the encoded data URI above which an [EmbedWarning] is reported.
+    pub warning_size: usize,
+}
+
+impl Default for EmbedOptions {
+    fn default() -> Self {
+        Self {
+            embed_images: true,
+            warning_size: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Warning about a large data URI embedded by [GltfAsset::to_gltf_embedded].
+/// Base64 data URIs are a third larger than the binary data, and slower to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmbedWarning {
+    /// A buffer was embedded into a data URI of given size.
+    LargeBuffer(Id, usize),
+    /// An image was embedded into a data URI of given size.
+    LargeImage(Id, usize),
+}
+
+impl<'a, ImageData> GltfAsset<'a, ImageData> {
+    /// Serializes this asset into a binary glTF (GLB) container.
+    ///
+    /// All buffers without URI are merged into the single BIN chunk, which becomes buffer 0,
+    /// with their buffer views rebased accordingly. Buffers with URI are kept as external references.
+    /// The data of a buffer without URI is taken from [GltfAsset::buffers] if loaded,
+    /// or from [GltfAsset::bin] otherwise.
+    pub fn to_glb(&self) -> Result<Vec<u8>, serde_json::Error> {
+        let (gltf, bin) = self.merge_embedded_buffers();
+
+        let mut json = serde_json::to_vec(&gltf)?;
+        json.resize(align4(json.len()), b' ');
+
+        let mut length = GLB_HEADER_LENGTH + 8 + json.len();
+        if !bin.is_empty() {
+            length += 8 + align4(bin.len());
+        }
+
+        let mut glb = Vec::with_capacity(length);
+        glb.extend_from_slice(GLB_HEADER_MAGIC);
+        glb.extend_from_slice(&2_u32.to_le_bytes());
+        glb.extend_from_slice(&(length as u32).to_le_bytes());
+
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(&(GLBChunk::Json as u32).to_le_bytes());
+        glb.extend_from_slice(&json);
+
+        if !bin.is_empty() {
+            glb.extend_from_slice(&(align4(bin.len()) as u32).to_le_bytes());
+            glb.extend_from_slice(&(GLBChunk::Bin as u32).to_le_bytes());
+            glb.extend_from_slice(&bin);
+            glb.resize(length, 0);
+        }
+
+        Ok(glb)
+    }
+
+    /// Serializes this asset into a single JSON glTF, with buffers embedded as base64 data URIs.
+    /// This is useful for tools that require pure-JSON assets, at the cost of larger files than [GltfAsset::to_glb].
+    ///
+    /// The data of a buffer is taken from [GltfAsset::buffers] if loaded, or from [GltfAsset::bin] for the GLB buffer.
+    /// Buffers without data keep their URI. With [EmbedOptions::embed_images],
+    /// images stored in buffer views are also moved into data URIs of their MIME type.
+    /// Their buffer views are kept, as they may be referenced elsewhere.
+    ///
+    /// Returns the JSON, and a warning for each data URI larger than [EmbedOptions::warning_size].
+    pub fn to_gltf_embedded(
+        &self,
+        options: &EmbedOptions,
+    ) -> Result<(String, Vec<EmbedWarning>), serde_json::Error> {
+        let mut gltf = self.gltf.clone();
+        let mut warnings = Vec::new();
+
+        if options.embed_images {
+            for (id, image) in gltf.images.iter_mut().enumerate() {
+                let data = image
+                    .buffer_view
+                    .and_then(|view| self.gltf.buffer_views.get(view))
+                    .and_then(|view| {
+                        self.buffer_data(view.buffer)?
+                            .get(view.byte_offset..(view.byte_offset + view.byte_length))
+                    });
+                if let Some(data) = data {
+                    image.uri = data_uri(&image.mime_type, data);
+                    image.buffer_view = None;
+                    if image.uri.len() > options.warning_size {
+                        warnings.push(EmbedWarning::LargeImage(id, image.uri.len()));
+                    }
+                }
+            }
+        }
+
+        for (id, buffer) in gltf.buffers.iter_mut().enumerate() {
+            if let Some(data) = self.buffer_data(id) {
+                let data = &data[..buffer.byte_length.min(data.len())];
+                buffer.uri = data_uri("application/octet-stream", data);
+                if buffer.uri.len() > options.warning_size {
+                    warnings.push(EmbedWarning::LargeBuffer(id, buffer.uri.len()));
+                }
+            }
+        }
+
+        Ok((serde_json::to_string(&gltf)?, warnings))
+    }
+
+    /// Returns the data of a buffer: the loaded data if any, or the BIN chunk for the GLB buffer.
+    fn buffer_data(&self, id: Id) -> Option<&[u8]> {
+        match self.buffers.get(id) {
+            Some(data) => Some(data),
+            None => {
+                let glb_buffer = self.gltf.buffers.iter().position(|b| b.uri.is_empty());
+                if glb_buffer == Some(id) && !self.bin.is_empty() {
+                    Some(&self.bin)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Returns a copy of the glTF model with all embedded buffers merged into buffer 0, and the merged data.
+    fn merge_embedded_buffers(&self) -> (Gltf, Vec<u8>) {
+        let mut gltf = self.gltf.clone();
+        let mut bin = Vec::new();
+
+        // New index and data offset of each buffer
+        let mut remap = Vec::with_capacity(gltf.buffers.len());
+        let mut external = Vec::new();
+        // The merged buffer keeps the other properties of the first buffer without URI
+        let merged = gltf.buffers.iter().find(|b| b.uri.is_empty()).cloned();
+        let mut bin_used = false;
+
+        for (id, buffer) in gltf.buffers.iter().enumerate() {
+            if buffer.uri.is_empty() {
+                let data: &[u8] = match self.buffers.get(id) {
+                    Some(data) => data,
+                    None if !bin_used => {
+                        bin_used = true;
+                        &self.bin
+                    }
+                    None => &[],
+                };
+                let offset = align4(bin.len());
+                bin.resize(offset, 0);
+                bin.extend_from_slice(&data[..buffer.byte_length.min(data.len())]);
+                remap.push((0, offset));
+            } else {
+                let new_id = merged.is_some() as usize + external.len();
+                external.push(buffer.clone());
+                remap.push((new_id, 0));
+            }
+        }
+
+        for view in gltf.buffer_views.iter_mut() {
+            if let Some(&(buffer, offset)) = remap.get(view.buffer) {
+                view.buffer = buffer;
+                view.byte_offset += offset;
+            }
+        }
+
+        gltf.buffers = match merged {
+            Some(mut merged) => {
+                merged.byte_length = bin.len();
+
+                let mut buffers = Vec::with_capacity(1 + external.len());
+                buffers.push(merged);
+                buffers.append(&mut external);
+                buffers
+            }
+            None => external,
+        };
+
+        (gltf, bin)
+    }
+}
+
+#[inline]
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Encodes data into a base64 data URI.
+fn data_uri(mime_type: &str, data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mime_type = if mime_type.is_empty() {
+        "application/octet-stream"
+    } else {
+        mime_type
+    };
+    let mut uri = String::with_capacity(13 + mime_type.len() + (data.len() + 2) / 3 * 4);
+    uri.push_str("data:");
+    uri.push_str(mime_type);
+    uri.push_str(";base64,");
+
+    for chunk in data.chunks(3) {
+        let bits = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                uri.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                uri.push('=');
+            }
+        }
+    }
+    uri
+}
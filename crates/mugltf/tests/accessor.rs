@@ -0,0 +1,119 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::{sparse_asset, view_asset};
+use mugltf::GltfAsset;
+
+#[test]
+fn read_accessor_sparse() {
+    let asset = sparse_asset();
+
+    let values = asset.read_accessor::<2>(0).unwrap().collect::<Vec<_>>();
+
+    assert_eq!(values, [[0., 1.], [10., 11.], [4., 5.], [30., 31.]]);
+}
+
+#[test]
+fn read_accessor_sparse_without_buffer_view() {
+    let asset = sparse_asset();
+
+    let values = asset.read_accessor::<1>(1).unwrap().collect::<Vec<_>>();
+
+    assert_eq!(values, [[0.], [0.], [255. / 65535.]]);
+}
+
+#[test]
+fn read_accessor_invalid() {
+    let mut asset = sparse_asset();
+
+    assert!(asset.read_accessor::<3>(0).is_none());
+    assert!(asset.read_accessor::<1>(2).is_none());
+
+    asset.gltf.accessors[0].sparse.as_mut().unwrap().count = 5;
+    assert!(asset.read_accessor::<2>(0).is_none());
+}
+
+#[test]
+fn view_accessor_tight() {
+    let asset = view_asset();
+
+    let view = asset.view_accessor::<[f32; 3]>(0).unwrap();
+
+    assert_eq!(view.len(), 2);
+    assert_eq!(view.as_slice(), Some(&[[1., 2., 3.], [4., 5., 6.]][..]));
+    assert_eq!(
+        view.iter().collect::<Vec<_>>(),
+        [[1., 2., 3.], [4., 5., 6.]]
+    );
+}
+
+#[test]
+fn view_accessor_strided() {
+    let asset = view_asset();
+
+    let view = asset.view_accessor::<[u16; 2]>(1).unwrap();
+
+    assert_eq!(view.as_slice(), None);
+    assert_eq!(view.iter().collect::<Vec<_>>(), [[7, 8], [9, 10]]);
+}
+
+#[test]
+fn view_accessor_sparse() {
+    let asset = sparse_asset();
+
+    let view = asset.view_accessor::<[f32; 2]>(0).unwrap();
+
+    assert_eq!(view.as_slice(), None);
+    assert_eq!(
+        view.iter().collect::<Vec<_>>(),
+        [[0., 1.], [10., 11.], [4., 5.], [30., 31.]]
+    );
+    assert_eq!(
+        asset
+            .view_accessor::<u16>(1)
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>(),
+        [0, 0, 255]
+    );
+}
+
+#[test]
+fn view_accessor_type_mismatch() {
+    let asset = view_asset();
+
+    assert!(asset.view_accessor::<[f32; 2]>(0).is_none());
+    assert!(asset.view_accessor::<[u32; 3]>(0).is_none());
+    assert!(asset.view_accessor::<[i16; 2]>(1).is_none());
+    assert!(asset.view_accessor::<f32>(2).is_none());
+}
+
+#[test]
+fn view_accessor_oversized_count() {
+    let gltf = r#"{
+        "asset": { "version": "2.0" },
+        "buffers": [{ "byteLength": 8 }],
+        "bufferViews": [{ "buffer": 0, "byteLength": 8 }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 4611686018427387904, "type": "SCALAR" },
+            { "bufferView": 0, "componentType": 5126, "count": 4611686018427387904, "type": "VEC2" },
+            {
+                "componentType": 5126, "count": 1, "type": "SCALAR",
+                "sparse": {
+                    "count": 4611686018427387904,
+                    "indices": { "bufferView": 0, "componentType": 5125 },
+                    "values": { "bufferView": 0 }
+                }
+            }
+        ]
+    }"#;
+    let mut asset = <GltfAsset>::parse_gltf(gltf).unwrap();
+    asset.buffers = vec![vec![0; 8]];
+
+    assert!(asset.view_accessor::<f32>(0).is_none());
+    assert!(asset.view_accessor::<[f32; 2]>(1).is_none());
+    assert!(asset.read_accessor::<1>(0).is_none());
+    assert!(asset.read_accessor::<2>(1).is_none());
+    assert!(asset.read_accessor::<1>(2).is_none());
+}
@@ -0,0 +1,156 @@
+#![cfg(all(feature = "serde", feature = "std"))]
+
+use mugltf::{
+    animation::{AnimationPlayer, AnimationSample, AnimationValue, KeyframeReduction},
+    AccessorComponentType, GltfAsset, Interpolation,
+};
+
+#[test]
+fn reduce_keyframes_interpolation_test() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/InterpolationTest/glTF/InterpolationTest.gltf"
+    ))
+    .unwrap();
+    asset.buffers =
+        vec![include_bytes!("./model/InterpolationTest/glTF/interpolation.bin").to_vec()];
+    let original = asset.gltf.clone();
+
+    let removed = asset.reduce_keyframes(KeyframeReduction::default());
+
+    assert_eq!(asset.gltf.buffers.len(), 2);
+    assert_eq!(asset.buffers.len(), 2);
+    assert_eq!(asset.gltf.buffers[1].byte_length, asset.buffers[1].len());
+
+    let mut kept = 0;
+    let mut total = 0;
+    for (animation, original_animation) in asset.gltf.animations.iter().zip(&original.animations) {
+        for (sampler, original_sampler) in
+            animation.samplers.iter().zip(&original_animation.samplers)
+        {
+            let input = &asset.gltf.accessors[sampler.input];
+            let output = &asset.gltf.accessors[sampler.output];
+            let original_input = &original.accessors[original_sampler.input];
+            total += original_input.count;
+            kept += input.count;
+
+            if let Interpolation::Cubicspline = sampler.interpolation {
+                assert_eq!(sampler.input, original_sampler.input);
+                assert_eq!(
+                    output.count,
+                    original.accessors[original_sampler.output].count
+                );
+            } else {
+                assert!(input.count >= 2 && input.count <= original_input.count);
+                assert_eq!(output.count, input.count);
+                assert_eq!(input.min.len(), 1);
+                assert_eq!(input.max, original_input.max);
+            }
+
+            if let AccessorComponentType::Short | AccessorComponentType::Byte =
+                output.component_type
+            {
+                assert!(output.normalized);
+            }
+        }
+    }
+    assert_eq!(total - kept, removed);
+}
+
+#[test]
+fn sample_morph_weights() {
+    let mut asset =
+        <GltfAsset>::parse_gltf(include_str!("./model/SimpleMorph/glTF/SimpleMorph.gltf")).unwrap();
+    asset.buffers = vec![
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin").to_vec(),
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin").to_vec(),
+    ];
+
+    let clip = asset.animation_clip(0).unwrap();
+    assert_eq!(clip.len(), 1);
+    assert_eq!(clip.duration(), 4.);
+    assert_eq!(
+        clip.sample(1.5),
+        [AnimationSample {
+            node: 0,
+            value: AnimationValue::Weights(vec![0.5, 1.])
+        }]
+    );
+    assert_eq!(
+        clip.sample(10.)[0].value,
+        AnimationValue::Weights(vec![0., 0.])
+    );
+    assert!(asset.animation_clip(1).is_none());
+}
+
+#[test]
+fn animation_player_cross_fade() {
+    let mut asset =
+        <GltfAsset>::parse_gltf(include_str!("./model/SimpleMorph/glTF/SimpleMorph.gltf")).unwrap();
+    asset.buffers = vec![
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin").to_vec(),
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin").to_vec(),
+    ];
+    let clip = asset.animation_clip(0).unwrap();
+    let weights_at = |time| match &clip.sample(time)[0].value {
+        AnimationValue::Weights(weights) => weights.clone(),
+        _ => unreachable!(),
+    };
+
+    let mut player = AnimationPlayer::new();
+    player.set_looping(true);
+    player.play(clip.clone());
+    player.update(5.5);
+    assert_eq!(player.time(), 1.5);
+    assert_eq!(player.pose()[&0].weights, Some(vec![0.5, 1.]));
+
+    player.cross_fade(clip.clone(), 1.);
+    player.update(0.25);
+    assert_eq!(player.fade_weight(), 0.25);
+    let (from, to) = (weights_at(1.75), weights_at(0.25));
+    let weights = player.pose()[&0].weights.clone().unwrap();
+    for ((weight, from), to) in weights.iter().zip(&from).zip(&to) {
+        assert!((weight - (from + (to - from) * 0.25)).abs() < 1e-6);
+    }
+
+    player.update(1.);
+    assert_eq!(player.fade_weight(), 1.);
+    assert_eq!(player.pose()[&0].weights, Some(weights_at(1.25)));
+
+    player.set_looping(false);
+    assert!(!player.is_finished());
+    player.update(10.);
+    assert!(player.is_finished());
+    assert_eq!(player.time(), clip.duration());
+}
+
+#[test]
+fn sample_interpolation_test() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/InterpolationTest/glTF/InterpolationTest.gltf"
+    ))
+    .unwrap();
+    asset.buffers =
+        vec![include_bytes!("./model/InterpolationTest/glTF/interpolation.bin").to_vec()];
+
+    for id in 0..asset.gltf.animations.len() {
+        let clip = asset.animation_clip(id).unwrap();
+        assert_eq!(clip.len(), asset.gltf.animations[id].channels.len());
+
+        let mut time = 0.;
+        while time <= clip.duration() + 0.5 {
+            for sample in clip.sample(time) {
+                match sample.value {
+                    AnimationValue::Rotation(q) => {
+                        let len = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+                        assert!((len - 1.).abs() < 1e-5);
+                    }
+                    AnimationValue::Translation(v) | AnimationValue::Scale(v) => {
+                        assert!(v.iter().all(|v| v.is_finite()));
+                    }
+                    AnimationValue::Weights(_) => unreachable!(),
+                }
+            }
+            time += 0.1;
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Asset fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use mugltf::GltfAsset;
+
+/// Parses an asset from glTF JSON, with given data as its only buffer.
+pub fn asset_with_buffer(gltf: &str, data: Vec<u8>) -> GltfAsset<'static> {
+    let mut asset = <GltfAsset>::parse_gltf(gltf).unwrap().into_owned();
+    asset.buffers = vec![data];
+    asset
+}
+
+/// Creates an asset with a VEC2 float accessor of 4 elements, with elements 1 and 3 replaced by sparse values,
+/// and a scalar accessor of 3 elements without buffer view, with element 2 replaced.
+pub fn sparse_asset() -> GltfAsset<'static> {
+    let mut data = Vec::new();
+    for value in [0_f32, 1., 2., 3., 4., 5., 6., 7.] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    for index in [1_u16, 3] {
+        data.extend_from_slice(&index.to_le_bytes());
+    }
+    for value in [10_f32, 11., 30., 31.] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&[2, 0, 0, 0]);
+    data.extend_from_slice(&255_u16.to_le_bytes());
+
+    let gltf = r#"{
+        "asset": { "version": "2.0" },
+        "buffers": [{ "byteLength": 58 }],
+        "bufferViews": [
+            { "buffer": 0, "byteLength": 32 },
+            { "buffer": 0, "byteOffset": 32, "byteLength": 4 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 16 },
+            { "buffer": 0, "byteOffset": 52, "byteLength": 6 }
+        ],
+        "accessors": [
+            {
+                "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC2",
+                "sparse": {
+                    "count": 2,
+                    "indices": { "bufferView": 1, "componentType": 5123 },
+                    "values": { "bufferView": 2 }
+                }
+            },
+            {
+                "componentType": 5123, "normalized": true, "count": 3, "type": "SCALAR",
+                "sparse": {
+                    "count": 1,
+                    "indices": { "bufferView": 3, "componentType": 5121 },
+                    "values": { "bufferView": 3, "byteOffset": 4 }
+                }
+            }
+        ]
+    }"#;
+
+    asset_with_buffer(gltf, data)
+}
+
+/// Creates an asset with a tightly packed VEC3 float accessor of 2 elements,
+/// and a VEC2 unsigned short accessor of 2 elements interleaved with padding.
+pub fn view_asset() -> GltfAsset<'static> {
+    let mut data = Vec::new();
+    for value in [1_f32, 2., 3., 4., 5., 6.] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in [7_u16, 8, 0, 0, 9, 10, 0, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let gltf = r#"{
+        "asset": { "version": "2.0" },
+        "buffers": [{ "byteLength": 40 }],
+        "bufferViews": [
+            { "buffer": 0, "byteLength": 24 },
+            { "buffer": 0, "byteOffset": 24, "byteLength": 16, "byteStride": 8 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3" },
+            { "bufferView": 1, "componentType": 5123, "count": 2, "type": "VEC2" }
+        ]
+    }"#;
+
+    asset_with_buffer(gltf, data)
+}
+
+pub const GRID_SIZE: u32 = 10;
+
+/// Creates a mesh of a flat grid with GRID_SIZE x GRID_SIZE vertices.
+pub fn grid_asset() -> GltfAsset<'static> {
+    let mut data = Vec::new();
+    for y in 0..GRID_SIZE {
+        for x in 0..GRID_SIZE {
+            for value in [x as f32, 0., y as f32] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    let positions_len = data.len();
+    let mut index_count = 0;
+    for y in 0..(GRID_SIZE - 1) {
+        for x in 0..(GRID_SIZE - 1) {
+            let i = y * GRID_SIZE + x;
+            for index in [
+                i,
+                i + GRID_SIZE,
+                i + 1,
+                i + 1,
+                i + GRID_SIZE,
+                i + GRID_SIZE + 1,
+            ] {
+                data.extend_from_slice(&(index as u16).to_le_bytes());
+                index_count += 1;
+            }
+        }
+    }
+
+    let gltf = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": {} }}],
+            "bufferViews": [
+                {{ "buffer": 0, "byteLength": {} }},
+                {{ "buffer": 0, "byteOffset": {}, "byteLength": {} }}
+            ],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": {}, "type": "VEC3" }},
+                {{ "bufferView": 1, "componentType": 5123, "count": {}, "type": "SCALAR" }}
+            ],
+            "meshes": [{{
+                "name": "grid",
+                "primitives": [{{ "attributes": {{ "POSITION": 0 }}, "indices": 1 }}]
+            }}]
+        }}"#,
+        data.len(),
+        positions_len,
+        positions_len,
+        data.len() - positions_len,
+        GRID_SIZE * GRID_SIZE,
+        index_count,
+    );
+
+    asset_with_buffer(&gltf, data)
+}
+
+#[cfg(feature = "render")]
+/// Two triangles of a mesh sharing interleaved positions and normals, with quantized texture coordinates.
+pub fn mesh_asset() -> GltfAsset<'static> {
+    let mut data = Vec::new();
+    for i in 0..3 {
+        for value in [i as f32, 0., 0., 0., 1., 0.] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(&[0, 0, 255, 0, 0, 255, 0, 0]);
+    data.extend_from_slice(&[0, 1, 2, 0]);
+
+    let gltf = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": {} }}],
+            "bufferViews": [
+                {{ "buffer": 0, "byteLength": 72, "byteStride": 24 }},
+                {{ "buffer": 0, "byteOffset": 72, "byteLength": 6 }},
+                {{ "buffer": 0, "byteOffset": 80, "byteLength": 3 }}
+            ],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                {{ "bufferView": 0, "byteOffset": 12, "componentType": 5126, "count": 3, "type": "VEC3" }},
+                {{ "bufferView": 1, "componentType": 5121, "normalized": true, "count": 3, "type": "VEC2" }},
+                {{ "bufferView": 2, "componentType": 5121, "count": 3, "type": "SCALAR" }}
+            ],
+            "images": [{{ "uri": "color.png" }}],
+            "samplers": [{{ "magFilter": 9728, "minFilter": 9987, "wrapS": 33071 }}],
+            "textures": [{{ "source": 0, "sampler": 0 }}, {{ "source": 0 }}],
+            "materials": [
+                {{ "pbrMetallicRoughness": {{ "baseColorTexture": {{ "index": 0 }} }} }},
+                {{ "doubleSided": true }}
+            ],
+            "meshes": [{{
+                "primitives": [
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3, "material": 0 }},
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3, "material": 0 }},
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1 }}, "material": 1 }}
+                ]
+            }}],
+            "nodes": [{{ "children": [1] }}, {{ "mesh": 0, "translation": [1, 2, 3] }}],
+            "scenes": [{{ "nodes": [0] }}]
+        }}"#,
+        data.len()
+    );
+
+    let mut asset = asset_with_buffer(&gltf, data);
+    asset
+        .images
+        .push((vec![255; 2 * 2 * 3], mugl::Extent2D(2, 2)));
+    asset
+}
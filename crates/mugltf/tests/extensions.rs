@@ -0,0 +1,206 @@
+#![cfg(all(feature = "serde", feature = "gltf-extensions"))]
+
+use async_trait::async_trait;
+use mugltf::{Accessor, DecompressAttribute, DecompressedMesh, GltfAsset, MeshDecompressor};
+use serde_json::json;
+
+const MATERIALS: &str = r#"{
+    "asset": { "version": "2.0" },
+    "materials": [
+        {
+            "pbrMetallicRoughness": {
+                "baseColorTexture": {
+                    "index": 0,
+                    "extensions": {
+                        "KHR_texture_transform": { "offset": [0.5, 0], "rotation": 1.5, "texCoord": 1 }
+                    }
+                }
+            },
+            "extensions": {
+                "KHR_materials_emissive_strength": { "emissiveStrength": 5 },
+                "KHR_materials_ior": {},
+                "KHR_materials_unlit": {},
+                "EXT_custom": { "value": 1 }
+            }
+        },
+        {}
+    ]
+}"#;
+
+#[test]
+fn parse_material_extensions() {
+    let asset = <GltfAsset>::parse_gltf(MATERIALS).unwrap();
+
+    let extensions = asset.gltf.materials[0].extensions.as_ref().unwrap();
+    assert_eq!(
+        extensions
+            .emissive_strength
+            .as_ref()
+            .unwrap()
+            .emissive_strength,
+        5.
+    );
+    assert_eq!(extensions.ior.as_ref().unwrap().ior, 1.5);
+    assert!(extensions.unlit.is_some());
+    assert_eq!(extensions.others["EXT_custom"], json!({ "value": 1 }));
+    assert!(asset.gltf.materials[1].extensions.is_none());
+
+    let transform = asset.gltf.materials[0]
+        .pbr_metallic_roughness
+        .as_ref()
+        .unwrap()
+        .base_color_texture
+        .as_ref()
+        .unwrap()
+        .extensions
+        .as_ref()
+        .unwrap()
+        .texture_transform
+        .as_ref()
+        .unwrap();
+    assert_eq!(transform.offset, [0.5, 0.]);
+    assert_eq!(transform.rotation, 1.5);
+    assert_eq!(transform.scale, [1., 1.]);
+    assert_eq!(transform.tex_coord, Some(1));
+}
+
+#[test]
+fn serialize_material_extensions() {
+    let asset = <GltfAsset>::parse_gltf(MATERIALS).unwrap();
+
+    let json = serde_json::to_value(&asset.gltf.materials[0]).unwrap();
+
+    assert_eq!(
+        json["extensions"],
+        json!({
+            "KHR_materials_emissive_strength": { "emissiveStrength": 5.0 },
+            "KHR_materials_ior": { "ior": 1.5 },
+            "KHR_materials_unlit": {},
+            "EXT_custom": { "value": 1 }
+        })
+    );
+    assert_eq!(
+        json["pbrMetallicRoughness"]["baseColorTexture"]["extensions"],
+        json!({
+            "KHR_texture_transform": { "offset": [0.5, 0.0], "rotation": 1.5, "scale": [1.0, 1.0], "texCoord": 1 }
+        })
+    );
+}
+
+const DRACO_TRIANGLE: &str = r#"{
+    "asset": { "version": "2.0" },
+    "extensionsUsed": ["KHR_draco_mesh_compression"],
+    "extensionsRequired": ["KHR_draco_mesh_compression"],
+    "buffers": [{ "byteLength": 4 }],
+    "bufferViews": [{ "buffer": 0, "byteLength": 4 }],
+    "accessors": [
+        { "componentType": 5121, "count": 3, "type": "SCALAR" },
+        { "componentType": 5126, "count": 3, "type": "VEC3" }
+    ],
+    "meshes": [{
+        "primitives": [{
+            "attributes": { "POSITION": 1 },
+            "indices": 0,
+            "extensions": {
+                "KHR_draco_mesh_compression": { "bufferView": 0, "attributes": { "POSITION": 7 } }
+            }
+        }]
+    }]
+}"#;
+
+/// Decompressor that expects the compressed data to be `DRCO`, and outputs a fixed triangle.
+struct TriangleDecompressor;
+
+#[async_trait(?Send)]
+impl MeshDecompressor for TriangleDecompressor {
+    type Error = Box<dyn std::error::Error>;
+
+    async fn decompress(
+        &self,
+        data: &[u8],
+        indices: Option<&Accessor>,
+        attributes: &[DecompressAttribute<'_>],
+    ) -> Result<DecompressedMesh, Self::Error> {
+        if data != b"DRCO" || indices.is_none() || attributes.len() != 1 || attributes[0].id != 7 {
+            return Err("unexpected input".into());
+        }
+        let positions: [f32; 9] = [0., 0., 0., 1., 0., 0., 0., 1., 0.];
+        Ok(DecompressedMesh {
+            indices: vec![0, 1, 2],
+            attributes: vec![positions.iter().flat_map(|v| v.to_le_bytes()).collect()],
+        })
+    }
+}
+
+#[test]
+fn decompress_draco_meshes() {
+    let mut asset = <GltfAsset>::parse_gltf(DRACO_TRIANGLE).unwrap();
+    asset.buffers = vec![b"DRCO".to_vec()];
+
+    pollster::block_on(asset.decompress_meshes(&TriangleDecompressor)).unwrap();
+
+    assert!(asset.gltf.extensions_required.is_empty());
+    let primitive = &asset.gltf.meshes[0].primitives[0];
+    assert!(primitive
+        .extensions
+        .as_ref()
+        .unwrap()
+        .draco_mesh_compression
+        .is_none());
+    assert_eq!(
+        asset.read_accessor::<1>(0).unwrap().collect::<Vec<_>>(),
+        [[0.], [1.], [2.]]
+    );
+    assert_eq!(
+        asset.read_accessor::<3>(1).unwrap().collect::<Vec<_>>(),
+        [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+    );
+}
+
+#[test]
+fn decompress_draco_meshes_error() {
+    let mut asset = <GltfAsset>::parse_gltf(DRACO_TRIANGLE).unwrap();
+    asset.buffers = vec![b"NONE".to_vec()];
+
+    assert!(pollster::block_on(asset.decompress_meshes(&TriangleDecompressor)).is_err());
+    assert!(asset.gltf.accessors[1].buffer_view.is_none());
+    assert_eq!(asset.buffers.len(), 1);
+}
+
+#[test]
+fn node_visibility() {
+    let asset = <GltfAsset>::parse_gltf(
+        r#"{
+            "asset": { "version": "2.0" },
+            "nodes": [
+                { "children": [1, 2] },
+                { "children": [3], "extensions": { "KHR_node_visibility": { "visible": false } } },
+                { "extensions": { "KHR_node_visibility": {} } },
+                { "extensions": { "KHR_node_visibility": { "visible": true } } }
+            ],
+            "scenes": [{ "nodes": [0] }]
+        }"#,
+    )
+    .unwrap();
+
+    assert!(asset.gltf.nodes[0].is_visible());
+    assert!(!asset.gltf.nodes[1].is_visible());
+    assert!(asset.gltf.nodes[2].is_visible());
+    assert!(asset.gltf.nodes[3].is_visible());
+
+    // Hidden nodes hide their descendants
+    assert_eq!(
+        asset
+            .gltf
+            .scene_nodes(0)
+            .map(|node| (node.id, node.visible))
+            .collect::<Vec<_>>(),
+        [(0, true), (1, false), (3, false), (2, true)]
+    );
+
+    let json = serde_json::to_value(&asset.gltf.nodes[1]).unwrap();
+    assert_eq!(
+        json["extensions"],
+        json!({ "KHR_node_visibility": { "visible": false } })
+    );
+}
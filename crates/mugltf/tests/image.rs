@@ -0,0 +1,167 @@
+use mugl::TextureFormat;
+use mugltf::{
+    decode_ktx2, image_mime_type, AsyncImageDecoder, DecodeImageError, DelegateImageDecoder,
+    KTX2_MIME_TYPE,
+};
+
+/// Builds a 2D KTX2 image without supercompression, with only the base level.
+fn ktx2(vk_format: u32, width: u32, height: u32, data: &[u8]) -> Vec<u8> {
+    let mut ktx2 = vec![
+        0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+    ];
+    let level_offset = 80 + 24;
+    for value in [vk_format, 1, width, height, 0, 0, 1, 1, 0] {
+        ktx2.extend_from_slice(&value.to_le_bytes());
+    }
+    // Empty data format descriptor, key/value data and supercompression global data
+    ktx2.resize(80, 0);
+    for value in [level_offset, data.len() as u64, data.len() as u64] {
+        ktx2.extend_from_slice(&value.to_le_bytes());
+    }
+    ktx2.extend_from_slice(data);
+    ktx2
+}
+
+#[test]
+fn detect_mime_type() {
+    assert_eq!(
+        image_mime_type(include_bytes!(
+            "./model/AnimatedCube/glTF/AnimatedCube_BaseColor.png"
+        )),
+        Some("image/png")
+    );
+    assert_eq!(
+        image_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+        Some("image/jpeg")
+    );
+    assert_eq!(
+        image_mime_type(&ktx2(37, 1, 1, &[0; 4])),
+        Some(KTX2_MIME_TYPE)
+    );
+    assert_eq!(image_mime_type(b"GIF89a"), None);
+}
+
+#[test]
+fn decode_ktx2_image() {
+    let data = (0..2 * 2 * 4).collect::<Vec<u8>>();
+    let image = decode_ktx2(&ktx2(43, 2, 2, &data)).unwrap();
+    assert_eq!((image.width, image.height), (2, 2));
+    assert_eq!(image.format, TextureFormat::SRGBA8);
+    assert_eq!(image.data, data);
+}
+
+#[test]
+fn decode_ktx2_invalid() {
+    // Basis Universal encoded images have an undefined format
+    assert_eq!(
+        decode_ktx2(&ktx2(0, 1, 1, &[0; 4])),
+        Err(DecodeImageError::UnsupportedFormat)
+    );
+    assert_eq!(
+        decode_ktx2(&ktx2(37, 2, 2, &[0; 4])),
+        Err(DecodeImageError::InvalidData)
+    );
+    assert_eq!(decode_ktx2(&[0; 8]), Err(DecodeImageError::InvalidData));
+    assert_eq!(
+        decode_ktx2(&ktx2(109, u32::MAX, u32::MAX, &[0; 16])),
+        Err(DecodeImageError::InvalidData)
+    );
+}
+
+#[cfg(feature = "file-loader")]
+#[test]
+fn file_loader_decode_ktx2() {
+    use mugltf::{GltfResourceFileLoader, GltfResourceLoader};
+
+    let loader = GltfResourceFileLoader::default();
+    let data = (0..2 * 2 * 4).collect::<Vec<u8>>();
+    let (pixels, size) =
+        pollster::block_on(loader.decode_image(&ktx2(37, 2, 2, &data), KTX2_MIME_TYPE)).unwrap();
+    assert_eq!(size, mugl::Extent2D(2, 2));
+    assert_eq!(pixels, data);
+
+    // Only 8-bit RGBA images are supported
+    let image = ktx2(109, 1, 1, &[0; 16]);
+    assert!(pollster::block_on(loader.decode_image(&image, KTX2_MIME_TYPE)).is_err());
+}
+
+#[test]
+fn delegate_image_decode() {
+    let decoder = DelegateImageDecoder::new(|data: Vec<u8>, mime_type: String| async move {
+        assert_eq!(mime_type, KTX2_MIME_TYPE);
+        decode_ktx2(&data)
+    });
+
+    let data = (0..2 * 2 * 4).collect::<Vec<u8>>();
+    let image = pollster::block_on(decoder.decode(ktx2(37, 2, 2, &data), KTX2_MIME_TYPE)).unwrap();
+    assert_eq!(image.size(), mugl::Extent2D(2, 2));
+    assert_eq!(image.data, data);
+
+    assert_eq!(
+        pollster::block_on(decoder.decode(vec![0; 8], KTX2_MIME_TYPE)),
+        Err(DecodeImageError::InvalidData)
+    );
+}
+
+#[cfg(all(feature = "serde", feature = "file-loader", feature = "image-decode"))]
+#[test]
+fn load_images_decoded_on_threads() -> Result<(), Box<dyn std::error::Error>> {
+    use mugltf::{
+        DefaultImageDecoder, GltfAsset, GltfResourceFileLoader, GltfResourceLoader, ImageDecoder,
+        ImageDecodingLoader,
+    };
+    use std::{path::PathBuf, thread};
+
+    let mut root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    root.push("tests");
+    root.push("model/AnimatedCube/glTF/");
+
+    // Decoding starts on a thread when the image file is loaded, and is joined when awaited
+    let decoder = DelegateImageDecoder::new(|data: Vec<u8>, mime_type: String| {
+        let decoding = thread::spawn(move || {
+            ImageDecoder::decode(&DefaultImageDecoder, &data, &mime_type)
+                .map_err(|err| err.to_string())
+        });
+        async move { decoding.join().expect("decoding thread panicked") }
+    });
+    let mut loader = ImageDecodingLoader::new(GltfResourceFileLoader::default(), decoder);
+    loader.set_path(root.to_str().expect("invalid root path"));
+
+    let asset = pollster::block_on(GltfAsset::load(&loader, "AnimatedCube.gltf", true))?;
+
+    assert_eq!(asset.images.len(), asset.gltf.images.len());
+    for image in &asset.images {
+        assert_eq!(image.format, TextureFormat::RGBA8);
+        assert_eq!(image.data.len(), (image.width * image.height * 4) as usize);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(feature = "serde", feature = "file-loader", feature = "image-decode"))]
+#[test]
+fn load_decoded_images() -> Result<(), Box<dyn std::error::Error>> {
+    use mugltf::{
+        DefaultImageDecoder, GltfAsset, GltfResourceFileLoader, GltfResourceLoader,
+        ImageDecodingLoader,
+    };
+    use std::path::PathBuf;
+
+    let mut root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    root.push("tests");
+    root.push("model/AnimatedCube/glTF/");
+
+    let mut loader =
+        ImageDecodingLoader::new(GltfResourceFileLoader::default(), DefaultImageDecoder);
+    loader.set_path(root.to_str().expect("invalid root path"));
+
+    let asset = pollster::block_on(GltfAsset::load(&loader, "AnimatedCube.gltf", true))?;
+
+    assert_eq!(asset.images.len(), asset.gltf.images.len());
+    for image in &asset.images {
+        assert_eq!(image.format, TextureFormat::RGBA8);
+        assert_eq!(image.data.len(), (image.width * image.height * 4) as usize);
+    }
+
+    Ok(())
+}
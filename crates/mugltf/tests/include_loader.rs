@@ -0,0 +1,72 @@
+#![cfg(feature = "serde")]
+
+use mugltf::{
+    include_loader::resolve_uri, GltfAsset, GltfResourceIncludeLoader, GltfResourceLoader,
+};
+
+#[test]
+fn test_load_gltf_included() {
+    let mut loader: GltfResourceIncludeLoader = [
+        (
+            "assets/SimpleMorph.gltf",
+            &include_bytes!("./model/SimpleMorph/glTF/SimpleMorph.gltf")[..],
+        ),
+        (
+            "assets/simpleMorphGeometry.bin",
+            &include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin")[..],
+        ),
+        (
+            "assets/./simpleMorphAnimation.bin",
+            &include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin")[..],
+        ),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(loader.len(), 3);
+    loader.set_path("assets/");
+
+    let asset = pollster::block_on(GltfAsset::load(&loader, "SimpleMorph.gltf", true)).unwrap();
+
+    assert!(asset.bin.as_ref().is_empty());
+    assert_eq!(asset.buffers.len(), 2);
+    assert_eq!(
+        asset.buffers[1],
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin")
+    );
+}
+
+#[test]
+fn test_load_gltf_included_data_uri() {
+    let mut loader = GltfResourceIncludeLoader::new();
+    loader.insert(
+        "SimpleMorph.gltf",
+        include_bytes!("./model/SimpleMorph/glTF-Embedded/SimpleMorph.gltf"),
+    );
+
+    let asset = pollster::block_on(GltfAsset::load(&loader, "SimpleMorph.gltf", true)).unwrap();
+
+    assert_eq!(
+        asset.buffers,
+        [
+            include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin").to_vec(),
+            include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin").to_vec(),
+        ]
+    );
+}
+
+#[test]
+fn test_load_gltf_included_not_found() {
+    let loader = GltfResourceIncludeLoader::new();
+    assert!(pollster::block_on(GltfAsset::<Vec<u8>>::load(&loader, "missing.gltf", true)).is_err());
+}
+
+#[test]
+fn test_resolve_uri() {
+    assert_eq!(resolve_uri("./", "a%20b.bin"), "a b.bin");
+    assert_eq!(
+        resolve_uri("root/dir", "..\\tex/%E5%9C%96.png"),
+        "root/tex/\u{5716}.png"
+    );
+    assert_eq!(resolve_uri("root", "100%.bin"), "root/100%.bin");
+    assert_eq!(resolve_uri("", "../a.bin"), "../a.bin");
+}
@@ -1,7 +1,7 @@
 #![cfg(all(feature = "serde", feature = "file-loader"))]
 
-use mugltf::{GltfAsset, GltfResourceFileLoader, GltfResourceLoader};
-use std::{error::Error, path::PathBuf};
+use mugltf::{file_loader::resolve_path, GltfAsset, GltfResourceFileLoader, GltfResourceLoader};
+use std::{collections::HashMap, error::Error, io, path::PathBuf};
 
 #[test]
 fn test_load_gltf() -> Result<(), Box<dyn Error>> {
@@ -51,6 +51,55 @@ fn test_load_glb() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_load_gltf_custom_reader() -> Result<(), Box<dyn Error>> {
+    let gltf = include_str!("./model/SimpleMorph/glTF/SimpleMorph.gltf")
+        .replace("simpleMorphGeometry.bin", "bin\\\\geometry%20data.bin")
+        .replace("simpleMorphAnimation.bin", "./bin/../bin/animation%2Ebin");
+
+    // In-memory archive of the asset
+    let mut files = HashMap::new();
+    files.insert(PathBuf::from("pak/Simple Morph.gltf"), gltf.into_bytes());
+    files.insert(
+        PathBuf::from("pak/bin/geometry data.bin"),
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin").to_vec(),
+    );
+    files.insert(
+        PathBuf::from("pak/bin/animation.bin"),
+        include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin").to_vec(),
+    );
+
+    let mut loader = GltfResourceFileLoader::default();
+    loader.set_path("pak");
+    loader.set_reader(move |path| {
+        files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    });
+
+    let asset = pollster::block_on(GltfAsset::load(&loader, "Simple%20Morph.gltf", true))?;
+
+    assert_gltf_res_loaded(&asset);
+    assert_eq!(asset.buffers.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_resolve_path() {
+    assert_eq!(resolve_path("./", "a%20b.bin"), PathBuf::from("a b.bin"));
+    assert_eq!(
+        resolve_path("root/dir", "..\\tex/%E5%9C%96.png"),
+        PathBuf::from("root/tex/\u{5716}.png")
+    );
+    assert_eq!(
+        resolve_path("root", "100%.bin"),
+        PathBuf::from("root/100%.bin")
+    );
+    assert_eq!(resolve_path("", "../a.bin"), PathBuf::from("../a.bin"));
+}
+
 fn assert_gltf_res_loaded(asset: &GltfAsset) {
     assert!(asset.bin.as_ref().is_empty());
     assert_eq!(asset.gltf.buffers.len(), asset.buffers.len());
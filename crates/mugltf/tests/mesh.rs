@@ -0,0 +1,159 @@
+#![cfg(all(feature = "serde", feature = "std"))]
+
+mod common;
+
+use common::grid_asset;
+use mugltf::{mesh::MeshSimplification, AccessorComponentType, GltfAsset};
+use munum::{transform, Mat4, Vec3};
+
+#[test]
+fn generate_lods_grid() {
+    let mut asset = grid_asset();
+    let original_count = asset.gltf.accessors[1].count;
+
+    let created = asset.generate_lods(MeshSimplification::default());
+
+    assert_eq!(created, 3);
+    assert_eq!(asset.gltf.meshes.len(), 4);
+    assert_eq!(asset.gltf.buffers.len(), 2);
+    assert_eq!(asset.gltf.buffers[1].byte_length, asset.buffers[1].len());
+
+    let mut previous_count = original_count;
+    for lod in &asset.gltf.meshes[1..] {
+        let primitive = &lod.primitives[0];
+        assert_eq!(primitive.attributes["POSITION"], 0);
+        let indices = &asset.gltf.accessors[primitive.indices.unwrap()];
+        assert!(matches!(
+            indices.component_type,
+            AccessorComponentType::UnsignedShort
+        ));
+        assert_eq!(indices.count % 3, 0);
+        assert!(indices.count < previous_count);
+        previous_count = indices.count;
+    }
+    assert_eq!(asset.gltf.meshes[1].name, "grid_LOD1");
+
+    #[cfg(feature = "gltf-extras")]
+    {
+        let lods = asset.gltf.meshes[0].extras["lods"].as_array().unwrap();
+        assert_eq!(lods.len(), 3);
+        assert_eq!(lods[0]["mesh"], 1);
+        assert_eq!(lods[0]["ratio"], 0.5);
+    }
+}
+
+#[test]
+fn generate_lods_keeps_seams() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/AnimatedCube/glTF/AnimatedCube.gltf"
+    ))
+    .unwrap();
+    asset.buffers = vec![include_bytes!("./model/AnimatedCube/glTF/AnimatedCube.bin").to_vec()];
+    let original = asset.gltf.clone();
+
+    // Every vertex of the cube is on a seam, so no LOD can be generated
+    assert_eq!(asset.generate_lods(MeshSimplification::default()), 0);
+    assert_eq!(asset.gltf.meshes.len(), original.meshes.len());
+    assert_eq!(asset.gltf.buffers.len(), original.buffers.len());
+}
+
+#[test]
+fn skin_primitive_two_joints() {
+    let mut data = Vec::new();
+    for value in [0., 0., 0., 0., 2., 0., 1., 0., 0., 1., 0., 0.] {
+        data.extend_from_slice(&f32::to_le_bytes(value));
+    }
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 1, 0, 0]);
+    for value in [1., 0., 0., 0., 0.5, 0.5, 0., 0.] {
+        data.extend_from_slice(&f32::to_le_bytes(value));
+    }
+
+    let gltf = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": {} }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": {} }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3" }},
+                {{ "bufferView": 0, "byteOffset": 24, "componentType": 5126, "count": 2, "type": "VEC3" }},
+                {{ "bufferView": 0, "byteOffset": 48, "componentType": 5121, "count": 2, "type": "VEC4" }},
+                {{ "bufferView": 0, "byteOffset": 56, "componentType": 5126, "count": 2, "type": "VEC4" }}
+            ],
+            "meshes": [{{
+                "primitives": [{{
+                    "attributes": {{ "POSITION": 0, "NORMAL": 1, "JOINTS_0": 2, "WEIGHTS_0": 3 }},
+                    "mode": 0
+                }}]
+            }}]
+        }}"#,
+        data.len(),
+        data.len(),
+    );
+    let mut asset = <GltfAsset>::parse_gltf(&gltf).unwrap();
+    asset.buffers = vec![data];
+
+    let joint_matrices = [
+        Mat4::identity(),
+        transform::translation(Vec3::new([[0., 0., 2.]])),
+    ];
+    let skinned = asset.skin_primitive(0, 0, &joint_matrices).unwrap();
+    assert_eq!(skinned.positions, [0., 0., 0., 0., 2., 1.]);
+    assert_eq!(skinned.normals, [1., 0., 0., 1., 0., 0.]);
+
+    // Influences of missing joints are ignored
+    let skinned = asset.skin_primitive(0, 0, &joint_matrices[..1]).unwrap();
+    assert_eq!(skinned.positions, [0., 0., 0., 0., 2., 0.]);
+
+    assert!(asset.skin_primitive(0, 1, &joint_matrices).is_none());
+}
+
+#[test]
+fn skin_joint_matrices() {
+    let mut data = Vec::new();
+    let inverse_bind_matrices = [
+        Mat4::identity(),
+        transform::translation(Vec3::new([[0., -1., 0.]])),
+    ];
+    for matrix in &inverse_bind_matrices {
+        for value in matrix.as_ref() {
+            data.extend_from_slice(&f32::to_le_bytes(*value));
+        }
+    }
+
+    let gltf = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": {} }}],
+            "bufferViews": [{{ "buffer": 0, "byteLength": {} }}],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 2, "type": "MAT4" }}
+            ],
+            "nodes": [
+                {{ "children": [1], "translation": [2, 0, 0] }},
+                {{ "translation": [0, 1, 0] }}
+            ],
+            "scenes": [{{ "nodes": [0] }}],
+            "skins": [{{ "inverseBindMatrices": 0, "joints": [0, 1] }}]
+        }}"#,
+        data.len(),
+        data.len(),
+    );
+    let mut asset = <GltfAsset>::parse_gltf(&gltf).unwrap();
+    asset.buffers = vec![data];
+
+    let mut global_transforms = vec![Mat4::identity(); asset.gltf.nodes.len()];
+    for node in asset.gltf.scene_nodes(0) {
+        global_transforms[node.id] = node.world_transform;
+    }
+    let joint_matrices = asset.joint_matrices(0, &global_transforms).unwrap();
+    let translation = transform::translation(Vec3::new([[2., 0., 0.]]));
+    assert_eq!(joint_matrices, [translation, translation]);
+
+    // Joints default to identity inverse bind matrices
+    asset.gltf.skins[0].inverse_bind_matrices = None;
+    let joint_matrices = asset.joint_matrices(0, &global_transforms).unwrap();
+    assert_eq!(joint_matrices, global_transforms);
+
+    assert!(asset.joint_matrices(0, &global_transforms[..1]).is_none());
+    assert!(asset.joint_matrices(1, &global_transforms).is_none());
+}
@@ -1,7 +1,8 @@
 #![cfg(all(feature = "serde", feature = "gltf-name"))]
 
 use assert_json_diff::{assert_json_matches, CompareMode, Config, NumericMode};
-use mugltf::GltfAsset;
+use async_trait::async_trait;
+use mugltf::{AsyncByteStream, GlbStream, GltfAsset, ParseOptions};
 use serde_json::Value;
 
 #[test]
@@ -51,6 +52,124 @@ fn serde_interpolation_test_glb() -> Result<(), Error> {
     )
 }
 
+#[test]
+fn parse_with_skipped_sections() -> Result<(), Error> {
+    let options = ParseOptions {
+        animations: false,
+        skins: false,
+        ..Default::default()
+    };
+    let gltf = <GltfAsset>::parse_gltf_with(
+        include_str!("./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"),
+        &options,
+    )?
+    .gltf;
+
+    assert!(gltf.animations.is_empty());
+    assert!(gltf.skins.is_empty());
+    assert_eq!(gltf.meshes.len(), 1);
+    assert!(gltf.nodes.iter().all(|node| node.skin.is_none()));
+    assert_eq!(gltf.validate(), []);
+    Ok(())
+}
+
+#[test]
+fn parse_glb_with_skipped_sections() -> Result<(), Error> {
+    let options = ParseOptions {
+        materials: false,
+        textures: false,
+        ..Default::default()
+    };
+    let asset = <GltfAsset>::parse_glb_with(
+        include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb"),
+        &options,
+    )?;
+
+    assert!(asset.gltf.materials.is_empty());
+    assert!(asset.gltf.images.is_empty() && asset.gltf.textures.is_empty());
+    assert!(!asset.gltf.animations.is_empty());
+    assert!(asset
+        .gltf
+        .meshes
+        .iter()
+        .flat_map(|mesh| &mesh.primitives)
+        .all(|primitive| primitive.material.is_none()));
+    assert_eq!(asset.gltf.validate(), []);
+    assert_eq!(asset.gltf.buffers[0].byte_length, asset.bin.len());
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stream_glb() -> Result<(), Error> {
+    let glb = include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb");
+    let expected = <GltfAsset>::parse_glb(glb)?;
+
+    let stream = GlbStream::new(&glb[..], &ParseOptions::default())?;
+    assert_eq!(stream.bin_len(), expected.bin.len());
+    let asset = stream.into_asset::<()>()?;
+    assert_eq!(
+        serde_json::to_value(&asset.gltf)?,
+        serde_json::to_value(&expected.gltf)?
+    );
+    assert_eq!(asset.bin, expected.bin);
+    Ok(())
+}
+
+#[test]
+fn stream_glb_async() -> Result<(), Error> {
+    let glb = include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb");
+    let expected = <GltfAsset>::parse_glb(glb)?;
+
+    let mut stream = pollster::block_on(GlbStream::new_async(
+        ChunkedStream(&glb[..]),
+        &ParseOptions::default(),
+    ))?;
+    assert_eq!(
+        serde_json::to_value(stream.gltf())?,
+        serde_json::to_value(&expected.gltf)?
+    );
+
+    let mut bin = vec![0; stream.bin_len() + 10];
+    let mut len = 0;
+    while let Ok(read @ 1..) = pollster::block_on(stream.read_bin_async(&mut bin[len..])) {
+        len += read;
+    }
+    assert_eq!(stream.bin_remaining(), 0);
+    assert_eq!(&bin[..len], &expected.bin[..]);
+    Ok(())
+}
+
+#[test]
+fn stream_truncated_glb() {
+    let glb = include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb");
+    let truncated = ChunkedStream(&glb[..(glb.len() - 1)]);
+    let stream = pollster::block_on(GlbStream::new_async(truncated, &ParseOptions::default()));
+    assert!(pollster::block_on(stream.unwrap().into_asset_async::<()>()).is_err());
+    assert!(pollster::block_on(GlbStream::new_async(
+        ChunkedStream(&glb[..100]),
+        &ParseOptions::default()
+    ))
+    .is_err());
+}
+
+#[test]
+fn stream_glb_oversized_chunk() {
+    let mut glb = Vec::new();
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&u32::MAX.to_le_bytes());
+    glb.extend_from_slice(&u32::MAX.to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(b"{}");
+
+    assert!(pollster::block_on(GlbStream::new_async(
+        ChunkedStream(&glb[..]),
+        &ParseOptions::default()
+    ))
+    .is_err());
+}
+
 fn assert_serde_matches(json: &str) -> Result<(), Error> {
     let gltf = <GltfAsset>::parse_gltf(json)?;
     let gltf_value = serde_json::to_value(&gltf.gltf)?;
@@ -75,6 +194,21 @@ fn assert_deser_glb_matches(glb: &[u8], json: &str, bin: &[u8]) -> Result<(), Er
     Ok(())
 }
 
+/// A stream returning at most 7 bytes per read.
+struct ChunkedStream<'a>(&'a [u8]);
+
+#[async_trait(?Send)]
+impl AsyncByteStream for ChunkedStream<'_> {
+    type Error = Box<mugltf::Error>;
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let len = buf.len().min(self.0.len()).min(7);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
 #[derive(Debug)]
 enum Error {
     ParseError(mugltf::ParseGltfError),
@@ -0,0 +1,175 @@
+#![cfg(all(feature = "serde", feature = "render"))]
+
+mod common;
+
+use common::mesh_asset;
+use mugl::{
+    empty::{EmptyGPU, EmptyGPUDevice},
+    AddressMode, CullMode, FilterMode, IndexFormat, VertexFormat,
+};
+use mugltf::{
+    render::{GltfScene, InstantiateOptions, PipelineKey},
+    InstantiateGltfError, Sampler,
+};
+
+#[test]
+fn instantiate_scene() {
+    let asset = mesh_asset();
+    let mut keys = Vec::<PipelineKey>::new();
+    let scene = GltfScene::<EmptyGPU>::new(
+        &EmptyGPUDevice,
+        &asset,
+        InstantiateOptions::default(),
+        |key| keys.push(key.clone()),
+    )
+    .unwrap();
+
+    // Interleaved view, decoded texture coordinates, and 2 index buffers
+    assert_eq!(scene.buffers.len(), 4);
+    assert_eq!(scene.textures.len(), 1);
+    assert_eq!(scene.samplers.len(), 2);
+
+    // The first 2 primitives share the same pipeline
+    assert_eq!(scene.pipelines.len(), 2);
+    assert_eq!(keys, scene.pipeline_keys);
+    let primitives = &scene.meshes[0];
+    assert_eq!(primitives[0].pipeline, 0);
+    assert_eq!(primitives[1].pipeline, 0);
+    assert_eq!(primitives[2].pipeline, 1);
+
+    let key = &keys[0];
+    assert_eq!(key.buffers.len(), 2);
+    assert_eq!(key.buffers[0].stride, 24);
+    assert_eq!(
+        key.buffers[0]
+            .attributes
+            .iter()
+            .map(|attribute| (
+                attribute.format,
+                attribute.offset,
+                attribute.shader_location
+            ))
+            .collect::<Vec<_>>(),
+        [(VertexFormat::F32x3, 12, 1), (VertexFormat::F32x3, 0, 0)]
+    );
+    assert_eq!(key.buffers[1].stride, 8);
+    assert_eq!(key.buffers[1].attributes[0].format, VertexFormat::F32x2);
+    assert_eq!(key.index_format, Some(IndexFormat::UI16));
+    assert_eq!(key.cull_mode, CullMode::Back);
+    assert_eq!(primitives[0].count, 3);
+
+    assert_eq!(keys[1].index_format, None);
+    assert_eq!(keys[1].cull_mode, CullMode::None);
+    assert_eq!(primitives[2].count, 3);
+    assert_eq!(primitives[2].index_buffer, None);
+
+    assert_eq!(scene.nodes.len(), 1);
+    assert_eq!(scene.nodes[0].node, 1);
+    assert_eq!(
+        scene.nodes[0].world_transform.as_ref()[12..15],
+        [1., 2., 3.]
+    );
+
+    assert!(scene.texture(&asset.gltf, 0).is_some());
+    assert!(scene.texture(&asset.gltf, 1).is_some());
+    assert!(scene.texture(&asset.gltf, 2).is_none());
+}
+
+#[test]
+fn instantiate_unaligned_stride() {
+    let mut asset = mesh_asset();
+    asset.gltf.buffer_views[0].byte_stride = 26;
+    asset.gltf.buffer_views[0].byte_length = 76;
+    let mut keys = Vec::<PipelineKey>::new();
+    GltfScene::<EmptyGPU>::new(
+        &EmptyGPUDevice,
+        &asset,
+        InstantiateOptions::default(),
+        |key| keys.push(key.clone()),
+    )
+    .unwrap();
+
+    // Unaligned interleaved attributes are decoded into separate buffers
+    let key = &keys[0];
+    assert_eq!(key.buffers.len(), 3);
+    for layout in &key.buffers[..2] {
+        assert_eq!(layout.stride, 12);
+        assert_eq!(layout.attributes[0].format, VertexFormat::F32x3);
+        assert_eq!(layout.attributes[0].offset, 0);
+    }
+}
+
+#[test]
+fn instantiate_out_of_bounds_accessor() {
+    let instantiate = |asset: &mugltf::GltfAsset| {
+        GltfScene::<EmptyGPU>::new(
+            &EmptyGPUDevice,
+            asset,
+            InstantiateOptions::default(),
+            |_| (),
+        )
+        .err()
+    };
+
+    let mut asset = mesh_asset();
+    asset.gltf.accessors[0].count = 4;
+    assert_eq!(
+        instantiate(&asset),
+        Some(InstantiateGltfError::InvalidAccessor(0))
+    );
+
+    let mut asset = mesh_asset();
+    asset.gltf.accessors[1].byte_offset = usize::MAX - 3;
+    assert_eq!(
+        instantiate(&asset),
+        Some(InstantiateGltfError::InvalidAccessor(1))
+    );
+
+    let mut asset = mesh_asset();
+    asset.gltf.buffer_views[0].byte_offset = usize::MAX;
+    assert_eq!(
+        instantiate(&asset),
+        Some(InstantiateGltfError::InvalidAccessor(1))
+    );
+}
+
+#[test]
+fn instantiate_invalid_image() {
+    let mut asset = mesh_asset();
+    asset.images[0].0.pop();
+    let result = GltfScene::<EmptyGPU>::new(
+        &EmptyGPUDevice,
+        &asset,
+        InstantiateOptions::default(),
+        |_| (),
+    );
+    assert_eq!(result.err(), Some(InstantiateGltfError::InvalidImage(0)));
+}
+
+#[test]
+fn instantiate_oversized_image() {
+    let mut asset = mesh_asset();
+    asset.images[0].1 = mugl::Extent2D(u32::MAX, u32::MAX);
+    let result = GltfScene::<EmptyGPU>::new(
+        &EmptyGPUDevice,
+        &asset,
+        InstantiateOptions::default(),
+        |_| (),
+    );
+    assert_eq!(result.err(), Some(InstantiateGltfError::InvalidImage(0)));
+}
+
+#[test]
+fn sampler_descriptor() {
+    let asset = mesh_asset();
+    let descriptor = asset.gltf.samplers[0].descriptor();
+    assert_eq!(descriptor.mag_filter, FilterMode::Nearest);
+    assert_eq!(descriptor.min_filter, FilterMode::Linear);
+    assert_eq!(descriptor.mipmap_filter, FilterMode::Linear);
+    assert_eq!(descriptor.address_mode_u, AddressMode::ClampToEdge);
+    assert_eq!(descriptor.address_mode_v, AddressMode::Repeat);
+
+    let descriptor = Sampler::default().descriptor();
+    assert_eq!(descriptor.min_filter, FilterMode::Linear);
+    assert_eq!(descriptor.lod_max_clamp, 0.);
+}
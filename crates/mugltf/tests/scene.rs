@@ -0,0 +1,69 @@
+#![cfg(feature = "serde")]
+
+use mugltf::GltfAsset;
+
+#[test]
+fn default_scene() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+
+    assert_eq!(asset.gltf.scenes_iter().count(), 1);
+    assert_eq!(asset.gltf.default_scene().map(|(id, _)| id), Some(0));
+
+    // Falls back to the first scene
+    asset.gltf.scene = None;
+    assert_eq!(asset.gltf.default_scene().map(|(id, _)| id), Some(0));
+
+    asset.gltf.scene = Some(1);
+    assert!(asset.gltf.default_scene().is_none());
+}
+
+#[test]
+fn scene_nodes_depth_first() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    asset.gltf.nodes[1].translation = Some([1., 0., 0.]);
+
+    let nodes = asset.gltf.scene_nodes(0).collect::<Vec<_>>();
+
+    assert_eq!(
+        nodes.iter().map(|n| (n.id, n.parent)).collect::<Vec<_>>(),
+        [(0, None), (1, None), (2, Some(1))]
+    );
+    assert_eq!(&nodes[2].world_transform.as_ref()[12..15], &[1., 1., 0.]);
+    assert_eq!(asset.gltf.scene_nodes(1).count(), 0);
+}
+
+#[test]
+fn scene_nodes_skips_cycles() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    asset.gltf.nodes[2].children = vec![1, 5];
+
+    assert_eq!(asset.gltf.scene_nodes(0).count(), 3);
+}
+
+#[cfg(feature = "gltf-extras")]
+#[test]
+fn scene_nodes_extras_visibility() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    asset.gltf.nodes[1].extras = serde_json::json!({ "visible": false });
+
+    assert_eq!(
+        asset
+            .gltf
+            .scene_nodes(0)
+            .map(|n| (n.id, n.visible))
+            .collect::<Vec<_>>(),
+        [(0, true), (1, false), (2, false)]
+    );
+}
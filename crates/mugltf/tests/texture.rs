@@ -0,0 +1,68 @@
+#![cfg(feature = "serde")]
+
+use mugl::TextureFormat;
+use mugltf::{ColorSpace, GltfAsset, TextureColorSpace, TextureUsage};
+
+const MATERIALS: &str = r#"{
+    "asset": { "version": "2.0" },
+    "images": [{ "uri": "color.png" }, { "uri": "data.png" }, { "uri": "unused.png" }],
+    "textures": [{ "source": 0 }, { "source": 1 }, { "source": 1 }, { "source": 2 }],
+    "materials": [
+        {
+            "pbrMetallicRoughness": {
+                "baseColorTexture": { "index": 0 },
+                "metallicRoughnessTexture": { "index": 1 }
+            },
+            "normalTexture": { "index": 1 },
+            "emissiveTexture": { "index": 2 }
+        },
+        {
+            "occlusionTexture": { "index": 2 }
+        }
+    ]
+}"#;
+
+#[test]
+fn material_textures() {
+    let asset = <GltfAsset>::parse_gltf(MATERIALS).unwrap();
+
+    assert_eq!(
+        asset.gltf.materials[0].textures().collect::<Vec<_>>(),
+        [
+            (TextureUsage::BaseColor, 0),
+            (TextureUsage::MetallicRoughness, 1),
+            (TextureUsage::Normal, 1),
+            (TextureUsage::Emissive, 2),
+        ]
+    );
+    assert!(asset.gltf.textures[0].is_srgb(TextureUsage::BaseColor));
+    assert!(!asset.gltf.textures[1].is_srgb(TextureUsage::Normal));
+}
+
+#[test]
+fn texture_color_spaces() {
+    let asset = <GltfAsset>::parse_gltf(MATERIALS).unwrap();
+
+    let textures = asset.gltf.texture_color_spaces();
+    assert_eq!(
+        textures,
+        [
+            TextureColorSpace::Used(ColorSpace::Srgb),
+            TextureColorSpace::Used(ColorSpace::Linear),
+            TextureColorSpace::Mixed,
+            TextureColorSpace::Unused,
+        ]
+    );
+    assert_eq!(textures[0].texture_format(), TextureFormat::SRGBA8);
+    assert_eq!(textures[1].texture_format(), TextureFormat::RGBA8);
+
+    // Image 1 is used by a linear and a mixed texture
+    assert_eq!(
+        asset.gltf.image_color_spaces(),
+        [
+            TextureColorSpace::Used(ColorSpace::Srgb),
+            TextureColorSpace::Mixed,
+            TextureColorSpace::Unused,
+        ]
+    );
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "serde")]
+
+use mugltf::{GltfAsset, GltfObject, ValidationError};
+
+#[test]
+fn validate_sample_models() {
+    for gltf in [
+        include_str!("./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"),
+        include_str!("./model/SimpleSparseAccessor/glTF-Embedded/SimpleSparseAccessor.gltf"),
+        include_str!("./model/AnimatedCube/glTF/AnimatedCube.gltf"),
+    ] {
+        let asset = <GltfAsset>::parse_gltf(gltf).unwrap();
+        assert_eq!(asset.gltf.validate(), []);
+    }
+}
+
+#[test]
+fn validate_invalid_references() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    asset.gltf.scene = Some(1);
+    asset.gltf.nodes[0].mesh = Some(3);
+    asset.gltf.nodes[2].children = vec![5];
+    asset.gltf.buffer_views[0].buffer = 4;
+    asset.gltf.animations[0].channels[0].sampler = 4;
+
+    assert_eq!(
+        asset.gltf.validate(),
+        [
+            ValidationError::InvalidReference {
+                object: GltfObject::Root,
+                property: "scene",
+                target: GltfObject::Scene(1),
+            },
+            ValidationError::InvalidReference {
+                object: GltfObject::BufferView(0),
+                property: "buffer",
+                target: GltfObject::Buffer(4),
+            },
+            ValidationError::InvalidReference {
+                object: GltfObject::Animation(0),
+                property: "channels.sampler",
+                target: GltfObject::AnimationSampler(0, 4),
+            },
+            ValidationError::InvalidReference {
+                object: GltfObject::Node(0),
+                property: "mesh",
+                target: GltfObject::Mesh(3),
+            },
+            ValidationError::InvalidReference {
+                object: GltfObject::Node(2),
+                property: "children",
+                target: GltfObject::Node(5),
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate_out_of_bounds() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    let view = asset.gltf.accessors[0].buffer_view.unwrap();
+    let view_length = asset.gltf.buffer_views[view].byte_length;
+    let buffer_length = asset.gltf.buffers[asset.gltf.buffer_views[view].buffer].byte_length;
+    asset.gltf.buffer_views[view].byte_offset = buffer_length;
+    asset.gltf.accessors[0].byte_offset = view_length;
+
+    let errors = asset.gltf.validate();
+    assert!(errors.contains(&ValidationError::OutOfBounds {
+        object: GltfObject::BufferView(view),
+        byte_end: buffer_length + view_length,
+        byte_length: buffer_length,
+    }));
+    assert!(errors.iter().any(|error| matches!(
+        error,
+        ValidationError::OutOfBounds {
+            object: GltfObject::Accessor(0),
+            byte_length,
+            ..
+        } if *byte_length == view_length
+    )));
+    assert_eq!(
+        errors[0].to_string(),
+        format!(
+            "buffer view {} data ends at byte {}, exceeding the available {} bytes",
+            view,
+            buffer_length + view_length,
+            buffer_length
+        )
+    );
+}
+
+#[test]
+fn validate_overflowing_bounds() {
+    let mut asset = <GltfAsset>::parse_gltf(include_str!(
+        "./model/SimpleSkin/glTF-Embedded/SimpleSkin.gltf"
+    ))
+    .unwrap();
+    let view = asset.gltf.accessors[0].buffer_view.unwrap();
+    asset.gltf.buffer_views[view].byte_offset = usize::MAX;
+    asset.gltf.accessors[0].count = usize::MAX;
+
+    let errors = asset.gltf.validate();
+    assert!(errors.contains(&ValidationError::OutOfBounds {
+        object: GltfObject::BufferView(view),
+        byte_end: usize::MAX,
+        byte_length: asset.gltf.buffers[asset.gltf.buffer_views[view].buffer].byte_length,
+    }));
+    assert!(errors.contains(&ValidationError::OutOfBounds {
+        object: GltfObject::Accessor(0),
+        byte_end: usize::MAX,
+        byte_length: asset.gltf.buffer_views[view].byte_length,
+    }));
+}
+
+#[cfg(feature = "gltf-extensions")]
+#[test]
+fn validate_required_extensions() {
+    let asset = <GltfAsset>::parse_gltf(
+        r#"{
+            "asset": { "version": "2.0" },
+            "extensionsUsed": ["KHR_texture_transform", "KHR_texture_basisu"],
+            "extensionsRequired": ["KHR_texture_transform", "KHR_texture_basisu"]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        asset.gltf.validate(),
+        [ValidationError::UnsupportedExtension(
+            "KHR_texture_basisu".into()
+        )]
+    );
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "serde")]
+
+use mugltf::{EmbedOptions, EmbedWarning, GltfAsset};
+
+#[test]
+fn write_glb_round_trip() {
+    let glb = include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb");
+    let asset = <GltfAsset>::parse_glb(glb).unwrap();
+
+    let written = asset.to_glb().unwrap();
+    assert_eq!(written.len() % 4, 0);
+    assert_eq!(
+        u32::from_le_bytes(written[8..12].try_into().unwrap()) as usize,
+        written.len()
+    );
+
+    let parsed = <GltfAsset>::parse_glb(&written).unwrap();
+    assert_eq!(
+        serde_json::to_value(&parsed.gltf).unwrap(),
+        serde_json::to_value(&asset.gltf).unwrap()
+    );
+    assert_eq!(&parsed.bin[..asset.bin.len()], &asset.bin[..]);
+}
+
+#[test]
+fn write_glb_merges_embedded_buffers() {
+    let mut asset =
+        <GltfAsset>::parse_gltf(include_str!("./model/SimpleMorph/glTF/SimpleMorph.gltf")).unwrap();
+    let geometry = include_bytes!("./model/SimpleMorph/glTF/simpleMorphGeometry.bin");
+    let animation = include_bytes!("./model/SimpleMorph/glTF/simpleMorphAnimation.bin");
+    for buffer in asset.gltf.buffers.iter_mut() {
+        buffer.uri.clear();
+    }
+    asset.buffers = vec![geometry.to_vec(), animation.to_vec()];
+
+    let written = asset.to_glb().unwrap();
+    let parsed = <GltfAsset>::parse_glb(&written).unwrap();
+
+    assert_eq!(parsed.gltf.buffers.len(), 1);
+    assert_eq!(parsed.gltf.buffers[0].byte_length, parsed.bin.len());
+    assert_eq!(
+        parsed.gltf.buffer_views.len(),
+        asset.gltf.buffer_views.len()
+    );
+    for (view, original) in parsed
+        .gltf
+        .buffer_views
+        .iter()
+        .zip(asset.gltf.buffer_views.iter())
+    {
+        let data = &asset.buffers[original.buffer]
+            [original.byte_offset..(original.byte_offset + original.byte_length)];
+        assert_eq!(view.buffer, 0);
+        assert_eq!(view.byte_offset % 4, original.byte_offset % 4);
+        assert_eq!(
+            &parsed.bin[view.byte_offset..(view.byte_offset + view.byte_length)],
+            data
+        );
+    }
+}
+
+#[test]
+fn write_gltf_embedded_buffers() {
+    let mut asset = <GltfAsset>::parse_gltf(
+        r#"{
+            "asset": { "version": "2.0" },
+            "buffers": [{ "byteLength": 5, "uri": "hello.bin" }, { "byteLength": 4, "uri": "missing.bin" }],
+            "bufferViews": [{ "buffer": 0, "byteOffset": 1, "byteLength": 3 }],
+            "images": [{ "bufferView": 0, "mimeType": "image/png" }, { "uri": "external.png" }]
+        }"#,
+    )
+    .unwrap();
+    asset.buffers = vec![b"hello".to_vec()];
+
+    let (json, warnings) = asset
+        .to_gltf_embedded(&EmbedOptions {
+            embed_images: true,
+            warning_size: 30,
+        })
+        .unwrap();
+    let parsed = <GltfAsset>::parse_gltf(&json).unwrap();
+
+    assert_eq!(
+        parsed.gltf.buffers[0].uri,
+        "data:application/octet-stream;base64,aGVsbG8="
+    );
+    assert_eq!(parsed.gltf.buffers[1].uri, "missing.bin");
+    assert_eq!(parsed.gltf.images[0].uri, "data:image/png;base64,ZWxs");
+    assert_eq!(parsed.gltf.images[0].buffer_view, None);
+    assert_eq!(parsed.gltf.images[1].uri, "external.png");
+    assert_eq!(warnings, [EmbedWarning::LargeBuffer(0, 45)]);
+}
+
+#[test]
+fn write_gltf_embedded_glb() {
+    let glb = include_bytes!("./model/InterpolationTest/glTF-Binary/InterpolationTest.glb");
+    let asset = <GltfAsset>::parse_glb(glb).unwrap();
+
+    let (json, _) = asset.to_gltf_embedded(&EmbedOptions::default()).unwrap();
+    let parsed = <GltfAsset>::parse_gltf(&json).unwrap();
+
+    assert_eq!(parsed.gltf.buffers.len(), asset.gltf.buffers.len());
+    assert!(parsed.gltf.buffers[0]
+        .uri
+        .starts_with("data:application/octet-stream;base64,"));
+    for image in &parsed.gltf.images {
+        assert!(image.uri.starts_with("data:image/"));
+        assert!(image.buffer_view.is_none());
+    }
+}
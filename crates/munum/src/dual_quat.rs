@@ -0,0 +1,315 @@
+use core::ops::{Mul, MulAssign};
+use num::traits::{NumAssign, One};
+
+#[cfg(any(feature = "std", feature = "libm"))]
+use num::traits::Float;
+
+use crate::{transform, Mat4, Quaternion, Vec3};
+
+/// A dual quaternion q = real + ε dual, representing a rigid transformation of a rotation followed by a translation.
+/// Unit dual quaternions can be blended without the volume loss of linear blend skinning.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct DualQuaternion<T: Copy + NumAssign = f32> {
+    /// The real part, i.e. the rotation.
+    pub real: Quaternion<T>,
+    /// The dual part, i.e. half the translation multiplied by the rotation.
+    pub dual: Quaternion<T>,
+}
+
+impl<T: Copy + NumAssign> DualQuaternion<T> {
+    /// Creates a dual quaternion from its real and dual parts.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{quat, DualQuaternion};
+    /// let dq = DualQuaternion::new(quat(1., 2., 3., 4.), quat(5., 6., 7., 8.));
+    /// assert_eq!(dq.to_array(), [1., 2., 3., 4., 5., 6., 7., 8.]);
+    /// ```
+    #[inline]
+    pub fn new(real: Quaternion<T>, dual: Quaternion<T>) -> Self {
+        Self { real, dual }
+    }
+
+    /// Creates an identity dual quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::DualQuaternion;
+    /// assert_eq!(<DualQuaternion>::identity().to_array(), [0., 0., 0., 1., 0., 0., 0., 0.]);
+    /// ```
+    #[inline]
+    pub fn identity() -> Self {
+        Self::new(Quaternion::identity(), Quaternion::default())
+    }
+
+    /// Creates a dual quaternion from a unit rotation quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{quat, DualQuaternion};
+    /// let dq = DualQuaternion::from_rotation(quat(0., 0., 1., 0.));
+    /// assert_eq!(dq.to_array(), [0., 0., 1., 0., 0., 0., 0., 0.]);
+    /// ```
+    #[inline]
+    pub fn from_rotation(rotation: Quaternion<T>) -> Self {
+        Self::new(rotation, Quaternion::default())
+    }
+
+    /// Creates a dual quaternion from a translation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion};
+    /// let dq = DualQuaternion::from_translation(vec3(2., 4., 6.));
+    /// assert_eq!(dq.to_array(), [0., 0., 0., 1., 1., 2., 3., 0.]);
+    /// ```
+    #[inline]
+    pub fn from_translation(translation: Vec3<T>) -> Self {
+        Self::from_rotation_translation(Quaternion::identity(), translation)
+    }
+
+    /// Creates a dual quaternion from a unit rotation quaternion followed by a translation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion, Quaternion, assert_float_eq};
+    /// let rotation = <Quaternion>::from_angle_z(core::f32::consts::PI / 2.);
+    /// let dq = DualQuaternion::from_rotation_translation(rotation, vec3(1., 2., 3.));
+    /// assert_float_eq!(
+    ///     dq,
+    ///     DualQuaternion::from_translation(vec3(1., 2., 3.)) * DualQuaternion::from_rotation(rotation)
+    /// );
+    /// assert_float_eq!(dq.transform_point(vec3(1., 0., 0.)), vec3(1., 3., 3.));
+    /// ```
+    pub fn from_rotation_translation(rotation: Quaternion<T>, translation: Vec3<T>) -> Self {
+        let half = T::one() / (T::one() + T::one());
+        Self::new(rotation, Quaternion::from(translation) * rotation * half)
+    }
+
+    /// Gets the rotation of this unit dual quaternion.
+    #[inline]
+    pub fn rotation(&self) -> Quaternion<T> {
+        self.real
+    }
+
+    /// Gets the translation of this unit dual quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion, Quaternion, assert_float_eq};
+    /// let dq = DualQuaternion::from_rotation_translation(<Quaternion>::from_angle_x(1.), vec3(1., 2., 3.));
+    /// assert_float_eq!(dq.translation(), vec3(1., 2., 3.));
+    /// ```
+    pub fn translation(&self) -> Vec3<T> {
+        let mut real = self.real;
+        real.conj();
+        let t = self.dual * real;
+        let two = T::one() + T::one();
+        Vec3::new([[t[0] * two, t[1] * two, t[2] * two]])
+    }
+
+    /// Transforms this dual quaternion into its quaternion conjugate, which is its inverse if it is a unit dual quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion, Quaternion, assert_float_eq};
+    /// let dq = DualQuaternion::from_rotation_translation(<Quaternion>::from_angle_y(1.), vec3(1., 2., 3.));
+    /// let mut inverse = dq;
+    /// inverse.conj();
+    /// assert_float_eq!(dq * inverse, <DualQuaternion>::identity());
+    /// ```
+    pub fn conj(&mut self) {
+        self.real.conj();
+        self.dual.conj();
+    }
+
+    /// Returns the result from transforming given point by this unit dual quaternion.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion};
+    /// let dq = DualQuaternion::from_translation(vec3(1., 2., 3.));
+    /// assert_eq!(dq.transform_point(vec3(1., 1., 1.)), vec3(2., 3., 4.));
+    /// ```
+    pub fn transform_point(&self, point: Vec3<T>) -> Vec3<T> {
+        let mut result = self.real.rotate_vec3(point);
+        result += self.translation();
+        result
+    }
+
+    /// Returns the 8 components of this dual quaternion, real part first, e.g. to upload to a GPU buffer.
+    #[inline]
+    pub fn to_array(&self) -> [T; 8] {
+        let (r, d) = (self.real, self.dual);
+        [r[0], r[1], r[2], r[3], d[0], d[1], d[2], d[3]]
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: Copy + Float + NumAssign> DualQuaternion<T> {
+    /// Normalizes this dual quaternion into a unit dual quaternion,
+    /// with a unit real part and a dual part orthogonal to it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{quat, vec3, DualQuaternion, assert_float_eq};
+    /// let mut dq = DualQuaternion::from_translation(vec3(1., 2., 3.));
+    /// dq.real *= 2.;
+    /// dq.dual *= 2.;
+    /// dq.normalize();
+    /// assert_float_eq!(dq, DualQuaternion::from_translation(vec3(1., 2., 3.)));
+    /// ```
+    pub fn normalize(&mut self) {
+        let len = self.real.len();
+        if len != T::zero() {
+            self.real /= len;
+            self.dual /= len;
+        }
+        let dot = self.real.dot(self.dual);
+        self.dual -= self.real * dot;
+    }
+
+    /// Blends weighted unit dual quaternions by dual quaternion linear blending (DLB), e.g. for skinning.
+    /// Each dual quaternion is negated as needed to take the shortest path from the first one,
+    /// and the weighted sum is normalized.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{vec3, DualQuaternion, Quaternion, assert_float_eq};
+    /// let a = DualQuaternion::from_translation(vec3(1., 0., 0.));
+    /// let b = DualQuaternion::from_translation(vec3(3., 0., 0.));
+    /// let blended = DualQuaternion::blend([(a, 0.5), (b, 0.5)]);
+    /// assert_float_eq!(blended.translation(), vec3(2., 0., 0.));
+    ///
+    /// // Negated dual quaternions represent the same transformation
+    /// let mut c = DualQuaternion::from_rotation(<Quaternion>::from_angle_z(1.));
+    /// c.real *= -1.;
+    /// let blended = DualQuaternion::blend([(<DualQuaternion>::identity(), 0.5), (c, 0.5)]);
+    /// assert_float_eq!(blended.rotation(), <Quaternion>::from_angle_z(0.5));
+    /// ```
+    pub fn blend(weighted: impl IntoIterator<Item = (Self, T)>) -> Self {
+        let mut result = Self::new(Quaternion::default(), Quaternion::default());
+        let mut first = None;
+        for (dq, weight) in weighted {
+            let pivot = *first.get_or_insert(dq.real);
+            let weight = if pivot.dot(dq.real) < T::zero() {
+                -weight
+            } else {
+                weight
+            };
+            result.real += dq.real * weight;
+            result.dual += dq.dual * weight;
+        }
+        if first.is_none() {
+            return Self::identity();
+        }
+        result.normalize();
+        result
+    }
+}
+
+impl<T: Copy + NumAssign> Default for DualQuaternion<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Copy + NumAssign> One for DualQuaternion<T> {
+    #[inline]
+    fn one() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Copy + NumAssign> From<DualQuaternion<T>> for [T; 8] {
+    #[inline]
+    fn from(dq: DualQuaternion<T>) -> Self {
+        dq.to_array()
+    }
+}
+
+impl<T: Copy + NumAssign> From<DualQuaternion<T>> for Mat4<T> {
+    /// Converts a unit `DualQuaternion` into a `Mat4` rigid transformation.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform, vec3, DualQuaternion, Mat4, Quaternion, assert_float_eq};
+    /// let rotation = <Quaternion>::from_angle_y(1.);
+    /// let m = Mat4::from(DualQuaternion::from_rotation_translation(rotation, vec3(1., 2., 3.)));
+    /// assert_float_eq!(m, transform::transformation(vec3(1., 2., 3.), rotation, vec3(1., 1., 1.)));
+    /// ```
+    #[inline]
+    fn from(dq: DualQuaternion<T>) -> Self {
+        let one = T::one();
+        transform::transformation(dq.translation(), dq.real, Vec3::new([[one, one, one]]))
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: Copy + Float + NumAssign> From<Mat4<T>> for DualQuaternion<T> {
+    /// Converts the rotation and translation of a `Mat4` TRS transformation into a `DualQuaternion`.
+    /// Scaling cannot be represented by dual quaternions and is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform, vec3, DualQuaternion, Quaternion, assert_float_eq};
+    /// let rotation = <Quaternion>::from_angle_x(1.);
+    /// let m = transform::transformation(vec3(1., 2., 3.), rotation, vec3(2., 2., 2.));
+    /// assert_float_eq!(
+    ///     DualQuaternion::from(m),
+    ///     DualQuaternion::from_rotation_translation(rotation, vec3(1., 2., 3.))
+    /// );
+    /// ```
+    #[inline]
+    fn from(m: Mat4<T>) -> Self {
+        Self::from_rotation_translation(transform::rotation_of(m), transform::translation_of(m))
+    }
+}
+
+// region: Arithmetic Ops
+
+impl<T: Copy + NumAssign> MulAssign for DualQuaternion<T> {
+    /// Multiplies 2 dual quaternions, which composes their transformations, applying `rhs` first.
+    fn mul_assign(&mut self, rhs: Self) {
+        self.dual = self.real * rhs.dual + self.dual * rhs.real;
+        self.real *= rhs.real;
+    }
+}
+
+impl<T: Copy + NumAssign> Mul for DualQuaternion<T> {
+    type Output = DualQuaternion<T>;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = self;
+        result *= rhs;
+        result
+    }
+}
+
+// endregion: Arithmetic Ops
+
+#[cfg(feature = "serde")]
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::DualQuaternion;
+    use crate::quat;
+
+    #[test]
+    fn test_serde() {
+        let dq = DualQuaternion::new(quat(1., 2., 3., 4.), quat(5., 6., 7., 8.));
+        let json: Value = serde_json::to_value(dq).unwrap();
+        assert_eq!(
+            json,
+            json!({ "real": [1., 2., 3., 4.], "dual": [5., 6., 7., 8.] })
+        );
+
+        let deserialized: DualQuaternion = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, dq);
+    }
+}
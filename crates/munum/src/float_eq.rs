@@ -1,7 +1,7 @@
 //! Float type comparison helpers.
 
 use num::traits::{float::FloatCore, NumAssign, NumCast};
-use crate::{Matrix, Quaternion};
+use crate::{DualQuaternion, Matrix, Quaternion};
 
 /// Standard tolerance epsilon
 pub const EPSILON: f32 = 128. * core::f32::EPSILON;
@@ -67,6 +67,15 @@ impl<T: Copy + FloatEq<T> + NumAssign> FloatEq<T> for Quaternion<T> {
     }
 }
 
+impl<T: Copy + FloatEq<T> + NumAssign> FloatEq<T> for DualQuaternion<T> {
+    #[inline]
+    fn float_eq(&self, rhs: Self, epsilon: T) -> bool {
+        self.to_array()
+            .as_ref()
+            .float_eq(rhs.to_array().as_ref(), epsilon)
+    }
+}
+
 /// Asserts two floats or float arrays are equal within an epsilon.
 /// # Examples
 /// ```
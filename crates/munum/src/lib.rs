@@ -8,16 +8,21 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+mod dual_quat;
 mod matrix;
 mod matrix_special;
 mod matrix_ops;
+mod matrix_stack;
 mod quat;
+mod swizzle;
 
 pub mod float_eq;
 pub mod scalar;
 pub mod transform;
 
+pub use dual_quat::DualQuaternion;
 pub use float_eq::FloatEq;
 pub use matrix::Matrix;
 pub use matrix_special::*;
+pub use matrix_stack::MatrixStack;
 pub use quat::{quat, Quaternion};
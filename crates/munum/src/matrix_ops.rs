@@ -177,6 +177,136 @@ impl<T: Copy + NumAssign, const R: usize, const C: usize> Rem<T> for Matrix<T, R
 
 // endregion: Scalar Ops
 
+// region: Component-wise Ops
+
+impl<T: Copy + NumAssign, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Returns a matrix with `f` applied to each component.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1, 2, 3).map(|x| x * x).as_ref(), [1, 4, 9]);
+    /// ```
+    pub fn map(&self, mut f: impl FnMut(T) -> T) -> Self {
+        let mut result = *self;
+        for c in 0..C {
+            for r in 0..R {
+                result.0[c][r] = f(self.0[c][r]);
+            }
+        }
+        result
+    }
+
+    /// Returns a matrix with `f` applied to each pair of corresponding components of 2 matrices.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1, 2, 3).zip_map(vec3(4, 5, 6), |a, b| a * b).as_ref(), [4, 10, 18]);
+    /// ```
+    pub fn zip_map(&self, rhs: Self, mut f: impl FnMut(T, T) -> T) -> Self {
+        let mut result = *self;
+        for c in 0..C {
+            for r in 0..R {
+                result.0[c][r] = f(self.0[c][r], rhs.0[c][r]);
+            }
+        }
+        result
+    }
+
+    /// Linear interpolates between 2 matrices component-wise.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::Matrix;
+    /// let (v1, v2) = (Matrix::<f32, 3, 1>::from_slice(&[1., 2., 3.]), Matrix::<f32, 3, 1>::from_slice(&[5., 6., 7.]));
+    /// assert_eq!(*v1.lerp(v2, 0.5).as_ref(), [3., 4., 5.]);
+    /// ```
+    #[inline]
+    pub fn lerp(&self, rhs: Self, t: T) -> Self {
+        self.zip_map(rhs, |a, b| scalar::lerp(a, b, t))
+    }
+}
+
+impl<T: Copy + NumAssign + PartialOrd, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Returns the component-wise minimum of 2 matrices.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1, 5, 3).min(vec3(4, 2, 3)).as_ref(), [1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn min(&self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| if b < a { b } else { a })
+    }
+
+    /// Returns the component-wise maximum of 2 matrices.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1, 5, 3).max(vec3(4, 2, 3)).as_ref(), [4, 5, 3]);
+    /// ```
+    #[inline]
+    pub fn max(&self, rhs: Self) -> Self {
+        self.zip_map(rhs, |a, b| if b > a { b } else { a })
+    }
+
+    /// Clamps each component between the corresponding components of `min` and `max`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(-1, 5, 3).clamp(vec3(0, 0, 0), vec3(4, 4, 4)).as_ref(), [0, 4, 3]);
+    /// ```
+    #[inline]
+    pub fn clamp(&self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+
+    /// Returns the component-wise absolute value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::Mat2;
+    /// assert_eq!(*Mat2::<i32>::from_slice(&[-1, 2, -3, 0]).abs().as_ref(), [1, 2, 3, 0]);
+    /// ```
+    #[inline]
+    pub fn abs(&self) -> Self {
+        self.map(scalar::abs)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<T: Copy + Float + NumAssign, const R: usize, const C: usize> Matrix<T, R, C> {
+    /// Returns the component-wise largest integers less than or equal to the components.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1.5, -1.5, 2.).floor().as_ref(), [1., -2., 2.]);
+    /// ```
+    #[inline]
+    pub fn floor(&self) -> Self {
+        self.map(Float::floor)
+    }
+
+    /// Returns the component-wise smallest integers greater than or equal to the components.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(*vec3(1.5, -1.5, 2.).ceil().as_ref(), [2., -1., 2.]);
+    /// ```
+    #[inline]
+    pub fn ceil(&self) -> Self {
+        self.map(Float::ceil)
+    }
+}
+
+// endregion: Component-wise Ops
+
 // region: Square Matrix Ops
 
 impl<T: Copy + NumAssign, const N: usize> One for Matrix<T, N, N> {
@@ -262,19 +392,40 @@ impl<T: Copy + NumAssign, const N: usize> Matrix<T, N, 1> {
     pub fn sqr_len(&self) -> T {
         self.dot(*self)
     }
+}
 
-    /// Linear interpolates between 2 column matrices aka vectors.
+impl<T: Copy + NumAssign + PartialOrd, const N: usize> Matrix<T, N, 1> {
+    /// Calculates the Manhattan (L1) length of a column matrix aka vector,
+    /// i.e. the sum of absolute values of its components.
     ///
     /// # Examples
     /// ```
-    /// # use munum::Matrix;
-    /// let (v1, v2) = (Matrix::<f32, 3, 1>::from_slice(&[1., 2., 3.]), Matrix::<f32, 3, 1>::from_slice(&[5., 6., 7.]));
-    /// assert_eq!(*v1.lerp(v2, 0.5).as_ref(), [3., 4., 5.]);
+    /// # use munum::vec3;
+    /// assert_eq!(vec3(3, -4, 1).manhattan_len(), 8);
     /// ```
-    pub fn lerp(&self, rhs: Self, t: T) -> Self {
-        let mut result = Self::default();
+    pub fn manhattan_len(&self) -> T {
+        let mut result = T::zero();
+        for i in 0..N {
+            result += scalar::abs(self.0[0][i]);
+        }
+        result
+    }
+
+    /// Calculates the Chebyshev (L-infinity) length of a column matrix aka vector,
+    /// i.e. the maximum absolute value of its components.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// assert_eq!(vec3(3, -4, 1).chebyshev_len(), 4);
+    /// ```
+    pub fn chebyshev_len(&self) -> T {
+        let mut result = T::zero();
         for i in 0..N {
-            result.0[0][i] = scalar::lerp(self.0[0][i], rhs.0[0][i], t);
+            let value = scalar::abs(self.0[0][i]);
+            if value > result {
+                result = value;
+            }
         }
         result
     }
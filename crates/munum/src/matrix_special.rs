@@ -306,6 +306,24 @@ impl<T: Copy + NumAssign> Mat2<T> {
             true
         }
     }
+
+    /// Returns the inverse of this matrix, or `None` if this matrix is not invertible.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2};
+    /// assert_eq!(*<Mat2>::from_slice(&[1., 2., 3., 4.]).try_invert().unwrap().as_ref(), [-2., 1., 1.5, -0.5]);
+    /// assert!(<Mat2>::from_slice(&[1., 2., 2., 4.]).try_invert().is_none());
+    /// ```
+    #[inline]
+    pub fn try_invert(&self) -> Option<Self> {
+        let mut m = *self;
+        if m.invert() {
+            Some(m)
+        } else {
+            None
+        }
+    }
 }
 
 impl<T: Copy + NumAssign> Mat3<T> {
@@ -368,6 +386,25 @@ impl<T: Copy + NumAssign> Mat3<T> {
         }
     }
 
+    /// Returns the inverse of this matrix, or `None` if this matrix is not invertible.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat3, assert_float_eq};
+    /// let m = <Mat3>::from_slice(&[1., 0., 5., 2., 1., 6., 3., 4., 0.]);
+    /// assert_float_eq!(m * m.try_invert().unwrap(), Mat3::identity());
+    /// assert!(<Mat3>::from_slice(&[1., 0., 1., 0., 1., 0., 0., 0., 0.]).try_invert().is_none());
+    /// ```
+    #[inline]
+    pub fn try_invert(&self) -> Option<Self> {
+        let mut m = *self;
+        if m.invert() {
+            Some(m)
+        } else {
+            None
+        }
+    }
+
     /// Transforms this matrix into a normal matrix, which is the inverse transpose of itself.
     /// If this matrix is not invertible, this method returns false and the matrix is unchanged.
     ///
@@ -426,6 +463,95 @@ impl<T: Copy + NumAssign> Mat4<T> {
     /// assert_eq!(*m.as_ref(), [0.25, 0.25, 0.25, -0.25, 0.25, 0.25, -0.25, 0.25, 0.25, -0.25, 0.25, 0.25, -0.25, 0.25, 0.25, 0.25]);
     /// ```
     pub fn invert(&mut self) -> bool {
+        let (adjugate, det) = self.adjugate_det();
+        if det == T::zero() {
+            false
+        } else {
+            *self = adjugate;
+            *self /= det;
+            true
+        }
+    }
+
+    /// Returns the inverse of this matrix, or `None` if this matrix is not invertible.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat4, assert_float_eq};
+    /// let m = <Mat4>::from_slice(&[2., 0., 0., 0., 1., 3., 0., 0., 0., 4., 1., 0., 5., 0., 2., 1.]);
+    /// assert_float_eq!(m * m.try_invert().unwrap(), Mat4::identity());
+    /// assert!(<Mat4>::default().try_invert().is_none());
+    /// ```
+    #[inline]
+    pub fn try_invert(&self) -> Option<Self> {
+        let mut m = *self;
+        if m.invert() {
+            Some(m)
+        } else {
+            None
+        }
+    }
+
+    /// Invert this matrix, assuming that it is an affine transformation, i.e. its last row is (0, 0, 0, 1).
+    /// This is more efficient than [Mat4::invert], and applies to any affine matrix, including those with shear,
+    /// unlike `transform::invert_trs`.
+    /// If the upper-left 3x3 matrix is not invertible, this method returns false and the matrix is unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat4, assert_float_eq};
+    /// let mut m = <Mat4>::from_slice(&[2., 0., 0., 0., 1., 3., 0., 0., 0., 4., 1., 0., 5., 0., 2., 1.]);
+    /// let expected = m.try_invert().unwrap();
+    /// assert!(m.invert_affine());
+    /// assert_float_eq!(m, expected);
+    /// ```
+    pub fn invert_affine(&mut self) -> bool {
+        // M = [A  t]  =>  M^-1 = [A^-1  A^-1 * -t]
+        //     [0  1]             [ 0        1    ]
+        let mut a = Mat3::from(*self);
+        if !a.invert() {
+            return false;
+        }
+        let neg = scalar::neg();
+        let t = Vec3::new([[self.0[3][0] * neg, self.0[3][1] * neg, self.0[3][2] * neg]]);
+        let t = a * t;
+
+        *self = Self::from(a);
+        self.0[3][0] = t.0[0][0];
+        self.0[3][1] = t.0[0][1];
+        self.0[3][2] = t.0[0][2];
+        true
+    }
+
+    /// Calculates the cofactor matrix of this matrix, i.e. the matrix of the signed minors of every element.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat4};
+    /// let m = Mat4::<i32>::from_slice(&[1, 1, 1, -1, 1, 1, -1, 1, 1, -1, 1, 1, -1, 1, 1, 1]);
+    /// assert_eq!(*m.cofactor().as_ref(), [-4, -4, -4, 4, -4, -4, 4, -4, -4, 4, -4, -4, 4, -4, -4, -4]);
+    /// ```
+    #[inline]
+    pub fn cofactor(&self) -> Self {
+        self.adjugate().transposed()
+    }
+
+    /// Calculates the adjugate matrix of this matrix, i.e. the transpose of its cofactor matrix,
+    /// which equals to the inverse of this matrix multiplied by its determinant.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat4};
+    /// let m = Mat4::<i32>::from_slice(&[2, 0, 0, 0, 0, 2, 0, 0, 0, 0, 2, 0, 0, 0, 0, 1]);
+    /// assert_eq!(*m.adjugate().as_ref(), [4, 0, 0, 0, 0, 4, 0, 0, 0, 0, 4, 0, 0, 0, 0, 8]);
+    /// ```
+    #[inline]
+    pub fn adjugate(&self) -> Self {
+        self.adjugate_det().0
+    }
+
+    /// Calculates the adjugate matrix and determinant of this matrix together, sharing the 2x2 sub-determinants.
+    fn adjugate_det(&self) -> (Self, T) {
         let fa0 = self.0[0][0] * self.0[1][1] - self.0[1][0] * self.0[0][1];
         let fa1 = self.0[0][0] * self.0[2][1] - self.0[2][0] * self.0[0][1];
         let fa2 = self.0[0][0] * self.0[3][1] - self.0[3][0] * self.0[0][1];
@@ -440,47 +566,34 @@ impl<T: Copy + NumAssign> Mat4<T> {
         let fb5 = self.0[2][2] * self.0[3][3] - self.0[3][2] * self.0[2][3];
 
         let det = fa0 * fb5 - fa1 * fb4 + fa2 * fb3 + fa3 * fb2 - fa4 * fb1 + fa5 * fb0;
-        if det == T::zero() {
-            false
-        } else {
-            let zero = T::zero();
-            let (m0, m1, m2, m3, m4, m5, m6, m7, m8, m9, m10, m11, m12, m13, m14, m15) = (
+        let zero = T::zero();
+        let adjugate = Self::new([
+            [
                 self.0[1][1] * fb5 - self.0[2][1] * fb4 + self.0[3][1] * fb3,
                 zero - self.0[0][1] * fb5 + self.0[2][1] * fb2 - self.0[3][1] * fb1,
                 self.0[0][1] * fb4 - self.0[1][1] * fb2 + self.0[3][1] * fb0,
                 zero - self.0[0][1] * fb3 + self.0[1][1] * fb1 - self.0[2][1] * fb0,
+            ],
+            [
                 zero - self.0[1][0] * fb5 + self.0[2][0] * fb4 - self.0[3][0] * fb3,
                 self.0[0][0] * fb5 - self.0[2][0] * fb2 + self.0[3][0] * fb1,
                 zero - self.0[0][0] * fb4 + self.0[1][0] * fb2 - self.0[3][0] * fb0,
                 self.0[0][0] * fb3 - self.0[1][0] * fb1 + self.0[2][0] * fb0,
+            ],
+            [
                 self.0[1][3] * fa5 - self.0[2][3] * fa4 + self.0[3][3] * fa3,
                 zero - self.0[0][3] * fa5 + self.0[2][3] * fa2 - self.0[3][3] * fa1,
                 self.0[0][3] * fa4 - self.0[1][3] * fa2 + self.0[3][3] * fa0,
                 zero - self.0[0][3] * fa3 + self.0[1][3] * fa1 - self.0[2][3] * fa0,
+            ],
+            [
                 zero - self.0[1][2] * fa5 + self.0[2][2] * fa4 - self.0[3][2] * fa3,
                 self.0[0][2] * fa5 - self.0[2][2] * fa2 + self.0[3][2] * fa1,
                 zero - self.0[0][2] * fa4 + self.0[1][2] * fa2 - self.0[3][2] * fa0,
                 self.0[0][2] * fa3 - self.0[1][2] * fa1 + self.0[2][2] * fa0,
-            );
-            self.0[0][0] = m0 / det;
-            self.0[0][1] = m1 / det;
-            self.0[0][2] = m2 / det;
-            self.0[0][3] = m3 / det;
-            self.0[1][0] = m4 / det;
-            self.0[1][1] = m5 / det;
-            self.0[1][2] = m6 / det;
-            self.0[1][3] = m7 / det;
-            self.0[2][0] = m8 / det;
-            self.0[2][1] = m9 / det;
-            self.0[2][2] = m10 / det;
-            self.0[2][3] = m11 / det;
-            self.0[3][0] = m12 / det;
-            self.0[3][1] = m13 / det;
-            self.0[3][2] = m14 / det;
-            self.0[3][3] = m15 / det;
-
-            true
-        }
+            ],
+        ]);
+        (adjugate, det)
     }
 }
 
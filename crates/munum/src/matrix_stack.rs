@@ -0,0 +1,223 @@
+use num::traits::NumAssign;
+
+use crate::Matrix;
+
+/// A fixed-capacity stack of square matrices, for immediate-style hierarchical transforms.
+///
+/// The stack always holds at least one matrix, which starts as identity.
+/// `D` is the maximum depth of the stack.
+///
+/// # Examples
+/// ```
+/// # use munum::{transform, vec3, Mat4, MatrixStack};
+/// let mut stack = MatrixStack::<f32, 4>::new();
+/// stack.push_mul(transform::translation(vec3(1., 0., 0.)));
+/// stack.push_mul(transform::translation(vec3(0., 2., 0.)));
+/// assert_eq!(transform::translation_of(*stack.top()), vec3(1., 2., 0.));
+///
+/// stack.pop();
+/// assert_eq!(transform::translation_of(*stack.top()), vec3(1., 0., 0.));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixStack<T: Copy + NumAssign, const N: usize, const D: usize = 32> {
+    stack: [Matrix<T, N, N>; D],
+    len: usize,
+}
+
+impl<T: Copy + NumAssign, const N: usize, const D: usize> MatrixStack<T, N, D> {
+    /// Creates a stack with an identity matrix on top.
+    ///
+    /// # Panics
+    /// Panics if `D` is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat3, MatrixStack};
+    /// let stack = MatrixStack::<f32, 3>::new();
+    /// assert_eq!(*stack.top(), Mat3::identity());
+    /// assert_eq!(stack.depth(), 1);
+    /// ```
+    #[inline]
+    pub fn new() -> Self {
+        assert!(D > 0, "matrix stack must have non-zero capacity");
+        Self {
+            stack: [Matrix::identity(); D],
+            len: 1,
+        }
+    }
+
+    /// Returns the number of matrices on the stack, which is at least 1.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::MatrixStack;
+    /// let mut stack = MatrixStack::<f32, 3>::new();
+    /// stack.push();
+    /// assert_eq!(stack.depth(), 2);
+    /// ```
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the matrix on top of the stack.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let stack = MatrixStack::<i32, 2>::new();
+    /// assert_eq!(*stack.top(), Mat2::identity());
+    /// ```
+    #[inline]
+    pub fn top(&self) -> &Matrix<T, N, N> {
+        &self.stack[self.len - 1]
+    }
+
+    /// Returns a mutable reference to the matrix on top of the stack.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.top_mut()[(0, 1)] = 2;
+    /// assert_eq!(*stack.top(), Mat2::new([[1, 0], [2, 1]]));
+    /// ```
+    #[inline]
+    pub fn top_mut(&mut self) -> &mut Matrix<T, N, N> {
+        &mut self.stack[self.len - 1]
+    }
+
+    /// Pushes a copy of the top matrix onto the stack.
+    ///
+    /// # Panics
+    /// Panics if the stack is full.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.load(Mat2::new([[1, 2], [3, 4]]));
+    /// stack.push();
+    /// assert_eq!(stack.depth(), 2);
+    /// assert_eq!(*stack.top(), Mat2::new([[1, 2], [3, 4]]));
+    /// ```
+    #[inline]
+    pub fn push(&mut self) {
+        self.push_load(*self.top());
+    }
+
+    /// Pushes the given matrix onto the stack.
+    ///
+    /// # Panics
+    /// Panics if the stack is full.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.push_load(Mat2::new([[1, 2], [3, 4]]));
+    /// assert_eq!(*stack.top(), Mat2::new([[1, 2], [3, 4]]));
+    /// ```
+    #[inline]
+    pub fn push_load(&mut self, m: Matrix<T, N, N>) {
+        assert!(self.len < D, "matrix stack overflow");
+        self.stack[self.len] = m;
+        self.len += 1;
+    }
+
+    /// Pushes the product of the top matrix and the given matrix onto the stack,
+    /// i.e. a child transform relative to the current one.
+    ///
+    /// # Panics
+    /// Panics if the stack is full.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.load(Mat2::new([[1, 2], [3, 4]]));
+    /// stack.push_mul(Mat2::new([[0, 1], [1, 0]]));
+    /// assert_eq!(*stack.top(), Mat2::new([[3, 4], [1, 2]]));
+    /// ```
+    #[inline]
+    pub fn push_mul(&mut self, m: Matrix<T, N, N>) {
+        self.push_load(*self.top() * m);
+    }
+
+    /// Pops the top matrix off the stack.
+    /// Returns `None` without modifying the stack if only 1 matrix is left.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.push_load(Mat2::new([[1, 2], [3, 4]]));
+    /// assert_eq!(stack.pop(), Some(Mat2::new([[1, 2], [3, 4]])));
+    /// assert_eq!(stack.pop(), None);
+    /// assert_eq!(*stack.top(), Mat2::identity());
+    /// ```
+    #[inline]
+    pub fn pop(&mut self) -> Option<Matrix<T, N, N>> {
+        if self.len > 1 {
+            self.len -= 1;
+            Some(self.stack[self.len])
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the top matrix.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.load(Mat2::new([[1, 2], [3, 4]]));
+    /// assert_eq!(*stack.top(), Mat2::new([[1, 2], [3, 4]]));
+    /// assert_eq!(stack.depth(), 1);
+    /// ```
+    #[inline]
+    pub fn load(&mut self, m: Matrix<T, N, N>) {
+        *self.top_mut() = m;
+    }
+
+    /// Multiplies the top matrix by the given matrix in place, i.e. `top = top * m`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.load(Mat2::new([[1, 2], [3, 4]]));
+    /// stack.load_mul(Mat2::new([[0, 1], [1, 0]]));
+    /// assert_eq!(*stack.top(), Mat2::new([[3, 4], [1, 2]]));
+    /// ```
+    #[inline]
+    pub fn load_mul(&mut self, m: Matrix<T, N, N>) {
+        *self.top_mut() *= m;
+    }
+
+    /// Resets the stack to a single identity matrix.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Mat2, MatrixStack};
+    /// let mut stack = MatrixStack::<i32, 2>::new();
+    /// stack.push_load(Mat2::new([[1, 2], [3, 4]]));
+    /// stack.clear();
+    /// assert_eq!(stack.depth(), 1);
+    /// assert_eq!(*stack.top(), Mat2::identity());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 1;
+        self.stack[0] = Matrix::identity();
+    }
+}
+
+impl<T: Copy + NumAssign, const N: usize, const D: usize> Default for MatrixStack<T, N, D> {
+    /// Creates a stack with an identity matrix on top.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
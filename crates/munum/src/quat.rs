@@ -154,6 +154,33 @@ impl<T: Copy + Float + NumAssign> Quaternion<T> {
     }
 }
 
+impl<T: Copy + NumAssign + NumCast> Quaternion<T> {
+    /// Creates a quaternion that approximates a small rotation given by a rotation vector (= unit axis * angle),
+    /// using polynomials instead of trigonometric functions. This is useful to integrate an angular velocity
+    /// over a fixed time step, where the rotation per step is small.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{Vec3, Quaternion, assert_float_eq};
+    /// let q = <Quaternion>::from_small_rotation(<Vec3>::from_slice(&[0., 0.1, 0.]));
+    /// assert_float_eq!(q, <Quaternion>::from_angle_y(0.1), 0.0000001);
+    /// ```
+    pub fn from_small_rotation(v: Vec3<T>) -> Self {
+        let one = T::one();
+        let cast = |value: f32| -> T { NumCast::from(value).expect("incompatible type") };
+        let mut half = v;
+        half /= cast(2.);
+        let angle2 = half.dot(half);
+        let sinc = one - angle2 / cast(6.) * (one - angle2 / cast(20.));
+        Self::from_slice(&[
+            half[0] * sinc,
+            half[1] * sinc,
+            half[2] * sinc,
+            one - angle2 / cast(2.) * (one - angle2 / cast(12.)),
+        ])
+    }
+}
+
 #[cfg(any(feature = "std", feature = "libm"))]
 impl<T: Copy + Float + FloatEq<T> + NumAssign + NumCast> Quaternion<T> {
     /// Creates a quaternion that represents the shortest arc rotation between 2 unit vectors.
@@ -33,6 +33,23 @@ pub fn sign<T: Copy + Num + PartialOrd>(a: T) -> T {
     }
 }
 
+/// Returns the absolute value of a.
+///
+/// # Examples
+/// ```
+/// assert_eq!(munum::scalar::abs(-2), 2);
+/// assert_eq!(munum::scalar::abs(0), 0);
+/// assert_eq!(munum::scalar::abs(3u32), 3);
+/// ```
+#[inline]
+pub fn abs<T: Copy + Num + PartialOrd>(a: T) -> T {
+    if a < T::zero() {
+        T::zero() - a
+    } else {
+        a
+    }
+}
+
 /// Composes a number from the magnitude of a and the sign of b,
 /// i.e. copysign(a, b) = sgn(b)|a|.
 ///
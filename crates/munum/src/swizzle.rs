@@ -0,0 +1,191 @@
+//! Component accessors and swizzles of vectors.
+
+use num::traits::NumAssign;
+
+use crate::{Vec2, Vec3, Vec4};
+
+/// Implements component accessors for a vector type.
+macro_rules! impl_accessors {
+    ($(#[$attr:meta])* $vec:ident { $($name:ident, $name_mut:ident => $c:literal),+ $(,)? }) => {
+        $(#[$attr])*
+        impl<T: Copy + NumAssign> $vec<T> {
+            $(
+                #[doc = concat!("Returns the ", stringify!($name), " component.")]
+                #[inline]
+                pub fn $name(&self) -> T {
+                    self.0[0][$c]
+                }
+
+                #[doc = concat!("Returns a mutable reference to the ", stringify!($name), " component.")]
+                #[inline]
+                pub fn $name_mut(&mut self) -> &mut T {
+                    &mut self.0[0][$c]
+                }
+            )+
+        }
+    };
+}
+
+/// Implements swizzles for a vector type, each returning a new vector of the listed components.
+macro_rules! impl_swizzles {
+    ($(#[$attr:meta])* $vec:ident { $($name:ident => $out:ident [$($c:literal),+]),+ $(,)? }) => {
+        $(#[$attr])*
+        impl<T: Copy + NumAssign> $vec<T> {
+            $(
+                #[doc = concat!("Returns a copy of the ", stringify!($name), " components as a `", stringify!($out), "`.")]
+                #[inline]
+                pub fn $name(&self) -> $out<T> {
+                    $out::new([[$(self.0[0][$c]),+]])
+                }
+            )+
+        }
+    };
+}
+
+impl_accessors!(
+    /// Component accessors.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec2;
+    /// let mut v = vec2(1, 2);
+    /// *v.y_mut() = 3;
+    /// assert_eq!((v.x(), v.y()), (1, 3));
+    /// ```
+    Vec2 { x, x_mut => 0, y, y_mut => 1 }
+);
+
+impl_accessors!(
+    /// Component accessors.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// let mut v = vec3(1, 2, 3);
+    /// *v.z_mut() += 1;
+    /// assert_eq!((v.x(), v.y(), v.z()), (1, 2, 4));
+    /// ```
+    Vec3 { x, x_mut => 0, y, y_mut => 1, z, z_mut => 2 }
+);
+
+impl_accessors!(
+    /// Component accessors.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec4;
+    /// let mut v = vec4(1, 2, 3, 4);
+    /// *v.w_mut() = 0;
+    /// assert_eq!((v.x(), v.y(), v.z(), v.w()), (1, 2, 3, 0));
+    /// ```
+    Vec4 { x, x_mut => 0, y, y_mut => 1, z, z_mut => 2, w, w_mut => 3 }
+);
+
+impl_swizzles!(
+    /// Swizzles.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec2;
+    /// assert_eq!(*vec2(1, 2).yx().as_ref(), [2, 1]);
+    /// ```
+    Vec2 {
+    yx => Vec2 [1, 0],
+    }
+);
+
+impl_swizzles!(
+    /// Swizzles. See also [Vec3::xy].
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec3;
+    /// let v = vec3(1, 2, 3);
+    /// assert_eq!(*v.xz().as_ref(), [1, 3]);
+    /// assert_eq!(*v.zyx().as_ref(), [3, 2, 1]);
+    /// ```
+    Vec3 {
+    xz => Vec2 [0, 2],
+    yx => Vec2 [1, 0],
+    yz => Vec2 [1, 2],
+    zx => Vec2 [2, 0],
+    zy => Vec2 [2, 1],
+    xzy => Vec3 [0, 2, 1],
+    yxz => Vec3 [1, 0, 2],
+    yzx => Vec3 [1, 2, 0],
+    zxy => Vec3 [2, 0, 1],
+    zyx => Vec3 [2, 1, 0],
+    }
+);
+
+impl_swizzles!(
+    /// Swizzles. See also [Vec4::xy] and [Vec4::xyz].
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::vec4;
+    /// let v = vec4(1, 2, 3, 4);
+    /// assert_eq!(*v.zw().as_ref(), [3, 4]);
+    /// assert_eq!(*v.xyw().as_ref(), [1, 2, 4]);
+    /// assert_eq!(*v.wzyx().as_ref(), [4, 3, 2, 1]);
+    /// ```
+    Vec4 {
+    xz => Vec2 [0, 2],
+    xw => Vec2 [0, 3],
+    yx => Vec2 [1, 0],
+    yz => Vec2 [1, 2],
+    yw => Vec2 [1, 3],
+    zx => Vec2 [2, 0],
+    zy => Vec2 [2, 1],
+    zw => Vec2 [2, 3],
+    wx => Vec2 [3, 0],
+    wy => Vec2 [3, 1],
+    wz => Vec2 [3, 2],
+    xyw => Vec3 [0, 1, 3],
+    xzy => Vec3 [0, 2, 1],
+    xzw => Vec3 [0, 2, 3],
+    xwy => Vec3 [0, 3, 1],
+    xwz => Vec3 [0, 3, 2],
+    yxz => Vec3 [1, 0, 2],
+    yxw => Vec3 [1, 0, 3],
+    yzx => Vec3 [1, 2, 0],
+    yzw => Vec3 [1, 2, 3],
+    ywx => Vec3 [1, 3, 0],
+    ywz => Vec3 [1, 3, 2],
+    zxy => Vec3 [2, 0, 1],
+    zxw => Vec3 [2, 0, 3],
+    zyx => Vec3 [2, 1, 0],
+    zyw => Vec3 [2, 1, 3],
+    zwx => Vec3 [2, 3, 0],
+    zwy => Vec3 [2, 3, 1],
+    wxy => Vec3 [3, 0, 1],
+    wxz => Vec3 [3, 0, 2],
+    wyx => Vec3 [3, 1, 0],
+    wyz => Vec3 [3, 1, 2],
+    wzx => Vec3 [3, 2, 0],
+    wzy => Vec3 [3, 2, 1],
+    xywz => Vec4 [0, 1, 3, 2],
+    xzyw => Vec4 [0, 2, 1, 3],
+    xzwy => Vec4 [0, 2, 3, 1],
+    xwyz => Vec4 [0, 3, 1, 2],
+    xwzy => Vec4 [0, 3, 2, 1],
+    yxzw => Vec4 [1, 0, 2, 3],
+    yxwz => Vec4 [1, 0, 3, 2],
+    yzxw => Vec4 [1, 2, 0, 3],
+    yzwx => Vec4 [1, 2, 3, 0],
+    ywxz => Vec4 [1, 3, 0, 2],
+    ywzx => Vec4 [1, 3, 2, 0],
+    zxyw => Vec4 [2, 0, 1, 3],
+    zxwy => Vec4 [2, 0, 3, 1],
+    zyxw => Vec4 [2, 1, 0, 3],
+    zywx => Vec4 [2, 1, 3, 0],
+    zwxy => Vec4 [2, 3, 0, 1],
+    zwyx => Vec4 [2, 3, 1, 0],
+    wxyz => Vec4 [3, 0, 1, 2],
+    wxzy => Vec4 [3, 0, 2, 1],
+    wyxz => Vec4 [3, 1, 0, 2],
+    wyzx => Vec4 [3, 1, 2, 0],
+    wzxy => Vec4 [3, 2, 0, 1],
+    wzyx => Vec4 [3, 2, 1, 0],
+    }
+);
@@ -1,11 +1,11 @@
 //! Transformation matrix functions.
 
-use num::traits::NumAssign;
+use num::traits::{NumAssign, NumCast};
 
 #[cfg(any(feature = "std", feature = "libm"))]
 use num::traits::Float;
 
-use crate::{scalar, Mat3, Mat4, Quaternion, Vec3, Vec4};
+use crate::{scalar, Mat3, Mat4, Quaternion, Vec2, Vec3, Vec4};
 
 // region: Affine transformations
 
@@ -208,10 +208,174 @@ pub fn invert_trs<T: Copy + Float + NumAssign>(m: &mut Mat4<T>) {
     m[(2, 3)] = t[2];
 }
 
+/// Creates a 3x3 transformation matrix that represents a 2D translation of (x, y).
+///
+/// # Examples
+/// ```
+/// # use munum::{transform, vec2, vec3};
+/// let m = transform::translation_2d(vec2(2_i32, 3));
+/// assert_eq!(*m.as_ref(), [1, 0, 0, 0, 1, 0, 2, 3, 1]);
+/// assert_eq!(*(m * vec3(1, 1, 1)).as_ref(), [3, 4, 1]);
+/// ```
+pub fn translation_2d<T: Copy + NumAssign>(v: Vec2<T>) -> Mat3<T> {
+    let mut result = Mat3::identity();
+    result[(0, 2)] = v[0];
+    result[(1, 2)] = v[1];
+    result
+}
+
+/// Creates a 3x3 transformation matrix that represents a 2D counter-clockwise rotation by
+/// the given number of quarter turns (90 degrees). Negative values rotate clockwise.
+/// The matrix only contains 0 and ±1, so it is exact for signed integer types.
+///
+/// # Examples
+/// ```
+/// # use munum::{transform, vec3};
+/// let m = transform::rotation90_2d::<i32>(1);
+/// assert_eq!(*m.as_ref(), [0, 1, 0, -1, 0, 0, 0, 0, 1]);
+/// assert_eq!(*(m * vec3(2, 1, 1)).as_ref(), [-1, 2, 1]);
+/// assert_eq!(transform::rotation90_2d::<i32>(-1), transform::rotation90_2d(3));
+/// assert_eq!(transform::rotation90_2d::<i32>(4), munum::mat3());
+/// ```
+pub fn rotation90_2d<T: Copy + NumAssign>(quarter_turns: i32) -> Mat3<T> {
+    let (zero, one, neg) = (T::zero(), T::one(), scalar::neg::<T>());
+    let (cos, sin) = match quarter_turns.rem_euclid(4) {
+        0 => (one, zero),
+        1 => (zero, one),
+        2 => (neg, zero),
+        _ => (zero, neg),
+    };
+
+    let mut result = Mat3::identity();
+    result[(0, 0)] = cos;
+    result[(1, 0)] = sin;
+    result[(0, 1)] = zero - sin;
+    result[(1, 1)] = cos;
+    result
+}
+
+/// Creates a 3x3 matrix that represents a rotation around the x-axis, from the sine and cosine of the angle.
+/// This avoids trigonometric functions when the sine and cosine are already known,
+/// e.g. when advanced incrementally by [advance_sin_cos].
+///
+/// # Examples
+/// ```
+/// # use core::f32::consts::PI;
+/// # use munum::{transform, Mat3, Quaternion, assert_float_eq};
+/// let m = transform::rotation_x_sin_cos((PI/3.).sin(), (PI/3.).cos());
+/// assert_float_eq!(m.as_ref(), Mat3::from(<Quaternion>::from_angle_x(PI/3.)).as_ref(), 0.00001);
+/// ```
+pub fn rotation_x_sin_cos<T: Copy + NumAssign>(sin: T, cos: T) -> Mat3<T> {
+    let mut result = Mat3::identity();
+    result[(1, 1)] = cos;
+    result[(2, 1)] = sin;
+    result[(1, 2)] = T::zero() - sin;
+    result[(2, 2)] = cos;
+    result
+}
+
+/// Creates a 3x3 matrix that represents a rotation around the y-axis, from the sine and cosine of the angle.
+///
+/// # Examples
+/// ```
+/// # use core::f32::consts::PI;
+/// # use munum::{transform, Mat3, Quaternion, assert_float_eq};
+/// let m = transform::rotation_y_sin_cos((PI/3.).sin(), (PI/3.).cos());
+/// assert_float_eq!(m.as_ref(), Mat3::from(<Quaternion>::from_angle_y(PI/3.)).as_ref(), 0.00001);
+/// ```
+pub fn rotation_y_sin_cos<T: Copy + NumAssign>(sin: T, cos: T) -> Mat3<T> {
+    let mut result = Mat3::identity();
+    result[(0, 0)] = cos;
+    result[(2, 0)] = T::zero() - sin;
+    result[(0, 2)] = sin;
+    result[(2, 2)] = cos;
+    result
+}
+
+/// Creates a 3x3 matrix that represents a rotation around the z-axis, from the sine and cosine of the angle.
+/// This is also the 2D counter-clockwise rotation in homogeneous coordinates.
+///
+/// # Examples
+/// ```
+/// # use core::f32::consts::PI;
+/// # use munum::{transform, vec3, Mat3, Quaternion, assert_float_eq};
+/// let m = transform::rotation_z_sin_cos((PI/3.).sin(), (PI/3.).cos());
+/// assert_float_eq!(m.as_ref(), Mat3::from(<Quaternion>::from_angle_z(PI/3.)).as_ref(), 0.00001);
+/// assert_eq!(transform::rotation_z_sin_cos(1, 0) * vec3(2, 1, 1), transform::rotation90_2d(1) * vec3(2, 1, 1));
+/// ```
+pub fn rotation_z_sin_cos<T: Copy + NumAssign>(sin: T, cos: T) -> Mat3<T> {
+    let mut result = Mat3::identity();
+    result[(0, 0)] = cos;
+    result[(1, 0)] = sin;
+    result[(0, 1)] = T::zero() - sin;
+    result[(1, 1)] = cos;
+    result
+}
+
+/// Advances an angle given as (sine, cosine) by a fixed step also given as (sine, cosine),
+/// i.e. returns the (sine, cosine) of the sum of the angles, without trigonometric functions.
+///
+/// The result is renormalized to the unit circle to a first-order approximation,
+/// so that rounding errors do not accumulate when a rotation is advanced by the same step every frame.
+///
+/// # Examples
+/// ```
+/// # use munum::{transform, assert_float_eq};
+/// let step = (0.01_f32.sin(), 0.01_f32.cos());
+/// let mut angle = (0., 1.);
+/// for _ in 0..10000 {
+///     angle = transform::advance_sin_cos(angle, step);
+/// }
+/// assert_float_eq!(angle.0, 100_f32.sin(), 0.001);
+/// assert_float_eq!(angle.1, 100_f32.cos(), 0.001);
+/// ```
+pub fn advance_sin_cos<T: Copy + NumAssign>(angle: (T, T), step: (T, T)) -> (T, T) {
+    let (sin, cos) = (
+        angle.0 * step.1 + angle.1 * step.0,
+        angle.1 * step.1 - angle.0 * step.0,
+    );
+
+    // One Newton iteration of 1/sqrt(x) around x = 1: (3 - x) / 2
+    let one = T::one();
+    let two = one + one;
+    let scale = (two + one - (sin * sin + cos * cos)) / two;
+    (sin * scale, cos * scale)
+}
+
+/// Approximates the (sine, cosine) of a small angle in radians with polynomials, without trigonometric functions.
+/// The absolute error is below 1e-7 for angles within ±0.2 radians, e.g. the per-frame step of a fast rotation.
+///
+/// # Examples
+/// ```
+/// # use munum::{transform, assert_float_eq};
+/// let (sin, cos) = transform::small_angle_sin_cos(0.1_f32);
+/// assert_float_eq!(sin, 0.1_f32.sin(), 0.0000001);
+/// assert_float_eq!(cos, 0.1_f32.cos(), 0.0000001);
+/// ```
+pub fn small_angle_sin_cos<T: Copy + NumAssign + NumCast>(angle: T) -> (T, T) {
+    let one = T::one();
+    let cast = |value: f32| -> T { NumCast::from(value).expect("incompatible type") };
+    let angle2 = angle * angle;
+    (
+        angle * (one - angle2 / cast(6.) * (one - angle2 / cast(20.))),
+        one - angle2 / cast(2.) * (one - angle2 / cast(12.)),
+    )
+}
+
 // endregion: Affine transformations
 
 // region: Projection matrices
 
+/// Depth range of the clip space, which differs between graphics APIs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DepthRange {
+    /// Depth in [-1, 1], as in OpenGL and WebGL.
+    #[default]
+    NegativeOneToOne,
+    /// Depth in [0, 1], as in WebGPU, Vulkan and Direct3D.
+    ZeroToOne,
+}
+
 /// Creates the {@link Mat4} orthographic projection matrix.
 /// To apply a glTF orthographic camera, use: left = -xmag, right = xmag, bottom = -ymag, top = ymag.
 /// See: <https://www.khronos.org/registry/glTF/specs/2.0/glTF-2.0.html#projection-matrices>
@@ -328,6 +492,131 @@ pub fn perspective_viewport<T: Copy + Float + NumAssign>(
     result
 }
 
+/// Creates the 4x4 orthographic projection matrix with reversed-Z, which maps znear to depth 1
+/// and zfar to the minimum depth of given clip space depth range.
+///
+/// # Examples
+/// ```
+/// # use munum::{transform::{self, DepthRange}, vec4, assert_float_eq};
+/// let m = transform::orthographic_reversed_z(-1., 1., -1., 1., 1., 9., DepthRange::ZeroToOne);
+/// assert_float_eq!(m.as_ref(), &[1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.125, 0., 0., 0., 1.125, 1.]);
+///
+/// let m = transform::orthographic_reversed_z(-1., 1., -1., 1., 1., 9., DepthRange::NegativeOneToOne);
+/// assert_float_eq!((m * vec4(0., 0., -1., 1.))[2], 1.);
+/// assert_float_eq!((m * vec4(0., 0., -9., 1.))[2], -1.);
+/// ```
+pub fn orthographic_reversed_z<T: Copy + NumAssign>(
+    left: T,
+    right: T,
+    bottom: T,
+    top: T,
+    znear: T,
+    zfar: T,
+    depth: DepthRange,
+) -> Mat4<T> {
+    let one = T::one();
+    let mut result = ortho(left, right, bottom, top, znear, zfar);
+    let z = one / (zfar - znear);
+    match depth {
+        DepthRange::NegativeOneToOne => {
+            result[(2, 2)] = (one + one) * z;
+            result[(2, 3)] = (zfar + znear) * z;
+        }
+        DepthRange::ZeroToOne => {
+            result[(2, 2)] = z;
+            result[(2, 3)] = zfar * z;
+        }
+    }
+    result
+}
+
+/// Creates the 4x4 perspective projection matrix with reversed-Z, which maps znear to depth 1
+/// and zfar to the minimum depth of given clip space depth range.
+/// Uses infinite projection if zfar = Infinity, where depth approaches the minimum at infinity.
+///
+/// Reversed-Z improves depth precision with floating point depth buffers only if depth is in [0, 1],
+/// as the precision of the [-1, 1] range is lost around 0 when mapped to the [0, 1] depth buffer.
+/// Use a depth compare function of `Greater` and clear depth to the minimum with reversed-Z.
+///
+/// # Examples
+/// ```
+/// # use core::f32::consts::PI;
+/// # use munum::{transform::{self, DepthRange}, vec4, assert_float_eq};
+/// let m = transform::perspective_reversed_z(2., PI/2., 1., 9., DepthRange::ZeroToOne);
+/// assert_float_eq!(m.as_ref(), &[0.5, 0., 0., 0., 0., 1., 0., 0., 0., 0., 0.125, -1., 0., 0., 1.125, 0.]);
+///
+/// let m = transform::perspective_reversed_z(2., PI/2., 1., 9., DepthRange::NegativeOneToOne);
+/// let (near, far) = (m * vec4(0., 0., -1., 1.), m * vec4(0., 0., -9., 1.));
+/// assert_float_eq!(near[2] / near[3], 1.);
+/// assert_float_eq!(far[2] / far[3], -1.);
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn perspective_reversed_z<T: Copy + Float + NumAssign>(
+    aspect: T,
+    yfov: T,
+    znear: T,
+    zfar: T,
+    depth: DepthRange,
+) -> Mat4<T> {
+    let one = T::one();
+    let two = one + one;
+    let mut result = perspective(aspect, yfov, znear, zfar);
+
+    if zfar.is_finite() {
+        let range_inv = one / (zfar - znear);
+        match depth {
+            DepthRange::NegativeOneToOne => {
+                result[(2, 2)] = (zfar + znear) * range_inv;
+                result[(2, 3)] = two * znear * zfar * range_inv;
+            }
+            DepthRange::ZeroToOne => {
+                result[(2, 2)] = znear * range_inv;
+                result[(2, 3)] = znear * zfar * range_inv;
+            }
+        }
+    } else {
+        match depth {
+            DepthRange::NegativeOneToOne => {
+                result[(2, 2)] = one;
+                result[(2, 3)] = two * znear;
+            }
+            DepthRange::ZeroToOne => {
+                result[(2, 2)] = T::zero();
+                result[(2, 3)] = znear;
+            }
+        }
+    }
+
+    result
+}
+
+/// Creates the 4x4 infinite perspective projection matrix with reversed-Z,
+/// i.e. [perspective_reversed_z] with zfar = Infinity.
+///
+/// # Examples
+/// ```
+/// # use core::f32::consts::PI;
+/// # use munum::{transform::{self, DepthRange}, assert_float_eq};
+/// assert_float_eq!(
+///     transform::perspective_infinite_reversed_z(2., PI/2., 1., DepthRange::ZeroToOne).as_ref(),
+///     &[0.5, 0., 0., 0., 0., 1., 0., 0., 0., 0., 0., -1., 0., 0., 1., 0.]
+/// );
+/// assert_float_eq!(
+///     transform::perspective_infinite_reversed_z(2., PI/2., 1., DepthRange::NegativeOneToOne).as_ref(),
+///     &[0.5, 0., 0., 0., 0., 1., 0., 0., 0., 0., 1., -1., 0., 0., 2., 0.]
+/// );
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub fn perspective_infinite_reversed_z<T: Copy + Float + NumAssign>(
+    aspect: T,
+    yfov: T,
+    znear: T,
+    depth: DepthRange,
+) -> Mat4<T> {
+    perspective_reversed_z(aspect, yfov, znear, T::infinity(), depth)
+}
+
 // endregion: Projection matrices
 
 // region: Camera matrices
@@ -399,3 +688,310 @@ pub fn look_at<T: Copy + Float + NumAssign>(eye: Vec3<T>, center: Vec3<T>, up: V
 }
 
 // endregion: Camera matrices
+
+// region: Culling
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb<T: Copy + NumAssign = f32> {
+    /// Minimum corner of the box.
+    pub min: Vec3<T>,
+    /// Maximum corner of the box.
+    pub max: Vec3<T>,
+}
+
+impl<T: Copy + NumAssign + PartialOrd> Aabb<T> {
+    /// Creates an AABB from its min and max corners.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// let aabb = Aabb::new(vec3(0, 0, 0), vec3(1, 2, 3));
+    /// assert_eq!(aabb.max, vec3(1, 2, 3));
+    /// ```
+    #[inline]
+    pub fn new(min: Vec3<T>, max: Vec3<T>) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns `true` if the given point is inside this AABB, including its boundary.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// let aabb = Aabb::new(vec3(0, 0, 0), vec3(1, 2, 3));
+    /// assert!(aabb.contains_point(vec3(1, 1, 0)));
+    /// assert!(!aabb.contains_point(vec3(1, 1, 4)));
+    /// ```
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        (0..3).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    /// Returns the center point of this AABB.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// assert_eq!(Aabb::new(vec3(0, 2, -4), vec3(2, 6, 4)).center(), vec3(1, 4, 0));
+    /// ```
+    #[inline]
+    pub fn center(&self) -> Vec3<T> {
+        let two = T::one() + T::one();
+        (self.min + self.max) / two
+    }
+
+    /// Returns the size of this AABB along each axis.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// assert_eq!(Aabb::new(vec3(0, 2, -4), vec3(2, 6, 4)).size(), vec3(2, 4, 8));
+    /// ```
+    #[inline]
+    pub fn size(&self) -> Vec3<T> {
+        self.max - self.min
+    }
+
+    /// Returns the half size of this AABB along each axis, i.e. the distance from its center to the faces.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// assert_eq!(Aabb::new(vec3(0, 2, -4), vec3(2, 6, 4)).extent(), vec3(1, 2, 4));
+    /// ```
+    #[inline]
+    pub fn extent(&self) -> Vec3<T> {
+        let two = T::one() + T::one();
+        self.size() / two
+    }
+
+    /// Returns the surface area of this AABB, e.g. for the surface area heuristic of BVH construction.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// assert_eq!(Aabb::new(vec3(0, 0, 0), vec3(1, 2, 3)).surface_area(), 22);
+    /// ```
+    pub fn surface_area(&self) -> T {
+        let size = self.size();
+        let two = T::one() + T::one();
+        two * (size[0] * size[1] + size[1] * size[2] + size[2] * size[0])
+    }
+
+    /// Returns the smallest AABB enclosing both this and the other AABB.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// let aabb = Aabb::new(vec3(0, 0, 0), vec3(1, 2, 3)).merge(&Aabb::new(vec3(-1, 1, 1), vec3(0, 4, 2)));
+    /// assert_eq!(aabb, Aabb::new(vec3(-1, 0, 0), vec3(1, 4, 3)));
+    /// ```
+    #[inline]
+    pub fn merge(&self, other: &Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Expands this AABB to contain the given point.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Aabb, vec3};
+    /// let mut aabb = Aabb::new(vec3(0, 0, 0), vec3(0, 0, 0));
+    /// aabb.expand(vec3(1, -2, 0));
+    /// aabb.expand(vec3(-1, 0, 3));
+    /// assert_eq!(aabb, Aabb::new(vec3(-1, -2, 0), vec3(1, 0, 3)));
+    /// ```
+    #[inline]
+    pub fn expand(&mut self, point: Vec3<T>) {
+        self.min = self.min.min(point);
+        self.max = self.max.max(point);
+    }
+
+    /// Returns the smallest AABB enclosing this AABB transformed by an affine transformation matrix.
+    ///
+    /// # Examples
+    /// ```
+    /// # use core::f32::consts::PI;
+    /// # use munum::{transform::{self, Aabb}, vec3, Quaternion, assert_float_eq};
+    /// let m = transform::transformation(vec3(1., 2., 3.), <Quaternion>::from_angle_z(PI / 2.), vec3(2., 2., 2.));
+    /// let aabb = Aabb::new(vec3(0., 0., 0.), vec3(1., 2., 3.)).transform(&m);
+    /// assert_float_eq!(aabb.min.as_ref(), &[-3., 2., 3.], 0.00001);
+    /// assert_float_eq!(aabb.max.as_ref(), &[1., 4., 9.], 0.00001);
+    /// ```
+    pub fn transform(&self, m: &Mat4<T>) -> Self {
+        // Each transformed coordinate is a sum of terms, whose extremes are taken independently
+        let mut min = Vec3::<T>::default();
+        let mut max = Vec3::<T>::default();
+        for i in 0..3 {
+            min[i] = m[(i, 3)];
+            max[i] = m[(i, 3)];
+            for j in 0..3 {
+                let a = m[(i, j)] * self.min[j];
+                let b = m[(i, j)] * self.max[j];
+                if a < b {
+                    min[i] += a;
+                    max[i] += b;
+                } else {
+                    min[i] += b;
+                    max[i] += a;
+                }
+            }
+        }
+        Self::new(min, max)
+    }
+}
+
+/// A plane of points p satisfying `normal.dot(p) + distance = 0`.
+/// Points with positive signed distance are in front of the plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane<T: Copy + NumAssign = f32> {
+    /// Normal vector of the plane, pointing to its front side.
+    pub normal: Vec3<T>,
+    /// Signed distance of the origin from the plane, scaled by the length of normal.
+    pub distance: T,
+}
+
+impl<T: Copy + NumAssign> Plane<T> {
+    /// Creates a plane from its normal and distance.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Plane, vec3};
+    /// let plane = Plane::new(vec3(0, 1, 0), -2);
+    /// assert_eq!(plane.distance, -2);
+    /// ```
+    #[inline]
+    pub fn new(normal: Vec3<T>, distance: T) -> Self {
+        Self { normal, distance }
+    }
+
+    /// Creates a plane from its equation coefficients (a, b, c, d) of `ax + by + cz + d = 0`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Plane, vec3, vec4};
+    /// let plane = Plane::from_vec4(vec4(0, 1, 0, -2));
+    /// assert_eq!(plane, Plane::new(vec3(0, 1, 0), -2));
+    /// ```
+    #[inline]
+    pub fn from_vec4(v: Vec4<T>) -> Self {
+        Self::new(v.xyz(), v[3])
+    }
+
+    /// Returns the signed distance of a point from this plane, scaled by the length of normal.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Plane, vec3};
+    /// let plane = Plane::new(vec3(0, 2, 0), -4);
+    /// assert_eq!(plane.signed_distance(vec3(5, 3, 1)), 2);
+    /// assert_eq!(plane.signed_distance(vec3(5, 1, 1)), -2);
+    /// ```
+    #[inline]
+    pub fn signed_distance(&self, point: Vec3<T>) -> T {
+        self.normal.dot(point) + self.distance
+    }
+
+    /// Normalizes this plane to have a unit normal, so that [Plane::signed_distance] returns the actual distance.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::Plane, vec3};
+    /// let mut plane = Plane::new(vec3(0., 2., 0.), -4.);
+    /// plane.normalize();
+    /// assert_eq!(plane, Plane::new(vec3(0., 1., 0.), -2.));
+    /// ```
+    #[cfg(any(feature = "std", feature = "libm"))]
+    pub fn normalize(&mut self)
+    where
+        T: Float,
+    {
+        let len = self.normal.len();
+        if len > T::zero() {
+            self.normal /= len;
+            self.distance /= len;
+        }
+    }
+}
+
+/// A view frustum bounded by 6 planes facing inwards, in the order of: left, right, bottom, top, near, far.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum<T: Copy + NumAssign = f32> {
+    /// The frustum planes.
+    pub planes: [Plane<T>; 6],
+}
+
+impl<T: Copy + NumAssign + PartialOrd> Frustum<T> {
+    /// Extracts the frustum planes from a view-projection matrix, assuming clip space depth in [-1, 1]
+    /// as produced by [perspective] and [ortho]. The resulting planes are not normalized.
+    /// With an infinite perspective projection, the far plane contains every point.
+    ///
+    /// # Examples
+    /// ```
+    /// # use munum::{transform::{self, Frustum}, vec3};
+    /// let frustum = Frustum::from_view_projection(transform::ortho(-1., 1., -1., 1., 1., 10.));
+    /// assert!(frustum.contains_point(vec3(0.5, 0.5, -5.)));
+    /// assert!(!frustum.contains_point(vec3(0.5, 0.5, 5.)));
+    /// ```
+    pub fn from_view_projection(m: Mat4<T>) -> Self {
+        let row = |i| Vec4::from_array([m[(i, 0)], m[(i, 1)], m[(i, 2)], m[(i, 3)]]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        Self {
+            planes: [
+                Plane::from_vec4(w + x),
+                Plane::from_vec4(w - x),
+                Plane::from_vec4(w + y),
+                Plane::from_vec4(w - y),
+                Plane::from_vec4(w + z),
+                Plane::from_vec4(w - z),
+            ],
+        }
+    }
+
+    /// Returns `true` if the given point is inside this frustum, including its boundary.
+    ///
+    /// # Examples
+    /// ```
+    /// # use core::f32::consts::PI;
+    /// # use munum::{transform::{self, Frustum}, vec3};
+    /// let frustum = Frustum::from_view_projection(transform::perspective(1., PI / 2., 1., 10.));
+    /// assert!(frustum.contains_point(vec3(0., 0., -1.)));
+    /// assert!(frustum.contains_point(vec3(4., -4., -5.)));
+    /// assert!(!frustum.contains_point(vec3(0., 0., -11.)));
+    /// assert!(!frustum.contains_point(vec3(6., 0., -5.)));
+    /// ```
+    pub fn contains_point(&self, point: Vec3<T>) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= T::zero())
+    }
+
+    /// Returns `true` if the given AABB intersects or is inside this frustum.
+    /// The test is conservative: boxes near the frustum corners may be reported as intersecting
+    /// while being fully outside, which is acceptable for culling.
+    ///
+    /// # Examples
+    /// ```
+    /// # use core::f32::consts::PI;
+    /// # use munum::{transform::{self, Aabb, Frustum}, vec3};
+    /// let frustum = Frustum::from_view_projection(transform::perspective(1., PI / 2., 1., 10.));
+    /// assert!(frustum.intersects_aabb(&Aabb::new(vec3(-1., -1., -3.), vec3(1., 1., -2.))));
+    /// assert!(frustum.intersects_aabb(&Aabb::new(vec3(-1., -1., -12.), vec3(1., 1., -8.))));
+    /// assert!(!frustum.intersects_aabb(&Aabb::new(vec3(-1., -1., 0.), vec3(1., 1., 2.))));
+    /// assert!(!frustum.intersects_aabb(&Aabb::new(vec3(8., -1., -5.), vec3(9., 1., -4.))));
+    /// ```
+    pub fn intersects_aabb(&self, aabb: &Aabb<T>) -> bool {
+        self.planes.iter().all(|plane| {
+            // Test the corner furthest along the plane normal
+            let mut corner = aabb.min;
+            for i in 0..3 {
+                if plane.normal[i] >= T::zero() {
+                    corner[i] = aabb.max[i];
+                }
+            }
+            plane.signed_distance(corner) >= T::zero()
+        })
+    }
+}
+
+// endregion: Culling